@@ -0,0 +1,298 @@
+//! Portable, password-protected copy of the bank (questions, images, edit
+//! history) for sharing over USB or email.
+//!
+//! The archive is a zip (`bank.json`, `assets/<id>/<file>`,
+//! `history/<id>.json`) encrypted with AES-256-GCM. The key is derived from
+//! the password with PBKDF2-HMAC-SHA256 (`crypto::derive_key`); the header stores everything needed
+//! to derive it again and is authenticated along with the content:
+//!
+//! ```text
+//! "SIROBANK" | version (1 byte) | rounds (u32 LE) | salt (16) | nonce (12) | ciphertext
+//! ```
+
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path};
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use zip::read::ZipArchive;
+use zip::write::{FileOptions, ZipWriter};
+use zip::CompressionMethod;
+
+use super::dedup::{import_questions, DedupOptions, DedupReport, DuplicateMatch};
+use super::{image_paths_mut, QuestionBank};
+use crate::i18n::CommandError;
+use crate::storage::{crypto, fs};
+
+const MAGIC: &[u8; 8] = b"SIROBANK";
+const FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + 4 + SALT_LEN + NONCE_LEN;
+
+/// PBKDF2 rounds for new archives
+pub const KDF_ROUNDS: u32 = 200_000;
+
+const BANK_ENTRY: &str = "bank.json";
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    /// Not a bank archive, or an unsupported version
+    Invalid(String),
+    /// Authentication failed: wrong password or corrupted file
+    WrongPassword,
+    Io(String),
+}
+
+impl From<String> for ArchiveError {
+    fn from(detail: String) -> Self {
+        ArchiveError::Io(detail)
+    }
+}
+
+impl From<ArchiveError> for CommandError {
+    fn from(error: ArchiveError) -> Self {
+        match error {
+            ArchiveError::Invalid(detail) => {
+                CommandError::with_detail("E145_BANK_ARCHIVE_INVALID", detail)
+            }
+            ArchiveError::WrongPassword => CommandError::new("E146_BANK_ARCHIVE_PASSWORD"),
+            ArchiveError::Io(detail) => CommandError::from(detail),
+        }
+    }
+}
+
+/// Contents of an archive: the bank with image paths relative to the archive
+/// root, and the other files by archive path
+pub struct BankArchive {
+    pub bank: QuestionBank,
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+/// Zip the bank in `dir` with its images and history files.
+pub fn pack(dir: &Path, bank: &QuestionBank) -> Result<Vec<u8>, String> {
+    let mut bank = bank.clone();
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let zip_err = |e: zip::result::ZipError| format!("Không tạo được tệp nén: {e}");
+    let io_err = |e: std::io::Error| format!("Không tạo được tệp nén: {e}");
+
+    for entry in &mut bank.questions {
+        for asset_path in image_paths_mut(&mut entry.question) {
+            let source = Path::new(asset_path.as_str()).to_path_buf();
+            let Some(file_name) = source.file_name().filter(|_| source.is_file()) else {
+                continue;
+            };
+            let name = format!("assets/{}/{}", entry.id, file_name.to_string_lossy());
+            let data = std::fs::read(&source)
+                .map_err(|e| format!("Không đọc được {}: {e}", source.display()))?;
+            zip.start_file(name.as_str(), options).map_err(zip_err)?;
+            zip.write_all(&data).map_err(io_err)?;
+            *asset_path = name;
+        }
+
+        let history = dir.join("history").join(format!("{}.json", entry.id));
+        if history.is_file() {
+            let data = std::fs::read(&history)
+                .map_err(|e| format!("Không đọc được {}: {e}", history.display()))?;
+            zip.start_file(format!("history/{}.json", entry.id), options)
+                .map_err(zip_err)?;
+            zip.write_all(&data).map_err(io_err)?;
+        }
+    }
+
+    let json = serde_json::to_vec_pretty(&bank).map_err(|e| format!("Không serialize được ngân hàng: {e}"))?;
+    zip.start_file(BANK_ENTRY, options).map_err(zip_err)?;
+    zip.write_all(&json).map_err(io_err)?;
+
+    Ok(zip.finish().map_err(zip_err)?.into_inner())
+}
+
+/// Read a zip produced by [`pack`]. Entries outside `assets/` and `history/`
+/// or with unsafe paths (`..`, absolute) are rejected.
+pub fn unpack(data: &[u8]) -> Result<BankArchive, ArchiveError> {
+    let invalid = |e: zip::result::ZipError| ArchiveError::Invalid(e.to_string());
+    let mut archive = ZipArchive::new(Cursor::new(data)).map_err(invalid)?;
+    let mut bank = None;
+    let mut files = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(invalid)?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().to_string();
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)
+            .map_err(|e| ArchiveError::Invalid(format!("{}: {}", name, e)))?;
+
+        if name == BANK_ENTRY {
+            bank = Some(
+                serde_json::from_slice(&content)
+                    .map_err(|e| ArchiveError::Invalid(format!("{}: {}", name, e)))?,
+            );
+        } else if is_safe_entry(&name) {
+            files.push((name, content));
+        } else {
+            return Err(ArchiveError::Invalid(name));
+        }
+    }
+
+    let bank = bank.ok_or_else(|| ArchiveError::Invalid(BANK_ENTRY.to_string()))?;
+    Ok(BankArchive { bank, files })
+}
+
+/// Merge `archive` into `bank`, stored in `dir`. Questions whose id is already
+/// in the bank (shared back and forth) are skipped, the others go through the
+/// usual duplicate check; the files of imported questions are written under
+/// `dir` and their image paths made absolute again.
+pub fn install(
+    dir: &Path,
+    bank: &mut QuestionBank,
+    archive: BankArchive,
+    options: &DedupOptions,
+) -> Result<DedupReport, String> {
+    let (known, incoming): (Vec<_>, Vec<_>) = archive
+        .bank
+        .questions
+        .into_iter()
+        .partition(|q| bank.questions.iter().any(|existing| existing.id == q.id));
+
+    let mut report = import_questions(bank, incoming, options);
+    report.skipped.extend(known.iter().map(|q| DuplicateMatch {
+        question_number: q.question.number,
+        existing_id: q.id.clone(),
+        similarity: 1.0,
+    }));
+
+    for (name, content) in &archive.files {
+        // assets/<id>/<file> or history/<id>.json
+        let id = name
+            .split('/')
+            .nth(1)
+            .map(|part| part.trim_end_matches(".json"))
+            .unwrap_or_default();
+        if report.imported.iter().any(|imported| imported == id) {
+            fs::write_bytes(&dir.join(name), content)?;
+        }
+    }
+
+    for id in &report.imported {
+        if let Some(entry) = bank.get_mut(id) {
+            for asset_path in image_paths_mut(&mut entry.question) {
+                if is_safe_entry(asset_path) {
+                    *asset_path = dir.join(asset_path.as_str()).to_string_lossy().to_string();
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn is_safe_entry(name: &str) -> bool {
+    let path = Path::new(name);
+    path.components().all(|c| matches!(c, Component::Normal(_)))
+        && matches!(
+            path.components().next(),
+            Some(Component::Normal(first)) if first == "assets" || first == "history"
+        )
+}
+
+/// Encrypt `plain` with a key derived from `password`.
+pub fn encrypt(plain: &[u8], password: &str) -> Vec<u8> {
+    encrypt_with_rounds(plain, password, KDF_ROUNDS)
+}
+
+fn encrypt_with_rounds(plain: &[u8], password: &str, rounds: u32) -> Vec<u8> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(FORMAT_VERSION);
+    header.extend_from_slice(&rounds.to_le_bytes());
+    header.extend_from_slice(&salt);
+    header.extend_from_slice(&nonce);
+
+    let cipher = Aes256Gcm::new(&crypto::derive_key(password, &salt, rounds).into());
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plain, aad: &header })
+        .expect("AES-GCM encryption of an in-memory buffer cannot fail");
+
+    header.extend_from_slice(&ciphertext);
+    header
+}
+
+/// Decrypt an archive produced by [`encrypt`].
+pub fn decrypt(data: &[u8], password: &str) -> Result<Vec<u8>, ArchiveError> {
+    if data.len() < HEADER_LEN || !data.starts_with(MAGIC) {
+        return Err(ArchiveError::Invalid("SIROBANK".to_string()));
+    }
+    let (header, ciphertext) = data.split_at(HEADER_LEN);
+    if header[MAGIC.len()] != FORMAT_VERSION {
+        return Err(ArchiveError::Invalid(format!("version {}", header[MAGIC.len()])));
+    }
+
+    let rounds_at = MAGIC.len() + 1;
+    let salt_at = rounds_at + 4;
+    let nonce_at = salt_at + SALT_LEN;
+    let mut rounds = [0u8; 4];
+    rounds.copy_from_slice(&header[rounds_at..salt_at]);
+    let rounds = u32::from_le_bytes(rounds);
+
+    let cipher = Aes256Gcm::new(&crypto::derive_key(password, &header[salt_at..nonce_at], rounds).into());
+    cipher
+        .decrypt(
+            Nonce::from_slice(&header[nonce_at..]),
+            Payload { msg: ciphertext, aad: header },
+        )
+        .map_err(|_| ArchiveError::WrongPassword)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bank::BankQuestion;
+    use crate::docx::model::{Question, Segment};
+
+    #[test]
+    fn encrypted_archive_round_trip() {
+        let dir = std::env::temp_dir().join(format!("siromix-archive-{}", uuid::Uuid::new_v4()));
+        let image = dir.join("assets").join("q1").join("img1.png");
+        std::fs::create_dir_all(image.parent().unwrap()).unwrap();
+        std::fs::write(&image, b"png").unwrap();
+
+        let bank = QuestionBank {
+            questions: vec![BankQuestion {
+                id: "q1".to_string(),
+                question: Question {
+                    stem: vec![Segment::Image {
+                        asset_path: image.to_string_lossy().to_string(),
+                        raw_xml: String::new(),
+                        width_emu: 0,
+                        height_emu: 0,
+                        anchor: None,
+                        ocr_text: None,
+                    }],
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+        };
+
+        let encrypted = encrypt_with_rounds(&pack(&dir, &bank).unwrap(), "mật khẩu", 1000);
+        assert!(matches!(decrypt(&encrypted, "sai"), Err(ArchiveError::WrongPassword)));
+
+        let archive = unpack(&decrypt(&encrypted, "mật khẩu").unwrap()).unwrap();
+        assert_eq!(archive.files, vec![("assets/q1/img1.png".to_string(), b"png".to_vec())]);
+        let mut question = archive.bank.questions[0].question.clone();
+        assert_eq!(image_paths_mut(&mut question).next().unwrap(), "assets/q1/img1.png");
+
+        assert!(!is_safe_entry("assets/../../evil.dll"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
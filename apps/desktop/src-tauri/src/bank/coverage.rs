@@ -0,0 +1,384 @@
+//! Topic coverage of an exam ("Bản đặc tả"): question counts and points per
+//! topic and cognitive level, compared with the target matrix the education
+//! department asks for.
+//!
+//! A question's level and topic come from its bank tags ("Nhận biết", "TH",
+//! "Dao động cơ") — the tags of the bank question imported from the job, or
+//! of a near duplicate (same test as [`super::usage`]) — and its chapter
+//! heading, which is a topic too.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Workbook, XlsxError};
+use serde::{Deserialize, Serialize};
+
+use super::dedup::{normalized_text, similarity_at_least, DEFAULT_THRESHOLD};
+use super::search::fold;
+use super::BankQuestion;
+use crate::docx::model::Question;
+
+/// Name of the sheet the departments expect
+pub const SHEET_NAME: &str = "Bản đặc tả";
+
+/// Allowed difference between actual and target points
+const POINTS_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TargetMatrix {
+    /// Cognitive levels, easiest first; a tag matches a level by name or
+    /// by initials ("VDC" for "Vận dụng cao")
+    pub levels: Vec<String>,
+    pub targets: Vec<TargetCell>,
+}
+
+impl Default for TargetMatrix {
+    fn default() -> Self {
+        Self {
+            levels: ["Nhận biết", "Thông hiểu", "Vận dụng", "Vận dụng cao"].map(String::from).to_vec(),
+            targets: Vec::new(),
+        }
+    }
+}
+
+/// Wanted number of questions (and points, if set) of a topic at a level
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TargetCell {
+    pub topic: String,
+    pub level: String,
+    pub questions: u32,
+    pub points: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageCell {
+    pub topic: String,
+    pub level: String,
+    /// Numbers of the questions in the cell
+    pub questions: Vec<u32>,
+    pub points: f64,
+    pub target_questions: Option<u32>,
+    pub target_points: Option<f64>,
+}
+
+impl CoverageCell {
+    /// Set by the matrix and not reached (or exceeded)
+    pub fn off_target(&self) -> bool {
+        self.target_questions.is_some_and(|target| target as usize != self.questions.len())
+            || self.target_points.is_some_and(|target| (target - self.points).abs() > POINTS_TOLERANCE)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReport {
+    pub levels: Vec<String>,
+    /// Topics of the matrix, then those only found in the exam
+    pub topics: Vec<String>,
+    /// Every topic × level with questions or a target
+    pub cells: Vec<CoverageCell>,
+    /// Questions without a topic or without a level
+    pub untagged: Vec<u32>,
+    pub total_points: f64,
+}
+
+impl CoverageReport {
+    pub fn cell(&self, topic: &str, level: &str) -> Option<&CoverageCell> {
+        self.cells.iter().find(|c| c.topic == topic && c.level == level)
+    }
+}
+
+/// Tags of each question of job `job_id`, from the bank questions imported
+/// from it or near duplicates; questions pending review are ignored
+pub fn job_tags(bank: &[BankQuestion], job_id: &str, questions: &[Question]) -> HashMap<u32, Vec<String>> {
+    let entries: Vec<(&BankQuestion, String)> = bank
+        .iter()
+        .filter(|entry| !entry.pending_review)
+        .map(|entry| (entry, normalized_text(&entry.question)))
+        .collect();
+    questions
+        .iter()
+        .filter_map(|question| {
+            let own = entries
+                .iter()
+                .find(|(entry, _)| entry.source_job_id == job_id && entry.question.number == question.number);
+            let entry = own.or_else(|| {
+                let text = normalized_text(question);
+                entries.iter().find(|(_, entry_text)| similarity_at_least(&text, entry_text, DEFAULT_THRESHOLD).is_some())
+            })?;
+            Some((question.number, entry.0.tags.clone()))
+        })
+        .collect()
+}
+
+fn same(a: &str, b: &str) -> bool {
+    fold(a.trim()) == fold(b.trim())
+}
+
+fn initials(name: &str) -> String {
+    name.split_whitespace().filter_map(|word| word.chars().next()).collect()
+}
+
+fn level_of<'a>(levels: &'a [String], tags: &[String]) -> Option<&'a String> {
+    levels
+        .iter()
+        .find(|level| tags.iter().any(|tag| same(tag, level) || same(tag, &initials(level))))
+}
+
+/// Coverage of `questions` against `matrix`. `points_per_question` counts
+/// for the questions that do not state their points.
+pub fn report(
+    questions: &[Question],
+    tags: &HashMap<u32, Vec<String>>,
+    matrix: &TargetMatrix,
+    points_per_question: f64,
+) -> CoverageReport {
+    let mut topics: Vec<String> = Vec::new();
+    for target in &matrix.targets {
+        if !topics.iter().any(|t| same(t, &target.topic)) {
+            topics.push(target.topic.trim().to_string());
+        }
+    }
+    let mut cells: Vec<CoverageCell> = Vec::new();
+    let mut untagged = Vec::new();
+    let mut total_points = 0.0;
+
+    for question in questions {
+        let points = question.points.unwrap_or(points_per_question);
+        total_points += points;
+        let question_tags = tags.get(&question.number).map(Vec::as_slice).unwrap_or_default();
+        let level = level_of(&matrix.levels, question_tags);
+        // A topic of the matrix first, then the chapter, then any other tag
+        let topic = question_tags
+            .iter()
+            .find_map(|tag| topics.iter().find(|t| same(t, tag)).cloned())
+            .or_else(|| question.chapter.clone())
+            .or_else(|| {
+                question_tags
+                    .iter()
+                    .find(|tag| level_of(&matrix.levels, std::slice::from_ref(*tag)).is_none())
+                    .cloned()
+            });
+        let (Some(topic), Some(level)) = (topic, level) else {
+            untagged.push(question.number);
+            continue;
+        };
+        let topic = match topics.iter().find(|t| same(t, &topic)) {
+            Some(known) => known.clone(),
+            None => {
+                topics.push(topic.trim().to_string());
+                topic.trim().to_string()
+            }
+        };
+        match cells.iter_mut().find(|c| c.topic == topic && c.level == *level) {
+            Some(cell) => {
+                cell.questions.push(question.number);
+                cell.points += points;
+            }
+            None => cells.push(CoverageCell {
+                topic,
+                level: level.clone(),
+                questions: vec![question.number],
+                points,
+                target_questions: None,
+                target_points: None,
+            }),
+        }
+    }
+
+    for target in &matrix.targets {
+        let Some(level) = matrix.levels.iter().find(|l| same(l, &target.level)) else {
+            continue;
+        };
+        let topic = topics.iter().find(|t| same(t, &target.topic)).cloned().unwrap_or_default();
+        let index = match cells.iter().position(|c| c.topic == topic && c.level == *level) {
+            Some(index) => index,
+            None => {
+                cells.push(CoverageCell {
+                    topic,
+                    level: level.clone(),
+                    questions: Vec::new(),
+                    points: 0.0,
+                    target_questions: None,
+                    target_points: None,
+                });
+                cells.len() - 1
+            }
+        };
+        cells[index].target_questions = Some(target.questions);
+        cells[index].target_points = target.points;
+    }
+
+    CoverageReport {
+        levels: matrix.levels.clone(),
+        topics,
+        cells,
+        untagged,
+        total_points,
+    }
+}
+
+/// Write `report` as the "Bản đặc tả" sheet: one row per topic, question
+/// count and points for each level, cells off target in red. A second
+/// sheet lists the differences with the matrix.
+pub fn write_xlsx(report: &CoverageReport, output_path: &Path) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    let header = Format::new()
+        .set_bold()
+        .set_align(FormatAlign::Center)
+        .set_align(FormatAlign::VerticalCenter)
+        .set_text_wrap()
+        .set_border(FormatBorder::Thin);
+    let cell = Format::new().set_border(FormatBorder::Thin).set_align(FormatAlign::Center);
+    let off_target = cell.clone().set_background_color(Color::RGB(0xFECACA));
+    let total = cell.clone().set_bold();
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(SHEET_NAME)?;
+    sheet.merge_range(0, 0, 1, 0, "TT", &header)?;
+    sheet.merge_range(0, 1, 1, 1, "Chủ đề", &header)?;
+    for (i, level) in report.levels.iter().enumerate() {
+        let col = 2 + 2 * i as u16;
+        sheet.merge_range(0, col, 0, col + 1, level, &header)?;
+        sheet.write_string_with_format(1, col, "Số câu", &header)?;
+        sheet.write_string_with_format(1, col + 1, "Điểm", &header)?;
+    }
+    let total_col = 2 + 2 * report.levels.len() as u16;
+    sheet.merge_range(0, total_col, 0, total_col + 1, "Tổng", &header)?;
+    sheet.write_string_with_format(1, total_col, "Số câu", &header)?;
+    sheet.write_string_with_format(1, total_col + 1, "Điểm", &header)?;
+
+    let mut row = 2;
+    for (index, topic) in report.topics.iter().enumerate() {
+        sheet.write_number_with_format(row, 0, (index + 1) as f64, &cell)?;
+        sheet.write_string_with_format(row, 1, topic, &cell)?;
+        let (mut count, mut points) = (0, 0.0);
+        for (i, level) in report.levels.iter().enumerate() {
+            let col = 2 + 2 * i as u16;
+            let found = report.cell(topic, level);
+            let format = if found.is_some_and(CoverageCell::off_target) { &off_target } else { &cell };
+            let (cell_count, cell_points) = found.map_or((0, 0.0), |c| (c.questions.len(), c.points));
+            sheet.write_number_with_format(row, col, cell_count as f64, format)?;
+            sheet.write_number_with_format(row, col + 1, cell_points, format)?;
+            count += cell_count;
+            points += cell_points;
+        }
+        sheet.write_number_with_format(row, total_col, count as f64, &total)?;
+        sheet.write_number_with_format(row, total_col + 1, points, &total)?;
+        row += 1;
+    }
+
+    sheet.merge_range(row, 0, row, 1, "Tổng", &header)?;
+    for (i, level) in report.levels.iter().enumerate() {
+        let col = 2 + 2 * i as u16;
+        let level_cells = report.cells.iter().filter(|c| c.level == *level);
+        let count: usize = level_cells.clone().map(|c| c.questions.len()).sum();
+        let points: f64 = level_cells.map(|c| c.points).sum();
+        sheet.write_number_with_format(row, col, count as f64, &total)?;
+        sheet.write_number_with_format(row, col + 1, points, &total)?;
+    }
+    let tagged: usize = report.cells.iter().map(|c| c.questions.len()).sum();
+    sheet.write_number_with_format(row, total_col, tagged as f64, &total)?;
+    sheet.write_number_with_format(row, total_col + 1, report.cells.iter().map(|c| c.points).sum::<f64>(), &total)?;
+    if !report.untagged.is_empty() {
+        let numbers: Vec<String> = report.untagged.iter().map(u32::to_string).collect();
+        sheet.write_string(row + 2, 1, format!("Câu chưa gắn chủ đề hoặc mức độ: {}", numbers.join(", ")))?;
+    }
+    sheet.set_column_width(0, 5)?;
+    sheet.set_column_width(1, 36)?;
+    sheet.set_freeze_panes(2, 2)?;
+
+    let differences = workbook.add_worksheet();
+    differences.set_name("Chênh lệch")?;
+    let columns = ["Chủ đề", "Mức độ", "Số câu", "Số câu mục tiêu", "Điểm", "Điểm mục tiêu"];
+    for (col, title) in columns.iter().enumerate() {
+        differences.write_string_with_format(0, col as u16, *title, &header)?;
+    }
+    for (row, found) in report.cells.iter().filter(|c| c.off_target()).enumerate() {
+        let row = row as u32 + 1;
+        differences.write_string(row, 0, &found.topic)?;
+        differences.write_string(row, 1, &found.level)?;
+        differences.write_number(row, 2, found.questions.len() as f64)?;
+        if let Some(target) = found.target_questions {
+            differences.write_number(row, 3, target as f64)?;
+        }
+        differences.write_number(row, 4, found.points)?;
+        if let Some(target) = found.target_points {
+            differences.write_number(row, 5, target)?;
+        }
+    }
+    differences.set_column_width(0, 36)?;
+    differences.set_column_width(1, 16)?;
+
+    workbook.save(output_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(number: u32, chapter: Option<&str>) -> Question {
+        Question {
+            number,
+            chapter: chapter.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn counts_questions_per_topic_and_level() {
+        let questions = [
+            question(1, Some("Dao động cơ")),
+            question(2, None),
+            Question {
+                points: Some(1.0),
+                ..question(3, None)
+            },
+            question(4, None),
+        ];
+        let tags: HashMap<u32, Vec<String>> = [
+            (1, vec!["NB".to_string()]),
+            (2, vec!["dao động cơ".to_string(), "Nhận biết".to_string()]),
+            (3, vec!["Sóng cơ".to_string(), "vdc".to_string()]),
+            (4, vec!["Sóng cơ".to_string()]),
+        ]
+        .into_iter()
+        .collect();
+        let matrix = TargetMatrix {
+            targets: vec![
+                TargetCell {
+                    topic: "Dao động cơ".to_string(),
+                    level: "Nhận biết".to_string(),
+                    questions: 2,
+                    points: Some(0.5),
+                },
+                TargetCell {
+                    topic: "Sóng cơ".to_string(),
+                    level: "Thông hiểu".to_string(),
+                    questions: 1,
+                    points: None,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let report = report(&questions, &tags, &matrix, 0.25);
+        assert_eq!(report.topics, ["Dao động cơ", "Sóng cơ"]);
+        let recall = report.cell("Dao động cơ", "Nhận biết").unwrap();
+        assert_eq!(recall.questions, [1, 2]);
+        assert!(!recall.off_target());
+        assert_eq!(report.cell("Sóng cơ", "Vận dụng cao").unwrap().points, 1.0);
+        assert!(report.cell("Sóng cơ", "Thông hiểu").unwrap().off_target());
+        assert_eq!(report.untagged, [4]);
+        assert_eq!(report.total_points, 1.75);
+
+        let path = std::env::temp_dir().join(format!("siromix-coverage-{}.xlsx", uuid::Uuid::new_v4()));
+        write_xlsx(&report, &path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        let _ = std::fs::remove_file(&path);
+    }
+}
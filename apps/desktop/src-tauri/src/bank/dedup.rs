@@ -0,0 +1,323 @@
+//! Near-duplicate detection when importing questions into the bank.
+//!
+//! Two questions are compared on their stem and options text, folded like
+//! the search index (lowercase, no diacritics, whitespace collapsed) with
+//! formulas kept as markers and images as the hash of their file. A pair whose similarity reaches the
+//! threshold is a duplicate; the import then skips the new question, merges
+//! its tags into the existing one, or imports it flagged for review.
+
+use serde::{Deserialize, Serialize};
+
+use super::search::fold;
+use super::{normalize_tags, BankQuestion, QuestionBank};
+use crate::docx::diff::{segments_text, similarity};
+use crate::docx::model::{Question, Segment};
+use crate::storage::audit::hex_digest;
+
+/// Default similarity from which two questions count as duplicates
+pub const DEFAULT_THRESHOLD: f64 = 0.9;
+
+/// What to do with an incoming question that duplicates a bank question
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DedupMode {
+    /// Don't import it
+    #[default]
+    Skip,
+    /// Don't import it, add its tags to the existing question
+    Merge,
+    /// Import it with `duplicateOf` set so it can be reviewed
+    Flag,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DedupOptions {
+    pub mode: DedupMode,
+    /// Similarity in [0, 1] from which questions are duplicates
+    pub threshold: f64,
+}
+
+impl Default for DedupOptions {
+    fn default() -> Self {
+        Self {
+            mode: DedupMode::default(),
+            threshold: DEFAULT_THRESHOLD,
+        }
+    }
+}
+
+/// An incoming question that matched a question already in the bank
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateMatch {
+    /// Number of the question in the imported job
+    #[serde(rename = "questionNumber")]
+    pub question_number: u32,
+    #[serde(rename = "existingId")]
+    pub existing_id: String,
+    pub similarity: f64,
+}
+
+/// Outcome of an import
+#[derive(Debug, Default, Serialize)]
+pub struct DedupReport {
+    /// Ids of the questions added to the bank (flagged ones included)
+    pub imported: Vec<String>,
+    pub skipped: Vec<DuplicateMatch>,
+    pub merged: Vec<DuplicateMatch>,
+    pub flagged: Vec<DuplicateMatch>,
+}
+
+/// Add `incoming` to the bank according to `options`. Questions of the same
+/// import are also checked against each other.
+pub fn import_questions(
+    bank: &mut QuestionBank,
+    incoming: Vec<BankQuestion>,
+    options: &DedupOptions,
+) -> DedupReport {
+    let mut report = DedupReport::default();
+    let mut texts: Vec<String> = bank
+        .questions
+        .iter()
+        .map(|q| normalized_text(&q.question))
+        .collect();
+
+    for mut entry in incoming {
+        let text = normalized_text(&entry.question);
+        let best = texts
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, existing)| {
+                let score = similarity_at_least(&text, existing, options.threshold)?;
+                Some((idx, score))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        let Some((idx, score)) = best else {
+            report.imported.push(entry.id.clone());
+            bank.questions.push(entry);
+            texts.push(text);
+            continue;
+        };
+
+        let existing = &mut bank.questions[idx];
+        let duplicate = DuplicateMatch {
+            question_number: entry.question.number,
+            existing_id: existing.id.clone(),
+            similarity: score,
+        };
+        match options.mode {
+            DedupMode::Skip => report.skipped.push(duplicate),
+            DedupMode::Merge => {
+                let mut tags = std::mem::take(&mut existing.tags);
+                tags.extend(entry.tags);
+                existing.tags = normalize_tags(tags);
+                report.merged.push(duplicate);
+            }
+            DedupMode::Flag => {
+                entry.duplicate_of = Some(existing.id.clone());
+                report.imported.push(entry.id.clone());
+                report.flagged.push(duplicate);
+                bank.questions.push(entry);
+                texts.push(text);
+            }
+        }
+    }
+
+    report
+}
+
+/// Comparable text of a question: stem then options, folded.
+pub fn normalized_text(question: &Question) -> String {
+    let mut parts = vec![content_text(&question.stem)];
+    parts.extend(question.options.iter().map(|o| content_text(&o.content)));
+    fold(&parts.join(" "))
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Text of `segments` with each image marked by its content, so questions
+/// made of different figures do not read the same
+fn content_text(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Image { asset_path, .. } => format!("[image {}]", image_key(asset_path)),
+            other => segments_text(std::slice::from_ref(other)),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Short hash of the image file, or its file name when it cannot be read
+fn image_key(asset_path: &str) -> String {
+    match std::fs::read(asset_path) {
+        Ok(data) => hex_digest(&data)[..16].to_string(),
+        Err(_) => std::path::Path::new(asset_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// Normalized text (see [`normalized_text`]) with its character trigrams,
+/// for texts compared against many others
+pub struct Fingerprint {
+    pub text: String,
+    chars: usize,
+    /// Sorted, repeats kept
+    trigrams: Vec<[char; 3]>,
+}
+
+impl Fingerprint {
+    pub fn new(text: String) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        let mut trigrams: Vec<[char; 3]> = chars.windows(3).map(|w| [w[0], w[1], w[2]]).collect();
+        trigrams.sort_unstable();
+        Self {
+            text,
+            chars: chars.len(),
+            trigrams,
+        }
+    }
+
+    /// Same as [`similarity_at_least`], but most pairs are ruled out by
+    /// counting shared trigrams: each edit changes at most three of them, so
+    /// texts within edit distance `d` share at least `longest - 2 - 3d`.
+    pub fn similarity_at_least(&self, other: &Fingerprint, threshold: f64) -> Option<f64> {
+        let longest = self.chars.max(other.chars);
+        // Largest edit distance that still reaches the threshold
+        let max_distance = ((1.0 - threshold) * longest as f64 + 1e-9).floor() as usize;
+        let needed = longest.saturating_sub(2 + 3 * max_distance);
+        if needed > 0 && shared_count(&self.trigrams, &other.trigrams) < needed {
+            return None;
+        }
+        similarity_at_least(&self.text, &other.text, threshold)
+    }
+}
+
+/// Number of items two sorted lists have in common, repeats included
+fn shared_count(a: &[[char; 3]], b: &[[char; 3]]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Similarity of `a` and `b` if it reaches `threshold`. The length ratio
+/// bounds the similarity, which skips the edit distance for most pairs.
+pub fn similarity_at_least(a: &str, b: &str, threshold: f64) -> Option<f64> {
+    let (len_a, len_b) = (a.chars().count(), b.chars().count());
+    let longest = len_a.max(len_b);
+    if longest > 0 && (len_a.min(len_b) as f64) / (longest as f64) < threshold {
+        return None;
+    }
+    let score = if a == b { 1.0 } else { similarity(a, b) };
+    (score >= threshold).then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, number: u32, stem: &str, tags: &[&str]) -> BankQuestion {
+        BankQuestion {
+            id: id.to_string(),
+            question: Question {
+                number,
+                stem: vec![Segment::Text {
+                    text: stem.to_string(),
+                    raw_xml: String::new(),
+                }],
+                ..Default::default()
+            },
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn trigram_filter_keeps_every_pair_reaching_the_threshold() {
+        let texts = [
+            "mot vat dao dong dieu hoa voi bien do 5 cm",
+            "mot vat dao dong dieu hoa voi bien do 6 cm",
+            "mot vat dao dong dieu hoa voi bien do 5 mm.",
+            "con lac don dao dong tat dan",
+            "tinh dao ham cua ham so",
+            "ab",
+            "",
+        ];
+        let prints: Vec<Fingerprint> = texts.iter().map(|t| Fingerprint::new(t.to_string())).collect();
+        for threshold in [0.5, 0.85, 0.9, 0.95] {
+            for (a, fa) in texts.iter().zip(&prints) {
+                for (b, fb) in texts.iter().zip(&prints) {
+                    assert_eq!(fa.similarity_at_least(fb, threshold), similarity_at_least(a, b, threshold), "{a} / {b}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn skips_merges_or_flags_near_duplicates() {
+        let existing = entry("old", 1, "Một vật dao động điều hoà với biên độ 5 cm.", &["2023"]);
+        let incoming = || {
+            vec![
+                entry("a", 1, "Một vật dao động điều hòa với biên độ 5cm.", &["2024"]),
+                entry("b", 2, "Tính đạo hàm của hàm số y = x^2.", &["2024"]),
+            ]
+        };
+
+        let mut bank = QuestionBank { questions: vec![existing.clone()] };
+        let report = import_questions(&mut bank, incoming(), &DedupOptions::default());
+        assert_eq!(report.imported, vec!["b"]);
+        assert_eq!(report.skipped[0].existing_id, "old");
+        assert_eq!(bank.questions.len(), 2);
+
+        let mut bank = QuestionBank { questions: vec![existing.clone()] };
+        let options = DedupOptions { mode: DedupMode::Merge, ..Default::default() };
+        let report = import_questions(&mut bank, incoming(), &options);
+        assert_eq!(report.merged.len(), 1);
+        assert_eq!(bank.questions[0].tags, vec!["2023", "2024"]);
+
+        let mut bank = QuestionBank { questions: vec![existing] };
+        let options = DedupOptions { mode: DedupMode::Flag, ..Default::default() };
+        let report = import_questions(&mut bank, incoming(), &options);
+        assert_eq!(report.imported, vec!["a", "b"]);
+        assert_eq!(bank.questions[1].duplicate_of.as_deref(), Some("old"));
+    }
+
+    #[test]
+    fn questions_with_different_figures_are_not_duplicates() {
+        let dir = std::env::temp_dir().join(format!("siromix-dedup-images-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let figure = |name: &str, data: &[u8]| {
+            let path = dir.join(name);
+            std::fs::write(&path, data).unwrap();
+            let mut question = entry(name, 1, "Hình nào là hình vuông?", &[]);
+            question.question.stem.push(Segment::Image {
+                asset_path: path.to_string_lossy().to_string(),
+                raw_xml: String::new(),
+                width_emu: 0,
+                height_emu: 0,
+                anchor: None,
+                ocr_text: None,
+            });
+            question
+        };
+        let (first, second, copy) = (figure("a.png", b"square"), figure("b.png", b"circle"), figure("c.png", b"square"));
+
+        assert_ne!(normalized_text(&first.question), normalized_text(&second.question));
+        assert_eq!(normalized_text(&first.question), normalized_text(&copy.question));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,106 @@
+//! Edit history of bank questions.
+//!
+//! Every edit first snapshots the current version of the question into
+//! `bank/history/<question id>.json`, so the bank file itself only holds the
+//! current revision. Restoring is an edit too: the version being replaced is
+//! kept, which makes a restore reversible as well.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{now_secs, BankQuestion};
+use crate::docx::model::Question;
+use crate::storage::fs;
+
+/// A past (or the current) version of a bank question
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Revision {
+    pub revision: u32,
+    pub question: Question,
+    pub tags: Vec<String>,
+    /// Unix timestamp (seconds) at which this version was saved
+    #[serde(rename = "editedAt")]
+    pub edited_at: u64,
+    pub author: String,
+}
+
+fn history_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join("history").join(format!("{}.json", id))
+}
+
+/// Previous revisions of a question, oldest first.
+pub fn load_history(dir: &Path, id: &str) -> Result<Vec<Revision>, String> {
+    let path = history_path(dir, id);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    fs::read_json(&path)
+}
+
+/// The current version of `entry` as a revision.
+pub fn current_revision(entry: &BankQuestion) -> Revision {
+    Revision {
+        revision: entry.revision,
+        question: entry.question.clone(),
+        tags: entry.tags.clone(),
+        edited_at: if entry.updated_at > 0 { entry.updated_at } else { entry.created_at },
+        author: entry.updated_by.clone(),
+    }
+}
+
+/// Snapshot `entry` into its history and stamp it as a new revision by
+/// `author`. Call before changing the question or its tags.
+pub fn record_edit(dir: &Path, entry: &mut BankQuestion, author: &str) -> Result<(), String> {
+    let mut history = load_history(dir, &entry.id)?;
+    history.push(current_revision(entry));
+    fs::write_json_atomic(&history_path(dir, &entry.id), &history)?;
+
+    entry.revision += 1;
+    entry.updated_at = now_secs();
+    entry.updated_by = author.to_string();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::Segment;
+
+    fn stem(text: &str) -> Question {
+        Question {
+            number: 1,
+            stem: vec![Segment::Text {
+                text: text.to_string(),
+                raw_xml: String::new(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn edits_keep_previous_revisions() {
+        let dir = std::env::temp_dir().join(format!("siromix-history-{}", uuid::Uuid::new_v4()));
+        let mut entry = BankQuestion {
+            id: "q1".to_string(),
+            question: stem("1 + 1 = ?"),
+            tags: vec!["toán".to_string()],
+            created_at: 100,
+            revision: 1,
+            ..Default::default()
+        };
+
+        record_edit(&dir, &mut entry, "cô Lan").unwrap();
+        entry.question = stem("2 + 2 = ?");
+
+        let history = load_history(&dir, "q1").unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].revision, 1);
+        assert_eq!(history[0].edited_at, 100);
+        assert!(matches!(&history[0].question.stem[0], Segment::Text { text, .. } if text == "1 + 1 = ?"));
+        assert_eq!(entry.revision, 2);
+        assert_eq!(entry.updated_by, "cô Lan");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,145 @@
+//! SQLite FTS5 index of the bank text, used by [`search`](super::search) to
+//! find candidate questions without scanning the whole bank.
+//!
+//! `bank.json` stays the source of truth; the index is a local cache
+//! (`<app_data>/SiroMix/cache/bank_index.sqlite`, never on the shared bank
+//! drive) brought up to date before each search: questions are reindexed
+//! when their revision or edit time changed, and dropped once they leave
+//! the bank. Text is indexed folded (see [`fold`]), so "đ" and the
+//! diacritics match whatever the query uses.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::{params, Connection};
+
+use super::search::{fold, question_text};
+use super::BankQuestion;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS indexed (id TEXT PRIMARY KEY, stamp TEXT NOT NULL);
+    CREATE VIRTUAL TABLE IF NOT EXISTS question_text USING fts5(id UNINDEXED, text, tokenize = 'unicode61');
+";
+
+pub struct SearchIndex {
+    conn: Connection,
+}
+
+impl SearchIndex {
+    /// Open (or create) the index file at `path`
+    pub fn open(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+        }
+        let conn = Connection::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Self::init(conn)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, String> {
+        Self::init(Connection::open_in_memory().map_err(|e| e.to_string())?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, String> {
+        // Another window may be syncing the same file
+        conn.busy_timeout(Duration::from_secs(5)).map_err(|e| e.to_string())?;
+        conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+        Ok(Self { conn })
+    }
+
+    /// Reindex the questions changed since the last sync and drop the ones
+    /// no longer in `questions`.
+    pub fn sync(&mut self, questions: &[BankQuestion]) -> Result<(), String> {
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        let indexed: HashMap<String, String> = {
+            let mut stmt = tx.prepare("SELECT id, stamp FROM indexed").map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(|e| e.to_string())?;
+            rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+        };
+
+        let current: HashSet<&str> = questions.iter().map(|q| q.id.as_str()).collect();
+        for id in indexed.keys().filter(|id| !current.contains(id.as_str())) {
+            remove(&tx, id)?;
+        }
+        for question in questions {
+            let stamp = stamp(question);
+            if indexed.get(&question.id) == Some(&stamp) {
+                continue;
+            }
+            remove(&tx, &question.id)?;
+            tx.execute(
+                "INSERT INTO question_text (id, text) VALUES (?1, ?2)",
+                params![question.id, fold(&question_text(&question.question))],
+            )
+            .map_err(|e| e.to_string())?;
+            tx.execute("INSERT INTO indexed (id, stamp) VALUES (?1, ?2)", params![question.id, stamp])
+                .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    /// Ids of the questions holding a word starting with any of `words`
+    /// (folded query words)
+    pub fn candidates(&self, words: &[&str]) -> Result<HashSet<String>, String> {
+        if words.is_empty() {
+            return Ok(HashSet::new());
+        }
+        // Folded words are alphanumeric: quoting them is enough
+        let query = words.iter().map(|w| format!("\"{}\"*", w)).collect::<Vec<_>>().join(" OR ");
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id FROM question_text WHERE question_text MATCH ?1")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([query], |row| row.get(0)).map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+    }
+}
+
+/// Changes whenever the question text may have changed
+fn stamp(question: &BankQuestion) -> String {
+    format!("{}:{}:{}", question.created_at, question.revision, question.updated_at)
+}
+
+fn remove(tx: &rusqlite::Transaction, id: &str) -> Result<(), String> {
+    tx.execute("DELETE FROM question_text WHERE id = ?1", [id]).map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM indexed WHERE id = ?1", [id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::{Question, Segment};
+
+    fn bank_question(id: &str, stem: &str) -> BankQuestion {
+        BankQuestion {
+            id: id.to_string(),
+            question: Question {
+                stem: vec![Segment::Text {
+                    text: stem.to_string(),
+                    raw_xml: String::new(),
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sync_follows_edits_and_removals() {
+        let mut index = SearchIndex::open_in_memory().unwrap();
+        let mut bank = vec![bank_question("1", "Con lắc đơn"), bank_question("2", "Đạo hàm")];
+        index.sync(&bank).unwrap();
+        assert_eq!(index.candidates(&["dao"]).unwrap(), HashSet::from(["2".to_string()]));
+
+        bank[0] = bank_question("1", "Dao động tắt dần");
+        bank[0].revision = 2;
+        bank.remove(1);
+        index.sync(&bank).unwrap();
+        assert_eq!(index.candidates(&["dao"]).unwrap(), HashSet::from(["1".to_string()]));
+        assert!(index.candidates(&["lac"]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+//! Lock file guarding writes to a bank shared over a network folder.
+//!
+//! Writers create `bank.lock` exclusively (`create_new`, which SMB and NFS
+//! shares honour) and hold it for one read-modify-write of the bank; readers
+//! don't lock because the bank file is replaced atomically. A busy lock is
+//! retried a few times. A lock whose owner process is gone (same machine) or
+//! older than [`STALE_AFTER_SECS`] is assumed to be left over from a crashed
+//! session and broken. The age of another machine's lock comes from the lock
+//! file's modification time, so a clock set wrong over there neither breaks
+//! a live lock nor keeps a dead one. Each lock carries a random token, and a lock file is
+//! only removed, on release or when broken, while it still holds the token
+//! seen, so a session never deletes a lock someone else has taken since.
+//! When the folder can't be written at all the bank is read-only.
+
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::now_secs;
+use crate::i18n::CommandError;
+
+const LOCK_FILE: &str = "bank.lock";
+
+/// Attempts before giving up on a busy lock
+pub const RETRIES: u32 = 10;
+
+const RETRY_DELAY: Duration = Duration::from_millis(300);
+
+/// A lock held longer than this is considered abandoned
+pub const STALE_AFTER_SECS: u64 = 120;
+
+/// Who holds the lock, written into the lock file for the busy message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub author: String,
+    pub host: String,
+    pub pid: u32,
+    #[serde(rename = "lockedAt")]
+    pub locked_at: u64,
+    /// Tells this lock apart from a later one of the same session
+    #[serde(default)]
+    pub token: String,
+}
+
+impl std::fmt::Display for LockOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.author, self.host)
+    }
+}
+
+#[derive(Debug)]
+pub enum LockError {
+    /// Another session holds the lock
+    Busy(Option<LockOwner>),
+    /// The bank folder can't be written
+    ReadOnly(String),
+}
+
+impl From<LockError> for CommandError {
+    fn from(error: LockError) -> Self {
+        match error {
+            LockError::Busy(Some(owner)) => CommandError::with_detail("E142_BANK_BUSY", owner),
+            LockError::Busy(None) => CommandError::new("E142_BANK_BUSY"),
+            LockError::ReadOnly(detail) => CommandError::with_detail("E144_BANK_READ_ONLY", detail),
+        }
+    }
+}
+
+/// Held lock; the lock file is removed on drop, unless it was broken as
+/// stale and taken by another session meanwhile.
+#[derive(Debug)]
+pub struct BankLock {
+    path: PathBuf,
+    token: String,
+}
+
+impl Drop for BankLock {
+    fn drop(&mut self) {
+        match remove_if_token(&self.path, &self.token) {
+            Ok(true) => {}
+            Ok(false) => eprintln!("[Bank] Lock {} was taken over, left in place", self.path.display()),
+            Err(e) => eprintln!("[Bank] Could not release lock {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+/// Take the bank lock in `dir`, retrying up to `retries` times while busy.
+pub fn acquire(dir: &Path, author: &str, retries: u32) -> Result<BankLock, LockError> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| LockError::ReadOnly(format!("{}: {}", dir.display(), e)))?;
+
+    let path = dir.join(LOCK_FILE);
+    let mut attempt = 0;
+    loop {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let owner = LockOwner {
+                    author: author.to_string(),
+                    host: host_name(),
+                    pid: std::process::id(),
+                    locked_at: now_secs(),
+                    token: uuid::Uuid::new_v4().to_string(),
+                };
+                let json = serde_json::to_vec(&owner).unwrap_or_default();
+                // The lock is the file's existence; the content identifies the holder
+                if let Err(e) = file.write_all(&json).and_then(|_| file.sync_all()) {
+                    drop(file);
+                    let _ = std::fs::remove_file(&path);
+                    return Err(LockError::ReadOnly(format!("{}: {}", path.display(), e)));
+                }
+                return Ok(BankLock { path, token: owner.token });
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let owner = owner(dir);
+                if is_stale(&path, owner.as_ref()) {
+                    // Only the lock judged stale: another session may have
+                    // broken it and locked again in between
+                    let broken = match &owner {
+                        Some(owner) => remove_if_token(&path, &owner.token),
+                        None => std::fs::remove_file(&path).map(|_| true),
+                    };
+                    match broken {
+                        Ok(_) => continue,
+                        Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                        Err(e) => return Err(LockError::ReadOnly(format!("{}: {}", path.display(), e))),
+                    }
+                }
+                if attempt >= retries {
+                    return Err(LockError::Busy(owner));
+                }
+                attempt += 1;
+                std::thread::sleep(RETRY_DELAY);
+            }
+            Err(e) => return Err(LockError::ReadOnly(format!("{}: {}", path.display(), e))),
+        }
+    }
+}
+
+/// Current holder of the lock in `dir`, if any.
+pub fn owner(dir: &Path) -> Option<LockOwner> {
+    let data = std::fs::read(dir.join(LOCK_FILE)).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+/// Remove the lock file at `path` if it still holds `token`; whether it did.
+fn remove_if_token(path: &Path, token: &str) -> std::io::Result<bool> {
+    let data = std::fs::read(path)?;
+    let current: Option<LockOwner> = serde_json::from_slice(&data).ok();
+    if current.is_some_and(|owner| owner.token == token) {
+        std::fs::remove_file(path)?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// Whether the lock was left by a session that is gone: its process no longer
+/// runs on this machine, or it is older than [`STALE_AFTER_SECS`].
+fn is_stale(path: &Path, owner: Option<&LockOwner>) -> bool {
+    if let Some(owner) = owner {
+        let host = host_name();
+        if !host.is_empty()
+            && owner.host == host
+            && owner.pid != std::process::id()
+            && process_running(owner.pid) == Some(false)
+        {
+            return true;
+        }
+    }
+    lock_age_secs(path, owner).is_some_and(|age| age > STALE_AFTER_SECS)
+}
+
+/// Whether process `pid` of this machine is running, if it can be told
+#[cfg(windows)]
+fn process_running(pid: u32) -> Option<bool> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let output = std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).contains(&format!("\"{}\"", pid)))
+}
+
+#[cfg(target_os = "linux")]
+fn process_running(pid: u32) -> Option<bool> {
+    Some(Path::new("/proc").join(pid.to_string()).exists())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn process_running(_pid: u32) -> Option<bool> {
+    None
+}
+
+/// Age of the lock from the time recorded by its owner when that is this
+/// machine (same clock), otherwise from the file's modification time.
+fn lock_age_secs(path: &Path, owner: Option<&LockOwner>) -> Option<u64> {
+    let host = host_name();
+    match owner {
+        Some(owner) if !host.is_empty() && owner.host == host => Some(now_secs().saturating_sub(owner.locked_at)),
+        _ => std::fs::metadata(path)
+            .and_then(|m| m.modified())
+            .ok()
+            .map(|modified| modified.elapsed().map_or(0, |elapsed| elapsed.as_secs())),
+    }
+}
+
+/// Whether files can be created in `dir`.
+pub fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".write-test-{}", uuid::Uuid::new_v4()));
+    let writable = std::fs::create_dir_all(dir).is_ok() && std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+fn host_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .or_else(|_| std::fs::read_to_string("/etc/hostname").map(|name| name.trim().to_string()))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_is_exclusive_until_dropped_or_stale() {
+        let dir = std::env::temp_dir().join(format!("siromix-lock-{}", uuid::Uuid::new_v4()));
+
+        let lock = acquire(&dir, "cô Lan", 0).unwrap();
+        match acquire(&dir, "thầy Minh", 0) {
+            Err(LockError::Busy(Some(owner))) => assert_eq!(owner.author, "cô Lan"),
+            other => panic!("expected busy lock, got {:?}", other),
+        }
+        drop(lock);
+        let lock = acquire(&dir, "thầy Minh", 0).unwrap();
+
+        // Abandoned lock from a crashed session on another machine, whose
+        // clock runs an hour behind: aged by the file, not by `locked_at`
+        std::mem::forget(lock);
+        let old = LockOwner {
+            author: "cô Lan".to_string(),
+            host: "MAY-PHONG-GV".to_string(),
+            pid: 0,
+            locked_at: now_secs() - 3600,
+            token: String::new(),
+        };
+        std::fs::write(dir.join(LOCK_FILE), serde_json::to_vec(&old).unwrap()).unwrap();
+        assert!(matches!(acquire(&dir, "thầy Minh", 0), Err(LockError::Busy(_))));
+        let stale = std::time::SystemTime::now() - Duration::from_secs(STALE_AFTER_SECS + 1);
+        std::fs::File::options()
+            .write(true)
+            .open(dir.join(LOCK_FILE))
+            .and_then(|file| file.set_modified(stale))
+            .unwrap();
+        let lock = acquire(&dir, "thầy Minh", 0).unwrap();
+
+        // Broken meanwhile and taken by another session: releasing leaves it
+        let other = LockOwner {
+            token: "other".to_string(),
+            locked_at: now_secs(),
+            ..old
+        };
+        std::fs::write(dir.join(LOCK_FILE), serde_json::to_vec(&other).unwrap()).unwrap();
+        drop(lock);
+        assert_eq!(owner(&dir).unwrap().token, "other");
+        std::fs::remove_file(dir.join(LOCK_FILE)).unwrap();
+
+        // Fresh lock of a process that no longer runs on this machine
+        if process_running(u32::MAX) == Some(false) && !host_name().is_empty() {
+            let crashed = LockOwner {
+                author: "cô Lan".to_string(),
+                host: host_name(),
+                pid: u32::MAX,
+                locked_at: now_secs(),
+                token: "crashed".to_string(),
+            };
+            std::fs::write(dir.join(LOCK_FILE), serde_json::to_vec(&crashed).unwrap()).unwrap();
+            assert!(acquire(&dir, "thầy Minh", 0).is_ok());
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
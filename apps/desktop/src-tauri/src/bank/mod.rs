@@ -0,0 +1,116 @@
+//! Question bank: questions imported from analyzed jobs, kept across exams.
+//!
+//! The bank is a single JSON document (`<app_data>/SiroMix/bank/bank.json`)
+//! plus the images of its questions under `bank/assets/<question id>/`, so it
+//! survives deleting the jobs it was imported from. The folder can be moved to
+//! a shared network drive (`bankDir` setting); writes then go through
+//! [`lock`] so several teachers can maintain the same bank.
+
+pub mod archive;
+pub mod coverage;
+pub mod dedup;
+pub mod history;
+pub mod index;
+pub mod lock;
+pub mod paraphrase;
+pub mod search;
+pub mod store;
+pub mod usage;
+
+use serde::{Deserialize, Serialize};
+
+use crate::docx::model::{Question, Segment};
+
+/// A question stored in the bank
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BankQuestion {
+    pub id: String,
+    pub question: Question,
+    /// Free-form tags ("vật lý 12", "dao động", "khó", ...)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Job the question was imported from
+    #[serde(rename = "sourceJobId", default)]
+    pub source_job_id: String,
+    /// Unix timestamp (seconds) of the import
+    #[serde(rename = "createdAt", default)]
+    pub created_at: u64,
+    /// Set when the question was imported although it looks like a
+    /// duplicate of this bank question; cleared once reviewed
+    /// (`resolve_bank_duplicate`)
+    #[serde(rename = "duplicateOf", default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+    /// Bank question this one rewords (see [`paraphrase`])
+    #[serde(rename = "paraphraseOf", default, skip_serializing_if = "Option::is_none")]
+    pub paraphrase_of: Option<String>,
+    /// Generated and not yet accepted by a teacher; left out of searches
+    /// unless asked for (`SearchFilters::pending_review`)
+    #[serde(rename = "pendingReview", default)]
+    pub pending_review: bool,
+    /// Exported exams the question appeared in, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub usage: Vec<usage::UsageRecord>,
+    /// Current revision number, starting at 1; older ones are in the history
+    #[serde(default = "first_revision")]
+    pub revision: u32,
+    /// Unix timestamp (seconds) of the last edit, 0 if never edited
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: u64,
+    /// Author of the last edit
+    #[serde(rename = "updatedBy", default)]
+    pub updated_by: String,
+}
+
+fn first_revision() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuestionBank {
+    pub questions: Vec<BankQuestion>,
+}
+
+impl QuestionBank {
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut BankQuestion> {
+        self.questions.iter_mut().find(|q| q.id == id)
+    }
+}
+
+/// `asset_path` of every image in the question (stem, options, both languages).
+pub fn image_paths_mut(question: &mut Question) -> impl Iterator<Item = &mut String> {
+    question
+        .stem
+        .iter_mut()
+        .chain(question.stem_alt.iter_mut())
+        .chain(
+            question
+                .options
+                .iter_mut()
+                .flat_map(|o| o.content.iter_mut().chain(o.content_alt.iter_mut())),
+        )
+        .filter_map(|segment| match segment {
+            Segment::Image { asset_path, .. } => Some(asset_path),
+            _ => None,
+        })
+}
+
+/// Current time as a Unix timestamp in seconds
+pub fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Trim, drop empty and duplicate tags (case-insensitive), keeping the first spelling.
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if !tag.is_empty() && !result.iter().any(|t| t.to_lowercase() == tag.to_lowercase()) {
+            result.push(tag);
+        }
+    }
+    result
+}
@@ -0,0 +1,271 @@
+//! Reworded copies of bank questions from a paraphrasing service, so
+//! variants can use different wording of the same item.
+//!
+//! The service is any HTTP endpoint set in the settings (`paraphrase`): a
+//! model running on the school's own machine (`http://localhost…`) or a
+//! remote API, which must use HTTPS. It receives
+//! the plain text of a question and returns reworded versions:
+//!
+//! ```json
+//! → { "language": "vi", "count": 2, "stem": "Thủ đô của Việt Nam là", "options": ["Hà Nội", "Huế"] }
+//! ← { "paraphrases": [{ "stem": "Thành phố nào là thủ đô của Việt Nam?", "options": ["Hà Nội", "Huế"] }] }
+//! ```
+//!
+//! Answers are cached by request under `<app_data>/SiroMix/cache/paraphrases`,
+//! so asking again for the same question does not call the service. The
+//! results are added to the bank pending review (`pending_review`) and are
+//! left out of searches until a teacher accepts them.
+
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{now_secs, BankQuestion};
+use crate::docx::model::{Question, Segment};
+use crate::i18n::Lang;
+use crate::storage::fs;
+
+/// Seconds before giving up on the service; models can be slow
+const REQUEST_TIMEOUT_SECS: u64 = 120;
+
+/// Largest answer read from the service
+const MAX_RESPONSE_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ParaphraseSettings {
+    /// URL the questions are posted to (empty = paraphrasing disabled)
+    pub endpoint: String,
+    /// Sent as `Authorization: Bearer …` when set
+    pub api_key: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParaphraseRequest {
+    pub language: Lang,
+    pub count: u32,
+    pub stem: String,
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Paraphrase {
+    pub stem: String,
+    /// Options in the original order; left unchanged unless there is one
+    /// for each option
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParaphraseResponse {
+    paraphrases: Vec<Paraphrase>,
+}
+
+/// Request for `count` paraphrases of `question`, or `None` if its stem or
+/// options hold something plain text would lose (images, equations, blanks)
+pub fn request_for(question: &Question, count: u32, language: Lang) -> Option<ParaphraseRequest> {
+    Some(ParaphraseRequest {
+        language,
+        count,
+        stem: plain_text(&question.stem)?,
+        options: question.options.iter().map(|o| plain_text(&o.content)).collect::<Option<_>>()?,
+    })
+}
+
+fn plain_text(segments: &[Segment]) -> Option<String> {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Option<String>>()
+        .map(|text| text.trim().to_string())
+}
+
+/// Paraphrases from the cache in `cache_dir`, or from the service (then cached)
+pub fn fetch(
+    settings: &ParaphraseSettings,
+    request: &ParaphraseRequest,
+    cache_dir: Option<&Path>,
+) -> Result<Vec<Paraphrase>, String> {
+    let body = serde_json::to_vec(request).map_err(|e| e.to_string())?;
+    let key: String = Sha256::digest([settings.endpoint.as_bytes(), b"\n", &body].concat())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let cache_path = cache_dir.map(|dir| dir.join(format!("{}.json", key)));
+    if let Some(cached) = cache_path.as_deref().and_then(|path| fs::read_json(path).ok()) {
+        return Ok(cached);
+    }
+
+    let response = post(settings, &body)?;
+    let paraphrases: Vec<Paraphrase> = serde_json::from_slice::<ParaphraseResponse>(&response)
+        .map_err(|e| format!("Phản hồi không hợp lệ: {e}"))?
+        .paraphrases
+        .into_iter()
+        .filter(|p| !p.stem.trim().is_empty())
+        .collect();
+    if let Some(path) = &cache_path {
+        // A cache that cannot be written only costs a new request next time
+        let _ = fs::write_json(path, &paraphrases);
+    }
+    Ok(paraphrases)
+}
+
+fn post(settings: &ParaphraseSettings, body: &[u8]) -> Result<Vec<u8>, String> {
+    let endpoint = settings.endpoint.trim();
+    let local = is_local_http(endpoint);
+    if !local && !endpoint.starts_with("https://") {
+        return Err(format!("Địa chỉ dịch vụ phải là https:// (hoặc http:// trên máy này): {endpoint}"));
+    }
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .https_only(!local)
+        .redirects(if local { 0 } else { 5 })
+        .build();
+    let mut request = agent.post(endpoint).set("Content-Type", "application/json");
+    if !settings.api_key.trim().is_empty() {
+        request = request.set("Authorization", &format!("Bearer {}", settings.api_key.trim()));
+    }
+    let response = request.send_bytes(body).map_err(|e| e.to_string())?;
+
+    let mut data = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_RESPONSE_BYTES + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| e.to_string())?;
+    if data.len() as u64 > MAX_RESPONSE_BYTES {
+        return Err(format!("Phản hồi lớn hơn {} MB", MAX_RESPONSE_BYTES / (1024 * 1024)));
+    }
+    Ok(data)
+}
+
+/// `http://` to this machine (a model served locally), where TLS is not needed
+fn is_local_http(endpoint: &str) -> bool {
+    let Some(rest) = endpoint.strip_prefix("http://") else {
+        return false;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    host.eq_ignore_ascii_case("localhost") || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// New bank entries, pending review, for the paraphrases of `source`. They
+/// keep its tags, options and correct answer; a paraphrase that rewords
+/// every option replaces their text.
+pub fn pending_questions(source: &BankQuestion, paraphrases: Vec<Paraphrase>) -> Vec<BankQuestion> {
+    let created_at = now_secs();
+    paraphrases
+        .into_iter()
+        .map(|paraphrase| {
+            let mut question = source.question.clone();
+            question.stem = vec![text(&paraphrase.stem)];
+            // Translation and statements belonged to the old wording
+            question.stem_alt.clear();
+            question.statements.clear();
+            if paraphrase.options.len() == question.options.len() {
+                for (option, content) in question.options.iter_mut().zip(&paraphrase.options) {
+                    option.content = vec![text(content)];
+                    option.content_alt.clear();
+                }
+            }
+
+            BankQuestion {
+                id: uuid::Uuid::new_v4().to_string(),
+                question,
+                tags: source.tags.clone(),
+                source_job_id: source.source_job_id.clone(),
+                created_at,
+                revision: 1,
+                paraphrase_of: Some(source.id.clone()),
+                pending_review: true,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+fn text(text: &str) -> Segment {
+    Segment::Text {
+        text: text.trim().to_string(),
+        raw_xml: String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::OptionItem;
+
+    #[test]
+    fn paraphrases_become_pending_copies() {
+        let option = |label: &str, content: &str| OptionItem {
+            label: label.to_string(),
+            content: vec![text(content)],
+            ..Default::default()
+        };
+        let source = BankQuestion {
+            id: "q1".to_string(),
+            question: Question {
+                stem: vec![text("Thủ đô của Việt Nam là ")],
+                options: vec![option("A", "Hà Nội"), option("B", "Huế")],
+                correct_label: "A".to_string(),
+                ..Default::default()
+            },
+            tags: vec!["địa lý".to_string()],
+            ..Default::default()
+        };
+        let request = request_for(&source.question, 2, Lang::Vi).unwrap();
+        assert_eq!(request.stem, "Thủ đô của Việt Nam là");
+        assert_eq!(request.options, ["Hà Nội", "Huế"]);
+
+        let pending = pending_questions(
+            &source,
+            vec![
+                Paraphrase {
+                    stem: "Thành phố nào là thủ đô của Việt Nam?".to_string(),
+                    options: vec!["TP Hà Nội".to_string(), "TP Huế".to_string()],
+                },
+                Paraphrase {
+                    stem: "Việt Nam có thủ đô là".to_string(),
+                    options: vec!["Hà Nội".to_string()],
+                },
+            ],
+        );
+        assert_eq!(pending.len(), 2);
+        assert!(pending.iter().all(|q| q.pending_review && q.paraphrase_of.as_deref() == Some("q1")));
+        assert_eq!(pending[0].question.correct_label, "A");
+        assert_eq!(plain_text(&pending[0].question.options[0].content).unwrap(), "TP Hà Nội");
+        // Not every option reworded: the options are kept
+        assert_eq!(plain_text(&pending[1].question.options[0].content).unwrap(), "Hà Nội");
+
+        let mut with_blank = source.question.clone();
+        with_blank.stem.push(Segment::Blank { width: 5 });
+        assert!(request_for(&with_blank, 2, Lang::Vi).is_none());
+    }
+
+    #[test]
+    fn only_local_endpoints_may_use_plain_http() {
+        assert!(is_local_http("http://localhost:11434/api"));
+        assert!(is_local_http("http://127.0.0.1/paraphrase"));
+        assert!(is_local_http("http://[::1]:8080"));
+        assert!(!is_local_http("http://localhost.example.com/api"));
+        assert!(!is_local_http("http://10.0.0.5/api"));
+        assert!(!is_local_http("https://localhost/api"));
+
+        let settings = ParaphraseSettings {
+            endpoint: "http://api.example.com/paraphrase".to_string(),
+            api_key: "secret".to_string(),
+        };
+        assert!(post(&settings, b"{}").unwrap_err().contains("https://"));
+    }
+}
@@ -0,0 +1,258 @@
+//! Full-text search over the bank.
+//!
+//! Candidates come from the SQLite FTS5 index (see [`index`](super::index)),
+//! synced with the bank before each search; they are then filtered and
+//! ranked here. Text is folded to lowercase ASCII (Vietnamese diacritics removed, "đ" → "d")
+//! so "dao dong dieu hoa", "dao động điều hoà" and "Dao Động Điều Hòa" all
+//! match. A question matches when at least half of the query words are a
+//! prefix of a word in its stem/options; results are ranked by the number of
+//! query words matched, with a bonus when the whole query appears as a phrase.
+
+use serde::{Deserialize, Serialize};
+
+use super::index::SearchIndex;
+use super::usage::recent_exams;
+use super::BankQuestion;
+use crate::docx::model::{Question, Segment};
+
+/// Query words too common in Vietnamese requests to be useful ("câu về ...")
+const STOP_WORDS: &[&str] = &["cau", "ve", "hoi", "cho", "cac", "nhung", "mot", "la", "va"];
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SearchFilters {
+    /// Every tag must be present (case-insensitive)
+    pub tags: Vec<String>,
+    /// Exclude questions used in any of the N most recently exported exams
+    #[serde(rename = "notUsedInLast")]
+    pub not_used_in_last: Option<usize>,
+    /// Only the questions pending review (the review queue) instead of the
+    /// accepted ones
+    #[serde(rename = "pendingReview")]
+    pub pending_review: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PageRequest {
+    /// 1-based page number
+    pub page: usize,
+    #[serde(rename = "pageSize")]
+    pub page_size: usize,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            page_size: 20,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub question: BankQuestion,
+    pub score: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    pub total: usize,
+    pub page: usize,
+    #[serde(rename = "pageSize")]
+    pub page_size: usize,
+    pub items: Vec<SearchHit>,
+}
+
+/// Search `questions` for `query` (empty query = everything), apply filters
+/// and return the requested page. `index` is brought up to date with
+/// `questions` first.
+pub fn search(
+    index: &mut SearchIndex,
+    questions: &[BankQuestion],
+    query: &str,
+    filters: &SearchFilters,
+    page: &PageRequest,
+) -> Result<SearchResult, String> {
+    let folded_query = fold(query);
+    let all_words = words(&folded_query);
+    let query_words: Vec<&str> = {
+        let meaningful: Vec<&str> = all_words
+            .iter()
+            .copied()
+            .filter(|w| !STOP_WORDS.contains(w))
+            .collect();
+        // A query made only of stop words still searches for them
+        if meaningful.is_empty() { all_words.clone() } else { meaningful }
+    };
+    let phrase = all_words.join(" ");
+    index.sync(questions)?;
+    let candidates = index.candidates(&query_words)?;
+    let wanted_tags: Vec<String> = filters.tags.iter().map(|t| t.to_lowercase()).collect();
+    let recent = filters
+        .not_used_in_last
+        .map(|count| recent_exams(questions, count))
+        .unwrap_or_default();
+
+    let mut hits: Vec<SearchHit> = questions
+        .iter()
+        .filter(|q| {
+            wanted_tags
+                .iter()
+                .all(|wanted| q.tags.iter().any(|t| t.to_lowercase() == *wanted))
+        })
+        .filter(|q| q.pending_review == filters.pending_review)
+        .filter(|q| !q.usage.iter().any(|u| recent.contains(&u.job_id)))
+        .filter_map(|q| {
+            if query_words.is_empty() {
+                return Some(SearchHit { question: q.clone(), score: 0 });
+            }
+            if !candidates.contains(&q.id) {
+                return None;
+            }
+            let text = fold(&question_text(&q.question));
+            let text_words = words(&text);
+            let matched = query_words
+                .iter()
+                .filter(|w| text_words.iter().any(|t| t.starts_with(*w)))
+                .count() as u32;
+            if matched == 0 || (matched as usize) * 2 < query_words.len() {
+                return None;
+            }
+            let phrase_bonus = if text_words.join(" ").contains(&phrase) { 10 } else { 0 };
+            Some(SearchHit { question: q.clone(), score: matched * 10 + phrase_bonus })
+        })
+        .collect();
+
+    hits.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then(b.question.created_at.cmp(&a.question.created_at))
+    });
+
+    let page_size = page.page_size.clamp(1, 200);
+    let page_number = page.page.max(1);
+    let total = hits.len();
+    let items = hits
+        .into_iter()
+        .skip((page_number - 1) * page_size)
+        .take(page_size)
+        .collect();
+
+    Ok(SearchResult {
+        total,
+        page: page_number,
+        page_size,
+        items,
+    })
+}
+
+/// Plain text of a question's stem and options (both languages).
+pub fn question_text(question: &Question) -> String {
+    let mut text = String::new();
+    let segments = question
+        .stem
+        .iter()
+        .chain(question.stem_alt.iter())
+        .chain(
+            question
+                .options
+                .iter()
+                .flat_map(|o| o.content.iter().chain(o.content_alt.iter())),
+        );
+    for segment in segments {
+        match segment {
+            Segment::Text { text: t, .. }
+            | Segment::Image { ocr_text: Some(t), .. } => {
+                text.push_str(t);
+                text.push(' ');
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Lowercase and strip Vietnamese diacritics.
+pub fn fold(text: &str) -> String {
+    text.chars().flat_map(|c| c.to_lowercase()).map(fold_char).collect()
+}
+
+fn words(text: &str) -> Vec<&str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn fold_char(c: char) -> char {
+    const GROUPS: &[(&str, char)] = &[
+        ("àáạảãâầấậẩẫăằắặẳẵ", 'a'),
+        ("èéẹẻẽêềếệểễ", 'e'),
+        ("ìíịỉĩ", 'i'),
+        ("òóọỏõôồốộổỗơờớợởỡ", 'o'),
+        ("ùúụủũưừứựửữ", 'u'),
+        ("ỳýỵỷỹ", 'y'),
+        ("đ", 'd'),
+    ];
+    GROUPS
+        .iter()
+        .find(|(chars, _)| chars.contains(c))
+        .map(|(_, base)| *base)
+        .unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bank_question(id: &str, stem: &str, tags: &[&str]) -> BankQuestion {
+        BankQuestion {
+            id: id.to_string(),
+            question: Question {
+                stem: vec![Segment::Text {
+                    text: stem.to_string(),
+                    raw_xml: String::new(),
+                }],
+                ..Default::default()
+            },
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn finds_questions_ignoring_diacritics_and_stop_words() {
+        let bank = vec![
+            bank_question("1", "Một vật dao động điều hoà với biên độ 5 cm.", &["Vật lý 12"]),
+            bank_question("2", "Con lắc đơn dao động tắt dần.", &["Vật lý 12"]),
+            bank_question("3", "Tính đạo hàm của hàm số.", &["Toán 12"]),
+        ];
+
+        let mut index = SearchIndex::open_in_memory().unwrap();
+        let result = search(
+            &mut index,
+            &bank,
+            "câu về dao động điều hòa",
+            &SearchFilters::default(),
+            &PageRequest::default(),
+        )
+        .unwrap();
+        let ids: Vec<&str> = result.items.iter().map(|h| h.question.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+
+        let filtered = search(
+            &mut index,
+            &bank,
+            "",
+            &SearchFilters {
+                tags: vec!["toán 12".to_string()],
+                ..Default::default()
+            },
+            &PageRequest::default(),
+        )
+        .unwrap();
+        assert_eq!(filtered.total, 1);
+        assert_eq!(filtered.items[0].question.id, "3");
+    }
+}
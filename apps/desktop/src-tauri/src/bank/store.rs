@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+
+use super::lock::{self, LockError};
+use super::{image_paths_mut, normalize_tags, now_secs, BankQuestion, QuestionBank};
+use crate::docx::model::ParsedDoc;
+use crate::storage::{fs, paths, settings};
+
+/// Bank folder: the one set in the settings (shared drive), or the local default.
+pub fn bank_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let configured = settings::load(app_handle)?.bank_dir;
+    if !configured.trim().is_empty() {
+        return Ok(PathBuf::from(configured.trim()));
+    }
+    Ok(paths::app_root_dir(app_handle)?.join("bank"))
+}
+
+fn bank_path(dir: &Path) -> PathBuf {
+    dir.join("bank.json")
+}
+
+/// Load the bank in `dir`, or an empty one if it doesn't exist yet.
+pub fn load_from(dir: &Path) -> Result<QuestionBank, String> {
+    let path = bank_path(dir);
+    if !path.exists() {
+        return Ok(QuestionBank::default());
+    }
+    fs::read_json(&path)
+}
+
+fn save_to(dir: &Path, bank: &QuestionBank) -> Result<(), String> {
+    fs::write_json_atomic(&bank_path(dir), bank)
+}
+
+pub fn load(app_handle: &AppHandle) -> Result<QuestionBank, String> {
+    load_from(&bank_dir(app_handle)?)
+}
+
+/// Read-modify-write of the bank in `dir` under the bank lock. The bank is
+/// re-read after locking so changes saved by other users are never lost;
+/// nothing is written if `f` fails.
+pub fn update<T, E>(
+    dir: &Path,
+    author: &str,
+    f: impl FnOnce(&mut QuestionBank) -> Result<T, E>,
+) -> Result<T, E>
+where
+    E: From<String> + From<LockError>,
+{
+    let _lock = lock::acquire(dir, author, lock::RETRIES)?;
+    let mut bank = load_from(dir)?;
+    let result = f(&mut bank)?;
+    save_to(dir, &bank)?;
+    Ok(result)
+}
+
+/// Build bank entries for every question of a parsed job. Images still
+/// point into the job workspace until [`store_assets`] copies them. The
+/// chapter of a question, if any, is added to its tags.
+pub fn questions_from_job(job_id: &str, parsed: ParsedDoc, tags: &[String]) -> Vec<BankQuestion> {
+    let created_at = now_secs();
+
+    parsed
+        .questions
+        .into_iter()
+        .map(|mut question| {
+            // Raw paragraphs and paragraph indices refer to the job's source
+            // document; they mean nothing in the bank
+            question.raw_paragraphs.clear();
+            question.continuation_paragraphs.clear();
+            for option in &mut question.options {
+                option.raw_paragraphs.clear();
+            }
+
+            let mut question_tags = tags.to_vec();
+            question_tags.extend(question.chapter.clone());
+
+            BankQuestion {
+                id: uuid::Uuid::new_v4().to_string(),
+                question,
+                tags: normalize_tags(question_tags),
+                source_job_id: job_id.to_string(),
+                created_at,
+                revision: 1,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Copy the images of a bank question to `<dir>/assets/<question id>/` and
+/// point its segments at the copies, so it survives deleting the job.
+pub fn store_assets(dir: &Path, entry: &mut BankQuestion) -> Result<(), String> {
+    let assets_dir = dir.join("assets").join(&entry.id);
+
+    for asset_path in image_paths_mut(&mut entry.question) {
+        let source = Path::new(asset_path.as_str());
+        if let Some(file_name) = source.file_name().filter(|_| source.is_file()) {
+            let destination = assets_dir.join(file_name);
+            fs::copy_file(source, &destination)?;
+            *asset_path = destination.to_string_lossy().to_string();
+        }
+    }
+
+    Ok(())
+}
+
+/// Delete the copied images of a bank question.
+pub fn remove_assets(dir: &Path, id: &str) -> Result<(), String> {
+    fs::remove_dir(&dir.join("assets").join(id))
+}
@@ -0,0 +1,130 @@
+//! Which exported exams each bank question appeared in.
+//!
+//! An export records usage for every bank question that is one of the
+//! exported job's questions: either imported from that job, or a near
+//! duplicate of one of its questions (same test as [`super::dedup`]), so a
+//! question retyped into next year's draft still counts as used.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::dedup::{normalized_text, Fingerprint, DEFAULT_THRESHOLD};
+use super::{now_secs, BankQuestion, QuestionBank};
+use crate::docx::model::Question;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    /// Unix timestamp (seconds) of the export
+    #[serde(rename = "usedAt")]
+    pub used_at: u64,
+    /// Exam codes of the variants containing the question
+    #[serde(rename = "variantCodes")]
+    pub variant_codes: Vec<String>,
+}
+
+/// Record that the job `job_id` was exported with `used` questions, each with
+/// the codes of the variants it appears in. Exporting the same job again
+/// replaces its previous record. Returns whether any bank question changed.
+pub fn record_export(bank: &mut QuestionBank, job_id: &str, used: &[(Question, Vec<String>)]) -> bool {
+    let used_at = now_secs();
+    let used_texts: Vec<Fingerprint> = used.iter().map(|(q, _)| Fingerprint::new(normalized_text(q))).collect();
+    let mut changed = false;
+
+    for entry in &mut bank.questions {
+        let own_job = entry.source_job_id == job_id;
+        let mut text = None;
+        let matched = used.iter().zip(&used_texts).find(|((question, _), used_text)| {
+            if own_job && question.number == entry.question.number {
+                return true;
+            }
+            let text = text.get_or_insert_with(|| Fingerprint::new(normalized_text(&entry.question)));
+            text.similarity_at_least(used_text, DEFAULT_THRESHOLD).is_some()
+        });
+        let Some(((_, variant_codes), _)) = matched else {
+            continue;
+        };
+
+        entry.usage.retain(|u| u.job_id != job_id);
+        entry.usage.push(UsageRecord {
+            job_id: job_id.to_string(),
+            used_at,
+            variant_codes: variant_codes.clone(),
+        });
+        changed = true;
+    }
+
+    changed
+}
+
+/// Job ids of the `count` most recently exported exams across the bank.
+pub fn recent_exams(questions: &[BankQuestion], count: usize) -> HashSet<String> {
+    let mut latest: Vec<(&str, u64)> = Vec::new();
+    for record in questions.iter().flat_map(|q| q.usage.iter()) {
+        match latest.iter_mut().find(|(job, _)| *job == record.job_id) {
+            Some((_, used_at)) => *used_at = (*used_at).max(record.used_at),
+            None => latest.push((&record.job_id, record.used_at)),
+        }
+    }
+    latest.sort_by_key(|&(_, used_at)| std::cmp::Reverse(used_at));
+    latest
+        .into_iter()
+        .take(count)
+        .map(|(job, _)| job.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::Segment;
+
+    fn question(number: u32, stem: &str) -> Question {
+        Question {
+            number,
+            stem: vec![Segment::Text {
+                text: stem.to_string(),
+                raw_xml: String::new(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn records_usage_by_source_job_or_similar_text() {
+        let entry = |id: &str, job: &str, q: Question| BankQuestion {
+            id: id.to_string(),
+            question: q,
+            tags: Vec::new(),
+            source_job_id: job.to_string(),
+            ..Default::default()
+        };
+        let mut bank = QuestionBank {
+            questions: vec![
+                entry("a", "job-2023", question(1, "Con lắc đơn dao động tắt dần.")),
+                entry("b", "job-2023", question(3, "Tính đạo hàm của hàm số.")),
+                entry("c", "job-old", question(5, "Một vật dao động điều hoà.")),
+            ],
+        };
+
+        let codes = vec!["101".to_string(), "102".to_string()];
+        let used = vec![
+            (question(1, "Con lắc đơn dao động tắt dần."), codes.clone()),
+            (question(2, "Một vật dao động điều hòa."), codes),
+        ];
+        assert!(record_export(&mut bank, "job-2023", &used));
+        assert!(!record_export(&mut bank, "job-2023", &used[..0]));
+
+        let used_ids: Vec<&str> = bank
+            .questions
+            .iter()
+            .filter(|q| !q.usage.is_empty())
+            .map(|q| q.id.as_str())
+            .collect();
+        assert_eq!(used_ids, vec!["a", "c"]);
+        assert_eq!(bank.questions[0].usage[0].variant_codes, vec!["101", "102"]);
+        assert_eq!(recent_exams(&bank.questions, 1).len(), 1);
+    }
+}
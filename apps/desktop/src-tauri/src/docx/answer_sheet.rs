@@ -0,0 +1,286 @@
+// src-tauri/src/docx/answer_sheet.rs
+//! Blank answer sheets (phiếu trả lời trắc nghiệm) in the standard layouts
+//! of the optical mark readers used in Vietnamese schools: 20, 40, 50 or
+//! 100 questions with options A to D, a 6-digit student ID (số báo danh)
+//! matrix and a 3-digit exam code matrix, each digit a column of bubbles 0
+//! to 9 under a box to write it in.
+//!
+//! Readers locate the bubbles from the corner marks, so the geometry is
+//! fixed: fixed-width tables with rows of exact height, the same for every
+//! exam. One sheet serves all variants; students fill in their exam code.
+
+use serde::{Deserialize, Serialize};
+
+use super::config::NghiDinh30;
+use super::cover_page::paragraph;
+use super::excel::MixedExam;
+use super::export_options::ExportOptions;
+use super::writer::{escape_xml, write_document_only};
+
+/// Name of the answer sheet in the output folder
+pub const ANSWER_SHEET_FILE_NAME: &str = "PhieuTraLoi.docx";
+
+/// Digits of the student ID and exam code matrices
+pub const ID_DIGITS: usize = 6;
+pub const CODE_DIGITS: usize = 3;
+
+const OPTIONS: [char; 4] = ['A', 'B', 'C', 'D'];
+
+/// Font with the circled digits and letters of the bubbles
+const BUBBLE_FONT: &str = "Segoe UI Symbol";
+
+const MARGIN_TWIPS: i32 = 720;
+const ROW_TWIPS: i32 = 320;
+const NUMBER_TWIPS: i32 = 450;
+const BUBBLE_TWIPS: i32 = 360;
+const GAP_TWIPS: i32 = 150;
+
+/// Standard sheet, by number of questions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnswerSheetLayout {
+    #[serde(rename = "20")]
+    Questions20,
+    #[serde(rename = "40")]
+    Questions40,
+    #[serde(rename = "50")]
+    Questions50,
+    #[serde(rename = "100")]
+    Questions100,
+}
+
+impl AnswerSheetLayout {
+    pub fn questions(self) -> usize {
+        match self {
+            Self::Questions20 => 20,
+            Self::Questions40 => 40,
+            Self::Questions50 => 50,
+            Self::Questions100 => 100,
+        }
+    }
+
+    /// Columns of questions side by side, numbered down each column
+    fn blocks(self) -> usize {
+        match self {
+            Self::Questions20 => 2,
+            Self::Questions40 => 4,
+            Self::Questions50 => 5,
+            Self::Questions100 => 4,
+        }
+    }
+
+    fn rows(self) -> usize {
+        self.questions() / self.blocks()
+    }
+}
+
+/// Fails with the reason when a variant does not fit `layout`: too many
+/// questions or options, or an exam code the code matrix cannot hold
+pub fn check(layout: AnswerSheetLayout, exams: &[MixedExam]) -> Result<(), String> {
+    for exam in exams {
+        if exam.questions.len() > layout.questions() {
+            return Err(format!(
+                "mã đề {} có {} câu, phiếu chỉ có {} câu",
+                exam.exam_code,
+                exam.questions.len(),
+                layout.questions()
+            ));
+        }
+        if let Some(question) = exam.questions.iter().find(|q| q.options.len() > OPTIONS.len()) {
+            return Err(format!(
+                "câu {} có {} phương án, phiếu chỉ có A, B, C, D",
+                question.display_number,
+                question.options.len()
+            ));
+        }
+        let code = exam.exam_code.trim();
+        if code.is_empty() || code.len() > CODE_DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("mã đề {} không phải số có tối đa {} chữ số", exam.exam_code, CODE_DIGITS));
+        }
+    }
+    Ok(())
+}
+
+/// Bubble of a digit or letter: ⓪ ① ... ⑨, Ⓐ Ⓑ ...
+fn circled(c: char) -> char {
+    let code = match c {
+        '0' => 0x24EA,
+        '1'..='9' => 0x2460 + c as u32 - '1' as u32,
+        'A'..='Z' => 0x24B6 + c as u32 - 'A' as u32,
+        _ => return c,
+    };
+    char::from_u32(code).unwrap_or(c)
+}
+
+fn run(text: &str, font: &str, size: i32, bold: bool) -> String {
+    format!(
+        r#"<w:r><w:rPr><w:rFonts w:ascii="{font}" w:hAnsi="{font}" w:cs="{font}" w:eastAsia="{font}"/>{}<w:sz w:val="{size}"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r>"#,
+        if bold { "<w:b/>" } else { "" },
+        escape_xml(text)
+    )
+}
+
+/// Table cell with one paragraph, no spacing so that it fits the row
+fn cell(width: i32, span: usize, jc: &str, content: &str, boxed: bool) -> String {
+    let span = if span > 1 { format!(r#"<w:gridSpan w:val="{span}"/>"#) } else { String::new() };
+    let borders = if boxed {
+        r#"<w:tcBorders><w:top w:val="single" w:sz="6"/><w:left w:val="single" w:sz="6"/><w:bottom w:val="single" w:sz="6"/><w:right w:val="single" w:sz="6"/></w:tcBorders>"#
+    } else {
+        ""
+    };
+    format!(
+        r#"<w:tc><w:tcPr><w:tcW w:w="{width}" w:type="dxa"/>{span}{borders}<w:vAlign w:val="center"/></w:tcPr><w:p><w:pPr><w:spacing w:before="0" w:after="0" w:line="240" w:lineRule="auto"/><w:jc w:val="{jc}"/></w:pPr>{content}</w:p></w:tc>"#
+    )
+}
+
+fn bubble(c: char) -> String {
+    cell(BUBBLE_TWIPS, 1, "center", &run(&circled(c).to_string(), BUBBLE_FONT, 22, false), false)
+}
+
+fn gap() -> String {
+    cell(GAP_TWIPS, 1, "center", "", false)
+}
+
+fn row(cells: &str) -> String {
+    format!(r#"<w:tr><w:trPr><w:cantSplit/><w:trHeight w:val="{ROW_TWIPS}" w:hRule="exact"/></w:trPr>{cells}</w:tr>"#)
+}
+
+/// Borderless table of fixed column widths, centered on the page
+fn table(widths: &[i32], rows: &str) -> String {
+    let width: i32 = widths.iter().sum();
+    let grid: String = widths.iter().map(|w| format!(r#"<w:gridCol w:w="{w}"/>"#)).collect();
+    format!(
+        r#"<w:tbl><w:tblPr><w:tblW w:w="{width}" w:type="dxa"/><w:jc w:val="center"/><w:tblLayout w:type="fixed"/><w:tblCellMar><w:left w:w="0" w:type="dxa"/><w:right w:w="0" w:type="dxa"/></w:tblCellMar></w:tblPr><w:tblGrid>{grid}</w:tblGrid>{rows}</w:tbl>"#
+    )
+}
+
+/// Corner marks: a black square at each end of the row
+fn corner_marks() -> String {
+    let width = NghiDinh30::PAGE_WIDTH_TWIPS - 2 * MARGIN_TWIPS;
+    let mark = run("■", BUBBLE_FONT, 28, false);
+    table(
+        &[BUBBLE_TWIPS, width - 2 * BUBBLE_TWIPS, BUBBLE_TWIPS],
+        &row(&[
+            cell(BUBBLE_TWIPS, 1, "left", &mark, false),
+            cell(width - 2 * BUBBLE_TWIPS, 1, "center", "", false),
+            cell(BUBBLE_TWIPS, 1, "right", &mark, false),
+        ]
+        .concat()),
+    )
+}
+
+/// Student ID and exam code: a label, boxes to write the digits in, then a
+/// column of bubbles 0-9 per digit
+fn digit_matrices(student_id: &str, exam_code: &str) -> String {
+    let widths: Vec<i32> = std::iter::repeat_n(BUBBLE_TWIPS, ID_DIGITS)
+        .chain([4 * GAP_TWIPS])
+        .chain(std::iter::repeat_n(BUBBLE_TWIPS, CODE_DIGITS))
+        .collect();
+    let label = |text: &str, digits: usize| {
+        cell(BUBBLE_TWIPS * digits as i32, digits, "center", &run(text, NghiDinh30::FONT_NAME, 20, true), false)
+    };
+    let spacer = cell(4 * GAP_TWIPS, 1, "center", "", false);
+    let boxes = |digits: usize| cell(BUBBLE_TWIPS, 1, "center", "", true).repeat(digits);
+
+    let mut rows = row(&[label(student_id, ID_DIGITS), spacer.clone(), label(exam_code, CODE_DIGITS)].concat());
+    rows.push_str(&row(&[boxes(ID_DIGITS), spacer.clone(), boxes(CODE_DIGITS)].concat()));
+    for digit in '0'..='9' {
+        let bubbles = |digits: usize| bubble(digit).repeat(digits);
+        rows.push_str(&row(&[bubbles(ID_DIGITS), spacer.clone(), bubbles(CODE_DIGITS)].concat()));
+    }
+    table(&widths, &rows)
+}
+
+/// Questions in `layout.blocks()` columns: number, then bubbles A-D
+fn answer_grid(layout: AnswerSheetLayout) -> String {
+    let block: Vec<i32> = std::iter::once(NUMBER_TWIPS)
+        .chain(std::iter::repeat_n(BUBBLE_TWIPS, OPTIONS.len()))
+        .collect();
+    let widths: Vec<i32> = (0..layout.blocks())
+        .flat_map(|b| if b == 0 { vec![] } else { vec![GAP_TWIPS] }.into_iter().chain(block.iter().copied()))
+        .collect();
+    let rows: String = (0..layout.rows())
+        .map(|r| {
+            let cells: String = (0..layout.blocks())
+                .map(|b| {
+                    let number = b * layout.rows() + r + 1;
+                    let number = cell(NUMBER_TWIPS, 1, "right", &run(&format!("{}. ", number), NghiDinh30::FONT_NAME, 20, true), false);
+                    let bubbles: String = OPTIONS.iter().map(|&option| bubble(option)).collect();
+                    format!("{}{}{}", if b == 0 { String::new() } else { gap() }, number, bubbles)
+                })
+                .collect();
+            row(&cells)
+        })
+        .collect();
+    table(&widths, &rows)
+}
+
+/// Write a blank answer sheet in `layout`; labels follow the lexicon
+pub fn write(layout: AnswerSheetLayout, options: &ExportOptions, output_path: &std::path::Path) -> std::io::Result<()> {
+    let lexicon = options.lexicon();
+    let mut body = corner_marks();
+    body.push_str(&paragraph("PHIẾU TRẢ LỜI TRẮC NGHIỆM", NghiDinh30::FONT_SIZE_TITLE, true, true));
+    body.push_str(&paragraph(
+        &format!("{}: {} - {} ({} câu)", lexicon.cover_subject, options.subject, options.grade, layout.questions()),
+        NghiDinh30::FONT_SIZE_BODY,
+        false,
+        true,
+    ));
+    body.push_str(&paragraph(&format!("{}: ......................................................", lexicon.student_name), NghiDinh30::FONT_SIZE_BODY, false, false));
+    body.push_str(&paragraph(&format!("{}: ...........................", lexicon.room), NghiDinh30::FONT_SIZE_BODY, false, false));
+    body.push_str(&digit_matrices(&lexicon.student_id, &lexicon.exam_code));
+    body.push_str(&paragraph(
+        "Dùng bút chì tô kín ô tròn. Ghi số báo danh, mã đề vào ô vuông rồi tô ô số tương ứng ở mỗi cột.",
+        20,
+        false,
+        true,
+    ));
+    body.push_str(&answer_grid(layout));
+    body.push_str(&paragraph("", 20, false, false));
+    body.push_str(&corner_marks());
+
+    let document_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{body}<w:sectPr><w:pgSz w:w="{}" w:h="{}"/><w:pgMar w:top="{MARGIN_TWIPS}" w:right="{MARGIN_TWIPS}" w:bottom="{MARGIN_TWIPS}" w:left="{MARGIN_TWIPS}" w:header="0" w:footer="0" w:gutter="0"/></w:sectPr></w:body></w:document>"#,
+        NghiDinh30::PAGE_WIDTH_TWIPS,
+        NghiDinh30::PAGE_HEIGHT_TWIPS
+    );
+    write_document_only(output_path, &document_xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::excel::MixedQuestion;
+
+    #[test]
+    fn standard_layouts_fit_their_questions() {
+        let layout: AnswerSheetLayout = serde_json::from_str(r#""100""#).unwrap();
+        assert_eq!((layout.blocks(), layout.rows()), (4, 25));
+        assert_eq!(circled('0'), '⓪');
+        assert_eq!(circled('9'), '⑨');
+        assert_eq!(circled('D'), 'Ⓓ');
+
+        let exam = |code: &str, questions: usize| MixedExam {
+            exam_code: code.to_string(),
+            questions: (1..=questions)
+                .map(|display_number| MixedQuestion {
+                    display_number,
+                    ..Default::default()
+                })
+                .collect(),
+            groups: Vec::new(),
+            parts: Vec::new(),
+        };
+        assert!(check(AnswerSheetLayout::Questions40, &[exam("101", 40)]).is_ok());
+        assert!(check(AnswerSheetLayout::Questions20, &[exam("101", 28)]).unwrap_err().contains("28"));
+        assert!(check(AnswerSheetLayout::Questions40, &[exam("A1", 10)]).is_err());
+
+        let path = std::env::temp_dir().join(format!("siromix-answer-sheet-{}.docx", uuid::Uuid::new_v4()));
+        write(AnswerSheetLayout::Questions50, &ExportOptions::default(), &path).unwrap();
+        let document_xml = crate::docx::read::read_document_xml(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(document_xml.matches('Ⓐ').count(), 50);
+        assert_eq!(document_xml.matches('⓪').count(), ID_DIGITS + CODE_DIGITS);
+        assert!(document_xml.contains("50. "));
+        assert!(document_xml.contains("Số báo danh"));
+    }
+}
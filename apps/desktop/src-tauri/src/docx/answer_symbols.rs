@@ -0,0 +1,81 @@
+// src-tauri/src/docx/answer_symbols.rs
+//! Answer symbols by question kind. Multiple-choice answers are the option
+//! letters; a true/false question (two options reading "Đúng" and "Sai",
+//! or "True" and "False") is answered "Đ" or "S" in the answer keys, whatever
+//! letters its options got when mixing.
+
+use std::collections::HashMap;
+
+use super::diff::segments_text;
+use super::model::{ParsedDoc, Segment};
+
+/// Symbol of a true/false option from its content
+fn true_false_symbol(content: &[Segment]) -> Option<&'static str> {
+    let text = segments_text(content).to_lowercase();
+    match text.trim_end_matches('.').trim() {
+        "đúng" | "đ" | "true" => Some("Đ"),
+        "sai" | "s" | "false" => Some("S"),
+        _ => None,
+    }
+}
+
+/// Option label → answer symbol, when the question is true/false; `None`
+/// for a multiple-choice question (answers are the labels).
+pub fn true_false_symbols<'a>(
+    options: impl IntoIterator<Item = (&'a str, &'a [Segment])>,
+) -> Option<HashMap<String, &'static str>> {
+    let symbols: HashMap<String, &'static str> = options
+        .into_iter()
+        .map(|(label, content)| true_false_symbol(content).map(|symbol| (label.to_string(), symbol)))
+        .collect::<Option<_>>()?;
+    let has = |symbol| symbols.values().any(|s| *s == symbol);
+    (symbols.len() == 2 && has("Đ") && has("S")).then_some(symbols)
+}
+
+/// Answer to print for `label`: its symbol for a true/false question, the
+/// label itself otherwise
+pub fn answer_symbol(symbols: Option<&HashMap<String, &'static str>>, label: &str) -> String {
+    symbols
+        .and_then(|symbols| symbols.get(label))
+        .map_or_else(|| label.to_string(), |symbol| symbol.to_string())
+}
+
+/// Original question number → symbols of its original labels, for the
+/// true/false questions of `doc`
+pub fn from_parsed_doc(doc: &ParsedDoc) -> HashMap<u32, HashMap<String, &'static str>> {
+    doc.questions
+        .iter()
+        .filter_map(|q| {
+            let options = q.options.iter().map(|o| (o.label.as_str(), o.content.as_slice()));
+            true_false_symbols(options).map(|symbols| (q.number, symbols))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    #[test]
+    fn true_false_questions_answer_with_symbols() {
+        let (sai, dung) = (text("Sai."), text(" Đúng"));
+        let symbols = true_false_symbols([("A", sai.as_slice()), ("B", dung.as_slice())]);
+        assert_eq!(answer_symbol(symbols.as_ref(), "B"), "Đ");
+        assert_eq!(answer_symbol(symbols.as_ref(), "A"), "S");
+
+        let (one, two) = (text("1"), text("Đúng"));
+        let symbols = true_false_symbols([("A", one.as_slice()), ("B", two.as_slice())]);
+        assert!(symbols.is_none());
+        assert_eq!(answer_symbol(symbols.as_ref(), "B"), "B");
+
+        let (a, b) = (text("Đúng"), text("Đúng"));
+        assert!(true_false_symbols([("A", a.as_slice()), ("B", b.as_slice())]).is_none());
+    }
+}
@@ -0,0 +1,95 @@
+// src-tauri/src/docx/answer_text.rs
+//! Answer key of one variant as plain text, for pasting into a spreadsheet
+//! or a chat message to colleagues.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::answer_symbols::answer_symbol;
+use super::mixer::VariantMapping;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnswerTextFormat {
+    /// One question per line, "number<TAB>answer", under a header row;
+    /// pastes as two columns in Excel/Sheets
+    Tsv,
+    /// "Mã đề 101: 1A 2C 3D …" on a single line
+    Compact,
+}
+
+/// `symbols`: original question number → symbols of its original labels,
+/// for true/false questions (see `answer_symbols::from_parsed_doc`)
+pub fn render(
+    mapping: &VariantMapping,
+    format: AnswerTextFormat,
+    symbols: &HashMap<u32, HashMap<String, &'static str>>,
+) -> String {
+    let mut questions: Vec<_> = mapping.questions.iter().collect();
+    questions.sort_by_key(|q| q.display_number);
+    let answers: Vec<String> = questions
+        .iter()
+        .map(|q| {
+            let original_label = q
+                .options
+                .iter()
+                .find(|o| o.label == q.correct_answer)
+                .map_or(q.correct_answer.as_str(), |o| o.original_label.as_str());
+            match symbols.get(&q.original_number) {
+                Some(symbols) => answer_symbol(Some(symbols), original_label),
+                None => q.correct_answer.clone(),
+            }
+        })
+        .collect();
+
+    match format {
+        AnswerTextFormat::Tsv => {
+            let mut lines = vec![format!("Câu\t{}", mapping.exam_code)];
+            lines.extend(
+                questions
+                    .iter()
+                    .zip(&answers)
+                    .map(|(q, answer)| format!("{}\t{}", q.display_number, answer)),
+            );
+            lines.join("\n")
+        }
+        AnswerTextFormat::Compact => {
+            let answers: Vec<String> = questions
+                .iter()
+                .zip(&answers)
+                .map(|(q, answer)| format!("{}{}", q.display_number, answer))
+                .collect();
+            format!("Mã đề {}: {}", mapping.exam_code, answers.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::mixer::QuestionMapping;
+
+    #[test]
+    fn renders_both_formats_in_display_order() {
+        let question = |display_number: u32, correct: &str| QuestionMapping {
+            display_number,
+            original_number: 10 - display_number,
+            options: Vec::new(),
+            correct_answer: correct.to_string(),
+        };
+        let mapping = VariantMapping {
+            exam_code: "101".to_string(),
+            seed: 0,
+            questions: vec![question(2, "C"), question(1, "A"), question(3, "D")],
+        };
+
+        let no_symbols = HashMap::new();
+        assert_eq!(render(&mapping, AnswerTextFormat::Compact, &no_symbols), "Mã đề 101: 1A 2C 3D");
+        assert_eq!(render(&mapping, AnswerTextFormat::Tsv, &no_symbols), "Câu\t101\n1\tA\n2\tC\n3\tD");
+
+        // Question 3 (original 7) is true/false
+        let symbols = HashMap::from([(7, HashMap::from([("C".to_string(), "S"), ("D".to_string(), "Đ")]))]);
+        assert_eq!(render(&mapping, AnswerTextFormat::Compact, &symbols), "Mã đề 101: 1A 2C 3Đ");
+    }
+}
@@ -1,27 +1,59 @@
 use std::fs::{self, File};
-use std::io::copy;
+use std::io::{copy, Read};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
 
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use zip::read::ZipArchive;
 use tokio::task;
 
 use super::{AppError, ExtractedAsset};
 
+/// Most entries accepted in a `.docx`, all parts included
+const MAX_ARCHIVE_ENTRIES: usize = 10_000;
+
+/// Largest media file extracted, after decompression
+pub(super) const MAX_MEDIA_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Largest total of the extracted media, after decompression
+const MAX_MEDIA_TOTAL_BYTES: u64 = 500 * 1024 * 1024;
+
 /// Extract all image files under `word/media/` from a `.docx` into
 /// the given `assets_dir`. Returns the list of extracted assets
 /// (file name and absolute path).
-pub async fn extract_media(
+///
+/// Archives with too many entries, media over the size limits (counted
+/// while decompressing, not trusted from the headers) or entry names that
+/// could escape `assets_dir` are rejected with `AppError::UnsafeArchive`.
+///
+/// WMF/EMF files are queued for rasterizing with `conversion`; those already
+/// converted for another job are taken from `conversion_cache` at once (see
+/// `queue_wmf_conversions`).
+pub fn extract_media(
     docx_path: &Path,
     assets_dir: &Path,
+    conversion: &ConversionParams,
+    conversion_cache: Option<&Path>,
 ) -> Result<Vec<ExtractedAsset>, AppError> {
     // Ensure the destination directory exists
     fs::create_dir_all(assets_dir)?;
 
     let file = File::open(docx_path)?;
     let mut archive = ZipArchive::new(file)?;
+    if archive.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(AppError::UnsafeArchive(format!(
+            "{} entries (max {})",
+            archive.len(),
+            MAX_ARCHIVE_ENTRIES
+        )));
+    }
 
     let mut extracted = Vec::new();
+    let mut total_bytes: u64 = 0;
 
     for i in 0..archive.len() {
         let mut entry = archive.by_index(i)?;
@@ -55,17 +87,32 @@ pub async fn extract_media(
             continue;
         }
 
-        let file_name = Path::new(&name)
-            .file_name()
-            .and_then(|s| s.to_str())
-            .unwrap_or("media")
-            .to_string();
+        let file_name = sanitize_media_name(&name)
+            .ok_or_else(|| AppError::UnsafeArchive(format!("entry name {:?}", name)))?;
+        // "word/media/a/x.png" and "word/media/b/x.png" flatten to the same name
+        let file_name = unique_name(file_name, |candidate| {
+            extracted.iter().any(|a: &ExtractedAsset| a.file_name.eq_ignore_ascii_case(candidate))
+        });
 
         let out_path: PathBuf = assets_dir.join(&file_name);
 
-        // Write the media file out
+        // Write the media file out, stopping one byte past the limit. An
+        // existing file is replaced, not rewritten: it may be a hard link
+        // shared with a duplicated job (see `storage::duplicate`)
+        let _ = fs::remove_file(&out_path);
         let mut out_file = File::create(&out_path)?;
-        copy(&mut entry, &mut out_file)?;
+        let written = copy(&mut (&mut entry).take(MAX_MEDIA_FILE_BYTES + 1), &mut out_file)?;
+        total_bytes += written;
+        if written > MAX_MEDIA_FILE_BYTES || total_bytes > MAX_MEDIA_TOTAL_BYTES {
+            drop(out_file);
+            let _ = fs::remove_file(&out_path);
+            return Err(AppError::UnsafeArchive(format!(
+                "{}: media larger than {} MB (file) or {} MB (total)",
+                name,
+                MAX_MEDIA_FILE_BYTES / (1024 * 1024),
+                MAX_MEDIA_TOTAL_BYTES / (1024 * 1024)
+            )));
+        }
 
         // Best-effort absolute path; if canonicalize fails, keep as-is
         let absolute_path = out_path
@@ -79,101 +126,541 @@ pub async fn extract_media(
         });
     }
 
-    // Post-process: attempt to convert WMF/EMF files to PNG
-    convert_wmf_assets(&mut extracted, assets_dir).await;
+    // Post-process: queue the conversion of WMF/EMF files to PNG
+    queue_wmf_conversions(&mut extracted, assets_dir, conversion, conversion_cache);
 
     Ok(extracted)
 }
 
-/// Attempt to convert WMF/EMF files to PNG using ImageMagick.
-/// 
-/// This function tries to use the system's ImageMagick `magick` command
-/// to convert WMF/EMF files to PNG. If conversion succeeds, the
-/// `converted_path` field is populated.
-/// 
-/// Falls back gracefully if ImageMagick is not available.
-/// 
-/// Uses async background tasks to avoid blocking the main thread,
-/// preventing "Not responding" UI freezes when converting multiple images.
-async fn convert_wmf_assets(assets: &mut Vec<ExtractedAsset>, assets_dir: &Path) {
-    // Convert all WMF/EMF files concurrently using background tasks
-    let mut tasks = Vec::new();
-    
-    for (index, asset) in assets.iter().enumerate() {
-        // Check if this is a WMF or EMF file
-        let ext = asset.absolute_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .map(|s| s.to_ascii_lowercase());
-        
-        let is_wmf = matches!(ext.as_deref(), Some("wmf") | Some("emf"));
-        if !is_wmf {
-            continue;
+/// File in the job workspace recording every WMF/EMF conversion
+pub const CONVERSIONS_FILE: &str = "conversions.json";
+
+/// Event sent with the job id when background conversions have updated a job
+pub const CONVERSIONS_EVENT: &str = "wmf-conversions";
+
+/// Attempts per conversion before it is marked failed
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry, doubled for each further one
+const RETRY_BACKOFF_MS: u64 = 500;
+
+/// Size the conversion cache is brought back to, least recently used PNGs
+/// first, after each addition
+const MAX_CACHE_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Distinguishes the temporary files of concurrent cache writes
+static CACHE_WRITES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConversionStatus {
+    Pending,
+    Done,
+    /// Every attempt failed (ImageMagick policy, locked file, ...)
+    Failed,
+    /// ImageMagick is not installed
+    Unavailable,
+}
+
+/// File name to write a `word/media/` entry under: its last path component
+/// (either separator), with characters Windows rejects replaced. `None` for
+/// names that are empty, `.`/`..`, absolute or contain a NUL.
+pub(super) fn sanitize_media_name(entry_name: &str) -> Option<String> {
+    if entry_name.starts_with('/') || entry_name.starts_with('\\') || entry_name.contains('\0') {
+        return None;
+    }
+    if entry_name.split(['/', '\\']).any(|part| part == "..") {
+        return None;
+    }
+    let last = entry_name.rsplit(['/', '\\']).next()?;
+    let name: String = last
+        .chars()
+        .map(|c| if matches!(c, ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control() { '_' } else { c })
+        .collect();
+    if name.is_empty() || name == "." {
+        return None;
+    }
+    Some(name)
+}
+
+/// `name`, or `<stem>_2.<ext>`, `<stem>_3.<ext>`… for the first one not
+/// `taken` (compared as the file system would, ignoring case on Windows)
+pub(super) fn unique_name(name: String, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(&name) {
+        return name;
+    }
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{}", extension)),
+        _ => (name.as_str(), String::new()),
+    };
+    (2..)
+        .map(|n| format!("{}_{}{}", stem, n, extension))
+        .find(|candidate| !taken(candidate))
+        .expect("an unused name exists")
+}
+
+/// Resolutions accepted for rasterizing; anything else is clamped, as a
+/// huge density makes ImageMagick allocate gigantic rasters
+pub const MIN_DPI: u32 = 72;
+pub const MAX_DPI: u32 = 600;
+
+/// How WMF/EMF files are rasterized, set in the settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConversionParams {
+    /// Resolution of the PNG (ImageMagick `-density`); 96 suits the screen,
+    /// print needs 300 or more. Used through `density()`
+    pub dpi: u32,
+    /// Cut the blank margin around the picture (`-trim`)
+    pub trim: bool,
+    /// Background color, e.g. "white" or "none" for transparent (empty =
+    /// ImageMagick default)
+    pub background: String,
+}
+
+impl Default for ConversionParams {
+    fn default() -> Self {
+        Self {
+            dpi: 96,
+            trim: true,
+            background: String::new(),
         }
+    }
+}
 
-        // Generate output PNG path
-        let png_filename = asset.file_name
-            .trim_end_matches(".wmf")
-            .trim_end_matches(".emf")
-            .trim_end_matches(".WMF")
-            .trim_end_matches(".EMF")
-            .to_string() + ".png";
-        
-        let png_path = assets_dir.join(&png_filename);
-        let wmf_path = asset.absolute_path.clone();
-        let file_name = asset.file_name.clone();
-        
-        // Spawn blocking task to run ImageMagick without blocking main thread
-        let task = task::spawn_blocking(move || {
-            let result = convert_wmf_to_png(&wmf_path, &png_path);
-            (index, result, png_path, png_filename, file_name)
-        });
-        
-        tasks.push(task);
+impl ConversionParams {
+    /// `dpi` within `MIN_DPI..=MAX_DPI`
+    pub fn density(&self) -> u32 {
+        self.dpi.clamp(MIN_DPI, MAX_DPI)
     }
-    
-    // Wait for all conversions to complete and update assets
-    for task in tasks {
-        if let Ok((index, result, png_path, png_filename, file_name)) = task.await {
-            match result {
+
+    /// Distinguishes cached conversions of the same file with other parameters
+    fn cache_key(&self) -> String {
+        format!("{}dpi;trim={};bg={}", self.density(), self.trim, self.background)
+    }
+}
+
+/// One WMF/EMF → PNG conversion of a job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionJob {
+    #[serde(rename = "fileName")]
+    pub file_name: String,
+    pub source: PathBuf,
+    pub target: PathBuf,
+    pub status: ConversionStatus,
+    pub attempts: u32,
+    #[serde(rename = "lastError", default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Parameters of the conversion (defaults for queues saved before they
+    /// were configurable)
+    #[serde(default)]
+    pub params: ConversionParams,
+}
+
+impl ConversionJob {
+    /// PNG of an earlier conversion of the same source content and
+    /// parameters in `cache_dir`
+    fn cache_path(&self, cache_dir: Option<&Path>) -> Option<PathBuf> {
+        let mut data = fs::read(&self.source).ok()?;
+        data.extend_from_slice(self.params.cache_key().as_bytes());
+        Some(cache_dir?.join(format!("{}.png", hex_digest(&data))))
+    }
+
+    /// Copy the cached PNG of this conversion, if there is one
+    fn copy_cached(&mut self, cached: Option<&Path>) -> bool {
+        // Replaced rather than rewritten, like the extracted media
+        let _ = fs::remove_file(&self.target);
+        let Some(cached) = cached.filter(|path| path.is_file()) else {
+            return false;
+        };
+        if fs::copy(cached, &self.target).is_err() {
+            return false;
+        }
+        // Recently used: pruned last
+        let _ = File::options().append(true).open(cached).and_then(|file| file.set_modified(SystemTime::now()));
+        self.status = ConversionStatus::Done;
+        self.last_error = None;
+        true
+    }
+
+    /// Convert, retrying with backoff while ImageMagick fails. A PNG cached
+    /// for the same source content and parameters is copied instead, and new
+    /// conversions are added to the cache.
+    fn run(&mut self, cache_dir: Option<&Path>) {
+        let cached = self.cache_path(cache_dir);
+        if self.copy_cached(cached.as_deref()) {
+            return;
+        }
+
+        self.convert();
+        if let (ConversionStatus::Done, Some(cached)) = (self.status, cached) {
+            if let Err(e) = store_in_cache(&self.target, &cached) {
+                eprintln!("[WMF] Could not cache {}: {}", self.file_name, e);
+            }
+        }
+    }
+
+    fn convert(&mut self) {
+        for attempt in 0..MAX_ATTEMPTS {
+            if attempt > 0 {
+                std::thread::sleep(Duration::from_millis(RETRY_BACKOFF_MS << (attempt - 1)));
+            }
+            self.attempts += 1;
+            match convert_wmf_to_png(&self.source, &self.target, &self.params) {
                 Ok(true) => {
-                    println!("[WMF] Successfully converted: {} → {}", 
-                        file_name, png_filename);
-                    if let Some(asset) = assets.get_mut(index) {
-                        asset.converted_path = Some(png_path);
-                    }
+                    self.status = ConversionStatus::Done;
+                    self.last_error = None;
+                    return;
                 }
                 Ok(false) => {
-                    println!("[WMF] ImageMagick not available, keeping original: {}", 
-                        file_name);
+                    self.status = ConversionStatus::Unavailable;
+                    return;
                 }
                 Err(e) => {
-                    eprintln!("[WMF] Conversion failed for {}: {:?}", file_name, e);
+                    self.status = ConversionStatus::Failed;
+                    self.last_error = Some(e.to_string());
                 }
             }
         }
     }
 }
 
+/// Copy `png` to `cached` through a temporary file renamed into place, so a
+/// concurrent reader never sees a partial PNG, then prune the cache.
+fn store_in_cache(png: &Path, cached: &Path) -> std::io::Result<()> {
+    let dir = cached.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(dir)?;
+    let temp = dir.join(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        CACHE_WRITES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let stored = fs::copy(png, &temp).and_then(|_| fs::rename(&temp, cached));
+    if stored.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    stored?;
+    prune_cache(dir, MAX_CACHE_BYTES);
+    Ok(())
+}
+
+/// Delete the least recently used PNGs of `cache_dir` until the rest fits
+/// in `max_bytes`
+fn prune_cache(cache_dir: &Path, max_bytes: u64) {
+    let Ok(entries) = fs::read_dir(cache_dir) else {
+        return;
+    };
+    let mut files: Vec<(SystemTime, u64, PathBuf)> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("png") {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            Some((metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH), metadata.len(), path))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    files.sort();
+    for (_, len, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        if fs::remove_file(&path).is_ok() {
+            total -= len;
+        }
+    }
+}
+
+/// Conversion queue of the job whose workspace is `workspace_dir`
+pub fn load_conversions(workspace_dir: &Path) -> Vec<ConversionJob> {
+    fs::read(workspace_dir.join(CONVERSIONS_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_conversions(workspace_dir: &Path, jobs: &[ConversionJob]) -> bool {
+    let result = serde_json::to_vec_pretty(jobs)
+        .map_err(std::io::Error::other)
+        .and_then(|json| fs::write(workspace_dir.join(CONVERSIONS_FILE), json));
+    if let Err(e) = &result {
+        eprintln!("[WMF] Could not save conversion queue: {}", e);
+    }
+    result.is_ok()
+}
+
+/// Workspaces whose queue is being run by `run_pending_conversions`. Also
+/// held while a queue file is read and written back, so that a runner and
+/// an analysis queueing new conversions never overwrite each other.
+static RUNNING_QUEUES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+fn running_queues() -> std::sync::MutexGuard<'static, Vec<PathBuf>> {
+    RUNNING_QUEUES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn log_conversion(job: &ConversionJob) {
+    match job.status {
+        ConversionStatus::Done => println!("[WMF] Successfully converted: {}", job.file_name),
+        ConversionStatus::Unavailable => {
+            println!("[WMF] ImageMagick not available, keeping original: {}", job.file_name)
+        }
+        _ => eprintln!(
+            "[WMF] Conversion failed for {} after {} attempt(s): {}",
+            job.file_name,
+            job.attempts,
+            job.last_error.as_deref().unwrap_or_default()
+        ),
+    }
+}
+
+/// Run `jobs` concurrently in background tasks (avoids "Not responding" UI
+/// freezes with many images).
+async fn run_conversions(jobs: Vec<ConversionJob>, cache_dir: Option<&Path>) -> Vec<ConversionJob> {
+    let tasks: Vec<_> = jobs
+        .into_iter()
+        .map(|mut job| {
+            let cache_dir = cache_dir.map(Path::to_path_buf);
+            task::spawn_blocking(move || {
+                job.run(cache_dir.as_deref());
+                job
+            })
+        })
+        .collect();
+
+    let mut finished = Vec::new();
+    for task in tasks {
+        if let Ok(job) = task.await {
+            log_conversion(&job);
+            finished.push(job);
+        }
+    }
+    finished
+}
+
+/// Queue the conversion of the WMF/EMF assets to PNG in
+/// `<workspace>/conversions.json`, for `run_pending_conversions` to run
+/// after the analysis. Conversions found in `cache_dir` (PNGs of earlier
+/// conversions named by the SHA-256 of the WMF/EMF content and `params`: the
+/// same equations recur across documents of an author) are done at once and
+/// fill `converted_path`.
+fn queue_wmf_conversions(
+    assets: &mut [ExtractedAsset],
+    assets_dir: &Path,
+    params: &ConversionParams,
+    cache_dir: Option<&Path>,
+) {
+    let mut png_names: Vec<String> = Vec::new();
+    let mut jobs: Vec<ConversionJob> = assets
+        .iter()
+        .filter(|asset| {
+            let ext = asset
+                .absolute_path
+                .extension()
+                .and_then(|s| s.to_str())
+                .map(|s| s.to_ascii_lowercase());
+            matches!(ext.as_deref(), Some("wmf") | Some("emf"))
+        })
+        .map(|asset| {
+            let png_filename = asset
+                .file_name
+                .trim_end_matches(".wmf")
+                .trim_end_matches(".emf")
+                .trim_end_matches(".WMF")
+                .trim_end_matches(".EMF")
+                .to_string()
+                + ".png";
+            // Neither an extracted image nor another conversion may own the name
+            let png_filename = unique_name(png_filename, |candidate| {
+                assets.iter().any(|a| a.file_name.eq_ignore_ascii_case(candidate))
+                    || png_names.iter().any(|n| n.eq_ignore_ascii_case(candidate))
+            });
+            png_names.push(png_filename.clone());
+            ConversionJob {
+                file_name: asset.file_name.clone(),
+                source: asset.absolute_path.clone(),
+                target: assets_dir.join(png_filename),
+                status: ConversionStatus::Pending,
+                attempts: 0,
+                last_error: None,
+                params: params.clone(),
+            }
+        })
+        .collect();
+
+    for job in &mut jobs {
+        let cached = job.cache_path(cache_dir);
+        if job.copy_cached(cached.as_deref()) {
+            if let Some(asset) = assets.iter_mut().find(|a| a.absolute_path == job.source) {
+                asset.converted_path = Some(job.target.clone());
+            }
+        }
+    }
+
+    if let Some(workspace_dir) = assets_dir.parent() {
+        let _queues = running_queues();
+        save_conversions(workspace_dir, &jobs);
+    }
+}
+
+/// Record the outcome of `job` in the queue of `workspace_dir`. `false` when
+/// the queue could not be written.
+fn update_conversion(workspace_dir: &Path, job: &ConversionJob) -> bool {
+    let _queues = running_queues();
+    let mut jobs = load_conversions(workspace_dir);
+    // A new analysis may have replaced the queue meanwhile: its own entry
+    // for the same file is pending and is run next
+    let Some(entry) = jobs
+        .iter_mut()
+        .find(|entry| entry.source == job.source && entry.target == job.target && entry.params == job.params)
+    else {
+        return true;
+    };
+    if entry.status != ConversionStatus::Pending {
+        return true;
+    }
+    *entry = job.clone();
+    save_conversions(workspace_dir, &jobs)
+}
+
+/// Run the pending conversions of the job whose workspace is
+/// `workspace_dir`, off the analysis. Each conversion is saved to the queue
+/// as soon as it finishes, so a crash loses only those in progress;
+/// conversions queued meanwhile by another analysis are run too. Returns at
+/// once, with nothing, when the queue is already being run.
+///
+/// Returns the conversions run, in the order they were queued.
+pub async fn run_pending_conversions(workspace_dir: &Path, cache_dir: Option<&Path>) -> Vec<ConversionJob> {
+    {
+        let mut running = running_queues();
+        if running.iter().any(|dir| dir == workspace_dir) {
+            return Vec::new();
+        }
+        running.push(workspace_dir.to_path_buf());
+    }
+
+    let mut finished = Vec::new();
+    loop {
+        let pending: Vec<ConversionJob> = {
+            let mut running = running_queues();
+            let pending: Vec<_> = load_conversions(workspace_dir)
+                .into_iter()
+                .filter(|job| job.status == ConversionStatus::Pending)
+                .collect();
+            if pending.is_empty() {
+                running.retain(|dir| dir != workspace_dir);
+                break;
+            }
+            pending
+        };
+
+        let tasks: Vec<_> = pending
+            .into_iter()
+            .map(|mut job| {
+                let cache_dir = cache_dir.map(Path::to_path_buf);
+                task::spawn_blocking(move || {
+                    job.run(cache_dir.as_deref());
+                    job
+                })
+            })
+            .collect();
+        let mut saved = true;
+        for task in tasks {
+            if let Ok(job) = task.await {
+                log_conversion(&job);
+                saved &= update_conversion(workspace_dir, &job);
+                finished.push(job);
+            }
+        }
+        // The queue cannot be written: stop rather than run it again
+        if !saved {
+            running_queues().retain(|dir| dir != workspace_dir);
+            break;
+        }
+    }
+    finished
+}
+
+/// Queue again the conversions of a job that are not done and run them.
+/// Returns the whole queue, updated.
+pub async fn retry_failed_conversions(workspace_dir: &Path, cache_dir: Option<&Path>) -> Vec<ConversionJob> {
+    {
+        let _queues = running_queues();
+        let mut jobs = load_conversions(workspace_dir);
+        for job in jobs.iter_mut().filter(|job| job.status != ConversionStatus::Done) {
+            job.status = ConversionStatus::Pending;
+        }
+        save_conversions(workspace_dir, &jobs);
+    }
+    run_pending_conversions(workspace_dir, cache_dir).await;
+    load_conversions(workspace_dir)
+}
+
+/// Convert again at `dpi` the WMF/EMF files of a job converted at a lower
+/// resolution, into `<workspace>/assets/print_<dpi>/`, for print exports.
+/// Returns the conversions that succeeded; the job's own queue and PNGs are
+/// left as they are.
+pub async fn convert_for_print(workspace_dir: &Path, dpi: u32, cache_dir: Option<&Path>) -> Vec<ConversionJob> {
+    let dpi = dpi.clamp(MIN_DPI, MAX_DPI);
+    let print_dir = workspace_dir.join("assets").join(format!("print_{}", dpi));
+    let jobs: Vec<ConversionJob> = load_conversions(workspace_dir)
+        .into_iter()
+        .filter(|job| job.status == ConversionStatus::Done && job.params.density() < dpi)
+        .filter_map(|job| {
+            let target = print_dir.join(job.target.file_name()?);
+            Some(ConversionJob {
+                target,
+                status: ConversionStatus::Pending,
+                attempts: 0,
+                last_error: None,
+                params: ConversionParams { dpi, ..job.params },
+                ..job
+            })
+        })
+        .collect();
+    if jobs.is_empty() {
+        return jobs;
+    }
+    if let Err(e) = fs::create_dir_all(&print_dir) {
+        eprintln!("[WMF] Could not create {}: {}", print_dir.display(), e);
+        return Vec::new();
+    }
+
+    run_conversions(jobs, cache_dir)
+        .await
+        .into_iter()
+        .filter(|job| job.status == ConversionStatus::Done)
+        .collect()
+}
+
+fn hex_digest(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 /// Try to convert a WMF/EMF file to PNG using ImageMagick.
 /// 
 /// Returns:
 /// - Ok(true) if conversion succeeded
 /// - Ok(false) if ImageMagick is not available
 /// - Err(_) if conversion was attempted but failed
-fn convert_wmf_to_png(wmf_path: &Path, png_path: &Path) -> Result<bool, std::io::Error> {
+fn convert_wmf_to_png(wmf_path: &Path, png_path: &Path, params: &ConversionParams) -> Result<bool, std::io::Error> {
     // Convert paths to strings (ImageMagick needs string args)
     let wmf_str = wmf_path.to_string_lossy();
     let png_str = png_path.to_string_lossy();
-    
-    // Simple conversion without resize - let frontend handle sizing via CSS
-    let output = Command::new("magick")
-        .arg(wmf_str.as_ref())
-        .arg("-density")
-        .arg("96")  // Screen resolution
-        .arg("-trim")  // Remove whitespace
-        .arg(png_str.as_ref())
-        .output();
+
+    // No resize - let frontend handle sizing via CSS. Density and background
+    // apply when reading the vector file, so they come before it.
+    let mut command = Command::new("magick");
+    command.arg("-density").arg(params.density().to_string());
+    if !params.background.is_empty() {
+        command.arg("-background").arg(&params.background);
+    }
+    command.arg(wmf_str.as_ref());
+    if params.trim {
+        command.arg("-trim"); // Remove whitespace
+    }
+    let output = command.arg(png_str.as_ref()).output();
 
     match output {
         Ok(result) if result.status.success() => {
@@ -185,10 +672,7 @@ fn convert_wmf_to_png(wmf_path: &Path, png_path: &Path) -> Result<bool, std::io:
             let stderr = String::from_utf8_lossy(&result.stderr);
             let stdout = String::from_utf8_lossy(&result.stdout);
             eprintln!("[WMF] ImageMagick error: {}{}", stderr, stdout);
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("ImageMagick failed: {}{}", stderr, stdout)
-            ))
+            Err(std::io::Error::other(format!("ImageMagick failed: {}{}", stderr, stdout)))
         }
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             // ImageMagick not available
@@ -197,3 +681,120 @@ fn convert_wmf_to_png(wmf_path: &Path, png_path: &Path) -> Result<bool, std::io:
         Err(e) => Err(e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizes_media_entry_names() {
+        assert_eq!(sanitize_media_name("word/media/image1.png").as_deref(), Some("image1.png"));
+        assert_eq!(sanitize_media_name("word/media/a:b?.wmf").as_deref(), Some("a_b_.wmf"));
+        assert_eq!(sanitize_media_name("word/media/sub\\x.png").as_deref(), Some("x.png"));
+        assert_eq!(sanitize_media_name("word/media/../../evil.png"), None);
+        assert_eq!(sanitize_media_name("word/media/..\\..\\evil.png"), None);
+        assert_eq!(sanitize_media_name("/word/media/x.png"), None);
+        assert_eq!(sanitize_media_name("word/media/"), None);
+    }
+
+    #[test]
+    fn flattened_media_names_are_made_unique() {
+        let taken = ["image1.png", "IMAGE1_2.png", "chart"];
+        let is_taken = |name: &str| taken.iter().any(|t| t.eq_ignore_ascii_case(name));
+        assert_eq!(unique_name("image2.png".to_string(), is_taken), "image2.png");
+        assert_eq!(unique_name("Image1.PNG".to_string(), is_taken), "Image1_3.PNG");
+        assert_eq!(unique_name("chart".to_string(), is_taken), "chart_2");
+    }
+
+    #[test]
+    fn reuses_cached_conversion_of_same_content() {
+        let dir = std::env::temp_dir().join(format!("siromix-conversion-cache-{}", std::process::id()));
+        let cache_dir = dir.join("cache");
+        fs::create_dir_all(&cache_dir).unwrap();
+        let source = dir.join("image1.wmf");
+        fs::write(&source, b"wmf bytes").unwrap();
+        let params = ConversionParams::default();
+        let key = [b"wmf bytes".as_slice(), params.cache_key().as_bytes()].concat();
+        fs::write(cache_dir.join(format!("{}.png", hex_digest(&key))), b"png bytes").unwrap();
+
+        let mut job = ConversionJob {
+            file_name: "image1.wmf".to_string(),
+            source,
+            target: dir.join("image1.png"),
+            status: ConversionStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            params,
+        };
+        job.run(Some(&cache_dir));
+        assert_eq!(job.status, ConversionStatus::Done);
+        assert_eq!(job.attempts, 0);
+        assert_eq!(fs::read(&job.target).unwrap(), b"png bytes");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn resolution_is_clamped() {
+        let at = |dpi| ConversionParams { dpi, ..Default::default() };
+        assert_eq!(at(0).density(), MIN_DPI);
+        assert_eq!(at(300).density(), 300);
+        assert_eq!(at(u32::MAX).density(), MAX_DPI);
+        assert_eq!(at(5000).cache_key(), at(MAX_DPI).cache_key());
+    }
+
+    #[test]
+    fn cache_keeps_the_most_recently_used_pngs() {
+        let dir = std::env::temp_dir().join(format!("siromix-conversion-prune-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let now = SystemTime::now();
+        for (name, age) in [("old.png", 30), ("used.png", 20), ("new.png", 10)] {
+            let path = dir.join(name);
+            fs::write(&path, [0u8; 100]).unwrap();
+            File::options()
+                .append(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(now - Duration::from_secs(age))
+                .unwrap();
+        }
+
+        prune_cache(&dir, 250);
+        assert!(!dir.join("old.png").exists());
+        assert!(dir.join("used.png").exists() && dir.join("new.png").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn pending_conversions_run_after_analysis_and_are_saved() {
+        let dir = std::env::temp_dir().join(format!("siromix-conversion-queue-{}", std::process::id()));
+        let cache_dir = dir.join("cache");
+        fs::create_dir_all(&cache_dir).unwrap();
+        let source = dir.join("image1.wmf");
+        fs::write(&source, b"wmf bytes").unwrap();
+        let params = ConversionParams::default();
+        let key = [b"wmf bytes".as_slice(), params.cache_key().as_bytes()].concat();
+        let cached = cache_dir.join(format!("{}.png", hex_digest(&key)));
+        let job = ConversionJob {
+            file_name: "image1.wmf".to_string(),
+            source,
+            target: dir.join("image1.png"),
+            status: ConversionStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            params,
+        };
+        save_conversions(&dir, &[job]);
+
+        // Cached only after queueing, so the runner is the one to copy it
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        fs::write(&cached, b"png bytes").unwrap();
+        let finished = runtime.block_on(run_pending_conversions(&dir, Some(&cache_dir)));
+        assert_eq!(finished.len(), 1);
+        assert_eq!(load_conversions(&dir)[0].status, ConversionStatus::Done);
+        assert!(runtime.block_on(run_pending_conversions(&dir, Some(&cache_dir))).is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
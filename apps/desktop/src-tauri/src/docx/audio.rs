@@ -0,0 +1,265 @@
+// src-tauri/src/docx/audio.rs
+//! Recordings embedded in the source for listening questions.
+//!
+//! A sound inserted as an object (Insert > Object > Package, or a file
+//! dragged into Word) is stored as an OLE package under `word/embeddings/`
+//! and drawn as an icon, so the parser sees a picture. Sound files linked as
+//! media (`word/media/*.mp3`) are taken as they are. At analysis the
+//! recordings are extracted to `<workspace>/assets/audio/` and their icons
+//! replaced with `Segment::Audio` (`link`); the export copies them next to
+//! the variants with the play order of each (see `listening`).
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use zip::read::ZipArchive;
+
+use super::assets::{sanitize_media_name, MAX_MEDIA_FILE_BYTES};
+use super::model::{ParsedDoc, Segment};
+use super::parts;
+use super::AppError;
+
+/// Folder of `<workspace>/assets/` holding the recordings
+pub const AUDIO_DIR: &str = "audio";
+
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "m4a", "wma", "ogg", "aac", "mid", "midi"];
+
+/// Stream of an OLE package holding the embedded file
+const NATIVE_STREAM: &str = "\u{1}Ole10Native";
+
+/// A recording extracted from the source
+#[derive(Debug, Clone)]
+pub struct AudioAsset {
+    /// Relationship of `word/document.xml` pointing to the object or file
+    pub rel_id: String,
+    pub file_name: String,
+    pub absolute_path: PathBuf,
+}
+
+pub fn is_audio_name(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Extract the recordings of the `.docx` at `docx_path` into `audio_dir`
+/// (created only when there is one). Parts over the media size limit are
+/// rejected with `AppError::UnsafeArchive`, like the pictures.
+pub fn extract_audio(docx_path: &Path, audio_dir: &Path) -> Result<Vec<AudioAsset>, AppError> {
+    let mut archive = ZipArchive::new(File::open(docx_path)?)?;
+    let mut relationships: Vec<(String, String)> = parts::relationships(&mut archive)
+        .into_iter()
+        .filter(|(_, rel)| !rel.external)
+        .map(|(id, rel)| (id, parts::part_name(&rel.target)))
+        .collect();
+    // "rId2" before "rId10": file names given on collision follow the document
+    relationships.sort_by(|a, b| (a.0.len(), &a.0).cmp(&(b.0.len(), &b.0)));
+
+    let mut assets: Vec<AudioAsset> = Vec::new();
+    for (rel_id, part) in relationships {
+        let found = if is_audio_name(&part) {
+            read_limited(&mut archive, &part)?.map(|data| (part.clone(), data))
+        } else if part.starts_with("word/embeddings/") && part.to_ascii_lowercase().ends_with(".bin") {
+            let stem = Path::new(&part).file_stem().and_then(|s| s.to_str()).unwrap_or("audio").to_string();
+            read_limited(&mut archive, &part)?.and_then(|data| package_content(&data, &stem))
+        } else {
+            None
+        };
+        let Some((name, data)) = found else {
+            continue;
+        };
+        let Some(name) = sanitize_media_name(&name) else {
+            continue;
+        };
+        let file_name = if assets.iter().any(|a| a.file_name.eq_ignore_ascii_case(&name)) {
+            format!("{}_{}", rel_id, name)
+        } else {
+            name
+        };
+
+        fs::create_dir_all(audio_dir)?;
+        let out_path = audio_dir.join(&file_name);
+        // Replaced rather than rewritten, like the extracted media
+        let _ = fs::remove_file(&out_path);
+        fs::write(&out_path, &data)?;
+        assets.push(AudioAsset {
+            rel_id,
+            file_name,
+            absolute_path: out_path.canonicalize().unwrap_or(out_path),
+        });
+    }
+    Ok(assets)
+}
+
+/// Content of a package part, `None` when missing
+fn read_limited<R: Read + std::io::Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Option<Vec<u8>>, AppError> {
+    let Ok(entry) = archive.by_name(name) else {
+        return Ok(None);
+    };
+    let mut data = Vec::new();
+    entry.take(MAX_MEDIA_FILE_BYTES + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_MEDIA_FILE_BYTES {
+        return Err(AppError::UnsafeArchive(format!(
+            "{}: media larger than {} MB",
+            name,
+            MAX_MEDIA_FILE_BYTES / (1024 * 1024)
+        )));
+    }
+    Ok(Some(data))
+}
+
+/// Sound file held by an OLE object: a package (`Ole10Native`: size, flags,
+/// label, source path, temporary path, then the file) whose file is a sound,
+/// or a Sound Recorder object (the WAV data right after the size).
+fn package_content(ole: &[u8], fallback_stem: &str) -> Option<(String, Vec<u8>)> {
+    let mut compound = cfb::CompoundFile::open(Cursor::new(ole)).ok()?;
+    let mut native = Vec::new();
+    compound.open_stream(NATIVE_STREAM).ok()?.read_to_end(&mut native).ok()?;
+    let body = native.get(4..)?;
+    if body.starts_with(b"RIFF") {
+        return Some((format!("{}.wav", fallback_stem), body.to_vec()));
+    }
+
+    let mut rest = body.get(2..)?;
+    let label = take_cstr(&mut rest)?;
+    let source_path = take_cstr(&mut rest)?;
+    rest = rest.get(4..)?;
+    let temp_len = take_u32(&mut rest)? as usize;
+    rest = rest.get(temp_len..)?;
+    let data_len = take_u32(&mut rest)? as usize;
+    let data = rest.get(..data_len)?;
+    let name = [label, source_path].into_iter().find(|name| is_audio_name(name))?;
+    Some((name, data.to_vec()))
+}
+
+fn take_cstr(rest: &mut &[u8]) -> Option<String> {
+    let end = rest.iter().position(|&b| b == 0)?;
+    let text = String::from_utf8_lossy(&rest[..end]).into_owned();
+    *rest = &rest[end + 1..];
+    Some(text)
+}
+
+fn take_u32(rest: &mut &[u8]) -> Option<u32> {
+    let bytes: [u8; 4] = rest.get(..4)?.try_into().ok()?;
+    *rest = &rest[4..];
+    Some(u32::from_le_bytes(bytes))
+}
+
+/// Replace with `Segment::Audio` the icons of `doc` whose OLE object is one
+/// of the recordings. Returns how many were linked.
+pub fn link(doc: &mut ParsedDoc, assets: &[AudioAsset]) -> usize {
+    if assets.is_empty() {
+        return 0;
+    }
+    let ole_re = Regex::new(r#"<o:OLEObject\b[^>]*?\br:id="([^"]+)""#).unwrap();
+    let by_rel: HashMap<&str, &AudioAsset> = assets.iter().map(|a| (a.rel_id.as_str(), a)).collect();
+
+    let segments = doc
+        .questions
+        .iter_mut()
+        .flat_map(|q| {
+            q.stem.iter_mut().chain(q.stem_alt.iter_mut()).chain(
+                q.options
+                    .iter_mut()
+                    .flat_map(|o| o.content.iter_mut().chain(o.content_alt.iter_mut())),
+            )
+        })
+        .chain(doc.groups.iter_mut().flat_map(|g| g.header.iter_mut().chain(g.paragraphs.iter_mut().flatten())))
+        .chain(doc.parts.iter_mut().flat_map(|p| p.heading.iter_mut()));
+
+    let mut linked = 0;
+    for segment in segments {
+        let Segment::Image { raw_xml, .. } = segment else {
+            continue;
+        };
+        let Some(asset) = ole_re.captures(raw_xml).and_then(|caps| by_rel.get(&caps[1]).copied()) else {
+            continue;
+        };
+        let raw_xml = std::mem::take(raw_xml);
+        *segment = Segment::Audio {
+            asset_path: asset.absolute_path.to_string_lossy().to_string(),
+            file_name: asset.file_name.clone(),
+            raw_xml,
+        };
+        linked += 1;
+    }
+    linked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::Question;
+    use std::io::Write;
+
+    fn package(label: &str, data: &[u8]) -> Vec<u8> {
+        let mut native = vec![2, 0];
+        for text in [label, &format!("C:\\Nghe\\{}", label)] {
+            native.extend_from_slice(text.as_bytes());
+            native.push(0);
+        }
+        native.extend_from_slice(&[0, 0, 3, 0]);
+        native.extend_from_slice(&4u32.to_le_bytes());
+        native.extend_from_slice(b"tmp\0");
+        native.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        native.extend_from_slice(data);
+        let mut stream = (native.len() as u32).to_le_bytes().to_vec();
+        stream.extend(native);
+
+        let mut compound = cfb::CompoundFile::create(Cursor::new(Vec::new())).unwrap();
+        compound.create_stream(NATIVE_STREAM).unwrap().write_all(&stream).unwrap();
+        compound.flush().unwrap();
+        compound.into_inner().into_inner()
+    }
+
+    #[test]
+    fn extracts_packaged_sounds_and_links_their_icons() {
+        let dir = std::env::temp_dir().join(format!("siromix-audio-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let docx_path = dir.join("source.docx");
+
+        let mut zip = zip::ZipWriter::new(File::create(&docx_path).unwrap());
+        let options = zip::write::FileOptions::default();
+        zip.start_file("word/_rels/document.xml.rels", options).unwrap();
+        zip.write_all(concat!(
+            r#"<Relationships><Relationship Id="rId7" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/oleObject" Target="embeddings/oleObject1.bin"/>"#,
+            r#"<Relationship Id="rId8" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/oleObject" Target="embeddings/oleObject2.bin"/></Relationships>"#,
+        ).as_bytes()).unwrap();
+        zip.start_file("word/embeddings/oleObject1.bin", options).unwrap();
+        zip.write_all(&package("Track1.mp3", b"ID3 sound")).unwrap();
+        zip.start_file("word/embeddings/oleObject2.bin", options).unwrap();
+        zip.write_all(&package("bang.xlsx", b"PK sheet")).unwrap();
+        zip.finish().unwrap();
+
+        let assets = extract_audio(&docx_path, &dir.join(AUDIO_DIR)).unwrap();
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].file_name, "Track1.mp3");
+        assert_eq!(fs::read(&assets[0].absolute_path).unwrap(), b"ID3 sound");
+
+        let icon = |rel_id: &str| Segment::Image {
+            asset_path: "image1.png".to_string(),
+            raw_xml: format!(r#"<w:r><w:object><o:OLEObject Type="Embed" ProgID="Package" r:id="{}"/></w:object></w:r>"#, rel_id),
+            width_emu: 0,
+            height_emu: 0,
+            anchor: None,
+            ocr_text: None,
+        };
+        let mut doc = ParsedDoc {
+            questions: vec![Question {
+                number: 1,
+                stem: vec![icon("rId7"), icon("rId8")],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(link(&mut doc, &assets), 1);
+        assert!(matches!(&doc.questions[0].stem[0], Segment::Audio { file_name, .. } if file_name == "Track1.mp3"));
+        assert!(matches!(doc.questions[0].stem[1], Segment::Image { .. }));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,442 @@
+// src-tauri/src/docx/autofix.rs
+//! Safe corrections of common marking mistakes, applied to `document.xml`
+//! before the document is analyzed again.
+//!
+//! Only option label runs and a stray leading '#' are touched; paragraphs
+//! are edited in place so paragraph indices (and manual reassignments) stay
+//! valid. A label split over several runs is rebuilt as one run, which is why
+//! the spacing and mark-style fixes of such a label need `MergeLabelRuns`.
+//!
+//! Paragraphs are read like the parser reads them: fields reduced to their
+//! display text (`fields::flatten`), and the paragraphs of merged altChunks
+//! and subdocuments counted in the indices, though those parts are never
+//! edited. A corrected paragraph is written back flattened.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use zip::read::ZipArchive;
+use zip::write::{FileOptions, ZipWriter};
+
+use super::fields;
+use super::labels;
+use super::parts::PartNotice;
+use super::AppError;
+
+/// How many leading runs a split label may span ("#", "A", " ", ".")
+const MAX_LABEL_RUNS: usize = 4;
+
+/// Consistent style of the correct-answer mark: red and underlined
+const MARK_COLOR: &str = "FF0000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AutofixRule {
+    /// "A ." → "A."
+    LabelSpacing,
+    /// Label split over several runs ("A" + ".") → one run
+    MergeLabelRuns,
+    /// A label marked only red or only underlined → red and underlined
+    MarkStyle,
+    /// '#' at the start of a paragraph that is not a locked option label
+    StrayHash,
+}
+
+/// One corrected paragraph
+#[derive(Debug, Clone, Serialize)]
+pub struct AutofixChange {
+    pub rule: AutofixRule,
+    #[serde(rename = "paragraphIndex")]
+    pub paragraph_index: usize,
+    pub before: String,
+    pub after: String,
+}
+
+/// A text-only run of a paragraph (byte range in the paragraph XML)
+#[derive(Debug)]
+struct Run {
+    start: usize,
+    end: usize,
+    rpr: String,
+    text: String,
+}
+
+/// Apply `rules` to every paragraph of `document_xml`. `parts` are the
+/// notices of `parts::merge` on the same XML.
+pub fn apply(
+    document_xml: &str,
+    parts: &[PartNotice],
+    rules: &[AutofixRule],
+    max_options: usize,
+) -> (String, Vec<AutofixChange>) {
+    let label_re = Regex::new(&format!(r"^\s*#?{}\s*\.", labels::label_char_class(max_options))).unwrap();
+
+    let mut output = String::with_capacity(document_xml.len());
+    let mut changes = Vec::new();
+    let mut cursor = 0;
+    let mut paragraph_index = 0;
+    let mut parts = parts.iter().peekable();
+
+    // Same paragraph walk as the parser, so indices match `paragraph_index`
+    while let Some(start_rel) = document_xml[cursor..].find("<w:p") {
+        let start = cursor + start_rel;
+
+        // Merged parts come before this paragraph in the parser's walk; a
+        // subdocument replaces its placeholder paragraph, left as it is
+        let mut skip_to = None;
+        while let Some(part) = parts.next_if(|part| part.source_range.start <= start) {
+            paragraph_index += part.paragraph_count;
+            if start < part.source_range.end {
+                skip_to = Some(part.source_range.end);
+            }
+        }
+        if let Some(end) = skip_to {
+            output.push_str(&document_xml[cursor..end]);
+            cursor = end;
+            continue;
+        }
+
+        let Some(end_rel) = document_xml[start..].find("</w:p>") else {
+            break;
+        };
+        let end = start + end_rel + "</w:p>".len();
+
+        let raw = &document_xml[start..end];
+        let mut block = fields::flatten(raw).into_owned();
+        let changes_before = changes.len();
+        if rules.contains(&AutofixRule::StrayHash) {
+            fix_stray_hash(&mut block, &label_re, paragraph_index, &mut changes);
+        }
+        fix_label(&mut block, rules, &label_re, paragraph_index, &mut changes);
+
+        output.push_str(&document_xml[cursor..start]);
+        output.push_str(if changes.len() > changes_before { &block } else { raw });
+        cursor = end;
+        paragraph_index += 1;
+    }
+    output.push_str(&document_xml[cursor..]);
+    (output, changes)
+}
+
+/// Rebuild the leading label runs of an option paragraph.
+fn fix_label(
+    block: &mut String,
+    rules: &[AutofixRule],
+    label_re: &Regex,
+    paragraph_index: usize,
+    changes: &mut Vec<AutofixChange>,
+) {
+    let runs = text_runs(block);
+    let leading: Vec<&Run> = runs
+        .iter()
+        .take(MAX_LABEL_RUNS)
+        .take_while(|run| !run.text.is_empty())
+        .collect();
+
+    let Some(first) = leading.first() else {
+        return;
+    };
+    if !only_markup(&block[..first.start]) {
+        return;
+    }
+
+    // Smallest number of leading runs holding the whole label
+    let mut text = String::new();
+    let mut label_runs = 0;
+    for (index, run) in leading.iter().enumerate() {
+        // Runs must be contiguous, with only proofing marks in between
+        if index > 0 && !only_markup(&block[leading[index - 1].end..run.start]) {
+            return;
+        }
+        text.push_str(&run.text);
+        if label_re.is_match(&text) {
+            label_runs = index + 1;
+            break;
+        }
+    }
+    if label_runs == 0 {
+        return;
+    }
+    let label_match = label_re.find(&text).unwrap();
+    let label = &text[..label_match.end()];
+    let rest = &text[label_match.end()..];
+
+    let mut applied = Vec::new();
+    if label_runs > 1 {
+        if !rules.contains(&AutofixRule::MergeLabelRuns) {
+            return;
+        }
+        applied.push(AutofixRule::MergeLabelRuns);
+    }
+
+    let mut new_label = label.to_string();
+    if rules.contains(&AutofixRule::LabelSpacing) {
+        let spaced = Regex::new(r"\s+\.$").unwrap();
+        if spaced.is_match(&new_label) {
+            new_label = spaced.replace(&new_label, ".").into_owned();
+            applied.push(AutofixRule::LabelSpacing);
+        }
+    }
+
+    let label_run_list = &leading[..label_runs];
+    let mut rpr = label_run_list
+        .iter()
+        .find(|run| is_marked(&run.rpr))
+        .unwrap_or(&label_run_list[0])
+        .rpr
+        .clone();
+    if rules.contains(&AutofixRule::MarkStyle) && is_marked(&rpr) {
+        let styled = mark_style(&rpr);
+        if styled != rpr {
+            rpr = styled;
+            applied.push(AutofixRule::MarkStyle);
+        }
+    }
+
+    if applied.is_empty() {
+        return;
+    }
+
+    let last = label_run_list[label_runs - 1];
+    let mut replacement = run_xml(&rpr, &new_label);
+    if !rest.is_empty() {
+        replacement.push_str(&run_xml(&last.rpr, rest));
+    }
+    let before = paragraph_text(block);
+    block.replace_range(label_run_list[0].start..last.end, &replacement);
+    let after = paragraph_text(block);
+    for rule in applied {
+        changes.push(AutofixChange {
+            rule,
+            paragraph_index,
+            before: before.clone(),
+            after: after.clone(),
+        });
+    }
+}
+
+/// Whether `xml` holds no run (paragraph start, proofing marks)
+fn only_markup(xml: &str) -> bool {
+    !xml.contains("<w:r>") && !xml.contains("<w:r ") && !xml.contains("<m:") && !xml.contains("<w:hyperlink")
+}
+
+/// Remove a leading '#' that is not the lock marker of an option label.
+fn fix_stray_hash(block: &mut String, label_re: &Regex, paragraph_index: usize, changes: &mut Vec<AutofixChange>) {
+    let before = paragraph_text(block);
+    if !before.trim_start().starts_with('#') || label_re.is_match(&before) {
+        return;
+    }
+    let Some(run) = text_runs(block).into_iter().find(|run| run.text.contains('#')) else {
+        return;
+    };
+    let text = run.text.replacen('#', "", 1);
+    block.replace_range(run.start..run.end, &run_xml(&run.rpr, &text));
+    changes.push(AutofixChange {
+        rule: AutofixRule::StrayHash,
+        paragraph_index,
+        after: paragraph_text(block),
+        before,
+    });
+}
+
+/// Runs made only of run properties and `<w:t>` elements, in order.
+fn text_runs(block: &str) -> Vec<Run> {
+    let run_re = Regex::new(r"(?s)<w:r(?:\s[^>]*)?>(.*?)</w:r>").unwrap();
+    let simple_re = Regex::new(r"(?s)^(<w:rPr>.*?</w:rPr>)?((?:<w:t(?:\s[^>]*)?>[^<]*</w:t>|<w:t(?:\s[^>]*)?/>)+)$").unwrap();
+    let text_re = Regex::new(r"<w:t(?:\s[^>]*)?>([^<]*)</w:t>").unwrap();
+
+    run_re
+        .captures_iter(block)
+        .filter_map(|caps| {
+            let whole = caps.get(0).unwrap();
+            let simple = simple_re.captures(&caps[1])?;
+            let text = text_re
+                .captures_iter(&simple[2])
+                .map(|t| decode_entities(&t[1]))
+                .collect();
+            Some(Run {
+                start: whole.start(),
+                end: whole.end(),
+                rpr: simple.get(1).map(|m| m.as_str().to_string()).unwrap_or_default(),
+                text,
+            })
+        })
+        .collect()
+}
+
+fn run_xml(rpr: &str, text: &str) -> String {
+    format!(r#"<w:r>{}<w:t xml:space="preserve">{}</w:t></w:r>"#, rpr, encode_entities(text))
+}
+
+/// (underlined, red) as the validator reads the mark
+fn mark_flags(rpr: &str) -> (bool, bool) {
+    let underline_re = Regex::new(r"<w:u\b[^>]*>").unwrap();
+    let red_re = Regex::new(&format!(r#"(?i)<w:color\b[^>]*w:val="{}""#, MARK_COLOR)).unwrap();
+    let underline = underline_re
+        .find_iter(rpr)
+        .any(|m| !m.as_str().contains("w:val=\"none\""));
+    (underline, red_re.is_match(rpr))
+}
+
+fn is_marked(rpr: &str) -> bool {
+    let (underline, red) = mark_flags(rpr);
+    underline || red
+}
+
+/// Run properties with both the red color and a single underline, keeping
+/// the element order of the schema (color before sz, u after highlight).
+fn mark_style(rpr: &str) -> String {
+    if mark_flags(rpr) == (true, true) {
+        return rpr.to_string();
+    }
+    let color_re = Regex::new(r"<w:color\b[^>]*/>").unwrap();
+    let underline_re = Regex::new(r"<w:u\b[^>]*/>").unwrap();
+    let props = if rpr.is_empty() { "<w:rPr></w:rPr>" } else { rpr };
+    let props = color_re.replace_all(props, "");
+    let props = underline_re.replace_all(&props, "");
+
+    let props = insert_property(
+        &props,
+        &format!(r#"<w:color w:val="{}"/>"#, MARK_COLOR),
+        &["<w:spacing", "<w:w ", "<w:kern", "<w:position", "<w:sz", "<w:highlight", "<w:effect", "<w:bdr", "<w:shd", "<w:vertAlign", "<w:lang"],
+    );
+    insert_property(
+        &props,
+        r#"<w:u w:val="single"/>"#,
+        &["<w:effect", "<w:bdr", "<w:shd", "<w:vertAlign", "<w:lang"],
+    )
+}
+
+/// Insert `element` before the first of `followers`, else at the end.
+fn insert_property(rpr: &str, element: &str, followers: &[&str]) -> String {
+    let end = rpr.rfind("</w:rPr>").unwrap_or(rpr.len());
+    let at = followers
+        .iter()
+        .filter_map(|tag| rpr[..end].find(tag))
+        .min()
+        .unwrap_or(end);
+    format!("{}{}{}", &rpr[..at], element, &rpr[at..])
+}
+
+fn paragraph_text(block: &str) -> String {
+    let text_re = Regex::new(r"<w:t(?:\s[^>]*)?>([^<]*)</w:t>").unwrap();
+    text_re.captures_iter(block).map(|caps| decode_entities(&caps[1])).collect()
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn encode_entities(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Replace `word/document.xml` in the package at `docx_path`, keeping every
+/// other entry as it is. The package is written next to it and renamed over
+/// it, so a failure leaves the original intact.
+pub fn write_document_xml(docx_path: &Path, document_xml: &str) -> Result<(), AppError> {
+    let mut data = Vec::new();
+    File::open(docx_path)?.read_to_end(&mut data)?;
+    let mut archive = ZipArchive::new(std::io::Cursor::new(data))?;
+
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        let name = entry.name().to_string();
+        zip.start_file(name.as_str(), options)?;
+        if name == "word/document.xml" {
+            zip.write_all(document_xml.as_bytes())?;
+        } else {
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            zip.write_all(&content)?;
+        }
+    }
+    let bytes = zip.finish()?.into_inner();
+    let temp_path = docx_path.with_extension("docx.tmp");
+    let written = File::create(&temp_path)
+        .and_then(|mut file| file.write_all(&bytes).and_then(|_| file.sync_all()))
+        .and_then(|_| std::fs::rename(&temp_path, docx_path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    Ok(written?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::parts::PartKind;
+
+    #[test]
+    fn fixes_split_spaced_and_half_marked_labels() {
+        let xml = concat!(
+            r#"<w:body><w:p><w:r><w:t>Câu 1. Chọn đáp án đúng</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:rPr><w:u w:val="single"/></w:rPr><w:t>A</w:t></w:r><w:r><w:t xml:space="preserve"> . 2</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>B. 3</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>#Ghi chú</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>#C. 4</w:t></w:r></w:p></w:body>"#,
+        );
+        let rules = [
+            AutofixRule::LabelSpacing,
+            AutofixRule::MergeLabelRuns,
+            AutofixRule::MarkStyle,
+            AutofixRule::StrayHash,
+        ];
+        let (fixed, changes) = apply(xml, &[], &rules, labels::DEFAULT_MAX_OPTIONS);
+
+        assert!(fixed.contains(
+            r#"<w:r><w:rPr><w:color w:val="FF0000"/><w:u w:val="single"/></w:rPr><w:t xml:space="preserve">A.</w:t></w:r><w:r><w:t xml:space="preserve"> 2</w:t></w:r>"#
+        ));
+        assert!(fixed.contains(r#"<w:t xml:space="preserve">Ghi chú</w:t>"#));
+        // Locked option and untouched paragraphs stay as they are
+        assert!(fixed.contains(r#"<w:t>#C. 4</w:t>"#));
+        assert!(fixed.contains(r#"<w:t>B. 3</w:t>"#));
+
+        let rules: Vec<(AutofixRule, usize)> = changes.iter().map(|c| (c.rule, c.paragraph_index)).collect();
+        assert_eq!(
+            rules,
+            vec![
+                (AutofixRule::MergeLabelRuns, 1),
+                (AutofixRule::LabelSpacing, 1),
+                (AutofixRule::MarkStyle, 1),
+                (AutofixRule::StrayHash, 3),
+            ]
+        );
+        assert_eq!(changes[0].after, "A. 2");
+    }
+
+    #[test]
+    fn indices_follow_the_parser_across_fields_and_merged_parts() {
+        let question = r#"<w:p><w:r><w:t>Câu 1. Chọn</w:t></w:r></w:p>"#;
+        let chunk = r#"<w:altChunk r:id="rId9"/>"#;
+        let option = concat!(
+            r#"<w:p><w:bookmarkStart w:id="0" w:name="_GoBack"/><w:r><w:t>A</w:t></w:r>"#,
+            r#"<w:r><w:t xml:space="preserve"> . 2</w:t></w:r><w:bookmarkEnd w:id="0"/></w:p>"#,
+        );
+        let xml = format!("<w:body>{question}{chunk}{option}</w:body>");
+        let start = xml.find(chunk).unwrap();
+        let merged_chunk = PartNotice {
+            kind: PartKind::AltChunk,
+            target: "afchunk.mht".to_string(),
+            after_question: Some(1),
+            merged: true,
+            images_dropped: 0,
+            source_range: start..start + chunk.len(),
+            paragraph_count: 3,
+        };
+
+        let (fixed, changes) = apply(&xml, &[merged_chunk], &[AutofixRule::LabelSpacing, AutofixRule::MergeLabelRuns], 4);
+        assert_eq!(changes[0].paragraph_index, 4);
+        assert!(fixed.contains(chunk));
+        assert!(fixed.contains(r#"<w:t xml:space="preserve">A.</w:t>"#));
+    }
+}
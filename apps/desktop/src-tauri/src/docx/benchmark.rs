@@ -0,0 +1,210 @@
+// src-tauri/src/docx/benchmark.rs
+//! Synthetic exam for the support benchmark (`run_benchmark`).
+//!
+//! The document is generated rather than shipped so every installation times
+//! exactly the same input: numbered questions with four options, the correct
+//! label underlined, an equation every fifth question and a PNG every tenth.
+//! Timings from two machines can then be compared directly.
+
+use std::io::{Cursor, Write};
+use std::time::Instant;
+
+use serde::Serialize;
+use zip::write::{FileOptions, ZipWriter};
+
+/// Questions of the benchmark document
+pub const QUESTION_COUNT: u32 = 100;
+
+/// Variants mixed and written by the benchmark
+pub const VARIANT_COUNT: usize = 4;
+
+const IMAGE_EVERY: u32 = 10;
+const EQUATION_EVERY: u32 = 5;
+const LABELS: [&str; 4] = ["A", "B", "C", "D"];
+
+/// Time spent in one phase of the benchmark
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTiming {
+    /// `unzip`, `convert`, `parse`, `mix` or `write`
+    pub phase: &'static str,
+    #[serde(rename = "ms")]
+    pub millis: u128,
+}
+
+/// Times consecutive phases
+pub struct Stopwatch {
+    started: Instant,
+    lap: Instant,
+    phases: Vec<PhaseTiming>,
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        let now = Instant::now();
+        Self {
+            started: now,
+            lap: now,
+            phases: Vec::new(),
+        }
+    }
+
+    /// End `phase`, started when the previous one ended
+    pub fn lap(&mut self, phase: &'static str) {
+        let now = Instant::now();
+        self.phases.push(PhaseTiming {
+            phase,
+            millis: now.duration_since(self.lap).as_millis(),
+        });
+        self.lap = now;
+    }
+
+    /// Phases and total time, in milliseconds
+    pub fn finish(self) -> (Vec<PhaseTiming>, u128) {
+        (self.phases, self.started.elapsed().as_millis())
+    }
+}
+
+fn text_run(text: &str) -> String {
+    format!(r#"<w:r><w:t xml:space="preserve">{text}</w:t></w:r>"#)
+}
+
+fn image_run(rel_id: &str) -> String {
+    format!(
+        concat!(
+            r#"<w:r><w:drawing><wp:inline><wp:extent cx="914400" cy="914400"/><a:graphic>"#,
+            r#"<a:graphicData><pic:pic><pic:blipFill><a:blip r:embed="{}"/></pic:blipFill></pic:pic>"#,
+            r#"</a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"#
+        ),
+        rel_id
+    )
+}
+
+fn question_xml(number: u32) -> String {
+    let mut stem = text_run(&format!("Câu {number}. Giá trị của biểu thức {number} + {} bằng", number + 1));
+    if number.is_multiple_of(EQUATION_EVERY) {
+        stem.push_str(&text_run(" (biết "));
+        stem.push_str(&format!("<m:oMath><m:r><m:t>x={number}</m:t></m:r></m:oMath>"));
+        stem.push_str(&text_run(")"));
+    }
+    if number.is_multiple_of(IMAGE_EVERY) {
+        stem.push_str(&image_run(&format!("rIdImg{}", number / IMAGE_EVERY)));
+    }
+
+    let mut xml = format!("<w:p>{stem}</w:p>");
+    let correct = (number as usize) % LABELS.len();
+    for (i, label) in LABELS.iter().enumerate() {
+        let mark = if i == correct { r#"<w:rPr><w:u w:val="single"/></w:rPr>"# } else { "" };
+        xml.push_str(&format!(
+            r#"<w:p><w:r>{mark}<w:t>{label}.</w:t></w:r>{}</w:p>"#,
+            text_run(&format!(" {}", (2 * number + 1) as i64 + i as i64 - correct as i64))
+        ));
+    }
+    xml
+}
+
+/// Small PNG, different for each image so none is deduplicated
+fn png(seed: u32) -> Vec<u8> {
+    let shade = (seed * 37 % 200) as u8;
+    let image = image::RgbImage::from_fn(120, 80, |x, y| {
+        image::Rgb([shade, (x % 256) as u8, (y * 3 % 256) as u8])
+    });
+    let mut data = Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgb8(image)
+        .write_to(&mut data, image::ImageOutputFormat::Png)
+        .expect("PNG encoding in memory");
+    data.into_inner()
+}
+
+/// The benchmark `.docx` with `question_count` questions
+pub fn synthetic_docx(question_count: u32) -> zip::result::ZipResult<Vec<u8>> {
+    let body: String = (1..=question_count).map(question_xml).collect();
+    let document_xml = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" "#,
+            r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" "#,
+            r#"xmlns:m="http://schemas.openxmlformats.org/officeDocument/2006/math" "#,
+            r#"xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" "#,
+            r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" "#,
+            r#"xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture">"#,
+            r#"<w:body>{}</w:body></w:document>"#
+        ),
+        body
+    );
+
+    let images = question_count / IMAGE_EVERY;
+    let relationships: String = (1..=images)
+        .map(|i| {
+            format!(
+                r#"<Relationship Id="rIdImg{i}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="media/image{i}.png"/>"#
+            )
+        })
+        .collect();
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default();
+    zip.start_file("[Content_Types].xml", options)?;
+    zip.write_all(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+            r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+            r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+            r#"<Default Extension="png" ContentType="image/png"/>"#,
+            r#"<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>"#,
+            r#"</Types>"#
+        )
+        .as_bytes(),
+    )?;
+    zip.start_file("_rels/.rels", options)?;
+    zip.write_all(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+            r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>"#,
+            r#"</Relationships>"#
+        )
+        .as_bytes(),
+    )?;
+    zip.start_file("word/document.xml", options)?;
+    zip.write_all(document_xml.as_bytes())?;
+    zip.start_file("word/_rels/document.xml.rels", options)?;
+    zip.write_all(
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{relationships}</Relationships>"#
+        )
+        .as_bytes(),
+    )?;
+    for i in 1..=images {
+        zip.start_file(format!("word/media/image{i}.png"), options)?;
+        zip.write_all(&png(i))?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::parser::{self, ParseOptions};
+    use crate::docx::validator;
+
+    #[test]
+    fn synthetic_document_parses_completely() {
+        let dir = std::env::temp_dir().join(format!("siromix-benchmark-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let docx_path = dir.join("benchmark.docx");
+        std::fs::write(&docx_path, synthetic_docx(20).unwrap()).unwrap();
+        let document_xml = crate::docx::read::read_document_xml(&docx_path);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let document_xml = document_xml.unwrap();
+        let options = ParseOptions::default();
+        let doc = parser::parse_document_xml_to_parsed_doc(&document_xml, &[], &options);
+        assert_eq!(doc.questions.len(), 20);
+        assert!(doc.questions.iter().all(|q| q.options.len() == 4));
+
+        let runs = parser::collect_labeled_option_runs(&document_xml, &options);
+        let correct = validator::detect_correct_label_for_question(7, &runs[&7]).unwrap();
+        assert_eq!(correct, "D");
+    }
+}
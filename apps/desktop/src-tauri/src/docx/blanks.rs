@@ -0,0 +1,148 @@
+// src-tauri/src/docx/blanks.rs
+//! Fill-in blanks: runs of dots, underscores or ellipses typed in the
+//! source ("Thủ đô của Việt Nam là ..........").
+//!
+//! Their length in the source is arbitrary and they wrap or collapse once
+//! the text is laid out again, so they are taken out of the text as
+//! `Segment::Blank` and printed by the writer with a width of its own.
+
+use regex::Regex;
+
+use super::model::{Question, Segment};
+
+/// Shortest run counted as a blank, in characters ("…" counts as three)
+pub const MIN_BLANK_WIDTH: u32 = 4;
+
+/// Dots printed for a blank inside a line, short and long
+const SHORT_BLANK_DOTS: usize = 10;
+const LONG_BLANK_DOTS: usize = 25;
+
+/// Blanks from this width on are printed long
+const LONG_BLANK_FROM: u32 = 15;
+
+/// Number of dots printed for a blank of `width` inside a line: blanks get
+/// one of two fixed lengths, whatever their exact length in the source.
+/// A blank ending its paragraph runs to the right margin instead.
+pub fn printed_dots(width: u32) -> usize {
+    if width < LONG_BLANK_FROM {
+        SHORT_BLANK_DOTS
+    } else {
+        LONG_BLANK_DOTS
+    }
+}
+
+/// Width of a run of blank characters, in characters
+fn width(run: &str) -> u32 {
+    run.chars().map(|c| if c == '…' { 3 } else { 1 }).sum()
+}
+
+/// Split the blanks out of the text segments of `segments`. A blank split
+/// over several runs becomes one segment.
+pub fn split(segments: Vec<Segment>) -> Vec<Segment> {
+    let run_re = Regex::new(r"[._…]+").unwrap();
+    let mut result: Vec<Segment> = Vec::with_capacity(segments.len());
+
+    for segment in segments {
+        let Segment::Text { text, raw_xml } = segment else {
+            result.push(segment);
+            continue;
+        };
+        let mut cursor = 0;
+        for run in run_re.find_iter(&text) {
+            let run_width = width(run.as_str());
+            // A short run touching a blank of the previous segment continues it
+            let continues = run.start() == 0 && matches!(result.last(), Some(Segment::Blank { .. }));
+            if run_width < MIN_BLANK_WIDTH && !continues {
+                continue;
+            }
+            push_text(&mut result, &text[cursor..run.start()], &raw_xml);
+            push_blank(&mut result, run_width);
+            cursor = run.end();
+        }
+        push_text(&mut result, &text[cursor..], &raw_xml);
+    }
+
+    // Short runs ending a segment right before a blank
+    let mut i = 1;
+    while i < result.len() {
+        if let (Segment::Text { text, .. }, Segment::Blank { .. }) = (&result[i - 1], &result[i]) {
+            let kept = text.trim_end_matches(['.', '_', '…']).len();
+            let tail = width(&text[kept..]);
+            if tail > 0 {
+                if let Segment::Blank { width } = &mut result[i] {
+                    *width += tail;
+                }
+                if let Segment::Text { text, .. } = &mut result[i - 1] {
+                    text.truncate(kept);
+                }
+                if kept == 0 {
+                    result.remove(i - 1);
+                    continue;
+                }
+            }
+        }
+        i += 1;
+    }
+    result
+}
+
+fn push_text(result: &mut Vec<Segment>, text: &str, raw_xml: &str) {
+    if !text.is_empty() {
+        result.push(Segment::Text {
+            text: text.to_string(),
+            raw_xml: raw_xml.to_string(),
+        });
+    }
+}
+
+fn push_blank(result: &mut Vec<Segment>, width: u32) {
+    match result.last_mut() {
+        Some(Segment::Blank { width: previous }) => *previous += width,
+        _ => result.push(Segment::Blank { width }),
+    }
+}
+
+/// Split the blanks of the stem and options of `question`, both languages.
+pub fn split_question(question: &mut Question) {
+    question.stem = split(std::mem::take(&mut question.stem));
+    question.stem_alt = split(std::mem::take(&mut question.stem_alt));
+    for option in &mut question.options {
+        option.content = split(std::mem::take(&mut option.content));
+        option.content_alt = split(std::mem::take(&mut option.content_alt));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Segment {
+        Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }
+    }
+
+    fn describe(segments: &[Segment]) -> Vec<String> {
+        segments
+            .iter()
+            .map(|s| match s {
+                Segment::Text { text, .. } => text.clone(),
+                Segment::Blank { width } => format!("[{width}]"),
+                _ => "?".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_blank_runs_out_of_text() {
+        let segments = split(vec![text("Thủ đô là ..........; dân số … triệu, diện tích ___ km2.")]);
+        assert_eq!(describe(&segments), vec!["Thủ đô là ", "[10]", "; dân số … triệu, diện tích ___ km2."]);
+
+        let segments = split(vec![text("Điền vào chỗ trống: ..."), text("......"), text("__ (1)")]);
+        assert_eq!(describe(&segments), vec!["Điền vào chỗ trống: ", "[11]", " (1)"]);
+
+        let segments = split(vec![text("……")]);
+        assert_eq!(describe(&segments), vec!["[6]"]);
+    }
+}
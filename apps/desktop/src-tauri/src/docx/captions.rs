@@ -0,0 +1,140 @@
+// src-tauri/src/docx/captions.rs
+//! Figure and table captions ("Hình 1.", "Bảng 2:") and their renumbering
+//! after shuffling.
+//!
+//! A caption paragraph belongs to the question it was parsed into, so it
+//! always moves with its figure. Once a variant's question order is known,
+//! captions are renumbered in reading order (per kind) and every reference
+//! to them ("quan sát hình 2") is rewritten to match. Captions are keyed by
+//! question: two questions may each have their own "Hình 1". A reference
+//! goes to the caption of its own question, else to the only question with
+//! that caption; references to numbers with no caption, or with one in
+//! several other questions, are left alone.
+
+use std::collections::HashMap;
+
+use regex::{Captures, Regex};
+
+use super::mixer::MixedQuestion;
+use super::model::Segment;
+
+/// Words introducing a caption or a reference to one
+const KINDS: &str = "Hình|Bảng|Biểu đồ|Sơ đồ|Figure|Table";
+
+/// Renumber captions of a variant (questions in display order) and the
+/// references to them.
+pub fn renumber(questions: &mut [MixedQuestion]) {
+    let caption_re = Regex::new(&format!(r"^\s*({})\s+(\d+)\s*[.:]", KINDS)).unwrap();
+    let reference_re = Regex::new(&format!(r"(?i)\b({})\s+(\d+)\b", KINDS)).unwrap();
+
+    // (question index, kind, old number) -> new number, in order of first caption
+    let mut numbers: HashMap<(usize, String, String), usize> = HashMap::new();
+    // (kind, old number) -> questions captioning it
+    let mut captioned_in: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    let mut counters: HashMap<String, usize> = HashMap::new();
+    for (index, question) in questions.iter_mut().enumerate() {
+        for segment in question.segments_mut() {
+            let Segment::Text { text, .. } = segment else {
+                continue;
+            };
+            if let Some(caps) = caption_re.captures(text) {
+                let kind = caps[1].to_lowercase();
+                let key = (index, kind.clone(), caps[2].to_string());
+                if numbers.contains_key(&key) {
+                    continue;
+                }
+                let counter = counters.entry(kind.clone()).or_insert(0);
+                *counter += 1;
+                numbers.insert(key, *counter);
+                captioned_in.entry((kind, caps[2].to_string())).or_default().push(index);
+            }
+        }
+    }
+    if numbers.is_empty() {
+        return;
+    }
+
+    for (index, question) in questions.iter_mut().enumerate() {
+        for segment in question.segments_mut() {
+            let Segment::Text { text, .. } = segment else {
+                continue;
+            };
+            let renumbered = reference_re.replace_all(text, |caps: &Captures| {
+                let (kind, old) = (caps[1].to_lowercase(), caps[2].to_string());
+                let owner = match captioned_in.get(&(kind.clone(), old.clone())) {
+                    Some(owners) if owners.contains(&index) => Some(index),
+                    Some(owners) if owners.len() == 1 => Some(owners[0]),
+                    _ => None,
+                };
+                match owner.and_then(|owner| numbers.get(&(owner, kind, old))) {
+                    Some(number) => format!("{} {}", &caps[1], number),
+                    None => caps[0].to_string(),
+                }
+            });
+            if renumbered != *text {
+                *text = renumbered.into_owned();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(display_number: u32, stem: &[&str]) -> MixedQuestion {
+        MixedQuestion {
+            original_number: display_number,
+            display_number,
+            stem: stem
+                .iter()
+                .map(|text| Segment::Text {
+                    text: text.to_string(),
+                    raw_xml: String::new(),
+                })
+                .collect(),
+            options: Vec::new(),
+            correct_answer: "A".to_string(),
+            stem_alt: Vec::new(),
+        }
+    }
+
+    fn stem_text(question: &MixedQuestion) -> Vec<String> {
+        question
+            .stem
+            .iter()
+            .filter_map(|s| match s {
+                Segment::Text { text, .. } => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn renumbers_in_display_order() {
+        // Originally question 2 (Hình 2) now comes first
+        let mut questions = vec![
+            question(1, &["Quan sát hình 2 và bảng 1.", "Hình 2. Lực ma sát"]),
+            question(2, &["Hình 1: Mặt phẳng nghiêng", "Dựa vào Hình 1, tính góc."]),
+            question(3, &["Xem Hình 7 trong sách."]),
+        ];
+        renumber(&mut questions);
+
+        assert_eq!(stem_text(&questions[0]), vec!["Quan sát hình 1 và bảng 1.", "Hình 1. Lực ma sát"]);
+        assert_eq!(stem_text(&questions[1]), vec!["Hình 2: Mặt phẳng nghiêng", "Dựa vào Hình 2, tính góc."]);
+        // No caption for Hình 7: untouched
+        assert_eq!(stem_text(&questions[2]), vec!["Xem Hình 7 trong sách."]);
+    }
+
+    #[test]
+    fn same_caption_in_two_questions_is_numbered_apart() {
+        let mut questions = vec![
+            question(1, &["Hình 1. Con lắc đơn", "Chu kì của con lắc ở hình 1?"]),
+            question(2, &["Hình 1. Mạch điện", "Điện trở tương đương của mạch ở Hình 1?"]),
+        ];
+        renumber(&mut questions);
+
+        assert_eq!(stem_text(&questions[0]), vec!["Hình 1. Con lắc đơn", "Chu kì của con lắc ở hình 1?"]);
+        assert_eq!(stem_text(&questions[1]), vec!["Hình 2. Mạch điện", "Điện trở tương đương của mạch ở Hình 2?"]);
+    }
+}
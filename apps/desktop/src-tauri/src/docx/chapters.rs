@@ -0,0 +1,73 @@
+// src-tauri/src/docx/chapters.rs
+//! Chapter headings of long bank documents ("CHƯƠNG 1: DAO ĐỘNG CƠ"). Every
+//! question after a heading belongs to that chapter, which becomes a topic
+//! tag when the job is imported into the question bank.
+//!
+//! A heading is a paragraph that is not a question or an option and either
+//! starts with "Chương N" / "Chuyên đề N" / "Chapter N", or is styled as a
+//! level 1–2 heading (style `Heading1`/`Heading2` or outline level 0–1).
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::model::ParsedDoc;
+
+/// Number of questions of one chapter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChapterCount {
+    pub chapter: String,
+    pub count: usize,
+}
+
+/// Heading text of the paragraph `block` (plain text `text`), if it is a
+/// chapter heading.
+pub fn heading_title(block: &str, text: &str) -> Option<String> {
+    let numbered_re =
+        Regex::new(r"^(?i:chương|chuyên đề|chapter)\s+(\d+|[IVXLC]+)\b").unwrap();
+    let style_re = Regex::new(r#"<w:pStyle w:val="(?i:heading)\s?[12]""#).unwrap();
+    let outline_re = Regex::new(r#"<w:outlineLvl w:val="[01]""#).unwrap();
+
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    let properties = block.find("</w:pPr>").map_or("", |end| &block[..end]);
+    let is_heading =
+        numbered_re.is_match(&text) || style_re.is_match(properties) || outline_re.is_match(properties);
+    is_heading.then_some(text)
+}
+
+/// Questions per chapter, in document order. Questions before the first
+/// heading are not counted.
+pub fn counts(doc: &ParsedDoc) -> Vec<ChapterCount> {
+    let mut counts: Vec<ChapterCount> = Vec::new();
+    for chapter in doc.questions.iter().filter_map(|q| q.chapter.as_ref()) {
+        match counts.iter_mut().find(|c| &c.chapter == chapter) {
+            Some(count) => count.count += 1,
+            None => counts.push(ChapterCount {
+                chapter: chapter.clone(),
+                count: 1,
+            }),
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_numbered_and_styled_headings() {
+        assert_eq!(
+            heading_title("<w:p>", "CHƯƠNG 1:  DAO ĐỘNG CƠ").as_deref(),
+            Some("CHƯƠNG 1: DAO ĐỘNG CƠ")
+        );
+        assert_eq!(
+            heading_title(r#"<w:p><w:pPr><w:pStyle w:val="Heading2"/></w:pPr>"#, "Sóng cơ").as_deref(),
+            Some("Sóng cơ")
+        );
+        assert_eq!(heading_title("<w:p>", "Chương trình học kỳ I gồm"), None);
+        assert_eq!(heading_title(r#"<w:p><w:pPr><w:pStyle w:val="Heading3"/></w:pPr>"#, "Ghi chú"), None);
+    }
+}
@@ -0,0 +1,122 @@
+// src-tauri/src/docx/closing.rs
+//! Optional end of each variant: the centered "----------- HẾT -----------"
+//! line and a signature block for the teacher who wrote the exam and the
+//! one who approved it, as most schools require on the printed original.
+
+use serde::{Deserialize, Serialize};
+
+use super::cover_page::paragraph;
+use super::lexicon::Lexicon;
+
+/// Content of the end of the exam, set in the export options (`closing`).
+/// Missing fields fall back to the usual layout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClosingTemplate {
+    /// Print the "HẾT" line after the last question
+    pub end_marker: bool,
+    /// Signature columns, side by side (empty = no signature block)
+    pub signatures: Vec<SignatureColumn>,
+}
+
+/// One column of the signature block
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SignatureColumn {
+    /// e.g. "GIÁO VIÊN RA ĐỀ"
+    pub title: String,
+    /// Printed under the room to sign; empty = written by hand
+    pub name: String,
+}
+
+impl Default for ClosingTemplate {
+    fn default() -> Self {
+        let column = |title: &str| SignatureColumn {
+            title: title.to_string(),
+            name: String::new(),
+        };
+        Self {
+            end_marker: true,
+            signatures: vec![column("GIÁO VIÊN RA ĐỀ"), column("DUYỆT ĐỀ")],
+        }
+    }
+}
+
+/// Empty paragraphs left to sign in
+const SIGNATURE_LINES: usize = 3;
+
+impl ClosingTemplate {
+    /// Paragraphs and table after the last question. `font_size` is in
+    /// half-points, `width` is the width of the column in twips.
+    pub fn to_xml(&self, lexicon: &Lexicon, font_size: i32, width: i32) -> String {
+        let mut xml = String::new();
+        if self.end_marker {
+            let line = format!("----------- {} -----------", lexicon.end_marker);
+            xml.push_str(&paragraph(&line, font_size, true, true));
+        }
+        if !self.signatures.is_empty() {
+            xml.push_str(&paragraph("", font_size, false, false));
+            xml.push_str(&self.signature_block(lexicon, font_size, width));
+        }
+        xml
+    }
+
+    /// Lines taken on the page, for the page estimate
+    pub fn line_count(&self) -> usize {
+        let signature_block = if self.signatures.is_empty() { 0 } else { SIGNATURE_LINES + 4 };
+        usize::from(self.end_marker) + signature_block
+    }
+
+    /// Borderless table, one column per signature
+    fn signature_block(&self, lexicon: &Lexicon, font_size: i32, width: i32) -> String {
+        let column_width = width / self.signatures.len() as i32;
+        let cells: String = self
+            .signatures
+            .iter()
+            .map(|column| {
+                let mut cell = paragraph(&column.title, font_size, true, true);
+                cell.push_str(&paragraph(&lexicon.signature_note, font_size - 2, false, true));
+                for _ in 0..SIGNATURE_LINES {
+                    cell.push_str(&paragraph("", font_size, false, true));
+                }
+                cell.push_str(&paragraph(&column.name, font_size, true, true));
+                format!(r#"<w:tc><w:tcPr><w:tcW w:w="{column_width}" w:type="dxa"/></w:tcPr>{cell}</w:tc>"#)
+            })
+            .collect();
+        let grid: String = self
+            .signatures
+            .iter()
+            .map(|_| format!(r#"<w:gridCol w:w="{column_width}"/>"#))
+            .collect();
+        format!(
+            r#"<w:tbl><w:tblPr><w:tblW w:w="{width}" w:type="dxa"/><w:tblBorders><w:top w:val="nil"/><w:left w:val="nil"/><w:bottom w:val="nil"/><w:right w:val="nil"/><w:insideH w:val="nil"/><w:insideV w:val="nil"/></w:tblBorders></w:tblPr><w:tblGrid>{grid}</w:tblGrid><w:tr><w:trPr><w:cantSplit/></w:trPr>{cells}</w:tr></w:tbl>"#
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_end_marker_and_signatures() {
+        let template: ClosingTemplate =
+            serde_json::from_str(r#"{"signatures":[{"title":"GIÁO VIÊN RA ĐỀ","name":"Nguyễn Văn A"},{"title":"TỔ TRƯỞNG"},{"title":"HIỆU TRƯỞNG"}]}"#)
+                .unwrap();
+        assert!(template.end_marker);
+
+        let xml = template.to_xml(&Lexicon::default(), 26, 9000);
+        assert!(xml.contains("----------- HẾT -----------"));
+        assert_eq!(xml.matches(r#"<w:gridCol w:w="3000"/>"#).count(), 3);
+        assert!(xml.contains("Nguyễn Văn A"));
+
+        let english = Lexicon::english();
+        let end_only = ClosingTemplate {
+            signatures: Vec::new(),
+            ..Default::default()
+        };
+        let xml = end_only.to_xml(&english, 26, 9000);
+        assert!(xml.contains("----------- END -----------"));
+        assert!(!xml.contains("<w:tbl>"));
+    }
+}
@@ -44,6 +44,7 @@ impl NghiDinh30 {
     pub const FONT_NAME: &'static str = "Times New Roman";
     
     /// Font encoding: Unicode per TCVN 6909:2001
+    #[allow(dead_code)]
     pub const FONT_ENCODING: &'static str = "Unicode (TCVN 6909:2001)";
     
     // ==================== FONT SIZES ====================
@@ -53,9 +54,11 @@ impl NghiDinh30 {
     pub const FONT_SIZE_TITLE: i32 = 28;
     
     /// Exam name font size: 16pt
+    #[allow(dead_code)]
     pub const FONT_SIZE_EXAM_NAME: i32 = 32;
     
     /// Subtitle font size: 12pt
+    #[allow(dead_code)]
     pub const FONT_SIZE_SUBTITLE: i32 = 24;
     
     /// Body text font size: 13pt (main content)
@@ -78,6 +81,47 @@ impl NghiDinh30 {
     pub const HEADER_SPACING_AFTER: i32 = 80;
 }
 
+/// Gap between the columns of a two-column exam body (1 cm, in twips)
+pub const COLUMN_GAP_TWIPS: i32 = 567;
+
+/// Sheet geometry for the booklet and 2-up print layouts: A5 pages printed two
+/// per A4 landscape sheet. Not covered by the decree (which only defines A4
+/// portrait documents); margins are reduced so the content fits half a sheet.
+pub struct A5OnA4;
+
+impl A5OnA4 {
+    /// A4 landscape sheet width: 297mm
+    pub const SHEET_WIDTH_TWIPS: i32 = 16838;
+
+    /// A4 landscape sheet height: 210mm
+    pub const SHEET_HEIGHT_TWIPS: i32 = 11906;
+
+    /// A5 page width (half a sheet): 148mm
+    pub const PAGE_WIDTH_TWIPS: i32 = 8419;
+
+    /// Margin on every side of an A5 page: 15mm
+    pub const MARGIN_TWIPS: i32 = 851;
+}
+
+/// Large-print profile for visually impaired students (accessible copies of
+/// each variant). Follows common large-print guidance rather than the decree:
+/// at least 18pt text and 1.5 line spacing.
+pub struct LargePrint;
+
+impl LargePrint {
+    /// Body text font size: 18pt
+    pub const FONT_SIZE_BODY: i32 = 36;
+
+    /// Header table font size: 16pt
+    pub const FONT_SIZE_HEADER: i32 = 32;
+
+    /// Line spacing: 1.5 (in 240ths of a line)
+    pub const LINE_SPACING: i32 = 360;
+
+    /// Space after each paragraph: 12pt
+    pub const SPACING_AFTER: i32 = 240;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,4 +241,12 @@ mod tests {
         assert_eq!(NghiDinh30::FONT_SIZE_BODY, 26); // 13pt
         assert_eq!(NghiDinh30::FONT_SIZE_PAGE_NUMBER, 26); // 13pt
     }
+
+    #[test]
+    fn test_large_print_sizes() {
+        // Large print MUST stay at 18pt body text, 1.5 line spacing
+        assert_eq!(LargePrint::FONT_SIZE_BODY, 36, "Large-print body MUST be 36 (18pt)");
+        assert_eq!(LargePrint::FONT_SIZE_HEADER, 32, "Large-print header MUST be 32 (16pt)");
+        assert_eq!(LargePrint::LINE_SPACING, 360, "Large-print line spacing MUST be 360 (1.5)");
+    }
 }
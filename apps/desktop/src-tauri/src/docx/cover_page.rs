@@ -0,0 +1,221 @@
+// src-tauri/src/docx/cover_page.rs
+//! Optional cover page printed before the header of each variant:
+//! instructions, materials allowed, student information grid and signature
+//! boxes for the proctors.
+
+use serde::{Deserialize, Serialize};
+
+use super::config::NghiDinh30;
+use super::lexicon::{fill, Lexicon};
+use super::writer::escape_xml;
+
+/// Content of the cover page, set in the export options (`coverPage`).
+/// Missing fields fall back to the usual Vietnamese wording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CoverPageTemplate {
+    /// e.g. "ĐỀ KIỂM TRA", "ĐỀ THI THỬ"
+    pub title: String,
+    /// One line each, printed as a numbered list
+    pub instructions: Vec<String>,
+    /// Printed after "Thí sinh được sử dụng:"; empty = no materials allowed
+    pub materials_allowed: Vec<String>,
+    /// Labels of the student information grid, one row each
+    pub student_fields: Vec<String>,
+    /// Number of proctor signature boxes (0 = none)
+    pub proctor_signatures: u32,
+}
+
+impl Default for CoverPageTemplate {
+    fn default() -> Self {
+        Self {
+            title: "ĐỀ KIỂM TRA".to_string(),
+            instructions: vec![
+                "Thí sinh kiểm tra đủ số trang của đề trước khi làm bài.".to_string(),
+                "Ghi mã đề vào phiếu trả lời.".to_string(),
+                "Cán bộ coi thi không giải thích gì thêm.".to_string(),
+            ],
+            materials_allowed: Vec::new(),
+            student_fields: vec![
+                "Họ và tên thí sinh".to_string(),
+                "Số báo danh".to_string(),
+                "Lớp".to_string(),
+                "Phòng thi".to_string(),
+            ],
+            proctor_signatures: 2,
+        }
+    }
+}
+
+/// Exam details printed on the cover page
+pub struct CoverInfo<'a> {
+    pub school_name: &'a str,
+    pub exam_name: &'a str,
+    pub academic_year: &'a str,
+    pub subject: &'a str,
+    pub grade: &'a str,
+    pub exam_code: &'a str,
+    pub duration_minutes: u32,
+    pub lexicon: &'a Lexicon,
+}
+
+/// Height of a signature box, in twips (about 2.5 cm)
+const SIGNATURE_BOX_HEIGHT: i32 = 1400;
+
+/// Height of a row of the student grid, in twips
+const STUDENT_ROW_HEIGHT: i32 = 560;
+
+impl CoverPageTemplate {
+    /// Paragraphs and tables of the cover page. `font_size` is in
+    /// half-points, `width` is the text width in twips. The caller ends the
+    /// page (and its section) after this.
+    pub fn to_xml(&self, info: &CoverInfo, font_size: i32, width: i32) -> String {
+        let mut xml = String::new();
+        xml.push_str(&paragraph(info.school_name, font_size, true, true));
+        xml.push_str(&paragraph(
+            &format!(
+                "{} - {} {}",
+                info.exam_name,
+                info.lexicon.academic_year.to_uppercase(),
+                info.academic_year
+            ),
+            font_size,
+            true,
+            true,
+        ));
+        xml.push_str(&paragraph("", font_size, false, true));
+        xml.push_str(&paragraph(&self.title, font_size + 8, true, true));
+        let lexicon = info.lexicon;
+        xml.push_str(&paragraph(
+            &format!("{}: {} - {}", lexicon.cover_subject, info.subject, info.grade),
+            font_size,
+            true,
+            true,
+        ));
+        xml.push_str(&paragraph(
+            &fill(&lexicon.cover_duration, &[("minutes", &info.duration_minutes.to_string())]),
+            font_size,
+            false,
+            true,
+        ));
+        xml.push_str(&paragraph(&format!("{}: {}", lexicon.exam_code, info.exam_code), font_size, true, true));
+        xml.push_str(&paragraph("", font_size, false, true));
+
+        if !self.student_fields.is_empty() {
+            xml.push_str(&self.student_grid(font_size, width));
+            xml.push_str(&paragraph("", font_size, false, false));
+        }
+
+        if !self.instructions.is_empty() {
+            xml.push_str(&paragraph(&format!("{}:", lexicon.cover_instructions), font_size, true, false));
+            for (i, line) in self.instructions.iter().enumerate() {
+                xml.push_str(&paragraph(&format!("{}. {}", i + 1, line), font_size, false, false));
+            }
+        }
+
+        let materials = if self.materials_allowed.is_empty() {
+            lexicon.no_materials.clone()
+        } else {
+            format!("{}: {}.", lexicon.materials_allowed, self.materials_allowed.join("; "))
+        };
+        xml.push_str(&paragraph(&materials, font_size, false, false));
+
+        if self.proctor_signatures > 0 {
+            xml.push_str(&paragraph("", font_size, false, false));
+            xml.push_str(&self.signature_boxes(font_size, width));
+        }
+        xml
+    }
+
+    /// Two-column grid: label | blank to fill in by hand
+    fn student_grid(&self, font_size: i32, width: i32) -> String {
+        let label_width = width * 2 / 5;
+        let blank_width = width - label_width;
+        let rows: String = self
+            .student_fields
+            .iter()
+            .map(|field| {
+                format!(
+                    r#"<w:tr><w:trPr><w:trHeight w:val="{STUDENT_ROW_HEIGHT}"/></w:trPr><w:tc><w:tcPr><w:tcW w:w="{label_width}" w:type="dxa"/><w:vAlign w:val="center"/></w:tcPr>{}</w:tc><w:tc><w:tcPr><w:tcW w:w="{blank_width}" w:type="dxa"/></w:tcPr>{}</w:tc></w:tr>"#,
+                    paragraph(&format!("{}:", field), font_size, false, false),
+                    paragraph("", font_size, false, false),
+                )
+            })
+            .collect();
+        table(&[label_width, blank_width], &rows)
+    }
+
+    /// One box per proctor, side by side, with room to sign
+    fn signature_boxes(&self, font_size: i32, width: i32) -> String {
+        let count = self.proctor_signatures as i32;
+        let column_width = width / count;
+        let cells: String = (1..=count)
+            .map(|i| {
+                format!(
+                    r#"<w:tc><w:tcPr><w:tcW w:w="{column_width}" w:type="dxa"/></w:tcPr>{}{}</w:tc>"#,
+                    paragraph(&format!("Cán bộ coi thi {}", i), font_size, true, true),
+                    paragraph("(Ký, ghi rõ họ tên)", font_size - 2, false, true),
+                )
+            })
+            .collect();
+        let rows = format!(
+            r#"<w:tr><w:trPr><w:trHeight w:val="{SIGNATURE_BOX_HEIGHT}"/></w:trPr>{cells}</w:tr>"#
+        );
+        table(&vec![column_width; count as usize], &rows)
+    }
+}
+
+pub(super) fn paragraph(text: &str, font_size: i32, bold: bool, centered: bool) -> String {
+    let font = NghiDinh30::FONT_NAME;
+    let jc = if centered { r#"<w:jc w:val="center"/>"# } else { "" };
+    let b = if bold { "<w:b/>" } else { "" };
+    let run = if text.is_empty() {
+        String::new()
+    } else {
+        format!(
+            r#"<w:r><w:rPr><w:rFonts w:ascii="{font}" w:hAnsi="{font}" w:cs="{font}" w:eastAsia="{font}"/>{b}<w:sz w:val="{font_size}"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r>"#,
+            escape_xml(text)
+        )
+    };
+    format!(r#"<w:p><w:pPr>{jc}<w:spacing w:after="60"/></w:pPr>{run}</w:p>"#)
+}
+
+fn table(columns: &[i32], rows: &str) -> String {
+    let width: i32 = columns.iter().sum();
+    let grid: String = columns.iter().map(|w| format!(r#"<w:gridCol w:w="{w}"/>"#)).collect();
+    let border = r#"w:val="single" w:sz="4" w:space="0" w:color="000000""#;
+    format!(
+        r#"<w:tbl><w:tblPr><w:tblW w:w="{width}" w:type="dxa"/><w:tblBorders><w:top {border}/><w:left {border}/><w:bottom {border}/><w:right {border}/><w:insideH {border}/><w:insideV {border}/></w:tblBorders></w:tblPr><w:tblGrid>{grid}</w:tblGrid>{rows}</w:tbl>"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_fields_and_signature_boxes() {
+        let template: CoverPageTemplate =
+            serde_json::from_str(r#"{"materialsAllowed":["Máy tính cầm tay"],"proctorSignatures":3}"#).unwrap();
+        assert_eq!(template.student_fields.len(), 4);
+
+        let info = CoverInfo {
+            school_name: "TRƯỜNG THCS A & B",
+            exam_name: "KIỂM TRA GIỮA HKII",
+            academic_year: "2024 - 2025",
+            subject: "Toán",
+            grade: "LỚP 7",
+            exam_code: "101",
+            duration_minutes: 45,
+            lexicon: &Lexicon::default(),
+        };
+        let xml = template.to_xml(&info, 26, 9000);
+        assert!(xml.contains("TRƯỜNG THCS A &amp; B"));
+        assert!(xml.contains("Mã đề thi: 101"));
+        assert!(xml.contains("Số báo danh:"));
+        assert!(xml.contains("Thí sinh được sử dụng: Máy tính cầm tay."));
+        assert!(xml.contains("Cán bộ coi thi 3"));
+        assert!(!xml.contains("Cán bộ coi thi 4"));
+        assert_eq!(xml.matches("<w:tbl>").count(), 2);
+    }
+}
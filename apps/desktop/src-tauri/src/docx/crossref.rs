@@ -0,0 +1,144 @@
+// src-tauri/src/docx/crossref.rs
+//! References from one question to another ("sử dụng dữ kiện ở câu 12").
+//!
+//! "câu" is also the word for a sentence, so a reference is either a
+//! capitalized "Câu 12" / "Question 12" or a lowercase one after a word that
+//! points to it ("ở câu 12", "của câu 3", "in question 4"); "câu 2 của đoạn
+//! văn" is prose.
+//!
+//! References are detected when parsing (`Question::references`) so a target
+//! missing from the document is reported before mixing. In each variant the
+//! numbers are rewritten to the display number of the referenced question.
+//! Only the text of segments is rewritten, not the original XML used by the
+//! raw passthrough export.
+
+use std::collections::HashMap;
+
+use regex::{Captures, Regex};
+
+use super::mixer::MixedQuestion;
+use super::model::{Question, Segment};
+
+/// Words after which a lowercase "câu N" / "question N" is a reference
+const REFERENCE_WORDS: &str = "ở|tại|của|theo|từ|trong|và|với|như|xem|in|of|from|see|and|to";
+
+/// Group `number` is the referenced number; everything before it is kept
+/// as written when renumbering
+fn reference_re() -> Regex {
+    Regex::new(&format!(
+        r"(?:\b(?i:{})\s+(?i:câu|question)|\b(?:Câu|CÂU|Question|QUESTION))\s+(?P<number>\d+)\b",
+        REFERENCE_WORDS
+    ))
+    .unwrap()
+}
+
+/// Numbers of the other questions referenced by `question`, in order of
+/// appearance, without duplicates.
+pub fn detect(question: &Question) -> Vec<u32> {
+    let re = reference_re();
+    let segments = question.stem.iter().chain(question.stem_alt.iter()).chain(
+        question
+            .options
+            .iter()
+            .flat_map(|o| o.content.iter().chain(o.content_alt.iter())),
+    );
+
+    let mut references = Vec::new();
+    for segment in segments {
+        let Segment::Text { text, .. } = segment else {
+            continue;
+        };
+        for caps in re.captures_iter(text) {
+            let Ok(number) = caps["number"].parse::<u32>() else {
+                continue;
+            };
+            if number != question.number && !references.contains(&number) {
+                references.push(number);
+            }
+        }
+    }
+    references
+}
+
+/// Rewrite question numbers in the text of a variant (questions in display
+/// order) from original to display numbers. Numbers of questions that are not
+/// in the variant are left as they are.
+pub fn rewrite(questions: &mut [MixedQuestion]) {
+    let display: HashMap<String, u32> = questions
+        .iter()
+        .map(|q| (q.original_number.to_string(), q.display_number))
+        .collect();
+    let re = reference_re();
+
+    for question in questions.iter_mut() {
+        for segment in question.segments_mut() {
+            let Segment::Text { text, .. } = segment else {
+                continue;
+            };
+            let rewritten = re.replace_all(text, |caps: &Captures| match display.get(&caps["number"]) {
+                Some(number) => {
+                    let prefix_len = caps.name("number").unwrap().start() - caps.get(0).unwrap().start();
+                    format!("{}{}", &caps[0][..prefix_len], number)
+                }
+                None => caps[0].to_string(),
+            });
+            if rewritten != *text {
+                *text = rewritten.into_owned();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    #[test]
+    fn detects_and_rewrites_references() {
+        let question = Question {
+            number: 13,
+            stem: text("Sử dụng dữ kiện ở câu 12 và Câu 3, câu 13 này hỏi gì?"),
+            ..Default::default()
+        };
+        assert_eq!(detect(&question), vec![12, 3]);
+
+        let mixed = |original_number, display_number, stem: &str| MixedQuestion {
+            original_number,
+            display_number,
+            stem: text(stem),
+            options: Vec::new(),
+            correct_answer: "A".to_string(),
+            stem_alt: Vec::new(),
+        };
+        let mut questions = vec![
+            mixed(13, 1, "Sử dụng dữ kiện ở câu 12 và Câu 3, câu 40?"),
+            mixed(12, 2, "Cho hàm số y = x²."),
+            mixed(3, 3, "Tính đạo hàm."),
+        ];
+        rewrite(&mut questions);
+        assert!(matches!(
+            &questions[0].stem[0],
+            Segment::Text { text, .. } if text == "Sử dụng dữ kiện ở câu 2 và Câu 3, câu 40?"
+        ));
+    }
+
+    #[test]
+    fn sentences_named_in_prose_are_not_references() {
+        let question = |stem: &str| Question {
+            number: 5,
+            stem: text(stem),
+            ..Default::default()
+        };
+        assert!(detect(&question("Đọc đoạn văn, câu 2 nói về điều gì?")).is_empty());
+        assert!(detect(&question("Mỗi câu 3 ý, chọn ý đúng.")).is_empty());
+        assert_eq!(detect(&question("Theo kết quả của câu 4, tính x.")), vec![4]);
+        assert_eq!(detect(&question("Dùng số liệu trong Question 2.")), vec![2]);
+    }
+}
@@ -0,0 +1,267 @@
+// src-tauri/src/docx/diff.rs
+//! Question-by-question comparison of two parsed documents (exam drafts)
+
+use serde::Serialize;
+
+use super::model::{ParsedDoc, Question, Segment};
+
+/// Stems with a similarity below this are reported as rewritten rather than edited
+pub const REWRITE_THRESHOLD: f64 = 0.5;
+
+/// Structured diff between two parsed documents, matched by question number
+#[derive(Debug, Serialize)]
+pub struct JobDiff {
+    /// Questions present in both documents with at least one change
+    pub changed: Vec<QuestionDiff>,
+    /// Question numbers only in the second document
+    pub added: Vec<u32>,
+    /// Question numbers only in the first document
+    pub removed: Vec<u32>,
+    /// Number of questions identical in both documents
+    pub unchanged: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuestionDiff {
+    pub number: u32,
+    /// Similarity of the stems in [0, 1] (1 = identical text)
+    #[serde(rename = "stemSimilarity")]
+    pub stem_similarity: f64,
+    #[serde(rename = "stemBefore", skip_serializing_if = "Option::is_none")]
+    pub stem_before: Option<String>,
+    #[serde(rename = "stemAfter", skip_serializing_if = "Option::is_none")]
+    pub stem_after: Option<String>,
+    /// Stem changed so much it is effectively a different question
+    pub rewritten: bool,
+    #[serde(rename = "changedOptions")]
+    pub changed_options: Vec<OptionDiff>,
+    #[serde(rename = "answerBefore", skip_serializing_if = "Option::is_none")]
+    pub answer_before: Option<String>,
+    #[serde(rename = "answerAfter", skip_serializing_if = "Option::is_none")]
+    pub answer_after: Option<String>,
+}
+
+/// An option added, removed or edited; `None` on the side where it is missing
+#[derive(Debug, Serialize)]
+pub struct OptionDiff {
+    pub label: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Compare two documents question by question.
+pub fn diff_docs(before: &ParsedDoc, after: &ParsedDoc) -> JobDiff {
+    let mut diff = JobDiff {
+        changed: Vec::new(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        unchanged: 0,
+    };
+
+    for old in &before.questions {
+        match after.questions.iter().find(|q| q.number == old.number) {
+            Some(new) => match diff_question(old, new) {
+                Some(question_diff) => diff.changed.push(question_diff),
+                None => diff.unchanged += 1,
+            },
+            None => diff.removed.push(old.number),
+        }
+    }
+
+    diff.added = after
+        .questions
+        .iter()
+        .filter(|q| !before.questions.iter().any(|old| old.number == q.number))
+        .map(|q| q.number)
+        .collect();
+
+    diff
+}
+
+/// `None` when both versions of the question are identical.
+fn diff_question(old: &Question, new: &Question) -> Option<QuestionDiff> {
+    let stem_before = segments_text(&old.stem);
+    let stem_after = segments_text(&new.stem);
+    let stem_similarity = similarity(&stem_before, &stem_after);
+    let stem_changed = stem_before != stem_after;
+
+    let mut changed_options = Vec::new();
+    for option in &old.options {
+        let before = segments_text(&option.content);
+        let after = new
+            .options
+            .iter()
+            .find(|o| o.label == option.label)
+            .map(|o| segments_text(&o.content));
+        if after.as_deref() != Some(before.as_str()) {
+            changed_options.push(OptionDiff {
+                label: option.label.clone(),
+                before: Some(before),
+                after,
+            });
+        }
+    }
+    for option in &new.options {
+        if !old.options.iter().any(|o| o.label == option.label) {
+            changed_options.push(OptionDiff {
+                label: option.label.clone(),
+                before: None,
+                after: Some(segments_text(&option.content)),
+            });
+        }
+    }
+
+    let answer_changed = old.correct_label != new.correct_label;
+
+    if !stem_changed && changed_options.is_empty() && !answer_changed {
+        return None;
+    }
+
+    Some(QuestionDiff {
+        number: old.number,
+        stem_similarity,
+        rewritten: stem_similarity < REWRITE_THRESHOLD,
+        stem_before: stem_changed.then_some(stem_before),
+        stem_after: stem_changed.then_some(stem_after),
+        changed_options,
+        answer_before: answer_changed.then(|| old.correct_label.clone()),
+        answer_after: answer_changed.then(|| new.correct_label.clone()),
+    })
+}
+
+/// Normalized text of a segment list: whitespace collapsed, math/images as markers.
+pub fn segments_text(segments: &[Segment]) -> String {
+    let mut parts = Vec::new();
+    for segment in segments {
+        match segment {
+            Segment::Text { text, .. } => parts.push(text.clone()),
+            Segment::Math { omml, .. } => parts.push(format!("[{}]", math_text(omml))),
+            Segment::Image { .. } => parts.push("[image]".to_string()),
+            Segment::Symbol { font, char_code, .. } => {
+                parts.push(format!("[{} {}]", font, char_code))
+            }
+            Segment::Blank { .. } => parts.push("[...]".to_string()),
+            Segment::Audio { file_name, .. } => parts.push(format!("[audio {}]", file_name)),
+        }
+    }
+    parts.join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Text content of an OMML fragment (the `<m:t>` elements), enough to tell
+/// formulas apart.
+pub fn math_text(omml: &str) -> String {
+    let mut text = String::new();
+    let mut cursor = 0;
+    while let Some(rel) = omml[cursor..].find("<m:t") {
+        let start = cursor + rel;
+        // Skip <m:type>, <m:t...> look-alikes
+        if !matches!(omml.as_bytes().get(start + "<m:t".len()), Some(b'>') | Some(b' ')) {
+            cursor = start + "<m:t".len();
+            continue;
+        }
+        let Some(gt) = omml[start..].find('>') else {
+            break;
+        };
+        let content_start = start + gt + 1;
+        let Some(end) = omml[content_start..].find("</m:t>") else {
+            break;
+        };
+        text.push_str(&omml[content_start..content_start + end]);
+        cursor = content_start + end;
+    }
+    text
+}
+
+/// Levenshtein similarity ratio over characters, in [0, 1].
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    1.0 - previous[b.len()] as f64 / longest as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::OptionItem;
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    fn question(number: u32, stem: &str, options: &[(&str, &str)], answer: &str) -> Question {
+        Question {
+            number,
+            stem: text(stem),
+            options: options
+                .iter()
+                .map(|(label, content)| OptionItem {
+                    label: label.to_string(),
+                    content: text(content),
+                    ..Default::default()
+                })
+                .collect(),
+            correct_label: answer.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_changed_added_and_removed_questions() {
+        let before = ParsedDoc {
+            questions: vec![
+                question(1, "1 + 1 = ?", &[("A", "2"), ("B", "3")], "A"),
+                question(2, "Thủ đô Việt Nam?", &[("A", "Hà Nội"), ("B", "Huế")], "A"),
+                question(3, "Bỏ câu này", &[("A", "x")], "A"),
+            ],
+            ..Default::default()
+        };
+        let after = ParsedDoc {
+            questions: vec![
+                question(1, "1 + 1 = ?", &[("A", "2"), ("B", "3")], "A"),
+                question(2, "Thủ đô của Việt Nam?", &[("A", "Huế"), ("B", "Hà Nội")], "B"),
+                question(4, "Câu mới", &[("A", "y")], "A"),
+            ],
+            ..Default::default()
+        };
+
+        let diff = diff_docs(&before, &after);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.removed, vec![3]);
+        assert_eq!(diff.added, vec![4]);
+
+        let q2 = &diff.changed[0];
+        assert_eq!(q2.number, 2);
+        assert!(q2.stem_similarity >= 0.75 && !q2.rewritten);
+        assert_eq!(q2.changed_options.len(), 2);
+        assert_eq!(q2.answer_before.as_deref(), Some("A"));
+        assert_eq!(q2.answer_after.as_deref(), Some("B"));
+    }
+
+    #[test]
+    fn similarity_bounds() {
+        assert_eq!(similarity("", ""), 1.0);
+        assert_eq!(similarity("abc", "abc"), 1.0);
+        assert_eq!(similarity("abc", "xyz"), 0.0);
+    }
+}
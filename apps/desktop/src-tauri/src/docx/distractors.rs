@@ -0,0 +1,123 @@
+// src-tauri/src/docx/distractors.rs
+//! Wrong options for numeric questions, to turn a short-answer question
+//! ("Tính vận tốc của vật… ") into a multiple-choice one.
+//!
+//! The candidates are the usual slips: a flipped sign, the decimal point
+//! moved by one place, a forgotten factor of 2 and an off-by-one result.
+//! The teacher picks or edits them before the options are written into the
+//! question.
+
+use super::model::{OptionItem, Question, Segment};
+
+/// Digits kept after the decimal point, enough to hide floating-point noise
+/// (0.1 * 3) without rounding real answers
+const MAX_DECIMALS: usize = 6;
+
+/// Number written as "2,5", "2.5", "-3" or "1 000"
+pub fn parse_number(text: &str) -> Option<f64> {
+    let text: String = text.trim().chars().filter(|c| !c.is_whitespace()).collect();
+    let text = text.replace('−', "-").replace(',', ".");
+    text.parse().ok().filter(|value: &f64| value.is_finite())
+}
+
+/// `value` without trailing zeros, with `decimal_separator`
+pub fn format_number(value: f64, decimal_separator: char) -> String {
+    let text = format!("{:.*}", MAX_DECIMALS, value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    let text = if text == "-0" { "0" } else { text };
+    text.replace('.', &decimal_separator.to_string())
+}
+
+/// Up to `count` distinct wrong answers for `answer`, most plausible first
+pub fn numeric_distractors(answer: f64, count: usize) -> Vec<f64> {
+    let candidates = [
+        -answer,
+        answer * 10.0,
+        answer / 10.0,
+        answer * 2.0,
+        answer / 2.0,
+        answer + 1.0,
+        answer - 1.0,
+        answer * 100.0,
+        answer / 100.0,
+        -answer * 10.0,
+    ];
+    let mut distractors: Vec<f64> = Vec::new();
+    for candidate in candidates {
+        let candidate = round(candidate);
+        let taken = |value: f64| same(value, candidate);
+        if !taken(round(answer)) && !distractors.iter().copied().any(taken) {
+            distractors.push(candidate);
+        }
+        if distractors.len() == count {
+            break;
+        }
+    }
+    distractors
+}
+
+fn round(value: f64) -> f64 {
+    let scale = 10f64.powi(MAX_DECIMALS as i32);
+    (value * scale).round() / scale
+}
+
+fn same(a: f64, b: f64) -> bool {
+    (a - b).abs() < 0.5 / 10f64.powi(MAX_DECIMALS as i32)
+}
+
+/// Give `question` the options `answer` and `distractors` in ascending
+/// order, labelled A, B, C…, and mark the answer as correct. Values that
+/// are not numbers keep their order after the numeric ones.
+pub fn to_multiple_choice(question: &mut Question, answer: &str, distractors: &[String]) {
+    let mut values: Vec<&str> = std::iter::once(answer).chain(distractors.iter().map(String::as_str)).collect();
+    values.sort_by(|a, b| match (parse_number(a), parse_number(b)) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+
+    question.options = values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| OptionItem {
+            label: ((b'A' + i as u8) as char).to_string(),
+            content: vec![Segment::Text {
+                text: value.trim().to_string(),
+                raw_xml: String::new(),
+            }],
+            ..Default::default()
+        })
+        .collect();
+    let correct = values.iter().position(|value| *value == answer).unwrap_or(0);
+    question.correct_label = question.options[correct].label.clone();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distractors_become_sorted_options() {
+        let answer = parse_number(" 2,5 ").unwrap();
+        let distractors: Vec<String> =
+            numeric_distractors(answer, 3).into_iter().map(|value| format_number(value, ',')).collect();
+        assert_eq!(distractors, ["-2,5", "25", "0,25"]);
+        // -0 and 0 are the same answer
+        assert_eq!(numeric_distractors(0.0, 3).iter().map(|v| format_number(*v, '.')).collect::<Vec<_>>(), ["1", "-1"]);
+        assert_eq!(format_number(0.1 * 3.0, '.'), "0.3");
+
+        let mut question = Question::default();
+        to_multiple_choice(&mut question, "2,5", &distractors);
+        let labels: Vec<String> = question
+            .options
+            .iter()
+            .map(|o| match &o.content[0] {
+                Segment::Text { text, .. } => format!("{}. {}", o.label, text),
+                _ => String::new(),
+            })
+            .collect();
+        assert_eq!(labels, ["A. -2,5", "B. 0,25", "C. 2,5", "D. 25"]);
+        assert_eq!(question.correct_label, "C");
+    }
+}
@@ -0,0 +1,228 @@
+// src-tauri/src/docx/exam_parts.rs
+//! Exam parts with their own scoring: a heading such as "PHẦN I. TRẮC
+//! NGHIỆM (3 điểm)" or "II. PHẦN TỰ LUẬN: 7 điểm" starts a part, which holds
+//! the questions up to the next heading.
+//!
+//! The parser turns each heading into an `ExamPart`. When mixing, questions
+//! (and shared-data groups) only move within their part and the parts keep
+//! their document order; every variant prints the headings again before the
+//! first question of their part. Points stated at the start of a question
+//! ("Câu 3. (0,5 điểm) …") must add up to the total of its part.
+
+use std::collections::HashMap;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::model::{ExamPart, ParsedDoc, Question, Segment};
+
+/// Allowed difference between the points of the questions and the total
+const POINTS_TOLERANCE: f64 = 1e-6;
+
+/// A part in a mixed variant
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MixedPart {
+    pub heading: Vec<Segment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<f64>,
+    /// Display numbers of the questions, in order
+    pub questions: Vec<u32>,
+}
+
+/// Points of a part whose questions do not add up to its total
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointsMismatch {
+    /// First question of the part
+    pub question_number: u32,
+    pub heading: String,
+    pub total: f64,
+    /// Sum of the points stated on the questions
+    pub stated: f64,
+}
+
+/// Whether the paragraph text `text` is a part heading
+pub fn is_heading(text: &str) -> bool {
+    let re = Regex::new(r"^(?:(?:PHẦN|Phần|PART|Part)\s+(?:[IVX]+|\d+)\b|[IVX]+\s*[.:]\s*(?:PHẦN|PART)\b)").unwrap();
+    re.is_match(text.trim_start())
+}
+
+/// Total points named in a part heading: "(3 điểm)", ": 7 điểm", "(2.5 points)"
+pub fn heading_points(text: &str) -> Option<f64> {
+    let re = Regex::new(r"(?i)(\d+(?:[.,]\d+)?)\s*(?:điểm|points?)\b").unwrap();
+    parse_number(&re.captures(text)?[1])
+}
+
+/// Points stated at the start of a question stem: "(0,5 điểm) Cho hàm số…"
+pub fn stated_points(stem: &[Segment]) -> Option<f64> {
+    let re = Regex::new(r"(?i)^\s*\(\s*(\d+(?:[.,]\d+)?)\s*(?:điểm|points?)\s*\)").unwrap();
+    let text = stem.iter().find_map(|segment| match segment {
+        Segment::Text { text, .. } if !text.trim().is_empty() => Some(text.as_str()),
+        _ => None,
+    })?;
+    parse_number(&re.captures(text)?[1])
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    text.replace(',', ".").parse().ok()
+}
+
+/// Plain text of a heading
+pub fn heading_text(heading: &[Segment]) -> String {
+    heading
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Index of the part holding question `number`
+pub fn part_of(parts: &[ExamPart], number: u32) -> Option<usize> {
+    parts.iter().position(|part| part.questions.contains(&number))
+}
+
+/// Shuffle mixing units (see `groups::units`) within their part, parts in
+/// document order, questions before the first heading first. Without parts
+/// this is a plain shuffle of the units, drawing from `rng` as before parts
+/// existed.
+pub fn shuffle_units<R: Rng>(mut units: Vec<Vec<Question>>, parts: &[ExamPart], rng: &mut R) -> Vec<Question> {
+    if parts.is_empty() {
+        units.shuffle(rng);
+        return units.into_iter().flatten().collect();
+    }
+    // Bucket 0: no part; bucket i + 1: part i
+    let mut buckets: Vec<Vec<Vec<Question>>> = vec![Vec::new(); parts.len() + 1];
+    for unit in units {
+        let bucket = unit.first().and_then(|q| part_of(parts, q.number)).map_or(0, |i| i + 1);
+        buckets[bucket].push(unit);
+    }
+    buckets
+        .into_iter()
+        .flat_map(|mut bucket| {
+            bucket.shuffle(rng);
+            bucket.into_iter().flatten()
+        })
+        .collect()
+}
+
+/// The parts of a variant, given original → display question numbers
+pub fn mix_parts(parts: &[ExamPart], display: &HashMap<u32, u32>) -> Vec<MixedPart> {
+    parts
+        .iter()
+        .filter_map(|part| {
+            let mut questions: Vec<u32> =
+                part.questions.iter().filter_map(|number| display.get(number).copied()).collect();
+            questions.sort_unstable();
+            (!questions.is_empty()).then(|| MixedPart {
+                heading: part.heading.clone(),
+                points: part.points,
+                questions,
+            })
+        })
+        .collect()
+}
+
+/// Parts whose questions state points that cannot add up to the part
+/// total: all stated and a different sum, or some stated and nothing left
+/// for the others. Parts without a total or without stated points are fine.
+pub fn check_points(doc: &ParsedDoc) -> Vec<PointsMismatch> {
+    let points: HashMap<u32, Option<f64>> = doc.questions.iter().map(|q| (q.number, q.points)).collect();
+    doc.parts
+        .iter()
+        .filter_map(|part| {
+            let total = part.points?;
+            let first = *part.questions.first()?;
+            let stated: Vec<f64> = part.questions.iter().filter_map(|n| points.get(n).copied().flatten()).collect();
+            if stated.is_empty() {
+                return None;
+            }
+            let sum: f64 = stated.iter().sum();
+            let all_stated = stated.len() == part.questions.len();
+            let fits = if all_stated {
+                (sum - total).abs() < POINTS_TOLERANCE
+            } else {
+                sum < total - POINTS_TOLERANCE
+            };
+            (!fits).then(|| PointsMismatch {
+                question_number: first,
+                heading: heading_text(&part.heading),
+                total,
+                stated: sum,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    #[test]
+    fn headings_and_points() {
+        assert!(is_heading("PHẦN I. TRẮC NGHIỆM (3 điểm)"));
+        assert!(is_heading("II. PHẦN TỰ LUẬN: 7 điểm"));
+        assert!(is_heading("Part 2: Reading"));
+        assert!(!is_heading("Phần lớn diện tích nước ta là đồi núi."));
+        assert_eq!(heading_points("PHẦN I. TRẮC NGHIỆM (3 điểm)"), Some(3.0));
+        assert_eq!(heading_points("PHẦN II: 2,5 điểm"), Some(2.5));
+        assert_eq!(heading_points("PHẦN III"), None);
+        assert_eq!(stated_points(&text(" (0,5 điểm) Giải phương trình")), Some(0.5));
+        assert_eq!(stated_points(&text("Giải phương trình (0,5 điểm)")), None);
+    }
+
+    #[test]
+    fn questions_stay_in_their_part() {
+        let parts = vec![
+            ExamPart {
+                heading: text("PHẦN I (2 điểm)"),
+                points: Some(2.0),
+                questions: vec![1, 2, 3, 4],
+            },
+            ExamPart {
+                heading: text("PHẦN II (1,5 điểm)"),
+                points: Some(1.5),
+                questions: vec![5, 6],
+            },
+        ];
+        let questions: Vec<Question> = (1..=6)
+            .map(|number| Question {
+                number,
+                points: Some(0.5),
+                ..Default::default()
+            })
+            .collect();
+        for seed in 0..20 {
+            let units = questions.iter().cloned().map(|q| vec![q]).collect();
+            let order: Vec<u32> = shuffle_units(units, &parts, &mut StdRng::seed_from_u64(seed))
+                .iter()
+                .map(|q| q.number)
+                .collect();
+            assert!(order[..4].iter().all(|n| *n <= 4), "{:?}", order);
+        }
+
+        let doc = ParsedDoc {
+            questions,
+            parts,
+            ..Default::default()
+        };
+        let mismatches = check_points(&doc);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].question_number, 5);
+        assert_eq!(mismatches[0].stated, 1.0);
+        assert!(mismatches.iter().all(|m| m.heading != "PHẦN I (2 điểm)"));
+    }
+}
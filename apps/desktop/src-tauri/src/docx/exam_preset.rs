@@ -0,0 +1,161 @@
+// src-tauri/src/docx/exam_preset.rs
+//! Presets bundling what usually goes with a kind of test: the header's
+//! exam name and duration, points per question and layout. A few common
+//! ones are built in; schools add their own in the settings
+//! (`examPresets`). The frontend prefills its export form from them, and a
+//! preset named in the export options (`preset`) fills at export time the
+//! options left at their defaults.
+
+use serde::{Deserialize, Serialize};
+
+use super::answer_sheet::AnswerSheetLayout;
+use super::export_options::{AnswerLine, ExportOptions, PrintLayout, Spacing, SpacingPreset};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ExamPreset {
+    /// Shown in the preset list and matched by `ExportOptions::preset`
+    pub name: String,
+    /// Exam name printed in the header, e.g. "KIỂM TRA 15 PHÚT"
+    pub exam_name: String,
+    pub duration_minutes: u32,
+    /// Usual number of questions, for the frontend to flag a job that has
+    /// another count (None = any)
+    pub question_count: Option<u32>,
+    pub points_per_question: f64,
+    pub print_layout: PrintLayout,
+    pub two_columns: bool,
+    pub spacing: Spacing,
+    pub answer_line: AnswerLine,
+    /// Answer sheet written with the exam (None = none)
+    pub answer_sheet: Option<AnswerSheetLayout>,
+}
+
+impl Default for ExamPreset {
+    fn default() -> Self {
+        let options = ExportOptions::default();
+        Self {
+            name: String::new(),
+            exam_name: String::new(),
+            duration_minutes: 45,
+            question_count: None,
+            points_per_question: options.points_per_question,
+            print_layout: options.print_layout,
+            two_columns: options.two_columns,
+            spacing: options.spacing,
+            answer_line: options.answer_line,
+            answer_sheet: options.answer_sheet,
+        }
+    }
+}
+
+impl ExamPreset {
+    /// Presets offered before any is set in the settings
+    pub fn builtin() -> Vec<Self> {
+        vec![
+            // One A5 page, two copies per sheet, answered on the paper
+            Self {
+                name: "Kiểm tra 15 phút".to_string(),
+                exam_name: "KIỂM TRA 15 PHÚT".to_string(),
+                duration_minutes: 15,
+                question_count: Some(10),
+                points_per_question: 1.0,
+                print_layout: PrintLayout::TwoUp,
+                answer_line: AnswerLine::Bubbles,
+                ..Default::default()
+            },
+            Self {
+                name: "Giữa kỳ 45 phút".to_string(),
+                exam_name: "KIỂM TRA GIỮA KỲ".to_string(),
+                duration_minutes: 45,
+                question_count: Some(28),
+                points_per_question: 0.25,
+                ..Default::default()
+            },
+            Self {
+                name: "Cuối kỳ 90 phút THPT".to_string(),
+                exam_name: "KIỂM TRA CUỐI KỲ".to_string(),
+                duration_minutes: 90,
+                question_count: Some(40),
+                points_per_question: 0.25,
+                spacing: Spacing::Preset(SpacingPreset::Compact),
+                ..Default::default()
+            },
+        ]
+    }
+
+    /// Fill the options still at their default value with the preset's;
+    /// options the user changed are kept
+    pub fn apply(&self, options: &mut ExportOptions) {
+        let defaults = ExportOptions::default();
+        if options.exam_name.is_none() && !self.exam_name.is_empty() {
+            options.exam_name = Some(self.exam_name.clone());
+        }
+        if options.duration_minutes.is_none() {
+            options.duration_minutes = Some(self.duration_minutes);
+        }
+        if options.points_per_question == defaults.points_per_question {
+            options.points_per_question = self.points_per_question;
+        }
+        if options.print_layout == defaults.print_layout {
+            options.print_layout = self.print_layout;
+        }
+        if options.two_columns == defaults.two_columns {
+            options.two_columns = self.two_columns;
+        }
+        if options.spacing == defaults.spacing {
+            options.spacing = self.spacing;
+        }
+        if options.answer_line == defaults.answer_line {
+            options.answer_line = self.answer_line;
+        }
+        if options.answer_sheet.is_none() {
+            options.answer_sheet = self.answer_sheet;
+        }
+    }
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// `own` presets followed by the built-in ones they do not replace
+pub fn all(own: &[ExamPreset]) -> Vec<ExamPreset> {
+    let builtin = ExamPreset::builtin()
+        .into_iter()
+        .filter(|preset| !own.iter().any(|o| same_name(&o.name, &preset.name)));
+    own.iter().cloned().chain(builtin).collect()
+}
+
+/// Preset called `name` (case and surrounding spaces ignored) among `own`
+/// and the built-in ones
+pub fn find(own: &[ExamPreset], name: &str) -> Option<ExamPreset> {
+    all(own).into_iter().find(|preset| same_name(&preset.name, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_fills_only_default_options() {
+        let preset = find(&[], " kiểm tra 15 phút").unwrap();
+        let mut options: ExportOptions = serde_json::from_str(r#"{"pointsPerQuestion": 0.5}"#).unwrap();
+        preset.apply(&mut options);
+        assert_eq!(options.exam_name.as_deref(), Some("KIỂM TRA 15 PHÚT"));
+        assert_eq!(options.duration_minutes, Some(15));
+        assert_eq!(options.print_layout, PrintLayout::TwoUp);
+        // Set by the user
+        assert_eq!(options.points_per_question, 0.5);
+
+        let own = ExamPreset {
+            name: "Kiểm tra 15 phút".to_string(),
+            duration_minutes: 20,
+            ..Default::default()
+        };
+        let own = [own];
+        assert_eq!(find(&own, "Kiểm tra 15 phút").unwrap().duration_minutes, 20);
+        assert_eq!(all(&own).len(), ExamPreset::builtin().len());
+        assert!(find(&[], "Thi thử").is_none());
+    }
+}
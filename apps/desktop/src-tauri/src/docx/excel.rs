@@ -3,17 +3,26 @@
 
 use rust_xlsxwriter::*;
 use std::path::Path;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use super::answer_symbols::{answer_symbol, true_false_symbols};
+use super::export_options::ExportOptions;
 
 /// Mixed exam data structure (matches frontend)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct MixedExam {
     #[serde(rename = "examCode")]
     pub exam_code: String,
     pub questions: Vec<MixedQuestion>,
+    /// Shared-data headers (see `docx::groups`)
+    #[serde(default)]
+    pub groups: Vec<crate::docx::groups::MixedGroup>,
+    /// Part headings (see `docx::exam_parts`)
+    #[serde(default)]
+    pub parts: Vec<crate::docx::exam_parts::MixedPart>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct MixedQuestion {
     #[serde(rename = "originalNumber")]
     pub original_number: usize,
@@ -23,21 +32,69 @@ pub struct MixedQuestion {
     pub options: Vec<MixedOption>,
     #[serde(rename = "correctAnswer")]
     pub correct_answer: String,
+    #[serde(rename = "stemAlt", default, skip_serializing_if = "Vec::is_empty")]
+    pub stem_alt: Vec<crate::docx::model::Segment>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct MixedOption {
     pub label: String,
     #[serde(rename = "originalLabel")]
     pub original_label: String,
     pub content: Vec<crate::docx::model::Segment>,
+    #[serde(rename = "contentAlt", default, skip_serializing_if = "Vec::is_empty")]
+    pub content_alt: Vec<crate::docx::model::Segment>,
+}
+
+impl MixedExam {
+    /// Variant as stored in the workspace: the permutation of its
+    /// `mapping.json` applied to the parsed questions. Questions missing from
+    /// `questions` are left out.
+    pub fn from_mapping(mapping: &super::mixer::VariantMapping, questions: &[super::model::Question]) -> Self {
+        let questions = mapping
+            .questions
+            .iter()
+            .filter_map(|qm| {
+                let original = questions.iter().find(|q| q.number == qm.original_number)?;
+                let options = qm
+                    .options
+                    .iter()
+                    .map(|om| {
+                        let source = original.options.iter().find(|o| o.label == om.original_label);
+                        MixedOption {
+                            label: om.label.clone(),
+                            original_label: om.original_label.clone(),
+                            content: source.map(|o| o.content.clone()).unwrap_or_default(),
+                            content_alt: source.map(|o| o.content_alt.clone()).unwrap_or_default(),
+                        }
+                    })
+                    .collect();
+                Some(MixedQuestion {
+                    original_number: qm.original_number as usize,
+                    display_number: qm.display_number as usize,
+                    stem: original.stem.clone(),
+                    options,
+                    correct_answer: qm.correct_answer.clone(),
+                    stem_alt: original.stem_alt.clone(),
+                })
+            })
+            .collect();
+        Self {
+            exam_code: mapping.exam_code.clone(),
+            questions,
+            groups: Vec::new(),
+            parts: Vec::new(),
+        }
+    }
 }
 
-/// Write answer key to Excel file
+/// Write answer key to Excel file. True/false questions are answered
+/// "Đ"/"S" (see `answer_symbols`).
 pub fn write_answer_key(
     exams: &[MixedExam],
     original_answers: &[String],
     output_path: &Path,
+    options: &ExportOptions,
 ) -> Result<(), XlsxError> {
     let mut workbook = Workbook::new();
 
@@ -51,7 +108,7 @@ pub fn write_answer_key(
         let header_format = Format::new()
             .set_bold()
             .set_background_color(Color::RGB(0x4F46E5)); // Violet
-        worksheet.write_string_with_format(0, 0, "Câu hỏi", &header_format)?;
+        worksheet.write_string_with_format(0, 0, options.answer_key_question_header(), &header_format)?;
         worksheet.write_string_with_format(0, 1, "Đáp án", &header_format)?;
         worksheet.write_string_with_format(0, 2, "Câu gốc", &header_format)?;
         worksheet.write_string_with_format(0, 3, "Đáp án gốc", &header_format)?;
@@ -64,7 +121,8 @@ pub fn write_answer_key(
             worksheet.write_number(row, 0, question.display_number as f64)?;
 
             // Correct answer (after shuffle)
-            worksheet.write_string(row, 1, &question.correct_answer)?;
+            let symbols = true_false_symbols(question.options.iter().map(|o| (o.label.as_str(), o.content.as_slice())));
+            worksheet.write_string(row, 1, answer_symbol(symbols.as_ref(), &question.correct_answer))?;
 
             // Original question number
             worksheet.write_number(row, 2, question.original_number as f64)?;
@@ -72,7 +130,10 @@ pub fn write_answer_key(
             // Original answer
             let original_idx = question.original_number - 1;
             if let Some(orig_ans) = original_answers.get(original_idx) {
-                worksheet.write_string(row, 3, orig_ans)?;
+                let original_symbols = true_false_symbols(
+                    question.options.iter().map(|o| (o.original_label.as_str(), o.content.as_slice())),
+                );
+                worksheet.write_string(row, 3, answer_symbol(original_symbols.as_ref(), orig_ans))?;
             }
         }
 
@@ -86,3 +147,48 @@ pub fn write_answer_key(
     workbook.save(output_path)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::mixer::{OptionMapping, QuestionMapping, VariantMapping};
+    use crate::docx::model::{OptionItem, Question, Segment};
+
+    #[test]
+    fn rebuilds_a_variant_from_its_mapping() {
+        let text = |t: &str| Segment::Text { text: t.to_string(), raw_xml: String::new() };
+        let question = Question {
+            number: 1,
+            stem: vec![text("1 + 1 = ?")],
+            options: ["A", "B"]
+                .iter()
+                .zip(["2", "3"])
+                .map(|(label, content)| OptionItem {
+                    label: label.to_string(),
+                    content: vec![text(content)],
+                    ..Default::default()
+                })
+                .collect(),
+            correct_label: "A".to_string(),
+            ..Default::default()
+        };
+        let mapping = VariantMapping {
+            exam_code: "101".to_string(),
+            seed: 7,
+            questions: vec![QuestionMapping {
+                display_number: 1,
+                original_number: 1,
+                options: vec![
+                    OptionMapping { label: "A".to_string(), original_label: "B".to_string() },
+                    OptionMapping { label: "B".to_string(), original_label: "A".to_string() },
+                ],
+                correct_answer: "B".to_string(),
+            }],
+        };
+
+        let exam = MixedExam::from_mapping(&mapping, &[question]);
+        assert_eq!(exam.exam_code, "101");
+        assert_eq!(exam.questions[0].correct_answer, "B");
+        assert!(matches!(&exam.questions[0].options[1].content[0], Segment::Text { text, .. } if text == "2"));
+    }
+}
@@ -0,0 +1,489 @@
+// src-tauri/src/docx/export_options.rs
+//! User-configurable export options shared by the DOCX writer and answer-key exporters
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use super::answer_sheet::AnswerSheetLayout;
+use super::closing::ClosingTemplate;
+use super::cover_page::CoverPageTemplate;
+use super::lexicon::{fill, Lexicon};
+
+/// How an option label is separated from its content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OptionSeparator {
+    /// "A."
+    #[serde(rename = "dot")]
+    Dot,
+    /// "A)"
+    #[serde(rename = "paren")]
+    Paren,
+}
+
+/// How exam pages are laid out on the printed sheet. The DOCX is set up so
+/// that Word's own printing does the imposition; PDF output of the
+/// exporters is imposed by the app (see `exporters::imposition`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PrintLayout {
+    /// One A4 portrait page per side
+    #[default]
+    Standard,
+    /// Saddle-stitch booklet: A5 pages on A4 landscape sheets, printed in
+    /// fold order (Word's "Book fold")
+    Booklet,
+    /// Two copies of the exam side by side on each A4 landscape sheet, to be
+    /// cut into A5 halves; meant for one-page quizzes
+    TwoUp,
+}
+
+/// What is printed after a question without options (short answer, essay)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnswerSpaceStyle {
+    /// Next question right after the stem, as before
+    #[default]
+    None,
+    /// Empty lines
+    Blank,
+    /// Dotted lines to write on
+    Ruled,
+    /// A bordered box
+    Box,
+}
+
+/// Room left for the student's answer after each question without options
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnswerSpace {
+    pub style: AnswerSpaceStyle,
+    /// Height, in lines of body text
+    pub lines: u32,
+}
+
+impl Default for AnswerSpace {
+    fn default() -> Self {
+        Self {
+            style: AnswerSpaceStyle::None,
+            lines: 5,
+        }
+    }
+}
+
+/// Line printed under each question for answering on the exam paper itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnswerLine {
+    /// Answers go on a separate answer sheet
+    #[default]
+    None,
+    /// "Đáp án: ..........."
+    Line,
+    /// A row of circles, one per option ("○ A   ○ B   ○ C   ○ D"); questions
+    /// without options get the "Đáp án:" line
+    Bubbles,
+}
+
+/// How the correct option is shown in the teacher copy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CorrectMarkStyle {
+    /// Label underlined, as in the source documents
+    Underline,
+    /// Label and content in red
+    Red,
+    /// Content in bold (labels already are)
+    Bold,
+    /// "*" after the option
+    Asterisk,
+    /// "→ Đáp án: B" line after the options
+    AnswerLine,
+}
+
+/// Spacing of the questions: a preset name or custom values
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Spacing {
+    Preset(SpacingPreset),
+    Custom(QuestionSpacing),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpacingPreset {
+    /// Single-spaced paragraphs with no extra space, as before this option
+    Normal,
+    /// Tighter lines, to save a page on long exams
+    Compact,
+}
+
+/// Spacing of question and option paragraphs. Not applied to the
+/// large-print copy, which has its own, nor to raw passthrough.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct QuestionSpacing {
+    /// Space after every paragraph, in points
+    pub paragraph_after_pt: f64,
+    /// Extra space above each question, in points
+    pub question_gap_pt: f64,
+    /// Left indent of option paragraphs, in centimeters
+    pub option_indent_cm: f64,
+    /// Line spacing, in lines (1.0 = single)
+    pub line_spacing: f64,
+}
+
+impl Default for QuestionSpacing {
+    fn default() -> Self {
+        Self::NORMAL
+    }
+}
+
+impl QuestionSpacing {
+    pub const NORMAL: Self = Self {
+        paragraph_after_pt: 0.0,
+        question_gap_pt: 0.0,
+        option_indent_cm: 0.0,
+        line_spacing: 1.0,
+    };
+
+    pub const COMPACT: Self = Self {
+        paragraph_after_pt: 0.0,
+        question_gap_pt: 0.0,
+        option_indent_cm: 0.0,
+        line_spacing: 0.9,
+    };
+}
+
+impl Default for Spacing {
+    fn default() -> Self {
+        Spacing::Preset(SpacingPreset::Normal)
+    }
+}
+
+impl Spacing {
+    pub fn values(&self) -> QuestionSpacing {
+        match self {
+            Spacing::Preset(SpacingPreset::Normal) => QuestionSpacing::NORMAL,
+            Spacing::Preset(SpacingPreset::Compact) => QuestionSpacing::COMPACT,
+            Spacing::Custom(values) => *values,
+        }
+    }
+}
+
+const DEFAULT_QUESTION_PREFIX: &str = "Câu";
+
+/// Export options sent from the frontend. Missing fields fall back to the
+/// Vietnamese defaults used before these options existed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    /// Question prefix, e.g. "Câu", "Question", "Câu hỏi"
+    #[serde(rename = "questionPrefix")]
+    pub question_prefix: String,
+    #[serde(rename = "optionSeparator")]
+    pub option_separator: OptionSeparator,
+    /// Decimal separator for printed numbers (',' in Vietnamese, '.' in English)
+    #[serde(rename = "decimalSeparator")]
+    pub decimal_separator: char,
+    /// Points awarded per correct answer, printed in the instructions line
+    #[serde(rename = "pointsPerQuestion")]
+    pub points_per_question: f64,
+    /// Keep floating images (`wp:anchor`) floating instead of re-emitting
+    /// them inline after the text
+    #[serde(rename = "preserveFloatingImages")]
+    pub preserve_floating_images: bool,
+    /// Splice the original paragraphs of each question into the output
+    /// (needs a job parsed with `keepRawXml`); questions that cannot be passed
+    /// through are regenerated as usual
+    #[serde(rename = "rawPassthrough")]
+    pub raw_passthrough: bool,
+    #[serde(rename = "printLayout")]
+    pub print_layout: PrintLayout,
+    /// Also write a large-print copy of every variant (`De_<code>_ChuLon.docx`)
+    /// for visually impaired students, from the same mixed questions
+    #[serde(rename = "largePrintCopy")]
+    pub large_print_copy: bool,
+    /// Print "KHÔNG", "NGOẠI TRỪ" and "SAI" in stems in bold
+    #[serde(rename = "boldNegativeWords")]
+    pub bold_negative_words: bool,
+    /// Convert WMF/EMF equations again at this resolution for print, when
+    /// the analysis converted them at a lower one (None = analysis PNGs)
+    #[serde(rename = "imageDpi")]
+    pub image_dpi: Option<u32>,
+    /// Subject and grade printed in the exam header, also the `{subject}`
+    /// and `{grade}` tokens of the file name templates
+    pub subject: String,
+    pub grade: String,
+    /// Name of each variant file, without extension (see `file_names`);
+    /// the large-print copy adds "_ChuLon"
+    #[serde(rename = "fileNameTemplate")]
+    pub file_name_template: String,
+    /// Name of the answer key workbook, without extension
+    #[serde(rename = "answerKeyFileNameTemplate")]
+    pub answer_key_file_name_template: String,
+    /// Cover page printed before the header of each variant (None = no cover)
+    #[serde(rename = "coverPage")]
+    pub cover_page: Option<CoverPageTemplate>,
+    #[serde(rename = "answerSpace")]
+    pub answer_space: AnswerSpace,
+    #[serde(rename = "answerLine")]
+    pub answer_line: AnswerLine,
+    /// Questions in two columns under the full-width header, for subjects
+    /// with short questions; ignored by the large-print copy
+    #[serde(rename = "twoColumns")]
+    pub two_columns: bool,
+    /// "normal", "compact" or custom values (see `QuestionSpacing`)
+    pub spacing: Spacing,
+    /// Also write a teacher copy of every variant (`De_<code>_GiaoVien.docx`)
+    /// with the correct answers marked this way
+    #[serde(rename = "teacherCopy")]
+    pub teacher_copy: Option<CorrectMarkStyle>,
+    /// Wording of the header, instructions and answer lines (None = the
+    /// wording set for the subject in the settings, else Vietnamese)
+    pub lexicon: Option<Lexicon>,
+    /// "HẾT" line and signature block after the last question (None = none)
+    pub closing: Option<ClosingTemplate>,
+    /// Instructions printed after the header (materials allowed, how to
+    /// fill the answer sheet), one paragraph per line; `{questions}`,
+    /// `{minutes}` and `{points}` are filled for each variant (None = none)
+    pub instructions: Option<String>,
+    /// Exam preset (see `exam_preset`) filling the options left at their
+    /// defaults, by name
+    pub preset: Option<String>,
+    /// Exam name and duration printed in the header (None = the preset's,
+    /// else the usual mid-term header)
+    #[serde(rename = "examName")]
+    pub exam_name: Option<String>,
+    #[serde(rename = "durationMinutes")]
+    pub duration_minutes: Option<u32>,
+    /// School name and school year printed in the header (None = the school
+    /// named in the license, if any, and the current school year)
+    #[serde(rename = "schoolName")]
+    pub school_name: Option<String>,
+    #[serde(rename = "academicYear")]
+    pub academic_year: Option<String>,
+    /// Code128 barcode of the exam code and page number in the footer of
+    /// every page (see `barcode`), for sorting scanned stacks
+    #[serde(rename = "pageBarcode")]
+    pub page_barcode: bool,
+    /// Also write a blank answer sheet for optical mark readers in this
+    /// layout (`PhieuTraLoi.docx`, see `answer_sheet`)
+    #[serde(rename = "answerSheet")]
+    pub answer_sheet: Option<AnswerSheetLayout>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            question_prefix: DEFAULT_QUESTION_PREFIX.to_string(),
+            option_separator: OptionSeparator::Dot,
+            decimal_separator: ',',
+            points_per_question: 0.25,
+            preserve_floating_images: false,
+            raw_passthrough: false,
+            print_layout: PrintLayout::Standard,
+            large_print_copy: false,
+            bold_negative_words: false,
+            image_dpi: None,
+            subject: "Toán học".to_string(),
+            grade: "LỚP 7".to_string(),
+            file_name_template: "De_{code}".to_string(),
+            answer_key_file_name_template: "Dap_An".to_string(),
+            cover_page: None,
+            answer_space: AnswerSpace::default(),
+            answer_line: AnswerLine::None,
+            two_columns: false,
+            spacing: Spacing::default(),
+            teacher_copy: None,
+            lexicon: None,
+            closing: None,
+            instructions: None,
+            preset: None,
+            exam_name: None,
+            duration_minutes: None,
+            school_name: None,
+            academic_year: None,
+            page_barcode: false,
+            answer_sheet: None,
+        }
+    }
+}
+
+impl ExportOptions {
+    pub fn lexicon(&self) -> Cow<'_, Lexicon> {
+        match &self.lexicon {
+            Some(lexicon) => Cow::Borrowed(lexicon),
+            None => Cow::Owned(Lexicon::default()),
+        }
+    }
+
+    /// Paragraphs of the instructions block of a variant with `questions`
+    /// questions, placeholders filled; blank lines are dropped
+    pub fn instruction_lines(&self, questions: usize, duration_minutes: u32) -> Vec<String> {
+        let Some(instructions) = &self.instructions else {
+            return Vec::new();
+        };
+        let values = [
+            ("questions", questions.to_string()),
+            ("minutes", duration_minutes.to_string()),
+            ("points", self.format_decimal(self.points_per_question)),
+        ];
+        let values: Vec<(&str, &str)> = values.iter().map(|(name, value)| (*name, value.as_str())).collect();
+        instructions
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| fill(line, &values))
+            .collect()
+    }
+
+    /// Question heading, e.g. "Câu 3." / "Question 3."
+    pub fn question_heading(&self, number: usize) -> String {
+        format!("{} {}.", self.question_prefix, number)
+    }
+
+    /// Title of the question column of the answer key: "Câu hỏi" unless
+    /// another prefix is set
+    pub fn answer_key_question_header(&self) -> &str {
+        if self.question_prefix == DEFAULT_QUESTION_PREFIX {
+            "Câu hỏi"
+        } else {
+            &self.question_prefix
+        }
+    }
+
+    /// Option label with its separator, e.g. "A." / "A)"
+    pub fn option_label(&self, label: &str) -> String {
+        match self.option_separator {
+            OptionSeparator::Dot => format!("{}.", label),
+            OptionSeparator::Paren => format!("{})", label),
+        }
+    }
+
+    /// `academic_year`, else the school year of `today` ("2025 - 2026"); a
+    /// school year starts in August
+    pub fn academic_year_on(&self, today: chrono::NaiveDate) -> String {
+        use chrono::Datelike;
+
+        if let Some(year) = self.academic_year.as_ref().filter(|y| !y.trim().is_empty()) {
+            return year.trim().to_string();
+        }
+        let start = if today.month() >= 8 { today.year() } else { today.year() - 1 };
+        format!("{} - {}", start, start + 1)
+    }
+
+    /// Format a decimal number with the configured separator, trimming
+    /// trailing zeros (0.25 → "0,25", 1.0 → "1")
+    pub fn format_decimal(&self, value: f64) -> String {
+        let mut text = format!("{:.2}", value);
+        if text.contains('.') {
+            text = text.trim_end_matches('0').trim_end_matches('.').to_string();
+        }
+        text.replace('.', &self.decimal_separator.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_options_match_vietnamese_output() {
+        let options = ExportOptions::default();
+        assert_eq!(options.question_heading(1), "Câu 1.");
+        assert_eq!(options.option_label("A"), "A.");
+        assert_eq!(options.format_decimal(0.25), "0,25");
+        assert_eq!(options.format_decimal(1.0), "1");
+        assert_eq!(options.answer_key_question_header(), "Câu hỏi");
+    }
+
+    #[test]
+    fn test_english_options() {
+        let options = ExportOptions {
+            question_prefix: "Question".to_string(),
+            option_separator: OptionSeparator::Paren,
+            decimal_separator: '.',
+            ..Default::default()
+        };
+        assert_eq!(options.question_heading(12), "Question 12.");
+        assert_eq!(options.option_label("C"), "C)");
+        assert_eq!(options.format_decimal(0.5), "0.5");
+        assert_eq!(options.answer_key_question_header(), "Question");
+    }
+
+    #[test]
+    fn test_academic_year_follows_the_school_calendar() {
+        let date = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let options = ExportOptions::default();
+        assert_eq!(options.academic_year_on(date(2026, 3, 10)), "2025 - 2026");
+        assert_eq!(options.academic_year_on(date(2026, 8, 1)), "2026 - 2027");
+
+        let options: ExportOptions = serde_json::from_str(r#"{"academicYear":"2024 - 2025"}"#).unwrap();
+        assert_eq!(options.academic_year_on(date(2026, 3, 10)), "2024 - 2025");
+    }
+
+    #[test]
+    fn test_print_layout_defaults_to_standard() {
+        let options: ExportOptions = serde_json::from_str(r#"{"questionPrefix":"Câu"}"#).unwrap();
+        assert_eq!(options.print_layout, PrintLayout::Standard);
+
+        let options: ExportOptions = serde_json::from_str(r#"{"printLayout":"twoUp"}"#).unwrap();
+        assert_eq!(options.print_layout, PrintLayout::TwoUp);
+    }
+
+    #[test]
+    fn test_answer_space_and_line_default_to_none() {
+        let options = ExportOptions::default();
+        assert_eq!(options.answer_space.style, AnswerSpaceStyle::None);
+
+        let options: ExportOptions = serde_json::from_str(r#"{"answerSpace":{"style":"ruled"}}"#).unwrap();
+        assert_eq!(options.answer_space, AnswerSpace { style: AnswerSpaceStyle::Ruled, lines: 5 });
+        assert_eq!(options.answer_line, AnswerLine::None);
+
+        let options: ExportOptions = serde_json::from_str(r#"{"answerLine":"bubbles"}"#).unwrap();
+        assert_eq!(options.answer_line, AnswerLine::Bubbles);
+    }
+
+    #[test]
+    fn test_teacher_copy_is_opt_in() {
+        assert_eq!(ExportOptions::default().teacher_copy, None);
+        let options: ExportOptions = serde_json::from_str(r#"{"teacherCopy":"answerLine"}"#).unwrap();
+        assert_eq!(options.teacher_copy, Some(CorrectMarkStyle::AnswerLine));
+    }
+
+    #[test]
+    fn test_instruction_placeholders_are_filled() {
+        assert!(ExportOptions::default().instruction_lines(40, 50).is_empty());
+
+        let options: ExportOptions = serde_json::from_str(
+            r#"{"instructions":"Đề gồm {questions} câu, làm trong {minutes} phút.\n\nMỗi câu {points} điểm. Tô kín ô tròn trên phiếu trả lời."}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            options.instruction_lines(40, 50),
+            vec![
+                "Đề gồm 40 câu, làm trong 50 phút.".to_string(),
+                "Mỗi câu 0,25 điểm. Tô kín ô tròn trên phiếu trả lời.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spacing_preset_or_custom() {
+        assert_eq!(ExportOptions::default().spacing.values(), QuestionSpacing::NORMAL);
+
+        let options: ExportOptions = serde_json::from_str(r#"{"spacing":"compact"}"#).unwrap();
+        assert_eq!(options.spacing.values(), QuestionSpacing::COMPACT);
+
+        let options: ExportOptions = serde_json::from_str(r#"{"spacing":{"questionGapPt":6,"optionIndentCm":0.5}}"#).unwrap();
+        assert_eq!(
+            options.spacing.values(),
+            QuestionSpacing {
+                question_gap_pt: 6.0,
+                option_indent_cm: 0.5,
+                ..QuestionSpacing::NORMAL
+            }
+        );
+    }
+}
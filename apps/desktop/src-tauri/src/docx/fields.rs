@@ -0,0 +1,98 @@
+// src-tauri/src/docx/fields.rs
+//! Field codes and bookmarks inside a paragraph.
+//!
+//! A complex field is a run sequence
+//! `fldChar begin` → `instrText` ("PAGEREF _Toc123 \h", "SEQ Hình \* ARABIC")
+//! → `fldChar separate` → result runs → `fldChar end`. Only the result is
+//! content; the rest is machinery that must not reach the parsed text or be
+//! passed through to the export, where the bookmarks it points to do not
+//! exist. `w:fldSimple` wraps its result runs directly.
+
+use std::borrow::Cow;
+
+use regex::Regex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldPart {
+    Instruction,
+    Result,
+}
+
+/// The paragraph with every field replaced by its display text and the
+/// bookmarks removed. Borrowed when there is nothing to strip.
+pub fn flatten(paragraph_xml: &str) -> Cow<'_, str> {
+    if !paragraph_xml.contains("<w:fldChar")
+        && !paragraph_xml.contains("<w:fldSimple")
+        && !paragraph_xml.contains("<w:bookmark")
+    {
+        return Cow::Borrowed(paragraph_xml);
+    }
+
+    let run_re = Regex::new(r"(?s)<w:r\b[^>]*?(?:/>|>.*?</w:r>)").unwrap();
+    let fld_char_re = Regex::new(r#"<w:fldChar\b[^>]*?w:fldCharType="(begin|separate|end)""#).unwrap();
+
+    // Fields may nest (a PAGEREF inside an IF): one entry per open field
+    let mut open: Vec<FieldPart> = Vec::new();
+    let mut out = String::with_capacity(paragraph_xml.len());
+    let mut cursor = 0;
+    for run in run_re.find_iter(paragraph_xml) {
+        out.push_str(&paragraph_xml[cursor..run.start()]);
+        cursor = run.end();
+
+        let fld_chars: Vec<_> = fld_char_re.captures_iter(run.as_str()).collect();
+        if !fld_chars.is_empty() {
+            for caps in fld_chars {
+                match &caps[1] {
+                    "begin" => open.push(FieldPart::Instruction),
+                    "separate" => {
+                        if let Some(part) = open.last_mut() {
+                            *part = FieldPart::Result;
+                        }
+                    }
+                    _ => {
+                        open.pop();
+                    }
+                }
+            }
+            continue;
+        }
+        if !open.contains(&FieldPart::Instruction) {
+            out.push_str(run.as_str());
+        }
+    }
+    out.push_str(&paragraph_xml[cursor..]);
+
+    let simple_re = Regex::new(r"(?s)<w:fldSimple\b[^>]*/>|<w:fldSimple\b[^>]*>(.*?)</w:fldSimple>").unwrap();
+    let bookmark_re = Regex::new(r"<w:bookmark(?:Start|End)\b[^>]*/>").unwrap();
+    let out = simple_re.replace_all(&out, "$1");
+    let out = bookmark_re.replace_all(&out, "");
+    Cow::Owned(out.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_field_results_only() {
+        let paragraph = concat!(
+            r#"<w:p><w:bookmarkStart w:id="0" w:name="_Toc1"/><w:r><w:t xml:space="preserve">Xem trang </w:t></w:r>"#,
+            r#"<w:r><w:fldChar w:fldCharType="begin"/></w:r>"#,
+            r#"<w:r><w:instrText xml:space="preserve"> PAGEREF _Toc1 \h </w:instrText></w:r>"#,
+            r#"<w:r><w:fldChar w:fldCharType="separate"/></w:r>"#,
+            r#"<w:r><w:t>5</w:t></w:r>"#,
+            r#"<w:r><w:fldChar w:fldCharType="end"/></w:r><w:bookmarkEnd w:id="0"/>"#,
+            r#"<w:r><w:t>, hình </w:t></w:r>"#,
+            r#"<w:fldSimple w:instr=" SEQ Hình \* ARABIC "><w:r><w:t>2</w:t></w:r></w:fldSimple></w:p>"#,
+        );
+
+        assert_eq!(
+            flatten(paragraph),
+            concat!(
+                r#"<w:p><w:r><w:t xml:space="preserve">Xem trang </w:t></w:r><w:r><w:t>5</w:t></w:r>"#,
+                r#"<w:r><w:t>, hình </w:t></w:r><w:r><w:t>2</w:t></w:r></w:p>"#,
+            )
+        );
+        assert!(matches!(flatten("<w:p><w:r><w:t>A</w:t></w:r></w:p>"), Cow::Borrowed(_)));
+    }
+}
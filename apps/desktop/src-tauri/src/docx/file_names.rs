@@ -0,0 +1,141 @@
+// src-tauri/src/docx/file_names.rs
+//! Output file names from the templates in the export options, so exported
+//! files follow the school's own naming ("Toan7_GK1_101", "2025-03-10_De_3").
+//!
+//! Tokens: `{subject}`, `{grade}`, `{code}` (exam code), `{date}` (export
+//! date, YYYY-MM-DD) and `{variant_index}` (1-based position of the variant).
+//! Unknown tokens are kept as written. Characters that Windows does not allow
+//! in file names are replaced by `_`.
+
+/// Values of the template tokens for one variant
+#[derive(Debug, Clone, Default)]
+pub struct FileNameContext {
+    pub subject: String,
+    pub grade: String,
+    pub code: String,
+    pub date: String,
+    pub variant_index: usize,
+}
+
+impl FileNameContext {
+    /// Context of a whole export (answer key); `code` and `variant_index`
+    /// are filled in per variant.
+    pub fn new(subject: &str, grade: &str) -> Self {
+        Self {
+            subject: subject.to_string(),
+            grade: grade.to_string(),
+            date: today(),
+            ..Default::default()
+        }
+    }
+
+    pub fn for_variant(&self, code: &str, variant_index: usize) -> Self {
+        Self {
+            code: code.to_string(),
+            variant_index,
+            ..self.clone()
+        }
+    }
+}
+
+/// File name (without extension) of a variant. A template without `{code}`
+/// or `{variant_index}` would give every variant the same name, so the exam
+/// code is appended.
+pub fn variant_stem(template: &str, context: &FileNameContext) -> String {
+    let mut stem = render(template, context);
+    if !template.contains("{code}") && !template.contains("{variant_index}") {
+        stem = format!("{}_{}", stem, sanitize(&context.code));
+    }
+    stem
+}
+
+/// `template` with its tokens replaced, made safe as a file name. Falls back
+/// to the exam code (or "De") when nothing is left.
+pub fn render(template: &str, context: &FileNameContext) -> String {
+    let rendered = template
+        .replace("{subject}", &context.subject)
+        .replace("{grade}", &context.grade)
+        .replace("{code}", &context.code)
+        .replace("{date}", &context.date)
+        .replace("{variant_index}", &context.variant_index.to_string());
+    let name = sanitize(&rendered);
+    if !name.is_empty() {
+        name
+    } else if !context.code.is_empty() {
+        sanitize(&context.code)
+    } else {
+        "De".to_string()
+    }
+}
+
+/// `name` with the characters Windows rejects in file names replaced
+pub fn sanitize(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows drops trailing dots and spaces silently
+    name.trim().trim_end_matches(['.', ' ']).to_string()
+}
+
+/// Today's date in the local time zone as YYYY-MM-DD: an exam exported in
+/// the morning in Vietnam (UTC+7) carries that day's date, not the day before
+pub fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// Unix timestamp as "YYYY-MM-DD HH:MM UTC", for messages
+pub fn format_timestamp(secs: u64) -> String {
+    let minutes = secs / 60;
+    format!(
+        "{} {:02}:{:02} UTC",
+        format_date((secs / 86_400) as i64),
+        (minutes / 60) % 24,
+        minutes % 60
+    )
+}
+
+/// Civil date of `days` since 1970-01-01 (Howard Hinnant's algorithm)
+fn format_date(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_tokens_into_safe_names() {
+        let context = FileNameContext {
+            subject: "Toán".to_string(),
+            grade: "LỚP 7".to_string(),
+            date: format_date(20_157),
+            ..Default::default()
+        }
+        .for_variant("101", 2);
+
+        assert_eq!(context.date, "2025-03-10");
+        assert_eq!(format_timestamp(20_157 * 86_400 + 7 * 3600 + 30 * 60), "2025-03-10 07:30 UTC");
+        assert_eq!(variant_stem("De_{code}", &context), "De_101");
+        assert_eq!(
+            variant_stem("{subject}_{grade}/{date}_{variant_index}", &context),
+            "Toán_LỚP 7_2025-03-10_2"
+        );
+        assert_eq!(variant_stem("{subject}: GK1.", &context), "Toán_ GK1_101");
+        assert_eq!(render("Dap_An_{subject}", &context), "Dap_An_Toán");
+        assert_eq!(render("", &context), "101");
+    }
+}
@@ -0,0 +1,103 @@
+// src-tauri/src/docx/fonts.rs
+//! Font fallback for characters Times New Roman does not have.
+//!
+//! The writer sets Times New Roman for all four font slots of every run, so
+//! Chinese characters, IPA symbols or emoji in a stem print as boxes. Text is
+//! split into runs by the font it needs and those runs name a font that has
+//! the glyphs (Windows/Office fonts).
+
+use super::config::NghiDinh30;
+
+/// CJK text (Chinese, Japanese kana, Korean)
+pub const EAST_ASIAN_FONT: &str = "SimSun";
+
+/// IPA and phonetic extensions
+pub const PHONETIC_FONT: &str = "Segoe UI";
+
+pub const EMOJI_FONT: &str = "Segoe UI Emoji";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontClass {
+    /// Times New Roman
+    Default,
+    EastAsian,
+    Phonetic,
+    Emoji,
+}
+
+/// Font class of `c`; `None` for characters that go with any font
+/// (spaces, ASCII punctuation, joiners) and stay with their neighbours.
+fn class_of(c: char) -> Option<FontClass> {
+    let class = match c as u32 {
+        0x20..=0x40 | 0x5B..=0x60 | 0x7B..=0x7E | 0x200D | 0xFE0E | 0xFE0F => return None,
+        0x0250..=0x02AF | 0x1D00..=0x1DBF => FontClass::Phonetic,
+        0x2E80..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF | 0x20000..=0x2FA1F => {
+            FontClass::EastAsian
+        }
+        0x2600..=0x27BF | 0x1F000..=0x1FAFF => FontClass::Emoji,
+        _ => FontClass::Default,
+    };
+    Some(class)
+}
+
+/// Split `text` into pieces that each need one font class.
+pub fn split(text: &str) -> Vec<(&str, FontClass)> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut current: Option<FontClass> = None;
+    for (i, c) in text.char_indices() {
+        let Some(class) = class_of(c) else { continue };
+        match current {
+            Some(previous) if previous != class => {
+                pieces.push((&text[start..i], previous));
+                start = i;
+            }
+            _ => {}
+        }
+        current = Some(class);
+    }
+    if start < text.len() {
+        pieces.push((&text[start..], current.unwrap_or(FontClass::Default)));
+    }
+    pieces
+}
+
+/// `<w:rFonts>` of a run of `class`
+pub fn r_fonts(class: FontClass) -> String {
+    let font = NghiDinh30::FONT_NAME;
+    match class {
+        FontClass::Default => {
+            format!(r#"<w:rFonts w:ascii="{font}" w:hAnsi="{font}" w:cs="{font}" w:eastAsia="{font}"/>"#)
+        }
+        FontClass::EastAsian => format!(
+            r#"<w:rFonts w:ascii="{font}" w:hAnsi="{font}" w:cs="{font}" w:eastAsia="{EAST_ASIAN_FONT}" w:hint="eastAsia"/>"#
+        ),
+        FontClass::Phonetic => format!(
+            r#"<w:rFonts w:ascii="{PHONETIC_FONT}" w:hAnsi="{PHONETIC_FONT}" w:cs="{font}" w:eastAsia="{font}"/>"#
+        ),
+        FontClass::Emoji => format!(
+            r#"<w:rFonts w:ascii="{EMOJI_FONT}" w:hAnsi="{EMOJI_FONT}" w:cs="{EMOJI_FONT}" w:eastAsia="{EMOJI_FONT}"/>"#
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_text_by_font() {
+        assert_eq!(split("Tính x = 2."), vec![("Tính x = 2.", FontClass::Default)]);
+        assert_eq!(
+            split("Chữ 中文 đọc là /ʃiː/ 👍!"),
+            vec![
+                ("Chữ ", FontClass::Default),
+                ("中文 ", FontClass::EastAsian),
+                ("đọc là /", FontClass::Default),
+                ("ʃ", FontClass::Phonetic),
+                ("iː/ ", FontClass::Default),
+                ("👍!", FontClass::Emoji),
+            ]
+        );
+    }
+}
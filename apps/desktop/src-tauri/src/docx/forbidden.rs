@@ -0,0 +1,53 @@
+// src-tauri/src/docx/forbidden.rs
+//! Screening of questions against the forbidden-terms list of the settings
+//! (politically sensitive phrasing, outdated place names, offensive words).
+//!
+//! Terms are matched as whole words, ignoring case, in the stem and options
+//! of both languages.
+
+use regex::Regex;
+
+use super::diff::segments_text;
+use super::model::Question;
+
+/// Forbidden terms found in `question`, as written in the list, in list order.
+pub fn matches(question: &Question, terms: &[String]) -> Vec<String> {
+    let text = std::iter::once(&question.stem)
+        .chain(std::iter::once(&question.stem_alt))
+        .chain(question.options.iter().flat_map(|o| [&o.content, &o.content_alt]))
+        .map(|segments| segments_text(segments))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    terms
+        .iter()
+        .map(|term| term.trim())
+        .filter(|term| !term.is_empty())
+        .filter(|term| {
+            Regex::new(&format!(r"(?i)\b{}\b", regex::escape(term)))
+                .map(|re| re.is_match(&text))
+                .unwrap_or(false)
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::Segment;
+
+    #[test]
+    fn matches_whole_words_ignoring_case() {
+        let question = Question {
+            number: 2,
+            stem: vec![Segment::Text {
+                text: "Tỉnh Hà Tây nằm ở vùng nào?".to_string(),
+                raw_xml: String::new(),
+            }],
+            ..Default::default()
+        };
+        let terms = vec!["hà tây".to_string(), "Tây Nguyên".to_string(), "Hà".to_string(), " ".to_string()];
+        assert_eq!(matches(&question, &terms), vec!["hà tây", "Hà"]);
+    }
+}
@@ -0,0 +1,162 @@
+// src-tauri/src/docx/groups.rs
+//! Shared-data question sets: a header such as "Dùng dữ kiện sau cho câu 15
+//! và 16" followed by a passage used by a range of questions.
+//!
+//! The parser turns the header and its passage into a `QuestionGroup`. When
+//! mixing, the questions of a group move as one block (kept in their original
+//! order) and the header is re-emitted before the block with the question
+//! numbers of the variant.
+
+use std::collections::HashMap;
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use super::model::{Question, QuestionGroup, Segment};
+
+/// Groups larger than this are taken for a typo in the header
+const MAX_GROUP_SIZE: u32 = 20;
+
+/// A group in a mixed variant
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MixedGroup {
+    /// Header with the variant's question numbers
+    pub header: Vec<Segment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paragraphs: Vec<Vec<Segment>>,
+    /// Display numbers of the questions, in order
+    pub questions: Vec<u32>,
+}
+
+/// Question range named by a group header paragraph, e.g. "Dùng thông tin
+/// sau để trả lời câu 15 đến 17" → 15..=17, or in English "Listen to the
+/// conversation and answer questions 3 to 5" → 3..=5.
+pub fn header_range(text: &str) -> Option<Vec<u32>> {
+    let re = Regex::new(
+        r"(?i)^(?:dùng|sử dụng|đọc|dựa vào|cho|nghe|listen|read|use|look)\b.*?\b(?:câu|questions?)\s+(\d+)\s*(?:và|đến|tới|and|to|-|–|,)\s*(?:(?:câu|questions?)\s+)?(\d+)",
+    )
+    .unwrap();
+    let caps = re.captures(text)?;
+    let first: u32 = caps[1].parse().ok()?;
+    let last: u32 = caps[2].parse().ok()?;
+    (first < last && last - first < MAX_GROUP_SIZE).then(|| (first..=last).collect())
+}
+
+/// Split questions into mixing units: one per group (its questions in
+/// document order) and one per remaining question. Without groups every
+/// question is its own unit, so shuffling the units consumes the RNG exactly
+/// like shuffling the questions did.
+pub fn units(questions: Vec<Question>, groups: &[QuestionGroup]) -> Vec<Vec<Question>> {
+    let mut units: Vec<Vec<Question>> = Vec::new();
+    // group index -> unit index
+    let mut group_units: HashMap<usize, usize> = HashMap::new();
+
+    for question in questions {
+        match groups.iter().position(|g| g.questions.contains(&question.number)) {
+            Some(group) => match group_units.get(&group) {
+                Some(&unit) => units[unit].push(question),
+                None => {
+                    group_units.insert(group, units.len());
+                    units.push(vec![question]);
+                }
+            },
+            None => units.push(vec![question]),
+        }
+    }
+    units
+}
+
+/// The groups of a variant, given original → display question numbers.
+pub fn mix_groups(groups: &[QuestionGroup], display: &HashMap<u32, u32>) -> Vec<MixedGroup> {
+    groups
+        .iter()
+        .filter_map(|group| {
+            let questions: Vec<u32> = group
+                .questions
+                .iter()
+                .filter_map(|number| display.get(number).copied())
+                .collect();
+            (!questions.is_empty()).then(|| MixedGroup {
+                header: rewrite_header(&group.header, display),
+                paragraphs: group.paragraphs.clone(),
+                questions,
+            })
+        })
+        .collect()
+}
+
+/// Replace the question numbers after "câu" in the header text.
+fn rewrite_header(header: &[Segment], display: &HashMap<u32, u32>) -> Vec<Segment> {
+    let range_re =
+        Regex::new(r"(?i)\b(?:câu|questions?)\s+\d+(?:\s*(?:và|đến|tới|and|to|-|–|,)\s*(?:(?:câu|questions?)\s+)?\d+)*")
+            .unwrap();
+    let number_re = Regex::new(r"\d+").unwrap();
+
+    header
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text { text, raw_xml } => {
+                let text = range_re.replace_all(text, |range: &Captures| {
+                    number_re
+                        .replace_all(&range[0], |number: &Captures| {
+                            match number[0].parse().ok().and_then(|n: u32| display.get(&n)) {
+                                Some(new) => new.to_string(),
+                                None => number[0].to_string(),
+                            }
+                        })
+                        .into_owned()
+                });
+                Segment::Text {
+                    text: text.into_owned(),
+                    raw_xml: raw_xml.clone(),
+                }
+            }
+            other => other.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    #[test]
+    fn groups_move_as_one_block() {
+        assert_eq!(header_range("Dùng dữ kiện sau cho câu 15 và 16"), Some(vec![15, 16]));
+        assert_eq!(header_range("Đọc đoạn văn và trả lời câu 3 đến câu 5:"), Some(vec![3, 4, 5]));
+        assert_eq!(header_range("Listen to the conversation and answer questions 3 to 5."), Some(vec![3, 4, 5]));
+        assert_eq!(header_range("Cho hàm số y = f(x)."), None);
+
+        let group = QuestionGroup {
+            header: text("Dùng dữ kiện sau cho câu 2 và 3"),
+            paragraphs: Vec::new(),
+            questions: vec![2, 3],
+        };
+        let questions: Vec<Question> = (1..=4)
+            .map(|number| Question {
+                number,
+                ..Default::default()
+            })
+            .collect();
+        let numbers: Vec<Vec<u32>> = units(questions, std::slice::from_ref(&group))
+            .iter()
+            .map(|unit| unit.iter().map(|q| q.number).collect())
+            .collect();
+        assert_eq!(numbers, vec![vec![1], vec![2, 3], vec![4]]);
+
+        let display = HashMap::from([(1, 3), (2, 1), (3, 2), (4, 4)]);
+        let mixed = mix_groups(&[group], &display);
+        assert_eq!(mixed[0].questions, vec![1, 2]);
+        assert!(matches!(
+            &mixed[0].header[0],
+            Segment::Text { text, .. } if text == "Dùng dữ kiện sau cho câu 1 và 2"
+        ));
+    }
+}
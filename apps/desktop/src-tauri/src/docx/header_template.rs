@@ -4,6 +4,28 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::model::Segment;
+
+/// Characters counted for an equation, whatever its size
+const MATH_CHARS: usize = 12;
+
+const EMU_PER_TWIP: i64 = 635;
+
+/// Text area of the exam pages the page estimate is made for, in twips
+pub struct TextArea {
+    pub column_width: i32,
+    pub text_height: i32,
+    pub columns: usize,
+    /// Half-points
+    pub font_size: i32,
+    pub line_height: i32,
+    /// Lines taken by the header and instructions above the questions
+    pub header_lines: usize,
+    /// Lines taken below the text of the questions: answer lines and
+    /// spaces, closing
+    pub extra_lines: usize,
+}
+
 /// Standard header template for Vietnamese exam documents
 /// Based on common format: School info (left) | Exam info (right)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +47,7 @@ pub struct StandardHeaderTemplate {
 
 impl StandardHeaderTemplate {
     /// Create a new header template with default values
+    #[allow(dead_code)]
     pub fn new(
         school_name: String,
         exam_code: String,
@@ -48,11 +71,40 @@ impl StandardHeaderTemplate {
         }
     }
     
-    /// Estimate number of pages based on question count
-    /// Simple heuristic: ~25-30 questions per page depending on images
-    pub fn estimate_pages(question_count: usize) -> u32 {
-        let pages = (question_count as f32 / 28.0).ceil() as u32;
-        pages.max(1) // At least 1 page
+    /// Estimate number of pages of a variant from its paragraphs (group
+    /// texts, stems and options, in order). There is no rendering step, so
+    /// lines are counted from the average character width; images take
+    /// their own height.
+    pub fn estimate_pages<'a>(paragraphs: impl IntoIterator<Item = &'a [Segment]>, area: &TextArea) -> u32 {
+        let line_height = area.line_height.max(1);
+        // Average character of Times New Roman: about half the font size
+        let chars_per_line = (area.column_width / (area.font_size * 5).max(1)).max(1) as usize;
+        let body_lines: usize = paragraphs
+            .into_iter()
+            .map(|segments| {
+                let mut chars = 0;
+                let mut image_lines = 0;
+                for segment in segments {
+                    match segment {
+                        Segment::Text { text, .. } => chars += text.chars().count(),
+                        Segment::Math { .. } => chars += MATH_CHARS,
+                        Segment::Symbol { .. } => chars += 1,
+                        Segment::Blank { width } => chars += *width as usize,
+                        // Printed as "[Bài nghe: <file>]"
+                        Segment::Audio { file_name, .. } => chars += file_name.chars().count() + 12,
+                        Segment::Image { height_emu, .. } => {
+                            let twips = (*height_emu / EMU_PER_TWIP) as i32;
+                            image_lines += ((twips + line_height - 1) / line_height).max(1) as usize;
+                        }
+                    }
+                }
+                chars.div_ceil(chars_per_line).max(1) + image_lines
+            })
+            .sum();
+        // The header spans every column
+        let lines = body_lines + area.extra_lines + area.header_lines * area.columns.max(1);
+        let lines_per_page = (area.text_height / line_height).max(1) as usize * area.columns.max(1);
+        (lines.div_ceil(lines_per_page) as u32).max(1) // At least 1 page
     }
     
     /// Format page count as Vietnamese text (e.g., "02", "10")
@@ -60,3 +112,49 @@ impl StandardHeaderTemplate {
         format!("{:02}", pages)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(chars: usize) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: "x".repeat(chars),
+            raw_xml: String::new(),
+        }]
+    }
+
+    #[test]
+    fn pages_follow_the_content_of_the_variant() {
+        // 40 lines of 50 characters per page, one column
+        let area = TextArea {
+            column_width: 50 * 130,
+            text_height: 40 * 300,
+            columns: 1,
+            font_size: 26,
+            line_height: 300,
+            header_lines: 10,
+            extra_lines: 0,
+        };
+        let short: Vec<Vec<Segment>> = (0..30).map(|_| text(20)).collect();
+        assert_eq!(StandardHeaderTemplate::estimate_pages(short.iter().map(Vec::as_slice), &area), 1);
+
+        // Same paragraphs three lines long
+        let long: Vec<Vec<Segment>> = (0..30).map(|_| text(120)).collect();
+        assert_eq!(StandardHeaderTemplate::estimate_pages(long.iter().map(Vec::as_slice), &area), 3);
+
+        // A 10-line image and two columns
+        let image = vec![Segment::Image {
+            asset_path: String::new(),
+            raw_xml: String::new(),
+            width_emu: 0,
+            height_emu: 3000 * EMU_PER_TWIP,
+            anchor: None,
+            ocr_text: None,
+        }];
+        let paragraphs = || short.iter().chain(std::iter::once(&image)).map(Vec::as_slice);
+        assert_eq!(StandardHeaderTemplate::estimate_pages(paragraphs(), &area), 2);
+        let two_columns = TextArea { columns: 2, ..area };
+        assert_eq!(StandardHeaderTemplate::estimate_pages(paragraphs(), &two_columns), 1);
+    }
+}
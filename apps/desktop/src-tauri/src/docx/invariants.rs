@@ -0,0 +1,305 @@
+// src-tauri/src/docx/invariants.rs
+//! Structural invariants of a mixed variant against its source questions.
+//!
+//! Whatever the seed, a variant must hold every original question exactly
+//! once, numbered 1..n in display order; each question must show each of
+//! its options exactly once under the labels A, B, C…, with locked options
+//! in their original slot; the original → new label mapping must be a
+//! bijection that carries the correct answer; the questions of a
+//! shared-data group must stay adjacent and in order; and questions must
+//! stay within their exam part, parts in document order. `mix_exams` checks
+//! every variant before it is returned, and the property tests below check
+//! the mixer against generated documents.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use super::labels::option_label;
+use super::mixer::MixedExam;
+use super::exam_parts::part_of;
+use super::model::{ExamPart, Question, QuestionGroup};
+
+/// One broken invariant of a variant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// An original question is not in the variant
+    QuestionMissing { original_number: u32 },
+    /// An original question appears more than once
+    QuestionDuplicated { original_number: u32 },
+    /// A question of the variant is not in the source
+    QuestionUnknown { original_number: u32 },
+    /// Display numbers are not 1, 2, 3… in order
+    DisplayNumber { position: usize, display_number: u32 },
+    /// The options shown are not the question's options, each once
+    OptionsChanged { original_number: u32 },
+    /// New labels are not A, B, C… in order
+    LabelsOutOfOrder { original_number: u32 },
+    /// A locked option left its slot
+    LockedOptionMoved { original_number: u32, label: String },
+    /// The correct answer does not follow the option permutation
+    AnswerNotMapped {
+        original_number: u32,
+        expected: String,
+        actual: String,
+    },
+    /// The questions of a shared-data group were split or reordered
+    GroupSplit { first_question: u32 },
+    /// A question left its part, or parts are out of order
+    PartOrder { original_number: u32 },
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::QuestionMissing { original_number } => write!(f, "thiếu câu gốc {}", original_number),
+            Self::QuestionDuplicated { original_number } => write!(f, "câu gốc {} bị lặp", original_number),
+            Self::QuestionUnknown { original_number } => write!(f, "câu {} không có trong đề gốc", original_number),
+            Self::DisplayNumber { position, display_number } => {
+                write!(f, "câu thứ {} được đánh số {}", position + 1, display_number)
+            }
+            Self::OptionsChanged { original_number } => {
+                write!(f, "phương án của câu gốc {} bị thiếu hoặc lặp", original_number)
+            }
+            Self::LabelsOutOfOrder { original_number } => {
+                write!(f, "nhãn phương án của câu gốc {} không theo thứ tự", original_number)
+            }
+            Self::LockedOptionMoved { original_number, label } => {
+                write!(f, "phương án cố định {} của câu gốc {} bị đổi chỗ", label, original_number)
+            }
+            Self::AnswerNotMapped { original_number, expected, actual } => write!(
+                f,
+                "câu gốc {}: đáp án phải là {}, đang là {}",
+                original_number, expected, actual
+            ),
+            Self::GroupSplit { first_question } => {
+                write!(f, "nhóm câu bắt đầu từ câu gốc {} bị tách", first_question)
+            }
+            Self::PartOrder { original_number } => {
+                write!(f, "câu gốc {} nằm ngoài phần của nó", original_number)
+            }
+        }
+    }
+}
+
+/// Every invariant `exam` breaks, mixed from `questions`, `groups` and
+/// `parts`
+pub fn check_variant(
+    questions: &[Question],
+    groups: &[QuestionGroup],
+    parts: &[ExamPart],
+    exam: &MixedExam,
+) -> Vec<InvariantViolation> {
+    let mut violations = Vec::new();
+    let originals: HashMap<u32, &Question> = questions.iter().map(|q| (q.number, q)).collect();
+
+    let mut seen: BTreeMap<u32, usize> = BTreeMap::new();
+    for (position, mixed) in exam.questions.iter().enumerate() {
+        *seen.entry(mixed.original_number).or_default() += 1;
+        if mixed.display_number as usize != position + 1 {
+            violations.push(InvariantViolation::DisplayNumber {
+                position,
+                display_number: mixed.display_number,
+            });
+        }
+
+        let Some(original) = originals.get(&mixed.original_number) else {
+            violations.push(InvariantViolation::QuestionUnknown {
+                original_number: mixed.original_number,
+            });
+            continue;
+        };
+        let original_number = original.number;
+
+        let mut shown: Vec<&str> = mixed.options.iter().map(|o| o.original_label.as_str()).collect();
+        let mut expected: Vec<&str> = original.options.iter().map(|o| o.label.as_str()).collect();
+        shown.sort_unstable();
+        expected.sort_unstable();
+        if shown != expected || expected.iter().collect::<HashSet<_>>().len() != expected.len() {
+            violations.push(InvariantViolation::OptionsChanged { original_number });
+            continue;
+        }
+
+        if mixed.options.iter().enumerate().any(|(i, o)| o.label != option_label(i)) {
+            violations.push(InvariantViolation::LabelsOutOfOrder { original_number });
+        }
+        for (slot, option) in original.options.iter().enumerate() {
+            if option.locked && mixed.options[slot].original_label != option.label {
+                violations.push(InvariantViolation::LockedOptionMoved {
+                    original_number,
+                    label: option.label.clone(),
+                });
+            }
+        }
+
+        // Labels are unique on both sides, so the mapping is a bijection;
+        // the answer must be the image of the original answer
+        let mapped = mixed
+            .options
+            .iter()
+            .find(|o| o.original_label == original.correct_label)
+            .map(|o| o.label.clone())
+            .unwrap_or_else(|| original.correct_label.clone());
+        if mixed.correct_answer != mapped {
+            violations.push(InvariantViolation::AnswerNotMapped {
+                original_number,
+                expected: mapped,
+                actual: mixed.correct_answer.clone(),
+            });
+        }
+    }
+
+    for question in questions {
+        match seen.get(&question.number) {
+            None => violations.push(InvariantViolation::QuestionMissing {
+                original_number: question.number,
+            }),
+            Some(&count) if count > 1 => violations.push(InvariantViolation::QuestionDuplicated {
+                original_number: question.number,
+            }),
+            Some(_) => {}
+        }
+    }
+
+    let position: HashMap<u32, usize> = exam
+        .questions
+        .iter()
+        .enumerate()
+        .map(|(i, q)| (q.original_number, i))
+        .collect();
+    for group in groups {
+        let positions: Vec<usize> = group.questions.iter().filter_map(|n| position.get(n).copied()).collect();
+        if positions.windows(2).any(|pair| pair[1] != pair[0] + 1) {
+            violations.push(InvariantViolation::GroupSplit {
+                first_question: group.questions[0],
+            });
+        }
+    }
+
+    // Questions before the first heading come first, then part after part
+    let mut last_part = None;
+    for mixed in &exam.questions {
+        let part = part_of(parts, mixed.original_number);
+        if part < last_part {
+            violations.push(InvariantViolation::PartOrder {
+                original_number: mixed.original_number,
+            });
+        }
+        last_part = last_part.max(part);
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::mixer;
+    use crate::docx::model::{OptionItem, Segment};
+    use proptest::prelude::*;
+
+    fn text(s: String) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s,
+            raw_xml: String::new(),
+        }]
+    }
+
+    /// A question with 0-6 options, the last one possibly locked ("Cả ba ý
+    /// trên"), and an answer that may be missing (not marked in the document)
+    fn arb_question(number: u32) -> impl Strategy<Value = Question> {
+        (0usize..=6, any::<bool>())
+            .prop_flat_map(|(count, lock_last)| (Just(count), Just(lock_last), 0..=count))
+            .prop_map(move |(count, lock_last, correct)| Question {
+                number,
+                stem: text(format!("Câu {}", number)),
+                options: (0..count)
+                    .map(|i| OptionItem {
+                        label: option_label(i),
+                        locked: lock_last && i + 1 == count,
+                        content: text(format!("Phương án {} của câu {}", i, number)),
+                        ..Default::default()
+                    })
+                    .collect(),
+                correct_label: if correct < count { option_label(correct) } else { String::new() },
+                ..Default::default()
+            })
+    }
+
+    /// Questions 1..=n cut into runs of 1-3; some runs of two or more are
+    /// shared-data groups, and some runs start an exam part
+    fn arb_document() -> impl Strategy<Value = (Vec<Question>, Vec<QuestionGroup>, Vec<ExamPart>)> {
+        prop::collection::vec((1u32..=3, any::<bool>(), prop::bool::weighted(0.2)), 1..12).prop_flat_map(|runs| {
+            let mut groups = Vec::new();
+            let mut parts: Vec<ExamPart> = Vec::new();
+            let mut next = 1;
+            for (len, grouped, new_part) in runs {
+                let numbers: Vec<u32> = (next..next + len).collect();
+                if grouped && len > 1 {
+                    groups.push(QuestionGroup {
+                        questions: numbers.clone(),
+                        ..Default::default()
+                    });
+                }
+                if new_part {
+                    parts.push(ExamPart::default());
+                }
+                if let Some(part) = parts.last_mut() {
+                    part.questions.extend(numbers);
+                }
+                next += len;
+            }
+            let questions: Vec<_> = (1..next).map(arb_question).collect();
+            (questions, Just(groups), Just(parts))
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn mixing_keeps_every_question_and_option((questions, groups, parts) in arb_document(), seed in any::<u64>()) {
+            let variants = mixer::mix_exams(questions.clone(), &groups, &parts, 3, None, seed, false);
+            for variant in &variants {
+                let violations = check_variant(&questions, &groups, &parts, variant);
+                prop_assert!(violations.is_empty(), "{}: {:?}", variant.exam_code, violations);
+            }
+        }
+
+        #[test]
+        fn rearranging_keeps_every_question_and_option((questions, groups, _) in arb_document(), seed in any::<u64>()) {
+            let mut order: Vec<u32> = questions.iter().map(|q| q.number).collect();
+            order.reverse();
+            let variant = mixer::arrange_variant(mixer::order_questions(&questions, &order), &[], &[], "101", seed, true);
+            let violations = check_variant(&questions, &[], &[], &variant);
+            prop_assert!(violations.is_empty(), "{:?}", violations);
+            // Reversed on purpose: every group is out of order
+            let split = check_variant(&questions, &groups, &[], &variant);
+            prop_assert_eq!(split.len(), groups.len());
+        }
+    }
+
+    #[test]
+    fn reports_a_broken_variant() {
+        let questions: Vec<Question> = (1..=2)
+            .map(|number| Question {
+                number,
+                options: (0..3)
+                    .map(|i| OptionItem {
+                        label: option_label(i),
+                        ..Default::default()
+                    })
+                    .collect(),
+                correct_label: "A".to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let mut variant = mixer::mix_variant(&questions, &[], &[], "101", 7, false);
+        variant.questions[1] = variant.questions[0].clone();
+        variant.questions[1].display_number = 2;
+        variant.questions[0].options.pop();
+
+        let violations = check_variant(&questions, &[], &[], &variant);
+        let first = variant.questions[0].original_number;
+        assert!(violations.contains(&InvariantViolation::OptionsChanged { original_number: first }));
+        assert!(violations.contains(&InvariantViolation::QuestionDuplicated { original_number: first }));
+        assert!(violations.contains(&InvariantViolation::QuestionMissing { original_number: 3 - first }));
+    }
+}
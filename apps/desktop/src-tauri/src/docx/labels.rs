@@ -0,0 +1,72 @@
+// src-tauri/src/docx/labels.rs
+//! Option label helpers (A, B, C, ...) shared by the parser, validator and mixer
+
+/// Default maximum number of options per question (A–H)
+pub const DEFAULT_MAX_OPTIONS: usize = 8;
+
+/// Hard upper bound: labels are single Latin letters A–Z
+pub const MAX_SUPPORTED_OPTIONS: usize = 26;
+
+/// Clamp a user-provided maximum into the supported range
+pub fn clamp_max_options(max_options: usize) -> usize {
+    max_options.clamp(1, MAX_SUPPORTED_OPTIONS)
+}
+
+/// Label for the option at `index` (0 → "A", 1 → "B", ...)
+pub fn option_label(index: usize) -> String {
+    let letter = (b'A' + (index % MAX_SUPPORTED_OPTIONS) as u8) as char;
+    letter.to_string()
+}
+
+/// Regex character class matching every allowed label, e.g. `[A-H]`
+pub fn label_char_class(max_options: usize) -> String {
+    let last = option_label(clamp_max_options(max_options) - 1);
+    format!("[A-{}]", last)
+}
+
+/// Whether `label` starts a new option after `existing` options. Labels up to
+/// `max_options` always do; a later letter only when it continues the
+/// sequence, so an extra "E." is kept (and reported) while a stray "I." in
+/// the option text is not mistaken for an option. Nothing follows Z.
+pub fn starts_option(label: &str, existing: usize, max_options: usize) -> bool {
+    let label = label.trim_start_matches('#');
+    let within_max = label
+        .bytes()
+        .next()
+        .and_then(|b| b.checked_sub(b'A'))
+        .is_some_and(|offset| (offset as usize) < clamp_max_options(max_options));
+    within_max || (existing < MAX_SUPPORTED_OPTIONS && label == option_label(existing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::validator::validate_option_count;
+
+    #[test]
+    fn labels_stop_at_z() {
+        assert_eq!(option_label(0), "A");
+        assert_eq!(option_label(MAX_SUPPORTED_OPTIONS - 1), "Z");
+        assert_eq!(label_char_class(MAX_SUPPORTED_OPTIONS), "[A-Z]");
+        assert_eq!(clamp_max_options(MAX_SUPPORTED_OPTIONS), 26);
+
+        // Z is the 26th option; nothing follows it
+        assert!(starts_option("Z", 25, MAX_SUPPORTED_OPTIONS));
+        assert!(starts_option("#Z", 25, MAX_SUPPORTED_OPTIONS));
+        assert!(validate_option_count(1, 26, MAX_SUPPORTED_OPTIONS).is_ok());
+    }
+
+    #[test]
+    fn more_than_26_options_are_rejected() {
+        // A larger setting is clamped to the labels there are
+        assert_eq!(clamp_max_options(27), MAX_SUPPORTED_OPTIONS);
+        assert_eq!(clamp_max_options(usize::MAX), MAX_SUPPORTED_OPTIONS);
+        assert_eq!(label_char_class(40), "[A-Z]");
+        // Past Z the sequence does not wrap around
+        assert!(!starts_option("E", 26, 4));
+        assert!(!starts_option("1", 3, 4));
+        assert!(validate_option_count(1, 27, 27).is_err());
+        assert!(validate_option_count(1, 27, 40).is_err());
+        assert!(validate_option_count(1, 27, MAX_SUPPORTED_OPTIONS).is_err());
+    }
+}
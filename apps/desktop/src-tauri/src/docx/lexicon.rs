@@ -0,0 +1,176 @@
+// src-tauri/src/docx/lexicon.rs
+//! Wording of the boilerplate printed around the questions: exam header,
+//! instructions line, answer lines, end of the exam and cover page headings.
+//!
+//! The Vietnamese wording is the default; English-medium schools pick the
+//! English preset or their own wording, in the export options or per
+//! subject in the settings (`subjectLexicons`). Placeholders in braces
+//! (`{questions}`, `{pages}`, `{minutes}`, `{prefix}`, `{points}`) are filled when writing.
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Lang;
+
+/// Boilerplate of an exam document. Missing fields fall back to the
+/// Vietnamese wording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Lexicon {
+    /// Before the exam code in the header and on the cover page
+    pub exam_code: String,
+    pub official_exam: String,
+    /// `{questions}` and `{pages}` (counted for each variant) are printed
+    /// in bold
+    pub page_count: String,
+    pub academic_year: String,
+    /// Before "subject, grade" in the header
+    pub subject: String,
+    /// `{minutes}`: duration of the exam
+    pub duration: String,
+    pub distribution_note: String,
+    /// Centered heading above the questions
+    pub exam_heading: String,
+    pub multiple_choice_part: String,
+    /// `{prefix}`: question prefix, `{points}`: points per question
+    pub instructions: String,
+    /// Answer line and teacher copy ("Đáp án: C")
+    pub answer: String,
+    /// Placeholder of an image that could not be extracted
+    pub missing_image: String,
+    /// Before the file name where a recording is embedded
+    pub audio: String,
+    /// Personalized copies: labels of the student line under the header
+    pub student_name: String,
+    pub student_id: String,
+    pub room: String,
+    /// Cover page: "Môn"
+    pub cover_subject: String,
+    /// Cover page: `{minutes}`: duration of the exam
+    pub cover_duration: String,
+    pub cover_instructions: String,
+    /// Cover page, before the materials allowed
+    pub materials_allowed: String,
+    pub no_materials: String,
+    /// Word of the "----------- HẾT -----------" line
+    pub end_marker: String,
+    /// Under each title of the signature block
+    pub signature_note: String,
+}
+
+impl Default for Lexicon {
+    fn default() -> Self {
+        Self {
+            exam_code: "Mã đề thi".to_string(),
+            official_exam: "ĐỀ CHÍNH THỨC".to_string(),
+            page_count: "(Đề gồm có {questions} câu / {pages} trang)".to_string(),
+            academic_year: "Năm học".to_string(),
+            subject: "Tên môn".to_string(),
+            duration: "Thời gian làm bài: {minutes}".to_string(),
+            distribution_note: "(Không kể thời gian phát đề)".to_string(),
+            exam_heading: "ĐỀ".to_string(),
+            multiple_choice_part: "I. PHẦN TRẮC NGHIỆM".to_string(),
+            instructions: "Chọn ý trả lời đúng nhất trong các câu sau bằng cách ghi vào bài làm (ví dụ: {prefix} 1A; {prefix} 2B...), mỗi câu chọn đúng được {points} điểm.".to_string(),
+            answer: "Đáp án".to_string(),
+            missing_image: "Hình bị thiếu".to_string(),
+            audio: "Bài nghe".to_string(),
+            student_name: "Họ và tên".to_string(),
+            student_id: "Số báo danh".to_string(),
+            room: "Phòng thi".to_string(),
+            cover_subject: "Môn".to_string(),
+            cover_duration: "Thời gian làm bài: {minutes} phút (không kể thời gian phát đề)".to_string(),
+            cover_instructions: "Hướng dẫn làm bài".to_string(),
+            materials_allowed: "Thí sinh được sử dụng".to_string(),
+            no_materials: "Thí sinh không được sử dụng tài liệu.".to_string(),
+            end_marker: "HẾT".to_string(),
+            signature_note: "(Ký, ghi rõ họ tên)".to_string(),
+        }
+    }
+}
+
+impl Lexicon {
+    pub fn english() -> Self {
+        Self {
+            exam_code: "Test code".to_string(),
+            official_exam: "OFFICIAL TEST".to_string(),
+            page_count: "({questions} questions, {pages} pages)".to_string(),
+            academic_year: "School year".to_string(),
+            subject: "Subject".to_string(),
+            duration: "Time allowed: {minutes} minutes".to_string(),
+            distribution_note: "(excluding time to hand out the paper)".to_string(),
+            exam_heading: "TEST".to_string(),
+            multiple_choice_part: "I. MULTIPLE CHOICE".to_string(),
+            instructions: "Choose the best answer to each question and write it on your answer sheet (e.g. {prefix} 1A; {prefix} 2B...). Each correct answer scores {points} points.".to_string(),
+            answer: "Answer".to_string(),
+            missing_image: "Missing image".to_string(),
+            audio: "Recording".to_string(),
+            student_name: "Name".to_string(),
+            student_id: "Candidate number".to_string(),
+            room: "Room".to_string(),
+            cover_subject: "Subject".to_string(),
+            cover_duration: "Time allowed: {minutes} minutes (excluding time to hand out the paper)".to_string(),
+            cover_instructions: "Instructions".to_string(),
+            materials_allowed: "Candidates may use".to_string(),
+            no_materials: "No materials are allowed.".to_string(),
+            end_marker: "END".to_string(),
+            signature_note: "(Signature and full name)".to_string(),
+        }
+    }
+
+    /// Preset wording of `lang`
+    pub fn preset(lang: Lang) -> Self {
+        match lang {
+            Lang::Vi => Self::default(),
+            Lang::En => Self::english(),
+        }
+    }
+}
+
+/// `template` with each `{name}` replaced by its value
+pub fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+/// `template` cut into literal text and filled placeholders (`true`), for
+/// wording whose values are printed differently
+pub fn fill_parts(template: &str, values: &[(&str, &str)]) -> Vec<(String, bool)> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some((start, name, value)) = values
+        .iter()
+        .filter_map(|(name, value)| rest.find(&format!("{{{}}}", name)).map(|start| (start, *name, *value)))
+        .min_by_key(|(start, ..)| *start)
+    {
+        if start > 0 {
+            parts.push((rest[..start].to_string(), false));
+        }
+        parts.push((value.to_string(), true));
+        rest = &rest[start + name.len() + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push((rest.to_string(), false));
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_wording_falls_back_to_vietnamese() {
+        let lexicon: Lexicon = serde_json::from_str(r#"{"examCode": "Code"}"#).unwrap();
+        assert_eq!(lexicon.exam_code, "Code");
+        assert_eq!(lexicon.answer, "Đáp án");
+
+        let english = Lexicon::preset(Lang::En);
+        assert_eq!(fill(&english.duration, &[("minutes", "45")]), "Time allowed: 45 minutes");
+        assert_eq!(fill(&english.page_count, &[("questions", "40"), ("pages", "02")]), "(40 questions, 02 pages)");
+
+        let parts = fill_parts(&Lexicon::default().page_count, &[("pages", "02"), ("questions", "40")]);
+        let bold: Vec<&str> = parts.iter().filter(|(_, value)| *value).map(|(text, _)| text.as_str()).collect();
+        assert_eq!(bold, ["40", "02"]);
+        assert_eq!(parts.iter().map(|(text, _)| text.as_str()).collect::<String>(), "(Đề gồm có 40 câu / 02 trang)");
+    }
+}
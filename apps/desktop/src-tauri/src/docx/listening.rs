@@ -0,0 +1,247 @@
+// src-tauri/src/docx/listening.rs
+//! Play order of the recordings of a listening section, per variant.
+//!
+//! A track cue ("Track 3", "Bài nghe 2", "Audio 1") in a part heading, a
+//! shared-data header ("Listen to the conversation and answer questions 3
+//! to 7. Track 2") or a question stem ties a recording to those questions.
+//! Since questions are shuffled, the proctor needs for each paper the
+//! tracks in the order its questions come and the question numbers of
+//! that paper. A recording embedded in the source (`Segment::Audio`, see
+//! `audio`) is a cue too, named by its file, which the export copies next to
+//! the variants (`copy_audio`).
+
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::diff::segments_text;
+use super::excel::MixedExam;
+use super::export_options::ExportOptions;
+use super::file_names::{self, FileNameContext};
+use super::model::Segment;
+
+/// A recording and the questions of the variant answered from it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackCue {
+    /// As written in the source, e.g. "Track 2"
+    pub track: String,
+    /// Display numbers, in order
+    pub questions: Vec<u32>,
+    /// Text of the paragraph naming the track
+    pub instruction: String,
+}
+
+/// Track named in `text`
+pub fn track_name(text: &str) -> Option<String> {
+    let re = Regex::new(r"(?i)\b(?:track|bài nghe|audio)\s*(?:số\s*)?\d+").unwrap();
+    re.find(text).map(|found| found.as_str().to_string())
+}
+
+fn audio_file(segments: &[Segment]) -> Option<&str> {
+    segments.iter().find_map(|segment| match segment {
+        Segment::Audio { file_name, .. } => Some(file_name.as_str()),
+        _ => None,
+    })
+}
+
+/// Track and instruction of the first paragraphs naming one; an embedded
+/// recording wins over a track written in the text
+fn cue_of(paragraphs: &[&[Segment]]) -> Option<(String, String)> {
+    if let Some(file_name) = paragraphs.iter().find_map(|paragraph| audio_file(paragraph)) {
+        let instruction = paragraphs.first().map(|p| segments_text(p)).unwrap_or_default();
+        return Some((file_name.to_string(), instruction.trim().to_string()));
+    }
+    paragraphs.iter().find_map(|paragraph| {
+        let text = segments_text(paragraph);
+        track_name(&text).map(|track| (track, text.trim().to_string()))
+    })
+}
+
+/// Tracks of `exam` in the order its questions come. A part or group with a
+/// cue covers all its questions; consecutive questions naming the same
+/// track share one cue.
+pub fn track_cues(exam: &MixedExam) -> Vec<TrackCue> {
+    let mut numbers: Vec<u32> = exam.questions.iter().map(|q| q.display_number as u32).collect();
+    numbers.sort_unstable();
+    let mut cues: Vec<TrackCue> = Vec::new();
+    let covered = |cues: &[TrackCue], number: u32| cues.iter().any(|cue| cue.questions.contains(&number));
+
+    for number in numbers {
+        let part = exam
+            .parts
+            .iter()
+            .filter(|part| part.questions.first() == Some(&number))
+            .find_map(|part| cue_of(&[&part.heading]).map(|cue| (cue, &part.questions)));
+        let group = exam
+            .groups
+            .iter()
+            .filter(|group| group.questions.first() == Some(&number))
+            .find_map(|group| {
+                let paragraphs: Vec<&[Segment]> =
+                    std::iter::once(group.header.as_slice()).chain(group.paragraphs.iter().map(Vec::as_slice)).collect();
+                cue_of(&paragraphs).map(|cue| (cue, &group.questions))
+            });
+        if let Some(((track, instruction), questions)) = part.or(group) {
+            let questions: Vec<u32> = questions.iter().copied().filter(|n| !covered(&cues, *n)).collect();
+            if !questions.is_empty() {
+                cues.push(TrackCue { track, questions, instruction });
+            }
+            continue;
+        }
+        if covered(&cues, number) {
+            continue;
+        }
+
+        let Some(question) = exam.questions.iter().find(|q| q.display_number as u32 == number) else {
+            continue;
+        };
+        let Some((track, instruction)) = cue_of(&[&question.stem]) else {
+            continue;
+        };
+        match cues.last_mut() {
+            Some(last) if last.track == track && last.questions.last() == Some(&(number - 1)) => {
+                last.questions.push(number)
+            }
+            _ => cues.push(TrackCue {
+                track,
+                questions: vec![number],
+                instruction,
+            }),
+        }
+    }
+    cues
+}
+
+/// "3–7, 9" for questions 3 to 7 and 9
+fn number_ranges(numbers: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &number in numbers {
+        match ranges.last_mut() {
+            Some((_, last)) if *last + 1 == number => *last = number,
+            _ => ranges.push((number, number)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(first, last)| if first == last { first.to_string() } else { format!("{}–{}", first, last) })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Proctor script of one variant: the tracks to play, in order, with the
+/// questions of this paper they answer
+pub fn render_script(exam: &MixedExam, cues: &[TrackCue], options: &ExportOptions) -> String {
+    let mut script = format!("Đề {} - Thứ tự phát bài nghe\n", exam.exam_code);
+    for (index, cue) in cues.iter().enumerate() {
+        script.push_str(&format!(
+            "\n{}. {}: {} {}\n   {}\n",
+            index + 1,
+            cue.track,
+            options.question_prefix,
+            number_ranges(&cue.questions),
+            cue.instruction
+        ));
+    }
+    script
+}
+
+/// Copy into `output_dir` the recordings embedded in `exams`, each once,
+/// and return their file names (none when nothing is embedded).
+pub fn copy_audio(exams: &[MixedExam], output_dir: &Path) -> Result<Vec<String>, String> {
+    let mut files: Vec<String> = Vec::new();
+    for exam in exams {
+        let segments = exam
+            .questions
+            .iter()
+            .flat_map(|q| {
+                q.stem.iter().chain(&q.stem_alt).chain(q.options.iter().flat_map(|o| o.content.iter().chain(&o.content_alt)))
+            })
+            .chain(exam.groups.iter().flat_map(|g| g.header.iter().chain(g.paragraphs.iter().flatten())))
+            .chain(exam.parts.iter().flat_map(|p| p.heading.iter()));
+        for segment in segments {
+            let Segment::Audio { asset_path, file_name, .. } = segment else {
+                continue;
+            };
+            if files.contains(file_name) {
+                continue;
+            }
+            std::fs::copy(asset_path, output_dir.join(file_name)).map_err(|e| format!("{}: {}", file_name, e))?;
+            files.push(file_name.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// Write the proctor script of every variant with track cues to
+/// `<variant file name>_BaiNghe.txt` in `output_dir` and return the file
+/// names (none when the exam names no track).
+pub fn write_scripts(exams: &[MixedExam], output_dir: &Path, options: &ExportOptions) -> Result<Vec<String>, String> {
+    let context = FileNameContext::new(&options.subject, &options.grade);
+    let mut files = Vec::new();
+    for (index, exam) in exams.iter().enumerate() {
+        let cues = track_cues(exam);
+        if cues.is_empty() {
+            continue;
+        }
+        let stem = file_names::variant_stem(
+            &options.file_name_template,
+            &context.for_variant(&exam.exam_code, index + 1),
+        );
+        let file_name = format!("{}_BaiNghe.txt", stem);
+        let script = render_script(exam, &cues, options);
+        crate::storage::fs::write_bytes(&output_dir.join(&file_name), script.as_bytes())?;
+        files.push(file_name);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::excel::MixedQuestion;
+    use crate::docx::groups::MixedGroup;
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    fn question(display_number: usize, stem: &str) -> MixedQuestion {
+        MixedQuestion {
+            display_number,
+            stem: text(stem),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tracks_follow_the_shuffled_questions() {
+        let exam = MixedExam {
+            exam_code: "101".to_string(),
+            questions: vec![
+                question(1, "(Track 3) What time does the shop open?"),
+                question(2, "(Track 3) Where is the shop?"),
+                question(3, "Who is speaking?"),
+                question(4, "What does the man want?"),
+                question(5, "Choose the word with a different stress."),
+            ],
+            groups: vec![MixedGroup {
+                header: text("Listen to the conversation and answer questions 3 and 4. Track 1"),
+                paragraphs: Vec::new(),
+                questions: vec![3, 4],
+            }],
+            parts: Vec::new(),
+        };
+        let cues = track_cues(&exam);
+        let summary: Vec<(&str, Vec<u32>)> = cues.iter().map(|c| (c.track.as_str(), c.questions.clone())).collect();
+        assert_eq!(summary, [("Track 3", vec![1, 2]), ("Track 1", vec![3, 4])]);
+
+        let script = render_script(&exam, &cues, &ExportOptions::default());
+        assert!(script.contains("1. Track 3: Câu 1–2"), "{}", script);
+        assert!(script.contains("2. Track 1: Câu 3–4"), "{}", script);
+        assert_eq!(track_name("Nghe bài nghe số 2 và chọn"), Some("bài nghe số 2".to_string()));
+    }
+}
@@ -0,0 +1,100 @@
+// src-tauri/src/docx/manual_answers.rs
+//! Correct answers chosen by hand for questions whose mark is missing
+//! (E020). The choices live in the job's `ParseOptions::manual_answers` and
+//! are used by the analysis only once `continue_with_manual_answers` is on.
+
+use super::model::Question;
+use super::parser::ParseOptions;
+use super::validator::{self, ValidationError};
+
+/// Record (or, with `None`, remove) the label chosen for `question_number`
+/// and turn manual answers on. Returns the label as stored: trimmed,
+/// uppercase, without the lock marker.
+pub fn choose(options: &mut ParseOptions, question_number: u32, label: Option<&str>) -> Option<String> {
+    let label = label.map(|label| label.trim().trim_start_matches('#').to_uppercase());
+    match &label {
+        Some(label) => {
+            options.manual_answers.insert(question_number, label.clone());
+        }
+        None => {
+            options.manual_answers.remove(&question_number);
+        }
+    }
+    options.continue_with_manual_answers = true;
+    label
+}
+
+/// Use the label chosen for `question`, whose mark is missing. `None` when
+/// manual answers are off or none was chosen (the E020 stands); otherwise
+/// the label now set as the correct answer, or E025 when the question has
+/// no such option (the correct answer is left unset).
+pub fn apply(question: &mut Question, options: &ParseOptions) -> Option<Result<String, ValidationError>> {
+    if !options.continue_with_manual_answers {
+        return None;
+    }
+    let label = options.manual_answers.get(&question.number)?;
+    Some(validator::validate_manual_answer(question, label).map(|()| {
+        question.correct_label = label.clone();
+        label.clone()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::OptionItem;
+    use crate::docx::validator::ValidationErrorCode;
+
+    fn question(number: u32) -> Question {
+        Question {
+            number,
+            options: ["A", "B", "C", "D"]
+                .iter()
+                .map(|label| OptionItem {
+                    label: label.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn chosen_labels_are_set_and_cleared() {
+        let mut options = ParseOptions::default();
+        let mut q = question(3);
+        assert!(apply(&mut q, &options).is_none());
+
+        assert_eq!(choose(&mut options, 3, Some(" #c ")).as_deref(), Some("C"));
+        assert!(options.continue_with_manual_answers);
+        assert_eq!(apply(&mut q, &options).unwrap().unwrap(), "C");
+        assert_eq!(q.correct_label, "C");
+
+        // Another question keeps its missing mark
+        assert!(apply(&mut question(4), &options).is_none());
+
+        assert_eq!(choose(&mut options, 3, None), None);
+        assert!(options.manual_answers.is_empty());
+        let mut q = question(3);
+        assert!(apply(&mut q, &options).is_none());
+        assert!(q.correct_label.is_empty());
+    }
+
+    #[test]
+    fn labels_outside_the_options_are_rejected() {
+        let mut options = ParseOptions::default();
+        choose(&mut options, 3, Some("E"));
+        let mut q = question(3);
+        let err = apply(&mut q, &options).unwrap().unwrap_err();
+        assert!(matches!(err.code, ValidationErrorCode::E025ManualAnswerInvalid));
+        assert_eq!(err.question_number, 3);
+        assert!(q.correct_label.is_empty());
+    }
+
+    #[test]
+    fn choices_wait_until_manual_answers_are_on() {
+        let mut options = ParseOptions::default();
+        options.manual_answers.insert(3, "B".to_string());
+        assert!(apply(&mut question(3), &options).is_none());
+    }
+}
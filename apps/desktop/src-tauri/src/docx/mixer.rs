@@ -1,14 +1,29 @@
 // src-tauri/src/docx/mixer.rs
 //! Exam mixing logic - shuffles questions and options to create exam variants
 //! Ported from TypeScript for better performance with large documents
+//!
+//! Seeding scheme: a single 64-bit master seed (stored in the job manifest)
+//! is expanded with a SplitMix64-based PRF using a distinct domain tag per use:
+//! - exam codes:      `derive_seed(master, DOMAIN_EXAM_CODES, 0)`
+//! - variant `i`:     `derive_seed(master, DOMAIN_VARIANT, i)`
+//! - question order:  `derive_seed(variant_seed, DOMAIN_QUESTION_ORDER, 0)`
+//! - options of Q`n`: `derive_seed(variant_seed, DOMAIN_OPTIONS, n)` (original number)
+//! - statements of Q`n`: `derive_seed(variant_seed, DOMAIN_STATEMENTS, n)`
+//!
+//! Each derived value seeds its own `StdRng`, so shuffles are independent
+//! across variants and questions yet fully reproducible from the master seed.
 
 use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
-use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
 
-use super::model::{OptionItem, Question, Segment};
+use super::exam_parts::{self, MixedPart};
+use super::groups::{self, MixedGroup};
+use super::{captions, crossref, statements};
+use super::labels::option_label;
+use super::model::{ExamPart, OptionItem, Question, QuestionGroup, Segment};
 
 /// A mixed exam variant with unique exam code
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +31,15 @@ pub struct MixedExam {
     #[serde(rename = "examCode")]
     pub exam_code: String,
     pub questions: Vec<MixedQuestion>,
+    /// Per-variant seed derived from the master seed (see module docs)
+    #[serde(default, with = "seed_string")]
+    pub seed: u64,
+    /// Shared-data headers, with this variant's question numbers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<MixedGroup>,
+    /// Part headings, with this variant's question numbers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<MixedPart>,
 }
 
 /// A question in a mixed exam (after shuffling)
@@ -29,6 +53,19 @@ pub struct MixedQuestion {
     pub options: Vec<MixedOption>,
     #[serde(rename = "correctAnswer")]
     pub correct_answer: String,
+    #[serde(rename = "stemAlt", default, skip_serializing_if = "Vec::is_empty")]
+    pub stem_alt: Vec<Segment>,
+}
+
+impl MixedQuestion {
+    /// Every segment of the question: stem, then options (both languages)
+    pub fn segments_mut(&mut self) -> impl Iterator<Item = &mut Segment> {
+        self.stem.iter_mut().chain(self.stem_alt.iter_mut()).chain(
+            self.options
+                .iter_mut()
+                .flat_map(|o| o.content.iter_mut().chain(o.content_alt.iter_mut())),
+        )
+    }
 }
 
 /// An option after shuffling
@@ -38,6 +75,165 @@ pub struct MixedOption {
     #[serde(rename = "originalLabel")]
     pub original_label: String,
     pub content: Vec<Segment>,
+    #[serde(rename = "contentAlt", default, skip_serializing_if = "Vec::is_empty")]
+    pub content_alt: Vec<Segment>,
+}
+
+/// Machine-readable permutation of one variant, persisted as
+/// `<workspace>/variants/<code>/mapping.json` for external audit tooling
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantMapping {
+    #[serde(rename = "examCode")]
+    pub exam_code: String,
+    #[serde(with = "seed_string")]
+    pub seed: u64,
+    pub questions: Vec<QuestionMapping>,
+}
+
+/// Position of one original question inside a variant, with its option permutation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionMapping {
+    #[serde(rename = "displayNumber")]
+    pub display_number: u32,
+    #[serde(rename = "originalNumber")]
+    pub original_number: u32,
+    /// Options in display order (new label → original label)
+    pub options: Vec<OptionMapping>,
+    #[serde(rename = "correctAnswer")]
+    pub correct_answer: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptionMapping {
+    pub label: String,
+    #[serde(rename = "originalLabel")]
+    pub original_label: String,
+}
+
+/// Build the permutation mapping of a mixed variant
+pub fn build_variant_mapping(exam: &MixedExam) -> VariantMapping {
+    VariantMapping {
+        exam_code: exam.exam_code.clone(),
+        seed: exam.seed,
+        questions: exam
+            .questions
+            .iter()
+            .map(|q| QuestionMapping {
+                display_number: q.display_number,
+                original_number: q.original_number,
+                options: q
+                    .options
+                    .iter()
+                    .map(|opt| OptionMapping {
+                        label: opt.label.clone(),
+                        original_label: opt.original_label.clone(),
+                    })
+                    .collect(),
+                correct_answer: q.correct_answer.clone(),
+            })
+            .collect(),
+    }
+}
+
+/// Compact summary of one variant for a dry-run preview: where each
+/// question went and its new answer, without the content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VariantPreview {
+    #[serde(rename = "examCode")]
+    pub exam_code: String,
+    /// Original question number of each display position ("Câu 1" first)
+    #[serde(rename = "originalNumbers")]
+    pub original_numbers: Vec<u32>,
+    /// Correct label of each display position
+    pub answers: Vec<String>,
+}
+
+pub fn preview_variant(exam: &MixedExam) -> VariantPreview {
+    VariantPreview {
+        exam_code: exam.exam_code.clone(),
+        original_numbers: exam.questions.iter().map(|q| q.original_number).collect(),
+        answers: exam.questions.iter().map(|q| q.correct_answer.clone()).collect(),
+    }
+}
+
+const DOMAIN_EXAM_CODES: u64 = 0x4558_414D_434F_4445; // "EXAMCODE"
+const DOMAIN_VARIANT: u64 = 0x5641_5249_414E_5400; // "VARIANT"
+const DOMAIN_QUESTION_ORDER: u64 = 0x5155_4553_4F52_4452; // "QUESORDR"
+const DOMAIN_OPTIONS: u64 = 0x4F50_5449_4F4E_5300; // "OPTIONS"
+const DOMAIN_STATEMENTS: u64 = 0x5354_4154_454D_4E54; // "STATEMNT"
+
+/// SplitMix64 finalizer - a bijective 64-bit mixing function
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// A seed as it is serialized: a decimal string, since JavaScript numbers
+/// lose precision above 2^53 and a seed read back from the UI would not
+/// reproduce the mix. Numbers are still read, as written by earlier versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Seed(pub u64);
+
+impl Serialize for Seed {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Seed {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Number(u64),
+            Text(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(seed) => Ok(Seed(seed)),
+            Repr::Text(text) => text.trim().parse().map(Seed).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// `#[serde(with = "seed_string")]` for `u64` seed fields (see [`Seed`])
+pub mod seed_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        Seed(*seed).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        Seed::deserialize(deserializer).map(|seed| seed.0)
+    }
+}
+
+/// `#[serde(with = "optional_seed_string")]` for `Option<u64>` seed fields
+pub mod optional_seed_string {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(seed: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        seed.map(Seed).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+        Option::<Seed>::deserialize(deserializer).map(|seed| seed.map(|seed| seed.0))
+    }
+}
+
+/// Derive an independent child seed from `parent` for the given domain and index
+pub fn derive_seed(parent: u64, domain: u64, index: u64) -> u64 {
+    splitmix64(splitmix64(parent ^ domain) ^ index)
+}
+
+/// Generate a fresh random master seed for a new mix
+///
+/// Limited to 53 bits so the value survives a round-trip through JavaScript numbers.
+pub fn random_master_seed() -> u64 {
+    use rand::Rng;
+    rand::thread_rng().gen::<u64>() >> 11
 }
 
 /// Generate a random 3-digit exam code (100-999)
@@ -46,39 +242,56 @@ fn generate_exam_code(rng: &mut StdRng) -> String {
     rng.gen_range(100..=999).to_string()
 }
 
-/// Generate unique exam codes
-fn generate_exam_codes(count: usize) -> Vec<String> {
-    let mut codes = HashSet::new();
-    let mut rng = StdRng::from_entropy();
+/// Generate unique exam codes (reproducible from the master seed)
+fn generate_exam_codes(count: usize, master_seed: u64) -> Vec<String> {
+    let mut codes = Vec::new();
+    let mut rng = StdRng::seed_from_u64(derive_seed(master_seed, DOMAIN_EXAM_CODES, 0));
 
     while codes.len() < count {
-        codes.insert(generate_exam_code(&mut rng));
+        let code = generate_exam_code(&mut rng);
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
     }
 
-    codes.into_iter().collect()
+    codes
 }
 
 /// Shuffle options within a question and return mapping of old → new labels
+///
+/// Options flagged as `locked` (e.g. "D. Cả ba ý trên") keep their original
+/// slot; only the remaining options are shuffled among the free slots.
 fn shuffle_options(
     options: &[OptionItem],
     rng: &mut StdRng,
 ) -> (Vec<MixedOption>, HashMap<String, String>) {
-    let labels = ["A", "B", "C", "D", "E", "F"];
+    let free_slots: Vec<usize> = options
+        .iter()
+        .enumerate()
+        .filter(|(_, opt)| !opt.locked)
+        .map(|(idx, _)| idx)
+        .collect();
+    let mut movable: Vec<OptionItem> = free_slots.iter().map(|&idx| options[idx].clone()).collect();
+    movable.shuffle(rng);
+
     let mut shuffled = options.to_vec();
-    shuffled.shuffle(rng);
+    for (slot, opt) in free_slots.into_iter().zip(movable) {
+        shuffled[slot] = opt;
+    }
 
     let mut mapping = HashMap::new();
     let mixed_options: Vec<MixedOption> = shuffled
         .iter()
         .enumerate()
         .map(|(idx, opt)| {
-            let new_label = labels[idx].to_string();
+            let new_label = option_label(idx);
             mapping.insert(opt.label.clone(), new_label.clone());
 
             MixedOption {
                 label: new_label,
                 original_label: opt.label.clone(),
                 content: opt.content.clone(),
+                content_alt: opt.content_alt.clone(),
             }
         })
         .collect();
@@ -90,70 +303,172 @@ fn shuffle_options(
 ///
 /// # Arguments
 /// * `questions` - Original parsed questions
+/// * `question_groups` - Shared-data groups; their questions stay adjacent and in order
+/// * `parts` - Exam parts; their questions stay within the part, parts in order
 /// * `num_variants` - Number of exam variants to generate
 /// * `custom_exam_codes` - Optional custom exam codes to use instead of random generation
+/// * `master_seed` - Master seed every variant/question seed is derived from
+/// * `shuffle_statements` - Also shuffle (I), (II), … statements inside stems,
+///   except where the options name them (the answer is not recomputed)
 ///
 /// # Returns
 /// Vector of MixedExam with shuffled questions and options
 pub fn mix_exams(
     questions: Vec<Question>,
+    question_groups: &[QuestionGroup],
+    parts: &[ExamPart],
     num_variants: usize,
     custom_exam_codes: Option<Vec<String>>,
+    master_seed: u64,
+    shuffle_statements: bool,
 ) -> Vec<MixedExam> {
-    let mut variants = Vec::new();
-    let exam_codes = custom_exam_codes.unwrap_or_else(|| generate_exam_codes(num_variants));
+    let exam_codes =
+        custom_exam_codes.unwrap_or_else(|| generate_exam_codes(num_variants, master_seed));
 
-    for (variant_idx, exam_code) in exam_codes.iter().enumerate() {
-        // Use different seed for each variant
-        let seed = (variant_idx as u64).wrapping_mul(1000);
-        let mut rng = StdRng::seed_from_u64(seed);
+    exam_codes
+        .iter()
+        .enumerate()
+        .map(|(variant_idx, exam_code)| {
+            let seed = derive_seed(master_seed, DOMAIN_VARIANT, variant_idx as u64);
+            mix_variant(&questions, question_groups, parts, exam_code, seed, shuffle_statements)
+        })
+        .collect()
+}
 
-        // 1. Shuffle question order
-        let mut shuffled_questions = questions.clone();
-        shuffled_questions.shuffle(&mut rng);
+/// Mix one variant from its own seed (see [`mix_exams`] for the arguments).
+pub fn mix_variant(
+    questions: &[Question],
+    question_groups: &[QuestionGroup],
+    parts: &[ExamPart],
+    exam_code: &str,
+    seed: u64,
+    shuffle_statements: bool,
+) -> MixedExam {
+    let mut rng = StdRng::seed_from_u64(derive_seed(seed, DOMAIN_QUESTION_ORDER, 0));
 
-        // 2. Process each question
-        let mixed_questions: Vec<MixedQuestion> = shuffled_questions
-            .iter()
-            .enumerate()
-            .map(|(idx, q)| {
-                // Shuffle options with different seed for each question
-                let question_seed = seed.wrapping_add(idx as u64);
-                let mut question_rng = StdRng::seed_from_u64(question_seed);
-                let (shuffled_options, mapping) = shuffle_options(&q.options, &mut question_rng);
-
-                // Find new correct answer label
-                let new_correct_label = mapping
-                    .get(&q.correct_label)
-                    .cloned()
-                    .unwrap_or_else(|| q.correct_label.clone());
-
-                MixedQuestion {
-                    original_number: q.number,
-                    display_number: (idx + 1) as u32,
-                    stem: q.stem.clone(),
-                    options: shuffled_options,
-                    correct_answer: new_correct_label,
-                }
-            })
-            .collect();
+    // 1. Shuffle question order (a shared-data group moves as one block,
+    //    within its part)
+    let units = groups::units(questions.to_vec(), question_groups);
+    let shuffled_questions = exam_parts::shuffle_units(units, parts, &mut rng);
+
+    arrange_variant(shuffled_questions, question_groups, parts, exam_code, seed, shuffle_statements)
+}
 
-        variants.push(MixedExam {
-            exam_code: exam_code.clone(),
-            questions: mixed_questions,
-        });
+/// Swap the questions at display positions `a` and `b` (1-based) of a
+/// variant's order (original numbers). Questions of a shared-data group
+/// cannot be moved on their own, nor questions out of their part. Errors are
+/// catalog codes.
+pub fn swap_positions(
+    order: &mut [u32],
+    a: u32,
+    b: u32,
+    question_groups: &[QuestionGroup],
+    parts: &[ExamPart],
+) -> Result<(), &'static str> {
+    let position = |n: u32| (1..=order.len() as u32).contains(&n).then(|| n as usize - 1);
+    let (Some(i), Some(j)) = (position(a), position(b)) else {
+        return Err("E114_QUESTION_POSITION_INVALID");
+    };
+    let grouped = |number: u32| question_groups.iter().any(|g| g.questions.contains(&number));
+    if i != j && (grouped(order[i]) || grouped(order[j])) {
+        return Err("E115_SWAP_IN_GROUP");
+    }
+    if exam_parts::part_of(parts, order[i]) != exam_parts::part_of(parts, order[j]) {
+        return Err("E118_SWAP_ACROSS_PARTS");
     }
+    order.swap(i, j);
+    Ok(())
+}
+
+/// `questions` in the order of `order` (original numbers); numbers without
+/// a question are skipped.
+pub fn order_questions(questions: &[Question], order: &[u32]) -> Vec<Question> {
+    order
+        .iter()
+        .filter_map(|number| questions.iter().find(|q| q.number == *number).cloned())
+        .collect()
+}
+
+/// Build a variant with its questions in the given order. Options and
+/// statements are shuffled from `seed` and the original number only, so a
+/// question keeps its option order (and answer) wherever it is placed.
+pub fn arrange_variant(
+    ordered_questions: Vec<Question>,
+    question_groups: &[QuestionGroup],
+    parts: &[ExamPart],
+    exam_code: &str,
+    seed: u64,
+    shuffle_statements: bool,
+) -> MixedExam {
+    // 2. Process each question
+    let mut mixed_questions: Vec<MixedQuestion> = ordered_questions
+        .iter()
+        .enumerate()
+        .map(|(idx, q)| {
+            // Option seed is keyed by the original number, independent of question order
+            let question_seed = derive_seed(seed, DOMAIN_OPTIONS, q.number as u64);
+            let mut question_rng = StdRng::seed_from_u64(question_seed);
+            let (shuffled_options, mapping) = shuffle_options(&q.options, &mut question_rng);
+
+            // Find new correct answer label
+            let new_correct_label = mapping
+                .get(&q.correct_label)
+                .cloned()
+                .unwrap_or_else(|| q.correct_label.clone());
+
+            // Statements only move when the stem has no second language
+            // to keep in step and the answer cannot depend on their order
+            let stem = if shuffle_statements
+                && !q.statements.is_empty()
+                && q.stem_alt.is_empty()
+                && !statements::referenced_by_options(&q.options)
+            {
+                let mut statement_rng =
+                    StdRng::seed_from_u64(derive_seed(seed, DOMAIN_STATEMENTS, q.number as u64));
+                statements::shuffle(&q.stem, &q.statements, &mut statement_rng)
+            } else {
+                q.stem.clone()
+            };
+
+            MixedQuestion {
+                original_number: q.number,
+                display_number: (idx + 1) as u32,
+                stem,
+                options: shuffled_options,
+                correct_answer: new_correct_label,
+                stem_alt: q.stem_alt.clone(),
+            }
+        })
+        .collect();
+
+    // 3. Figures/tables are numbered in reading order of this variant
+    captions::renumber(&mut mixed_questions);
+
+    // 4. "dùng dữ kiện ở câu 12" follows question 12 to its new number
+    crossref::rewrite(&mut mixed_questions);
+
+    let display: HashMap<u32, u32> = mixed_questions
+        .iter()
+        .map(|q| (q.original_number, q.display_number))
+        .collect();
 
-    variants
+    MixedExam {
+        exam_code: exam_code.to_string(),
+        questions: mixed_questions,
+        seed,
+        groups: groups::mix_groups(question_groups, &display),
+        parts: exam_parts::mix_parts(parts, &display),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
 
     #[test]
     fn test_generate_exam_codes() {
-        let codes = generate_exam_codes(4);
+        let codes = generate_exam_codes(4, 7);
         assert_eq!(codes.len(), 4);
         
         // All codes should be unique
@@ -163,7 +478,7 @@ mod tests {
         // All codes should be 3 digits
         for code in &codes {
             let num: u32 = code.parse().unwrap();
-            assert!(num >= 100 && num <= 999);
+            assert!((100..=999).contains(&num));
         }
     }
 
@@ -184,6 +499,7 @@ mod tests {
                             text: "Option A".to_string(),
                             raw_xml: String::new(),
                         }],
+                        ..Default::default()
                     },
                     OptionItem {
                         label: "B".to_string(),
@@ -192,13 +508,15 @@ mod tests {
                             text: "Option B".to_string(),
                             raw_xml: String::new(),
                         }],
+                        ..Default::default()
                     },
                 ],
                 correct_label: "A".to_string(),
+                ..Default::default()
             },
         ];
 
-        let variants = mix_exams(questions, 3, None);
+        let variants = mix_exams(questions, &[], &[], 3, None, 42, false);
         assert_eq!(variants.len(), 3);
         
         // Each variant should have questions
@@ -208,6 +526,108 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_variant_mapping_matches_mixed_exam() {
+        let questions = vec![Question {
+            number: 1,
+            stem: Vec::new(),
+            options: ["A", "B", "C", "D"]
+                .iter()
+                .map(|label| OptionItem {
+                    label: label.to_string(),
+                    locked: false,
+                    content: Vec::new(),
+                    ..Default::default()
+                })
+                .collect(),
+            correct_label: "C".to_string(),
+            ..Default::default()
+        }];
+
+        let variants = mix_exams(questions, &[], &[], 2, Some(vec!["101".into(), "102".into()]), 42, false);
+        for variant in &variants {
+            let mapping = build_variant_mapping(variant);
+            assert_eq!(mapping.exam_code, variant.exam_code);
+            assert_eq!(mapping.seed, variant.seed);
+
+            // Following the permutation from the original answer lands on the variant's answer
+            let q = &mapping.questions[0];
+            let moved = q.options.iter().find(|o| o.original_label == "C").unwrap();
+            assert_eq!(moved.label, q.correct_answer);
+        }
+    }
+
+    #[test]
+    fn test_preview_follows_display_order() {
+        let question = |number: u32, correct: &str| Question {
+            number,
+            options: ["A", "B", "C", "D"]
+                .iter()
+                .map(|label| OptionItem {
+                    label: label.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            correct_label: correct.to_string(),
+            ..Default::default()
+        };
+        let questions = vec![question(1, "A"), question(2, "B"), question(3, "D")];
+
+        for variant in mix_exams(questions, &[], &[], 2, None, 7, false) {
+            let preview = preview_variant(&variant);
+            assert_eq!(preview.exam_code, variant.exam_code);
+            let mut numbers = preview.original_numbers.clone();
+            numbers.sort();
+            assert_eq!(numbers, vec![1, 2, 3]);
+            for (i, q) in variant.questions.iter().enumerate() {
+                assert_eq!(preview.original_numbers[i], q.original_number);
+                assert_eq!(preview.answers[i], q.correct_answer);
+            }
+        }
+    }
+
+    #[test]
+    fn test_swap_keeps_each_question_answer() {
+        let question = |number: u32, correct: &str| Question {
+            number,
+            options: ["A", "B", "C", "D"]
+                .iter()
+                .map(|label| OptionItem {
+                    label: label.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            correct_label: correct.to_string(),
+            ..Default::default()
+        };
+        let questions = vec![question(1, "A"), question(2, "B"), question(3, "C"), question(4, "D")];
+        let group = QuestionGroup {
+            questions: vec![3, 4],
+            ..Default::default()
+        };
+        let groups = vec![group];
+
+        let variant = mix_variant(&questions, &groups, &[], "101", 9, false);
+        let answer_of = |exam: &MixedExam, number: u32| {
+            exam.questions.iter().find(|q| q.original_number == number).unwrap().correct_answer.clone()
+        };
+        let mut order: Vec<u32> = variant.questions.iter().map(|q| q.original_number).collect();
+        let a = order.iter().position(|n| *n == 1).unwrap() as u32 + 1;
+        let b = order.iter().position(|n| *n == 2).unwrap() as u32 + 1;
+        let c = order.iter().position(|n| *n == 3).unwrap() as u32 + 1;
+
+        assert_eq!(swap_positions(&mut order.clone(), a, c, &groups, &[]), Err("E115_SWAP_IN_GROUP"));
+        assert_eq!(swap_positions(&mut order.clone(), a, 5, &groups, &[]), Err("E114_QUESTION_POSITION_INVALID"));
+        swap_positions(&mut order, a, b, &groups, &[]).unwrap();
+
+        let swapped = arrange_variant(order_questions(&questions, &order), &groups, &[], "101", variant.seed, false);
+        assert_eq!(swapped.questions[a as usize - 1].original_number, 2);
+        assert_eq!(swapped.questions[b as usize - 1].original_number, 1);
+        for number in 1..=4 {
+            assert_eq!(answer_of(&swapped, number), answer_of(&variant, number));
+        }
+    }
+
     #[test]
     fn test_shuffle_options_preserves_content() {
         let options = vec![
@@ -218,6 +638,7 @@ mod tests {
                     text: "Option A".to_string(),
                     raw_xml: String::new(),
                 }],
+                ..Default::default()
             },
             OptionItem {
                 label: "B".to_string(),
@@ -226,6 +647,7 @@ mod tests {
                     text: "Option B".to_string(),
                     raw_xml: String::new(),
                 }],
+                ..Default::default()
             },
         ];
 
@@ -244,4 +666,108 @@ mod tests {
         assert!(new_labels.contains("A"));
         assert!(new_labels.contains("B"));
     }
+
+    #[test]
+    fn test_mix_exams_reproducible_and_independent() {
+        let questions: Vec<Question> = (1..=20)
+            .map(|number| Question {
+                number,
+                stem: Vec::new(),
+                options: ["A", "B", "C", "D"]
+                    .iter()
+                    .map(|label| OptionItem {
+                        label: label.to_string(),
+                        locked: false,
+                        content: Vec::new(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                correct_label: "A".to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        let order = |exam: &MixedExam| -> Vec<u32> {
+            exam.questions.iter().map(|q| q.original_number).collect()
+        };
+
+        // Same master seed → identical codes and permutations
+        let first = mix_exams(questions.clone(), &[], &[], 4, None, 2024, false);
+        let second = mix_exams(questions.clone(), &[], &[], 4, None, 2024, false);
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(a.exam_code, b.exam_code);
+            assert_eq!(order(a), order(b));
+        }
+
+        // Variants of one mix should not share the same question order
+        let orders: HashSet<Vec<u32>> = first.iter().map(order).collect();
+        assert_eq!(orders.len(), first.len());
+    }
+
+    #[test]
+    fn test_shuffle_options_keeps_locked_slots() {
+        let options: Vec<OptionItem> = ["A", "B", "C", "D"]
+            .iter()
+            .map(|label| OptionItem {
+                label: label.to_string(),
+                locked: *label == "D",
+                content: Vec::new(),
+                ..Default::default()
+            })
+            .collect();
+
+        for seed in 0..20 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (shuffled, mapping) = shuffle_options(&options, &mut rng);
+
+            // "D. Cả ba ý trên" must stay in the last slot
+            assert_eq!(shuffled[3].original_label, "D");
+            assert_eq!(mapping["D"], "D");
+        }
+    }
+
+    #[test]
+    fn test_locked_option_stays_while_the_correct_answer_moves() {
+        let questions = vec![Question {
+            number: 1,
+            options: ["A", "B", "C", "D"]
+                .iter()
+                .map(|label| OptionItem {
+                    label: label.to_string(),
+                    locked: *label == "D",
+                    ..Default::default()
+                })
+                .collect(),
+            correct_label: "A".to_string(),
+            ..Default::default()
+        }];
+
+        let variants = mix_exams(questions, &[], &[], 12, None, 42, false);
+        let answers: HashSet<&str> = variants.iter().map(|v| v.questions[0].correct_answer.as_str()).collect();
+        assert!(answers.len() > 1, "correct answer never moved: {:?}", answers);
+        assert!(!answers.contains("D"));
+        for variant in &variants {
+            assert_eq!(variant.questions[0].options[3].original_label, "D");
+        }
+    }
+
+    #[test]
+    fn test_seeds_round_trip_as_strings() {
+        let mapping = VariantMapping {
+            exam_code: "101".to_string(),
+            seed: u64::MAX,
+            questions: Vec::new(),
+        };
+        let json = serde_json::to_value(&mapping).unwrap();
+        assert_eq!(json["seed"], serde_json::json!("18446744073709551615"));
+        let back: VariantMapping = serde_json::from_value(json).unwrap();
+        assert_eq!(back.seed, u64::MAX);
+
+        // Files written before seeds were strings still load
+        let old: VariantMapping = serde_json::from_value(serde_json::json!({
+            "examCode": "101", "seed": 42, "questions": []
+        }))
+        .unwrap();
+        assert_eq!(old.seed, 42);
+    }
 }
@@ -1,7 +1,16 @@
 use std::path::PathBuf;
 
 pub mod read;
+pub mod answer_sheet;
+pub mod answer_symbols;
+pub mod answer_text;
 pub mod assets;
+pub mod audio;
+pub mod barcode;
+pub mod benchmark;
+pub mod blanks;
+pub mod closing;
+pub mod autofix;
 pub mod model;
 pub mod parser;
 pub mod validator;
@@ -10,6 +19,41 @@ pub mod excel;
 pub mod mixer;
 pub mod config;
 pub mod header_template;
+pub mod labels;
+pub mod lexicon;
+pub mod manual_answers;
+pub mod listening;
+pub mod verify;
+pub mod exam_parts;
+pub mod exam_preset;
+pub mod export_options;
+pub mod symbols;
+pub mod passthrough;
+pub mod diff;
+pub mod speech;
+pub mod ocr;
+pub mod captions;
+pub mod chapters;
+pub mod cover_page;
+pub mod crossref;
+pub mod distractors;
+pub mod fields;
+pub mod fonts;
+pub mod file_names;
+pub mod forbidden;
+pub mod groups;
+pub mod invariants;
+pub mod negation;
+pub mod parts;
+pub mod partial;
+pub mod reanalysis;
+pub mod reimport;
+pub mod rules;
+pub mod spell;
+pub mod statements;
+pub mod stats;
+pub mod summary;
+pub mod thumbnails;
 
 #[allow(dead_code)]
 #[derive(Debug)]
@@ -17,6 +61,9 @@ pub enum AppError {
     Io(std::io::Error),
     Zip(zip::result::ZipError),
     Utf8(std::string::FromUtf8Error),
+    /// The archive looks crafted: too many entries, oversized media or
+    /// entry names escaping the destination folder
+    UnsafeArchive(String),
 }
 
 impl From<std::io::Error> for AppError {
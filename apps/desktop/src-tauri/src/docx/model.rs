@@ -1,23 +1,117 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use super::statements::StatementItem;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ParsedDoc {
     pub questions: Vec<Question>,
+    /// Shared-data headers ("Dùng dữ kiện sau cho câu 15 và 16")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<QuestionGroup>,
+    /// Part headings with their points ("PHẦN I. TRẮC NGHIỆM (3 điểm)")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<ExamPart>,
+    /// `<w:p>` indexes of paragraphs too damaged to parse, kept as plain text
+    #[serde(rename = "recoveredParagraphs", default, skip_serializing_if = "Vec::is_empty")]
+    pub recovered_paragraphs: Vec<usize>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Stimulus shared by a range of questions, which stay adjacent and in order
+/// when mixing (see `docx::groups`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuestionGroup {
+    /// The header paragraph naming the questions
+    pub header: Vec<Segment>,
+    /// Paragraphs of shared data after the header, up to the first question
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paragraphs: Vec<Vec<Segment>>,
+    /// Numbers of the questions it applies to, in order
+    pub questions: Vec<u32>,
+}
+
+/// Part of the exam with its own scoring; its questions are only mixed
+/// among themselves (see `docx::exam_parts`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExamPart {
+    /// The heading paragraph
+    pub heading: Vec<Segment>,
+    /// Total points of the part, when the heading names them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<f64>,
+    /// Numbers of its questions, in document order
+    pub questions: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Question {
     pub number: u32,
     pub stem: Vec<Segment>,
     pub options: Vec<OptionItem>,
     pub correct_label: String,
+    /// Second-language (English) version of the stem for bilingual exams
+    #[serde(rename = "stemAlt", default, skip_serializing_if = "Vec::is_empty")]
+    pub stem_alt: Vec<Segment>,
+    /// Unprefixed paragraphs attached to this question, so the user can
+    /// reassign misplaced ones (see `reassign_paragraph`)
+    #[serde(rename = "continuationParagraphs", default, skip_serializing_if = "Vec::is_empty")]
+    pub continuation_paragraphs: Vec<ContinuationParagraph>,
+    /// Original `<w:p>` blocks of the stem (heading paragraph included), kept
+    /// when parsing with `keep_raw_xml` for the raw passthrough export
+    #[serde(rename = "rawParagraphs", default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_paragraphs: Vec<String>,
+    /// Numbers of other questions referenced in the text ("dữ kiện ở câu
+    /// 12"), renumbered in each variant (see `docx::crossref`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<u32>,
+    /// Roman-numeral statements (I), (II), … found in the stem (see
+    /// `docx::statements`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statements: Vec<StatementItem>,
+    /// Chapter heading the question appears under, when parsing with
+    /// `detect_chapters` (see `docx::chapters`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<String>,
+    /// Points stated at the start of the stem ("(0,5 điểm)"), checked
+    /// against the total of its part
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub points: Option<f64>,
+}
+
+/// Where an unprefixed (continuation) paragraph is attached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ParagraphAssignment {
+    /// Stem of the current question
+    Stem,
+    /// Last option of the current question
+    Option,
+    /// Beginning of the next question's stem (e.g. a shared passage)
+    NextQuestion,
 }
 
+/// A continuation paragraph and the place the parser put it
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContinuationParagraph {
+    /// Index of the `<w:p>` in document.xml
+    #[serde(rename = "paragraphIndex")]
+    pub paragraph_index: usize,
+    pub assignment: ParagraphAssignment,
+    /// Start of the paragraph text, for display
+    pub preview: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct OptionItem {
     pub label: String,
     pub locked: bool,
     pub content: Vec<Segment>,
+    /// Second-language (English) version of the option content
+    #[serde(rename = "contentAlt", default, skip_serializing_if = "Vec::is_empty")]
+    pub content_alt: Vec<Segment>,
+    /// Original `<w:p>` blocks of the option (label paragraph first). Empty
+    /// when the option shares its paragraph with other options.
+    #[serde(rename = "rawParagraphs", default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_paragraphs: Vec<String>,
 }
 
 /// A segment represents a piece of content within a question or option.
@@ -44,6 +138,12 @@ pub enum Segment {
         /// Height in EMUs (parsed from original XML, 0 if not found)
         #[serde(rename = "heightEmu", default)]
         height_emu: i64,
+        /// Set for floating images (`<wp:anchor>`); `None` for inline images
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        anchor: Option<ImageAnchor>,
+        /// Text recognized in the image (see `docx::ocr`), used for search
+        #[serde(rename = "ocrText", default, skip_serializing_if = "Option::is_none")]
+        ocr_text: Option<String>,
     },
     /// Math segment with OMML content and original XML wrapper
     #[serde(rename = "Math")]
@@ -52,4 +152,57 @@ pub enum Segment {
         #[serde(rename = "rawXml")]
         raw_xml: String,
     },
+    /// `<w:sym>` character with no known Unicode equivalent, written back as-is
+    #[serde(rename = "Symbol")]
+    Symbol {
+        font: String,
+        /// Hex code from `w:char`, e.g. "F0B3"
+        #[serde(rename = "char")]
+        char_code: String,
+        #[serde(rename = "rawXml")]
+        raw_xml: String,
+    },
+    /// Fill-in blank typed as dots or underscores (see `docx::blanks`)
+    #[serde(rename = "Blank")]
+    Blank {
+        /// Length of the run in the source, in characters
+        width: u32,
+    },
+    /// Recording embedded for a listening question (see `docx::audio`)
+    #[serde(rename = "Audio")]
+    Audio {
+        asset_path: String,
+        /// Name of the file in `assets/audio/` and in the export
+        #[serde(rename = "fileName")]
+        file_name: String,
+        /// The OLE object drawn as an icon in the source
+        #[serde(rename = "rawXml")]
+        raw_xml: String,
+    },
+}
+
+/// Text wrapping of a floating image (`<wp:wrap*>` inside `<wp:anchor>`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WrapMode {
+    Square,
+    Tight,
+    Through,
+    TopAndBottom,
+    /// `<wp:wrapNone>`: in front of / behind text
+    None,
+}
+
+/// Placement of a floating image in the source document
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageAnchor {
+    /// Index (0-based) of the `<w:p>` holding the anchor in document.xml,
+    /// counted like `ContinuationParagraph::paragraph_index`
+    #[serde(rename = "paragraphIndex")]
+    pub paragraph_index: usize,
+    pub wrap: WrapMode,
+    /// Original `<wp:anchor>...</wp:anchor>` block, used when floating
+    /// placement is preserved on export
+    #[serde(rename = "anchorXml")]
+    pub anchor_xml: String,
 }
@@ -0,0 +1,101 @@
+// src-tauri/src/docx/negation.rs
+//! Negative wording in stems ("Phát biểu nào sau đây KHÔNG đúng?").
+//!
+//! Exam-writing rules ask for the negative word to be written in capitals and
+//! emphasized so students do not miss it. Words in capitals are found in the
+//! stem; a warning is raised when the run holding one is neither bold nor
+//! underlined in the source document, and the writer can bold them in the
+//! output.
+
+use regex::Regex;
+
+use super::model::{Question, Segment};
+
+fn negative_re() -> Regex {
+    Regex::new(r"\b(KHÔNG|NGOẠI TRỪ|SAI)\b").unwrap()
+}
+
+/// Split `text` into pieces, flagging the pieces that are negative words.
+pub fn split(text: &str) -> Vec<(&str, bool)> {
+    let mut pieces = Vec::new();
+    let mut cursor = 0;
+    for caps in negative_re().captures_iter(text) {
+        let word = caps.get(1).unwrap();
+        if word.start() > cursor {
+            pieces.push((&text[cursor..word.start()], false));
+        }
+        pieces.push((word.as_str(), true));
+        cursor = word.end();
+    }
+    if cursor < text.len() {
+        pieces.push((&text[cursor..], false));
+    }
+    pieces
+}
+
+/// Whether the stem has a negative word that is not emphasized. Segments
+/// without their original XML (question bank, tests) are not judged.
+pub fn has_unemphasized(question: &Question) -> bool {
+    let re = negative_re();
+    question.stem.iter().any(|segment| {
+        let Segment::Text { text, raw_xml } = segment else {
+            return false;
+        };
+        if raw_xml.is_empty() {
+            return false;
+        }
+        re.captures_iter(text).any(|caps| !emphasized(raw_xml, &caps[1]))
+    })
+}
+
+/// Whether a run of `raw_xml` holding `word` is bold or underlined. A word
+/// split across runs counts as not emphasized.
+fn emphasized(raw_xml: &str, word: &str) -> bool {
+    raw_xml
+        .split("</w:r>")
+        .filter(|run| run.contains(word))
+        .any(|run| {
+            run.contains("<w:b/>")
+                || run.contains("<w:b w:val=\"1\"")
+                || run.contains("<w:b w:val=\"true\"")
+                || (run.contains("<w:u ") && !run.contains("w:val=\"none\""))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(text: &str, raw_xml: &str) -> Question {
+        Question {
+            number: 1,
+            stem: vec![Segment::Text {
+                text: text.to_string(),
+                raw_xml: raw_xml.to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flags_plain_negative_words() {
+        assert_eq!(
+            split("Chất nào KHÔNG tan?"),
+            vec![("Chất nào ", false), ("KHÔNG", true), (" tan?", false)]
+        );
+        // Lowercase and words inside longer words are not negative markers
+        assert_eq!(split("không SAISO"), vec![("không SAISO", false)]);
+
+        let plain = question(
+            "Chất nào KHÔNG tan?",
+            "<w:r><w:t>Chất nào </w:t></w:r><w:r><w:t>KHÔNG</w:t></w:r><w:r><w:t> tan?</w:t></w:r>",
+        );
+        assert!(has_unemphasized(&plain));
+
+        let bold = question(
+            "Chất nào KHÔNG tan?",
+            "<w:r><w:t>Chất nào </w:t></w:r><w:r><w:rPr><w:b/></w:rPr><w:t>KHÔNG</w:t></w:r><w:r><w:t> tan?</w:t></w:r>",
+        );
+        assert!(!has_unemphasized(&bold));
+    }
+}
@@ -0,0 +1,115 @@
+// src-tauri/src/docx/ocr.rs
+//! Optional OCR of extracted images (figures, scanned tables) so the text they
+//! contain becomes searchable metadata on `Segment::Image`.
+//!
+//! Runs the tesseract sidecar shipped next to the app executable (bundled
+//! from `binaries/` by `externalBin` in `tauri.conf.json`), falling back to a
+//! `tesseract` on PATH. The Vietnamese and English language data are bundled
+//! as resources (`tessdata/`); without them tesseract uses its own. When no
+//! tesseract is available images are simply left without OCR text.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::model::{ParsedDoc, Segment};
+
+/// Recognition languages (tesseract traineddata names)
+const LANGUAGES: &str = "vie+eng";
+
+/// Folder of the bundled language data, in the app resources
+pub const TESSDATA_DIR: &str = "tessdata";
+
+/// Fill `ocr_text` on every image of the document, with the language data in
+/// `tessdata_dir` when it has them. Returns how many images got text.
+pub fn annotate(doc: &mut ParsedDoc, tessdata_dir: Option<&Path>) -> usize {
+    let Some(tesseract) = tesseract_command() else {
+        println!("[OCR] tesseract not available, skipping");
+        return 0;
+    };
+    let tessdata_dir = tessdata_dir.filter(|dir| {
+        LANGUAGES.split('+').all(|lang| dir.join(format!("{}.traineddata", lang)).is_file())
+    });
+
+    let mut recognized = 0;
+    for question in &mut doc.questions {
+        let segments = question
+            .stem
+            .iter_mut()
+            .chain(question.stem_alt.iter_mut())
+            .chain(
+                question
+                    .options
+                    .iter_mut()
+                    .flat_map(|o| o.content.iter_mut().chain(o.content_alt.iter_mut())),
+            );
+        for segment in segments {
+            let Segment::Image { asset_path, ocr_text, .. } = segment else {
+                continue;
+            };
+            if asset_path.is_empty() {
+                continue;
+            }
+            match recognize(&tesseract, tessdata_dir, Path::new(asset_path.as_str())) {
+                Ok(text) => {
+                    *ocr_text = clean(&text);
+                    recognized += usize::from(ocr_text.is_some());
+                }
+                Err(e) => eprintln!("[OCR] Failed for {}: {}", asset_path, e),
+            }
+        }
+    }
+    recognized
+}
+
+/// Sidecar next to the executable, else `tesseract` if it is on PATH.
+fn tesseract_command() -> Option<PathBuf> {
+    let name = if cfg!(windows) { "tesseract.exe" } else { "tesseract" };
+    let sidecar = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(name)))
+        .filter(|path| path.is_file());
+
+    sidecar.or_else(|| {
+        Command::new(name)
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|_| PathBuf::from(name))
+    })
+}
+
+fn recognize(tesseract: &Path, tessdata_dir: Option<&Path>, image: &Path) -> Result<String, String> {
+    let mut command = Command::new(tesseract);
+    if let Some(dir) = tessdata_dir {
+        command.arg("--tessdata-dir").arg(dir);
+    }
+    let output = command
+        .arg(image)
+        .arg("stdout")
+        .arg("-l")
+        .arg(LANGUAGES)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Collapse whitespace; `None` when nothing readable was found.
+fn clean(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    text.chars().any(char::is_alphanumeric).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleans_recognized_text() {
+        assert_eq!(clean("  Bảng 1:\n\n Dân số\x0c").as_deref(), Some("Bảng 1: Dân số"));
+        assert_eq!(clean(" \n|\x0c"), None);
+    }
+}
@@ -1,38 +1,162 @@
 use regex::Regex;
-use std::collections::HashMap;
-
-use crate::docx::model::{OptionItem, ParsedDoc, Question, Segment};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::docx::blanks;
+use crate::docx::chapters;
+use crate::docx::crossref;
+use crate::docx::exam_parts;
+use crate::docx::fields;
+use crate::docx::groups;
+use crate::docx::statements;
+use crate::docx::labels;
+use crate::docx::symbols;
+use crate::docx::model::{
+    ContinuationParagraph, ExamPart, ImageAnchor, OptionItem, ParagraphAssignment, ParsedDoc, Question,
+    QuestionGroup, Segment, WrapMode,
+};
 use crate::docx::validator::{LabelRunStyle, LabeledOptionRuns};
 use crate::docx::ExtractedAsset;
 
+/// Options controlling how `document.xml` is split into questions.
+/// Stored in the job manifest so a job can be re-parsed with the same settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParseOptions {
+    /// Maximum number of options per question (labels A..)
+    #[serde(rename = "maxOptions")]
+    pub max_options: usize,
+    /// Pair Vietnamese/English versions of each question (see `parse_document_xml_to_parsed_doc`)
+    pub bilingual: bool,
+    /// Heuristics deciding where unprefixed paragraphs go
+    pub continuation: ContinuationRules,
+    /// Per-paragraph assignments chosen by the user, keyed by `<w:p>` index.
+    /// They take precedence over the heuristics.
+    pub overrides: BTreeMap<usize, ParagraphAssignment>,
+    /// Keep the original `<w:p>` blocks of each question for the raw
+    /// passthrough export (ignored in bilingual mode)
+    #[serde(rename = "keepRawXml")]
+    pub keep_raw_xml: bool,
+    /// Run OCR over extracted images and keep the text for search
+    #[serde(rename = "ocrImages")]
+    pub ocr_images: bool,
+    /// Correct labels chosen by the user for questions without a mark,
+    /// keyed by question number (see `set_correct_label`)
+    #[serde(rename = "manualAnswers")]
+    pub manual_answers: BTreeMap<u32, String>,
+    /// Use `manual_answers` for questions whose mark is missing (E020)
+    /// instead of failing the analysis
+    #[serde(rename = "continueWithManualAnswers")]
+    pub continue_with_manual_answers: bool,
+    /// Save the questions without errors instead of rejecting the whole
+    /// document; the others are reported as skipped
+    #[serde(rename = "allowPartial")]
+    pub allow_partial: bool,
+    /// Read chapter headings ("CHƯƠNG 1: …") and tag the following
+    /// questions with them (see `docx::chapters`)
+    #[serde(rename = "detectChapters")]
+    pub detect_chapters: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_options: labels::DEFAULT_MAX_OPTIONS,
+            bilingual: false,
+            continuation: ContinuationRules::default(),
+            overrides: BTreeMap::new(),
+            keep_raw_xml: false,
+            ocr_images: false,
+            manual_answers: BTreeMap::new(),
+            continue_with_manual_answers: false,
+            allow_partial: false,
+            detect_chapters: false,
+        }
+    }
+}
+
+/// Heuristics for paragraphs that follow the last option without a prefix.
+/// By default such paragraphs continue the last option; each rule below can
+/// instead send them to the start of the next question (e.g. a reading passage).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContinuationRules {
+    /// An empty paragraph after the options ends the question
+    pub blank_line_separates: bool,
+    /// Paragraph styles (`w:pStyle` ids) that always belong to the next question
+    pub stem_styles: Vec<String>,
+    /// Paragraphs after the options indented less than this (twips) belong to
+    /// the next question; option continuations are usually indented
+    pub min_option_indent: Option<i64>,
+}
+
+/// Where continuation paragraphs of the current question are appended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentTarget {
+    Stem,
+    StemAlt,
+    Option(usize),
+    OptionAlt(usize),
+}
+
 /// Parse document.xml into ParsedDoc by extracting segments (text, math, images)
 /// from each paragraph while preserving question/option structure.
 ///
 /// Rules:
 /// - Each paragraph has ONE role: new question, new option, or continuation
 /// - Question starts with "Câu X." or "Question X."
-/// - Option starts with "A." / "B." / "C." / "D." / "E." / "F." (or "#A." for locked)
+/// - Option starts with "A." / "B." / ... up to the label allowed by `max_options`
+///   (or "#A." for an option kept in its slot when shuffling; the correct
+///   answer is still the one marked on its label)
 /// - Continuation paragraphs are added to current question stem or option content
+///
+/// Bilingual mode (`options.bilingual`):
+/// - A second heading with the same number ("Câu 1." then "Question 1.") is the
+///   English stem of that question
+/// - A repeated option label ("A." twice) is the English content of that option
+/// - A paragraph tagged "[EN]" continues the English side of the last element
 pub fn parse_document_xml_to_parsed_doc(
     document_xml: &str,
     assets: &[ExtractedAsset],
+    options: &ParseOptions,
 ) -> ParsedDoc {
     let question_re = Regex::new(r"^(Câu|Question)\s+(\d+)\.").unwrap();
-    let option_re = Regex::new(r"^(?P<label>#?[A-F])\s*\.").unwrap();
+    let option_re = Regex::new(&format!(
+        r"^(?P<label>#?{})\s*\.",
+        labels::label_char_class(labels::MAX_SUPPORTED_OPTIONS)
+    ))
+    .unwrap();
+    let translation_tag_re = Regex::new(r"^\[(EN|en)\]\s*").unwrap();
 
     let mut questions: Vec<Question> = Vec::new();
     let mut current_question: Option<Question> = None;
+    let mut target = ContentTarget::Stem;
     let mut cursor = 0;
     // Global cursor for mapping images (both <w:drawing> and <w:object>)
     // to extracted media assets by order of appearance.
     let mut next_asset_index: usize = 0;
+    let mut paragraph_index: usize = 0;
+    // Continuation heuristics: blank line seen after the options, and the
+    // paragraphs held back for the next question's stem
+    let mut blank_after_option = false;
+    let mut pending_preamble: Vec<Segment> = Vec::new();
+    let mut pending_paragraphs: Vec<ContinuationParagraph> = Vec::new();
+    let keep_raw = options.keep_raw_xml && !options.bilingual;
+    let mut pending_raw: Vec<String> = Vec::new();
+    // Shared-data header being read, and the finished ones
+    let mut pending_group: Option<QuestionGroup> = None;
+    let mut question_groups: Vec<QuestionGroup> = Vec::new();
+    // Chapter heading the next questions belong to
+    let mut current_chapter: Option<String> = None;
+    // Part headings, and the part each question was started in
+    let mut parts: Vec<ExamPart> = Vec::new();
+    let mut question_parts: HashMap<u32, usize> = HashMap::new();
+    // Paragraphs the extractor failed on, read as plain text instead
+    let mut recovered_paragraphs: Vec<usize> = Vec::new();
 
     // Walk through all <w:p> blocks
-    loop {
-        let start_rel = match document_xml[cursor..].find("<w:p") {
-            Some(idx) => idx,
-            None => break,
-        };
+    while let Some(start_rel) = document_xml[cursor..].find("<w:p") {
         let start = cursor + start_rel;
 
         let end_rel = match document_xml[start..].find("</w:p>") {
@@ -41,11 +165,28 @@ pub fn parse_document_xml_to_parsed_doc(
         };
         let end = start + end_rel;
 
-        let block = &document_xml[start..end];
+        // Field codes reduced to their display text, bookmarks dropped
+        let block = fields::flatten(&document_xml[start..end]);
+        let block = block.as_ref();
 
         // Extract segments (text, math, images) from this paragraph
-        let segments = extract_segments_from_paragraph(block, assets, &mut next_asset_index);
+        let segments = match panic::catch_unwind(AssertUnwindSafe(|| {
+            extract_segments_from_paragraph(block, paragraph_index, assets, &mut next_asset_index)
+        })) {
+            Ok(segments) => segments,
+            Err(_) => {
+                eprintln!("[Parser] Paragraph {} could not be read, kept as plain text", paragraph_index);
+                recovered_paragraphs.push(paragraph_index);
+                plain_text_segments(block)
+            }
+        };
+        paragraph_index += 1;
         if segments.is_empty() {
+            // A section break paragraph is layout, not a blank line
+            let section_break = block.contains("<w:sectPr");
+            if !section_break && matches!(target, ContentTarget::Option(_) | ContentTarget::OptionAlt(_)) {
+                blank_after_option = true;
+            }
             cursor = end;
             continue;
         }
@@ -56,13 +197,6 @@ pub fn parse_document_xml_to_parsed_doc(
 
         // Case 1: New question paragraph (starts with "Câu X." or "Question X.")
         if let Some(caps) = question_re.captures(trimmed) {
-            // Save previous question if any
-            if let Some(q) = current_question.take() {
-                if !q.options.is_empty() {
-                    questions.push(q);
-                }
-            }
-
             let number: u32 = caps
                 .get(2)
                 .and_then(|m| m.as_str().parse().ok())
@@ -72,19 +206,60 @@ pub fn parse_document_xml_to_parsed_doc(
             let prefix_end = caps.get(0).unwrap().end();
             let stem_segments = trim_prefix_from_segments(&segments, prefix_end);
 
+            // Bilingual: same number again → English version of the current stem
+            if options.bilingual {
+                if let Some(ref mut q) = current_question {
+                    if q.number == number && q.stem_alt.is_empty() {
+                        q.stem_alt = stem_segments;
+                        target = ContentTarget::StemAlt;
+                        cursor = end;
+                        continue;
+                    }
+                }
+            }
+
+            // Save previous question if any
+            if let Some(q) = current_question.take() {
+                if !q.options.is_empty() {
+                    questions.push(q);
+                }
+            }
+            question_groups.extend(pending_group.take());
+
+            // Paragraphs held back for this question go before its stem
+            let mut stem = std::mem::take(&mut pending_preamble);
+            stem.extend(stem_segments);
+
+            let mut raw_paragraphs = std::mem::take(&mut pending_raw);
+            if keep_raw {
+                raw_paragraphs.push(block.to_string());
+            }
+
+            if !parts.is_empty() {
+                question_parts.insert(number, parts.len() - 1);
+            }
             current_question = Some(Question {
                 number,
-                stem: stem_segments,
-                options: Vec::new(),
-                correct_label: String::new(),
+                stem,
+                continuation_paragraphs: std::mem::take(&mut pending_paragraphs),
+                raw_paragraphs,
+                chapter: current_chapter.clone(),
+                ..Default::default()
             });
+            target = ContentTarget::Stem;
+            blank_after_option = false;
 
             cursor = end;
             continue;
         }
 
-        // Case 2: New option paragraph (starts with "A." / "B." / etc.)
-        if let Some(caps) = option_re.captures(trimmed) {
+        // Case 2: New option paragraph (starts with "A." / "B." / etc.).
+        // Labels past `max_options` are kept so the validator can report them
+        if let Some(caps) = option_re.captures(trimmed).filter(|caps| {
+            let existing = current_question.as_ref().map_or(0, |q| q.options.len());
+            labels::starts_option(&caps["label"], existing, options.max_options)
+        }) {
+            blank_after_option = false;
             if let Some(ref mut q) = current_question {
                 // Check if there are multiple options in this paragraph (e.g., "C. ... D. ...")
                 // by finding all option patterns in the plain text
@@ -126,16 +301,7 @@ pub fn parse_document_xml_to_parsed_doc(
                                 raw_xml: String::new(), // TODO: Extract proper raw XML
                             }];
 
-                            q.options.push(OptionItem {
-                                label: label.clone(),
-                                locked: is_locked,
-                                content: content_segments,
-                            });
-
-                            // If this is a locked option (e.g., "#A."), set as correct answer
-                            if is_locked && q.correct_label.is_empty() {
-                                q.correct_label = label;
-                            }
+                            target = add_option(q, label, is_locked, content_segments, options.bilingual);
                         }
                     }
                 } else {
@@ -156,15 +322,11 @@ pub fn parse_document_xml_to_parsed_doc(
                     let prefix_end = caps.get(0).unwrap().end();
                     let content_segments = trim_prefix_from_segments(&segments, prefix_end);
 
-                    q.options.push(OptionItem {
-                        label: label.clone(),
-                        locked: is_locked,
-                        content: content_segments,
-                    });
-
-                    // If this is a locked option (e.g., "#A."), set as correct answer
-                    if is_locked && q.correct_label.is_empty() {
-                        q.correct_label = label;
+                    target = add_option(q, label, is_locked, content_segments, options.bilingual);
+                    if keep_raw {
+                        if let Some(option) = q.options.last_mut() {
+                            option.raw_paragraphs.push(block.to_string());
+                        }
                     }
                 }
             }
@@ -173,16 +335,124 @@ pub fn parse_document_xml_to_parsed_doc(
             continue;
         }
 
+        // Part heading ("PHẦN I. TRẮC NGHIỆM (3 điểm)"): starts a new part
+        if exam_parts::is_heading(trimmed) {
+            question_groups.extend(pending_group.take());
+            parts.push(ExamPart {
+                points: exam_parts::heading_points(trimmed),
+                heading: segments,
+                questions: Vec::new(),
+            });
+            cursor = end;
+            continue;
+        }
+
+        // Chapter heading: not content of the question before it
+        if options.detect_chapters {
+            if let Some(title) = chapters::heading_title(block, trimmed) {
+                current_chapter = Some(title);
+                cursor = end;
+                continue;
+            }
+        }
+
+        // Shared-data header ("Dùng dữ kiện sau cho câu 15 và 16"): it and the
+        // paragraphs up to the next question form a group
+        if let Some(range) = groups::header_range(trimmed) {
+            question_groups.extend(pending_group.take());
+            pending_group = Some(QuestionGroup {
+                header: segments,
+                paragraphs: Vec::new(),
+                questions: range,
+            });
+            cursor = end;
+            continue;
+        }
+        if let Some(ref mut group) = pending_group {
+            group.paragraphs.push(segments);
+            cursor = end;
+            continue;
+        }
+
         // Case 3: Continuation paragraph (no question/option prefix)
-        // Add to current question stem or current option content
+        // Add to current question stem or current option content, unless a
+        // user override or a heuristic sends it to the next question
         if let Some(ref mut q) = current_question {
-            if q.options.is_empty() {
-                // No options yet: add to stem
-                q.stem.extend(segments);
-            } else {
-                // Has options: add to last option's content
-                if let Some(last_option) = q.options.last_mut() {
-                    last_option.content.extend(segments);
+            let index = paragraph_index - 1;
+            let assignment = options.overrides.get(&index).copied().unwrap_or_else(|| {
+                default_assignment(block, target, blank_after_option, &options.continuation)
+            });
+            let preview: String = trimmed.chars().take(60).collect();
+
+            if assignment == ParagraphAssignment::NextQuestion {
+                pending_preamble.extend(segments);
+                if keep_raw {
+                    pending_raw.push(block.to_string());
+                }
+                pending_paragraphs.push(ContinuationParagraph {
+                    paragraph_index: index,
+                    assignment,
+                    preview,
+                });
+                cursor = end;
+                continue;
+            }
+
+            q.continuation_paragraphs.push(ContinuationParagraph {
+                paragraph_index: index,
+                assignment,
+                preview,
+            });
+
+            let mut segments = segments;
+            let mut target_now = match (assignment, target) {
+                (ParagraphAssignment::Stem, ContentTarget::Stem | ContentTarget::StemAlt)
+                | (ParagraphAssignment::Option, ContentTarget::Option(_) | ContentTarget::OptionAlt(_)) => target,
+                (ParagraphAssignment::Option, _) => q
+                    .options
+                    .len()
+                    .checked_sub(1)
+                    .map(ContentTarget::Option)
+                    .unwrap_or(ContentTarget::Stem),
+                _ => ContentTarget::Stem,
+            };
+
+            // Bilingual: "[EN] ..." continues the English side of the last element
+            if options.bilingual {
+                if let Some(m) = translation_tag_re.find(trimmed) {
+                    segments = trim_prefix_from_segments(&segments, trimmed[..m.end()].chars().count());
+                    target_now = match target {
+                        ContentTarget::Stem | ContentTarget::StemAlt => ContentTarget::StemAlt,
+                        ContentTarget::Option(idx) | ContentTarget::OptionAlt(idx) => {
+                            ContentTarget::OptionAlt(idx)
+                        }
+                    };
+                }
+            }
+
+            if keep_raw {
+                match target_now {
+                    ContentTarget::Option(idx) => {
+                        if let Some(option) = q.options.get_mut(idx) {
+                            option.raw_paragraphs.push(block.to_string());
+                        }
+                    }
+                    _ => q.raw_paragraphs.push(block.to_string()),
+                }
+            }
+
+            match target_now {
+                ContentTarget::Stem => q.stem.extend(segments),
+                ContentTarget::StemAlt => q.stem_alt.extend(segments),
+                ContentTarget::Option(idx) => {
+                    if let Some(option) = q.options.get_mut(idx) {
+                        option.content.extend(segments);
+                    }
+                }
+                ContentTarget::OptionAlt(idx) => {
+                    if let Some(option) = q.options.get_mut(idx) {
+                        option.content_alt.extend(segments);
+                    }
                 }
             }
         }
@@ -190,24 +460,145 @@ pub fn parse_document_xml_to_parsed_doc(
         cursor = end;
     }
 
-    // Push last question if valid
-    if let Some(q) = current_question {
+    // Push last question if valid. Paragraphs held back for a next question
+    // that never came stay with the last one.
+    if let Some(mut q) = current_question {
+        if !pending_preamble.is_empty() {
+            match q.options.last_mut() {
+                Some(option) => {
+                    option.content.extend(pending_preamble);
+                    option.raw_paragraphs.extend(pending_raw);
+                }
+                None => {
+                    q.stem.extend(pending_preamble);
+                    q.raw_paragraphs.extend(pending_raw);
+                }
+            }
+            q.continuation_paragraphs.extend(pending_paragraphs);
+        }
         if !q.options.is_empty() {
             questions.push(q);
         }
     }
 
-    ParsedDoc { questions }
+    question_groups.extend(pending_group);
+
+    for q in &mut questions {
+        if let Some(&part) = question_parts.get(&q.number) {
+            parts[part].questions.push(q.number);
+        }
+        q.points = exam_parts::stated_points(&q.stem);
+        q.references = crossref::detect(q);
+        q.statements = statements::detect(&q.stem);
+        blanks::split_question(q);
+    }
+
+    ParsedDoc {
+        questions,
+        groups: question_groups,
+        parts,
+        recovered_paragraphs,
+    }
 }
 
-/// Extract segments (Text, Math, Image) from a single <w:p> block preserving order.
+/// Fallback for a paragraph whose runs cannot be split into segments: its
+/// text only, without formatting, math or images. Empty when even the text
+/// cannot be read, and the paragraph is then skipped.
+fn plain_text_segments(block: &str) -> Vec<Segment> {
+    match panic::catch_unwind(|| extract_text_from_w_p(block)) {
+        Ok(text) if !text.trim().is_empty() => vec![Segment::Text {
+            text,
+            raw_xml: String::new(),
+        }],
+        _ => Vec::new(),
+    }
+}
+
+/// Heuristic placement of an unprefixed paragraph (see `ContinuationRules`).
+fn default_assignment(
+    block: &str,
+    target: ContentTarget,
+    blank_after_option: bool,
+    rules: &ContinuationRules,
+) -> ParagraphAssignment {
+    if !matches!(target, ContentTarget::Option(_) | ContentTarget::OptionAlt(_)) {
+        return ParagraphAssignment::Stem;
+    }
+
+    let styled_as_stem = paragraph_style(block)
+        .map(|style| rules.stem_styles.iter().any(|s| s == style))
+        .unwrap_or(false);
+    let under_indented = rules
+        .min_option_indent
+        .map(|min| paragraph_left_indent(block) < min)
+        .unwrap_or(false);
+
+    if styled_as_stem || (rules.blank_line_separates && blank_after_option) || under_indented {
+        ParagraphAssignment::NextQuestion
+    } else {
+        ParagraphAssignment::Option
+    }
+}
+
+/// Style id from `<w:pStyle w:val="..."/>`, if any.
+fn paragraph_style(block: &str) -> Option<&str> {
+    let start = block.find("<w:pStyle ")?;
+    let tag = &block[start..start + block[start..].find("/>")?];
+    let value_start = tag.find("w:val=\"")? + "w:val=\"".len();
+    let value_end = tag[value_start..].find('"')? + value_start;
+    Some(&tag[value_start..value_end])
+}
+
+/// Left indentation in twips from `<w:ind w:left=".."/>` (or `w:start`), 0 if absent.
+fn paragraph_left_indent(block: &str) -> i64 {
+    let Some(start) = block.find("<w:ind ") else {
+        return 0;
+    };
+    let tag = match block[start..].find("/>") {
+        Some(end) => &block[start..start + end],
+        None => return 0,
+    };
+    ["w:left=\"", "w:start=\""]
+        .iter()
+        .find_map(|key| {
+            let value_start = tag.find(key)? + key.len();
+            let value_end = tag[value_start..].find('"')? + value_start;
+            tag[value_start..value_end].parse().ok()
+        })
+        .unwrap_or(0)
+}
+
+/// Append a parsed option to `question`, or - in bilingual mode - attach it as
+/// the English content of an existing option with the same label.
 ///
-/// Walks through the paragraph XML and creates appropriate Segment variants:
-/// - <w:t>text</w:t> → Segment::Text
-/// - <m:oMath>...</m:oMath> → Segment::Math (preserves full OMML for frontend)
-/// - <w:drawing>...</w:drawing> or <w:object>...</w:object> → Segment::Image
-///   Images (including OLE Equation objects with VML/v:imagedata) are mapped
-///   to extracted media assets purely by global order of appearance.
+/// Returns where following continuation paragraphs should go.
+fn add_option(
+    question: &mut Question,
+    label: String,
+    is_locked: bool,
+    content: Vec<Segment>,
+    bilingual: bool,
+) -> ContentTarget {
+    if bilingual {
+        if let Some(idx) = question
+            .options
+            .iter()
+            .position(|o| o.label == label && o.content_alt.is_empty())
+        {
+            question.options[idx].content_alt = content;
+            return ContentTarget::OptionAlt(idx);
+        }
+    }
+
+    question.options.push(OptionItem {
+        label: label.clone(),
+        locked: is_locked,
+        content,
+        ..Default::default()
+    });
+
+    ContentTarget::Option(question.options.len() - 1)
+}
 
 /// Extract ALL text from ALL <w:t> elements within a single <w:r> run block.
 /// This handles cases where Word splits text into multiple <w:t> elements.
@@ -219,23 +610,16 @@ fn extract_all_text_from_run(run_block: &str) -> String {
     let mut result = String::new();
     let mut cursor = 0;
 
-    loop {
-        // Find next <w:t> or <w:t ...>
-        let start_rel = match run_block[cursor..].find("<w:t") {
-            Some(idx) => idx,
-            None => break,
-        };
+    // Find next <w:t> or <w:t ...>
+    while let Some(start_rel) = run_block[cursor..].find("<w:t") {
         let start = cursor + start_rel;
         
         // Make sure it's actually <w:t> or <w:t ...>, not <w:tab> etc.
         let after_wt = start + "<w:t".len();
-        if after_wt < run_block.len() {
-            let next_char = &run_block[after_wt..after_wt + 1];
-            if next_char != ">" && next_char != " " {
-                // It's <w:tab> or similar, skip
-                cursor = after_wt;
-                continue;
-            }
+        if after_wt < run_block.len() && !ends_tag_name(run_block, after_wt) {
+            // It's <w:tab> or similar, skip
+            cursor = after_wt;
+            continue;
         }
 
         // Find end of opening tag
@@ -279,6 +663,13 @@ fn extract_all_text_from_run(run_block: &str) -> String {
     result
 }
 
+/// The tag name just opened ends at byte `pos` (`>` or a space follows), so
+/// `<w:t` is `<w:t>` and not `<w:tab>`. Compares bytes: text after the tag
+/// may start with a multibyte character.
+fn ends_tag_name(block: &str, pos: usize) -> bool {
+    matches!(block.as_bytes().get(pos), Some(b'>' | b' '))
+}
+
 /// Helper function to find the start of a <w:r> tag (not <w:rPr>)
 fn find_run_start(block: &str, before_pos: usize) -> usize {
     let mut search_end = before_pos;
@@ -287,9 +678,8 @@ fn find_run_start(block: &str, before_pos: usize) -> usize {
             // Check the character after "<w:r"
             let check_pos = idx + "<w:r".len();
             if check_pos < block.len() {
-                let ch = &block[check_pos..check_pos + 1];
                 // Valid <w:r> tag if followed by '>' or ' '
-                if ch == ">" || ch == " " {
+                if ends_tag_name(block, check_pos) {
                     return idx;
                 }
                 // Otherwise it's <w:rPr> or similar, keep searching backwards
@@ -303,12 +693,25 @@ fn find_run_start(block: &str, before_pos: usize) -> usize {
     }
 }
 
+/// Extract segments (Text, Math, Image) from a single <w:p> block preserving order.
+///
+/// Walks through the paragraph XML and creates appropriate Segment variants:
+/// - <w:t>text</w:t> → Segment::Text
+/// - <m:oMath>...</m:oMath> → Segment::Math (preserves full OMML for frontend)
+/// - <w:drawing>...</w:drawing> or <w:object>...</w:object> → Segment::Image
+///   Images (including OLE Equation objects with VML/v:imagedata) are mapped
+///   to extracted media assets purely by global order of appearance.
+/// - Floating drawings (`<wp:anchor>`) are moved to the end of the paragraph
+///   with their wrap mode recorded, so an anchor placed before "Câu 1." does
+///   not break prefix detection and the image follows the text it floats beside.
 fn extract_segments_from_paragraph(
     block: &str,
+    paragraph_index: usize,
     assets: &[ExtractedAsset],
     next_asset_index: &mut usize,
 ) -> Vec<Segment> {
     let mut segments = Vec::new();
+    let mut floating = Vec::new();
     let mut cursor = 0;
     let mut pending_text = String::new();
     let mut pending_raw_xml = String::new();
@@ -324,6 +727,14 @@ fn extract_segments_from_paragraph(
             (None, Some(b)) => Some(b),
             (None, None) => None,
         };
+        // <w:sym> (Insert → Symbol) is handled as text
+        let next_sym = block[cursor..].find("<w:sym ");
+        let next_text = match (next_text, next_sym) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (Some(a), None) => Some(a),
+            (None, Some(b)) => Some(b),
+            (None, None) => None,
+        };
         let next_math = block[cursor..].find("<m:oMath");
         let next_drawing = block[cursor..].find("<w:drawing");
         let next_object = block[cursor..].find("<w:object");
@@ -416,6 +827,45 @@ fn extract_segments_from_paragraph(
         let start = cursor + offset;
 
         match element_type {
+            "text" if block[start..].starts_with("<w:sym ") => {
+                let tag_end = match block[start..].find("/>") {
+                    Some(idx) => start + idx + "/>".len(),
+                    None => break,
+                };
+                let run_start = find_run_start(block, start).max(cursor);
+                let run_end = match block[tag_end..].find("</w:r>") {
+                    Some(idx) => tag_end + idx + "</w:r>".len(),
+                    None => tag_end,
+                };
+                let raw_xml = block[run_start..run_end].to_string();
+
+                if let Some((font, char_code)) = symbols::parse_sym_tag(&block[start..tag_end]) {
+                    if let Some(ch) = symbols::sym_to_unicode(&font, &char_code) {
+                        pending_text.push(ch);
+                        pending_raw_xml.push_str(&raw_xml);
+                    } else {
+                        // Unknown symbol: flush pending text and keep the sym run
+                        if !pending_text.is_empty() {
+                            let trimmed = pending_text.trim();
+                            if !trimmed.is_empty() {
+                                segments.push(Segment::Text {
+                                    text: trimmed.to_string(),
+                                    raw_xml: pending_raw_xml.clone(),
+                                });
+                            }
+                            pending_text.clear();
+                            pending_raw_xml.clear();
+                        }
+                        segments.push(Segment::Symbol {
+                            font,
+                            char_code,
+                            raw_xml,
+                        });
+                    }
+                }
+
+                cursor = run_end;
+            }
             "text" => {
                 // Find the <w:r> that CONTAINS this <w:t> by searching BACKWARDS
                 // But we need to be careful not to find a run that was already processed
@@ -450,8 +900,7 @@ fn extract_segments_from_paragraph(
                     let abs_idx = cursor + r_idx;
                     let check_pos = abs_idx + "<w:r".len();
                     if check_pos < block.len() {
-                        let ch = &block[check_pos..check_pos + 1];
-                        if ch == ">" || ch == " " {
+                        if ends_tag_name(block, check_pos) {
                             abs_idx
                         } else {
                             start // Fallback to <w:t> position
@@ -508,8 +957,7 @@ fn extract_segments_from_paragraph(
                     let abs_idx = cursor + r_idx;
                     let check_pos = abs_idx + "<w:r".len();
                     if check_pos < block.len() {
-                        let ch = &block[check_pos..check_pos + 1];
-                        if ch == ">" || ch == " " {
+                        if ends_tag_name(block, check_pos) {
                             abs_idx
                         } else {
                             start
@@ -563,8 +1011,15 @@ fn extract_segments_from_paragraph(
 
                 let raw_xml = block[run_start..run_end].to_string();
 
+                // Charts / SmartArt have no media file of their own. They can
+                // only be shown through the prerendered picture Word stores in
+                // <mc:Fallback>; without one, keep an empty image so the writer
+                // prints the missing-image placeholder and validation warns.
+                let is_chart_or_diagram = is_chart_or_diagram(&block[start..end]);
+                let has_media = !is_chart_or_diagram || has_fallback_picture(&raw_xml);
+
                 // Map this drawing to the next extracted media asset (if any)
-                let asset_path = if *next_asset_index < assets.len() {
+                let asset_path = if has_media && *next_asset_index < assets.len() {
                     let asset = &assets[*next_asset_index];
                     *next_asset_index += 1;
                     // Prefer converted PNG if available (for WMF/EMF files)
@@ -587,15 +1042,19 @@ fn extract_segments_from_paragraph(
 
                 // Parse dimensions from <wp:extent cx="..." cy="..."/>
                 let (width_emu, height_emu) = parse_image_dimensions(&raw_xml);
+                let anchor = parse_image_anchor(&raw_xml, paragraph_index);
 
-                if !asset_path.is_empty() {
-                    segments.push(Segment::Image { 
-                        asset_path,
-                        raw_xml,
-                        width_emu,
-                        height_emu,
-                    });
-                }
+                // An empty path is kept: the writer prints the missing-image
+                // placeholder and `validate_images` warns (W001)
+                let target = if anchor.is_some() { &mut floating } else { &mut segments };
+                target.push(Segment::Image { 
+                    asset_path,
+                    raw_xml,
+                    width_emu,
+                    height_emu,
+                    anchor,
+                    ocr_text: None,
+                });
 
                 cursor = run_end;
             }
@@ -657,14 +1116,14 @@ fn extract_segments_from_paragraph(
                 // Parse dimensions from <v:shape style="width:...;height:..."/>
                 let (width_emu, height_emu) = parse_image_dimensions(&raw_xml);
 
-                if !asset_path.is_empty() {
-                    segments.push(Segment::Image { 
-                        asset_path,
-                        raw_xml,
-                        width_emu,
-                        height_emu,
-                    });
-                }
+                segments.push(Segment::Image { 
+                    asset_path,
+                    raw_xml,
+                    width_emu,
+                    height_emu,
+                    anchor: None,
+                    ocr_text: None,
+                });
 
                 cursor = run_end;
             }
@@ -683,9 +1142,56 @@ fn extract_segments_from_paragraph(
         }
     }
 
+    segments.extend(floating);
     segments
 }
 
+/// Whether a `<w:drawing>` holds a chart (`c:chart`, `cx:chart`) or
+/// SmartArt diagram rather than a picture.
+fn is_chart_or_diagram(drawing_xml: &str) -> bool {
+    drawing_xml.contains("drawingml/2006/chart")
+        || drawing_xml.contains("drawingml/2014/chartex")
+        || drawing_xml.contains("drawingml/2006/diagram")
+}
+
+/// Whether the run carries a prerendered picture in `<mc:Fallback>`
+/// (a `<v:imagedata>` or `<a:blip>` pointing into `word/media/`).
+fn has_fallback_picture(run_xml: &str) -> bool {
+    run_xml
+        .find("<mc:Fallback")
+        .map(|idx| {
+            let fallback = &run_xml[idx..];
+            fallback.contains("<v:imagedata") || fallback.contains("<a:blip")
+        })
+        .unwrap_or(false)
+}
+
+/// Detect a floating drawing (`<wp:anchor>`) and record its wrap mode.
+/// Returns `None` for inline drawings (`<wp:inline>`).
+fn parse_image_anchor(xml: &str, paragraph_index: usize) -> Option<ImageAnchor> {
+    let start = xml.find("<wp:anchor")?;
+    let end = xml[start..].find("</wp:anchor>")? + start + "</wp:anchor>".len();
+    let anchor_xml = &xml[start..end];
+
+    let wrap = if anchor_xml.contains("<wp:wrapSquare") {
+        WrapMode::Square
+    } else if anchor_xml.contains("<wp:wrapTight") {
+        WrapMode::Tight
+    } else if anchor_xml.contains("<wp:wrapThrough") {
+        WrapMode::Through
+    } else if anchor_xml.contains("<wp:wrapTopAndBottom") {
+        WrapMode::TopAndBottom
+    } else {
+        WrapMode::None
+    };
+
+    Some(ImageAnchor {
+        paragraph_index,
+        wrap,
+        anchor_xml: anchor_xml.to_string(),
+    })
+}
+
 /// Parse image dimensions from XML (either <wp:extent> for drawings or <v:shape> for objects)
 /// 
 /// This function tries multiple sources for image dimensions in order of preference:
@@ -856,7 +1362,15 @@ fn segments_to_plain_text(segments: &[Segment]) -> String {
                 }
                 result.push(' '); // Placeholder for math
             }
-            Segment::Image { .. } => {
+            Segment::Symbol { .. } => {
+                // Unmapped symbol counts as one character
+                result.push('\u{FFFD}');
+            }
+            Segment::Blank { .. } => {
+                // Blanks are split out after parsing (see `blanks`)
+                result.push('_');
+            }
+            Segment::Image { .. } | Segment::Audio { .. } => {
                 // Images don't contribute to text matching
                 // But add space if needed to avoid words sticking together
                 if !result.is_empty() && !result.ends_with(' ') {
@@ -920,7 +1434,15 @@ fn trim_prefix_from_segments(segments: &[Segment], prefix_len: usize) -> Vec<Seg
                     chars_skipped += 2;
                 }
             }
-            Segment::Image { .. } => {
+            Segment::Symbol { .. } | Segment::Blank { .. } => {
+                // Symbols occupy 1 character in plain text
+                if chars_skipped >= prefix_len {
+                    result.push(seg.clone());
+                } else {
+                    chars_skipped += 1;
+                }
+            }
+            Segment::Image { .. } | Segment::Audio { .. } => {
                 // Images occupy 1 space in plain text (separator space)
                 if chars_skipped >= prefix_len {
                     result.push(seg.clone());
@@ -945,22 +1467,14 @@ fn extract_text_from_w_p(block: &str) -> String {
     let mut result = String::new();
     let mut cursor = 0;
 
-    loop {
-        let start_rel = match block[cursor..].find("<w:t") {
-            Some(idx) => idx,
-            None => break,
-        };
+    while let Some(start_rel) = block[cursor..].find("<w:t") {
         let start = cursor + start_rel;
         
         // Skip <w:tab> and similar
         let after_wt = start + "<w:t".len();
-        if after_wt < block.len() {
-            let check_end = (after_wt + 1).min(block.len());
-            let next_char = &block[after_wt..check_end];
-            if !next_char.is_empty() && next_char != ">" && next_char != " " {
-                cursor = after_wt;
-                continue;
-            }
+        if after_wt < block.len() && !ends_tag_name(block, after_wt) {
+            cursor = after_wt;
+            continue;
         }
 
         let gt_rel = match block[start..].find('>') {
@@ -1015,11 +1529,7 @@ fn extract_runs_from_w_p(block: &str) -> Vec<RunInfo> {
     let underline_re = Regex::new(r"<w:u\b[^>]*>").unwrap();
     let color_re = Regex::new(r#"<w:color[^>]*w:val=\"([^\"]+)\""#).unwrap();
 
-    loop {
-        let start_rel = match block[cursor..].find("<w:r") {
-            Some(idx) => idx,
-            None => break,
-        };
+    while let Some(start_rel) = block[cursor..].find("<w:r") {
         let start = cursor + start_rel;
 
         let end_rel = match block[start..].find("</w:r>") {
@@ -1065,22 +1575,29 @@ fn extract_runs_from_w_p(block: &str) -> Vec<RunInfo> {
 /// For each question number, returns a vector of `LabeledOptionRuns` whose
 /// `runs` contain the underline/color information for the option label
 /// (e.g. the run whose text is exactly "A." or "#A.").
-pub fn collect_labeled_option_runs(document_xml: &str) -> HashMap<u32, Vec<LabeledOptionRuns>> {
+///
+/// In bilingual mode only the first label of each letter counts: the
+/// repeated one starts the English content (see
+/// `parse_document_xml_to_parsed_doc`), so its mark is not read.
+pub fn collect_labeled_option_runs(
+    document_xml: &str,
+    options: &ParseOptions,
+) -> HashMap<u32, Vec<LabeledOptionRuns>> {
     let question_re = Regex::new(r"^(Câu|Question)\s+(\d+)\.").unwrap();
     // Chấp nhận cả trường hợp nhãn chỉ là chữ cái ("D") lẫn "D." trong cùng một run.
     // Điều này xử lý các tình huống DOCX tách "D" và "." thành hai run khác nhau.
-    let option_label_re = Regex::new(r"^(?P<label>#?[A-F])\s*(\.|$)").unwrap();
+    let option_label_re = Regex::new(&format!(
+        r"^(?P<label>#?{})\s*(\.|$)",
+        labels::label_char_class(labels::MAX_SUPPORTED_OPTIONS)
+    ))
+    .unwrap();
 
     let mut result: HashMap<u32, Vec<LabeledOptionRuns>> = HashMap::new();
 
     let mut cursor = 0;
     let mut current_question: Option<u32> = None;
 
-    loop {
-        let start_rel = match document_xml[cursor..].find("<w:p") {
-            Some(idx) => idx,
-            None => break,
-        };
+    while let Some(start_rel) = document_xml[cursor..].find("<w:p") {
         let start = cursor + start_rel;
 
         let end_rel = match document_xml[start..].find("</w:p>") {
@@ -1089,7 +1606,8 @@ pub fn collect_labeled_option_runs(document_xml: &str) -> HashMap<u32, Vec<Label
         };
         let end = start + end_rel;
 
-        let block = &document_xml[start..end];
+        let block = fields::flatten(&document_xml[start..end]);
+        let block = block.as_ref();
         let text = extract_text_from_w_p(block);
         let trimmed = text.trim();
         if trimmed.is_empty() {
@@ -1118,13 +1636,16 @@ pub fn collect_labeled_option_runs(document_xml: &str) -> HashMap<u32, Vec<Label
 
         // For this paragraph, inspect each run and collect those whose
         // text looks like a label (e.g. "A." or "#A.").
-        let run_infos = extract_runs_from_w_p(block);
+        // A paragraph that cannot be read has no label to check
+        let run_infos = panic::catch_unwind(|| extract_runs_from_w_p(block)).unwrap_or_default();
         if run_infos.is_empty() {
             cursor = end;
             continue;
         }
 
-        let entry = result.entry(q_number).or_insert_with(Vec::new);
+        let entry = result.entry(q_number).or_default();
+        // Labels read in earlier paragraphs: repeated ones are English
+        let earlier = entry.len();
 
         for run in run_infos {
             let candidate = run.text.trim();
@@ -1138,15 +1659,22 @@ pub fn collect_labeled_option_runs(document_xml: &str) -> HashMap<u32, Vec<Label
                     .map(|m| m.as_str().to_string())
                     .unwrap_or_default();
 
-                let label = if raw_label.starts_with('#') {
-                    raw_label[1..].to_string()
-                } else {
-                    raw_label.clone()
+                let label = match raw_label.strip_prefix('#') {
+                    Some(rest) => rest.to_string(),
+                    None => raw_label.clone(),
                 };
 
                 if label.is_empty() {
                     continue;
                 }
+                if options.bilingual && entry[..earlier].iter().any(|o| o.label == label) {
+                    continue;
+                }
+                if !entry.iter().any(|o| o.label == label)
+                    && !labels::starts_option(&label, entry.len(), options.max_options)
+                {
+                    continue;
+                }
 
                 // Find or create entry for this label
                 if let Some(existing) = entry.iter_mut().find(|o| o.label == label) {
@@ -1243,6 +1771,8 @@ pub fn build_segments_from_pieces(
                     raw_xml: String::new(), // Legacy function - no raw XML available
                     width_emu: 0,
                     height_emu: 0,
+                    anchor: None,
+                    ocr_text: None,
                 });
             }
         }
@@ -1250,3 +1780,303 @@ pub fn build_segments_from_pieces(
 
     segments
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    const SAMPLE: &str = concat!(
+        r#"<w:body><w:p><w:r><w:t>Câu 1. Tính </w:t></w:r><m:oMath><m:r><m:t>x²</m:t></m:r></m:oMath>"#,
+        r#"<w:r><w:t xml:space="preserve"> khi x = 2</w:t></w:r></w:p>"#,
+        r#"<w:p><w:r><w:rPr><w:u w:val="single"/></w:rPr><w:t>A.</w:t></w:r><w:r><w:t xml:space="preserve"> 4</w:t></w:r></w:p>"#,
+        r#"<w:p><w:r><w:t>B. 2</w:t></w:r></w:p>"#,
+        r#"<w:p><w:r><w:t>Câu 2. Chọn hình đúng</w:t></w:r><w:r><w:drawing><wp:inline><a:blip r:embed="rId5"/></wp:inline></w:drawing></w:r></w:p>"#,
+        r#"<w:p><w:r><w:rPr><w:color w:val="FF0000"/></w:rPr><w:t>A.</w:t></w:r><w:r><w:sym w:font="Symbol" w:char="F0B4"/></w:r></w:p>"#,
+        r#"<w:p><w:r><w:t>B. Đáp án 中文 ʃ 👍</w:t></w:r></w:p></w:body>"#
+    );
+
+    fn parse(xml: &str) -> ParsedDoc {
+        let options = ParseOptions::default();
+        collect_labeled_option_runs(xml, &options);
+        parse_document_xml_to_parsed_doc(xml, &[], &options)
+    }
+
+    /// Random edits of a valid document: truncations, multi-byte characters
+    /// right after tag names, dropped `>` and closing tags.
+    #[test]
+    fn mutated_documents_never_panic() {
+        let mut rng = StdRng::seed_from_u64(1969);
+        let boundaries: Vec<usize> = SAMPLE.char_indices().map(|(i, _)| i).collect();
+        for _ in 0..300 {
+            let mut xml = SAMPLE.to_string();
+            for _ in 0..rng.gen_range(1..4) {
+                let at = boundaries[rng.gen_range(0..boundaries.len())].min(xml.len());
+                if !xml.is_char_boundary(at) {
+                    continue;
+                }
+                match rng.gen_range(0..4) {
+                    0 => xml.truncate(at),
+                    1 => xml.insert_str(at, ["á", "中", "👍", "<w:t", "<w:r", "</w:p>"][rng.gen_range(0..6)]),
+                    2 => {
+                        if let Some(gt) = xml[at..].find('>') {
+                            xml.remove(at + gt);
+                        }
+                    }
+                    _ => {
+                        let end = xml[at..].find('<').map_or(xml.len(), |i| at + i);
+                        xml.replace_range(at..end, "");
+                    }
+                }
+            }
+            parse(&xml);
+        }
+    }
+
+    /// Damage seen in real documents: read without falling back to plain text
+    #[test]
+    fn malformed_paragraphs_are_read_in_full() {
+        let corpus = [
+            r#"<w:p><w:r><w:t>Câu 1. Số nào</w:t></w:r><w:r><w:tá</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>Câu 1. Số nào</w:t></w:r><w:r中><w:t>lớn</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>Câu 1. Tính</w:t></w:r><w:r👍<m:oMath><m:r><m:t>x</m:t></m:r></m:oMath></w:p>"#,
+            r#"<w:p><w:r><w:t>Câu 1. Chưa đóng thẻ</w:t></w:r><w:r><w:t>nữa</w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>Câu 1. Hết đột ngột <w:r"#,
+        ];
+        for damaged in corpus {
+            let xml = format!("{damaged}<w:p><w:r><w:t>A. 1</w:t></w:r></w:p><w:p><w:r><w:t>B. 2</w:t></w:r></w:p>");
+            let doc = parse(&xml);
+            assert!(doc.recovered_paragraphs.is_empty(), "{xml}");
+            assert_eq!(doc.questions.len(), 1, "{xml}");
+        }
+    }
+
+    /// "Câu 1" and its options, `between` (paragraph 3 on), then "Câu 2"
+    fn with_passage_between(between: &[&str]) -> String {
+        let paragraph = |text: &str| format!("<w:p><w:r><w:t>{text}</w:t></w:r></w:p>");
+        let mut xml: String = ["Câu 1. Số nào chẵn?", "A. 1", "B. 2"].iter().map(|text| paragraph(text)).collect();
+        xml.extend(between.iter().map(|p| p.to_string()));
+        xml.extend(["Câu 2. Theo đoạn văn, ai đến trước?", "A. An", "B. Bình"].iter().map(|text| paragraph(text)));
+        xml
+    }
+
+    const PASSAGE: &str = "<w:p><w:r><w:t>An đến lúc 7 giờ, Bình đến lúc 8 giờ.</w:t></w:r></w:p>";
+
+    /// Where the passage paragraph went: (in the last option of Câu 1, at the start of Câu 2)
+    fn passage_place(doc: &ParsedDoc) -> (bool, bool) {
+        let text = |segments: &[Segment]| segments_to_plain_text(segments);
+        (
+            text(&doc.questions[0].options[1].content).contains("An đến"),
+            text(&doc.questions[1].stem).starts_with("An đến"),
+        )
+    }
+
+    fn parse_with(xml: &str, options: ParseOptions) -> ParsedDoc {
+        parse_document_xml_to_parsed_doc(xml, &[], &options)
+    }
+
+    #[test]
+    fn paragraphs_after_the_options_continue_the_last_option_by_default() {
+        let doc = parse(&with_passage_between(&["<w:p></w:p>", PASSAGE]));
+        assert_eq!(passage_place(&doc), (true, false));
+        let paragraph = &doc.questions[0].continuation_paragraphs[0];
+        assert_eq!((paragraph.paragraph_index, paragraph.assignment), (4, ParagraphAssignment::Option));
+    }
+
+    #[test]
+    fn a_blank_line_after_the_options_starts_the_next_question() {
+        let rules = ContinuationRules {
+            blank_line_separates: true,
+            ..Default::default()
+        };
+        let options = || ParseOptions {
+            continuation: rules.clone(),
+            ..Default::default()
+        };
+        let doc = parse_with(&with_passage_between(&["<w:p></w:p>", PASSAGE]), options());
+        assert_eq!(passage_place(&doc), (false, true));
+        assert_eq!(doc.questions[1].continuation_paragraphs[0].assignment, ParagraphAssignment::NextQuestion);
+
+        // Without the blank line the passage still continues the option
+        let doc = parse_with(&with_passage_between(&[PASSAGE]), options());
+        assert_eq!(passage_place(&doc), (true, false));
+    }
+
+    #[test]
+    fn stem_styles_send_the_paragraph_to_the_next_question() {
+        let styled = "<w:p><w:pPr><w:pStyle w:val=\"Passage\"/></w:pPr><w:r><w:t>An đến lúc 7 giờ, Bình đến lúc 8 giờ.</w:t></w:r></w:p>";
+        let options = ParseOptions {
+            continuation: ContinuationRules {
+                stem_styles: vec!["Passage".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(passage_place(&parse_with(&with_passage_between(&[styled]), options.clone())), (false, true));
+        assert_eq!(passage_place(&parse_with(&with_passage_between(&[PASSAGE]), options)), (true, false));
+    }
+
+    #[test]
+    fn under_indented_paragraphs_go_to_the_next_question() {
+        let indented = "<w:p><w:pPr><w:ind w:left=\"720\"/></w:pPr><w:r><w:t>An đến lúc 7 giờ, Bình đến lúc 8 giờ.</w:t></w:r></w:p>";
+        let options = ParseOptions {
+            continuation: ContinuationRules {
+                min_option_indent: Some(360),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(passage_place(&parse_with(&with_passage_between(&[indented]), options.clone())), (true, false));
+        assert_eq!(passage_place(&parse_with(&with_passage_between(&[PASSAGE]), options)), (false, true));
+    }
+
+    /// Assignments chosen in the review screen (`reassign_paragraph`) win
+    /// over the rules
+    #[test]
+    fn overrides_take_precedence_over_the_rules() {
+        let xml = with_passage_between(&["<w:p></w:p>", PASSAGE]);
+        let with_override = |assignment, blank_line_separates| ParseOptions {
+            continuation: ContinuationRules {
+                blank_line_separates,
+                ..Default::default()
+            },
+            overrides: BTreeMap::from([(4, assignment)]),
+            ..Default::default()
+        };
+
+        let doc = parse_with(&xml, with_override(ParagraphAssignment::NextQuestion, false));
+        assert_eq!(passage_place(&doc), (false, true));
+
+        let doc = parse_with(&xml, with_override(ParagraphAssignment::Option, true));
+        assert_eq!(passage_place(&doc), (true, false));
+
+        let doc = parse_with(&xml, with_override(ParagraphAssignment::Stem, true));
+        assert_eq!(passage_place(&doc), (false, false));
+        assert!(segments_to_plain_text(&doc.questions[0].stem).ends_with("An đến lúc 7 giờ, Bình đến lúc 8 giờ."));
+        assert_eq!(doc.questions[0].continuation_paragraphs[0].assignment, ParagraphAssignment::Stem);
+    }
+
+    #[test]
+    fn locked_option_is_not_the_correct_answer() {
+        let paragraph = |text: &str| format!("<w:p><w:r><w:t>{text}</w:t></w:r></w:p>");
+        let xml: String = ["Câu 1. Chọn câu đúng", "A. 1", "B. 2", "C. 3", "#D. Cả ba ý trên"]
+            .iter()
+            .map(|text| paragraph(text))
+            .collect();
+        let doc = parse(&xml);
+        let question = &doc.questions[0];
+        let locked: Vec<&str> = question.options.iter().filter(|o| o.locked).map(|o| o.label.as_str()).collect();
+        assert_eq!(locked, vec!["D"]);
+        assert!(question.correct_label.is_empty());
+    }
+
+    #[test]
+    fn options_past_the_maximum_are_kept_for_the_validator() {
+        let paragraph = |text: &str| format!("<w:p><w:r><w:t>{text}</w:t></w:r></w:p>");
+        let xml: String = ["Câu 1. Số nào lớn nhất?", "A. 1", "B. 2", "C. 3", "D. 4", "E. 5", "I. Không phải phương án"]
+            .iter()
+            .map(|text| paragraph(text))
+            .collect();
+        let options = ParseOptions {
+            max_options: 4,
+            ..Default::default()
+        };
+        let doc = parse_document_xml_to_parsed_doc(&xml, &[], &options);
+        let labels: Vec<&str> = doc.questions[0].options.iter().map(|o| o.label.as_str()).collect();
+        assert_eq!(labels, ["A", "B", "C", "D", "E"]);
+        assert_eq!(collect_labeled_option_runs(&xml, &options)[&1].len(), 5);
+    }
+
+    #[test]
+    fn english_labels_are_not_read_for_the_correct_mark() {
+        let paragraph = |text: &str| format!("<w:p><w:r><w:t>{text}</w:t></w:r></w:p>");
+        let marked = |label: &str, text: &str| {
+            format!("<w:p><w:r><w:rPr><w:u w:val=\"single\"/></w:rPr><w:t>{label}</w:t></w:r><w:r><w:t> {text}</w:t></w:r></w:p>")
+        };
+        let xml = [
+            paragraph("Câu 1. Thủ đô của Pháp?"),
+            paragraph("A. Lyon"),
+            marked("B.", "Paris"),
+            paragraph("Question 1. The capital of France?"),
+            marked("A.", "Lyon"),
+            marked("B.", "Paris"),
+        ]
+        .concat();
+        let options = ParseOptions {
+            bilingual: true,
+            ..Default::default()
+        };
+        let runs = collect_labeled_option_runs(&xml, &options);
+        let correct = crate::docx::validator::detect_correct_label_for_question(1, &runs[&1]).unwrap();
+        assert_eq!(correct, "B");
+    }
+
+    #[test]
+    fn images_without_an_asset_are_kept_and_warned() {
+        let xml = concat!(
+            "<w:p><w:r><w:t>Câu 1. Hình nào?</w:t></w:r>",
+            "<w:r><w:drawing><wp:extent cx=\"100\" cy=\"100\"/></w:drawing></w:r></w:p>",
+            "<w:p><w:r><w:t>A. 1</w:t></w:r></w:p><w:p><w:r><w:t>B. 2</w:t></w:r></w:p>",
+        );
+        let doc = parse(xml);
+        let images: Vec<&Segment> = doc.questions[0]
+            .stem
+            .iter()
+            .filter(|s| matches!(s, Segment::Image { .. }))
+            .collect();
+        assert!(matches!(images[..], [Segment::Image { asset_path, .. }] if asset_path.is_empty()));
+        assert!(crate::docx::validator::validate_images(&doc.questions[0]).is_err());
+    }
+
+    #[test]
+    fn part_headings_hold_the_questions_after_them() {
+        let paragraph = |text: &str| format!("<w:p><w:r><w:t>{text}</w:t></w:r></w:p>");
+        let xml: String = [
+            "PHẦN I. TRẮC NGHIỆM (1 điểm)",
+            "Câu 1. (0,5 điểm) Số nào lớn nhất?",
+            "A. 1",
+            "B. 2",
+            "Câu 2. (0,5 điểm) Số nào nhỏ nhất?",
+            "A. 1",
+            "B. 2",
+            "II. PHẦN TỰ LUẬN: 2 điểm",
+            "Câu 3. Số nào chẵn?",
+            "A. 1",
+            "B. 2",
+        ]
+        .iter()
+        .map(|text| paragraph(text))
+        .collect();
+        let doc = parse(&xml);
+        assert_eq!(doc.questions.len(), 3);
+        assert_eq!(doc.parts.len(), 2);
+        assert_eq!(doc.parts[0].questions, vec![1, 2]);
+        assert_eq!(doc.parts[0].points, Some(1.0));
+        assert_eq!(doc.parts[1].questions, vec![3]);
+        assert_eq!(doc.parts[1].points, Some(2.0));
+        assert_eq!(doc.questions[1].points, Some(0.5));
+        // The heading is not part of the question before it
+        assert_eq!(doc.questions[1].options[1].content.len(), 1);
+    }
+
+    /// Collected real-world exams: `SIROMIX_PARSER_CORPUS=<folder of .docx>
+    /// cargo test corpus`. Skipped when the variable is not set.
+    #[test]
+    fn corpus_documents_never_panic() {
+        let Some(dir) = std::env::var_os("SIROMIX_PARSER_CORPUS") else {
+            return;
+        };
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().and_then(|e| e.to_str()) != Some("docx") {
+                continue;
+            }
+            let Ok(xml) = crate::docx::read::read_document_xml(&path) else {
+                continue;
+            };
+            let doc = parse(&xml);
+            println!("{}: {} question(s), {} recovered", path.display(), doc.questions.len(), doc.recovered_paragraphs.len());
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// src-tauri/src/docx/partial.rs
+//! Partial analysis (`ParseOptions::allow_partial`): the questions with
+//! errors are dropped and the rest of the document is kept, with the groups
+//! and parts narrowed to the questions left.
+
+use super::model::ParsedDoc;
+
+/// Drop the questions numbered in `failed` from `doc`. Returns the numbers
+/// dropped, or nothing - leaving `doc` as it is - when the document cannot
+/// be kept in part: an error belongs to no question of the document (e.g.
+/// E024) or every question failed.
+pub fn drop_failed(doc: &mut ParsedDoc, failed: &[u32]) -> Vec<u32> {
+    let numbers: Vec<u32> = doc.questions.iter().map(|q| q.number).collect();
+    let document_level = failed.iter().any(|n| !numbers.contains(n));
+    let skipped: Vec<u32> = numbers.iter().copied().filter(|n| failed.contains(n)).collect();
+    if document_level || skipped.len() == numbers.len() {
+        return Vec::new();
+    }
+
+    doc.questions.retain(|q| !skipped.contains(&q.number));
+    let kept: Vec<u32> = doc.questions.iter().map(|q| q.number).collect();
+    for group in &mut doc.groups {
+        group.questions.retain(|n| kept.contains(n));
+    }
+    doc.groups.retain(|g| !g.questions.is_empty());
+    for part in &mut doc.parts {
+        part.questions.retain(|n| kept.contains(n));
+    }
+    skipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::manual_answers;
+    use crate::docx::parser::{collect_labeled_option_runs, parse_document_xml_to_parsed_doc, ParseOptions};
+    use crate::docx::validator::{self, ValidationErrorCode};
+
+    /// Câu 1 marked B; Câu 2, 3 and 4 unmarked
+    fn document() -> String {
+        let paragraph = |text: &str| format!("<w:p><w:r><w:t>{}</w:t></w:r></w:p>", text);
+        let marked = |text: &str| format!("<w:p><w:r><w:rPr><w:u w:val=\"single\"/></w:rPr><w:t>{}</w:t></w:r></w:p>", text);
+        let mut xml = String::new();
+        for number in 1..=4 {
+            xml.push_str(&paragraph(&format!("Câu {}. Số nào lớn hơn {}?", number, number)));
+            xml.push_str(&paragraph("A. 0"));
+            xml.push_str(&if number == 1 { marked("B. 9") } else { paragraph("B. 9") });
+        }
+        xml
+    }
+
+    /// The answer step of the analysis: (question, error code) of the
+    /// questions still without an answer, after the manual answers
+    fn answer(doc: &mut ParsedDoc, xml: &str, options: &ParseOptions) -> Vec<(u32, &'static str)> {
+        let runs = collect_labeled_option_runs(xml, options);
+        let mut errors = Vec::new();
+        for q in &mut doc.questions {
+            match validator::detect_correct_label_for_question(q.number, &runs[&q.number]) {
+                Ok(label) => q.correct_label = label,
+                Err(err) if matches!(err.code, ValidationErrorCode::E020CorrectMarkMissing) => {
+                    match manual_answers::apply(q, options) {
+                        Some(Ok(_)) => {}
+                        Some(Err(err)) => errors.push((q.number, err.code.as_str())),
+                        None => errors.push((q.number, err.code.as_str())),
+                    }
+                }
+                Err(err) => errors.push((q.number, err.code.as_str())),
+            }
+        }
+        errors
+    }
+
+    #[test]
+    fn unanswered_questions_are_reported_and_skipped() {
+        let xml = document();
+        let mut options = ParseOptions {
+            allow_partial: true,
+            ..Default::default()
+        };
+        manual_answers::choose(&mut options, 2, Some("A"));
+        manual_answers::choose(&mut options, 3, Some("C"));
+        let mut doc = parse_document_xml_to_parsed_doc(&xml, &[], &options);
+
+        let errors = answer(&mut doc, &xml, &options);
+        // Câu 3 chose an option it does not have; Câu 4 has no answer at all
+        assert_eq!(errors, vec![(3, "E025_MANUAL_ANSWER_INVALID"), (4, "E020_CORRECT_MARK_MISSING")]);
+
+        let failed: Vec<u32> = errors.iter().map(|(n, _)| *n).collect();
+        assert_eq!(drop_failed(&mut doc, &failed), vec![3, 4]);
+        let answers: Vec<(u32, &str)> = doc.questions.iter().map(|q| (q.number, q.correct_label.as_str())).collect();
+        assert_eq!(answers, vec![(1, "B"), (2, "A")]);
+    }
+
+    #[test]
+    fn nothing_is_kept_when_no_question_is_left_or_the_error_is_not_a_question() {
+        let xml = document();
+        let options = ParseOptions::default();
+        let mut doc = parse_document_xml_to_parsed_doc(&xml, &[], &options);
+        assert!(drop_failed(&mut doc, &[1, 2, 3, 4]).is_empty());
+        assert!(drop_failed(&mut doc, &[2, 0]).is_empty());
+        assert_eq!(doc.questions.len(), 4);
+    }
+}
@@ -0,0 +1,565 @@
+// src-tauri/src/docx/parts.rs
+//! Content that lives outside `word/document.xml` and that the parser would
+//! otherwise skip:
+//! - `w:altChunk`: a part imported as-is by Word (HTML, MHT, plain text or
+//!   another .docx), typically pasted from a web page or generated by an LMS
+//! - `w:subDoc`: a section of a master document, stored in a separate file
+//!
+//! Both are merged into the document XML before parsing. HTML and text become
+//! plain paragraphs (bold/italic/underline kept, so correct-answer underlines
+//! still count); .docx content is spliced as is, minus its pictures, whose
+//! relationships belong to another package. Subdocuments are external files:
+//! they are copied next to `source.docx` at import (`copy_subdocuments`).
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Seek};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use zip::read::ZipArchive;
+
+use super::AppError;
+
+/// Folder of the job workspace holding the subdocuments of `source.docx`
+pub const SUBDOCS_DIR: &str = "subdocs";
+
+const SUBDOCUMENT_TYPE: &str = "/subDocument";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartKind {
+    AltChunk,
+    SubDocument,
+}
+
+/// What happened to one altChunk or subdocument
+#[derive(Debug, Clone)]
+pub struct PartNotice {
+    pub kind: PartKind,
+    /// Relationship target, e.g. "afchunk1.mht" or "file:///C:/De/Phan2.docx"
+    pub target: String,
+    /// Last question heading before the part (`None` = before the first
+    /// question)
+    pub after_question: Option<u32>,
+    /// The content was merged; otherwise it is missing from the analysis
+    pub merged: bool,
+    /// Pictures of merged .docx content that could not be carried over
+    pub images_dropped: usize,
+    /// Byte range of the placeholder in `word/document.xml`
+    pub source_range: Range<usize>,
+    /// Paragraphs in place of the placeholder in the merged XML, counted
+    /// like the parser walks them
+    pub paragraph_count: usize,
+}
+
+/// One relationship of `word/_rels/document.xml.rels`
+pub(super) struct Relationship {
+    kind: String,
+    pub(super) target: String,
+    pub(super) external: bool,
+}
+
+/// Merge the altChunks and subdocuments of the `.docx` at `docx_path` into
+/// `document_xml`. Subdocuments are looked up by file name in `subdocs_dir`.
+pub fn merge(
+    docx_path: &Path,
+    document_xml: &str,
+    subdocs_dir: &Path,
+) -> Result<(String, Vec<PartNotice>), AppError> {
+    if !document_xml.contains("<w:altChunk") && !document_xml.contains("<w:subDoc") {
+        return Ok((document_xml.to_string(), Vec::new()));
+    }
+
+    let mut archive = ZipArchive::new(File::open(docx_path)?)?;
+    let rels = relationships(&mut archive);
+
+    let alt_chunk_re = Regex::new(r#"(?s)<w:altChunk\b[^>]*?r:id="([^"]+)"[^>]*?(?:/>|>.*?</w:altChunk>)"#).unwrap();
+    let sub_doc_re = Regex::new(r#"<w:subDoc\b[^>]*?r:id="([^"]+)"[^>]*/>"#).unwrap();
+
+    // (start, end, kind, target, converted content), in document order
+    let mut replacements = Vec::new();
+    for caps in alt_chunk_re.captures_iter(document_xml) {
+        let whole = caps.get(0).unwrap();
+        let rel = rels.get(&caps[1]);
+        let target = rel.map(|r| r.target.clone()).unwrap_or_else(|| caps[1].to_string());
+        let content = rel
+            .filter(|r| !r.external)
+            .and_then(|r| read_entry(&mut archive, &part_name(&r.target)))
+            .and_then(|data| alt_chunk_to_xml(&target, &data));
+        replacements.push((whole.start(), whole.end(), PartKind::AltChunk, target, content));
+    }
+    for caps in sub_doc_re.captures_iter(document_xml) {
+        let whole = caps.get(0).unwrap();
+        // The subdocument replaces its whole placeholder paragraph
+        let start = ["<w:p>", "<w:p "]
+            .iter()
+            .filter_map(|tag| document_xml[..whole.start()].rfind(tag))
+            .max()
+            .unwrap_or(whole.start());
+        let end = document_xml[whole.end()..]
+            .find("</w:p>")
+            .map_or(whole.end(), |i| whole.end() + i + "</w:p>".len());
+        let rel = rels.get(&caps[1]);
+        let target = rel.map(|r| r.target.clone()).unwrap_or_else(|| caps[1].to_string());
+        let content = external_file_name(&target)
+            .map(|name| subdocs_dir.join(name))
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|data| docx_body(&data));
+        replacements.push((start, end, PartKind::SubDocument, target, content));
+    }
+    replacements.sort_by_key(|r| r.0);
+
+    let heading_re = Regex::new(r"^\s*(?:Câu|Question)\s+(\d+)\.").unwrap();
+    let mut merged = String::with_capacity(document_xml.len());
+    let mut notices = Vec::new();
+    let mut cursor = 0;
+    for (start, end, kind, target, content) in replacements {
+        if start < cursor {
+            continue;
+        }
+        merged.push_str(&document_xml[cursor..start]);
+        let mut notice = PartNotice {
+            kind,
+            target,
+            after_question: last_question_heading(&document_xml[..start], &heading_re),
+            merged: content.is_some(),
+            images_dropped: 0,
+            source_range: start..end,
+            paragraph_count: 0,
+        };
+        let inserted = match content {
+            Some(xml) => {
+                let (xml, dropped) = strip_pictures(&xml);
+                notice.images_dropped = dropped;
+                xml
+            }
+            // Keep the placeholder: Word still shows it, and paragraph
+            // positions stay the same
+            None => document_xml[start..end].to_string(),
+        };
+        notice.paragraph_count = paragraph_count(&inserted);
+        merged.push_str(&inserted);
+        notices.push(notice);
+        cursor = end;
+    }
+    merged.push_str(&document_xml[cursor..]);
+
+    Ok((merged, notices))
+}
+
+/// Targets of the subdocument relationships of the `.docx` at `docx_path`.
+pub fn subdocument_targets(docx_path: &Path) -> Vec<String> {
+    let Some(mut archive) = File::open(docx_path).ok().and_then(|f| ZipArchive::new(f).ok()) else {
+        return Vec::new();
+    };
+    relationships(&mut archive)
+        .into_values()
+        .filter(|r| r.kind.ends_with(SUBDOCUMENT_TYPE))
+        .map(|r| r.target)
+        .collect()
+}
+
+/// Copy the subdocuments of `source` (an imported `.docx`) into
+/// `<workspace>/subdocs/`, resolving relative targets against the folder of
+/// `source`. Returns the targets that could not be found.
+pub fn copy_subdocuments(source: &Path, docx_path: &Path, workspace_dir: &Path) -> Vec<String> {
+    let base_dir = source.parent().unwrap_or(Path::new("."));
+    let mut missing = Vec::new();
+    for target in subdocument_targets(docx_path) {
+        let copied = resolve_external(&target, base_dir).zip(external_file_name(&target)).and_then(|(path, name)| {
+            let dir = workspace_dir.join(SUBDOCS_DIR);
+            fs::create_dir_all(&dir).ok()?;
+            fs::copy(path, dir.join(name)).ok()
+        });
+        if copied.is_none() {
+            missing.push(target);
+        }
+    }
+    missing
+}
+
+pub(super) fn relationships<R: Read + Seek>(archive: &mut ZipArchive<R>) -> HashMap<String, Relationship> {
+    let Some(data) = read_entry(archive, "word/_rels/document.xml.rels") else {
+        return HashMap::new();
+    };
+    let xml = String::from_utf8_lossy(&data);
+    let rel_re = Regex::new(r"<Relationship\s([^>]*?)/?>").unwrap();
+    let attr_re = Regex::new(r#"(\w+)="([^"]*)""#).unwrap();
+    rel_re
+        .captures_iter(&xml)
+        .filter_map(|caps| {
+            let attrs: HashMap<&str, String> = attr_re
+                .captures_iter(caps.get(1).unwrap().as_str())
+                .map(|a| (a.get(1).unwrap().as_str(), decode_entities(&a[2])))
+                .collect();
+            Some((
+                attrs.get("Id")?.clone(),
+                Relationship {
+                    kind: attrs.get("Type").cloned().unwrap_or_default(),
+                    target: attrs.get("Target")?.clone(),
+                    external: attrs.get("TargetMode").is_some_and(|m| m == "External"),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Number of `<w:p` … `</w:p>` blocks in `xml`, found the way the parser
+/// walks the document
+pub(super) fn paragraph_count(xml: &str) -> usize {
+    let mut count = 0;
+    let mut cursor = 0;
+    while let Some(start_rel) = xml[cursor..].find("<w:p") {
+        let start = cursor + start_rel;
+        let Some(end_rel) = xml[start..].find("</w:p>") else {
+            break;
+        };
+        cursor = start + end_rel + "</w:p>".len();
+        count += 1;
+    }
+    count
+}
+
+/// Content of a package part; `None` when missing, unreadable or over
+/// `read::MAX_XML_PART_BYTES`
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Option<Vec<u8>> {
+    let entry = archive.by_name(name).ok()?;
+    match super::read::read_part(entry, name, super::read::MAX_XML_PART_BYTES) {
+        Ok(data) => Some(data),
+        Err(e) => {
+            eprintln!("[Parts] Skipping {}: {:?}", name, e);
+            None
+        }
+    }
+}
+
+/// Package part name of a relationship target of `word/document.xml`
+pub(super) fn part_name(target: &str) -> String {
+    match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_string(),
+        None => format!("word/{}", target),
+    }
+}
+
+/// Path of an external target: a `file://` URI or a path, absolute or
+/// relative to `base_dir`.
+fn resolve_external(target: &str, base_dir: &Path) -> Option<PathBuf> {
+    let path = match target.strip_prefix("file:///").or_else(|| target.strip_prefix("file://")) {
+        Some(rest) => percent_decode(rest),
+        None => percent_decode(target),
+    };
+    let path = PathBuf::from(path);
+    let path = if path.is_absolute() { path } else { base_dir.join(path) };
+    path.is_file().then_some(path)
+}
+
+fn external_file_name(target: &str) -> Option<String> {
+    let name = percent_decode(target.rsplit(['/', '\\']).next()?);
+    (!name.is_empty() && name != "..").then_some(name)
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Paragraphs of an altChunk, by the format its target extension names.
+/// `None` for formats that cannot be converted (RTF, ...).
+fn alt_chunk_to_xml(target: &str, data: &[u8]) -> Option<String> {
+    let ext = target.rsplit('.').next().unwrap_or_default().to_ascii_lowercase();
+    match ext.as_str() {
+        "htm" | "html" | "xhtml" => Some(html_to_paragraphs(&String::from_utf8_lossy(data))),
+        "mht" | "mhtml" => Some(html_to_paragraphs(&mht_html(data)?)),
+        "txt" => Some(
+            String::from_utf8_lossy(data)
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| paragraph_xml(&[(line.trim().to_string(), RunStyle::default())]))
+                .collect(),
+        ),
+        "docx" | "docm" => docx_body(data),
+        _ => None,
+    }
+}
+
+/// Body of the `word/document.xml` of a `.docx`, without its section
+/// properties.
+fn docx_body(data: &[u8]) -> Option<String> {
+    let mut archive = ZipArchive::new(Cursor::new(data)).ok()?;
+    let xml = String::from_utf8(read_entry(&mut archive, "word/document.xml")?).ok()?;
+    let start = xml.find("<w:body>")? + "<w:body>".len();
+    let end = xml.rfind("</w:body>")?;
+    let body = &xml[start..end];
+    let body = match body.rfind("<w:sectPr") {
+        Some(sect) if body[sect..].trim_end().ends_with("</w:sectPr>") => &body[..sect],
+        _ => body,
+    };
+    Some(body.to_string())
+}
+
+/// Remove pictures (their relationship ids point into another package).
+fn strip_pictures(xml: &str) -> (String, usize) {
+    let picture_re = Regex::new(r"(?s)<w:drawing>.*?</w:drawing>|<w:object\b.*?</w:object>|<w:pict>.*?</w:pict>").unwrap();
+    let dropped = picture_re.find_iter(xml).count();
+    (picture_re.replace_all(xml, "").into_owned(), dropped)
+}
+
+/// HTML part of an MHT (MIME) archive, decoded.
+fn mht_html(data: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(data);
+    let lower = text.to_ascii_lowercase();
+    let type_at = lower.find("content-type: text/html")?;
+    let headers_end = lower[type_at..].find("\r\n\r\n").or_else(|| lower[type_at..].find("\n\n"))? + type_at;
+    let headers = &lower[type_at..headers_end];
+    let body_start = headers_end + if lower[headers_end..].starts_with("\r\n\r\n") { 4 } else { 2 };
+    let body_end = lower[body_start..].find("\n--").map_or(text.len(), |i| body_start + i);
+    let body = &text[body_start..body_end];
+
+    let bytes = if headers.contains("quoted-printable") {
+        quoted_printable_decode(body)
+    } else if headers.contains("base64") {
+        base64_decode(body)?
+    } else {
+        body.as_bytes().to_vec()
+    };
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn quoted_printable_decode(text: &str) -> Vec<u8> {
+    let text = text.replace("=\r\n", "").replace("=\n", "");
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'=', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let value = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    };
+    let digits: Vec<u8> = text
+        .bytes()
+        .filter(|c| !c.is_ascii_whitespace() && *c != b'=')
+        .map(value)
+        .collect::<Option<_>>()?;
+    let mut out = Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.chunks(4) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, d)| n | (*d as u32) << (18 - 6 * i));
+        out.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(out)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct RunStyle {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+/// Paragraphs of an HTML fragment: one per block element or line break,
+/// keeping bold, italic and underline.
+fn html_to_paragraphs(html: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    let body = match (lower.find("<body"), lower.rfind("</body>")) {
+        (Some(start), Some(end)) if start < end => {
+            let content = start + lower[start..].find('>').map_or(0, |i| i + 1);
+            &html[content..end]
+        }
+        _ => html,
+    };
+    let hidden_re = Regex::new(r"(?is)<(script|style|head)\b.*?</(script|style|head)>|<!--.*?-->").unwrap();
+    let body = hidden_re.replace_all(body, "");
+    let token_re = Regex::new(r"(?s)<(/?)([a-zA-Z][a-zA-Z0-9]*)[^>]*>|([^<]+)").unwrap();
+
+    let mut xml = String::new();
+    let mut runs: Vec<(String, RunStyle)> = Vec::new();
+    let (mut bold, mut italic, mut underline) = (0usize, 0usize, 0usize);
+    for caps in token_re.captures_iter(&body) {
+        if let Some(text) = caps.get(3) {
+            let text = decode_html_entities(text.as_str());
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            let spaced = caps[3].starts_with(char::is_whitespace) || caps[3].ends_with(char::is_whitespace);
+            if text.is_empty() && !spaced {
+                continue;
+            }
+            let style = RunStyle {
+                bold: bold > 0,
+                italic: italic > 0,
+                underline: underline > 0,
+            };
+            let leading = if caps[3].starts_with(char::is_whitespace) { " " } else { "" };
+            let trailing = if caps[3].ends_with(char::is_whitespace) && !text.is_empty() { " " } else { "" };
+            runs.push((format!("{}{}{}", leading, text, trailing), style));
+            continue;
+        }
+        let closing = &caps[1] == "/";
+        let counter = match caps[2].to_ascii_lowercase().as_str() {
+            "b" | "strong" => Some(&mut bold),
+            "i" | "em" => Some(&mut italic),
+            "u" | "ins" => Some(&mut underline),
+            "p" | "div" | "li" | "br" | "tr" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "table" | "ul" | "ol" => {
+                flush_paragraph(&mut xml, &mut runs);
+                None
+            }
+            _ => None,
+        };
+        if let Some(counter) = counter {
+            *counter = if closing { counter.saturating_sub(1) } else { *counter + 1 };
+        }
+    }
+    flush_paragraph(&mut xml, &mut runs);
+    xml
+}
+
+fn flush_paragraph(xml: &mut String, runs: &mut Vec<(String, RunStyle)>) {
+    if runs.iter().any(|(text, _)| !text.trim().is_empty()) {
+        // Whitespace at the edges of the paragraph is not content
+        if let Some(first) = runs.first_mut() {
+            first.0 = first.0.trim_start().to_string();
+        }
+        if let Some(last) = runs.last_mut() {
+            last.0 = last.0.trim_end().to_string();
+        }
+        xml.push_str(&paragraph_xml(runs));
+    }
+    runs.clear();
+}
+
+fn paragraph_xml(runs: &[(String, RunStyle)]) -> String {
+    let mut xml = String::from("<w:p>");
+    for (text, style) in runs.iter().filter(|(text, _)| !text.is_empty()) {
+        xml.push_str("<w:r>");
+        if *style != RunStyle::default() {
+            xml.push_str("<w:rPr>");
+            if style.bold {
+                xml.push_str("<w:b/>");
+            }
+            if style.italic {
+                xml.push_str("<w:i/>");
+            }
+            if style.underline {
+                xml.push_str("<w:u w:val=\"single\"/>");
+            }
+            xml.push_str("</w:rPr>");
+        }
+        xml.push_str(&format!("<w:t xml:space=\"preserve\">{}</w:t></w:r>", encode_entities(text)));
+    }
+    xml.push_str("</w:p>");
+    xml
+}
+
+/// Number of the last "Câu N." heading among the paragraphs of `xml`.
+fn last_question_heading(xml: &str, heading_re: &Regex) -> Option<u32> {
+    let text_re = Regex::new(r"<w:t(?:\s[^>]*)?>([^<]*)</w:t>").unwrap();
+    xml.rsplit("</w:p>").find_map(|paragraph| {
+        let text: String = text_re.captures_iter(paragraph).map(|c| decode_entities(&c[1])).collect();
+        heading_re.captures(&text).and_then(|c| c[1].parse().ok())
+    })
+}
+
+fn decode_html_entities(text: &str) -> String {
+    let entity_re = Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap();
+    entity_re
+        .replace_all(text, |caps: &regex::Captures| {
+            let name = &caps[1];
+            let code = if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok()
+            } else if let Some(dec) = name.strip_prefix('#') {
+                dec.parse().ok()
+            } else {
+                match name {
+                    "amp" => Some('&' as u32),
+                    "lt" => Some('<' as u32),
+                    "gt" => Some('>' as u32),
+                    "quot" => Some('"' as u32),
+                    "apos" => Some('\'' as u32),
+                    "nbsp" => Some(' ' as u32),
+                    _ => None,
+                }
+            };
+            code.and_then(char::from_u32)
+                .map(String::from)
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn encode_entities(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn merges_html_alt_chunks_after_their_question() {
+        let dir = std::env::temp_dir().join(format!("siromix-parts-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let docx_path = dir.join("source.docx");
+
+        let mut zip = zip::ZipWriter::new(File::create(&docx_path).unwrap());
+        let options = zip::write::FileOptions::default();
+        zip.start_file("word/_rels/document.xml.rels", options).unwrap();
+        zip.write_all(br#"<Relationships><Relationship Id="rId9" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/aFChunk" Target="afchunk1.htm"/></Relationships>"#).unwrap();
+        zip.start_file("word/afchunk1.htm", options).unwrap();
+        zip.write_all(b"<html><body><p><u>A.</u> 4&nbsp;m</p><p>B. 5 m</p></body></html>").unwrap();
+        zip.finish().unwrap();
+
+        let document_xml = r#"<w:body><w:p><w:r><w:t>Câu 3. Chiều dài?</w:t></w:r></w:p><w:altChunk r:id="rId9"/></w:body>"#;
+        let (merged, notices) = merge(&docx_path, document_xml, &dir.join(SUBDOCS_DIR)).unwrap();
+
+        assert!(merged.contains(r#"<w:r><w:rPr><w:u w:val="single"/></w:rPr><w:t xml:space="preserve">A.</w:t></w:r><w:r><w:t xml:space="preserve"> 4 m</w:t></w:r>"#));
+        assert!(merged.contains("B. 5 m"));
+        assert!(!merged.contains("altChunk"));
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].after_question, Some(3));
+        assert!(notices[0].merged);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,176 @@
+// src-tauri/src/docx/passthrough.rs
+//! Helpers for the raw passthrough export: the original `<w:p>` blocks of a
+//! question are spliced into the output instead of regenerated runs.
+//!
+//! Only two things are rewritten in a source paragraph:
+//! - the leading "Câu N." / "A." prefix, replaced by the renumbered heading
+//!   or relabelled option run
+//! - relationship ids (`r:embed`, `r:id`), remapped to the output package.
+//!   A paragraph referencing a relationship we did not copy (OLE payloads,
+//!   hyperlinks, charts) cannot be passed through and the caller falls back
+//!   to the regenerated layout.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+/// Relationship attributes that may point into `word/_rels/document.xml.rels`
+const REL_ATTRIBUTES: [&str; 3] = ["r:embed=\"", "r:id=\"", "r:link=\""];
+
+/// Relationship id of the picture an image segment was parsed from: the
+/// `a:blip` of a drawing, or the `v:imagedata` preview of an OLE object.
+pub fn image_relationship_id(raw_xml: &str) -> Option<String> {
+    let (tag_start, attr) = match raw_xml.find("<a:blip ") {
+        Some(idx) => (idx, "r:embed=\""),
+        None => (raw_xml.find("<v:imagedata ")?, "r:id=\""),
+    };
+    let tag = &raw_xml[tag_start..tag_start + raw_xml[tag_start..].find('>')?];
+    let start = tag.find(attr)? + attr.len();
+    let len = tag[start..].find('"')?;
+    Some(tag[start..start + len].to_string())
+}
+
+/// Rewrite every relationship id in `xml` through `rel_map`.
+/// Returns `None` if some id has no counterpart in the output package.
+pub fn remap_relationships(xml: &str, rel_map: &HashMap<String, String>) -> Option<String> {
+    let mut result = xml.to_string();
+    for attr in REL_ATTRIBUTES {
+        let mut out = String::with_capacity(result.len());
+        let mut cursor = 0;
+        while let Some(rel) = result[cursor..].find(attr) {
+            let start = cursor + rel + attr.len();
+            let len = result[start..].find('"')?;
+            let new_id = rel_map.get(&result[start..start + len])?;
+            out.push_str(&result[cursor..start]);
+            out.push_str(new_id);
+            cursor = start + len;
+        }
+        out.push_str(&result[cursor..]);
+        result = out;
+    }
+    Some(result)
+}
+
+/// Remove the leading prefix matched by `prefix_re` (plus surrounding
+/// whitespace) from the text of a paragraph, editing the `<w:t>` elements in
+/// place so run formatting is untouched. Returns `None` if the paragraph text
+/// does not start with the prefix.
+pub fn strip_text_prefix(paragraph_xml: &str, prefix_re: &Regex) -> Option<String> {
+    let text = paragraph_text(paragraph_xml);
+    let leading_ws = text.chars().take_while(|c| c.is_whitespace()).count();
+    let rest: String = text.chars().skip(leading_ws).collect();
+    let prefix = prefix_re.find(&rest).filter(|m| m.start() == 0)?;
+    let after = &rest[prefix.end()..];
+    let trailing_ws = after.chars().take_while(|c| c.is_whitespace()).count();
+    let mut to_remove = leading_ws + rest[..prefix.end()].chars().count() + trailing_ws;
+
+    let mut out = String::with_capacity(paragraph_xml.len());
+    let mut cursor = 0;
+    while to_remove > 0 {
+        let Some((content_start, content_end)) = next_text_element(paragraph_xml, cursor) else {
+            break;
+        };
+        let content = &paragraph_xml[content_start..content_end];
+        let chars = decode_entities(content).chars().count();
+        out.push_str(&paragraph_xml[cursor..content_start]);
+        if chars <= to_remove {
+            to_remove -= chars;
+        } else {
+            let kept: String = decode_entities(content).chars().skip(to_remove).collect();
+            out.push_str(&encode_entities(&kept));
+            to_remove = 0;
+        }
+        cursor = content_end;
+    }
+    out.push_str(&paragraph_xml[cursor..]);
+    Some(out)
+}
+
+/// Insert `run_xml` as the first run of the paragraph (after `<w:pPr>`).
+pub fn insert_leading_run(paragraph_xml: &str, run_xml: &str) -> String {
+    let insert_at = paragraph_xml
+        .find("</w:pPr>")
+        .map(|idx| idx + "</w:pPr>".len())
+        .or_else(|| paragraph_xml.find('>').map(|idx| idx + 1))
+        .unwrap_or(0);
+    format!(
+        "{}{}{}",
+        &paragraph_xml[..insert_at],
+        run_xml,
+        &paragraph_xml[insert_at..]
+    )
+}
+
+/// Concatenated text of all `<w:t>` elements in a paragraph.
+fn paragraph_text(paragraph_xml: &str) -> String {
+    let mut text = String::new();
+    let mut cursor = 0;
+    while let Some((start, end)) = next_text_element(paragraph_xml, cursor) {
+        text.push_str(&decode_entities(&paragraph_xml[start..end]));
+        cursor = end;
+    }
+    text
+}
+
+/// Byte range of the content of the next `<w:t>` / `<w:t ...>` element.
+fn next_text_element(xml: &str, from: usize) -> Option<(usize, usize)> {
+    let mut cursor = from;
+    loop {
+        let start = cursor + xml[cursor..].find("<w:t")?;
+        let after = start + "<w:t".len();
+        if !matches!(xml.as_bytes().get(after), Some(b'>') | Some(b' ')) {
+            // <w:tab>, <w:tbl>, ...
+            cursor = after;
+            continue;
+        }
+        let content_start = start + xml[start..].find('>')? + 1;
+        if xml[..content_start].ends_with("/>") {
+            cursor = content_start;
+            continue;
+        }
+        let content_end = content_start + xml[content_start..].find("</w:t>")?;
+        return Some((content_start, content_end));
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn encode_entities(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_prefix_across_runs() {
+        let re = Regex::new(r"^(Câu|Question)\s+\d+\.").unwrap();
+        let xml = r#"<w:p><w:pPr/><w:r><w:rPr><w:b/></w:rPr><w:t>Câu 1</w:t></w:r><w:r><w:t xml:space="preserve">. Tính x &amp; y</w:t></w:r></w:p>"#;
+
+        let stripped = strip_text_prefix(xml, &re).unwrap();
+        assert_eq!(paragraph_text(&stripped), "Tính x & y");
+        assert!(stripped.contains("<w:b/>"));
+
+        let with_heading = insert_leading_run(&stripped, "<w:r><w:t>Câu 7. </w:t></w:r>");
+        assert_eq!(paragraph_text(&with_heading), "Câu 7. Tính x & y");
+    }
+
+    #[test]
+    fn remaps_known_relationships_only() {
+        let mut map = HashMap::new();
+        map.insert("rId8".to_string(), "rId2".to_string());
+
+        let xml = r#"<a:blip r:embed="rId8"/>"#;
+        assert_eq!(remap_relationships(xml, &map).unwrap(), r#"<a:blip r:embed="rId2"/>"#);
+        assert!(remap_relationships(r#"<o:OLEObject r:id="rId9"/>"#, &map).is_none());
+    }
+}
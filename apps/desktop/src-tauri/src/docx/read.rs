@@ -1,4 +1,4 @@
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Read;
 use std::path::Path;
 
@@ -6,6 +6,25 @@ use zip::read::ZipArchive;
 
 use super::AppError;
 
+/// Largest XML part read from a package, after decompression: far above
+/// any real exam, small enough that a zip bomb cannot exhaust memory
+pub(super) const MAX_XML_PART_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Content of the package part `name` read from `entry`, stopping one byte
+/// past `limit`; larger parts are rejected with `AppError::UnsafeArchive`.
+pub(super) fn read_part(entry: impl Read, name: &str, limit: u64) -> Result<Vec<u8>, AppError> {
+    let mut buffer = Vec::new();
+    entry.take(limit + 1).read_to_end(&mut buffer)?;
+    if buffer.len() as u64 > limit {
+        return Err(AppError::UnsafeArchive(format!(
+            "{}: larger than {} MB",
+            name,
+            limit / (1024 * 1024)
+        )));
+    }
+    Ok(buffer)
+}
+
 /// Open a .docx file as a ZIP archive and read the `word/document.xml`
 /// entry into a UTF-8 string.
 pub fn read_document_xml(docx_path: &Path) -> Result<String, AppError> {
@@ -16,14 +35,126 @@ pub fn read_document_xml(docx_path: &Path) -> Result<String, AppError> {
     let mut archive = ZipArchive::new(file)?;
 
     // Access the `word/document.xml` entry
-    let mut doc_xml = archive.by_name("word/document.xml")?;
+    let doc_xml = archive.by_name("word/document.xml")?;
 
-    // Read the entry contents into memory
-    let mut buffer = Vec::new();
-    doc_xml.read_to_end(&mut buffer)?;
+    // Read the entry contents into memory, within the size limit
+    let buffer = read_part(doc_xml, "word/document.xml", MAX_XML_PART_BYTES)?;
 
     // Convert bytes to UTF-8 string
     let xml = String::from_utf8(buffer)?;
 
     Ok(xml)
 }
+
+/// Why a file given as `.docx` cannot be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputProblem {
+    /// Another format, described (e.g. "PDF", "Word 97-2003 (.doc)")
+    NotADocx(String),
+    /// Password-protected Word document (an OLE container wrapping the
+    /// encrypted package)
+    EncryptedDocx,
+    /// ZIP whose end is missing (interrupted download or copy)
+    TruncatedZip,
+}
+
+impl InputProblem {
+    /// Stable error code for the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            InputProblem::NotADocx(_) => "E170_NOT_A_DOCX",
+            InputProblem::EncryptedDocx => "E171_ENCRYPTED_DOCX",
+            InputProblem::TruncatedZip => "E172_TRUNCATED_ZIP",
+        }
+    }
+}
+
+/// OLE compound file signature (legacy .doc, encrypted OOXML)
+const OLE_MAGIC: &[u8] = &[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// Work out why `read_document_xml` would fail on `path`, from the content
+/// rather than the extension. `None` when the file looks like a readable
+/// `.docx` (or cannot be read at all).
+pub fn inspect(path: &Path) -> Option<InputProblem> {
+    let data = fs::read(path).ok()?;
+    inspect_bytes(&data)
+}
+
+fn inspect_bytes(data: &[u8]) -> Option<InputProblem> {
+    let not_a_docx = |kind: &str| Some(InputProblem::NotADocx(kind.to_string()));
+
+    if data.is_empty() {
+        return not_a_docx("empty file");
+    }
+    if data.starts_with(b"%PDF") {
+        return not_a_docx("PDF");
+    }
+    if data.starts_with(OLE_MAGIC) {
+        // The stream names of an encrypted package are stored in UTF-16LE
+        let encrypted: Vec<u8> = "EncryptedPackage".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        if data.windows(encrypted.len()).any(|w| w == encrypted.as_slice()) {
+            return Some(InputProblem::EncryptedDocx);
+        }
+        return not_a_docx("Word 97-2003 (.doc)");
+    }
+    if data.starts_with(b"{\\rtf") {
+        return not_a_docx("RTF");
+    }
+    if !data.starts_with(b"PK") {
+        let head = String::from_utf8_lossy(&data[..data.len().min(512)]).to_ascii_lowercase();
+        if head.contains("<html") {
+            return not_a_docx("HTML");
+        }
+        if head.contains("<?xml") {
+            return not_a_docx("XML (Word 2003 XML?)");
+        }
+        return not_a_docx("unknown format");
+    }
+
+    let mut archive = match ZipArchive::new(std::io::Cursor::new(data)) {
+        Ok(archive) => archive,
+        Err(_) => return Some(InputProblem::TruncatedZip),
+    };
+    if archive.by_name("word/document.xml").is_ok() {
+        return None;
+    }
+    let names: Vec<&str> = archive.file_names().collect();
+    if names.iter().any(|n| n.starts_with("xl/")) {
+        not_a_docx("Excel (.xlsx)")
+    } else if names.iter().any(|n| n.starts_with("ppt/")) {
+        not_a_docx("PowerPoint (.pptx)")
+    } else {
+        not_a_docx("ZIP without word/document.xml")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn recognizes_common_wrong_inputs() {
+        assert_eq!(inspect_bytes(b"%PDF-1.7\n..."), Some(InputProblem::NotADocx("PDF".to_string())));
+
+        let mut ole = OLE_MAGIC.to_vec();
+        ole.extend("EncryptedPackage".encode_utf16().flat_map(u16::to_le_bytes));
+        assert_eq!(inspect_bytes(&ole), Some(InputProblem::EncryptedDocx));
+
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        zip.start_file("word/document.xml", zip::write::FileOptions::default()).unwrap();
+        zip.write_all(b"<w:document/>").unwrap();
+        let data = zip.finish().unwrap().into_inner();
+        assert_eq!(inspect_bytes(&data), None);
+        assert_eq!(inspect_bytes(&data[..data.len() - 10]), Some(InputProblem::TruncatedZip));
+    }
+
+    #[test]
+    fn parts_past_the_limit_are_rejected() {
+        assert_eq!(read_part(&b"<w:document/>"[..], "word/document.xml", 13).unwrap(), b"<w:document/>");
+        assert!(matches!(
+            read_part(&b"<w:document/>"[..], "word/document.xml", 12),
+            Err(AppError::UnsafeArchive(_))
+        ));
+    }
+}
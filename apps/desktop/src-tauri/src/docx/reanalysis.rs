@@ -0,0 +1,225 @@
+// src-tauri/src/docx/reanalysis.rs
+//! Carry manual edits over to a new analysis of an edited source.
+//!
+//! Manual answers (`set_correct_label`) are keyed by question number and
+//! paragraph reassignments (`reassign_paragraph`) by `<w:p>` index, so both
+//! point at the wrong place once questions or paragraphs are added to the
+//! source. Each edit is looked up in the previous parse (what it was made
+//! against) and moved to the same question or paragraph of the new parse;
+//! edits that cannot be placed any more are reported as conflicts.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::diff::{segments_text, similarity};
+use super::model::{ParagraphAssignment, ParsedDoc, Question};
+use super::parser::ParseOptions;
+
+/// Stems at least this similar are taken for the same question, edited
+pub const MATCH_THRESHOLD: f64 = 0.8;
+
+/// A manual edit that could not be carried over
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum EditConflict {
+    /// The question of a manual answer is not in the new document
+    #[serde(rename_all = "camelCase")]
+    QuestionMissing { question_number: u32, label: String },
+    /// The option chosen as the answer was removed or its text changed
+    #[serde(rename_all = "camelCase")]
+    OptionChanged { question_number: u32, label: String },
+    /// The reassigned paragraph is not found in the new document, or found
+    /// several times
+    #[serde(rename_all = "camelCase")]
+    ParagraphMissing { paragraph_index: usize, preview: String },
+}
+
+/// Edits placed on the new parse, and those that could not be
+#[derive(Debug, Default)]
+pub struct CarriedEdits {
+    pub manual_answers: BTreeMap<u32, String>,
+    pub overrides: BTreeMap<usize, ParagraphAssignment>,
+    pub conflicts: Vec<EditConflict>,
+}
+
+/// Move the manual edits of `options`, made against `before`, to the
+/// questions and paragraphs of `after` (parsed without any edit).
+pub fn carry_over(options: &ParseOptions, before: &ParsedDoc, after: &ParsedDoc) -> CarriedEdits {
+    let mut carried = CarriedEdits::default();
+    let matches = match_questions(before, after);
+
+    for (&number, label) in &options.manual_answers {
+        let old = before.questions.iter().find(|q| q.number == number);
+        let new = old.and_then(|old| matches.get(&old.number)).map(|&i| &after.questions[i]);
+        let (Some(old), Some(new)) = (old, new) else {
+            carried.conflicts.push(EditConflict::QuestionMissing {
+                question_number: number,
+                label: label.clone(),
+            });
+            continue;
+        };
+        // Same option text, whatever its label now
+        let chosen = old.options.iter().find(|o| &o.label == label).map(|o| segments_text(&o.content));
+        let moved = chosen.and_then(|text| new.options.iter().find(|o| segments_text(&o.content) == text));
+        match moved {
+            Some(option) => {
+                carried.manual_answers.insert(new.number, option.label.clone());
+            }
+            None => carried.conflicts.push(EditConflict::OptionChanged {
+                question_number: number,
+                label: label.clone(),
+            }),
+        }
+    }
+
+    for (&index, &assignment) in &options.overrides {
+        let preview = paragraphs(before)
+            .find(|p| p.paragraph_index == index)
+            .map(|p| p.preview.clone())
+            .unwrap_or_default();
+        let mut found = paragraphs(after).filter(|p| !preview.is_empty() && p.preview == preview);
+        match (found.next(), found.next()) {
+            (Some(paragraph), None) => {
+                carried.overrides.insert(paragraph.paragraph_index, assignment);
+            }
+            _ => carried.conflicts.push(EditConflict::ParagraphMissing {
+                paragraph_index: index,
+                preview,
+            }),
+        }
+    }
+
+    carried
+}
+
+fn paragraphs(doc: &ParsedDoc) -> impl Iterator<Item = &super::model::ContinuationParagraph> {
+    doc.questions.iter().flat_map(|q| &q.continuation_paragraphs)
+}
+
+/// Old question number → index of the same question in `after`: same stem
+/// text first, then the most similar stem not taken yet.
+fn match_questions(before: &ParsedDoc, after: &ParsedDoc) -> BTreeMap<u32, usize> {
+    let stem = |q: &Question| segments_text(&q.stem);
+    let new_stems: Vec<String> = after.questions.iter().map(stem).collect();
+    let mut taken = vec![false; new_stems.len()];
+    let mut matches = BTreeMap::new();
+
+    let mut unmatched = Vec::new();
+    for old in &before.questions {
+        let old_stem = stem(old);
+        match new_stems.iter().enumerate().position(|(i, s)| !taken[i] && *s == old_stem) {
+            Some(i) => {
+                taken[i] = true;
+                matches.insert(old.number, i);
+            }
+            None => unmatched.push((old.number, old_stem)),
+        }
+    }
+
+    for (number, old_stem) in unmatched {
+        let best = new_stems
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !taken[*i])
+            .map(|(i, s)| (i, similarity(&old_stem, s)))
+            .filter(|(_, score)| *score >= MATCH_THRESHOLD)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, _)) = best {
+            taken[i] = true;
+            matches.insert(number, i);
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::{ContinuationParagraph, OptionItem, Segment};
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    fn question(number: u32, stem: &str, options: &[&str]) -> Question {
+        Question {
+            number,
+            stem: text(stem),
+            options: options
+                .iter()
+                .zip(["A", "B", "C", "D"])
+                .map(|(content, label)| OptionItem {
+                    label: label.to_string(),
+                    content: text(content),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn moves_edits_to_the_same_questions() {
+        let mut reassigned = question(2, "Thủ đô của Pháp là", &["Paris", "Lyon"]);
+        reassigned.continuation_paragraphs.push(ContinuationParagraph {
+            paragraph_index: 7,
+            assignment: ParagraphAssignment::Stem,
+            preview: "(Hình bên)".to_string(),
+        });
+        let before = ParsedDoc {
+            questions: vec![
+                question(1, "1 + 1 = ?", &["1", "2", "3"]),
+                reassigned,
+                question(3, "Chọn số chẵn", &["3", "5"]),
+            ],
+            ..Default::default()
+        };
+
+        // A question inserted at the top, options of question 1 reordered,
+        // option B of the last question rewritten
+        let mut moved = question(3, "Thủ đô của Pháp là gì", &["Lyon", "Paris"]);
+        moved.continuation_paragraphs.push(ContinuationParagraph {
+            paragraph_index: 10,
+            assignment: ParagraphAssignment::Option,
+            preview: "(Hình bên)".to_string(),
+        });
+        let after = ParsedDoc {
+            questions: vec![
+                question(1, "Câu mới", &["x", "y"]),
+                question(2, "1 + 1 = ?", &["2", "1", "3"]),
+                moved,
+                question(4, "Chọn số chẵn", &["3", "4"]),
+            ],
+            ..Default::default()
+        };
+
+        let options = ParseOptions {
+            manual_answers: BTreeMap::from([
+                (1, "B".to_string()),
+                (2, "A".to_string()),
+                (3, "B".to_string()),
+                (9, "A".to_string()),
+            ]),
+            overrides: BTreeMap::from([(7, ParagraphAssignment::Stem)]),
+            ..Default::default()
+        };
+        let carried = carry_over(&options, &before, &after);
+
+        assert_eq!(
+            carried.manual_answers,
+            BTreeMap::from([(2, "A".to_string()), (3, "B".to_string())])
+        );
+        assert_eq!(carried.overrides, BTreeMap::from([(10, ParagraphAssignment::Stem)]));
+        assert_eq!(
+            carried.conflicts,
+            vec![
+                EditConflict::OptionChanged { question_number: 3, label: "B".to_string() },
+                EditConflict::QuestionMissing { question_number: 9, label: "A".to_string() },
+            ]
+        );
+    }
+}
@@ -0,0 +1,169 @@
+// src-tauri/src/docx/reimport.rs
+//! Reconcile a stored answer key with an exported variant edited in Word.
+//!
+//! The edited file is read back with the analysis rules ("Câu N." headings,
+//! "A." labels, answer marked by underline or red on the label) and each
+//! question is compared, by display number, with the variant's
+//! `mapping.json`. Headings or labels changed to another format (e.g. "A)")
+//! are not recognized.
+
+use serde::Serialize;
+
+use super::mixer::VariantMapping;
+use super::parser::{self, ParseOptions};
+use super::validator::{self, ValidationErrorCode};
+
+/// One difference between the edited file and the stored key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum KeyDiscrepancy {
+    /// Another label is marked than the stored answer
+    #[serde(rename_all = "camelCase")]
+    AnswerChanged { display_number: u32, stored: String, marked: String },
+    /// No label is marked: the answer cannot be checked
+    #[serde(rename_all = "camelCase")]
+    Unmarked { display_number: u32, stored: String },
+    /// Several labels are marked
+    #[serde(rename_all = "camelCase")]
+    MultipleMarked { display_number: u32, stored: String },
+    #[serde(rename_all = "camelCase")]
+    OptionCountChanged { display_number: u32, stored: usize, found: usize },
+    /// In the stored variant, not in the file
+    #[serde(rename_all = "camelCase")]
+    QuestionMissing { display_number: u32 },
+    /// In the file, not in the stored variant
+    #[serde(rename_all = "camelCase")]
+    QuestionAdded { display_number: u32 },
+}
+
+/// Compare the `document.xml` of an edited variant with its stored mapping.
+/// Returns the discrepancies in display order.
+pub fn compare(document_xml: &str, mapping: &VariantMapping, options: &ParseOptions) -> Vec<KeyDiscrepancy> {
+    let found = parser::collect_labeled_option_runs(document_xml, options);
+    let mut discrepancies = Vec::new();
+
+    for stored in &mapping.questions {
+        let display_number = stored.display_number;
+        let Some(option_runs) = found.get(&display_number) else {
+            discrepancies.push(KeyDiscrepancy::QuestionMissing { display_number });
+            continue;
+        };
+        if option_runs.len() != stored.options.len() {
+            discrepancies.push(KeyDiscrepancy::OptionCountChanged {
+                display_number,
+                stored: stored.options.len(),
+                found: option_runs.len(),
+            });
+        }
+        let stored_answer = stored.correct_answer.clone();
+        match validator::detect_correct_label_for_question(display_number, option_runs) {
+            Ok(marked) if marked != stored_answer => discrepancies.push(KeyDiscrepancy::AnswerChanged {
+                display_number,
+                stored: stored_answer,
+                marked,
+            }),
+            Ok(_) => {}
+            Err(e) if e.code == ValidationErrorCode::E021CorrectMarkMultiple => {
+                discrepancies.push(KeyDiscrepancy::MultipleMarked { display_number, stored: stored_answer })
+            }
+            Err(_) => discrepancies.push(KeyDiscrepancy::Unmarked { display_number, stored: stored_answer }),
+        }
+    }
+
+    let mut added: Vec<u32> = found
+        .keys()
+        .filter(|number| !mapping.questions.iter().any(|q| q.display_number == **number))
+        .copied()
+        .collect();
+    added.sort();
+    discrepancies.extend(added.into_iter().map(|display_number| KeyDiscrepancy::QuestionAdded { display_number }));
+    discrepancies
+}
+
+/// Take the marked answers of `discrepancies` into `mapping`. Returns how
+/// many answers changed.
+pub fn apply_marked_answers(mapping: &mut VariantMapping, discrepancies: &[KeyDiscrepancy]) -> usize {
+    let mut changed = 0;
+    for discrepancy in discrepancies {
+        if let KeyDiscrepancy::AnswerChanged { display_number, marked, .. } = discrepancy {
+            if let Some(question) = mapping.questions.iter_mut().find(|q| q.display_number == *display_number) {
+                question.correct_answer = marked.clone();
+                changed += 1;
+            }
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::mixer::{OptionMapping, QuestionMapping};
+
+    fn paragraph(runs: &[(&str, bool)]) -> String {
+        let runs: String = runs
+            .iter()
+            .map(|(text, underline)| {
+                let props = if *underline { r#"<w:rPr><w:u w:val="single"/></w:rPr>"# } else { "" };
+                format!(r#"<w:r>{props}<w:t xml:space="preserve">{text}</w:t></w:r>"#)
+            })
+            .collect();
+        format!("<w:p>{runs}</w:p>")
+    }
+
+    fn question(display_number: u32, correct_answer: &str) -> QuestionMapping {
+        QuestionMapping {
+            display_number,
+            original_number: display_number + 10,
+            options: ["A", "B", "C", "D"]
+                .iter()
+                .map(|label| OptionMapping {
+                    label: label.to_string(),
+                    original_label: label.to_string(),
+                })
+                .collect(),
+            correct_answer: correct_answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn flags_answers_that_drifted() {
+        let options = |marked: Option<&str>| -> String {
+            ["A", "B", "C", "D"]
+                .iter()
+                .map(|label| paragraph(&[(&format!("{label}."), marked == Some(*label)), (" nội dung", false)]))
+                .collect()
+        };
+        let xml = [
+            paragraph(&[("Câu 1.", false), (" Tính x", false)]),
+            options(Some("B")),
+            paragraph(&[("Câu 2.", false), (" Tính y", false)]),
+            options(Some("D")),
+            paragraph(&[("Câu 3.", false), (" Tính z", false)]),
+            options(None),
+            paragraph(&[("Câu 5.", false), (" Câu thêm", false)]),
+            options(Some("A")),
+        ]
+        .concat();
+        let mut mapping = VariantMapping {
+            exam_code: "101".to_string(),
+            seed: 1,
+            questions: vec![question(1, "B"), question(2, "A"), question(3, "C"), question(4, "A")],
+        };
+
+        let discrepancies = compare(&xml, &mapping, &ParseOptions::default());
+        assert_eq!(
+            discrepancies,
+            vec![
+                KeyDiscrepancy::AnswerChanged { display_number: 2, stored: "A".to_string(), marked: "D".to_string() },
+                KeyDiscrepancy::Unmarked { display_number: 3, stored: "C".to_string() },
+                KeyDiscrepancy::QuestionMissing { display_number: 4 },
+                KeyDiscrepancy::QuestionAdded { display_number: 5 },
+            ]
+        );
+
+        assert_eq!(apply_marked_answers(&mut mapping, &discrepancies), 1);
+        assert_eq!(mapping.questions[1].correct_answer, "D");
+        assert_eq!(mapping.questions[2].correct_answer, "C");
+    }
+}
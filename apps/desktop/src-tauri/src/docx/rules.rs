@@ -0,0 +1,248 @@
+// src-tauri/src/docx/rules.rs
+//! School-specific validation rules written as Rhai scripts, one `.rhai`
+//! file per rule in `<app_data>/SiroMix/rules`.
+//!
+//! Each script runs once per question with a `question` map in scope
+//! (`number`, `stem`, `stemAlt`, `options` = [#{label, text}], `correct`,
+//! `chapter`, `tags`, `images`) and reports with `warn(message)` or
+//! `error(message)`:
+//!
+//! ```rhai
+//! if !question.stem.contains("SGK") {
+//!     warn("Câu hỏi chưa ghi mục sách giáo khoa");
+//! }
+//! ```
+//!
+//! Scripts cannot touch files or the network, and are stopped after
+//! [`MAX_OPERATIONS`] steps per question.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+
+use super::diff::segments_text;
+use super::model::{ParsedDoc, Question, Segment};
+
+/// Budget of one script run, so a runaway loop cannot hang the analysis
+pub const MAX_OPERATIONS: u64 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleFinding {
+    /// Reported by a script with `warn` / `error`
+    Reported {
+        severity: Severity,
+        question_number: u32,
+        script: String,
+        message: String,
+    },
+    /// The script did not compile or stopped with an error
+    Failed {
+        question_number: u32,
+        script: String,
+        message: String,
+    },
+}
+
+pub struct RuleScript {
+    /// File name without extension
+    pub name: String,
+    ast: AST,
+}
+
+type Reports = Arc<Mutex<Vec<(Severity, String)>>>;
+
+fn engine(reports: &Reports) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    let warnings = reports.clone();
+    engine.register_fn("warn", move |message: &str| {
+        warnings.lock().unwrap_or_else(|e| e.into_inner()).push((Severity::Warning, message.to_string()));
+    });
+    let errors = reports.clone();
+    engine.register_fn("error", move |message: &str| {
+        errors.lock().unwrap_or_else(|e| e.into_inner()).push((Severity::Error, message.to_string()));
+    });
+    engine
+}
+
+/// Compile the `.rhai` files of `dir` (sorted by name). Scripts that do not
+/// compile are returned as failures.
+pub fn load_scripts(dir: &Path) -> (Vec<RuleScript>, Vec<RuleFinding>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (Vec::new(), Vec::new());
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("rhai")))
+        .collect();
+    paths.sort();
+
+    let engine = engine(&Reports::default());
+    let mut scripts = Vec::new();
+    let mut failures = Vec::new();
+    for path in paths {
+        let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        match engine.compile_file(path.clone()) {
+            Ok(ast) => scripts.push(RuleScript { name, ast }),
+            Err(e) => failures.push(RuleFinding::Failed {
+                question_number: 0,
+                script: name,
+                message: e.to_string(),
+            }),
+        }
+    }
+    (scripts, failures)
+}
+
+/// Run every script on every question of `doc`, in document order.
+pub fn run(scripts: &[RuleScript], doc: &ParsedDoc) -> Vec<RuleFinding> {
+    let reports = Reports::default();
+    let engine = engine(&reports);
+    let mut findings = Vec::new();
+
+    for question in &doc.questions {
+        let value = question_value(question);
+        for script in scripts {
+            let mut scope = Scope::new();
+            scope.push_constant("question", value.clone());
+            let result = engine.run_ast_with_scope(&mut scope, &script.ast);
+
+            let reported = std::mem::take(&mut *reports.lock().unwrap_or_else(|e| e.into_inner()));
+            findings.extend(reported.into_iter().map(|(severity, message)| RuleFinding::Reported {
+                severity,
+                question_number: question.number,
+                script: script.name.clone(),
+                message,
+            }));
+            if let Err(e) = result {
+                findings.push(RuleFinding::Failed {
+                    question_number: question.number,
+                    script: script.name.clone(),
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// The question as seen by scripts
+fn question_value(question: &Question) -> Dynamic {
+    let options: Array = question
+        .options
+        .iter()
+        .map(|option| {
+            let mut map = Map::new();
+            map.insert("label".into(), option.label.clone().into());
+            map.insert("text".into(), segments_text(&option.content).into());
+            map.into()
+        })
+        .collect();
+    let images = std::iter::once(&question.stem)
+        .chain(question.options.iter().map(|o| &o.content))
+        .flatten()
+        .filter(|segment| matches!(segment, Segment::Image { .. }))
+        .count();
+    let tags: Array = question.chapter.iter().map(|c| c.clone().into()).collect();
+
+    let mut map = Map::new();
+    map.insert("number".into(), (question.number as i64).into());
+    map.insert("stem".into(), segments_text(&question.stem).into());
+    map.insert("stemAlt".into(), segments_text(&question.stem_alt).into());
+    map.insert("options".into(), options.into());
+    map.insert("correct".into(), question.correct_label.clone().into());
+    map.insert(
+        "chapter".into(),
+        question.chapter.clone().map_or(Dynamic::UNIT, Dynamic::from),
+    );
+    map.insert("tags".into(), tags.into());
+    map.insert("images".into(), (images as i64).into());
+    map.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::OptionItem;
+
+    fn text(text: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: text.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    #[test]
+    fn reports_findings_of_each_script() {
+        let dir = std::env::temp_dir().join(format!("siromix-rules-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("sgk.rhai"),
+            r#"if !question.stem.contains("SGK") { warn("Chưa ghi mục SGK"); }
+               if question.options.len() < 4 { error(`Câu ${question.number} chỉ có ${question.options.len()} phương án`); }"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("loop.rhai"), "loop {}").unwrap();
+        std::fs::write(dir.join("broken.rhai"), "if (").unwrap();
+
+        let (scripts, failures) = load_scripts(&dir);
+        assert_eq!(scripts.len(), 2);
+        assert!(matches!(&failures[..], [RuleFinding::Failed { script, .. }] if script == "broken"));
+
+        let option = |label: &str| OptionItem {
+            label: label.to_string(),
+            content: text("x"),
+            ..Default::default()
+        };
+        let doc = ParsedDoc {
+            questions: vec![
+                Question {
+                    number: 1,
+                    stem: text("Theo SGK mục 2.1, ..."),
+                    options: ["A", "B", "C", "D"].map(option).to_vec(),
+                    ..Default::default()
+                },
+                Question {
+                    number: 2,
+                    stem: text("Tính x"),
+                    options: ["A", "B"].map(option).to_vec(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let findings = run(&scripts, &doc);
+        let reported: Vec<_> = findings
+            .iter()
+            .filter_map(|f| match f {
+                RuleFinding::Reported { severity, question_number, message, .. } => {
+                    Some((*severity, *question_number, message.as_str()))
+                }
+                RuleFinding::Failed { .. } => None,
+            })
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (Severity::Warning, 2, "Chưa ghi mục SGK"),
+                (Severity::Error, 2, "Câu 2 chỉ có 2 phương án"),
+            ]
+        );
+        // The endless loop is stopped on each question
+        assert_eq!(
+            findings.iter().filter(|f| matches!(f, RuleFinding::Failed { script, .. } if script == "loop")).count(),
+            2
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
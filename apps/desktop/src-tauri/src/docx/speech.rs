@@ -0,0 +1,225 @@
+// src-tauri/src/docx/speech.rs
+//! Read-aloud scripts of an exam variant for text-to-speech tools
+//! (listening accommodations, oral exams): plain text with reading cues, or
+//! SSML with pauses
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::diff::math_text;
+use super::excel::MixedExam;
+use super::export_options::ExportOptions;
+use super::file_names::{self, FileNameContext};
+use super::model::Segment;
+use super::symbols::sym_to_unicode;
+
+/// Cue read before each option
+const OPTION_CUE: &str = "Phương án";
+
+/// Read in place of a figure, which TTS cannot describe
+const IMAGE_CUE: &str = "(xem hình)";
+const BLANK_CUE: &str = "(chỗ trống)";
+const AUDIO_CUE: &str = "(bài nghe)";
+
+/// SSML pauses: after a stem, between options, and after a question to
+/// leave time to answer
+const STEM_PAUSE: &str = "1s";
+const OPTION_PAUSE: &str = "500ms";
+const ANSWER_PAUSE: &str = "5s";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScriptFormat {
+    /// `.txt`, one cue per line
+    Text,
+    /// `.ssml` (`<speak>` document, Vietnamese voice)
+    Ssml,
+}
+
+impl ScriptFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ScriptFormat::Text => "txt",
+            ScriptFormat::Ssml => "ssml",
+        }
+    }
+}
+
+/// Script of one variant, questions in their mixed order.
+pub fn render_script(exam: &MixedExam, options: &ExportOptions, format: ScriptFormat) -> String {
+    let title = format!("Đề {}.", exam.exam_code);
+    let questions = exam.questions.iter().map(|question| {
+        let heading = options.question_heading(question.display_number);
+        let stem = spoken_text(&question.stem);
+        let choices: Vec<String> = question
+            .options
+            .iter()
+            .map(|option| format!("{} {}: {}", OPTION_CUE, option.label, spoken_text(&option.content)))
+            .collect();
+        // Shared data is read before the first question of its group
+        let shared: Vec<String> = exam
+            .groups
+            .iter()
+            .filter(|g| g.questions.first() == Some(&(question.display_number as u32)))
+            .flat_map(|g| std::iter::once(&g.header).chain(&g.paragraphs))
+            .map(|paragraph| spoken_text(paragraph))
+            .collect();
+        (shared, heading, stem, choices)
+    });
+
+    match format {
+        ScriptFormat::Text => {
+            let mut script = format!("{}\n", title);
+            for (shared, heading, stem, choices) in questions {
+                for paragraph in shared {
+                    script.push_str(&format!("\n{}\n", paragraph));
+                }
+                script.push_str(&format!("\n{} {} …\n", heading, stem));
+                for choice in choices {
+                    script.push_str(&format!("{}\n", choice));
+                }
+            }
+            script
+        }
+        ScriptFormat::Ssml => {
+            let mut script = format!(
+                "<speak version=\"1.1\" xmlns=\"http://www.w3.org/2001/10/synthesis\" xml:lang=\"vi-VN\">\n<p><s>{}</s></p>\n",
+                escape(&title)
+            );
+            for (shared, heading, stem, choices) in questions {
+                for paragraph in shared {
+                    script.push_str(&format!(
+                        "<p><s>{}</s></p>\n<break time=\"{}\"/>\n",
+                        escape(&paragraph),
+                        STEM_PAUSE
+                    ));
+                }
+                script.push_str(&format!(
+                    "<p>\n<s>{} {}</s>\n<break time=\"{}\"/>\n",
+                    escape(&heading),
+                    escape(&stem),
+                    STEM_PAUSE
+                ));
+                for choice in choices {
+                    script.push_str(&format!(
+                        "<s>{}</s>\n<break time=\"{}\"/>\n",
+                        escape(&choice),
+                        OPTION_PAUSE
+                    ));
+                }
+                script.push_str(&format!("<break time=\"{}\"/>\n</p>\n", ANSWER_PAUSE));
+            }
+            script.push_str("</speak>\n");
+            script
+        }
+    }
+}
+
+/// Write the script of every variant to `<variant file name>_Doc.<ext>` in
+/// `output_dir` (file names from the export options' template) and return
+/// the file names.
+pub fn write_scripts(
+    exams: &[MixedExam],
+    output_dir: &Path,
+    options: &ExportOptions,
+    format: ScriptFormat,
+) -> Result<Vec<String>, String> {
+    let context = FileNameContext::new(&options.subject, &options.grade);
+    exams
+        .iter()
+        .enumerate()
+        .map(|(index, exam)| {
+            let stem = file_names::variant_stem(
+                &options.file_name_template,
+                &context.for_variant(&exam.exam_code, index + 1),
+            );
+            let file_name = format!("{}_Doc.{}", stem, format.extension());
+            let script = render_script(exam, options, format);
+            crate::storage::fs::write_bytes(&output_dir.join(&file_name), script.as_bytes())?;
+            Ok(file_name)
+        })
+        .collect()
+}
+
+/// Text to read for a segment list: formulas by their text, symbols as
+/// Unicode, figures as a cue.
+fn spoken_text(segments: &[Segment]) -> String {
+    let parts: Vec<String> = segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Text { text, .. } => text.clone(),
+            Segment::Math { omml, .. } => math_text(omml),
+            Segment::Image { .. } => IMAGE_CUE.to_string(),
+            Segment::Symbol { font, char_code, .. } => sym_to_unicode(font, char_code)
+                .map(String::from)
+                .unwrap_or_default(),
+            Segment::Blank { .. } => BLANK_CUE.to_string(),
+            Segment::Audio { .. } => AUDIO_CUE.to_string(),
+        })
+        .collect();
+    parts.join(" ").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::excel::{MixedOption, MixedQuestion};
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    #[test]
+    fn reads_questions_with_cues() {
+        let exam = MixedExam {
+            exam_code: "101".to_string(),
+            questions: vec![MixedQuestion {
+                display_number: 5,
+                stem: text("Số nào lớn hơn 1 & nhỏ hơn 3?"),
+                options: vec![
+                    MixedOption {
+                        label: "A".to_string(),
+                        content: text("2"),
+                        ..Default::default()
+                    },
+                    MixedOption {
+                        label: "B".to_string(),
+                        content: vec![Segment::Image {
+                            asset_path: String::new(),
+                            raw_xml: String::new(),
+                            width_emu: 0,
+                            height_emu: 0,
+                            anchor: None,
+                            ocr_text: None,
+                        }],
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+            groups: Vec::new(),
+            parts: Vec::new(),
+        };
+        let options = ExportOptions::default();
+
+        let plain = render_script(&exam, &options, ScriptFormat::Text);
+        assert!(plain.contains("Câu 5. Số nào lớn hơn 1 & nhỏ hơn 3? …"));
+        assert!(plain.contains("Phương án A: 2\nPhương án B: (xem hình)"));
+
+        let ssml = render_script(&exam, &options, ScriptFormat::Ssml);
+        assert!(ssml.starts_with("<speak"));
+        assert!(ssml.contains("1 &amp; nhỏ hơn 3"));
+        assert!(ssml.contains("<break time=\"5s\"/>"));
+    }
+}
@@ -0,0 +1,162 @@
+// src-tauri/src/docx/spell.rs
+//! Spell check of stems and options against the hunspell vi-VN dictionary.
+//!
+//! Vietnamese is written in syllables and the vi-VN hunspell dictionary lists
+//! them without affix rules, so checking a word is a lookup in the `.dic`
+//! word list. The dictionary shipped with the app (`dictionaries/vi_VN.dic`
+//! in the bundle resources) is used, falling back to a system-wide hunspell
+//! install; without either, the check is skipped and the missing dictionary
+//! reported once per session. Words with a capital past the first letter (acronyms, chemical
+//! formulas) and words containing digits are not checked.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use regex::Regex;
+use serde::Serialize;
+
+use super::diff::segments_text;
+use super::model::ParsedDoc;
+
+const DICTIONARY_FILE: &str = "vi_VN.dic";
+
+/// Set once the missing dictionary has been reported
+static MISSING_REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// System locations of hunspell dictionaries
+const SYSTEM_DICTIONARY_DIRS: &[&str] = &["/usr/share/hunspell", "/usr/share/myspell/dicts", "/Library/Spelling"];
+
+/// A word not found in the dictionary
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Misspelling {
+    #[serde(rename = "questionNumber")]
+    pub question_number: u32,
+    /// Option label, `None` for the stem
+    #[serde(rename = "optionLabel")]
+    pub option_label: Option<String>,
+    pub word: String,
+}
+
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    /// Load the dictionary bundled in `resource_dir`, else the system one.
+    pub fn load(resource_dir: Option<&Path>) -> Result<Self, String> {
+        let path = dictionary_path(resource_dir).ok_or_else(|| format!("{} not found", DICTIONARY_FILE))?;
+        let data = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Self::parse(&data))
+    }
+
+    /// Parse a hunspell `.dic` file: a word count line, then `word[/flags]`.
+    pub fn parse(data: &str) -> Self {
+        let words = data
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split('/').next())
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+        Self { words }
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+}
+
+/// `true` the first time it is called in the session, to report a missing
+/// dictionary once rather than on every check
+pub fn first_missing_report() -> bool {
+    !MISSING_REPORTED.swap(true, Ordering::Relaxed)
+}
+
+fn dictionary_path(resource_dir: Option<&Path>) -> Option<PathBuf> {
+    resource_dir
+        .map(|dir| dir.join("dictionaries").join(DICTIONARY_FILE))
+        .into_iter()
+        .chain(SYSTEM_DICTIONARY_DIRS.iter().map(|dir| PathBuf::from(dir).join(DICTIONARY_FILE)))
+        .find(|path| path.is_file())
+}
+
+/// Words of stems and options missing from the dictionary, each reported
+/// once per stem or option.
+pub fn check(doc: &ParsedDoc, dictionary: &Dictionary) -> Vec<Misspelling> {
+    let word_re = Regex::new(r"[\p{L}\p{N}]+").unwrap();
+    let mut misspellings = Vec::new();
+
+    for question in &doc.questions {
+        let parts = std::iter::once((None, &question.stem)).chain(
+            question
+                .options
+                .iter()
+                .map(|option| (Some(option.label.clone()), &option.content)),
+        );
+        for (option_label, segments) in parts {
+            let text = segments_text(segments);
+            let mut seen: Vec<&str> = Vec::new();
+            for found in word_re.find_iter(&text) {
+                let word = found.as_str();
+                if !checkable(word) || seen.contains(&word) || dictionary.contains(word) {
+                    continue;
+                }
+                seen.push(word);
+                misspellings.push(Misspelling {
+                    question_number: question.number,
+                    option_label: option_label.clone(),
+                    word: word.to_string(),
+                });
+            }
+        }
+    }
+    misspellings
+}
+
+fn checkable(word: &str) -> bool {
+    word.chars().count() > 1
+        && !word.chars().any(|c| c.is_numeric())
+        && !word.chars().skip(1).any(char::is_uppercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::{OptionItem, Question, Segment};
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    #[test]
+    fn reports_words_missing_from_dictionary() {
+        let dictionary = Dictionary::parse("6\nnước\nsôi\nở\nbao\nnhiêu/A\nđộ\n");
+        let doc = ParsedDoc {
+            questions: vec![Question {
+                number: 4,
+                stem: text("Nước sôi ở bao nhiu độ?"),
+                options: vec![OptionItem {
+                    label: "A".to_string(),
+                    content: text("100 độ, H2O, NaCl"),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            check(&doc, &dictionary),
+            vec![Misspelling {
+                question_number: 4,
+                option_label: None,
+                word: "nhiu".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// src-tauri/src/docx/statements.rs
+//! Roman-numeral statements inside a stem ("Cho các phát biểu: (I) … (II) …
+//! Số phát biểu đúng là").
+//!
+//! Statements are recorded as segment ranges of the stem when parsing. With
+//! the opt-in statement shuffle, their order is shuffled in each variant and
+//! they are relabelled (I), (II), … in their new order. The answer is never
+//! recomputed: this is only safe when the options count statements, so a
+//! question whose options name statements ("I và III") is left as it is and
+//! flagged when parsing.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::model::{OptionItem, Segment};
+
+const NUMERALS: [&str; 10] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X"];
+
+/// One statement: `stem[start..end]`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatementItem {
+    /// Roman numeral, e.g. "III"
+    pub label: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+fn label_re() -> Regex {
+    Regex::new(r"^(\s*\(?)(I|II|III|IV|V|VI|VII|VIII|IX|X)([).])").unwrap()
+}
+
+/// "a)" … "d)" sub-items, which end a statement
+fn sub_item_re() -> Regex {
+    Regex::new(r"^\s*\(?[a-d]\)").unwrap()
+}
+
+/// Statements of a stem, when it holds at least two labelled I, II, … in
+/// order. Each statement runs to the next one; the last one stops at an
+/// "a)" … "d)" label, at the next paragraph ("Số phát biểu đúng là") or at
+/// the end of the stem. Text within a paragraph is one segment, so a text
+/// segment right after another one starts a paragraph; text after a formula
+/// or image continues the statement, whatever its first letter.
+pub fn detect(stem: &[Segment]) -> Vec<StatementItem> {
+    let re = label_re();
+    let sub_item = sub_item_re();
+    let mut items: Vec<StatementItem> = Vec::new();
+
+    for (index, segment) in stem.iter().enumerate() {
+        let Segment::Text { text, .. } = segment else {
+            continue;
+        };
+        let new_paragraph = index > 0 && matches!(stem[index - 1], Segment::Text { .. });
+        if let Some(caps) = re.captures(text) {
+            if caps[2] == *NUMERALS[items.len().min(NUMERALS.len() - 1)] {
+                if let Some(previous) = items.last_mut() {
+                    previous.end = index;
+                }
+                items.push(StatementItem {
+                    label: caps[2].to_string(),
+                    start: index,
+                    end: stem.len(),
+                });
+                continue;
+            }
+        }
+        // A sub-item or the paragraph after the last statement ends it
+        if let Some(last) = items.last_mut() {
+            if last.end == stem.len() && (new_paragraph || sub_item.is_match(text)) {
+                last.end = index;
+            }
+        }
+    }
+
+    if items.len() < 2 {
+        return Vec::new();
+    }
+    items
+}
+
+/// Whether any option names statements ("I và III", "Chỉ II"), in which
+/// case shuffling them would change the answer.
+pub fn referenced_by_options(options: &[OptionItem]) -> bool {
+    let re = Regex::new(r"\b(I|II|III|IV|V|VI|VII|VIII|IX|X)\b").unwrap();
+    options.iter().flat_map(|o| o.content.iter()).any(|segment| {
+        matches!(segment, Segment::Text { text, .. } if re.is_match(text))
+    })
+}
+
+/// Shuffle the statements of `stem` and relabel them in their new order.
+pub fn shuffle(stem: &[Segment], items: &[StatementItem], rng: &mut StdRng) -> Vec<Segment> {
+    let (Some(first), Some(last)) = (items.first(), items.last()) else {
+        return stem.to_vec();
+    };
+    let mut order: Vec<&StatementItem> = items.iter().collect();
+    order.shuffle(rng);
+
+    let re = label_re();
+    let mut shuffled = stem[..first.start].to_vec();
+    for (position, item) in order.iter().enumerate() {
+        let mut statement = stem[item.start..item.end].to_vec();
+        if let Some(Segment::Text { text, .. }) = statement.first_mut() {
+            *text = re
+                .replace(text, format!("${{1}}{}${{3}}", NUMERALS[position]))
+                .into_owned();
+        }
+        shuffled.extend(statement);
+    }
+    shuffled.extend_from_slice(&stem[last.end..]);
+    shuffled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn text(s: &str) -> Segment {
+        Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }
+    }
+
+    fn texts(stem: &[Segment]) -> Vec<String> {
+        stem.iter()
+            .filter_map(|s| match s {
+                Segment::Text { text, .. } => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shuffles_and_relabels_statements() {
+        let stem = vec![
+            text("Cho các phát biểu sau:"),
+            text("(I) Nước sôi ở 100 °C."),
+            text("(II) Sắt nhẹ hơn nước."),
+            text("(III) Trái Đất quay quanh Mặt Trời."),
+            text("Số phát biểu đúng là"),
+        ];
+        let items = detect(&stem);
+        assert_eq!(items.len(), 3);
+        assert_eq!((items[2].start, items[2].end), (3, 4));
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let shuffled = shuffle(&stem, &items, &mut rng);
+        let lines = texts(&shuffled);
+        assert_eq!(lines[0], "Cho các phát biểu sau:");
+        assert_eq!(lines[4], "Số phát biểu đúng là");
+        assert!(lines[1].starts_with("(I) ") && lines[2].starts_with("(II) ") && lines[3].starts_with("(III) "));
+
+        let options = vec![OptionItem {
+            label: "A".to_string(),
+            content: vec![text("I và III")],
+            ..Default::default()
+        }];
+        assert!(referenced_by_options(&options));
+    }
+
+    #[test]
+    fn proper_nouns_inside_a_statement_do_not_end_it() {
+        let math = || Segment::Math {
+            omml: String::new(),
+            raw_xml: String::new(),
+        };
+        let stem = vec![
+            text("Cho các phát biểu sau:"),
+            text("(I) Tại "),
+            math(),
+            text("Hà Nội nước sôi ở 100 °C."),
+            text("(II) Năm "),
+            math(),
+            text("Việt Nam có 63 tỉnh thành."),
+            text("Số phát biểu đúng là"),
+        ];
+        let items = detect(&stem);
+        assert_eq!(items.len(), 2);
+        assert_eq!((items[0].start, items[0].end), (1, 4));
+        assert_eq!((items[1].start, items[1].end), (4, 7));
+    }
+}
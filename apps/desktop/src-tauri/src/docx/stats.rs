@@ -0,0 +1,240 @@
+// src-tauri/src/docx/stats.rs
+//! Length and wording statistics of the questions of a document: basic item
+//! writing checks (test-wise students pick the longest option, absolute terms
+//! give away wrong options) shown before the exam is mixed, and the spread of
+//! the original correct labels.
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use serde::Serialize;
+
+use super::diff::segments_text;
+use super::model::{ParsedDoc, Question};
+
+/// Words that make an option obviously true or false
+const ABSOLUTE_TERMS: &str = "luôn luôn|luôn|không bao giờ|tất cả|mọi|chắc chắn|hoàn toàn|duy nhất|always|never|all|none|only";
+
+/// Options are unbalanced when the longest has at least this many times the
+/// words of the shortest...
+const IMBALANCE_RATIO: f64 = 2.0;
+/// ...and at least this many more words
+const IMBALANCE_MIN_WORDS: usize = 4;
+
+/// The correct option being the longest more often than this (chance is 25%
+/// with four options) is reported as a bias...
+const LONGEST_BIAS_RATE: f64 = 0.4;
+/// ...once there are enough questions to tell (also for `ANSWER_BIAS_RATE`)
+const LONGEST_BIAS_MIN_QUESTIONS: usize = 8;
+
+/// One label being correct in more than this share of the questions is
+/// reported as a bias: shuffling gains little, or the marks are wrong
+const ANSWER_BIAS_RATE: f64 = 0.5;
+
+#[derive(Debug, Serialize)]
+pub struct QuestionStats {
+    pub number: u32,
+    #[serde(rename = "stemWords")]
+    pub stem_words: usize,
+    /// Word count of each option, in option order
+    #[serde(rename = "optionWords")]
+    pub option_words: Vec<usize>,
+    /// The correct option is strictly longer than every other one
+    #[serde(rename = "correctLongest")]
+    pub correct_longest: bool,
+    #[serde(rename = "optionsUnbalanced")]
+    pub options_unbalanced: bool,
+    /// Absolute terms found in the stem and options, lowercase
+    #[serde(rename = "absoluteTerms")]
+    pub absolute_terms: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DocStats {
+    pub questions: Vec<QuestionStats>,
+    /// Share of questions whose correct option is the longest
+    #[serde(rename = "correctLongestRate")]
+    pub correct_longest_rate: f64,
+    /// The correct option is systematically the longest
+    #[serde(rename = "longestAnswerBias")]
+    pub longest_answer_bias: bool,
+}
+
+/// How often each label is the correct one in the original document
+#[derive(Debug, Serialize)]
+pub struct AnswerDistribution {
+    /// Questions per correct label ("A" → 12); questions without a correct
+    /// label are not counted
+    pub counts: BTreeMap<String, usize>,
+    /// Most frequent correct label
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dominant: Option<String>,
+    /// Share of the counted questions whose correct label is `dominant`
+    #[serde(rename = "dominantRate")]
+    pub dominant_rate: f64,
+    /// `dominant` is correct far more often than chance
+    pub biased: bool,
+}
+
+/// Spread of the correct labels of `doc`.
+pub fn answer_distribution(doc: &ParsedDoc) -> AnswerDistribution {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for question in doc.questions.iter().filter(|q| !q.correct_label.is_empty()) {
+        *counts.entry(question.correct_label.clone()).or_default() += 1;
+    }
+    let total: usize = counts.values().sum();
+    // First label wins ties, so the result does not depend on map order
+    let dominant = counts
+        .iter()
+        .fold(None, |best: Option<(&String, usize)>, (label, &count)| match best {
+            Some((_, best_count)) if best_count >= count => best,
+            _ => Some((label, count)),
+        });
+    let dominant_rate = match dominant {
+        Some((_, count)) if total > 0 => count as f64 / total as f64,
+        _ => 0.0,
+    };
+
+    AnswerDistribution {
+        dominant: dominant.map(|(label, _)| label.clone()),
+        dominant_rate,
+        biased: total >= LONGEST_BIAS_MIN_QUESTIONS && dominant_rate > ANSWER_BIAS_RATE,
+        counts,
+    }
+}
+
+/// Statistics of every question of `doc`.
+pub fn doc_stats(doc: &ParsedDoc) -> DocStats {
+    let re = Regex::new(&format!(r"(?i)\b({})\b", ABSOLUTE_TERMS)).unwrap();
+    let questions: Vec<QuestionStats> = doc.questions.iter().map(|q| question_stats(q, &re)).collect();
+
+    let longest = questions.iter().filter(|q| q.correct_longest).count();
+    let correct_longest_rate = if questions.is_empty() {
+        0.0
+    } else {
+        longest as f64 / questions.len() as f64
+    };
+
+    DocStats {
+        longest_answer_bias: questions.len() >= LONGEST_BIAS_MIN_QUESTIONS
+            && correct_longest_rate > LONGEST_BIAS_RATE,
+        correct_longest_rate,
+        questions,
+    }
+}
+
+fn question_stats(question: &Question, absolute_re: &Regex) -> QuestionStats {
+    let stem = segments_text(&question.stem);
+    let options: Vec<String> = question.options.iter().map(|o| segments_text(&o.content)).collect();
+    let option_words: Vec<usize> = options.iter().map(|text| word_count(text)).collect();
+
+    let correct = question
+        .options
+        .iter()
+        .position(|o| o.label == question.correct_label)
+        .map(|index| option_words[index]);
+    let correct_longest = match correct {
+        Some(words) => option_words.iter().filter(|&&w| w >= words).count() == 1 && option_words.len() > 1,
+        None => false,
+    };
+
+    let options_unbalanced = match (option_words.iter().max(), option_words.iter().min()) {
+        (Some(&max), Some(&min)) => {
+            max >= min + IMBALANCE_MIN_WORDS && max as f64 >= min.max(1) as f64 * IMBALANCE_RATIO
+        }
+        _ => false,
+    };
+
+    let mut absolute_terms: Vec<String> = Vec::new();
+    for text in std::iter::once(&stem).chain(options.iter()) {
+        for found in absolute_re.find_iter(text) {
+            let term = found.as_str().to_lowercase();
+            if !absolute_terms.contains(&term) {
+                absolute_terms.push(term);
+            }
+        }
+    }
+
+    QuestionStats {
+        number: question.number,
+        stem_words: word_count(&stem),
+        option_words,
+        correct_longest,
+        options_unbalanced,
+        absolute_terms,
+    }
+}
+
+fn word_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::{OptionItem, Segment};
+
+    fn text(s: &str) -> Vec<Segment> {
+        vec![Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }]
+    }
+
+    #[test]
+    fn flags_longest_answer_and_absolute_terms() {
+        let option = |label: &str, content: &str| OptionItem {
+            label: label.to_string(),
+            content: text(content),
+            ..Default::default()
+        };
+        let question = Question {
+            number: 1,
+            stem: text("Phát biểu nào đúng về ánh sáng?"),
+            options: vec![
+                option("A", "Luôn truyền thẳng."),
+                option("B", "Có bản chất sóng điện từ và truyền được trong chân không."),
+                option("C", "Không bao giờ bị khúc xạ."),
+                option("D", "Là sóng dọc."),
+            ],
+            correct_label: "B".to_string(),
+            ..Default::default()
+        };
+        let doc = ParsedDoc {
+            questions: vec![question],
+            ..Default::default()
+        };
+
+        let stats = doc_stats(&doc);
+        let q = &stats.questions[0];
+        assert_eq!(q.stem_words, 7);
+        assert_eq!(q.option_words, vec![3, 12, 6, 3]);
+        assert!(q.correct_longest);
+        assert!(q.options_unbalanced);
+        assert_eq!(q.absolute_terms, vec!["luôn", "không bao giờ"]);
+        // A single question is not enough to call it a bias
+        assert!(!stats.longest_answer_bias);
+    }
+
+    #[test]
+    fn flags_dominant_correct_label() {
+        let doc = ParsedDoc {
+            questions: ["C", "C", "A", "C", "C", "B", "C", "C", "C", "D"]
+                .iter()
+                .enumerate()
+                .map(|(i, label)| Question {
+                    number: i as u32 + 1,
+                    correct_label: label.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let distribution = answer_distribution(&doc);
+        assert_eq!(distribution.counts["C"], 7);
+        assert_eq!(distribution.dominant.as_deref(), Some("C"));
+        assert!((distribution.dominant_rate - 0.7).abs() < 1e-9);
+        assert!(distribution.biased);
+    }
+}
@@ -0,0 +1,132 @@
+// src-tauri/src/docx/summary.rs
+//! Paged and summarized views of a parsed document, so the question list of
+//! a very large exam does not travel over IPC in one payload (OMML and raw
+//! XML included).
+
+use serde::Serialize;
+
+use super::diff::segments_text;
+use super::model::{ParsedDoc, Question, QuestionGroup, Segment};
+
+/// Words of the stem shown in a summary row
+const PREVIEW_WORDS: usize = 12;
+
+/// A slice of the questions of a document
+#[derive(Debug, Serialize)]
+pub struct ParsedPage {
+    /// Number of questions in the whole document
+    pub total: usize,
+    pub offset: usize,
+    pub questions: Vec<Question>,
+    /// Groups with at least one question on this page
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<QuestionGroup>,
+}
+
+/// One row of the question list
+#[derive(Debug, Serialize)]
+pub struct QuestionSummary {
+    pub number: u32,
+    /// First words of the stem
+    pub preview: String,
+    #[serde(rename = "optionCount")]
+    pub option_count: usize,
+    #[serde(rename = "correctLabel")]
+    pub correct_label: String,
+    #[serde(rename = "hasImages")]
+    pub has_images: bool,
+    #[serde(rename = "hasMath")]
+    pub has_math: bool,
+    /// Some option is locked ("#A.")
+    #[serde(rename = "hasLockedOptions")]
+    pub has_locked_options: bool,
+    #[serde(rename = "inGroup")]
+    pub in_group: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<String>,
+}
+
+/// `limit` questions starting at `offset` (in document order).
+pub fn page(doc: &ParsedDoc, offset: usize, limit: usize) -> ParsedPage {
+    let questions: Vec<Question> = doc.questions.iter().skip(offset).take(limit).cloned().collect();
+    let groups = doc
+        .groups
+        .iter()
+        .filter(|g| questions.iter().any(|q| g.questions.contains(&q.number)))
+        .cloned()
+        .collect();
+    ParsedPage {
+        total: doc.questions.len(),
+        offset,
+        questions,
+        groups,
+    }
+}
+
+/// One summary row per question.
+pub fn summarize(doc: &ParsedDoc) -> Vec<QuestionSummary> {
+    doc.questions
+        .iter()
+        .map(|question| {
+            let segments = || {
+                question
+                    .stem
+                    .iter()
+                    .chain(question.options.iter().flat_map(|o| o.content.iter()))
+            };
+            let stem = segments_text(&question.stem);
+            let words: Vec<&str> = stem.split_whitespace().collect();
+            let mut preview = words.iter().take(PREVIEW_WORDS).copied().collect::<Vec<_>>().join(" ");
+            if words.len() > PREVIEW_WORDS {
+                preview.push('…');
+            }
+            QuestionSummary {
+                number: question.number,
+                preview,
+                option_count: question.options.len(),
+                correct_label: question.correct_label.clone(),
+                has_images: segments().any(|s| matches!(s, Segment::Image { .. })),
+                has_math: segments().any(|s| matches!(s, Segment::Math { .. })),
+                has_locked_options: question.options.iter().any(|o| o.locked),
+                in_group: doc.groups.iter().any(|g| g.questions.contains(&question.number)),
+                chapter: question.chapter.clone(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_and_summarizes_questions() {
+        let doc = ParsedDoc {
+            questions: (1..=5)
+                .map(|number| Question {
+                    number,
+                    stem: vec![Segment::Text {
+                        text: "Một hai ba bốn năm sáu bảy tám chín mười mười một mười hai mười ba".to_string(),
+                        raw_xml: String::new(),
+                    }],
+                    ..Default::default()
+                })
+                .collect(),
+            groups: vec![QuestionGroup {
+                questions: vec![4, 5],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let second = page(&doc, 2, 2);
+        assert_eq!(second.total, 5);
+        assert_eq!(second.questions.iter().map(|q| q.number).collect::<Vec<_>>(), vec![3, 4]);
+        assert_eq!(second.groups.len(), 1);
+        assert!(page(&doc, 10, 2).questions.is_empty());
+
+        let rows = summarize(&doc);
+        assert_eq!(rows[0].preview, "Một hai ba bốn năm sáu bảy tám chín mười mười một…");
+        assert!(!rows[0].in_group && rows[4].in_group);
+    }
+}
@@ -0,0 +1,191 @@
+// src-tauri/src/docx/symbols.rs
+//! Mapping of `<w:sym>` characters (Insert → Symbol) to Unicode.
+//!
+//! Word stores these as a font name plus a code in the private-use area
+//! (`w:char="F0B3"` = byte 0xB3 of the Symbol font). Symbols we cannot map
+//! are kept as `Segment::Symbol` and written back as the original sym run.
+
+/// Parse the `w:font` and `w:char` attributes of a `<w:sym .../>` tag.
+pub fn parse_sym_tag(tag: &str) -> Option<(String, String)> {
+    let attr = |name: &str| -> Option<String> {
+        let key = format!("{}=\"", name);
+        let start = tag.find(&key)? + key.len();
+        let end = tag[start..].find('"')? + start;
+        Some(tag[start..end].to_string())
+    };
+
+    Some((attr("w:font")?, attr("w:char")?))
+}
+
+/// Unicode character for a symbol code in the given font, if known.
+pub fn sym_to_unicode(font: &str, char_code: &str) -> Option<char> {
+    let code = u32::from_str_radix(char_code, 16).ok()?;
+    // Codes are usually shifted into the private-use area (0xF000 + byte)
+    let byte = if (0xF000..=0xF0FF).contains(&code) { code - 0xF000 } else { code };
+    if byte > 0xFF {
+        return None;
+    }
+
+    match font.to_ascii_lowercase().as_str() {
+        "symbol" => symbol_font(byte as u8),
+        "wingdings" => wingdings_font(byte as u8),
+        _ => None,
+    }
+}
+
+/// Adobe Symbol encoding (the symbols used in maths/physics exams).
+fn symbol_font(byte: u8) -> Option<char> {
+    let ch = match byte {
+        0x22 => '∀',
+        0x24 => '∃',
+        0x27 => '∋',
+        0x2A => '∗',
+        0x2D => '−',
+        0x20..=0x3F => byte as char,
+        0x40 => '≅',
+        0x41 => 'Α',
+        0x42 => 'Β',
+        0x43 => 'Χ',
+        0x44 => 'Δ',
+        0x45 => 'Ε',
+        0x46 => 'Φ',
+        0x47 => 'Γ',
+        0x48 => 'Η',
+        0x49 => 'Ι',
+        0x4A => 'ϑ',
+        0x4B => 'Κ',
+        0x4C => 'Λ',
+        0x4D => 'Μ',
+        0x4E => 'Ν',
+        0x4F => 'Ο',
+        0x50 => 'Π',
+        0x51 => 'Θ',
+        0x52 => 'Ρ',
+        0x53 => 'Σ',
+        0x54 => 'Τ',
+        0x55 => 'Υ',
+        0x56 => 'ς',
+        0x57 => 'Ω',
+        0x58 => 'Ξ',
+        0x59 => 'Ψ',
+        0x5A => 'Ζ',
+        0x5C => '∴',
+        0x5E => '⊥',
+        0x61 => 'α',
+        0x62 => 'β',
+        0x63 => 'χ',
+        0x64 => 'δ',
+        0x65 => 'ε',
+        0x66 => 'φ',
+        0x67 => 'γ',
+        0x68 => 'η',
+        0x69 => 'ι',
+        0x6A => 'ϕ',
+        0x6B => 'κ',
+        0x6C => 'λ',
+        0x6D => 'μ',
+        0x6E => 'ν',
+        0x6F => 'ο',
+        0x70 => 'π',
+        0x71 => 'θ',
+        0x72 => 'ρ',
+        0x73 => 'σ',
+        0x74 => 'τ',
+        0x75 => 'υ',
+        0x76 => 'ϖ',
+        0x77 => 'ω',
+        0x78 => 'ξ',
+        0x79 => 'ψ',
+        0x7A => 'ζ',
+        0x7E => '∼',
+        0xA2 => '′',
+        0xA3 => '≤',
+        0xA5 => '∞',
+        0xAB => '↔',
+        0xAC => '←',
+        0xAD => '↑',
+        0xAE => '→',
+        0xAF => '↓',
+        0xB0 => '°',
+        0xB1 => '±',
+        0xB2 => '″',
+        0xB3 => '≥',
+        0xB4 => '×',
+        0xB5 => '∝',
+        0xB6 => '∂',
+        0xB7 => '•',
+        0xB8 => '÷',
+        0xB9 => '≠',
+        0xBA => '≡',
+        0xBB => '≈',
+        0xBC => '…',
+        0xC6 => '∅',
+        0xC7 => '∩',
+        0xC8 => '∪',
+        0xC9 => '⊃',
+        0xCA => '⊇',
+        0xCC => '⊂',
+        0xCD => '⊆',
+        0xCE => '∈',
+        0xCF => '∉',
+        0xD0 => '∠',
+        0xD1 => '∇',
+        0xD6 => '√',
+        0xD7 => '⋅',
+        0xD8 => '¬',
+        0xD9 => '∧',
+        0xDA => '∨',
+        0xDB => '⇔',
+        0xDC => '⇐',
+        0xDD => '⇑',
+        0xDE => '⇒',
+        0xDF => '⇓',
+        0xE5 => '∑',
+        0xF2 => '∫',
+        _ => return None,
+    };
+    Some(ch)
+}
+
+/// Common Wingdings bullets and check marks.
+fn wingdings_font(byte: u8) -> Option<char> {
+    let ch = match byte {
+        0x4A => '☺',
+        0x4C => '☹',
+        0x6C => '●',
+        0x6E => '■',
+        0x6F => '□',
+        0x9F => '•',
+        0xA7 => '▪',
+        0xA8 => '◻',
+        0xD8 => '➢',
+        0xFB => '✗',
+        0xFC => '✓',
+        0xFD => '☒',
+        0xFE => '☑',
+        _ => return None,
+    };
+    Some(ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_private_use_symbol_codes() {
+        assert_eq!(sym_to_unicode("Symbol", "F0B3"), Some('≥'));
+        assert_eq!(sym_to_unicode("Symbol", "F061"), Some('α'));
+        assert_eq!(sym_to_unicode("Wingdings", "F0FC"), Some('✓'));
+        assert_eq!(sym_to_unicode("Webdings", "F021"), None);
+    }
+
+    #[test]
+    fn parses_sym_tag() {
+        let tag = r#"<w:sym w:font="Symbol" w:char="F0B3"/>"#;
+        assert_eq!(
+            parse_sym_tag(tag),
+            Some(("Symbol".to_string(), "F0B3".to_string()))
+        );
+    }
+}
@@ -0,0 +1,68 @@
+// src-tauri/src/docx/thumbnails.rs
+//! Small PNG previews of the extracted images (WMF/EMF equations included,
+//! through their converted PNG) for the question list, which would otherwise
+//! decode every full-size image.
+//!
+//! Thumbnails live in `<workspace>/thumbnails/<asset file name>.png`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::ExtractedAsset;
+
+/// Longest side of a thumbnail, in pixels
+pub const THUMBNAIL_SIZE: u32 = 160;
+
+/// Thumbnail path of the image at `asset_path`.
+pub fn thumbnail_path(thumbnails_dir: &Path, asset_path: &Path) -> PathBuf {
+    let name = asset_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    thumbnails_dir.join(format!("{}.png", name))
+}
+
+/// Make the thumbnail of one image. Images that cannot be decoded (WMF/EMF
+/// without a converted PNG) are skipped with `Err`.
+pub fn generate(asset_path: &Path, thumbnails_dir: &Path) -> Result<PathBuf, String> {
+    fs::create_dir_all(thumbnails_dir).map_err(|e| e.to_string())?;
+    let image = image::open(asset_path).map_err(|e| format!("{}: {}", asset_path.display(), e))?;
+    let path = thumbnail_path(thumbnails_dir, asset_path);
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .save_with_format(&path, image::ImageFormat::Png)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Thumbnails of every extracted asset. Returns how many were made.
+pub fn generate_all(assets: &[ExtractedAsset], thumbnails_dir: &Path) -> usize {
+    let mut generated = 0;
+    for asset in assets {
+        let source = asset.converted_path.as_ref().unwrap_or(&asset.absolute_path);
+        match generate(source, thumbnails_dir) {
+            Ok(_) => generated += 1,
+            Err(e) => eprintln!("[Thumbnail] Skipped {}: {}", asset.file_name, e),
+        }
+    }
+    generated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shrinks_images_to_thumbnail_size() {
+        let dir = std::env::temp_dir().join(format!("siromix-thumbnails-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("image1.png");
+        image::RgbImage::new(800, 400).save(&source).unwrap();
+
+        let path = generate(&source, &dir.join("thumbnails")).unwrap();
+        assert_eq!(path, dir.join("thumbnails").join("image1.png.png"));
+        assert_eq!(image::image_dimensions(&path).unwrap(), (THUMBNAIL_SIZE, THUMBNAIL_SIZE / 2));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
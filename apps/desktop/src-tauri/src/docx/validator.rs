@@ -1,14 +1,19 @@
-/// Utilities for validating questions and detecting the correct answer
-/// based on DOCX run styling.
-///
-/// Rules:
-/// - The correct answer is marked on the option LABEL (e.g. "A." or "#A.")
-///   using either underline or red color.
-/// - We only inspect runs that belong to the label (from the start of the
-///   label up to the trailing dot). Higher-level parsing code is responsible
-///   for slicing the DOCX runs so that only the label runs are passed here.
-/// - Underline: `<w:rPr><w:u w:val != "none" />`
-/// - Red color: `<w:rPr><w:color w:val="FF0000" />` (case‑insensitive).
+//! Utilities for validating questions and detecting the correct answer
+//! based on DOCX run styling.
+//!
+//! Rules:
+//! - The correct answer is marked on the option LABEL (e.g. "A." or "#A.")
+//!   using either underline or red color.
+//! - We only inspect runs that belong to the label (from the start of the
+//!   label up to the trailing dot). Higher-level parsing code is responsible
+//!   for slicing the DOCX runs so that only the label runs are passed here.
+//! - Underline: `<w:rPr><w:u w:val != "none" />`
+//! - Red color: `<w:rPr><w:color w:val="FF0000" />` (case‑insensitive).
+
+use std::path::Path;
+
+use super::model::{Question, QuestionGroup, Segment};
+use super::{negation, statements};
 
 /// Minimal styling info for a single DOCX run within an option label.
 #[derive(Debug, Clone)]
@@ -54,6 +59,30 @@ pub enum ValidationErrorCode {
     E020CorrectMarkMissing,
     /// More than one option in the question has a correct-marked label.
     E021CorrectMarkMultiple,
+    /// The question has more options than the configured maximum.
+    E022TooManyOptions,
+    /// A shared-data header names a question that is not in the document.
+    E024GroupQuestionMissing,
+    /// The correct label chosen by hand is not an option of the question.
+    E025ManualAnswerInvalid,
+    /// An image referenced by the question could not be extracted or
+    /// converted. Warning only: the question can still be mixed, the export
+    /// shows a placeholder instead of the figure.
+    W001ImageMissing,
+    /// The options name the question's (I), (II), … statements, so the
+    /// statement shuffle leaves this question unchanged. Warning only.
+    W002StatementsNamedByOptions,
+    /// "KHÔNG", "NGOẠI TRỪ" or "SAI" in the stem is neither bold nor
+    /// underlined. Warning only.
+    W003NegativeNotEmphasized,
+    /// A term of the forbidden-terms list (settings) is used. Warning only.
+    W004ForbiddenTerm,
+    /// The correct label was chosen by hand, not read from a mark.
+    W005ManualAnswer,
+    /// The question refers to another question ("ở câu 12") that is not in
+    /// the document, so the reference keeps its number after mixing.
+    /// Warning only: the match may be ordinary prose.
+    W014CrossReferenceMissing,
 }
 
 impl ValidationErrorCode {
@@ -62,6 +91,15 @@ impl ValidationErrorCode {
         match self {
             ValidationErrorCode::E020CorrectMarkMissing => "E020_CORRECT_MARK_MISSING",
             ValidationErrorCode::E021CorrectMarkMultiple => "E021_CORRECT_MARK_MULTIPLE",
+            ValidationErrorCode::E022TooManyOptions => "E022_TOO_MANY_OPTIONS",
+            ValidationErrorCode::E024GroupQuestionMissing => "E024_GROUP_QUESTION_MISSING",
+            ValidationErrorCode::E025ManualAnswerInvalid => "E025_MANUAL_ANSWER_INVALID",
+            ValidationErrorCode::W001ImageMissing => "W001_IMAGE_MISSING",
+            ValidationErrorCode::W002StatementsNamedByOptions => "W002_STATEMENTS_NAMED_BY_OPTIONS",
+            ValidationErrorCode::W003NegativeNotEmphasized => "W003_NEGATIVE_NOT_EMPHASIZED",
+            ValidationErrorCode::W004ForbiddenTerm => "W004_FORBIDDEN_TERM",
+            ValidationErrorCode::W005ManualAnswer => "W005_MANUAL_ANSWER",
+            ValidationErrorCode::W014CrossReferenceMissing => "W014_CROSS_REFERENCE_MISSING",
         }
     }
 }
@@ -111,3 +149,133 @@ pub fn detect_correct_label_for_question(
         }),
     }
 }
+
+/// Check that a correct label chosen by hand is one of the options.
+///
+/// Returns `Err(ValidationError)` with `E025_MANUAL_ANSWER_INVALID`
+/// otherwise.
+pub fn validate_manual_answer(question: &Question, label: &str) -> Result<(), ValidationError> {
+    if question.options.iter().any(|o| o.label == label) {
+        return Ok(());
+    }
+    Err(ValidationError {
+        code: ValidationErrorCode::E025ManualAnswerInvalid,
+        question_number: question.number,
+    })
+}
+
+/// Enforce the configured maximum number of options for a question.
+///
+/// Returns `Err(ValidationError)` with `E022_TOO_MANY_OPTIONS` when the
+/// question has more than `max_options` options, or more than there are
+/// labels whatever the setting.
+pub fn validate_option_count(
+    question_number: u32,
+    option_count: usize,
+    max_options: usize,
+) -> Result<(), ValidationError> {
+    if option_count > super::labels::clamp_max_options(max_options) {
+        return Err(ValidationError {
+            code: ValidationErrorCode::E022TooManyOptions,
+            question_number,
+        });
+    }
+
+    Ok(())
+}
+
+/// Check that every question referenced by `question` exists.
+///
+/// Returns `Err(ValidationError)` with `W014_CROSS_REFERENCE_MISSING`
+/// otherwise.
+pub fn validate_references(question: &Question, question_numbers: &[u32]) -> Result<(), ValidationError> {
+    if question.references.iter().all(|n| question_numbers.contains(n)) {
+        return Ok(());
+    }
+    Err(ValidationError {
+        code: ValidationErrorCode::W014CrossReferenceMissing,
+        question_number: question.number,
+    })
+}
+
+/// Check that every question named by a shared-data header exists.
+///
+/// Returns `Err(ValidationError)` with `E024_GROUP_QUESTION_MISSING` and the
+/// first missing number otherwise.
+pub fn validate_group(group: &QuestionGroup, question_numbers: &[u32]) -> Result<(), ValidationError> {
+    match group.questions.iter().find(|n| !question_numbers.contains(n)) {
+        Some(&missing) => Err(ValidationError {
+            code: ValidationErrorCode::E024GroupQuestionMissing,
+            question_number: missing,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Check whether the statements of the question can be shuffled without
+/// changing its answer.
+///
+/// Returns `Err(ValidationError)` with `W002_STATEMENTS_NAMED_BY_OPTIONS`
+/// when the options name statements.
+pub fn validate_statements(question: &Question) -> Result<(), ValidationError> {
+    if question.statements.is_empty() || !statements::referenced_by_options(&question.options) {
+        return Ok(());
+    }
+    Err(ValidationError {
+        code: ValidationErrorCode::W002StatementsNamedByOptions,
+        question_number: question.number,
+    })
+}
+
+/// Check that negative words of the stem are emphasized.
+///
+/// Returns `Err(ValidationError)` with `W003_NEGATIVE_NOT_EMPHASIZED`
+/// otherwise.
+pub fn validate_negative_stem(question: &Question) -> Result<(), ValidationError> {
+    if !negation::has_unemphasized(question) {
+        return Ok(());
+    }
+    Err(ValidationError {
+        code: ValidationErrorCode::W003NegativeNotEmphasized,
+        question_number: question.number,
+    })
+}
+
+/// Whether an image segment points to an asset that is missing on disk
+/// (extraction/conversion failed or the path is empty).
+pub fn is_image_missing(segment: &Segment) -> bool {
+    match segment {
+        Segment::Image { asset_path, .. } => {
+            asset_path.is_empty() || !Path::new(asset_path).is_file()
+        }
+        _ => false,
+    }
+}
+
+/// Check that every image in the question (stem, options and their
+/// second-language content) resolves to an extracted asset.
+///
+/// Returns `Err(ValidationError)` with `W001_IMAGE_MISSING` otherwise.
+pub fn validate_images(question: &Question) -> Result<(), ValidationError> {
+    let segments = question
+        .stem
+        .iter()
+        .chain(question.stem_alt.iter())
+        .chain(
+            question
+                .options
+                .iter()
+                .flat_map(|o| o.content.iter().chain(o.content_alt.iter())),
+        );
+
+    for segment in segments {
+        if is_image_missing(segment) {
+            return Err(ValidationError {
+                code: ValidationErrorCode::W001ImageMissing,
+                question_number: question.number,
+            });
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,222 @@
+// src-tauri/src/docx/verify.rs
+//! Post-mix answer-key verification
+//! Guards exports against mapping bugs: following the option permutation from
+//! the original correct label must land on the variant's stored answer. The
+//! answers exported are always those of the stored permutations
+//! ([`apply_stored_answers`]), never the ones the frontend sends.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use super::excel::MixedExam;
+use super::mixer::VariantMapping;
+
+/// One question whose stored answer disagrees with its permutation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnswerKeyMismatch {
+    pub exam_code: String,
+    pub display_number: usize,
+    pub original_number: usize,
+    /// Label obtained by following the permutation from the original answer
+    pub expected: Option<String>,
+    /// Label stored as the variant's correct answer
+    pub actual: String,
+}
+
+impl fmt::Display for AnswerKeyMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Đề {} câu {} (câu gốc {}): mong đợi {}, đáp án lưu {}",
+            self.exam_code,
+            self.display_number,
+            self.original_number,
+            self.expected.as_deref().unwrap_or("?"),
+            self.actual
+        )
+    }
+}
+
+/// Verify every variant against the original answers (indexed by `original_number - 1`).
+///
+/// When a stored mapping exists for a variant (keyed by exam code), the
+/// permutation is taken from it; otherwise the `original_label` carried on
+/// each option is used.
+pub fn verify_answer_keys(
+    exams: &[MixedExam],
+    original_answers: &[String],
+    mappings: &HashMap<String, VariantMapping>,
+) -> Vec<AnswerKeyMismatch> {
+    let mut mismatches = Vec::new();
+
+    for exam in exams {
+        let mapping = mappings.get(&exam.exam_code);
+
+        for question in &exam.questions {
+            let original_answer = match question
+                .original_number
+                .checked_sub(1)
+                .and_then(|idx| original_answers.get(idx))
+            {
+                Some(answer) => answer,
+                None => continue,
+            };
+
+            let expected = match mapping {
+                Some(mapping) => mapping
+                    .questions
+                    .iter()
+                    .find(|q| q.original_number as usize == question.original_number)
+                    .and_then(|q| q.options.iter().find(|o| &o.original_label == original_answer))
+                    .map(|o| o.label.clone()),
+                None => question
+                    .options
+                    .iter()
+                    .find(|o| &o.original_label == original_answer)
+                    .map(|o| o.label.clone()),
+            };
+
+            if expected.as_deref() != Some(question.correct_answer.as_str()) {
+                mismatches.push(AnswerKeyMismatch {
+                    exam_code: exam.exam_code.clone(),
+                    display_number: question.display_number,
+                    original_number: question.original_number,
+                    expected,
+                    actual: question.correct_answer.clone(),
+                });
+            }
+        }
+    }
+
+    mismatches
+}
+
+/// Replace the answers of every variant with the ones of its stored
+/// permutation, so the key never comes from the caller. Returns the problems
+/// found: a variant without a mapping, or a question placed or ordered
+/// differently from it.
+pub fn apply_stored_answers(exams: &mut [MixedExam], mappings: &HashMap<String, VariantMapping>) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for exam in exams {
+        let Some(mapping) = mappings.get(&exam.exam_code) else {
+            problems.push(format!("Đề {}: không có hoán vị đã lưu (mapping.json)", exam.exam_code));
+            continue;
+        };
+        if mapping.questions.len() != exam.questions.len() {
+            problems.push(format!(
+                "Đề {}: {} câu, hoán vị đã lưu có {} câu",
+                exam.exam_code,
+                exam.questions.len(),
+                mapping.questions.len()
+            ));
+            continue;
+        }
+
+        for question in &mut exam.questions {
+            let stored = mapping
+                .questions
+                .iter()
+                .find(|q| q.display_number as usize == question.display_number)
+                .filter(|q| q.original_number as usize == question.original_number)
+                .filter(|q| {
+                    q.options.len() == question.options.len()
+                        && q.options
+                            .iter()
+                            .zip(&question.options)
+                            .all(|(stored, option)| stored.label == option.label && stored.original_label == option.original_label)
+                });
+            match stored {
+                Some(stored) => question.correct_answer = stored.correct_answer.clone(),
+                None => problems.push(format!(
+                    "Đề {} câu {} (câu gốc {}): khác hoán vị đã lưu",
+                    exam.exam_code, question.display_number, question.original_number
+                )),
+            }
+        }
+    }
+
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::excel::{MixedOption, MixedQuestion};
+
+    fn exam(correct_answer: &str) -> MixedExam {
+        MixedExam {
+            exam_code: "132".to_string(),
+            questions: vec![MixedQuestion {
+                original_number: 1,
+                display_number: 1,
+                stem: Vec::new(),
+                options: vec![
+                    MixedOption {
+                        label: "A".to_string(),
+                        original_label: "B".to_string(),
+                        content: Vec::new(),
+                        ..Default::default()
+                    },
+                    MixedOption {
+                        label: "B".to_string(),
+                        original_label: "A".to_string(),
+                        content: Vec::new(),
+                        ..Default::default()
+                    },
+                ],
+                correct_answer: correct_answer.to_string(),
+                ..Default::default()
+            }],
+            groups: Vec::new(),
+            parts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_verify_accepts_consistent_key() {
+        let mismatches = verify_answer_keys(&[exam("B")], &["A".to_string()], &HashMap::new());
+        assert!(mismatches.is_empty());
+    }
+
+    fn mapping(correct_answer: &str) -> VariantMapping {
+        use crate::docx::mixer::{OptionMapping, QuestionMapping};
+        let option = |label: &str, original_label: &str| OptionMapping {
+            label: label.to_string(),
+            original_label: original_label.to_string(),
+        };
+        VariantMapping {
+            exam_code: "132".to_string(),
+            seed: 7,
+            questions: vec![QuestionMapping {
+                display_number: 1,
+                original_number: 1,
+                options: vec![option("A", "B"), option("B", "A")],
+                correct_answer: correct_answer.to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_stored_answers_replace_the_callers() {
+        let mut exams = vec![exam("")];
+        let mappings = HashMap::from([("132".to_string(), mapping("B"))]);
+        assert!(apply_stored_answers(&mut exams, &mappings).is_empty());
+        assert_eq!(exams[0].questions[0].correct_answer, "B");
+
+        // Options in another order than the stored permutation
+        exams[0].questions[0].options.swap(0, 1);
+        assert_eq!(apply_stored_answers(&mut exams, &mappings).len(), 1);
+
+        // No mapping at all
+        assert_eq!(apply_stored_answers(&mut exams, &HashMap::new()).len(), 1);
+    }
+
+    #[test]
+    fn test_verify_reports_corrupted_key() {
+        let mismatches = verify_answer_keys(&[exam("A")], &["A".to_string()], &HashMap::new());
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].expected.as_deref(), Some("B"));
+        assert_eq!(mismatches[0].actual, "A");
+    }
+}
@@ -9,22 +9,93 @@ use std::collections::HashMap;
 use zip::write::{FileOptions, ZipWriter};
 use zip::CompressionMethod;
 
-use super::model::{Question, Segment};
-use super::config::NghiDinh30;
+use super::model::{ImageAnchor, Question, Segment, WrapMode};
+use super::config::{A5OnA4, LargePrint, NghiDinh30, COLUMN_GAP_TWIPS};
+use super::cover_page::{paragraph, CoverInfo};
+use super::export_options::{AnswerLine, AnswerSpaceStyle, CorrectMarkStyle, ExportOptions, PrintLayout};
+use super::exam_parts::{self, MixedPart};
+use super::groups::MixedGroup;
+use super::lexicon::{fill, fill_parts};
+use super::blanks;
+use super::fonts;
+use super::negation;
+
+/// Lines taken by the header table, the heading and the instruction line,
+/// for the page estimate
+const HEADER_LINES: usize = 11;
 
 /// Exam writer that generates a complete DOCX file
 pub struct ExamWriter {
     pub exam_code: String,
     pub questions: Vec<Question>,
+    #[allow(dead_code)]
     pub exam_title: String,
     pub subject: String,
     pub duration_minutes: u32,
+    #[allow(dead_code)]
     pub assets_dir: PathBuf,
     // Header metadata
     pub school_name: String,
     pub exam_name: String,
     pub academic_year: String,
     pub grade: String,
+    /// Question prefix, option separator, decimal separator, ...
+    pub options: ExportOptions,
+    /// Accessible copy: larger text, wider spacing, images inline and no
+    /// passthrough of the original formatting
+    pub large_print: bool,
+    /// Shared-data headers, emitted before their first question
+    pub groups: Vec<MixedGroup>,
+    /// Part headings, emitted before their first question instead of the
+    /// multiple-choice heading and instruction line of the header
+    pub parts: Vec<MixedPart>,
+    /// Teacher copy: correct options marked this way, no passthrough of the
+    /// original formatting
+    pub teacher_mark: Option<CorrectMarkStyle>,
+    /// Personalized copy: the student's name, ID and room printed under the
+    /// header (see `roster`)
+    pub student: Option<crate::roster::Student>,
+}
+
+/// Escape XML special characters in text content
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Write a package holding only `document_xml`, for printouts without
+/// images, headers or styles (room maps, answer sheets)
+pub(crate) fn write_document_only(output_path: &Path, document_xml: &str) -> std::io::Result<()> {
+    let mut zip = ZipWriter::new(BufWriter::new(File::create(output_path)?));
+    let options = FileOptions::default();
+    zip.start_file("[Content_Types].xml", options)?;
+    zip.write_all(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+            r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+            r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+            r#"<Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>"#,
+            r#"</Types>"#
+        )
+        .as_bytes(),
+    )?;
+    zip.start_file("_rels/.rels", options)?;
+    zip.write_all(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+            r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/>"#,
+            r#"</Relationships>"#
+        )
+        .as_bytes(),
+    )?;
+    zip.start_file("word/document.xml", options)?;
+    zip.write_all(document_xml.as_bytes())?;
+    zip.finish()?;
+    Ok(())
 }
 
 /// Image information for embedding
@@ -32,6 +103,7 @@ pub struct ExamWriter {
 struct ImageInfo {
     rel_id: String,
     path: PathBuf,
+    #[allow(dead_code)]
     extension: String,
     width_emu: i64,  // Width in EMUs (English Metric Units)
     height_emu: i64, // Height in EMUs
@@ -73,7 +145,19 @@ impl ExamWriter {
         zip.start_file("word/footer1.xml", options)?;
         zip.write_all(self.generate_footer_xml().as_bytes())?;
 
-        // 7. Embed images
+        // 6b. word/footer2.xml (first page: barcode only, no page number)
+        if self.options.page_barcode {
+            zip.start_file("word/footer2.xml", options)?;
+            zip.write_all(self.generate_first_page_footer_xml().as_bytes())?;
+        }
+
+        // 7. word/settings.xml (print layout, only when not standard)
+        if let Some(settings) = self.generate_settings_xml() {
+            zip.start_file("word/settings.xml", options)?;
+            zip.write_all(settings.as_bytes())?;
+        }
+
+        // 8. Embed images
         self.embed_images(&mut zip, options, &image_map)?;
 
         zip.finish()?;
@@ -86,8 +170,8 @@ impl ExamWriter {
         let mut rel_counter = 1;
 
         for question in &self.questions {
-            // Check stem segments
-            for segment in &question.stem {
+            // Check stem segments (both languages)
+            for segment in question.stem.iter().chain(&question.stem_alt) {
                 if let Segment::Image { asset_path, .. } = segment {
                     if !image_map.contains_key(asset_path) {
                         if let Some(info) = self.create_image_info(asset_path, rel_counter) {
@@ -100,7 +184,7 @@ impl ExamWriter {
 
             // Check option segments
             for option in &question.options {
-                for segment in &option.content {
+                for segment in option.content.iter().chain(&option.content_alt) {
                     if let Segment::Image { asset_path, .. } = segment {
                         if !image_map.contains_key(asset_path) {
                             if let Some(info) = self.create_image_info(asset_path, rel_counter) {
@@ -113,6 +197,22 @@ impl ExamWriter {
             }
         }
 
+        // Shared-data paragraphs
+        let group_segments = self
+            .groups
+            .iter()
+            .flat_map(|g| g.header.iter().chain(g.paragraphs.iter().flatten()));
+        for segment in group_segments {
+            if let Segment::Image { asset_path, .. } = segment {
+                if !image_map.contains_key(asset_path) {
+                    if let Some(info) = self.create_image_info(asset_path, rel_counter) {
+                        image_map.insert(asset_path.clone(), info);
+                        rel_counter += 1;
+                    }
+                }
+            }
+        }
+
         image_map
     }
 
@@ -154,6 +254,18 @@ impl ExamWriter {
 
     /// Generate [Content_Types].xml
     fn generate_content_types(&self) -> String {
+        let settings = if self.options.print_layout == PrintLayout::Standard {
+            ""
+        } else {
+            r#"
+    <Override PartName="/word/settings.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.settings+xml"/>"#
+        };
+        let first_page_footer = if self.options.page_barcode {
+            r#"
+    <Override PartName="/word/footer2.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.footer+xml"/>"#
+        } else {
+            ""
+        };
         format!(
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
@@ -166,7 +278,7 @@ impl ExamWriter {
     <Default Extension="emf" ContentType="image/x-emf"/>
     <Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
     <Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/>
-    <Override PartName="/word/footer1.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.footer+xml"/>
+    <Override PartName="/word/footer1.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.footer+xml"/>{first_page_footer}{settings}
 </Types>"#
         )
     }
@@ -189,20 +301,34 @@ impl ExamWriter {
             xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"
             xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing"
             xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
-            xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture">
+            xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture"
+            xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006"
+            xmlns:o="urn:schemas-microsoft-com:office:office"
+            xmlns:v="urn:schemas-microsoft-com:vml"
+            xmlns:w10="urn:schemas-microsoft-com:office:word"
+            xmlns:w14="http://schemas.microsoft.com/office/word/2010/wordml"
+            xmlns:wp14="http://schemas.microsoft.com/office/word/2010/wordprocessingDrawing"
+            xmlns:wps="http://schemas.microsoft.com/office/word/2010/wordprocessingShape"
+            mc:Ignorable="w14 wp14">
     <w:body>"#,
         );
 
-        // Header
-        doc.push_str(&self.generate_header());
+        // Cover page, header and questions
+        doc.push_str(&self.generate_copy(image_map));
 
-        // Questions
-        for (idx, question) in self.questions.iter().enumerate() {
-            doc.push_str(&self.generate_question_xml(idx + 1, question, image_map));
+        // 2-up: end the first copy with its own section (so the second one
+        // starts on the next A5 page with page numbers from 1) and repeat it
+        if self.options.print_layout == PrintLayout::TwoUp {
+            doc.push_str(&format!(
+                r#"
+        <w:p><w:pPr>{}</w:pPr></w:p>"#,
+                self.generate_section_properties(true)
+            ));
+            doc.push_str(&self.generate_copy(image_map));
         }
 
         // Add section properties with page setup (A4) and footer reference
-        doc.push_str(&self.generate_section_properties());
+        doc.push_str(&self.generate_section_properties(true));
 
         doc.push_str(
             r#"
@@ -212,23 +338,231 @@ impl ExamWriter {
         doc
     }
 
+    /// One copy of the exam: cover page (in its own section, so that the
+    /// exam pages are numbered from 1), header, instructions, questions and
+    /// closing. With two
+    /// columns the header ends a full-width section of its own.
+    fn generate_copy(&self, image_map: &HashMap<String, ImageInfo>) -> String {
+        let mut xml = String::new();
+        if let Some(cover) = &self.options.cover_page {
+            let info = CoverInfo {
+                school_name: &self.school_name,
+                exam_name: &self.exam_name,
+                academic_year: &self.academic_year,
+                subject: &self.subject,
+                grade: &self.grade,
+                exam_code: &self.exam_code,
+                duration_minutes: self.duration_minutes,
+                lexicon: &self.options.lexicon(),
+            };
+            xml.push_str(&cover.to_xml(&info, self.body_font_size(), self.text_width()));
+            xml.push_str(&format!(
+                r#"
+        <w:p><w:pPr>{}</w:pPr></w:p>"#,
+                self.generate_section_properties(false)
+            ));
+        }
+        xml.push_str(&self.generate_header());
+        if let Some(student) = &self.student {
+            xml.push_str(&paragraph(&self.student_line(student), self.body_font_size(), true, false));
+        }
+        for line in self.options.instruction_lines(self.questions.len(), self.duration_minutes) {
+            xml.push_str(&paragraph(&line, self.body_font_size(), false, false));
+        }
+        if self.two_columns() {
+            xml.push_str(&format!(
+                r#"
+        <w:p><w:pPr>{}</w:pPr></w:p>"#,
+                self.generate_section_properties(false)
+            ));
+        }
+        xml.push_str(&self.generate_questions_xml(image_map));
+        if let Some(closing) = &self.options.closing {
+            xml.push_str(&closing.to_xml(&self.options.lexicon(), self.body_font_size(), self.column_width()));
+        }
+        xml
+    }
+
+    /// All questions, each part heading and shared-data group right before
+    /// its first question
+    fn generate_questions_xml(&self, image_map: &HashMap<String, ImageInfo>) -> String {
+        let mut xml = String::new();
+        for (idx, question) in self.questions.iter().enumerate() {
+            let num = idx + 1;
+            let mut gap_before = true;
+            for part in self.parts.iter().filter(|p| p.questions.first() == Some(&(num as u32))) {
+                xml.push_str("<w:p>");
+                xml.push_str(&self.paragraph_props(true, false));
+                xml.push_str(&self.text_runs(&exam_parts::heading_text(&part.heading), "<w:b/>"));
+                xml.push_str("</w:p>");
+                gap_before = false;
+            }
+            for group in self.groups.iter().filter(|g| g.questions.first() == Some(&(num as u32))) {
+                for paragraph in std::iter::once(&group.header).chain(&group.paragraphs) {
+                    xml.push_str("<w:p>");
+                    xml.push_str(&self.paragraph_props(gap_before, false));
+                    gap_before = false;
+                    for segment in paragraph {
+                        xml.push_str(&self.segment_to_xml(segment, num, image_map));
+                    }
+                    xml.push_str("</w:p>");
+                }
+            }
+            xml.push_str(&self.generate_question_xml(num, question, gap_before, image_map));
+            if question.options.is_empty() {
+                xml.push_str(&self.generate_answer_space());
+            }
+            xml.push_str(&self.generate_answer_line(question));
+        }
+        xml
+    }
+
+    /// Line under a question where the student records the answer: a row of
+    /// circles to fill in, or "Đáp án:" and a dot leader
+    fn generate_answer_line(&self, question: &Question) -> String {
+        let content = match self.options.answer_line {
+            AnswerLine::None => return String::new(),
+            AnswerLine::Bubbles if !question.options.is_empty() => question
+                .options
+                .iter()
+                .map(|option| format!("○ {}", option.label))
+                .collect::<Vec<_>>()
+                .join("     "),
+            AnswerLine::Line | AnswerLine::Bubbles => format!("{}: ", self.options.lexicon().answer),
+        };
+        let leader = if content.ends_with(' ') {
+            r#"<w:r><w:ptab w:relativeTo="margin" w:alignment="center" w:leader="dot"/></w:r>"#
+        } else {
+            ""
+        };
+        format!(
+            r#"<w:p><w:pPr><w:ind w:left="720"/><w:spacing w:before="60" w:after="120"/></w:pPr><w:r><w:rPr><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/><w:sz w:val="{}"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r>{}</w:p>"#,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_NAME,
+            self.body_font_size(),
+            escape_xml(&content),
+            leader
+        )
+    }
+
+    /// Room to write the answer of a short-answer or essay question: empty
+    /// lines, dotted lines or a box of the configured number of lines
+    fn generate_answer_space(&self) -> String {
+        let space = self.options.answer_space;
+        if space.lines == 0 {
+            return String::new();
+        }
+        // Body text at 1.5 line spacing: half-points × 15 = twips
+        let line_height = self.body_font_size() * 15;
+        let exact = format!(r#"<w:spacing w:before="0" w:after="0" w:line="{line_height}" w:lineRule="exact"/>"#);
+        match space.style {
+            AnswerSpaceStyle::None => String::new(),
+            AnswerSpaceStyle::Blank => format!("<w:p><w:pPr>{exact}</w:pPr></w:p>").repeat(space.lines as usize),
+            // `between` draws the line under every paragraph of the group,
+            // `bottom` under the last one
+            AnswerSpaceStyle::Ruled => format!(
+                r#"<w:p><w:pPr><w:pBdr><w:between w:val="dotted" w:sz="4" w:space="1" w:color="000000"/><w:bottom w:val="dotted" w:sz="4" w:space="1" w:color="000000"/></w:pBdr>{exact}</w:pPr></w:p>"#
+            )
+            .repeat(space.lines as usize),
+            AnswerSpaceStyle::Box => {
+                let width = self.column_width();
+                let height = line_height * space.lines as i32;
+                format!(
+                    r#"<w:tbl><w:tblPr><w:tblW w:w="{width}" w:type="dxa"/><w:tblBorders><w:top w:val="single" w:sz="4" w:space="0" w:color="000000"/><w:left w:val="single" w:sz="4" w:space="0" w:color="000000"/><w:bottom w:val="single" w:sz="4" w:space="0" w:color="000000"/><w:right w:val="single" w:sz="4" w:space="0" w:color="000000"/></w:tblBorders></w:tblPr><w:tblGrid><w:gridCol w:w="{width}"/></w:tblGrid><w:tr><w:trPr><w:cantSplit/><w:trHeight w:val="{height}" w:hRule="exact"/></w:trPr><w:tc><w:tcPr><w:tcW w:w="{width}" w:type="dxa"/></w:tcPr><w:p/></w:tc></w:tr></w:tbl><w:p><w:pPr>{exact}</w:pPr></w:p>"#
+                )
+            }
+        }
+    }
+
     /// Generate header section as a table with left and right columns
     fn generate_header(&self) -> String {
         use super::header_template::StandardHeaderTemplate;
         
-        let total_pages = StandardHeaderTemplate::estimate_pages(self.questions.len());
+        let total_pages = self.estimate_pages();
         let page_text = StandardHeaderTemplate::format_page_count(total_pages);
+        let question_text = self.questions.len().to_string();
+        let points_text = self.options.format_decimal(self.options.points_per_question);
+        let lexicon = self.options.lexicon();
         
         let font = NghiDinh30::FONT_NAME;
-        let size = NghiDinh30::FONT_SIZE_HEADER;
+        let size = if self.large_print {
+            LargePrint::FONT_SIZE_HEADER
+        } else {
+            NghiDinh30::FONT_SIZE_HEADER
+        };
+        // Question and page counts in bold
+        let page_count_runs: String = fill_parts(&lexicon.page_count, &[("questions", &question_text), ("pages", &page_text)])
+            .iter()
+            .map(|(text, value)| {
+                format!(
+                    r#"
+                        <w:r>
+                            <w:rPr>
+                                <w:rFonts w:ascii="{font}" w:hAnsi="{font}" w:cs="{font}" w:eastAsia="{font}"/>{}
+                                <w:sz w:val="{size}"/>
+                            </w:rPr>
+                            <w:t xml:space="preserve">{}</w:t>
+                        </w:r>"#,
+                    if *value { "\n                                <w:b/>" } else { "" },
+                    escape_xml(text)
+                )
+            })
+            .collect();
         let line_spacing = NghiDinh30::HEADER_LINE_SPACING;
         let spacing_after = NghiDinh30::HEADER_SPACING_AFTER;
+        let table_width = self.text_width();
+        let column_width = table_width / 2;
         
+        // "I. PHẦN TRẮC NGHIỆM" (bold) and the instruction line (bold italic),
+        // unless the document has its own part headings and scoring
+        let part_lines = if self.parts.is_empty() {
+            format!(
+                r#"        <w:p>
+            <w:pPr>
+                <w:spacing w:after="60"/>
+            </w:pPr>
+            <w:r>
+                <w:rPr>
+                    <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
+                    <w:b/>
+                    <w:sz w:val="{}"/>
+                </w:rPr>
+                <w:t>{}</w:t>
+            </w:r>
+        </w:p>
+        <w:p>
+            <w:pPr>
+                <w:spacing w:after="60"/>
+            </w:pPr>
+            <w:r>
+                <w:rPr>
+                    <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
+                    <w:b/>
+                    <w:i/>
+                    <w:sz w:val="{}"/>
+                </w:rPr>
+                <w:t xml:space="preserve">{}</w:t>
+            </w:r>
+        </w:p>
+"#,
+                font, font, font, font, size, escape_xml(&lexicon.multiple_choice_part),
+                font, font, font, font, size,
+                escape_xml(&fill(
+                    &lexicon.instructions,
+                    &[("prefix", &self.options.question_prefix), ("points", &points_text)]
+                ))
+            )
+        } else {
+            String::new()
+        };
+
         format!(
             r#"
         <w:tbl>
             <w:tblPr>
-                <w:tblW w:w="9576" w:type="dxa"/>
+                <w:tblW w:w="{table_width}" w:type="dxa"/>
                 <w:tblBorders>
                     <w:top w:val="single" w:sz="4" w:space="0" w:color="000000"/>
                     <w:left w:val="single" w:sz="4" w:space="0" w:color="000000"/>
@@ -239,12 +573,12 @@ impl ExamWriter {
                 </w:tblBorders>
             </w:tblPr>
             <w:tblGrid>
-                <w:gridCol w:w="4788"/>
-                <w:gridCol w:w="4788"/>
+                <w:gridCol w:w="{column_width}"/>
+                <w:gridCol w:w="{column_width}"/>
             </w:tblGrid>
             <w:tr>
                 <w:tc>
-                    <w:tcPr><w:tcW w:w="4788" w:type="dxa"/></w:tcPr>
+                    <w:tcPr><w:tcW w:w="{column_width}" w:type="dxa"/></w:tcPr>
                     <w:p>
                         <w:pPr>
                             <w:jc w:val="center"/>
@@ -271,7 +605,7 @@ impl ExamWriter {
                                 <w:b/>
                                 <w:sz w:val="{}"/>
                             </w:rPr>
-                            <w:t xml:space="preserve">Mã đề thi: {}</w:t>
+                            <w:t xml:space="preserve">{}: {}</w:t>
                         </w:r>
                     </w:p>
                     <w:p>
@@ -285,40 +619,18 @@ impl ExamWriter {
                                 <w:b/>
                                 <w:sz w:val="{}"/>
                             </w:rPr>
-                            <w:t xml:space="preserve">ĐỀ CHÍNH THỨC</w:t>
+                            <w:t xml:space="preserve">{}</w:t>
                         </w:r>
                     </w:p>
                     <w:p>
                         <w:pPr>
                             <w:jc w:val="center"/>
                             <w:spacing w:line="{}" w:lineRule="auto" w:after="{}"/>
-                        </w:pPr>
-                        <w:r>
-                            <w:rPr>
-                                <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
-                                <w:sz w:val="{}"/>
-                            </w:rPr>
-                            <w:t xml:space="preserve">(Gồm </w:t>
-                        </w:r>
-                        <w:r>
-                            <w:rPr>
-                                <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
-                                <w:b/>
-                                <w:sz w:val="{}"/>
-                            </w:rPr>
-                            <w:t xml:space="preserve">{}</w:t>
-                        </w:r>
-                        <w:r>
-                            <w:rPr>
-                                <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
-                                <w:sz w:val="{}"/>
-                            </w:rPr>
-                            <w:t xml:space="preserve"> trang)</w:t>
-                        </w:r>
+                        </w:pPr>{}
                     </w:p>
                 </w:tc>
                 <w:tc>
-                    <w:tcPr><w:tcW w:w="4788" w:type="dxa"/></w:tcPr>
+                    <w:tcPr><w:tcW w:w="{column_width}" w:type="dxa"/></w:tcPr>
                     <w:p>
                         <w:pPr>
                             <w:jc w:val="center"/>
@@ -344,7 +656,7 @@ impl ExamWriter {
                                 <w:b/>
                                 <w:sz w:val="{}"/>
                             </w:rPr>
-                            <w:t xml:space="preserve">Năm học: {}</w:t>
+                            <w:t xml:space="preserve">{}: {}</w:t>
                         </w:r>
                     </w:p>
                     <w:p>
@@ -358,7 +670,7 @@ impl ExamWriter {
                                 <w:b/>
                                 <w:sz w:val="{}"/>
                             </w:rPr>
-                            <w:t xml:space="preserve">Tên môn: {}, {}</w:t>
+                            <w:t xml:space="preserve">{}: {}, {}</w:t>
                         </w:r>
                     </w:p>
                     <w:p>
@@ -372,7 +684,7 @@ impl ExamWriter {
                                 <w:i/>
                                 <w:sz w:val="{}"/>
                             </w:rPr>
-                            <w:t xml:space="preserve">Thời gian làm bài: {}</w:t>
+                            <w:t xml:space="preserve">{}</w:t>
                         </w:r>
                     </w:p>
                     <w:p>
@@ -386,7 +698,7 @@ impl ExamWriter {
                                 <w:i/>
                                 <w:sz w:val="{}"/>
                             </w:rPr>
-                            <w:t xml:space="preserve">(Không kể thời gian phát đề)</w:t>
+                            <w:t xml:space="preserve">{}</w:t>
                         </w:r>
                     </w:p>
                 </w:tc>
@@ -404,99 +716,61 @@ impl ExamWriter {
                     <w:b/>
                     <w:sz w:val="{}"/>
                 </w:rPr>
-                <w:t>ĐỀ</w:t>
-            </w:r>
-        </w:p>
-        <w:p>
-            <w:pPr>
-                <w:spacing w:after="60"/>
-            </w:pPr>
-            <w:r>
-                <w:rPr>
-                    <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
-                    <w:b/>
-                    <w:sz w:val="{}"/>
-                </w:rPr>
-                <w:t>I. PHẦN TRẮC NGHIỆM</w:t>
-            </w:r>
-        </w:p>
-        <w:p>
-            <w:pPr>
-                <w:spacing w:after="60"/>
-            </w:pPr>
-            <w:r>
-                <w:rPr>
-                    <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
-                    <w:b/>
-                    <w:i/>
-                    <w:sz w:val="{}"/>
-                </w:rPr>
-                <w:t>Chọn ý trả lời đúng nhất trong các câu sau bằng cách ghi vào bài làm </w:t>
-            </w:r>
-            <w:r>
-                <w:rPr>
-                    <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
-                    <w:b/>
-                    <w:i/>
-                    <w:sz w:val="{}"/>
-                </w:rPr>
-                <w:t>(ví dụ: Câu 1A; Câu 2B...)</w:t>
-            </w:r>
-            <w:r>
-                <w:rPr>
-                    <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
-                    <w:b/>
-                    <w:i/>
-                    <w:sz w:val="{}"/>
-                </w:rPr>
-                <w:t>, mỗi câu chọn đúng được 0,25 điểm.</w:t>
+                <w:t>{}</w:t>
             </w:r>
         </w:p>
-"#,
+{}"#,
             // Left column - School name (bold + underline)
-            line_spacing, spacing_after, font, font, font, font, size, self.school_name,
+            line_spacing, spacing_after, font, font, font, font, size, escape_xml(&self.school_name),
             // Exam code (bold)
-            line_spacing, spacing_after, font, font, font, font, size, self.exam_code,
+            line_spacing, spacing_after, font, font, font, font, size, escape_xml(&lexicon.exam_code), escape_xml(&self.exam_code),
             // "ĐỀ CHÍNH THỨC" (bold)
-            line_spacing, spacing_after, font, font, font, font, size,
-            // Page count ("Gồm" normal, number bold, "trang" normal)
-            line_spacing, spacing_after, font, font, font, font, size,
-            font, font, font, font, size, page_text,
-            font, font, font, font, size,
+            line_spacing, spacing_after, font, font, font, font, size, escape_xml(&lexicon.official_exam),
+            // Question and page counts (numbers bold)
+            line_spacing, spacing_after, page_count_runs,
             // Right column - Exam name (bold)
-            line_spacing, spacing_after, font, font, font, font, size, self.exam_name,
+            line_spacing, spacing_after, font, font, font, font, size, escape_xml(&self.exam_name),
             // Academic year (bold)
-            line_spacing, spacing_after, font, font, font, font, size, self.academic_year,
+            line_spacing, spacing_after, font, font, font, font, size, escape_xml(&lexicon.academic_year), escape_xml(&self.academic_year),
             // Subject and grade (bold)
-            line_spacing, spacing_after, font, font, font, font, size, self.subject, self.grade,
+            line_spacing, spacing_after, font, font, font, font, size, escape_xml(&lexicon.subject), escape_xml(&self.subject), escape_xml(&self.grade),
             // Duration (italic)
-            line_spacing, spacing_after, font, font, font, font, size, self.duration_minutes,
-            // Distribution note (italic)
             line_spacing, spacing_after, font, font, font, font, size,
+            escape_xml(&fill(&lexicon.duration, &[("minutes", &self.duration_minutes.to_string())])),
+            // Distribution note (italic)
+            line_spacing, spacing_after, font, font, font, font, size, escape_xml(&lexicon.distribution_note),
             // "ĐỀ" heading (bold, centered)
-            font, font, font, font, size,
-            // "I. PHẦN TRẮC NGHIỆM" (bold)
-            font, font, font, font, size,
-            // Instruction line (bold) - part 1
-            font, font, font, font, size,
-            // Instruction line (bold) - example part
-            font, font, font, font, size,
-            // Instruction line (bold) - rest
-            font, font, font, font, size
+            font, font, font, font, size, escape_xml(&lexicon.exam_heading),
+            part_lines
         )
     }
 
     /// Generate XML for a single question
-    fn generate_question_xml(&self, num: usize, question: &Question, image_map: &HashMap<String, ImageInfo>) -> String {
+    /// `gap_before`: the question is not preceded by its shared-data group,
+    /// which already took the space between questions
+    fn generate_question_xml(
+        &self,
+        num: usize,
+        question: &Question,
+        gap_before: bool,
+        image_map: &HashMap<String, ImageInfo>,
+    ) -> String {
+        if self.options.raw_passthrough && !self.large_print && self.teacher_mark.is_none() {
+            if let Some(xml) = self.generate_raw_question_xml(num, question, image_map) {
+                return xml;
+            }
+        }
+
         let mut xml = String::new();
 
         // Question stem paragraph
         xml.push_str("<w:p>");
+        xml.push_str(&self.paragraph_props(gap_before, false));
         
-        // Check if first segment already contains "Câu X." prefix
-        let stem_has_prefix = question.stem.first().map_or(false, |seg| {
+        // Check if first segment already contains the question prefix ("Câu X.")
+        let stem_has_prefix = question.stem.first().is_some_and(|seg| {
             match seg {
-                Segment::Text { text, .. } => text.contains("Câu"),
+                Segment::Text { text, .. } => text.starts_with(&self.options.question_heading(num)),
                 _ => false,
             }
         });
@@ -504,23 +778,26 @@ impl ExamWriter {
         if !stem_has_prefix {
             // Add question number prefix if not already in content
             xml.push_str(&format!(
-                r#"<w:r><w:rPr><w:b/><w:sz w:val="{}"/><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/></w:rPr><w:t>Câu {}. </w:t></w:r>"#,
-                NghiDinh30::FONT_SIZE_BODY,
+                r#"<w:r><w:rPr><w:b/><w:sz w:val="{}"/><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/></w:rPr><w:t xml:space="preserve">{} </w:t></w:r>"#,
+                self.body_font_size(),
                 NghiDinh30::FONT_NAME,
                 NghiDinh30::FONT_NAME,
                 NghiDinh30::FONT_NAME,
                 NghiDinh30::FONT_NAME,
-                num
+                escape_xml(&self.options.question_heading(num))
             ));
         }
 
         // Stem content
-        for segment in &question.stem {
-            xml.push_str(&self.segment_to_xml(segment, image_map));
+        for (idx, segment) in question.stem.iter().enumerate() {
+            match segment {
+                Segment::Blank { width } if idx + 1 == question.stem.len() => xml.push_str(&self.blank_xml(*width, true)),
+                _ => xml.push_str(&self.stem_segment_to_xml(segment, num, image_map)),
+            }
         }
         
         // Check if stem ends with ? or : and add : if needed
-        let needs_punctuation = question.stem.last().map_or(false, |seg| {
+        let needs_punctuation = question.stem.last().is_some_and(|seg| {
             if let Segment::Text { text, .. } = seg {
                 let trimmed = text.trim_end();
                 !trimmed.ends_with('?') && !trimmed.ends_with(':')
@@ -536,24 +813,45 @@ impl ExamWriter {
                 NghiDinh30::FONT_NAME,
                 NghiDinh30::FONT_NAME,
                 NghiDinh30::FONT_NAME,
-                NghiDinh30::FONT_SIZE_BODY
+                self.body_font_size()
             ));
         }
         
         xml.push_str("</w:p>");
 
+        // Bilingual: English stem in its own paragraph with distinct styling
+        if !question.stem_alt.is_empty() {
+            xml.push_str("<w:p>");
+            xml.push_str(&self.paragraph_props(false, false));
+            for segment in &question.stem_alt {
+                xml.push_str(&self.alt_segment_to_xml(segment, num, image_map));
+            }
+            xml.push_str("</w:p>");
+        }
+
         // Options
         for option in &question.options {
             xml.push_str("<w:p>");
+            xml.push_str(&self.paragraph_props(false, true));
             
+            // Teacher copy: formatting of the correct option's label and content
+            let mark = self.teacher_mark.filter(|_| option.label == question.correct_label);
+            let (label_props, label_underline, content_props) = match mark {
+                Some(CorrectMarkStyle::Underline) => ("", r#"<w:u w:val="single"/>"#, ""),
+                Some(CorrectMarkStyle::Red) => (r#"<w:color w:val="FF0000"/>"#, "", r#"<w:color w:val="FF0000"/>"#),
+                Some(CorrectMarkStyle::Bold) => ("", "", "<w:b/>"),
+                _ => ("", "", ""),
+            };
+
             // Check if first segment already contains option label
-            let option_has_prefix = option.content.first().map_or(false, |seg| {
+            let label_text = self.options.option_label(&option.label);
+            let option_has_prefix = option.content.first().is_some_and(|seg| {
                 match seg {
                     Segment::Text { text, .. } => {
-                        text.starts_with(&format!("{}.", option.label)) ||
-                        text.starts_with(&format!("#{}.", option.label)) ||
-                        text.contains(&format!("{}. ", option.label)) ||
-                        text.contains(&format!("#{}. ", option.label))
+                        text.starts_with(&label_text) ||
+                        text.starts_with(&format!("#{}", label_text)) ||
+                        text.contains(&format!("{} ", label_text)) ||
+                        text.contains(&format!("#{} ", label_text))
                     },
                     _ => false,
                 }
@@ -562,19 +860,24 @@ impl ExamWriter {
             if !option_has_prefix {
                 // Add option label prefix if not already in content
                 let label_str = if option.locked {
-                    format!("#{}. ", option.label)
+                    format!("#{} ", label_text)
                 } else {
-                    format!("{}. ", option.label)
+                    format!("{} ", label_text)
                 };
+                // The separator space stays out of an underline
+                let label_str = label_str.trim_end();
                 xml.push_str(&format!(
-                    r#"<w:r><w:rPr><w:b/><w:sz w:val="{}"/><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/></w:rPr><w:t>{}</w:t></w:r>"#,
-                    NghiDinh30::FONT_SIZE_BODY,
+                    r#"<w:r><w:rPr><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/><w:b/>{}<w:sz w:val="{}"/>{}</w:rPr><w:t xml:space="preserve">{}</w:t></w:r>"#,
                     NghiDinh30::FONT_NAME,
                     NghiDinh30::FONT_NAME,
                     NghiDinh30::FONT_NAME,
                     NghiDinh30::FONT_NAME,
-                    label_str
+                    label_props,
+                    self.body_font_size(),
+                    label_underline,
+                    escape_xml(label_str)
                 ));
+                xml.push_str(&self.text_runs(" ", ""));
             }
 
             // Option content - capitalize first letter
@@ -583,33 +886,146 @@ impl ExamWriter {
                     // Capitalize first segment if we added the label
                     if let Segment::Text { text, .. } = segment {
                         let capitalized = Self::capitalize_first_char(text);
-                        let escaped = capitalized
-                            .replace('&', "&amp;")
-                            .replace('<', "&lt;")
-                            .replace('>', "&gt;")
-                            .replace('"', "&quot;");
-                        xml.push_str(&format!(
-                            r#"<w:r><w:rPr><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/><w:sz w:val="{}"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r>"#,
-                            NghiDinh30::FONT_NAME,
-                            NghiDinh30::FONT_NAME,
-                            NghiDinh30::FONT_NAME,
-                            NghiDinh30::FONT_NAME,
-                            NghiDinh30::FONT_SIZE_BODY,
-                            escaped
-                        ));
+                        xml.push_str(&self.text_runs(&capitalized, content_props));
                     } else {
-                        xml.push_str(&self.segment_to_xml(segment, image_map));
+                        xml.push_str(&self.segment_to_xml(segment, num, image_map));
                     }
+                } else if let (Segment::Blank { width }, true) = (segment, idx + 1 == option.content.len()) {
+                    xml.push_str(&self.blank_xml(*width, true));
+                } else if let (Segment::Text { text, .. }, false) = (segment, content_props.is_empty()) {
+                    xml.push_str(&self.text_runs(text, content_props));
                 } else {
-                    xml.push_str(&self.segment_to_xml(segment, image_map));
+                    xml.push_str(&self.segment_to_xml(segment, num, image_map));
+                }
+            }
+            if mark == Some(CorrectMarkStyle::Asterisk) {
+                xml.push_str(&self.text_runs(" *", "<w:b/>"));
+            }
+
+            // Bilingual: English content on a new line within the same option
+            if !option.content_alt.is_empty() {
+                xml.push_str("<w:r><w:br/></w:r>");
+                for segment in &option.content_alt {
+                    xml.push_str(&self.alt_segment_to_xml(segment, num, image_map));
                 }
             }
             xml.push_str("</w:p>");
         }
 
+        if self.teacher_mark == Some(CorrectMarkStyle::AnswerLine) && !question.options.is_empty() {
+            xml.push_str("<w:p>");
+            xml.push_str(&self.paragraph_props(false, true));
+            xml.push_str(&self.text_runs(&format!("→ {}: {}", self.options.lexicon().answer, question.correct_label), "<w:b/>"));
+            xml.push_str("</w:p>");
+        }
+
         xml
     }
 
+    /// Paragraph properties for the `spacing` option; empty when the spacing
+    /// is the plain single spacing written before the option existed
+    fn paragraph_props(&self, gap_before: bool, option: bool) -> String {
+        if self.large_print {
+            return String::new();
+        }
+        let spacing = self.options.spacing.values();
+        // 1 pt = 20 twips, 1 cm = 567 twips, single line = 240
+        let before = if gap_before { (spacing.question_gap_pt * 20.0).round() as i32 } else { 0 };
+        let after = (spacing.paragraph_after_pt * 20.0).round() as i32;
+        let line = (spacing.line_spacing * 240.0).round() as i32;
+        let indent = if option { (spacing.option_indent_cm * 567.0).round() as i32 } else { 0 };
+
+        let mut props = String::new();
+        if before != 0 || after != 0 || line != 240 {
+            props.push_str(&format!(
+                r#"<w:spacing w:before="{before}" w:after="{after}" w:line="{line}" w:lineRule="auto"/>"#
+            ));
+        }
+        if indent != 0 {
+            props.push_str(&format!(r#"<w:ind w:left="{indent}"/>"#));
+        }
+        if props.is_empty() {
+            props
+        } else {
+            format!("<w:pPr>{props}</w:pPr>")
+        }
+    }
+
+    /// Splice the original paragraphs of a question, renumbered and
+    /// relabelled, with image relationships remapped to this package.
+    /// Returns `None` when the question has no raw paragraphs (or an option
+    /// shared its paragraph) or references content we did not copy.
+    fn generate_raw_question_xml(&self, num: usize, question: &Question, image_map: &HashMap<String, ImageInfo>) -> Option<String> {
+        use super::passthrough;
+        use regex::Regex;
+
+        if question.raw_paragraphs.is_empty()
+            || question.options.iter().any(|o| o.raw_paragraphs.is_empty())
+        {
+            return None;
+        }
+
+        // Source relationship id -> relationship id in the output package
+        let mut rel_map = HashMap::new();
+        let segments = question
+            .stem
+            .iter()
+            .chain(question.options.iter().flat_map(|o| o.content.iter()));
+        for segment in segments {
+            if let Segment::Image { asset_path, raw_xml, .. } = segment {
+                if let (Some(source_id), Some(info)) =
+                    (passthrough::image_relationship_id(raw_xml), image_map.get(asset_path))
+                {
+                    rel_map.insert(source_id, info.rel_id.clone());
+                }
+            }
+        }
+
+        let label_run = |text: &str| {
+            format!(
+                r#"<w:r><w:rPr><w:b/><w:sz w:val="{}"/><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/></w:rPr><w:t xml:space="preserve">{} </w:t></w:r>"#,
+                self.body_font_size(),
+                NghiDinh30::FONT_NAME,
+                NghiDinh30::FONT_NAME,
+                NghiDinh30::FONT_NAME,
+                NghiDinh30::FONT_NAME,
+                escape_xml(text)
+            )
+        };
+
+        let mut xml = String::new();
+
+        // Stem: replace the heading of the "Câu N." paragraph, keep the rest
+        let heading_re = Regex::new(r"^(Câu|Question)\s+\d+\.").unwrap();
+        let mut heading_done = false;
+        for paragraph in &question.raw_paragraphs {
+            let paragraph = match passthrough::strip_text_prefix(paragraph, &heading_re) {
+                Some(stripped) if !heading_done => {
+                    heading_done = true;
+                    passthrough::insert_leading_run(&stripped, &label_run(&self.options.question_heading(num)))
+                }
+                _ => paragraph.clone(),
+            };
+            xml.push_str(&passthrough::remap_relationships(&paragraph, &rel_map)?);
+        }
+        if !heading_done {
+            return None;
+        }
+
+        // Options: first paragraph carries the (new) label
+        let option_re = Regex::new(r"^#?[A-Z]\s*\.").unwrap();
+        for option in &question.options {
+            let first = passthrough::strip_text_prefix(&option.raw_paragraphs[0], &option_re)?;
+            let first = passthrough::insert_leading_run(&first, &label_run(&self.options.option_label(&option.label)));
+            xml.push_str(&passthrough::remap_relationships(&first, &rel_map)?);
+            for paragraph in &option.raw_paragraphs[1..] {
+                xml.push_str(&passthrough::remap_relationships(paragraph, &rel_map)?);
+            }
+        }
+
+        Some(xml)
+    }
+
     /// Capitalize first character of a string
     fn capitalize_first_char(s: &str) -> String {
         let mut chars = s.chars();
@@ -624,7 +1040,7 @@ impl ExamWriter {
     }
 
     /// Convert segment to OpenXML - generate clean XML with embedded images
-    fn segment_to_xml(&self, segment: &Segment, image_map: &HashMap<String, ImageInfo>) -> String {
+    fn segment_to_xml(&self, segment: &Segment, num: usize, image_map: &HashMap<String, ImageInfo>) -> String {
         match segment {
             Segment::Text { text, .. } => {
                 // Generate clean text run with basic formatting
@@ -632,51 +1048,130 @@ impl ExamWriter {
                     return String::new();
                 }
                 
-                // Escape XML special characters
-                let escaped = text
-                    .replace('&', "&amp;")
-                    .replace('<', "&lt;")
-                    .replace('>', "&gt;")
-                    .replace('"', "&quot;");
-                
-                format!(
-                    r#"<w:r><w:rPr><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/><w:sz w:val="{}"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r>"#,
-                    NghiDinh30::FONT_NAME,
-                    NghiDinh30::FONT_NAME,
-                    NghiDinh30::FONT_NAME,
-                    NghiDinh30::FONT_NAME,
-                    NghiDinh30::FONT_SIZE_BODY,
-                    escaped
-                )
+                self.text_runs(text, "")
             }
-            Segment::Image { asset_path, width_emu, height_emu, .. } => {
+            Segment::Image { asset_path, width_emu, height_emu, anchor, .. } => {
+                let Some(img_info) = image_map.get(asset_path) else {
+                    return self.missing_image_xml(num);
+                };
                 // Use dimensions from original document if available, otherwise use from file
-                if *width_emu > 0 && *height_emu > 0 {
-                    // Use original document dimensions
-                    if let Some(img_info) = image_map.get(asset_path) {
-                        self.generate_image_xml(&img_info.rel_id, *width_emu, *height_emu)
-                    } else {
-                        format!(r#"<w:r><w:rPr><w:sz w:val="{}"/></w:rPr><w:t>[Image not found]</w:t></w:r>"#, NghiDinh30::FONT_SIZE_BODY)
-                    }
+                let (cx, cy) = if *width_emu > 0 && *height_emu > 0 {
+                    (*width_emu, *height_emu)
                 } else {
-                    // Fallback to image file dimensions
-                    if let Some(img_info) = image_map.get(asset_path) {
-                        self.generate_image_xml(&img_info.rel_id, img_info.width_emu, img_info.height_emu)
-                    } else {
-                        format!(r#"<w:r><w:rPr><w:sz w:val="{}"/></w:rPr><w:t>[Image not found]</w:t></w:r>"#, NghiDinh30::FONT_SIZE_BODY)
+                    (img_info.width_emu, img_info.height_emu)
+                };
+                // Floating images are re-emitted inline (after the text, see
+                // parser) unless the user asked to keep floating placement.
+                match anchor {
+                    Some(anchor) if self.options.preserve_floating_images && !self.large_print => {
+                        self.generate_anchored_image_xml(&img_info.rel_id, cx, cy, anchor)
                     }
+                    _ => self.generate_image_xml(&img_info.rel_id, cx, cy),
                 }
             }
+            Segment::Symbol { font, char_code, .. } => format!(
+                r#"<w:r><w:rPr><w:rFonts w:ascii="{}" w:hAnsi="{}"/><w:sz w:val="{}"/></w:rPr><w:sym w:font="{}" w:char="{}"/></w:r>"#,
+                escape_xml(font),
+                escape_xml(font),
+                self.body_font_size(),
+                escape_xml(font),
+                escape_xml(char_code)
+            ),
             Segment::Math { omml, .. } => {
                 // Use the OMML content directly
                 // Wrap it in a run
                 format!(r#"<w:r>{}</w:r>"#, omml)
             }
+            Segment::Blank { width } => self.blank_xml(*width, false),
+            // The recording is handed out with the variants, not embedded
+            Segment::Audio { file_name, .. } => {
+                self.text_runs(&format!("[{}: {}]", self.options.lexicon().audio, file_name), "")
+            }
         }
     }
 
+    /// Fill-in blank: dots of a fixed length inside a line, or a dot leader
+    /// up to the right margin when the blank ends the paragraph, so that
+    /// blanks keep their size whatever the new line breaks
+    fn blank_xml(&self, width: u32, trailing: bool) -> String {
+        let content = if trailing {
+            r#"<w:ptab w:relativeTo="margin" w:alignment="right" w:leader="dot"/>"#.to_string()
+        } else {
+            format!(r#"<w:t>{}</w:t>"#, ".".repeat(blanks::printed_dots(width)))
+        };
+        format!(
+            r#"<w:r><w:rPr><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/><w:sz w:val="{}"/></w:rPr>{}</w:r>"#,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_NAME,
+            self.body_font_size(),
+            content
+        )
+    }
+
+    /// Runs of body text with formatting `props` (e.g. `<w:b/>`), one per
+    /// font needed (see `fonts`)
+    fn text_runs(&self, text: &str, props: &str) -> String {
+        fonts::split(text)
+            .into_iter()
+            .map(|(piece, class)| {
+                format!(
+                    r#"<w:r><w:rPr>{}{}<w:sz w:val="{}"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r>"#,
+                    fonts::r_fonts(class),
+                    props,
+                    self.body_font_size(),
+                    escape_xml(piece)
+                )
+            })
+            .collect()
+    }
+
+    /// Convert a stem segment to OpenXML, bolding negative words when asked.
+    fn stem_segment_to_xml(&self, segment: &Segment, num: usize, image_map: &HashMap<String, ImageInfo>) -> String {
+        match segment {
+            Segment::Text { text, .. } if self.options.bold_negative_words => negation::split(text)
+                .into_iter()
+                .map(|(piece, negative)| self.text_runs(piece, if negative { "<w:b/>" } else { "" }))
+                .collect(),
+            _ => self.segment_to_xml(segment, num, image_map),
+        }
+    }
+
+    /// Convert a second-language (English) segment to OpenXML.
+    /// Text is rendered italic in a dark blue so both languages are easy to tell apart.
+    fn alt_segment_to_xml(&self, segment: &Segment, num: usize, image_map: &HashMap<String, ImageInfo>) -> String {
+        match segment {
+            Segment::Text { text, .. } if !text.is_empty() => self.text_runs(text, r#"<w:i/><w:color w:val="1F4E79"/>"#),
+            _ => self.segment_to_xml(segment, num, image_map),
+        }
+    }
+
+    /// Framed red placeholder for an image that could not be extracted or
+    /// converted, so the missing figure is obvious on a proof print.
+    fn missing_image_xml(&self, num: usize) -> String {
+        format!(
+            r#"<w:r><w:rPr><w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/><w:b/><w:color w:val="FF0000"/><w:sz w:val="{}"/><w:bdr w:val="single" w:sz="8" w:space="0" w:color="FF0000"/></w:rPr><w:t xml:space="preserve">[{} – {} {}]</w:t></w:r>"#,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_NAME,
+            self.body_font_size(),
+            escape_xml(&self.options.lexicon().missing_image),
+            escape_xml(&self.options.question_prefix),
+            num
+        )
+    }
+
     /// Generate DrawingML XML for an image with actual dimensions
     fn generate_image_xml(&self, rel_id: &str, width_emu: i64, height_emu: i64) -> String {
+        // Scale down images wider than a column (1 twip = 635 EMU)
+        let max_width = self.column_width() as i64 * 635;
+        let (width_emu, height_emu) = if self.two_columns() && width_emu > max_width {
+            (max_width, height_emu * max_width / width_emu)
+        } else {
+            (width_emu, height_emu)
+        };
 
         format!(
             r#"<w:r>
@@ -720,6 +1215,88 @@ impl ExamWriter {
         )
     }
 
+    /// Generate a floating (`<wp:anchor>`) image keeping the original
+    /// position and wrap mode. Only the `wp:` position elements are copied
+    /// from the source; everything else is regenerated so no foreign
+    /// namespaces or relationship ids leak into the output.
+    fn generate_anchored_image_xml(&self, rel_id: &str, width_emu: i64, height_emu: i64, anchor: &ImageAnchor) -> String {
+        let copy_block = |tag: &str, fallback: &str| -> String {
+            let open = format!("<wp:{}", tag);
+            let close = format!("</wp:{}>", tag);
+            anchor
+                .anchor_xml
+                .find(&open)
+                .and_then(|start| {
+                    anchor.anchor_xml[start..]
+                        .find(&close)
+                        .map(|end| anchor.anchor_xml[start..start + end + close.len()].to_string())
+                })
+                .unwrap_or_else(|| fallback.to_string())
+        };
+        let position_h = copy_block(
+            "positionH",
+            r#"<wp:positionH relativeFrom="column"><wp:align>right</wp:align></wp:positionH>"#,
+        );
+        let position_v = copy_block(
+            "positionV",
+            r#"<wp:positionV relativeFrom="paragraph"><wp:posOffset>0</wp:posOffset></wp:positionV>"#,
+        );
+        let wrap = match anchor.wrap {
+            // Tight/through need a wrap polygon; square is the closest safe equivalent
+            WrapMode::Square | WrapMode::Tight | WrapMode::Through => {
+                r#"<wp:wrapSquare wrapText="bothSides"/>"#
+            }
+            WrapMode::TopAndBottom => "<wp:wrapTopAndBottom/>",
+            WrapMode::None => "<wp:wrapNone/>",
+        };
+        let behind_doc = if anchor.anchor_xml.contains(r#"behindDoc="1""#) { 1 } else { 0 };
+
+        format!(
+            r#"<w:r>
+    <w:drawing>
+        <wp:anchor distT="0" distB="0" distL="114300" distR="114300" simplePos="0" relativeHeight="1" behindDoc="{}" locked="0" layoutInCell="1" allowOverlap="1">
+            <wp:simplePos x="0" y="0"/>
+            {}
+            {}
+            <wp:extent cx="{}" cy="{}"/>
+            <wp:effectExtent l="0" t="0" r="0" b="0"/>
+            {}
+            <wp:docPr id="1" name="Picture"/>
+            <wp:cNvGraphicFramePr>
+                <a:graphicFrameLocks xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" noChangeAspect="1"/>
+            </wp:cNvGraphicFramePr>
+            <a:graphic xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+                <a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">
+                    <pic:pic xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture">
+                        <pic:nvPicPr>
+                            <pic:cNvPr id="0" name="Picture"/>
+                            <pic:cNvPicPr/>
+                        </pic:nvPicPr>
+                        <pic:blipFill>
+                            <a:blip r:embed="{}"/>
+                            <a:stretch>
+                                <a:fillRect/>
+                            </a:stretch>
+                        </pic:blipFill>
+                        <pic:spPr>
+                            <a:xfrm>
+                                <a:off x="0" y="0"/>
+                                <a:ext cx="{}" cy="{}"/>
+                            </a:xfrm>
+                            <a:prstGeom prst="rect">
+                                <a:avLst/>
+                            </a:prstGeom>
+                        </pic:spPr>
+                    </pic:pic>
+                </a:graphicData>
+            </a:graphic>
+        </wp:anchor>
+    </w:drawing>
+</w:r>"#,
+            behind_doc, position_h, position_v, width_emu, height_emu, wrap, rel_id, width_emu, height_emu
+        )
+    }
+
     /// Generate word/_rels/document.xml.rels with image relationships
     fn generate_document_rels(&self, image_map: &HashMap<String, ImageInfo>) -> String {
         let mut rels = String::from(
@@ -729,7 +1306,7 @@ impl ExamWriter {
         );
 
         // Add image relationships
-        for (_, img_info) in image_map {
+        for img_info in image_map.values() {
             let filename = img_info.path
                 .file_name()
                 .and_then(|n| n.to_str())
@@ -742,12 +1319,50 @@ impl ExamWriter {
             ));
         }
 
+        if self.options.page_barcode {
+            rels.push_str(
+                r#"
+    <Relationship Id="rIdFooter2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/footer" Target="footer2.xml"/>"#,
+            );
+        }
+
+        if self.options.print_layout != PrintLayout::Standard {
+            rels.push_str(
+                r#"
+    <Relationship Id="rIdSettings" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/settings" Target="settings.xml"/>"#,
+            );
+        }
+
         rels.push_str("\n</Relationships>");
         rels
     }
 
     /// Generate word/styles.xml
     fn generate_styles_xml(&self) -> String {
+        // Large print: 1.5 line spacing and a gap after every paragraph
+        let paragraph_defaults = if self.large_print {
+            format!(
+                r#"
+        <w:pPrDefault>
+            <w:pPr>
+                <w:spacing w:after="{}" w:line="{}" w:lineRule="auto"/>
+            </w:pPr>
+        </w:pPrDefault>"#,
+                LargePrint::SPACING_AFTER,
+                LargePrint::LINE_SPACING
+            )
+        } else if self.two_columns() {
+            // Short columns: no single line of a paragraph left alone
+            r#"
+        <w:pPrDefault>
+            <w:pPr>
+                <w:widowControl/>
+            </w:pPr>
+        </w:pPrDefault>"#
+                .to_string()
+        } else {
+            String::new()
+        };
         format!(
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
@@ -757,20 +1372,22 @@ impl ExamWriter {
                 <w:rFonts w:ascii="{}" w:hAnsi="{}" w:cs="{}" w:eastAsia="{}"/>
                 <w:sz w:val="{}"/>
             </w:rPr>
-        </w:rPrDefault>
+        </w:rPrDefault>{}
     </w:docDefaults>
 </w:styles>"#,
             NghiDinh30::FONT_NAME,
             NghiDinh30::FONT_NAME,
             NghiDinh30::FONT_NAME,
             NghiDinh30::FONT_NAME,
-            NghiDinh30::FONT_SIZE_BODY
+            self.body_font_size(),
+            paragraph_defaults
         )
     }
 
     /// Generate word/footer1.xml with page numbers (Nghị định 30)
     /// Page numbers: size 13, centered, not shown on first page
     fn generate_footer_xml(&self) -> String {
+        let barcode = self.page_barcode_xml();
         format!(
             r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
 <w:ftr xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
@@ -799,38 +1416,240 @@ impl ExamWriter {
             </w:rPr>
             <w:fldChar w:fldCharType="end"/>
         </w:r>
-    </w:p>
+    </w:p>{}
 </w:ftr>"#,
             NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_SIZE_PAGE_NUMBER,
             NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_SIZE_PAGE_NUMBER,
-            NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_SIZE_PAGE_NUMBER
+            NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_NAME, NghiDinh30::FONT_SIZE_PAGE_NUMBER,
+            barcode
         )
     }
 
+    /// Generate word/footer2.xml, the first-page footer (`w:titlePg`): the
+    /// barcode alone, so that the first page is sorted like the others
+    fn generate_first_page_footer_xml(&self) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:ftr xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">{}
+</w:ftr>"#,
+            self.page_barcode_xml()
+        )
+    }
+
+    /// Code128 barcode of the exam code and page (see `barcode`), when asked
+    fn page_barcode_xml(&self) -> String {
+        if !self.options.page_barcode {
+            return String::new();
+        }
+        let run_properties = format!(
+            r#"<w:rPr><w:rFonts w:ascii="{0}" w:hAnsi="{0}" w:cs="{0}" w:eastAsia="{0}"/><w:sz w:val="{1}"/></w:rPr>"#,
+            NghiDinh30::FONT_NAME,
+            NghiDinh30::FONT_SIZE_PAGE_NUMBER
+        );
+        format!("\n    {}", super::barcode::footer_paragraph(&self.exam_code, &run_properties))
+    }
+
     /// Generate section properties (Nghị định 30)
     /// A4 paper (210mm x 297mm)
     /// Margins: top/bottom 20mm, left 30mm, right 15mm
-    fn generate_section_properties(&self) -> String {
+    ///
+    /// Booklet and 2-up layouts use an A4 landscape sheet instead, holding
+    /// two A5 pages with 15mm margins (see `generate_settings_xml`).
+    /// `body`: the section holding the questions, laid out in two columns
+    /// when asked and then starting on the page of the header
+    fn generate_section_properties(&self, body: bool) -> String {
+        let (width, height, orient, margins) = match self.options.print_layout {
+            PrintLayout::Standard => (
+                NghiDinh30::PAGE_WIDTH_TWIPS,
+                NghiDinh30::PAGE_HEIGHT_TWIPS,
+                "",
+                [
+                    NghiDinh30::MARGIN_TOP_TWIPS,
+                    NghiDinh30::MARGIN_RIGHT_TWIPS,
+                    NghiDinh30::MARGIN_BOTTOM_TWIPS,
+                    NghiDinh30::MARGIN_LEFT_TWIPS,
+                ],
+            ),
+            PrintLayout::Booklet | PrintLayout::TwoUp => (
+                A5OnA4::SHEET_WIDTH_TWIPS,
+                A5OnA4::SHEET_HEIGHT_TWIPS,
+                r#" w:orient="landscape""#,
+                [A5OnA4::MARGIN_TWIPS; 4],
+            ),
+        };
+        let two_column_body = body && self.two_columns();
+        // Each 2-up copy numbers its pages from 1, and so do the exam pages
+        // after a cover page
+        let page_numbering = if two_column_body {
+            // Continues the header's page
+            ""
+        } else if self.options.print_layout == PrintLayout::TwoUp || self.options.cover_page.is_some() {
+            r#"
+            <w:pgNumType w:start="1"/>"#
+        } else {
+            ""
+        };
+        let (section_start, columns) = if two_column_body {
+            (
+                r#"
+            <w:type w:val="continuous"/>"#,
+                format!(r#"<w:cols w:num="2" w:space="{COLUMN_GAP_TWIPS}"/>"#),
+            )
+        } else {
+            ("", r#"<w:cols w:space="708"/>"#.to_string())
+        };
+
+        let first_page_footer = if self.options.page_barcode {
+            r#"
+            <w:footerReference w:type="first" r:id="rIdFooter2"/>"#
+        } else {
+            ""
+        };
+
         format!(
             r#"
         <w:sectPr>
-            <w:footerReference w:type="default" r:id="rIdFooter1"/>
-            <w:pgSz w:w="{}" w:h="{}"/>
-            <w:pgMar w:top="{}" w:right="{}" w:bottom="{}" w:left="{}" w:header="{}" w:footer="{}" w:gutter="0"/>
-            <w:cols w:space="708"/>
+            <w:footerReference w:type="default" r:id="rIdFooter1"/>{}{}
+            <w:pgSz w:w="{}" w:h="{}"{}/>
+            <w:pgMar w:top="{}" w:right="{}" w:bottom="{}" w:left="{}" w:header="{}" w:footer="{}" w:gutter="0"/>{}
+            {}
             <w:titlePg/>
         </w:sectPr>"#,
-            NghiDinh30::PAGE_WIDTH_TWIPS,
-            NghiDinh30::PAGE_HEIGHT_TWIPS,
-            NghiDinh30::MARGIN_TOP_TWIPS,
-            NghiDinh30::MARGIN_RIGHT_TWIPS,
-            NghiDinh30::MARGIN_BOTTOM_TWIPS,
-            NghiDinh30::MARGIN_LEFT_TWIPS,
+            first_page_footer,
+            section_start,
+            width,
+            height,
+            orient,
+            margins[0],
+            margins[1],
+            margins[2],
+            margins[3],
             NghiDinh30::MARGIN_HEADER_TWIPS,
-            NghiDinh30::MARGIN_FOOTER_TWIPS
+            NghiDinh30::MARGIN_FOOTER_TWIPS,
+            page_numbering,
+            columns
         )
     }
 
+    /// "Họ và tên: …   Số báo danh: …   Phòng thi: …", empty fields left out
+    fn student_line(&self, student: &crate::roster::Student) -> String {
+        let lexicon = self.options.lexicon();
+        [
+            (&lexicon.student_name, &student.name),
+            (&lexicon.student_id, &student.id),
+            (&lexicon.room, &student.room),
+        ]
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(label, value)| format!("{}: {}", label, value))
+        .collect::<Vec<_>>()
+        .join("     ")
+    }
+
+    /// Font size of question text (half-points)
+    fn body_font_size(&self) -> i32 {
+        if self.large_print {
+            LargePrint::FONT_SIZE_BODY
+        } else {
+            NghiDinh30::FONT_SIZE_BODY
+        }
+    }
+
+    /// Two-column body; never for the large-print copy
+    fn two_columns(&self) -> bool {
+        self.options.two_columns && !self.large_print
+    }
+
+    /// Width of one column of the body, in twips
+    fn column_width(&self) -> i32 {
+        if self.two_columns() {
+            (self.text_width() - COLUMN_GAP_TWIPS) / 2
+        } else {
+            self.text_width()
+        }
+    }
+
+    /// Pages of this copy after the cover page, estimated from the mixed
+    /// questions and the layout they are printed in
+    fn estimate_pages(&self) -> u32 {
+        use super::header_template::{StandardHeaderTemplate, TextArea};
+
+        let line_spacing = if self.large_print {
+            LargePrint::LINE_SPACING as f64 / 240.0
+        } else {
+            self.options.spacing.values().line_spacing
+        };
+        let answer_lines = match self.options.answer_line {
+            AnswerLine::None => 0,
+            AnswerLine::Line | AnswerLine::Bubbles => self.questions.len(),
+        };
+        let answer_space = if self.options.answer_space.style == AnswerSpaceStyle::None {
+            0
+        } else {
+            let open = self.questions.iter().filter(|q| q.options.is_empty()).count();
+            open * self.options.answer_space.lines as usize
+        };
+        let area = TextArea {
+            column_width: self.column_width(),
+            text_height: self.text_height(),
+            columns: if self.two_columns() { 2 } else { 1 },
+            font_size: self.body_font_size(),
+            // A line of Times New Roman is about 1.15 times the font size
+            line_height: (self.body_font_size() as f64 * 10.0 * 1.15 * line_spacing) as i32,
+            header_lines: HEADER_LINES + self.options.instruction_lines(self.questions.len(), self.duration_minutes).len(),
+            extra_lines: answer_lines + answer_space + self.options.closing.as_ref().map_or(0, |c| c.line_count()),
+        };
+        let paragraphs = self
+            .parts
+            .iter()
+            .map(|part| &part.heading)
+            .chain(self.groups.iter().flat_map(|group| std::iter::once(&group.header).chain(&group.paragraphs)))
+            .chain(self.questions.iter().flat_map(|question| {
+                std::iter::once(&question.stem).chain(question.options.iter().map(|option| &option.content))
+            }))
+            .map(Vec::as_slice);
+        StandardHeaderTemplate::estimate_pages(paragraphs, &area)
+    }
+
+    /// Height available for content on one page, in twips
+    fn text_height(&self) -> i32 {
+        match self.options.print_layout {
+            PrintLayout::Standard => {
+                NghiDinh30::PAGE_HEIGHT_TWIPS - NghiDinh30::MARGIN_TOP_TWIPS - NghiDinh30::MARGIN_BOTTOM_TWIPS
+            }
+            PrintLayout::Booklet | PrintLayout::TwoUp => A5OnA4::SHEET_HEIGHT_TWIPS - 2 * A5OnA4::MARGIN_TWIPS,
+        }
+    }
+
+    /// Width available for content on one page, in twips
+    fn text_width(&self) -> i32 {
+        match self.options.print_layout {
+            // Header table width used since before print layouts existed
+            PrintLayout::Standard => 9576,
+            PrintLayout::Booklet | PrintLayout::TwoUp => {
+                A5OnA4::PAGE_WIDTH_TWIPS - 2 * A5OnA4::MARGIN_TWIPS
+            }
+        }
+    }
+
+    /// Generate word/settings.xml telling Word how to put two A5 pages on
+    /// each sheet when printing: in fold order for a booklet, side by side
+    /// for 2-up. `None` for the standard layout (no settings part).
+    fn generate_settings_xml(&self) -> Option<String> {
+        let setting = match self.options.print_layout {
+            PrintLayout::Standard => return None,
+            PrintLayout::Booklet => "<w:bookFoldPrinting/>",
+            PrintLayout::TwoUp => "<w:printTwoOnOne/>",
+        };
+        Some(format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:settings xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+    {}
+</w:settings>"#,
+            setting
+        ))
+    }
+
     /// Embed images into DOCX
     fn embed_images(
         &self,
@@ -840,7 +1659,7 @@ impl ExamWriter {
     ) -> Result<(), std::io::Error> {
         use std::io::Read;
 
-        for (_, img_info) in image_map {
+        for img_info in image_map.values() {
             // Read image file
             let mut file = File::open(&img_info.path)?;
             let mut buffer = Vec::new();
@@ -861,3 +1680,393 @@ impl ExamWriter {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::OptionItem;
+
+    /// Stored `document.xml` renderings, next to this file. Run the tests
+    /// with `SIROMIX_UPDATE_SNAPSHOTS=1` to accept a deliberate change, then
+    /// review the snapshot diff like any other code.
+    fn assert_snapshot(name: &str, actual: &str) {
+        let path = Path::new(file!()).with_file_name("snapshots").join(format!("{}.xml", name));
+        if std::env::var_os("SIROMIX_UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("no snapshot {} (run with SIROMIX_UPDATE_SNAPSHOTS=1)", path.display()));
+        if let Some((line, (want, got))) = expected
+            .lines()
+            .zip(actual.lines())
+            .enumerate()
+            .find(|(_, (want, got))| want != got)
+        {
+            panic!("{} differs at line {}:\n  snapshot: {}\n  written:  {}", name, line + 1, want, got);
+        }
+        assert_eq!(expected.lines().count(), actual.lines().count(), "{} changed length", name);
+    }
+
+    fn text(s: &str) -> Segment {
+        Segment::Text {
+            text: s.to_string(),
+            raw_xml: String::new(),
+        }
+    }
+
+    fn option(label: &str, content: Vec<Segment>) -> OptionItem {
+        OptionItem {
+            label: label.to_string(),
+            content,
+            ..Default::default()
+        }
+    }
+
+    /// One question of each kind the writer renders differently: text,
+    /// equation, image, and a table kept from the source (passthrough)
+    fn representative_questions(image_path: &Path) -> Vec<Question> {
+        let text_question = Question {
+            number: 1,
+            stem: vec![text("Chất nào sau đây KHÔNG phải là kim loại? (H₂O & <NaCl>)")],
+            options: vec![
+                option("A", vec![text("Sắt")]),
+                option("B", vec![text("Đồng")]),
+                option("C", vec![text("Lưu huỳnh")]),
+                option("D", vec![text("Nhôm")]),
+            ],
+            correct_label: "C".to_string(),
+            ..Default::default()
+        };
+        let math = |omml: &str| Segment::Math {
+            omml: omml.to_string(),
+            raw_xml: String::new(),
+        };
+        let math_question = Question {
+            number: 2,
+            stem: vec![
+                text("Nghiệm của phương trình "),
+                math("<m:oMath><m:sSup><m:e><m:r><m:t>x</m:t></m:r></m:e><m:sup><m:r><m:t>2</m:t></m:r></m:sup></m:sSup><m:r><m:t>=4</m:t></m:r></m:oMath>"),
+                text(" là"),
+            ],
+            options: vec![
+                option("A", vec![math("<m:oMath><m:r><m:t>x=±2</m:t></m:r></m:oMath>")]),
+                option("B", vec![math("<m:oMath><m:r><m:t>x=2</m:t></m:r></m:oMath>")]),
+            ],
+            correct_label: "A".to_string(),
+            ..Default::default()
+        };
+        let image_question = Question {
+            number: 3,
+            stem: vec![
+                text("Hình bên biểu diễn đồ thị hàm số nào?"),
+                Segment::Image {
+                    asset_path: image_path.to_string_lossy().to_string(),
+                    raw_xml: String::new(),
+                    width_emu: 1828800,
+                    height_emu: 914400,
+                    anchor: None,
+                    ocr_text: None,
+                },
+            ],
+            options: vec![option("A", vec![text("y = x")]), option("B", vec![text("y = −x")])],
+            correct_label: "B".to_string(),
+            ..Default::default()
+        };
+        let cell = |s: &str| format!("<w:tc><w:p><w:r><w:t>{}</w:t></w:r></w:p></w:tc>", s);
+        let raw_option = |label: &str, value: &str| {
+            let mut item = option(label, vec![text(value)]);
+            item.raw_paragraphs = vec![format!("<w:p><w:r><w:t>{}. {}</w:t></w:r></w:p>", label, value)];
+            item
+        };
+        let table_question = Question {
+            number: 4,
+            stem: vec![text("Cho bảng số liệu sau. Giá trị lớn nhất là")],
+            raw_paragraphs: vec![
+                "<w:p><w:r><w:t>Câu 4. Cho bảng số liệu sau. Giá trị lớn nhất là</w:t></w:r></w:p>".to_string(),
+                format!(
+                    "<w:tbl><w:tblGrid><w:gridCol/><w:gridCol/></w:tblGrid><w:tr>{}{}</w:tr><w:tr>{}{}</w:tr></w:tbl>",
+                    cell("Năm"),
+                    cell("Sản lượng"),
+                    cell("2024"),
+                    cell("1 250")
+                ),
+            ],
+            options: vec![raw_option("A", "1 250"), raw_option("B", "2 024")],
+            correct_label: "A".to_string(),
+            ..Default::default()
+        };
+        vec![text_question, math_question, image_question, table_question]
+    }
+
+    #[test]
+    fn document_xml_matches_snapshot() {
+        let dir = std::env::temp_dir().join(format!("siromix-writer-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image_path = dir.join("image1.png");
+        image::RgbImage::new(4, 2).save(&image_path).unwrap();
+
+        let writer = ExamWriter {
+            exam_code: "101".to_string(),
+            questions: representative_questions(&image_path),
+            exam_title: "ĐỀ THI GIỮA KỲ I".to_string(),
+            subject: "Hoá học".to_string(),
+            duration_minutes: 45,
+            assets_dir: dir.clone(),
+            school_name: "TRƯỜNG THPT MẪU".to_string(),
+            exam_name: "KIỂM TRA GIỮA KỲ".to_string(),
+            academic_year: "2025 - 2026".to_string(),
+            grade: "10".to_string(),
+            options: ExportOptions {
+                raw_passthrough: true,
+                ..Default::default()
+            },
+            large_print: false,
+            groups: Vec::new(),
+            parts: Vec::new(),
+            teacher_mark: None,
+            student: None,
+        };
+        let document_xml = writer.generate_document_xml(&writer.collect_images());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_snapshot("document", &document_xml);
+    }
+
+    fn writer_with(questions: Vec<Question>, options: ExportOptions) -> ExamWriter {
+        ExamWriter {
+            exam_code: "101".to_string(),
+            questions,
+            exam_title: String::new(),
+            subject: "Tiếng Anh".to_string(),
+            duration_minutes: 45,
+            assets_dir: PathBuf::new(),
+            school_name: String::new(),
+            exam_name: String::new(),
+            academic_year: String::new(),
+            grade: "9".to_string(),
+            options,
+            large_print: false,
+            groups: Vec::new(),
+            parts: Vec::new(),
+            teacher_mark: None,
+            student: None,
+        }
+    }
+
+    fn choice_question(number: u32) -> Question {
+        Question {
+            number,
+            stem: vec![text("Choose the word with a different stress pattern.")],
+            options: ["A", "B", "C", "D"].iter().map(|label| option(label, vec![text("table")])).collect(),
+            correct_label: "B".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The answer line paragraphs, one per question, in order
+    fn answer_lines(document_xml: &str) -> Vec<&str> {
+        document_xml
+            .split("<w:p>")
+            .filter(|p| p.starts_with(r#"<w:pPr><w:ind w:left="720"/><w:spacing w:before="60" w:after="120"/>"#))
+            .collect()
+    }
+
+    #[test]
+    fn answer_lines_follow_each_question() {
+        let essay = Question {
+            number: 2,
+            stem: vec![text("Write a sentence about your school.")],
+            ..Default::default()
+        };
+        let questions = vec![choice_question(1), essay];
+        let written = |answer_line| {
+            let writer = writer_with(
+                questions.clone(),
+                ExportOptions {
+                    answer_line,
+                    ..Default::default()
+                },
+            );
+            writer.generate_document_xml(&HashMap::new())
+        };
+
+        assert!(answer_lines(&written(AnswerLine::None)).is_empty());
+
+        let document_xml = written(AnswerLine::Line);
+        let lines = answer_lines(&document_xml);
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            assert!(line.contains(r#"<w:t xml:space="preserve">Đáp án: </w:t>"#));
+            assert!(line.contains(r#"w:leader="dot""#));
+        }
+
+        // Bubbles for the options; the question without options gets the line
+        let document_xml = written(AnswerLine::Bubbles);
+        let lines = answer_lines(&document_xml);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#"<w:t xml:space="preserve">○ A     ○ B     ○ C     ○ D</w:t>"#));
+        assert!(!lines[0].contains("w:leader"));
+        assert!(lines[1].contains("Đáp án: ") && lines[1].contains(r#"w:leader="dot""#));
+
+        // Each line comes right after its question's last option
+        let first_line = document_xml.find("○ A").unwrap();
+        assert!(document_xml[..first_line].contains("Choose the word"));
+        assert!(!document_xml[..first_line].contains("Write a sentence"));
+    }
+
+    #[test]
+    fn two_columns_start_after_the_header() {
+        let questions = vec![choice_question(1), choice_question(2)];
+        let options = ExportOptions {
+            two_columns: true,
+            ..Default::default()
+        };
+        let mut writer = writer_with(questions, options);
+        let document_xml = writer.generate_document_xml(&HashMap::new());
+
+        // The header keeps the full width: its section ends before the first
+        // question, and the body section after it has the two columns
+        let parts: Vec<&str> = document_xml.split("<w:sectPr>").collect();
+        assert_eq!(parts.len(), 3);
+        assert!(!parts[0].contains("Choose the word"));
+        let (header_section, body) = parts[1].split_once("</w:sectPr>").unwrap();
+        assert!(header_section.contains(r#"<w:cols w:space="708"/>"#));
+        assert!(body.contains("Choose the word"));
+        assert!(parts[2].contains(&format!(r#"<w:cols w:num="2" w:space="{}"/>"#, COLUMN_GAP_TWIPS)));
+        assert!(parts[2].contains(r#"<w:type w:val="continuous"/>"#));
+        assert_eq!(document_xml.matches("w:num=\"2\"").count(), 1);
+        assert!(writer.generate_styles_xml().contains("<w:widowControl/>"));
+
+        // A picture wider than a column is scaled down to it, keeping its ratio
+        let column_emu = writer.column_width() as i64 * 635;
+        assert_eq!(writer.column_width(), (writer.text_width() - COLUMN_GAP_TWIPS) / 2);
+        let image = writer.generate_image_xml("rId9", column_emu * 2, 914400);
+        assert!(image.contains(&format!(r#"<wp:extent cx="{}" cy="{}"/>"#, column_emu, 457200)));
+        let small = writer.generate_image_xml("rId9", column_emu / 2, 914400);
+        assert!(small.contains(&format!(r#"<wp:extent cx="{}" cy="914400"/>"#, column_emu / 2)));
+
+        // The large-print copy is never in columns
+        writer.large_print = true;
+        let document_xml = writer.generate_document_xml(&HashMap::new());
+        assert_eq!(document_xml.matches("<w:sectPr>").count(), 1);
+        assert!(!document_xml.contains("w:num=\"2\""));
+        assert!(!writer.generate_styles_xml().contains("<w:widowControl/>"));
+    }
+
+    #[test]
+    fn header_fields_are_escaped() {
+        let writer = ExamWriter {
+            exam_code: "101".to_string(),
+            questions: Vec::new(),
+            exam_title: String::new(),
+            subject: "Toán & Tin".to_string(),
+            duration_minutes: 45,
+            assets_dir: PathBuf::new(),
+            school_name: "TRƯỜNG <THPT> MẪU".to_string(),
+            exam_name: "KIỂM TRA <15'>".to_string(),
+            academic_year: "2025 - 2026".to_string(),
+            grade: "10 & 11".to_string(),
+            options: ExportOptions::default(),
+            large_print: false,
+            groups: Vec::new(),
+            parts: Vec::new(),
+            teacher_mark: None,
+            student: None,
+        };
+        let header = writer.generate_header();
+        assert!(header.contains("Toán &amp; Tin"));
+        assert!(header.contains("10 &amp; 11"));
+        assert!(header.contains("TRƯỜNG &lt;THPT&gt; MẪU"));
+        assert!(header.contains("KIỂM TRA &lt;15'&gt;"));
+        assert!(!header.contains("<THPT>"));
+    }
+
+    /// A floating image read from the source keeps its position and wrap
+    /// mode through mixing, wherever its option lands
+    #[test]
+    fn anchored_images_keep_their_placement_after_mixing() {
+        use crate::docx::mixer;
+        use crate::docx::parser::{parse_document_xml_to_parsed_doc, ParseOptions};
+        use crate::docx::ExtractedAsset;
+
+        let dir = std::env::temp_dir().join(format!("siromix-anchor-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image_path = dir.join("image1.png");
+        image::RgbImage::new(4, 2).save(&image_path).unwrap();
+
+        let position_h = r#"<wp:positionH relativeFrom="margin"><wp:posOffset>2743200</wp:posOffset></wp:positionH>"#;
+        let position_v = r#"<wp:positionV relativeFrom="paragraph"><wp:posOffset>-182880</wp:posOffset></wp:positionV>"#;
+        let drawing = format!(
+            concat!(
+                r#"<w:r><w:drawing><wp:anchor behindDoc="0" simplePos="0"><wp:simplePos x="0" y="0"/>{}{}"#,
+                r#"<wp:extent cx="1828800" cy="914400"/><wp:wrapTopAndBottom/><wp:docPr id="7" name="Hình 7"/>"#,
+                r#"<a:graphic><a:graphicData><pic:pic><pic:blipFill><a:blip r:embed="rId9"/></pic:blipFill></pic:pic>"#,
+                r#"</a:graphicData></a:graphic></wp:anchor></w:drawing></w:r>"#
+            ),
+            position_h, position_v
+        );
+        let paragraph = |text: &str| format!("<w:p><w:r><w:t>{}</w:t></w:r></w:p>", text);
+        let xml = [
+            paragraph("Câu 1. Hình nào là tam giác?"),
+            format!("<w:p><w:r><w:t>A. Hình bên</w:t></w:r>{}</w:p>", drawing),
+            paragraph("B. Hình tròn"),
+            paragraph("C. Hình vuông"),
+            paragraph("D. Hình thoi"),
+        ]
+        .concat();
+        let assets = [ExtractedAsset {
+            file_name: "image1.png".to_string(),
+            absolute_path: image_path.clone(),
+            converted_path: None,
+        }];
+        let parsed = parse_document_xml_to_parsed_doc(&xml, &assets, &ParseOptions::default());
+
+        let exams = mixer::mix_exams(parsed.questions, &[], &[], 4, None, 1892, false);
+        for exam in &exams {
+            let question = &exam.questions[0];
+            let moved = question.options.iter().find(|o| o.original_label == "A").unwrap();
+            let anchored = moved.content.iter().any(|segment| {
+                matches!(segment, Segment::Image { anchor: Some(anchor), .. }
+                    if anchor.wrap == WrapMode::TopAndBottom && anchor.anchor_xml.contains(position_h))
+            });
+            assert!(anchored, "{}: option {} lost its anchor", exam.exam_code, moved.label);
+
+            let writer = ExamWriter {
+                exam_code: exam.exam_code.clone(),
+                questions: vec![Question {
+                    number: question.display_number,
+                    stem: question.stem.clone(),
+                    options: question.options.iter().map(|o| option(&o.label, o.content.clone())).collect(),
+                    correct_label: question.correct_answer.clone(),
+                    ..Default::default()
+                }],
+                exam_title: String::new(),
+                subject: "Toán".to_string(),
+                duration_minutes: 45,
+                assets_dir: dir.clone(),
+                school_name: String::new(),
+                exam_name: String::new(),
+                academic_year: String::new(),
+                grade: "6".to_string(),
+                options: ExportOptions {
+                    preserve_floating_images: true,
+                    ..Default::default()
+                },
+                large_print: false,
+                groups: Vec::new(),
+                parts: Vec::new(),
+                teacher_mark: None,
+                student: None,
+            };
+            let document_xml = writer.generate_document_xml(&writer.collect_images());
+            let anchor_start = document_xml.find("<wp:anchor").expect("image written inline");
+            let written = &document_xml[anchor_start..];
+            assert!(written.contains(position_h) && written.contains(position_v));
+            assert!(written.contains("<wp:wrapTopAndBottom/>"));
+            assert!(written.contains(r#"<wp:extent cx="1828800" cy="914400"/>"#));
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
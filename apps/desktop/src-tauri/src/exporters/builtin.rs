@@ -0,0 +1,51 @@
+// src-tauri/src/exporters/builtin.rs
+//! Exporters compiled into the app
+
+use std::path::PathBuf;
+
+use super::{ExportRequest, Exporter, ExporterInfo};
+use crate::docx::speech::{self, ScriptFormat};
+
+pub fn exporters() -> Vec<Box<dyn Exporter>> {
+    vec![
+        Box::new(SpeechScripts::new(ScriptFormat::Text)),
+        Box::new(SpeechScripts::new(ScriptFormat::Ssml)),
+    ]
+}
+
+/// Read-aloud scripts, one `<variant file name>_Doc.<ext>` per variant
+struct SpeechScripts {
+    info: ExporterInfo,
+    format: ScriptFormat,
+}
+
+impl SpeechScripts {
+    fn new(format: ScriptFormat) -> Self {
+        let (id, name) = match format {
+            ScriptFormat::Text => ("speechText", "Kịch bản đọc (.txt)"),
+            ScriptFormat::Ssml => ("speechSsml", "Kịch bản đọc SSML (.ssml)"),
+        };
+        Self {
+            info: ExporterInfo {
+                id: id.to_string(),
+                name: name.to_string(),
+                extension: format.extension().to_string(),
+                description: "Đề đọc cho công cụ chuyển văn bản thành giọng nói, không kèm đáp án".to_string(),
+                includes_answers: false,
+                license_feature: super::required_feature(id).map(str::to_string),
+            },
+            format,
+        }
+    }
+}
+
+impl Exporter for SpeechScripts {
+    fn info(&self) -> &ExporterInfo {
+        &self.info
+    }
+
+    fn export(&self, request: &ExportRequest) -> Result<Vec<PathBuf>, String> {
+        let files = speech::write_scripts(request.exams, request.output_dir, request.options, self.format)?;
+        Ok(files.into_iter().map(|name| request.output_dir.join(name)).collect())
+    }
+}
@@ -0,0 +1,236 @@
+// src-tauri/src/exporters/imposition.rs
+//! Booklet and 2-up imposition of PDF output.
+//!
+//! PDF exporters always render the standard layout, one exam page per PDF
+//! page. When the export options ask for a booklet or 2-up print layout,
+//! the app then rearranges the pages of every PDF written: two pages, scaled
+//! to fit, on each A4 landscape sheet side. For a booklet the sides come in
+//! saddle-stitch order (print duplex, flip on the short edge, fold, staple),
+//! padded with blank pages to a multiple of four; for 2-up each page is
+//! printed twice side by side, to be cut into two A5 copies.
+//!
+//! The DOCX export does not go through here: Word's own book-fold and
+//! two-on-one printing does the imposition (see `docx::export_options::PrintLayout`).
+
+use std::path::Path;
+
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+
+use crate::docx::export_options::PrintLayout;
+
+/// A4 landscape sheet, in points
+const SHEET_WIDTH: f32 = 841.89;
+const SHEET_HEIGHT: f32 = 595.28;
+
+/// Pages printed on each side of a sheet, left then right (0-based, `None`
+/// for a blank half), in printing order. Empty for the standard layout.
+pub fn sheet_sides(page_count: usize, layout: PrintLayout) -> Vec<[Option<usize>; 2]> {
+    match layout {
+        PrintLayout::Standard => Vec::new(),
+        PrintLayout::TwoUp => (0..page_count).map(|page| [Some(page), Some(page)]).collect(),
+        PrintLayout::Booklet => {
+            let padded = page_count.div_ceil(4) * 4;
+            let page = |index: usize| (index < page_count).then_some(index);
+            (0..padded / 4)
+                .flat_map(|sheet| {
+                    let (outer, inner) = (2 * sheet, padded - 1 - 2 * sheet);
+                    [[page(inner), page(outer)], [page(outer + 1), page(inner - 1)]]
+                })
+                .collect()
+        }
+    }
+}
+
+/// Impose the PDF at `path` in place for `layout` (nothing to do for the
+/// standard layout). The file is replaced only once the new one is written.
+pub fn impose(path: &Path, layout: PrintLayout) -> Result<(), String> {
+    if layout == PrintLayout::Standard {
+        return Ok(());
+    }
+    let mut doc = Document::load(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    impose_document(&mut doc, layout).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let tmp_path = path.with_extension("pdf.tmp");
+    let result = doc
+        .save(&tmp_path)
+        .and_then(|file| file.sync_all())
+        .map_err(|e| e.to_string())
+        .and_then(|_| std::fs::rename(&tmp_path, path).map_err(|e| e.to_string()));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    result.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn impose_document(doc: &mut Document, layout: PrintLayout) -> lopdf::Result<()> {
+    let pages: Vec<ObjectId> = doc.get_pages().into_values().collect();
+    let pages_id = doc.catalog()?.get(b"Pages")?.as_reference()?;
+
+    // Each source page becomes a form XObject drawn on the sheets
+    let mut forms = Vec::with_capacity(pages.len());
+    for &page_id in &pages {
+        let media_box = page_box(doc, page_id)?;
+        let resources = inherited(doc, page_id, b"Resources").unwrap_or_else(|| Object::Dictionary(Dictionary::new()));
+        let content = doc.get_page_content(page_id)?;
+        let form = Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Form",
+                "BBox" => media_box.iter().map(|&v| Object::Real(v)).collect::<Vec<_>>(),
+                "Resources" => resources,
+            },
+            content,
+        );
+        forms.push((doc.add_object(form), media_box));
+    }
+
+    let half_width = SHEET_WIDTH / 2.0;
+    let mut sheets = Vec::new();
+    for side in sheet_sides(pages.len(), layout) {
+        let mut content = String::new();
+        let mut xobjects = Dictionary::new();
+        for (slot, page) in side.iter().enumerate() {
+            let Some(page) = page else {
+                continue;
+            };
+            let (form_id, [llx, lly, urx, ury]) = forms[*page];
+            let (width, height) = (urx - llx, ury - lly);
+            if width <= 0.0 || height <= 0.0 {
+                continue;
+            }
+            let scale = (half_width / width).min(SHEET_HEIGHT / height);
+            let x = slot as f32 * half_width + (half_width - width * scale) / 2.0 - llx * scale;
+            let y = (SHEET_HEIGHT - height * scale) / 2.0 - lly * scale;
+            let name = format!("P{}", slot);
+            content.push_str(&format!("q {scale:.5} 0 0 {scale:.5} {x:.3} {y:.3} cm /{name} Do Q\n"));
+            xobjects.set(name, Object::Reference(form_id));
+        }
+        let content_id = doc.add_object(Stream::new(Dictionary::new(), content.into_bytes()));
+        let sheet = dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), Object::Real(SHEET_WIDTH), Object::Real(SHEET_HEIGHT)],
+            "Resources" => dictionary! { "XObject" => xobjects },
+            "Contents" => content_id,
+        };
+        sheets.push(Object::Reference(doc.add_object(sheet)));
+    }
+
+    let pages_dict = doc.get_dictionary_mut(pages_id)?;
+    pages_dict.set("Count", sheets.len() as i64);
+    pages_dict.set("Kids", sheets);
+    // The former pages are no longer reachable
+    doc.prune_objects();
+    doc.compress();
+    Ok(())
+}
+
+/// Inheritable page attribute (`Resources`, `MediaBox`), from the page or
+/// the nearest ancestor in the page tree
+fn inherited(doc: &Document, page_id: ObjectId, key: &[u8]) -> Option<Object> {
+    let mut node = doc.get_dictionary(page_id).ok()?;
+    // The page tree is shallow; the bound only guards against cycles
+    for _ in 0..64 {
+        if let Ok(value) = node.get(key) {
+            return Some(value.clone());
+        }
+        node = doc.get_dictionary(node.get(b"Parent").ok()?.as_reference().ok()?).ok()?;
+    }
+    None
+}
+
+/// Media box of a page as [llx, lly, urx, ury]; A4 portrait when missing
+fn page_box(doc: &Document, page_id: ObjectId) -> lopdf::Result<[f32; 4]> {
+    let Some(media_box) = inherited(doc, page_id, b"MediaBox") else {
+        return Ok([0.0, 0.0, 595.28, 841.89]);
+    };
+    let media_box = match media_box {
+        Object::Reference(id) => doc.get_object(id)?.clone(),
+        other => other,
+    };
+    let values = media_box
+        .as_array()?
+        .iter()
+        .map(|v| v.as_float().or_else(|_| v.as_i64().map(|i| i as f32)))
+        .collect::<lopdf::Result<Vec<f32>>>()?;
+    match values[..] {
+        [llx, lly, urx, ury] => Ok([llx.min(urx), lly.min(ury), llx.max(urx), lly.max(ury)]),
+        _ => Err(lopdf::Error::Type),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// PDF with `count` A4 portrait pages
+    fn sample_pdf(count: usize) -> Document {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let kids: Vec<Object> = (0..count)
+            .map(|i| {
+                let content = format!("BT /F1 24 Tf 100 700 Td (Trang {}) Tj ET", i + 1);
+                let content_id = doc.add_object(Stream::new(Dictionary::new(), content.into_bytes()));
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "Contents" => content_id,
+                })
+                .into()
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => count as i64,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+                "Resources" => dictionary! {},
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    #[test]
+    fn booklet_sides_are_in_fold_order() {
+        let sides = sheet_sides(6, PrintLayout::Booklet);
+        assert_eq!(
+            sides,
+            vec![
+                [None, Some(0)],
+                [Some(1), None],
+                [Some(5), Some(2)],
+                [Some(3), Some(4)],
+            ]
+        );
+        assert_eq!(sheet_sides(2, PrintLayout::TwoUp), vec![[Some(0), Some(0)], [Some(1), Some(1)]]);
+        assert!(sheet_sides(3, PrintLayout::Standard).is_empty());
+    }
+
+    #[test]
+    fn pages_are_placed_two_per_landscape_sheet() {
+        let dir = std::env::temp_dir().join(format!("siromix-impose-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("De_101.pdf");
+        sample_pdf(3).save(&path).unwrap();
+
+        impose(&path, PrintLayout::Booklet).unwrap();
+
+        let doc = Document::load(&path).unwrap();
+        let sheets: Vec<ObjectId> = doc.get_pages().into_values().collect();
+        assert_eq!(sheets.len(), 2);
+        assert_eq!(page_box(&doc, sheets[0]).unwrap(), [0.0, 0.0, SHEET_WIDTH, SHEET_HEIGHT]);
+        // Front of the sheet: blank back cover on the left, page 1 on the right
+        let front = String::from_utf8(doc.get_page_content(sheets[0]).unwrap()).unwrap();
+        assert_eq!(front.matches(" Do Q").count(), 1);
+        assert!(front.contains("/P1 Do"));
+        let back = String::from_utf8(doc.get_page_content(sheets[1]).unwrap()).unwrap();
+        assert_eq!(back.matches(" Do Q").count(), 2);
+        assert!(!dir.join("De_101.pdf.tmp").exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,188 @@
+// src-tauri/src/exporters/mod.rs
+//! Output formats beyond the built-in DOCX/XLSX export.
+//!
+//! Every format is an [`Exporter`] in the [`Registry`] and is listed in the
+//! export screen. Formats compiled into the app register themselves in
+//! [`Registry::load`] (`builtin` has the read-aloud scripts); other formats
+//! come from sidecar executables installed under
+//! `<app_data>/SiroMix/exporters/<name>/exporter.json` (see [`sidecar`]), so
+//! a school can add one without rebuilding the app. PDF output is imposed
+//! for the booklet and 2-up print layouts afterwards (see [`imposition`]).
+
+pub mod builtin;
+pub mod imposition;
+pub mod sidecar;
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::docx::excel::MixedExam;
+use crate::docx::export_options::ExportOptions;
+
+/// Description of an exporter shown in the export screen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExporterInfo {
+    /// Stable identifier, e.g. "speechText", "moodle-xml"
+    pub id: String,
+    pub name: String,
+    /// Extension of the files written, without the dot
+    pub extension: String,
+    #[serde(default)]
+    pub description: String,
+    /// Whether the output contains the answers (answer-key passphrase and
+    /// release time apply)
+    #[serde(rename = "includesAnswers", default)]
+    pub includes_answers: bool,
+    /// License feature needed to run it (see `storage::license`), from
+    /// [`required_feature`]: never read from a sidecar's manifest
+    #[serde(rename = "licenseFeature", skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub license_feature: Option<String>,
+}
+
+/// Paid formats: exporter id and the license feature it needs. Kept in the
+/// app so that a sidecar cannot lift the requirement from its manifest.
+const LICENSED_EXPORTERS: &[(&str, &str)] = &[("moodle-xml", "moodleExport"), ("pdf", "pdfExport")];
+
+/// License feature needed to run the exporter `id`, if any
+pub fn required_feature(id: &str) -> Option<&'static str> {
+    LICENSED_EXPORTERS
+        .iter()
+        .find(|(exporter, _)| *exporter == id)
+        .map(|(_, feature)| *feature)
+}
+
+/// Everything an exporter gets: the mixed variants as sent by the frontend
+/// (same shape as for `export_mixed_exams`), the original answers and the
+/// export options. Sidecars receive it as JSON on stdin.
+#[derive(Debug, Serialize)]
+pub struct ExportRequest<'a> {
+    #[serde(rename = "jobId")]
+    pub job_id: &'a str,
+    pub exams: &'a [MixedExam],
+    #[serde(rename = "originalAnswers")]
+    pub original_answers: &'a [String],
+    #[serde(rename = "outputDirectory")]
+    pub output_dir: &'a Path,
+    pub options: &'a ExportOptions,
+}
+
+/// Leave out of a request everything that gives the answers away: correct
+/// labels, original option labels and original answers. Exporters not
+/// declaring `includesAnswers` run without the answer-key checks, so they
+/// get the variants this way; the flag is the sidecar's own claim.
+pub fn strip_answers(exams: &mut [MixedExam], original_answers: &mut Vec<String>) {
+    original_answers.clear();
+    for question in exams.iter_mut().flat_map(|exam| exam.questions.iter_mut()) {
+        question.correct_answer.clear();
+        for option in &mut question.options {
+            option.original_label.clear();
+        }
+    }
+}
+
+pub trait Exporter: Send + Sync {
+    fn info(&self) -> &ExporterInfo;
+
+    /// Write the output files into `request.output_dir` and return their
+    /// paths.
+    fn export(&self, request: &ExportRequest) -> Result<Vec<PathBuf>, String>;
+}
+
+#[derive(Default)]
+pub struct Registry {
+    exporters: Vec<Box<dyn Exporter>>,
+}
+
+impl Registry {
+    /// Built-in exporters, then the sidecars installed in `sidecars_dir`.
+    /// A sidecar reusing an existing id is skipped.
+    pub fn load(sidecars_dir: &Path) -> Self {
+        let mut registry = Registry::default();
+        for exporter in builtin::exporters() {
+            registry.register(exporter);
+        }
+        for exporter in sidecar::discover(sidecars_dir) {
+            registry.register(Box::new(exporter));
+        }
+        registry
+    }
+
+    pub fn register(&mut self, exporter: Box<dyn Exporter>) {
+        let id = &exporter.info().id;
+        if self.get(id).is_some() {
+            eprintln!("[Exporters] Duplicate exporter id {}, ignored", id);
+            return;
+        }
+        self.exporters.push(exporter);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn Exporter> {
+        self.exporters.iter().find(|e| e.info().id == id).map(|e| e.as_ref())
+    }
+
+    pub fn infos(&self) -> Vec<ExporterInfo> {
+        self.exporters.iter().map(|e| e.info().clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_builtin_and_installed_exporters() {
+        let dir = std::env::temp_dir().join(format!("siromix-exporters-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("moodle")).unwrap();
+        std::fs::write(
+            dir.join("moodle").join(sidecar::MANIFEST_FILE),
+            r#"{"id":"moodle-xml","name":"Moodle XML","extension":"xml","command":"moodle.exe","includesAnswers":true,"licenseFeature":null}"#,
+        )
+        .unwrap();
+        // Same id as a built-in exporter
+        std::fs::create_dir_all(dir.join("shadow")).unwrap();
+        std::fs::write(
+            dir.join("shadow").join(sidecar::MANIFEST_FILE),
+            r#"{"id":"speechText","name":"Shadow","extension":"txt","command":"shadow.exe"}"#,
+        )
+        .unwrap();
+
+        let registry = Registry::load(&dir);
+        let ids: Vec<String> = registry.infos().into_iter().map(|info| info.id).collect();
+        assert_eq!(ids, vec!["speechText", "speechSsml", "moodle-xml"]);
+        // The manifest does not say so, the app does
+        assert_eq!(registry.get("moodle-xml").unwrap().info().license_feature.as_deref(), Some("moodleExport"));
+        assert_eq!(registry.get("speechText").unwrap().info().license_feature, None);
+        assert!(registry.get("moodle-xml").unwrap().info().includes_answers);
+        assert_eq!(registry.get("speechText").unwrap().info().name, "Kịch bản đọc (.txt)");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn strips_answers_from_requests() {
+        use crate::docx::excel::{MixedOption, MixedQuestion};
+
+        let mut exams = vec![MixedExam {
+            exam_code: "101".to_string(),
+            questions: vec![MixedQuestion {
+                correct_answer: "B".to_string(),
+                options: vec![MixedOption {
+                    label: "A".to_string(),
+                    original_label: "C".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            groups: Vec::new(),
+            parts: Vec::new(),
+        }];
+        let mut original_answers = vec!["C".to_string()];
+        strip_answers(&mut exams, &mut original_answers);
+
+        let json = serde_json::to_string(&exams).unwrap();
+        assert!(original_answers.is_empty());
+        assert!(json.contains(r#""correctAnswer":"""#) && json.contains(r#""originalLabel":"""#));
+        assert_eq!(exams[0].questions[0].options[0].label, "A");
+    }
+}
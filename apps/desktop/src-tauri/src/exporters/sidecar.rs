@@ -0,0 +1,175 @@
+// src-tauri/src/exporters/sidecar.rs
+//! Exporters provided by external executables.
+//!
+//! Each one lives in its own folder with an `exporter.json`:
+//!
+//! ```json
+//! { "id": "moodle-xml", "name": "Moodle XML", "extension": "xml",
+//!   "command": "moodle-export.exe", "args": ["--quiz"], "includesAnswers": true }
+//! ```
+//!
+//! `command` is relative to that folder (or on PATH). It is started in the
+//! folder, gets the [`ExportRequest`](super::ExportRequest) as JSON on stdin,
+//! writes its files into `outputDirectory` and prints `{"files": [...]}`
+//! (names in the output directory) on stdout. A non-zero exit status is an
+//! error; its stderr is shown to the user.
+//!
+//! A sidecar without `"includesAnswers": true` runs without the answer-key
+//! checks and gets the variants without their answers (see
+//! [`strip_answers`](super::strip_answers)). Whether a license is needed
+//! is decided by the app from the id ([`required_feature`](super::required_feature)),
+//! not by the manifest.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::Deserialize;
+
+use super::{ExportRequest, Exporter, ExporterInfo};
+
+pub const MANIFEST_FILE: &str = "exporter.json";
+
+#[derive(Debug, Deserialize)]
+struct SidecarManifest {
+    #[serde(flatten)]
+    info: ExporterInfo,
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SidecarOutput {
+    files: Vec<String>,
+}
+
+pub struct SidecarExporter {
+    info: ExporterInfo,
+    dir: PathBuf,
+    command: String,
+    args: Vec<String>,
+}
+
+/// Sidecars installed in the subfolders of `dir`; folders without a valid
+/// manifest are skipped.
+pub fn discover(dir: &Path) -> Vec<SidecarExporter> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut folders: Vec<PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+    folders.sort();
+
+    folders
+        .into_iter()
+        .filter_map(|folder| {
+            let manifest_path = folder.join(MANIFEST_FILE);
+            if !manifest_path.exists() {
+                return None;
+            }
+            match crate::storage::fs::read_json::<SidecarManifest>(&manifest_path) {
+                Ok(manifest) => Some(SidecarExporter {
+                    info: ExporterInfo {
+                        license_feature: super::required_feature(&manifest.info.id).map(str::to_string),
+                        ..manifest.info
+                    },
+                    dir: folder,
+                    command: manifest.command,
+                    args: manifest.args,
+                }),
+                Err(e) => {
+                    eprintln!("[Exporters] Skipping {}: {}", manifest_path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+impl Exporter for SidecarExporter {
+    fn info(&self) -> &ExporterInfo {
+        &self.info
+    }
+
+    fn export(&self, request: &ExportRequest) -> Result<Vec<PathBuf>, String> {
+        let input = serde_json::to_vec(request).map_err(|e| format!("Không serialize được dữ liệu xuất: {e}"))?;
+        let local = self.dir.join(&self.command);
+        let program = if local.is_file() { local } else { PathBuf::from(&self.command) };
+
+        let mut child = Command::new(&program)
+            .args(&self.args)
+            .current_dir(&self.dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("{}: {e}", program.display()))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(&input).map_err(|e| format!("{}: {e}", program.display()))?;
+        }
+        let output = child.wait_with_output().map_err(|e| format!("{}: {e}", program.display()))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} ({}): {}",
+                self.info.name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let result: SidecarOutput = serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("{}: kết quả không hợp lệ: {e}", self.info.name))?;
+        result
+            .files
+            .into_iter()
+            .map(|name| {
+                // Only files inside the output directory are reported back
+                let path = request.output_dir.join(&name);
+                match Path::new(&name).file_name() {
+                    Some(file_name) if file_name == name.as_str() && path.is_file() => Ok(path),
+                    _ => Err(format!("{}: không tìm thấy file {} trong thư mục xuất", self.info.name, name)),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::docx::export_options::ExportOptions;
+
+    #[test]
+    fn runs_sidecar_over_stdin() {
+        let dir = std::env::temp_dir().join(format!("siromix-sidecar-{}", uuid::Uuid::new_v4()));
+        let plugin_dir = dir.join("plugin");
+        let output_dir = dir.join("out");
+        std::fs::create_dir_all(&plugin_dir).unwrap();
+        std::fs::create_dir_all(&output_dir).unwrap();
+        // Copies the request it receives into the output folder
+        std::fs::write(
+            plugin_dir.join(MANIFEST_FILE),
+            r#"{"id":"echo","name":"Echo","extension":"json","command":"sh",
+                "args":["-c","cat > ../out/request.json && echo '{\"files\":[\"request.json\"]}'"]}"#,
+        )
+        .unwrap();
+
+        let exporter = discover(&dir).pop().unwrap();
+        let request = ExportRequest {
+            job_id: "job",
+            exams: &[],
+            original_answers: &["A".to_string()],
+            output_dir: &output_dir,
+            options: &ExportOptions::default(),
+        };
+        let files = exporter.export(&request).unwrap();
+        assert_eq!(files, vec![output_dir.join("request.json")]);
+
+        let sent: serde_json::Value = serde_json::from_slice(&std::fs::read(&files[0]).unwrap()).unwrap();
+        assert_eq!(sent["jobId"], "job");
+        assert_eq!(sent["originalAnswers"][0], "A");
+        assert_eq!(sent["options"]["questionPrefix"], "Câu");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
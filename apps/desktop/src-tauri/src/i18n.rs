@@ -0,0 +1,198 @@
+// src-tauri/src/i18n.rs
+//! Message catalog for user-facing backend strings
+//! Every error/status has a stable code; the localized text is picked from the
+//! current language setting (Vietnamese by default).
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// UI language for backend messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Lang {
+    #[default]
+    #[serde(rename = "vi")]
+    Vi,
+    #[serde(rename = "en")]
+    En,
+}
+
+static CURRENT_LANG: AtomicU8 = AtomicU8::new(0);
+
+/// Set the language used by `message` / `CommandError` (from the settings).
+pub fn set_lang(lang: Lang) {
+    CURRENT_LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+pub fn current_lang() -> Lang {
+    match CURRENT_LANG.load(Ordering::Relaxed) {
+        1 => Lang::En,
+        _ => Lang::Vi,
+    }
+}
+
+/// (code, Vietnamese, English)
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Validation (per question)
+    ("E020_CORRECT_MARK_MISSING", "Chưa đánh dấu đáp án đúng", "No correct answer is marked"),
+    ("E021_CORRECT_MARK_MULTIPLE", "Có nhiều hơn một đáp án được đánh dấu đúng", "More than one answer is marked correct"),
+    ("E022_TOO_MANY_OPTIONS", "Số phương án vượt quá giới hạn cho phép", "Too many options for this question"),
+    ("E024_GROUP_QUESTION_MISSING", "Phần dữ kiện chung nhắc tới một câu không có trong đề", "A shared-data header names a question that is not in the document"),
+    ("E025_MANUAL_ANSWER_INVALID", "Đáp án chọn tay không phải là một phương án của câu hỏi", "The answer chosen by hand is not one of the question's options"),
+    ("E026_CUSTOM_RULE", "Câu hỏi vi phạm quy tắc kiểm tra của trường", "The question breaks one of the school's validation rules"),
+    ("W002_STATEMENTS_NAMED_BY_OPTIONS", "Phương án nhắc tới các phát biểu (I), (II)…, câu này sẽ không được đảo thứ tự phát biểu", "The options name the (I), (II)… statements; their order will not be shuffled in this question"),
+    ("W003_NEGATIVE_NOT_EMPHASIZED", "Từ phủ định (KHÔNG, NGOẠI TRỪ, SAI) trong thân câu chưa được in đậm hoặc gạch chân", "A negative word (KHÔNG, NGOẠI TRỪ, SAI) in the stem is neither bold nor underlined"),
+    ("W004_FORBIDDEN_TERM", "Câu hỏi dùng từ ngữ nằm trong danh sách cấm", "The question uses a term from the forbidden-terms list"),
+    ("W005_MANUAL_ANSWER", "Đáp án đúng được chọn tay, không lấy từ đánh dấu trong đề", "The correct answer was chosen by hand, not read from a mark in the document"),
+    ("W006_PART_MERGED", "Nội dung nhúng (altChunk) hoặc tài liệu con sau câu này đã được gộp vào đề, hãy kiểm tra lại định dạng", "Embedded content (altChunk) or a subdocument after this question was merged into the document; check its formatting"),
+    ("W007_PART_NOT_MERGED", "Nội dung nhúng (altChunk) hoặc tài liệu con sau câu này không đọc được và bị bỏ qua khi phân tích", "Embedded content (altChunk) or a subdocument after this question could not be read and is missing from the analysis"),
+    ("W008_CUSTOM_RULE", "Cảnh báo từ quy tắc kiểm tra của trường", "Warning from one of the school's validation rules"),
+    ("W009_RULE_SCRIPT_FAILED", "Quy tắc kiểm tra (script) bị lỗi và không chạy hết", "A validation rule script failed and did not run to the end"),
+    ("W010_PARAGRAPH_RECOVERED", "Một đoạn trong đề bị lỗi định dạng và chỉ giữ lại phần chữ (mất công thức, hình, định dạng), hãy kiểm tra lại", "A paragraph of the document is malformed and only its text was kept (equations, images and formatting are lost); check it"),
+    ("W011_PART_POINTS_MISMATCH", "Tổng điểm ghi ở các câu không khớp với điểm của phần", "The points stated on the questions do not add up to the points of their part"),
+    ("W012_SIMILAR_TO_PAST_EXAM", "Câu hỏi rất giống một câu trong đề các năm trước", "The question is very close to a question of a previous years' exam"),
+    ("W013_SPELL_DICTIONARY_MISSING", "Không tìm thấy từ điển tiếng Việt (vi_VN.dic), bỏ qua kiểm tra chính tả", "The Vietnamese dictionary (vi_VN.dic) was not found; spell checking is skipped"),
+    ("W014_CROSS_REFERENCE_MISSING", "Câu hỏi nhắc tới một câu không có trong đề (hoặc bị bỏ qua), số câu này sẽ giữ nguyên sau khi trộn", "The question refers to a question that is not in the document (or was skipped); that number is left as is after mixing"),
+    ("W001_IMAGE_MISSING", "Không trích xuất được hình trong câu hỏi, file xuất sẽ hiện khung thay thế", "An image in this question could not be extracted; exports will show a placeholder"),
+    // Commands
+    ("E100_DOCX_READ_FAILED", "Không đọc được document.xml", "Could not read document.xml"),
+    ("E101_MEDIA_EXTRACT_FAILED", "Không extract được media từ docx", "Could not extract media from the docx"),
+    ("E102_WORKSPACE_WRITE_FAILED", "Không ghi được dữ liệu vào workspace", "Could not write to the job workspace"),
+    ("E103_PARSED_NOT_FOUND", "Không tìm thấy parsed.json cho job", "parsed.json not found for this job"),
+    ("E104_PARSED_INVALID", "Không đọc được parsed.json", "Could not read parsed.json"),
+    ("E105_QUESTION_NOT_FOUND", "Không tìm thấy câu hỏi này trong đề", "Question not found in the document"),
+    ("E106_ASSET_NOT_FOUND", "Không tìm thấy hình này trong dữ liệu đề", "Image not found in the exam data"),
+    ("E107_ARCHIVE_UNSAFE", "File docx có dấu hiệu bất thường (quá nhiều mục, hình quá lớn hoặc tên file không hợp lệ), đã dừng giải nén", "The docx looks suspicious (too many entries, oversized images or invalid file names); extraction stopped"),
+    ("E108_INVALID_ID", "Mã job hoặc mã đề không hợp lệ", "Invalid job ID or exam code"),
+    ("E110_INVALID_VARIANT_COUNT", "Số đề phải lớn hơn 0", "Number of variants must be greater than 0"),
+    ("E111_NO_QUESTIONS", "Không tìm thấy câu hỏi nào trong đề gốc", "No questions found in the parsed document"),
+    ("E112_EXAM_CODE_COUNT_MISMATCH", "Số mã đề tự nhập không khớp với số đề", "Number of custom exam codes does not match number of variants"),
+    ("E113_VARIANT_NOT_FOUND", "Không tìm thấy mã đề này trong lần trộn gần nhất của job", "This exam code is not part of the job's latest mix"),
+    ("E114_QUESTION_POSITION_INVALID", "Số thứ tự câu không có trong mã đề", "Question position is out of range for this variant"),
+    ("E115_SWAP_IN_GROUP", "Không đổi chỗ riêng được câu thuộc nhóm dùng chung dữ kiện", "Questions sharing a stimulus cannot be moved on their own"),
+    ("E116_VARIANTS_FROZEN", "Có mã đề đã duyệt hoặc đã xuất; hãy bỏ duyệt hoặc xác nhận ghi đè", "Some variants are approved or exported; unapprove them or confirm overwriting"),
+    ("E117_MIX_INVARIANT_BROKEN", "Kết quả trộn đề bị lỗi (thiếu, lặp câu hoặc phương án, sai đáp án), đã dừng trộn", "The mixed variants are broken (missing or repeated questions or options, wrong answer); mixing stopped"),
+    ("E118_SWAP_ACROSS_PARTS", "Không đổi chỗ được hai câu thuộc hai phần khác nhau của đề", "Questions from different parts of the exam cannot be swapped"),
+    ("E130_ANSWER_KEY_MISMATCH", "Đáp án sau khi trộn không khớp với đáp án gốc, đã dừng xuất file", "Answer key does not match the original answers after mixing; export aborted"),
+    ("E131_DOCX_WRITE_FAILED", "Lỗi tạo file đề", "Failed to write exam file"),
+    ("E132_XLSX_WRITE_FAILED", "Lỗi tạo file Excel", "Failed to write Excel file"),
+    ("E133_EXAM_PRESET_NOT_FOUND", "Không tìm thấy mẫu đề đã chọn trong cài đặt", "The selected exam preset is not in the settings"),
+    ("E134_NO_TRACK_CUES", "Đề không ghi bài nghe nào (Track 1, Bài nghe 1...) ở tiêu đề phần, đoạn dẫn hay câu hỏi", "The exam names no recording (Track 1, Audio 1...) in a part heading, shared passage or question"),
+    ("E135_ROSTER_INVALID", "Không đọc được danh sách học sinh (cần tệp CSV có cột Họ và tên, Số báo danh, Phòng thi)", "Could not read the student list (a CSV file with Name, Candidate number and Room columns is expected)"),
+    ("E136_SEATING_IMPOSSIBLE", "Không xếp được chỗ ngồi (thiếu chỗ, thiếu phòng hoặc chỉ có một mã đề)", "Could not seat the students (not enough seats, a missing room or a single variant)"),
+    ("E137_ANSWER_SHEET_MISMATCH", "Phiếu trả lời không dùng được cho đề (quá nhiều câu, quá 4 phương án hoặc mã đề không phải số 3 chữ số)", "The answer sheet does not fit the exam (too many questions, more than 4 options or an exam code that is not a 3-digit number)"),
+    ("E140_BANK_QUESTION_NOT_FOUND", "Không tìm thấy câu hỏi trong ngân hàng", "Question not found in the bank"),
+    ("E141_BANK_REVISION_NOT_FOUND", "Không tìm thấy phiên bản này của câu hỏi", "Question revision not found"),
+    ("E142_BANK_BUSY", "Ngân hàng câu hỏi đang được người khác cập nhật, vui lòng thử lại", "The question bank is being updated by someone else, please try again"),
+    ("E143_BANK_CONFLICT", "Câu hỏi đã được người khác sửa, hãy tải lại trước khi lưu", "The question was changed by someone else, reload it before saving"),
+    ("E144_BANK_READ_ONLY", "Không ghi được vào thư mục ngân hàng câu hỏi, ngân hàng đang ở chế độ chỉ đọc", "The question bank folder is not writable, the bank is read-only"),
+    ("E145_BANK_ARCHIVE_INVALID", "Tệp không phải ngân hàng câu hỏi SiroMix hoặc đã bị hỏng", "Not a SiroMix question bank file, or the file is damaged"),
+    ("E146_BANK_ARCHIVE_PASSWORD", "Sai mật khẩu hoặc tệp đã bị chỉnh sửa", "Wrong password, or the file was modified"),
+    ("E147_BANK_PASSWORD_REQUIRED", "Cần nhập mật khẩu để mã hoá ngân hàng câu hỏi", "A password is required to encrypt the question bank"),
+    ("E148_BANK_QUESTION_HAS_OPTIONS", "Câu hỏi đã có phương án trả lời", "The question already has options"),
+    ("E149_ANSWER_NOT_NUMERIC", "Đáp án không phải là một số", "The answer is not a number"),
+    ("E150_WORKSPACE_LOCKED", "Dữ liệu đề đang được mã hoá, hãy nhập mật khẩu để mở khoá", "Exam data is encrypted, enter the passphrase to unlock it"),
+    ("E151_WORKSPACE_PASSPHRASE", "Sai mật khẩu mở khoá dữ liệu đề", "Wrong passphrase for the exam data"),
+    ("E152_WORKSPACE_ENCRYPTION_ENABLED", "Mã hoá dữ liệu đề đã được bật", "Exam data encryption is already enabled"),
+    ("E153_ANSWER_KEY_LOCKED", "Đáp án của đề này được bảo vệ, hãy nhập đúng mật khẩu", "The answer key of this exam is protected, enter the correct passphrase"),
+    ("E154_ANSWER_KEY_NOT_RELEASED", "Đáp án của đề này chưa đến giờ công bố; cần mật khẩu mở sớm để xuất trước giờ", "The answer key of this exam is not released yet; the early-release passphrase is needed to export it before then"),
+    ("E170_NOT_A_DOCX", "File không phải là tài liệu Word .docx. Hãy mở file bằng Word và chọn Lưu thành (Save As) định dạng .docx", "The file is not a Word .docx document. Open it in Word and use Save As with the .docx format"),
+    ("E171_ENCRYPTED_DOCX", "File docx đang được đặt mật khẩu. Hãy mở bằng Word, bỏ mật khẩu (File > Info > Protect Document) rồi lưu lại", "The docx is password-protected. Open it in Word, remove the password (File > Info > Protect Document) and save it again"),
+    ("E172_TRUNCATED_ZIP", "File docx bị hỏng hoặc chưa tải/chép xong. Hãy tải hoặc chép lại file; nếu vẫn lỗi, mở bằng Word để Word tự sửa rồi lưu lại", "The docx is damaged or was not fully downloaded or copied. Download or copy it again; if that fails, let Word repair it and save it again"),
+    ("E180_OPEN_FAILED", "Không mở được thư mục hoặc file trong trình quản lý file", "Could not open the folder or file in the file manager"),
+    ("E181_CLIPBOARD_FAILED", "Không chép được vào bộ nhớ tạm (clipboard)", "Could not copy to the clipboard"),
+    ("E182_NOTHING_EXPORTED", "Đề này chưa được xuất, hãy xuất đề trước khi tạo gói gửi", "This exam has not been exported yet; export it before creating a bundle to send"),
+    ("E183_PATH_NOT_ALLOWED", "Chỉ mở được file trong thư mục làm việc hoặc thư mục xuất của đề", "Only files in the exam's workspace or export folder can be opened"),
+    ("E190_UPDATE_URL_NOT_SET", "Chưa cấu hình địa chỉ kiểm tra cập nhật trong cài đặt", "No update check address is configured in the settings"),
+    ("E192_PARAPHRASE_URL_NOT_SET", "Chưa đặt địa chỉ dịch vụ diễn đạt lại câu hỏi trong cài đặt", "No paraphrasing service is set in the settings"),
+    ("E193_PARAPHRASE_FAILED", "Không lấy được câu diễn đạt lại từ dịch vụ", "The paraphrasing service did not answer"),
+    ("E194_PARAPHRASE_NOT_TEXT", "Câu hỏi có hình, công thức hoặc chỗ trống nên không diễn đạt lại được", "The question has images, equations or blanks and cannot be paraphrased"),
+    ("E191_UPDATE_CHECK_FAILED", "Không kiểm tra được bản cập nhật", "Could not check for updates"),
+    ("E200_LICENSE_NOT_SUPPORTED", "Bản cài đặt này không hỗ trợ kích hoạt giấy phép", "This build does not support license activation"),
+    ("E201_LICENSE_INVALID", "Mã giấy phép không hợp lệ", "The license key is not valid"),
+    ("E202_LICENSE_EXPIRED", "Giấy phép đã hết hạn", "The license has expired"),
+    ("E203_LICENSE_REQUIRED", "Định dạng xuất này cần giấy phép của trường", "This export format requires a school license"),
+    ("E210_EXPORTER_NOT_FOUND", "Không tìm thấy định dạng xuất này, có thể tiện ích đã bị gỡ", "Export format not found; the add-on may have been removed"),
+    ("E211_EXPORTER_FAILED", "Định dạng xuất bổ sung bị lỗi", "The add-on export format failed"),
+    ("E900_INTERNAL", "Lỗi hệ thống", "Internal error"),
+    // Status
+    ("S001_ANALYZE_OK", "Phân tích đề thành công", "Exam analyzed successfully"),
+    ("S002_ANALYZE_HAS_ERRORS", "Đề có lỗi cần sửa trước khi trộn", "The exam has errors to fix before mixing"),
+    ("S003_EXPORT_OK", "Xuất file thành công", "Export completed"),
+    ("S004_ANALYZE_PARTIAL", "Đã phân tích đề, các câu lỗi được bỏ qua", "Exam analyzed; questions with errors were left out"),
+    ("S005_EXPORT_KEY_WITHHELD", "Đã xuất đề; bảng đáp án và bản giáo viên chưa xuất vì chưa đến giờ công bố đáp án", "Exams exported; the answer key and teacher copies were held back until the key release time"),
+    ("S006_EXPORT_KEY_LOCKED", "Đã xuất đề; bảng đáp án và bản giáo viên chưa xuất vì thiếu hoặc sai mật khẩu đáp án", "Exams exported; the answer key and teacher copies were held back: the answer key passphrase is missing or wrong"),
+];
+
+/// Localized text for `code` in `lang`, falling back to the code itself.
+pub fn message_in(code: &str, lang: Lang) -> String {
+    CATALOG
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map(|(_, vi, en)| match lang {
+            Lang::Vi => *vi,
+            Lang::En => *en,
+        })
+        .unwrap_or(code)
+        .to_string()
+}
+
+/// Localized text for `code` in the current language.
+pub fn message(code: &str) -> String {
+    message_in(code, current_lang())
+}
+
+/// Error returned by Tauri commands: a stable code plus a localized message.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandError {
+    pub code: String,
+    pub message: String,
+}
+
+impl CommandError {
+    pub fn new(code: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message(code),
+        }
+    }
+
+    /// Localized message followed by technical details (paths, IO errors, ...)
+    pub fn with_detail(code: &str, detail: impl std::fmt::Display) -> Self {
+        Self {
+            code: code.to_string(),
+            message: format!("{}: {}", message(code), detail),
+        }
+    }
+}
+
+/// Helpers in `storage` still report plain strings; surface them as internal
+/// errors, unless the string is itself a catalog code.
+impl From<String> for CommandError {
+    fn from(detail: String) -> Self {
+        if CATALOG.iter().any(|(code, _, _)| *code == detail) {
+            return CommandError::new(&detail);
+        }
+        CommandError::with_detail("E900_INTERNAL", detail)
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_has_both_languages() {
+        for (code, vi, en) in CATALOG {
+            assert!(!vi.is_empty() && !en.is_empty(), "missing translation for {}", code);
+        }
+        assert_eq!(message_in("E900_INTERNAL", Lang::En), "Internal error");
+        assert_eq!(message_in("UNKNOWN_CODE", Lang::Vi), "UNKNOWN_CODE");
+    }
+}
@@ -0,0 +1,234 @@
+// src-tauri/src/intake.rs
+//! Queue of exams dropped together on the window.
+//!
+//! Each file gets its own job and the files are analyzed one after the other
+//! by a single worker, so dropping twenty files does not start twenty
+//! analyses at once. Every change of state is sent to the frontend as
+//! [`INTAKE_EVENT`]; the whole queue, with the outcome of the files done so
+//! far, is available at any time ([`status`]).
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+/// Name of the live event emitted whenever a queued file changes state
+pub const INTAKE_EVENT: &str = "analysis-queue";
+
+static QUEUE: Mutex<IntakeQueue> = Mutex::new(IntakeQueue::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IntakeState {
+    Queued,
+    Analyzing,
+    /// Analyzed and saved
+    Analyzed,
+    /// Analyzed, but the document has errors to fix (nothing saved)
+    HasErrors,
+    /// The analysis could not run (unreadable file, disk error...)
+    Failed,
+}
+
+impl IntakeState {
+    fn finished(self) -> bool {
+        !matches!(self, IntakeState::Queued | IntakeState::Analyzing)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntakeItem {
+    pub job_id: String,
+    pub path: String,
+    /// File name, for display
+    pub name: String,
+    pub state: IntakeState,
+    /// Questions saved by the analysis
+    pub question_count: usize,
+    pub error_count: usize,
+    pub warning_count: usize,
+    /// Why the analysis could not run (`Failed`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<crate::i18n::CommandError>,
+}
+
+/// Outcome of one analysis, as recorded in the queue
+pub enum Outcome {
+    Analyzed {
+        ok: bool,
+        question_count: usize,
+        error_count: usize,
+        warning_count: usize,
+    },
+    Failed(crate::i18n::CommandError),
+}
+
+/// Files of the queue in drop order, with the outcome of those done
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntakeStatus {
+    pub items: Vec<IntakeItem>,
+    /// A file is being analyzed or waiting
+    pub running: bool,
+    pub finished: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct IntakeQueue {
+    items: Vec<IntakeItem>,
+    running: bool,
+}
+
+impl IntakeQueue {
+    pub const fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            running: false,
+        }
+    }
+
+    /// Queue `(job id, path)` pairs; returns the new items and whether a
+    /// worker must be started (none is running).
+    pub fn enqueue(&mut self, files: Vec<(String, String)>) -> (Vec<IntakeItem>, bool) {
+        let added: Vec<IntakeItem> = files
+            .into_iter()
+            .map(|(job_id, path)| IntakeItem {
+                name: Path::new(&path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path.clone()),
+                job_id,
+                path,
+                state: IntakeState::Queued,
+                question_count: 0,
+                error_count: 0,
+                warning_count: 0,
+                error: None,
+            })
+            .collect();
+        self.items.extend(added.iter().cloned());
+        let start = !self.running && !added.is_empty();
+        self.running |= start;
+        (added, start)
+    }
+
+    /// Mark the next queued file as being analyzed. `None` when the queue is
+    /// done: the worker stops.
+    pub fn start_next(&mut self) -> Option<IntakeItem> {
+        match self.items.iter_mut().find(|item| item.state == IntakeState::Queued) {
+            Some(item) => {
+                item.state = IntakeState::Analyzing;
+                Some(item.clone())
+            }
+            None => {
+                self.running = false;
+                None
+            }
+        }
+    }
+
+    pub fn finish(&mut self, job_id: &str, outcome: Outcome) -> Option<IntakeItem> {
+        let item = self.items.iter_mut().find(|item| item.job_id == job_id)?;
+        match outcome {
+            Outcome::Analyzed {
+                ok,
+                question_count,
+                error_count,
+                warning_count,
+            } => {
+                item.state = if ok { IntakeState::Analyzed } else { IntakeState::HasErrors };
+                item.question_count = question_count;
+                item.error_count = error_count;
+                item.warning_count = warning_count;
+            }
+            Outcome::Failed(error) => {
+                item.state = IntakeState::Failed;
+                item.error = Some(error);
+            }
+        }
+        Some(item.clone())
+    }
+
+    /// Forget the files already done (the results list is cleared)
+    pub fn clear_finished(&mut self) {
+        self.items.retain(|item| !item.state.finished());
+    }
+
+    pub fn status(&self) -> IntakeStatus {
+        IntakeStatus {
+            items: self.items.clone(),
+            running: self.running,
+            finished: self.items.iter().filter(|item| item.state.finished()).count(),
+            total: self.items.len(),
+        }
+    }
+}
+
+pub fn enqueue(files: Vec<(String, String)>) -> (Vec<IntakeItem>, bool) {
+    QUEUE.lock().unwrap().enqueue(files)
+}
+
+pub fn start_next() -> Option<IntakeItem> {
+    QUEUE.lock().unwrap().start_next()
+}
+
+pub fn finish(job_id: &str, outcome: Outcome) -> Option<IntakeItem> {
+    QUEUE.lock().unwrap().finish(job_id, outcome)
+}
+
+pub fn clear_finished() {
+    QUEUE.lock().unwrap().clear_finished()
+}
+
+pub fn status() -> IntakeStatus {
+    QUEUE.lock().unwrap().status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(names: &[&str]) -> Vec<(String, String)> {
+        names
+            .iter()
+            .map(|name| (format!("job-{name}"), format!("/de/{name}.docx")))
+            .collect()
+    }
+
+    #[test]
+    fn analyzes_dropped_files_one_at_a_time() {
+        let mut queue = IntakeQueue::new();
+        let (added, start) = queue.enqueue(paths(&["toan", "ly"]));
+        assert!(start);
+        assert_eq!(added[0].name, "toan.docx");
+
+        let first = queue.start_next().unwrap();
+        assert_eq!(first.job_id, "job-toan");
+        // Files dropped while the worker runs join the same queue
+        let (_, start) = queue.enqueue(paths(&["hoa"]));
+        assert!(!start);
+
+        let outcome = Outcome::Analyzed {
+            ok: true,
+            question_count: 40,
+            error_count: 0,
+            warning_count: 1,
+        };
+        assert_eq!(queue.finish("job-toan", outcome).unwrap().state, IntakeState::Analyzed);
+        assert_eq!(queue.start_next().unwrap().job_id, "job-ly");
+        queue.finish("job-ly", Outcome::Failed(crate::i18n::CommandError::new("E100_DOCX_READ_FAILED")));
+        assert_eq!(queue.start_next().unwrap().job_id, "job-hoa");
+
+        let status = queue.status();
+        assert_eq!((status.finished, status.total, status.running), (2, 3, true));
+        assert_eq!(status.items[1].state, IntakeState::Failed);
+
+        queue.clear_finished();
+        assert_eq!(queue.status().total, 1);
+        queue.finish("job-hoa", Outcome::Analyzed { ok: false, question_count: 0, error_count: 2, warning_count: 0 });
+        assert!(queue.start_next().is_none());
+        assert!(!queue.status().running);
+    }
+}
@@ -0,0 +1,103 @@
+// src-tauri/src/launch.rs
+//! Files opened with the app ("Open with SiroMix", double-click on an
+//! associated file, or a path on the command line).
+//!
+//! The paths come from the command line of this process at startup, or from
+//! a second launch forwarded by the single-instance plugin. An exam (`.docx`)
+//! gets a new job id for the frontend to analyze it under; a bank archive
+//! (`.siromix`, see `bank::archive`) is imported once the user gives its
+//! password. Files wait in a queue until the frontend takes them, since the
+//! startup ones arrive before any window listens ([`OPENED_FILE_EVENT`]).
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+/// Event telling the frontend that files opened while running were queued
+pub const OPENED_FILE_EVENT: &str = "file-opened";
+
+/// Extension of bank archives (`export_bank`)
+pub const BANK_ARCHIVE_EXTENSION: &str = "siromix";
+
+static PENDING: Mutex<Vec<OpenedFile>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum OpenedFile {
+    /// Exam to analyze under the new job `job_id`
+    #[serde(rename_all = "camelCase")]
+    Exam { path: String, job_id: String },
+    /// Bank archive to import
+    #[serde(rename_all = "camelCase")]
+    BankArchive { path: String },
+}
+
+/// Files named in `args` (program name excluded), relative paths taken from
+/// `cwd`. Options, missing files and other extensions are skipped.
+pub fn from_args(args: impl IntoIterator<Item = String>, cwd: &Path) -> Vec<OpenedFile> {
+    args.into_iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .filter_map(|arg| {
+            let path = cwd.join(&arg);
+            let opened = classify(&path);
+            if opened.is_none() {
+                eprintln!("[Launch] Ignoring argument {:?}", arg);
+            }
+            opened
+        })
+        .collect()
+}
+
+fn classify(path: &Path) -> Option<OpenedFile> {
+    if !path.is_file() {
+        return None;
+    }
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    let path_text = path.to_string_lossy().to_string();
+    match extension.as_str() {
+        "docx" => Some(OpenedFile::Exam {
+            path: path_text,
+            job_id: uuid::Uuid::new_v4().to_string(),
+        }),
+        BANK_ARCHIVE_EXTENSION => Some(OpenedFile::BankArchive { path: path_text }),
+        _ => None,
+    }
+}
+
+/// Queue files for the frontend
+pub fn push(files: &[OpenedFile]) {
+    PENDING.lock().unwrap().extend_from_slice(files);
+}
+
+/// Queued files, oldest first; the queue is emptied
+pub fn take() -> Vec<OpenedFile> {
+    std::mem::take(&mut *PENDING.lock().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_opened_files() {
+        let dir = std::env::temp_dir().join(format!("siromix-launch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["De thi.DOCX", "nganhang.siromix", "ghichu.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let args = ["--flag", "De thi.DOCX", "nganhang.siromix", "ghichu.txt", "khongco.docx"];
+        let opened = from_args(args.map(String::from), &dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(opened.len(), 2);
+        assert!(matches!(&opened[0], OpenedFile::Exam { path, .. } if path.ends_with("De thi.DOCX")));
+        assert_eq!(
+            opened[1],
+            OpenedFile::BankArchive {
+                path: dir.join("nganhang.siromix").to_string_lossy().to_string()
+            }
+        );
+    }
+}
@@ -1,11 +1,21 @@
 mod storage;
 mod docx;
+mod i18n;
+mod bank;
+mod roster;
+mod exporters;
+mod intake;
+mod launch;
+mod shell;
+mod updates;
 
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::fs;
 
 use crate::docx::model::ParsedDoc;
+use crate::i18n::CommandError;
+use crate::storage::timeline::JobEvent;
 
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -13,12 +23,45 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 pub struct AnalyzeDocxPayload {
     #[serde(rename = "jobId")]
     pub job_id: String,
     #[serde(rename = "sourcePath")]
     pub source_path: String,
+    /// Số phương án tối đa mỗi câu (mặc định A–H)
+    #[serde(rename = "maxOptions", default)]
+    pub max_options: Option<usize>,
+    /// Đề song ngữ: ghép cặp bản tiếng Việt / tiếng Anh của mỗi câu
+    #[serde(default)]
+    pub bilingual: bool,
+    /// Quy tắc gán đoạn không có tiền tố (dòng trống, style, thụt lề)
+    #[serde(default)]
+    pub continuation: crate::docx::parser::ContinuationRules,
+    /// Giữ các đoạn <w:p> gốc để xuất ở chế độ giữ nguyên định dạng
+    #[serde(rename = "keepRawXml", default)]
+    pub keep_raw_xml: bool,
+    /// Nhận dạng chữ (OCR) trong hình để tìm kiếm trong ngân hàng câu hỏi
+    #[serde(rename = "ocrImages", default)]
+    pub ocr_images: bool,
+    /// Dùng đáp án chọn tay (`set_correct_label`) cho các câu chưa đánh dấu
+    /// đáp án đúng thay vì báo lỗi E020
+    #[serde(rename = "continueWithManualAnswers", default)]
+    pub continue_with_manual_answers: bool,
+    /// Bỏ qua các câu lỗi thay vì chặn cả đề (danh sách câu bị bỏ trong
+    /// `skipped`); lỗi không thuộc câu nào vẫn chặn cả đề
+    #[serde(rename = "allowPartial", default)]
+    pub allow_partial: bool,
+    /// Nhận các tiêu đề chương ("CHƯƠNG 1: …") và gắn chương cho các câu sau đó
+    #[serde(rename = "detectChapters", default)]
+    pub detect_chapters: bool,
+    /// Phân tích lại cả khi có mã đề đã duyệt/đã xuất (chúng trở về bản nháp)
+    #[serde(default)]
+    pub force: bool,
+    /// Giữ các chỉnh sửa tay (đáp án chọn tay, đoạn gán lại) khi phân tích lại
+    /// đề đã sửa: chúng được chuyển sang đúng câu/đoạn của lần phân tích mới
+    #[serde(rename = "preserveEdits", default)]
+    pub preserve_edits: bool,
 }
 
 #[derive(Serialize)]
@@ -26,8 +69,25 @@ pub struct AnalyzeDocxResponse {
     pub ok: bool,
     #[serde(rename = "jobId")]
     pub job_id: String,
+    /// Thông báo trạng thái đã bản địa hoá (theo cài đặt ngôn ngữ)
+    pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub errors: Option<Vec<AnalyzeDocxError>>,
+    /// Cảnh báo không chặn việc trộn đề (vd. hình bị thiếu)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<AnalyzeDocxError>>,
+    /// Câu bị bỏ khỏi đề ở chế độ `allowPartial` (lý do nằm trong `errors`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<Vec<u32>>,
+    /// Số câu theo từng chương (khi phân tích với `detectChapters`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chapters: Option<Vec<crate::docx::chapters::ChapterCount>>,
+    /// Phân bố đáp án đúng của đề gốc (A/B/C/D), cảnh báo khi lệch nhiều
+    #[serde(rename = "answerDistribution", skip_serializing_if = "Option::is_none")]
+    pub answer_distribution: Option<crate::docx::stats::AnswerDistribution>,
+    /// Chỉnh sửa tay không giữ lại được khi phân tích với `preserveEdits`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicts: Option<Vec<crate::docx::reanalysis::EditConflict>>,
 }
 
 #[derive(Serialize)]
@@ -35,276 +95,3211 @@ pub struct AnalyzeDocxError {
     pub code: String,
     #[serde(rename = "questionNumber")]
     pub question_number: u32,
+    pub message: String,
+    /// What was matched, e.g. the forbidden term
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl AnalyzeDocxError {
+    fn new(code: &str, question_number: u32) -> Self {
+        Self {
+            code: code.to_string(),
+            question_number,
+            message: i18n::message(code),
+            detail: None,
+        }
+    }
+
+    fn with_detail(code: &str, question_number: u32, detail: impl Into<String>) -> Self {
+        Self {
+            detail: Some(detail.into()),
+            ..Self::new(code, question_number)
+        }
+    }
 }
 
 #[tauri::command]
 async fn analyze_docx(
     app_handle: tauri::AppHandle,
     payload: AnalyzeDocxPayload,
-) -> Result<AnalyzeDocxResponse, String> {
-    use crate::storage::{fs, paths};
+) -> Result<AnalyzeDocxResponse, CommandError> {
+    use crate::storage::{fs, manifest, paths};
 
-    use crate::docx::{assets, parser, read};
-    use crate::docx::validator;
+    use crate::docx::{labels, parser, reanalysis};
+
+    let mut job_manifest = manifest::load(&app_handle, &payload.job_id)?;
+    // Các mã đề chỉ trở về bản nháp khi phân tích xong (`unfreeze_after_analysis`)
+    check_frozen(&job_manifest, None, payload.force)?;
+    let mut parse_options = parser::ParseOptions {
+        max_options: labels::clamp_max_options(
+            payload.max_options.unwrap_or(labels::DEFAULT_MAX_OPTIONS),
+        ),
+        bilingual: payload.bilingual,
+        continuation: payload.continuation,
+        keep_raw_xml: payload.keep_raw_xml,
+        ocr_images: payload.ocr_images,
+        // Đáp án chọn tay của lần phân tích trước được giữ lại
+        manual_answers: if payload.continue_with_manual_answers {
+            job_manifest.parse_options.manual_answers.clone()
+        } else {
+            Default::default()
+        },
+        continue_with_manual_answers: payload.continue_with_manual_answers,
+        allow_partial: payload.allow_partial,
+        detect_chapters: payload.detect_chapters,
+        ..Default::default()
+    };
 
     let workspace_dir =
         paths::job_workspace_dir(&app_handle, &payload.job_id)?;
 
     fs::ensure_dir(&workspace_dir)?;
 
+    // Bản phân tích mà các chỉnh sửa tay đã dựa vào, trước khi bị ghi đè
+    let previous = payload
+        .preserve_edits
+        .then(|| load_parsed_doc(&app_handle, &payload.job_id).unwrap_or_default());
+
     let source = Path::new(&payload.source_path);
     let destination = workspace_dir.join("source.docx");
 
     fs::copy_file(source, &destination)?;
-    let docx_path = &destination;
+
+    // Tài liệu con (master document) nằm ở file riêng: chép vào workspace
+    let missing = crate::docx::parts::copy_subdocuments(source, &destination, &workspace_dir);
+    if !missing.is_empty() {
+        eprintln!("[Parts] Subdocument(s) not found: {:?}", missing);
+    }
+
+    // Chuyển chỉnh sửa tay sang câu/đoạn tương ứng của đề mới
+    let mut conflicts = None;
+    if let Some(previous) = previous {
+        let fresh = parse_source(&workspace_dir, &parse_options);
+        let carried = reanalysis::carry_over(&job_manifest.parse_options, &previous, &fresh);
+        println!(
+            "[Analyze] Preserved {} answer(s), {} paragraph assignment(s), {} conflict(s)",
+            carried.manual_answers.len(),
+            carried.overrides.len(),
+            carried.conflicts.len()
+        );
+        parse_options.continue_with_manual_answers |= !carried.manual_answers.is_empty();
+        parse_options.manual_answers = carried.manual_answers;
+        parse_options.overrides = carried.overrides;
+        conflicts = Some(carried.conflicts).filter(|c| !c.is_empty());
+    }
+
+    // Lưu cấu hình phân tích để có thể phân tích lại (vd. reassign_paragraph)
+    job_manifest.parse_options = parse_options.clone();
+    manifest::save(&app_handle, &job_manifest)?;
+
+    let env = AnalyzeEnv::load(&app_handle)?;
+    let job_id = payload.job_id.clone();
+    let mut response = analyze_workspace(payload.job_id, &workspace_dir, &parse_options, &env).await?;
+    response.conflicts = conflicts;
+    unfreeze_after_analysis(&app_handle, &job_id, &response)?;
+    record_analyzed(&app_handle, &job_id, &response);
+    start_conversions(&app_handle, &job_id);
+    Ok(response)
+}
+
+/// Parse nhanh `<workspace>/source.docx` (không trích xuất hình, không kiểm
+/// tra) để biết các câu và đoạn văn của đề. Đề không đọc được: không có câu nào.
+fn parse_source(workspace_dir: &Path, parse_options: &crate::docx::parser::ParseOptions) -> ParsedDoc {
+    use crate::docx::{parser, parts, read};
+
+    let docx_path = workspace_dir.join("source.docx");
+    let Ok(document_xml) = read::read_document_xml(&docx_path) else {
+        return ParsedDoc::default();
+    };
+    let document_xml = parts::merge(&docx_path, &document_xml, &workspace_dir.join(parts::SUBDOCS_DIR))
+        .map(|(merged, _)| merged)
+        .unwrap_or(document_xml);
+    parser::parse_document_xml_to_parsed_doc(&document_xml, &[], parse_options)
+}
+
+/// Cấu hình dùng chung cho mọi lần phân tích, lấy từ cài đặt và thư mục ứng dụng
+struct AnalyzeEnv {
+    forbidden_terms: Vec<String>,
+    /// Cách chuyển WMF/EMF sang PNG (độ phân giải, cắt lề, màu nền)
+    wmf_conversion: crate::docx::assets::ConversionParams,
+    /// Bộ nhớ đệm PNG đã chuyển từ WMF/EMF, dùng chung giữa các job
+    conversion_cache_dir: Option<std::path::PathBuf>,
+    /// Thư mục các quy tắc kiểm tra riêng của trường (`*.rhai`)
+    rules_dir: Option<std::path::PathBuf>,
+    /// Đề các năm trước, để báo câu hỏi bị dùng lại
+    past_exams: storage::past_exams::PastExamCorpus,
+    /// Tài nguyên đi kèm ứng dụng (dữ liệu ngôn ngữ OCR `tessdata/`)
+    resource_dir: Option<std::path::PathBuf>,
+}
+
+impl AnalyzeEnv {
+    fn load(app_handle: &tauri::AppHandle) -> Result<Self, CommandError> {
+        use tauri::Manager;
+
+        let settings = storage::settings::load(app_handle)?;
+        Ok(Self {
+            forbidden_terms: settings.forbidden_terms,
+            wmf_conversion: settings.wmf_conversion,
+            conversion_cache_dir: storage::paths::conversion_cache_dir(app_handle).ok(),
+            rules_dir: storage::paths::rules_dir(app_handle).ok(),
+            // Kho đề cũ đọc không được thì chỉ bỏ qua bước so sánh
+            past_exams: storage::past_exams::load(app_handle).unwrap_or_default(),
+            resource_dir: app_handle.path().resource_dir().ok(),
+        })
+    }
+}
+
+/// Phân tích `<workspace>/source.docx`: parse, validate và ghi `parsed.json`
+/// nếu không có lỗi. Dùng chung cho `analyze_docx` và `reassign_paragraph`.
+async fn analyze_workspace(
+    job_id: String,
+    workspace_dir: &Path,
+    parse_options: &crate::docx::parser::ParseOptions,
+    env: &AnalyzeEnv,
+) -> Result<AnalyzeDocxResponse, CommandError> {
+    use crate::docx::{assets, audio, forbidden, parser, parts, read, rules};
+    use crate::docx::validator;
+
+    let docx_path = &workspace_dir.join("source.docx");
+    // Còn lại sau khi ứng dụng bị tắt ngang: job sẽ được báo khi khởi động lại
+    let _marker = storage::recovery::AnalysisMarker::create(workspace_dir);
 
     // 1) Read document.xml from the .docx
-    let document_xml = read::read_document_xml(docx_path)
-        .map_err(|e| format!("Không đọc được document.xml: {:?}", e))?;
+    let document_xml = read::read_document_xml(docx_path).map_err(|e| match read::inspect(docx_path) {
+        // Lỗi zip chung chung: nói rõ file là gì và cách khắc phục
+        Some(read::InputProblem::NotADocx(kind)) => CommandError::with_detail("E170_NOT_A_DOCX", kind),
+        Some(problem) => CommandError::new(problem.code()),
+        None => CommandError::with_detail("E100_DOCX_READ_FAILED", format!("{:?}", e)),
+    })?;
 
-    // 2) Extract media into `<workspace>/assets/` (async - uses background tasks for WMF conversion)
+    // 1b) Gộp nội dung altChunk (HTML/MHT/docx nhúng) và tài liệu con
+    let (document_xml, part_notices) =
+        match parts::merge(docx_path, &document_xml, &workspace_dir.join(parts::SUBDOCS_DIR)) {
+            Ok(merged) => merged,
+            Err(e) => {
+                eprintln!("[Parts] Could not merge altChunks/subdocuments: {:?}", e);
+                (document_xml, Vec::new())
+            }
+        };
+
+    // 2) Extract media into `<workspace>/assets/`; WMF conversions are queued
+    // and run after the analysis (`start_conversions`)
     let assets_dir = workspace_dir.join("assets");
-    let extracted_assets = assets::extract_media(docx_path, &assets_dir).await
-        .map_err(|e| format!("Không extract media từ docx: {:?}", e))?;
+    let extracted_assets = assets::extract_media(
+        docx_path,
+        &assets_dir,
+        &env.wmf_conversion,
+        env.conversion_cache_dir.as_deref(),
+    )
+    .map_err(|e| match e {
+        crate::docx::AppError::UnsafeArchive(detail) => CommandError::with_detail("E107_ARCHIVE_UNSAFE", detail),
+        e => CommandError::with_detail("E101_MEDIA_EXTRACT_FAILED", format!("{:?}", e)),
+    })?;
+
+    // 2b) Ảnh thu nhỏ cho danh sách câu hỏi, ở background task
+    {
+        let assets = extracted_assets.clone();
+        let thumbnails_dir = workspace_dir.join("thumbnails");
+        let generated = tokio::task::spawn_blocking(move || {
+            crate::docx::thumbnails::generate_all(&assets, &thumbnails_dir)
+        })
+        .await
+        .map_err(|e| CommandError::from(e.to_string()))?;
+        println!("[Thumbnail] Generated {} thumbnail(s)", generated);
+    }
 
     // 3) Parse -> ParsedDoc, đồng thời map các image (kể cả OLE Equation
     // object) theo thứ tự xuất hiện sang danh sách media đã extract.
     let mut parsed_doc = parser::parse_document_xml_to_parsed_doc(
         &document_xml,
         &extracted_assets,
+        parse_options,
     );
 
+    // 3a) Bài nghe nhúng (OLE): trích vào `assets/audio/`, thay biểu tượng bằng Segment::Audio
+    let audio_assets = audio::extract_audio(docx_path, &assets_dir.join(audio::AUDIO_DIR)).map_err(|e| match e {
+        crate::docx::AppError::UnsafeArchive(detail) => CommandError::with_detail("E107_ARCHIVE_UNSAFE", detail),
+        e => CommandError::with_detail("E101_MEDIA_EXTRACT_FAILED", format!("{:?}", e)),
+    })?;
+    let linked = audio::link(&mut parsed_doc, &audio_assets);
+    if linked > 0 {
+        println!("[Audio] Linked {} recording(s)", linked);
+    }
+
+    // 3b) OCR (tuỳ chọn): chạy tesseract trên từng hình, ở background task
+    if parse_options.ocr_images {
+        let tessdata_dir = env.resource_dir.as_ref().map(|dir| dir.join(crate::docx::ocr::TESSDATA_DIR));
+        parsed_doc = tokio::task::spawn_blocking(move || {
+            let recognized = crate::docx::ocr::annotate(&mut parsed_doc, tessdata_dir.as_deref());
+            println!("[OCR] Recognized text in {} image(s)", recognized);
+            parsed_doc
+        })
+        .await
+        .map_err(|e| CommandError::from(e.to_string()))?;
+    }
+
     // 4) Validation: enforce mỗi câu đúng 1 đáp án đúng, dựa trên
     // underline/màu đỏ ở phần label trong document.xml.
     let labeled_option_runs_by_question =
-        parser::collect_labeled_option_runs(&document_xml);
+        parser::collect_labeled_option_runs(&document_xml, parse_options);
     let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for notice in &part_notices {
+        let code = if notice.merged { "W006_PART_MERGED" } else { "W007_PART_NOT_MERGED" };
+        let kind = match notice.kind {
+            parts::PartKind::AltChunk => "altChunk",
+            parts::PartKind::SubDocument => "subDoc",
+        };
+        let mut detail = format!("{} {}", kind, notice.target);
+        if notice.images_dropped > 0 {
+            detail.push_str(&format!(" ({} hình không được gộp)", notice.images_dropped));
+        }
+        warnings.push(AnalyzeDocxError::with_detail(code, notice.after_question.unwrap_or(0), detail));
+    }
+    // Đoạn hỏng chỉ được giữ lại phần chữ: báo để giáo viên kiểm tra lại
+    for &index in &parsed_doc.recovered_paragraphs {
+        warnings.push(AnalyzeDocxError::with_detail("W010_PARAGRAPH_RECOVERED", 0, format!("đoạn {}", index + 1)));
+    }
+    let question_numbers: Vec<u32> = parsed_doc.questions.iter().map(|q| q.number).collect();
 
     for q in &mut parsed_doc.questions {
+        if let Err(err) = validator::validate_images(q) {
+            warnings.push(AnalyzeDocxError::new(err.code.as_str(), err.question_number));
+        }
+
+        for term in forbidden::matches(q, &env.forbidden_terms) {
+            warnings.push(AnalyzeDocxError::with_detail(
+                validator::ValidationErrorCode::W004ForbiddenTerm.as_str(),
+                q.number,
+                term,
+            ));
+        }
+
+        if let Err(err) = validator::validate_negative_stem(q) {
+            warnings.push(AnalyzeDocxError::new(err.code.as_str(), err.question_number));
+        }
+
+        if let Err(err) = validator::validate_statements(q) {
+            warnings.push(AnalyzeDocxError::new(err.code.as_str(), err.question_number));
+        }
+
+        if let Err(err) = validator::validate_references(q, &question_numbers) {
+            warnings.push(AnalyzeDocxError::new(err.code.as_str(), err.question_number));
+        }
+
+        if let Err(err) = validator::validate_option_count(q.number, q.options.len(), parse_options.max_options) {
+            errors.push(AnalyzeDocxError::new(err.code.as_str(), err.question_number));
+        }
+
         if let Some(option_runs) = labeled_option_runs_by_question.get(&q.number) {
             match validator::detect_correct_label_for_question(q.number, option_runs) {
                 Ok(label) => {
                     q.correct_label = label;
                 }
                 Err(err) => {
-                    errors.push(AnalyzeDocxError {
-                        code: err.code.as_str().to_string(),
-                        question_number: err.question_number,
-                    });
+                    errors.push(AnalyzeDocxError::new(err.code.as_str(), err.question_number));
                 }
             }
         } else {
             // Không tìm thấy bất kỳ label được style cho câu này.
-            errors.push(AnalyzeDocxError {
-                code: validator::ValidationErrorCode::E020CorrectMarkMissing
-                    .as_str()
-                    .to_string(),
-                question_number: q.number,
-            });
+            errors.push(AnalyzeDocxError::new(
+                validator::ValidationErrorCode::E020CorrectMarkMissing.as_str(),
+                q.number,
+            ));
+        }
+
+        // Câu thiếu đánh dấu: dùng đáp án chọn tay nếu người dùng cho phép
+        let mark_missing = validator::ValidationErrorCode::E020CorrectMarkMissing.as_str();
+        if errors.last().is_some_and(|e| e.code == mark_missing && e.question_number == q.number) {
+            match crate::docx::manual_answers::apply(q, parse_options) {
+                Some(Ok(label)) => {
+                    errors.pop();
+                    warnings.push(AnalyzeDocxError::with_detail(
+                        validator::ValidationErrorCode::W005ManualAnswer.as_str(),
+                        q.number,
+                        label,
+                    ));
+                }
+                Some(Err(err)) => {
+                    errors.pop();
+                    let label = parse_options.manual_answers.get(&q.number).cloned().unwrap_or_default();
+                    errors.push(AnalyzeDocxError::with_detail(err.code.as_str(), err.question_number, label));
+                }
+                None => {}
+            }
+        }
+    }
+
+    for group in &parsed_doc.groups {
+        if let Err(err) = validator::validate_group(group, &question_numbers) {
+            errors.push(AnalyzeDocxError::new(err.code.as_str(), err.question_number));
+        }
+    }
+
+    // Điểm ghi ở từng câu phải cộng lại đúng bằng điểm của phần
+    for mismatch in crate::docx::exam_parts::check_points(&parsed_doc) {
+        warnings.push(AnalyzeDocxError::with_detail(
+            "W011_PART_POINTS_MISMATCH",
+            mismatch.question_number,
+            format!("{}: {} / {}", mismatch.heading, mismatch.stated, mismatch.total),
+        ));
+    }
+
+    // Câu quá giống một câu trong đề các năm trước
+    let past_matches = storage::past_exams::find_similar(
+        &parsed_doc.questions,
+        &env.past_exams,
+        storage::past_exams::SIMILARITY_THRESHOLD,
+    );
+    for found in past_matches {
+        warnings.push(AnalyzeDocxError::with_detail(
+            "W012_SIMILAR_TO_PAST_EXAM",
+            found.question_number,
+            format!("{}, câu {} ({:.0}%)", found.exam_label, found.past_number, found.similarity * 100.0),
+        ));
+    }
+
+    // Quy tắc riêng của trường (script Rhai), chạy sau khi đã xác định đáp án
+    if let Some(rules_dir) = &env.rules_dir {
+        let (scripts, mut findings) = rules::load_scripts(rules_dir);
+        findings.extend(rules::run(&scripts, &parsed_doc));
+        for finding in findings {
+            match finding {
+                rules::RuleFinding::Reported { severity, question_number, script, message } => {
+                    let (code, list) = match severity {
+                        rules::Severity::Warning => ("W008_CUSTOM_RULE", &mut warnings),
+                        rules::Severity::Error => ("E026_CUSTOM_RULE", &mut errors),
+                    };
+                    list.push(AnalyzeDocxError::with_detail(code, question_number, format!("{}: {}", script, message)));
+                }
+                rules::RuleFinding::Failed { question_number, script, message } => {
+                    warnings.push(AnalyzeDocxError::with_detail(
+                        "W009_RULE_SCRIPT_FAILED",
+                        question_number,
+                        format!("{}: {}", script, message),
+                    ));
+                }
+            }
+        }
+    }
+
+    // Chế độ từng phần: bỏ các câu lỗi, giữ lại phần còn lại của đề
+    let mut skipped = Vec::new();
+    if parse_options.allow_partial && !errors.is_empty() {
+        // Lỗi không thuộc câu nào của đề (vd. E024) thì không bỏ câu được
+        let failed: Vec<u32> = errors.iter().map(|e| e.question_number).collect();
+        skipped = crate::docx::partial::drop_failed(&mut parsed_doc, &failed);
+        if !skipped.is_empty() {
+            // Câu nhắc tới câu bị bỏ không đánh số lại được: cảnh báo kèm số câu
+            for q in &parsed_doc.questions {
+                let missing: Vec<String> = q
+                    .references
+                    .iter()
+                    .filter(|n| skipped.contains(n))
+                    .map(|n| format!("câu {}", n))
+                    .collect();
+                if !missing.is_empty() {
+                    warnings.push(AnalyzeDocxError::with_detail(
+                        validator::ValidationErrorCode::W014CrossReferenceMissing.as_str(),
+                        q.number,
+                        missing.join(", "),
+                    ));
+                }
+            }
+            println!("[Analyze] Partial: skipped question(s) {:?}", skipped);
         }
     }
 
-    if !errors.is_empty() {
+    if !errors.is_empty() && skipped.is_empty() {
         return Ok(AnalyzeDocxResponse {
             ok: false,
-            job_id: payload.job_id,
+            job_id,
+            message: i18n::message("S002_ANALYZE_HAS_ERRORS"),
             errors: Some(errors),
+            warnings: (!warnings.is_empty()).then_some(warnings),
+            skipped: None,
+            chapters: None,
+            answer_distribution: None,
+            conflicts: None,
         });
     }
 
     // 5) Save `<workspace>/parsed.json` and return { ok: true, jobId }
-    let parsed_path = workspace_dir.join("parsed.json");
-    let json = serde_json::to_vec_pretty(&parsed_doc)
-        .map_err(|e| format!("Không serialize parsed.json: {e}"))?;
-
-    std::fs::write(&parsed_path, json).map_err(|e| {
-        format!(
-            "Không ghi parsed.json vào {}: {e}",
-            parsed_path
-                .to_str()
-                .unwrap_or("<invalid-path>")
-        )
-    })?;
+    save_parsed_doc(workspace_dir, &parsed_doc)?;
 
+    let message = if skipped.is_empty() { "S001_ANALYZE_OK" } else { "S004_ANALYZE_PARTIAL" };
+    let chapters = crate::docx::chapters::counts(&parsed_doc);
     Ok(AnalyzeDocxResponse {
         ok: true,
-        job_id: payload.job_id,
-        errors: None,
+        job_id,
+        message: i18n::message(message),
+        errors: (!errors.is_empty()).then_some(errors),
+        warnings: (!warnings.is_empty()).then_some(warnings),
+        skipped: (!skipped.is_empty()).then_some(skipped),
+        chapters: (!chapters.is_empty()).then_some(chapters),
+        answer_distribution: Some(crate::docx::stats::answer_distribution(&parsed_doc)),
+        conflicts: None,
     })
 }
 
-/// Đọc `<workspace>/parsed.json` cho một `job_id` và trả về `ParsedDoc` cho frontend.
+/// Gán lại một đoạn văn (theo chỉ số `<w:p>`) vào phần dẫn, phương án cuối
+/// hoặc câu kế tiếp, rồi phân tích lại đề. `assignment = None` bỏ gán thủ công.
+/// Khi đã có mã đề được duyệt/xuất cần `force` (xem `set_variant_approval`).
 #[tauri::command]
-fn get_parsed(
+async fn reassign_paragraph(
     app_handle: tauri::AppHandle,
     job_id: String,
-) -> Result<ParsedDoc, String> {
-    use crate::storage::paths;
+    paragraph_index: usize,
+    assignment: Option<crate::docx::model::ParagraphAssignment>,
+    force: Option<bool>,
+) -> Result<AnalyzeDocxResponse, CommandError> {
+    use crate::storage::{manifest, paths};
+
+    let mut job_manifest = manifest::load(&app_handle, &job_id)?;
+    check_frozen(&job_manifest, None, force.unwrap_or(false))?;
+    match assignment {
+        Some(assignment) => {
+            job_manifest.parse_options.overrides.insert(paragraph_index, assignment);
+        }
+        None => {
+            job_manifest.parse_options.overrides.remove(&paragraph_index);
+        }
+    }
+    manifest::save(&app_handle, &job_manifest)?;
+    record_event(&app_handle, &job_id, JobEvent::ParagraphReassigned { paragraph_index });
 
     let workspace_dir = paths::job_workspace_dir(&app_handle, &job_id)?;
-    let parsed_path = workspace_dir.join("parsed.json");
+    let env = AnalyzeEnv::load(&app_handle)?;
+    let response = analyze_workspace(job_id.clone(), &workspace_dir, &job_manifest.parse_options, &env).await?;
+    unfreeze_after_analysis(&app_handle, &job_id, &response)?;
+    record_analyzed(&app_handle, &job_id, &response);
+    start_conversions(&app_handle, &job_id);
+    Ok(response)
+}
 
-    if !parsed_path.exists() {
-        return Err(format!(
-            "Không tìm thấy parsed.json cho job_id {} tại {}",
-            job_id,
-            parsed_path.to_str().unwrap_or("<invalid-path>")
-        ));
+/// Các job phân tích dở dang tìm thấy lúc khởi động (ứng dụng bị tắt ngang
+/// khi đang phân tích, hoặc chưa từng phân tích xong).
+#[tauri::command]
+fn get_incomplete_jobs() -> Vec<storage::recovery::IncompleteJob> {
+    storage::recovery::incomplete_jobs()
+}
+
+/// Xử lý một job dở dang: phân tích lại `source.docx` của workspace với cấu
+/// hình đã lưu, hoặc xoá workspace. Trả về kết quả phân tích lại (`None` khi xoá).
+#[tauri::command]
+async fn recover_incomplete_job(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    action: storage::recovery::RecoveryAction,
+) -> Result<Option<AnalyzeDocxResponse>, CommandError> {
+    use crate::storage::{manifest, paths, recovery};
+
+    // Chỉ các job dang dở tìm thấy lúc khởi động: job_id đến từ giao diện và
+    // nhánh Discard xoá cả thư mục
+    if !recovery::incomplete_jobs().iter().any(|job| job.job_id == job_id) {
+        return Err(CommandError::with_detail("E108_INVALID_ID", job_id));
     }
+    let workspace_dir = paths::job_workspace_dir(&app_handle, &job_id)?;
+    let result = match action {
+        recovery::RecoveryAction::Reanalyze => {
+            let job_manifest = manifest::load(&app_handle, &job_id)?;
+            let env = AnalyzeEnv::load(&app_handle)?;
+            let response = analyze_workspace(job_id.clone(), &workspace_dir, &job_manifest.parse_options, &env).await?;
+            record_analyzed(&app_handle, &job_id, &response);
+            start_conversions(&app_handle, &job_id);
+            Some(response)
+        }
+        recovery::RecoveryAction::Discard => {
+            if workspace_dir.exists() {
+                fs::remove_dir_all(&workspace_dir).map_err(|e| {
+                    CommandError::with_detail("E102_WORKSPACE_WRITE_FAILED", format!("{}: {e}", workspace_dir.display()))
+                })?;
+            }
+            None
+        }
+    };
+    recovery::resolve(&job_id);
+    Ok(result)
+}
 
-    let data = fs::read(&parsed_path).map_err(|e| {
-        format!(
-            "Không đọc được parsed.json tại {}: {e}",
-            parsed_path.to_str().unwrap_or("<invalid-path>")
-        )
-    })?;
+#[derive(Serialize)]
+pub struct EnqueueAnalysisResponse {
+    pub queued: Vec<intake::IntakeItem>,
+    /// Tệp không phải .docx (hoặc không tồn tại), không được đưa vào hàng đợi
+    pub rejected: Vec<String>,
+}
 
-    let parsed: ParsedDoc = serde_json::from_slice(&data)
-        .map_err(|e| format!("Không parse được parsed.json: {e}"))?;
+/// Đưa các đề (.docx) được kéo thả cùng lúc vào hàng đợi phân tích. Mỗi tệp
+/// có job riêng và được phân tích lần lượt với cấu hình mặc định, như khi
+/// chọn một tệp; mỗi thay đổi trạng thái được gửi qua sự kiện `analysis-queue`.
+#[tauri::command]
+async fn enqueue_analysis(app_handle: tauri::AppHandle, paths: Vec<String>) -> EnqueueAnalysisResponse {
+    let (accepted, rejected): (Vec<String>, Vec<String>) = paths.into_iter().partition(|path| {
+        let path = Path::new(path);
+        path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("docx"))
+    });
+    let jobs = accepted
+        .into_iter()
+        .map(|path| (uuid::Uuid::new_v4().to_string(), path))
+        .collect();
 
-    Ok(parsed)
+    let (queued, start) = intake::enqueue(jobs);
+    for item in &queued {
+        emit_intake(&app_handle, item);
+    }
+    if start {
+        tokio::spawn(run_intake(app_handle));
+    }
+    EnqueueAnalysisResponse { queued, rejected }
 }
 
-/// Mix exams - shuffle questions and options to create exam variants
-/// This replaces the frontend TypeScript implementation for better performance
+/// Hàng đợi phân tích: các tệp theo thứ tự thả vào, kèm kết quả của các tệp
+/// đã xong (số câu, số lỗi, số cảnh báo).
 #[tauri::command]
-fn mix_exams(
-    parsed_doc: ParsedDoc,
-    num_variants: u32,
-    custom_exam_codes: Option<Vec<String>>,
-) -> Result<Vec<crate::docx::mixer::MixedExam>, String> {
-    use crate::docx::mixer;
+fn get_analysis_queue() -> intake::IntakeStatus {
+    intake::status()
+}
 
-    if num_variants == 0 {
-        return Err("Number of variants must be greater than 0".to_string());
+/// Xoá khỏi danh sách các tệp đã phân tích xong; tệp đang chờ vẫn được giữ.
+#[tauri::command]
+fn clear_analysis_queue() -> intake::IntakeStatus {
+    intake::clear_finished();
+    intake::status()
+}
+
+/// Phân tích lần lượt các tệp trong hàng đợi cho đến khi hết
+async fn run_intake(app_handle: tauri::AppHandle) {
+    while let Some(item) = intake::start_next() {
+        emit_intake(&app_handle, &item);
+        let payload = AnalyzeDocxPayload {
+            job_id: item.job_id.clone(),
+            source_path: item.path.clone(),
+            ..Default::default()
+        };
+        let outcome = match analyze_docx(app_handle.clone(), payload).await {
+            Ok(response) => intake::Outcome::Analyzed {
+                ok: response.ok,
+                question_count: response
+                    .answer_distribution
+                    .as_ref()
+                    .map_or(0, |distribution| distribution.counts.values().sum()),
+                error_count: response.errors.as_ref().map_or(0, Vec::len),
+                warning_count: response.warnings.as_ref().map_or(0, Vec::len),
+            },
+            Err(e) => {
+                eprintln!("[Intake] Analysis of {} failed: {}", item.path, e.code);
+                intake::Outcome::Failed(e)
+            }
+        };
+        if let Some(item) = intake::finish(&item.job_id, outcome) {
+            emit_intake(&app_handle, &item);
+        }
     }
+    println!("[Intake] Queue done");
+}
 
-    if parsed_doc.questions.is_empty() {
-        return Err("No questions found in parsed document".to_string());
+fn emit_intake(app_handle: &tauri::AppHandle, item: &intake::IntakeItem) {
+    use tauri::Emitter;
+
+    if let Err(e) = app_handle.emit(intake::INTAKE_EVENT, item.clone()) {
+        eprintln!("[Intake] Failed to emit {}: {}", intake::INTAKE_EVENT, e);
     }
+}
 
-    // Validate custom exam codes if provided
-    if let Some(ref codes) = custom_exam_codes {
-        if codes.len() != num_variants as usize {
-            return Err(format!(
-                "Number of custom exam codes ({}) must match number of variants ({})",
-                codes.len(),
-                num_variants
-            ));
-        }
+/// Các tệp được mở cùng ứng dụng (dòng lệnh, "Mở bằng SiroMix") mà giao diện
+/// chưa xử lý, cũ trước mới sau; hàng đợi được làm trống. Gọi lúc khởi động và
+/// mỗi khi nhận sự kiện `file-opened`.
+#[tauri::command]
+fn take_opened_files() -> Vec<launch::OpenedFile> {
+    launch::take()
+}
+
+/// Đưa các tệp mở khi ứng dụng đang chạy vào hàng đợi và báo cho giao diện
+fn open_files(app_handle: &tauri::AppHandle, files: Vec<launch::OpenedFile>) {
+    use tauri::Emitter;
+
+    if files.is_empty() {
+        return;
     }
+    println!("[Launch] Opened {} file(s) from another instance", files.len());
+    launch::push(&files);
+    if let Err(e) = app_handle.emit(launch::OPENED_FILE_EVENT, ()) {
+        eprintln!("[Launch] Failed to emit {}: {}", launch::OPENED_FILE_EVENT, e);
+    }
+}
 
-    let variants = mixer::mix_exams(parsed_doc.questions, num_variants as usize, custom_exam_codes);
-    Ok(variants)
+/// Nhân bản một job đã phân tích (đề gốc, kết quả phân tích, cấu hình) để
+/// thử cách trộn khác song song mà không phải phân tích lại. Các mã đề, bản
+/// xuất và nhật ký của job gốc không được chép. Trả về mã job mới.
+#[tauri::command]
+fn duplicate_job(app_handle: tauri::AppHandle, job_id: String) -> Result<String, CommandError> {
+    use crate::storage::{duplicate, manifest, paths};
+
+    let workspace_dir = paths::job_workspace_dir(&app_handle, &job_id)?;
+    if !workspace_dir.join("parsed.json").exists() {
+        return Err(CommandError::with_detail("E103_PARSED_NOT_FOUND", &job_id));
+    }
+
+    let new_job_id = uuid::Uuid::new_v4().to_string();
+    let new_workspace_dir = paths::job_workspace_dir(&app_handle, &new_job_id)?;
+    let copied = duplicate::clone_workspace(&workspace_dir, &new_workspace_dir).map_err(|e| {
+        let _ = fs::remove_dir_all(&new_workspace_dir);
+        CommandError::with_detail("E102_WORKSPACE_WRITE_FAILED", e)
+    })?;
+    println!("[Duplicate] Job {} copied to {} ({} file(s))", job_id, new_job_id, copied);
+
+    // Chưa trộn: mã đề và seed của job gốc không áp dụng cho bản sao
+    let mut job_manifest = manifest::load(&app_handle, &new_job_id)?;
+    job_manifest.job_id = new_job_id.clone();
+    job_manifest.master_seed = None;
+    job_manifest.exam_codes.clear();
+    manifest::save(&app_handle, &job_manifest)?;
+
+    record_event(&app_handle, &new_job_id, JobEvent::DuplicatedFrom { source_job_id: job_id });
+    Ok(new_job_id)
 }
 
-/// Export mixed exams to DOCX and XLSX files
+/// Chọn tay đáp án đúng cho một câu chưa được đánh dấu (E020) rồi phân tích
+/// lại với `continueWithManualAnswers`. `label = None` bỏ đáp án đã chọn.
+/// Khi đã có mã đề được duyệt/xuất cần `force`.
 #[tauri::command]
-async fn export_mixed_exams(
+async fn set_correct_label(
     app_handle: tauri::AppHandle,
     job_id: String,
-    exams: Vec<crate::docx::excel::MixedExam>,
-    original_answers: Vec<String>,
-    output_dir: String,
-) -> Result<ExportResponse, String> {
-    use crate::storage::paths;
-    use crate::docx::writer::ExamWriter;
-    use crate::docx::excel;
-    use std::path::PathBuf;
+    question_number: u32,
+    label: Option<String>,
+    force: Option<bool>,
+) -> Result<AnalyzeDocxResponse, CommandError> {
+    use crate::storage::{manifest, paths};
+
+    let mut job_manifest = manifest::load(&app_handle, &job_id)?;
+    check_frozen(&job_manifest, None, force.unwrap_or(false))?;
+    let label = crate::docx::manual_answers::choose(&mut job_manifest.parse_options, question_number, label.as_deref());
+    manifest::save(&app_handle, &job_manifest)?;
+    record_event(&app_handle, &job_id, JobEvent::AnswerChosen { question_number, label });
 
     let workspace_dir = paths::job_workspace_dir(&app_handle, &job_id)?;
-    let assets_dir = workspace_dir.join("assets");
-    let output_path = PathBuf::from(&output_dir);
+    let env = AnalyzeEnv::load(&app_handle)?;
+    let response = analyze_workspace(job_id.clone(), &workspace_dir, &job_manifest.parse_options, &env).await?;
+    unfreeze_after_analysis(&app_handle, &job_id, &response)?;
+    record_analyzed(&app_handle, &job_id, &response);
+    start_conversions(&app_handle, &job_id);
+    Ok(response)
+}
 
-    let mut docx_files = Vec::new();
+#[derive(Serialize)]
+pub struct AutofixResponse {
+    pub changes: Vec<crate::docx::autofix::AutofixChange>,
+    /// Kết quả phân tích lại sau khi sửa
+    pub analysis: AnalyzeDocxResponse,
+}
 
-    // Generate DOCX for each exam variant
-    for exam in &exams {
-        // Convert MixedQuestion to Question format
-        let questions: Vec<crate::docx::model::Question> = exam
-            .questions
-            .iter()
-            .map(|mq| {
-                // Convert MixedOptions to OptionItems
-                let options: Vec<crate::docx::model::OptionItem> = mq.options
-                    .iter()
-                    .map(|opt| crate::docx::model::OptionItem {
-                        label: opt.label.clone(),
-                        locked: false, // Options are not locked in mixed exams
-                        content: opt.content.clone(),
-                    })
-                    .collect();
+/// Tự sửa các lỗi đánh dấu thường gặp trong đề gốc ("A ." → "A.", nhãn bị
+/// tách nhiều run, đáp án chỉ tô đỏ hoặc chỉ gạch chân, dấu '#' thừa) rồi
+/// phân tích lại. Bản gốc được giữ ở `<workspace>/source.orig.docx` trong lần
+/// sửa đầu tiên. Khi đã có mã đề được duyệt/xuất cần `force`.
+#[tauri::command]
+async fn autofix_job(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    rules: Vec<crate::docx::autofix::AutofixRule>,
+    force: Option<bool>,
+) -> Result<AutofixResponse, CommandError> {
+    use crate::docx::{autofix, parts, read};
+    use crate::storage::{fs, manifest, paths};
 
-                crate::docx::model::Question {
-                    number: mq.display_number as u32,
-                    stem: mq.stem.clone(),
-                    options,
-                    correct_label: mq.correct_answer.clone(),
-                }
-            })
-            .collect();
+    let job_manifest = manifest::load(&app_handle, &job_id)?;
+    let workspace_dir = paths::job_workspace_dir(&app_handle, &job_id)?;
+    let docx_path = workspace_dir.join("source.docx");
 
-        let writer = ExamWriter {
-            exam_code: exam.exam_code.clone(),
-            questions,
-            exam_title: "ĐỀ THI GIỮA KỲ I".to_string(),
-            subject: "Toán học".to_string(),
-            duration_minutes: 90,
-            assets_dir: assets_dir.clone(),
-            // Header metadata (TODO: Get from frontend)
-            school_name: "TRƯỜNG THCS NGUYỄN AN NINH".to_string(),
-            exam_name: "KIỂM TRA GIỮA HKII".to_string(),
-            academic_year: "2024 - 2025".to_string(),
-            grade: "LỚP 7".to_string(),
-        };
+    let document_xml = read::read_document_xml(&docx_path)
+        .map_err(|e| CommandError::with_detail("E100_DOCX_READ_FAILED", format!("{:?}", e)))?;
+    // Chỉ số đoạn phải khớp với lần phân tích (gồm cả altChunk/tài liệu con đã gộp)
+    let part_notices = parts::merge(&docx_path, &document_xml, &workspace_dir.join(parts::SUBDOCS_DIR))
+        .map(|(_, notices)| notices)
+        .unwrap_or_default();
+    let (fixed_xml, changes) =
+        autofix::apply(&document_xml, &part_notices, &rules, job_manifest.parse_options.max_options);
 
-        let filename = format!("De_{}.docx", exam.exam_code);
-        let file_path = output_path.join(&filename);
+    if !changes.is_empty() {
+        check_frozen(&job_manifest, None, force.unwrap_or(false))?;
 
-        writer
-            .write_to_file(&file_path)
-            .map_err(|e| format!("Lỗi tạo file {}: {:?}", filename, e))?;
+        let original = workspace_dir.join("source.orig.docx");
+        if !original.exists() {
+            fs::copy_file(&docx_path, &original)?;
+        }
+        autofix::write_document_xml(&docx_path, &fixed_xml)
+            .map_err(|e| CommandError::with_detail("E102_WORKSPACE_WRITE_FAILED", format!("{:?}", e)))?;
+        println!("[Autofix] {} change(s) in job {}", changes.len(), job_id);
+        record_event(&app_handle, &job_id, JobEvent::Autofixed { change_count: changes.len() });
+    }
 
-        docx_files.push(filename);
+    let env = AnalyzeEnv::load(&app_handle)?;
+    let analysis = analyze_workspace(job_id.clone(), &workspace_dir, &job_manifest.parse_options, &env).await?;
+    if !changes.is_empty() {
+        unfreeze_after_analysis(&app_handle, &job_id, &analysis)?;
     }
+    record_analyzed(&app_handle, &job_id, &analysis);
+    start_conversions(&app_handle, &job_id);
+    Ok(AutofixResponse { changes, analysis })
+}
 
-    // Generate XLSX answer key
-    let xlsx_filename = "Dap_An.xlsx";
-    let xlsx_path = output_path.join(xlsx_filename);
+/// Đọc `<workspace>/parsed.json` cho một `job_id` và trả về `ParsedDoc` cho frontend.
+#[tauri::command]
+fn get_parsed(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+) -> Result<ParsedDoc, CommandError> {
+    load_parsed_doc(&app_handle, &job_id)
+}
 
-    excel::write_answer_key(&exams, &original_answers, &xlsx_path)
-        .map_err(|e| format!("Lỗi tạo file Excel: {:?}", e))?;
+/// Một trang câu hỏi của `parsed.json` (`limit` câu từ vị trí `offset`), cho
+/// đề rất dài không nên gửi qua IPC trong một lần.
+#[tauri::command]
+fn get_parsed_page(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    offset: usize,
+    limit: usize,
+) -> Result<crate::docx::summary::ParsedPage, CommandError> {
+    let parsed = load_parsed_doc(&app_handle, &job_id)?;
+    Ok(crate::docx::summary::page(&parsed, offset, limit))
+}
 
-    Ok(ExportResponse {
-        success: true,
-        docx_files,
-        xlsx_file: xlsx_filename.to_string(),
-        output_directory: output_dir,
-    })
+/// Một câu hỏi theo số câu trong đề gốc.
+#[tauri::command]
+fn get_question(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    number: u32,
+) -> Result<crate::docx::model::Question, CommandError> {
+    let parsed = load_parsed_doc(&app_handle, &job_id)?;
+    parsed
+        .questions
+        .into_iter()
+        .find(|q| q.number == number)
+        .ok_or_else(|| CommandError::with_detail("E105_QUESTION_NOT_FOUND", number.to_string()))
 }
 
-#[derive(Serialize)]
-pub struct ExportResponse {
-    pub success: bool,
-    #[serde(rename = "docxFiles")]
-    pub docx_files: Vec<String>,
-    #[serde(rename = "xlsxFile")]
-    pub xlsx_file: String,
-    #[serde(rename = "outputDirectory")]
-    pub output_directory: String,
+/// Danh sách rút gọn các câu (số câu, vài chữ đầu, số phương án, có hình/công
+/// thức…) để hiển thị danh sách câu hỏi mà không tải toàn bộ nội dung.
+#[tauri::command]
+fn get_parsed_summary(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+) -> Result<Vec<crate::docx::summary::QuestionSummary>, CommandError> {
+    let parsed = load_parsed_doc(&app_handle, &job_id)?;
+    Ok(crate::docx::summary::summarize(&parsed))
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_dialog::init())
+/// Đường dẫn ảnh thu nhỏ của một hình trong đề (`asset_path` của segment
+/// Image). Ảnh được tạo lại nếu chưa có; nếu không tạo được thì trả về chính
+/// hình gốc.
+#[tauri::command]
+fn get_asset_thumbnail(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    asset_path: String,
+) -> Result<String, CommandError> {
+    use crate::docx::thumbnails;
+    use crate::storage::paths;
+
+    let workspace_dir = paths::job_workspace_dir(&app_handle, &job_id)?;
+    let asset = Path::new(&asset_path);
+    let inside_workspace = asset
+        .canonicalize()
+        .ok()
+        .zip(workspace_dir.join("assets").canonicalize().ok())
+        .is_some_and(|(asset, assets_dir)| asset.starts_with(assets_dir));
+    if !inside_workspace {
+        return Err(CommandError::with_detail("E106_ASSET_NOT_FOUND", asset_path));
+    }
+
+    let thumbnails_dir = workspace_dir.join("thumbnails");
+    let thumbnail = thumbnails::thumbnail_path(&thumbnails_dir, asset);
+    if thumbnail.exists() {
+        return Ok(thumbnail.to_string_lossy().into_owned());
+    }
+    match thumbnails::generate(asset, &thumbnails_dir) {
+        Ok(path) => Ok(path.to_string_lossy().into_owned()),
+        Err(e) => {
+            eprintln!("[Thumbnail] {}", e);
+            Ok(asset_path)
+        }
+    }
+}
+
+/// So sánh hai bản nháp đề (hai job đã phân tích) theo từng câu: độ giống
+/// của phần dẫn, phương án thay đổi, đáp án thay đổi, câu thêm/bớt.
+#[tauri::command]
+fn diff_jobs(
+    app_handle: tauri::AppHandle,
+    job_a: String,
+    job_b: String,
+) -> Result<crate::docx::diff::JobDiff, CommandError> {
+    let before = load_parsed_doc(&app_handle, &job_a)?;
+    let after = load_parsed_doc(&app_handle, &job_b)?;
+    Ok(crate::docx::diff::diff_docs(&before, &after))
+}
+
+/// Thống kê độ dài từng câu (số từ phần dẫn, phương án), độ cân đối giữa các
+/// phương án, từ tuyệt đối ("luôn", "không bao giờ"…) và xu hướng đáp án đúng
+/// luôn là phương án dài nhất.
+#[tauri::command]
+fn get_question_stats(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+) -> Result<crate::docx::stats::DocStats, CommandError> {
+    let parsed = load_parsed_doc(&app_handle, &job_id)?;
+    Ok(crate::docx::stats::doc_stats(&parsed))
+}
+
+#[derive(Serialize)]
+pub struct SpellCheckResponse {
+    pub misspellings: Vec<crate::docx::spell::Misspelling>,
+    /// W013 khi không có từ điển, chỉ báo lần đầu trong phiên
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<CommandError>,
+}
+
+/// Kiểm tra chính tả phần dẫn và phương án theo từ điển hunspell vi-VN, trả
+/// về các từ nghi sai kèm số câu. Không có từ điển thì bỏ qua kiểm tra (danh
+/// sách rỗng) và chỉ cảnh báo một lần.
+#[tauri::command]
+fn spell_check_job(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+) -> Result<SpellCheckResponse, CommandError> {
+    use crate::docx::spell::{self, Dictionary};
+    use tauri::Manager;
+
+    let parsed = load_parsed_doc(&app_handle, &job_id)?;
+    let resource_dir = app_handle.path().resource_dir().ok();
+    match Dictionary::load(resource_dir.as_deref()) {
+        Ok(dictionary) => Ok(SpellCheckResponse { misspellings: spell::check(&parsed, &dictionary), warning: None }),
+        Err(e) => {
+            eprintln!("[Spell] Skipped: {}", e);
+            Ok(SpellCheckResponse {
+                misspellings: Vec::new(),
+                warning: spell::first_missing_report()
+                    .then(|| CommandError::with_detail("W013_SPELL_DICTIONARY_MISSING", e)),
+            })
+        }
+    }
+}
+
+fn save_parsed_doc(workspace_dir: &Path, parsed_doc: &ParsedDoc) -> Result<(), CommandError> {
+    storage::parsed_doc::save(workspace_dir, parsed_doc)
+        .map_err(|e| CommandError::with_detail("E102_WORKSPACE_WRITE_FAILED", e))
+}
+
+/// Chạy lại các lần chuyển WMF/EMF sang PNG chưa thành công của một job
+/// (ImageMagick lỗi tạm thời, file bị khoá…), cập nhật `parsed.json` và ảnh
+/// thu nhỏ cho các hình vừa chuyển được. Trả về trạng thái toàn bộ hàng đợi.
+#[tauri::command]
+async fn retry_failed_conversions(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+) -> Result<Vec<crate::docx::assets::ConversionJob>, CommandError> {
+    use crate::docx::assets::{self, ConversionStatus};
+    use crate::storage::paths;
+
+    let workspace_dir = paths::job_workspace_dir(&app_handle, &job_id)?;
+    let before: Vec<_> = assets::load_conversions(&workspace_dir)
+        .into_iter()
+        .filter(|job| job.status != ConversionStatus::Done)
+        .map(|job| job.source)
+        .collect();
+    let cache_dir = paths::conversion_cache_dir(&app_handle).ok();
+    let jobs = assets::retry_failed_conversions(&workspace_dir, cache_dir.as_deref()).await;
+    let converted: Vec<_> = jobs
+        .iter()
+        .filter(|job| job.status == ConversionStatus::Done && before.contains(&job.source))
+        .cloned()
+        .collect();
+    println!("[WMF] Retry converted {} of {} file(s)", converted.len(), before.len());
+
+    apply_conversions(&app_handle, &job_id, &converted)?;
+    Ok(jobs)
+}
+
+/// Chuyển WMF/EMF sang PNG ở background sau khi phân tích (hàng đợi trong
+/// `conversions.json`), cập nhật `parsed.json` và ảnh thu nhỏ rồi báo giao
+/// diện qua sự kiện `wmf-conversions`.
+fn start_conversions(app_handle: &tauri::AppHandle, job_id: &str) {
+    use crate::docx::assets::{self, ConversionStatus};
+    use crate::storage::paths;
+    use tauri::Emitter;
+
+    let Ok(workspace_dir) = paths::job_workspace_dir(app_handle, job_id) else {
+        return;
+    };
+    let app_handle = app_handle.clone();
+    let job_id = job_id.to_string();
+    tokio::spawn(async move {
+        let cache_dir = paths::conversion_cache_dir(&app_handle).ok();
+        let converted: Vec<_> = assets::run_pending_conversions(&workspace_dir, cache_dir.as_deref())
+            .await
+            .into_iter()
+            .filter(|job| job.status == ConversionStatus::Done)
+            .collect();
+        if converted.is_empty() {
+            return;
+        }
+        if let Err(e) = apply_conversions(&app_handle, &job_id, &converted) {
+            eprintln!("[WMF] Could not update job {}: {}", job_id, e);
+        }
+        if let Err(e) = app_handle.emit(assets::CONVERSIONS_EVENT, job_id.clone()) {
+            eprintln!("[WMF] Failed to emit {}: {}", assets::CONVERSIONS_EVENT, e);
+        }
+    });
+}
+
+/// Trỏ các hình của `parsed.json` sang PNG vừa chuyển và tạo ảnh thu nhỏ cho chúng.
+/// `parsed.json` được đọc lại và ghi dưới khoá của workspace, nên không ghi đè
+/// kết quả của một lần phân tích lại hay sửa đáp án chạy trong lúc chuyển ảnh.
+fn apply_conversions(
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    converted: &[crate::docx::assets::ConversionJob],
+) -> Result<(), CommandError> {
+    use crate::storage::paths;
+
+    let workspace_dir = paths::job_workspace_dir(app_handle, job_id)?;
+    if converted.is_empty() {
+        return Ok(());
+    }
+    let updated = storage::parsed_doc::update(&workspace_dir, |parsed| {
+        for question in &mut parsed.questions {
+            remap_image_paths(question, |path| {
+                converted
+                    .iter()
+                    .find(|job| job.source == Path::new(path))
+                    .map(|job| job.target.to_string_lossy().into_owned())
+            });
+        }
+    })
+    .map_err(|e| CommandError::with_detail("E102_WORKSPACE_WRITE_FAILED", e))?;
+    if !updated {
+        return Ok(());
+    }
+
+    let thumbnails_dir = workspace_dir.join("thumbnails");
+    for job in converted {
+        if let Err(e) = crate::docx::thumbnails::generate(&job.target, &thumbnails_dir) {
+            eprintln!("[Thumbnail] {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Thay đường dẫn ảnh trong câu hỏi (thân, phương án, bản ngôn ngữ thứ hai)
+/// bằng đường dẫn `remap` trả về
+fn remap_image_paths(question: &mut crate::docx::model::Question, remap: impl Fn(&str) -> Option<String>) {
+    use crate::docx::model::Segment;
+
+    let segments = question.stem.iter_mut().chain(question.stem_alt.iter_mut()).chain(
+        question
+            .options
+            .iter_mut()
+            .flat_map(|o| o.content.iter_mut().chain(o.content_alt.iter_mut())),
+    );
+    for segment in segments {
+        if let Segment::Image { asset_path, .. } = segment {
+            if let Some(path) = remap(asset_path) {
+                *asset_path = path;
+            }
+        }
+    }
+}
+
+fn load_parsed_doc(app_handle: &tauri::AppHandle, job_id: &str) -> Result<ParsedDoc, CommandError> {
+    use crate::storage::paths;
+
+    let workspace_dir = paths::job_workspace_dir(app_handle, job_id)?;
+    let parsed_path = workspace_dir.join("parsed.json");
+
+    if !parsed_path.exists() {
+        return Err(CommandError::with_detail(
+            "E103_PARSED_NOT_FOUND",
+            format!(
+                "{} ({})",
+                job_id,
+                parsed_path.to_str().unwrap_or("<invalid-path>")
+            ),
+        ));
+    }
+
+    let data = fs::read(&parsed_path).map_err(|e| {
+        CommandError::with_detail(
+            "E104_PARSED_INVALID",
+            format!("{}: {e}", parsed_path.to_str().unwrap_or("<invalid-path>")),
+        )
+    })?;
+    let data = storage::crypto::unprotect(data)?;
+
+    let parsed: ParsedDoc = serde_json::from_slice(&data)
+        .map_err(|e| CommandError::with_detail("E104_PARSED_INVALID", e))?;
+
+    Ok(parsed)
+}
+
+/// Mix exams - shuffle questions and options to create exam variants
+/// This replaces the frontend TypeScript implementation for better performance
+///
+/// When `job_id` is given, the permutation of every variant is persisted as
+/// `<workspace>/variants/<code>/mapping.json` and the master seed is recorded
+/// in the job manifest. Passing `master_seed` reproduces an earlier mix.
+/// Mixing again over approved or exported variants needs `force`.
+///
+/// For a job, the returned `correct_answer`s are cleared unless its answer
+/// key can be exported (`passphrase` and, before the release time,
+/// `override_passphrase`, as for `preview_mix`); the stored mappings keep them.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn mix_exams(
+    app_handle: tauri::AppHandle,
+    parsed_doc: ParsedDoc,
+    num_variants: u32,
+    custom_exam_codes: Option<Vec<String>>,
+    job_id: Option<String>,
+    master_seed: Option<crate::docx::mixer::Seed>,
+    shuffle_statements: Option<bool>,
+    force: Option<bool>,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+) -> Result<Vec<crate::docx::mixer::MixedExam>, CommandError> {
+    use crate::docx::mixer;
+    use crate::storage::{fs, manifest, paths};
+
+    check_mix_request(&parsed_doc, num_variants, custom_exam_codes.as_deref())?;
+    let job_manifest = match &job_id {
+        Some(job_id) => {
+            let mut job_manifest = manifest::load(&app_handle, job_id)?;
+            unfreeze_variants(&mut job_manifest, None, force.unwrap_or(false))?;
+            Some(job_manifest)
+        }
+        None => None,
+    };
+
+    let master_seed = master_seed.map_or_else(mixer::random_master_seed, |seed| seed.0);
+    let questions = parsed_doc.questions;
+    let mut variants = mixer::mix_exams(
+        questions.clone(),
+        &parsed_doc.groups,
+        &parsed_doc.parts,
+        num_variants as usize,
+        custom_exam_codes,
+        master_seed,
+        shuffle_statements.unwrap_or(false),
+    );
+
+    // Kiểm tra bất biến của từng mã đề trước khi lưu hoán vị
+    let violations: Vec<String> = variants
+        .iter()
+        .flat_map(|variant| {
+            crate::docx::invariants::check_variant(&questions, &parsed_doc.groups, &parsed_doc.parts, variant)
+                .into_iter()
+                .map(move |violation| format!("Đề {}: {}", variant.exam_code, violation))
+        })
+        .collect();
+    if !violations.is_empty() {
+        return Err(CommandError::with_detail(
+            "E117_MIX_INVARIANT_BROKEN",
+            format!("\n{}", violations.join("\n")),
+        ));
+    }
+
+    if let (Some(job_id), Some(mut job_manifest)) = (job_id, job_manifest) {
+        for variant in &variants {
+            let mapping_path = paths::variant_dir(&app_handle, &job_id, &variant.exam_code)?
+                .join("mapping.json");
+            fs::write_json_protected(&mapping_path, &mixer::build_variant_mapping(variant))?;
+        }
+
+        job_manifest.master_seed = Some(master_seed);
+        job_manifest.exam_codes = variants.iter().map(|v| v.exam_code.clone()).collect();
+        job_manifest.shuffle_statements = shuffle_statements.unwrap_or(false);
+        // Các mã đề mới đều là bản nháp
+        job_manifest.variant_states.clear();
+        manifest::save(&app_handle, &job_manifest)?;
+        record_event(
+            &app_handle,
+            &job_id,
+            JobEvent::Mixed { exam_codes: job_manifest.exam_codes.clone() },
+        );
+
+        let answers_hidden = check_answer_key_access(&app_handle, &job_id, passphrase.as_deref())
+            .and_then(|_| check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref()))
+            .is_err();
+        if answers_hidden {
+            for question in variants.iter_mut().flat_map(|v| v.questions.iter_mut()) {
+                question.correct_answer.clear();
+            }
+        }
+    }
+
+    Ok(variants)
+}
+
+fn check_mix_request(
+    parsed_doc: &ParsedDoc,
+    num_variants: u32,
+    custom_exam_codes: Option<&[String]>,
+) -> Result<(), CommandError> {
+    if num_variants == 0 {
+        return Err(CommandError::new("E110_INVALID_VARIANT_COUNT"));
+    }
+
+    if parsed_doc.questions.is_empty() {
+        return Err(CommandError::new("E111_NO_QUESTIONS"));
+    }
+
+    // Validate custom exam codes if provided
+    if let Some(codes) = custom_exam_codes {
+        if codes.len() != num_variants as usize {
+            return Err(CommandError::with_detail(
+                "E112_EXAM_CODE_COUNT_MISMATCH",
+                format!("{} / {}", codes.len(), num_variants),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Deserialize)]
+pub struct MixPreviewOptions {
+    #[serde(rename = "numVariants")]
+    pub num_variants: u32,
+    #[serde(rename = "customExamCodes", default)]
+    pub custom_exam_codes: Option<Vec<String>>,
+    #[serde(rename = "masterSeed", default, with = "crate::docx::mixer::optional_seed_string")]
+    pub master_seed: Option<u64>,
+    #[serde(rename = "shuffleStatements", default)]
+    pub shuffle_statements: bool,
+}
+
+#[derive(Serialize)]
+pub struct MixPreview {
+    /// Seed đã dùng: truyền lại cho `mix_exams` để trộn đúng như bản xem trước
+    #[serde(rename = "masterSeed", with = "crate::docx::mixer::seed_string")]
+    pub master_seed: u64,
+    pub variants: Vec<crate::docx::mixer::VariantPreview>,
+    /// Đáp án bị ẩn (danh sách `answers` trống) vì thiếu mật khẩu đáp án hoặc
+    /// chưa đến giờ công bố
+    #[serde(rename = "answersHidden")]
+    pub answers_hidden: bool,
+}
+
+/// Xem trước kết quả trộn đề của job (thứ tự câu và đáp án mới của từng mã
+/// đề) mà không ghi file nào, để kiểm tra cấu hình trước khi xuất. Đáp án chỉ
+/// có khi xuất được đáp án (`passphrase`, `override_passphrase` như khi xuất).
+#[tauri::command]
+fn preview_mix(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    options: MixPreviewOptions,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+) -> Result<MixPreview, CommandError> {
+    use crate::docx::mixer;
+
+    let parsed_doc = load_parsed_doc(&app_handle, &job_id)?;
+    check_mix_request(&parsed_doc, options.num_variants, options.custom_exam_codes.as_deref())?;
+
+    let master_seed = options.master_seed.unwrap_or_else(mixer::random_master_seed);
+    let variants = mixer::mix_exams(
+        parsed_doc.questions,
+        &parsed_doc.groups,
+        &parsed_doc.parts,
+        options.num_variants as usize,
+        options.custom_exam_codes,
+        master_seed,
+        options.shuffle_statements,
+    );
+    let answers_hidden = check_answer_key_access(&app_handle, &job_id, passphrase.as_deref())
+        .and_then(|_| check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref()))
+        .is_err();
+    let mut variants: Vec<_> = variants.iter().map(mixer::preview_variant).collect();
+    if answers_hidden {
+        for variant in &mut variants {
+            variant.answers.clear();
+        }
+    }
+    Ok(MixPreview {
+        master_seed,
+        variants,
+        answers_hidden,
+    })
+}
+
+/// Đọc `<workspace>/variants/<code>/mapping.json` (hoán vị câu hỏi + đáp án + seed)
+/// để công cụ kiểm tra bên ngoài có thể xác minh đáp án độc lập. Trước giờ
+/// công bố đáp án cần `override_passphrase`.
+#[tauri::command]
+fn get_variant_mapping(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    code: String,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+) -> Result<crate::docx::mixer::VariantMapping, CommandError> {
+    use crate::storage::{fs, paths};
+
+    check_answer_key_access(&app_handle, &job_id, passphrase.as_deref())?;
+    check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref())?;
+
+    let mapping_path = paths::variant_dir(&app_handle, &job_id, &code)?.join("mapping.json");
+    Ok(fs::read_json(&mapping_path)?)
+}
+
+/// Chặn thay đổi các mã đề đã duyệt/đã xuất (của lần trộn gần nhất, hoặc chỉ
+/// `code`) trừ khi có `force`; khi đó chúng trở về bản nháp vì không còn khớp
+/// với bản đã duyệt. Người gọi lưu manifest.
+fn unfreeze_variants(
+    job_manifest: &mut storage::manifest::JobManifest,
+    code: Option<&str>,
+    force: bool,
+) -> Result<(), CommandError> {
+    for c in check_frozen(job_manifest, code, force)? {
+        job_manifest.variant_states.remove(&c);
+    }
+    Ok(())
+}
+
+/// Các mã đề đã duyệt/xuất (của lần trộn gần nhất, hoặc chỉ `code`) sẽ bị
+/// thay đổi; lỗi E116 nếu có mà không có `force`.
+fn check_frozen(
+    job_manifest: &storage::manifest::JobManifest,
+    code: Option<&str>,
+    force: bool,
+) -> Result<Vec<String>, CommandError> {
+    let frozen: Vec<String> = job_manifest
+        .frozen_variants()
+        .into_iter()
+        .filter(|c| code.is_none_or(|code| c == code))
+        .collect();
+    if !frozen.is_empty() && !force {
+        return Err(CommandError::with_detail("E116_VARIANTS_FROZEN", frozen.join(", ")));
+    }
+    Ok(frozen)
+}
+
+/// Sau khi phân tích lại: các mã đề đã duyệt/xuất trở về bản nháp, chỉ khi
+/// `parsed.json` mới đã được lưu (`response.ok`). Phân tích lỗi thì giữ
+/// nguyên trạng thái duyệt. Người gọi đã kiểm tra `force` bằng `check_frozen`.
+fn unfreeze_after_analysis(
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    response: &AnalyzeDocxResponse,
+) -> Result<(), CommandError> {
+    use crate::storage::manifest;
+
+    if !response.ok {
+        return Ok(());
+    }
+    let mut job_manifest = manifest::load(app_handle, job_id)?;
+    if job_manifest.frozen_variants().is_empty() {
+        return Ok(());
+    }
+    unfreeze_variants(&mut job_manifest, None, true)?;
+    manifest::save(app_handle, &job_manifest)?;
+    Ok(())
+}
+
+/// Trạng thái duyệt của các mã đề trong lần trộn gần nhất (nháp, đã duyệt, đã xuất).
+#[tauri::command]
+fn get_variant_states(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+) -> Result<std::collections::BTreeMap<String, storage::manifest::VariantState>, CommandError> {
+    let job_manifest = storage::manifest::load(&app_handle, &job_id)?;
+    Ok(job_manifest
+        .exam_codes
+        .iter()
+        .map(|code| (code.clone(), job_manifest.variant_state(code)))
+        .collect())
+}
+
+/// Duyệt (khoá) hoặc bỏ duyệt một mã đề. Mã đề đã duyệt hoặc đã xuất không
+/// bị ghi đè khi phân tích lại, sửa đề hay trộn lại nếu không có `force`.
+#[tauri::command]
+fn set_variant_approval(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    code: String,
+    approved: bool,
+) -> Result<storage::manifest::VariantState, CommandError> {
+    use crate::storage::manifest::{self, VariantState};
+
+    let mut job_manifest = manifest::load(&app_handle, &job_id)?;
+    if !job_manifest.exam_codes.contains(&code) {
+        return Err(CommandError::with_detail("E113_VARIANT_NOT_FOUND", &code));
+    }
+    let state = match (approved, job_manifest.variant_state(&code)) {
+        // Đã xuất thì vẫn là đã xuất
+        (true, VariantState::Exported) => VariantState::Exported,
+        (true, _) => VariantState::Approved,
+        (false, _) => VariantState::Draft,
+    };
+    if state == VariantState::Draft {
+        job_manifest.variant_states.remove(&code);
+    } else {
+        job_manifest.variant_states.insert(code.clone(), state);
+    }
+    manifest::save(&app_handle, &job_manifest)?;
+
+    record_event(&app_handle, &job_id, JobEvent::VariantApproval { exam_code: code, approved });
+    Ok(state)
+}
+
+/// Đổi chỗ hai câu (số thứ tự `a`, `b` trong mã đề) của một mã đề đã trộn;
+/// mỗi câu giữ nguyên thứ tự phương án, đáp án được tính lại theo vị trí mới.
+/// Mã đề đã duyệt/xuất cần `force`; trước giờ công bố đáp án cần `override_passphrase`.
+/// Trả về mã đề sau khi sửa để giao diện thay cho bản cũ.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn swap_questions(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    code: String,
+    a: u32,
+    b: u32,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+    force: Option<bool>,
+) -> Result<crate::docx::mixer::MixedExam, CommandError> {
+    use crate::docx::mixer::{self, VariantMapping};
+    use crate::storage::{fs, manifest, paths};
+
+    // Kết quả chứa đáp án của mã đề
+    check_answer_key_access(&app_handle, &job_id, passphrase.as_deref())?;
+    check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref())?;
+    let mut job_manifest = manifest::load(&app_handle, &job_id)?;
+    if !job_manifest.exam_codes.contains(&code) {
+        return Err(CommandError::with_detail("E113_VARIANT_NOT_FOUND", &code));
+    }
+    unfreeze_variants(&mut job_manifest, Some(&code), force.unwrap_or(false))?;
+    let parsed_doc = load_parsed_doc(&app_handle, &job_id)?;
+    let mapping_path = paths::variant_dir(&app_handle, &job_id, &code)?.join("mapping.json");
+    let mapping: VariantMapping = fs::read_json(&mapping_path)?;
+
+    let mut order: Vec<u32> = mapping.questions.iter().map(|q| q.original_number).collect();
+    mixer::swap_positions(&mut order, a, b, &parsed_doc.groups, &parsed_doc.parts).map_err(CommandError::new)?;
+    let variant = mixer::arrange_variant(
+        mixer::order_questions(&parsed_doc.questions, &order),
+        &parsed_doc.groups,
+        &parsed_doc.parts,
+        &code,
+        mapping.seed,
+        job_manifest.shuffle_statements,
+    );
+    fs::write_json_protected(&mapping_path, &mixer::build_variant_mapping(&variant))?;
+    manifest::save(&app_handle, &job_manifest)?;
+
+    record_event(&app_handle, &job_id, JobEvent::QuestionsSwapped { exam_code: code, a, b });
+    Ok(variant)
+}
+
+/// Trộn lại riêng một mã đề với seed mới (các mã đề khác giữ nguyên), với
+/// cấu hình của lần trộn gần nhất. Trả về mã đề mới để giao diện thay cho bản cũ.
+/// Mã đề đã duyệt/xuất cần `force`; trước giờ công bố đáp án cần `override_passphrase`.
+#[tauri::command]
+fn regenerate_variant(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    code: String,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+    force: Option<bool>,
+) -> Result<crate::docx::mixer::MixedExam, CommandError> {
+    use crate::docx::mixer;
+    use crate::storage::{fs, manifest, paths};
+
+    check_answer_key_access(&app_handle, &job_id, passphrase.as_deref())?;
+    check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref())?;
+    let mut job_manifest = manifest::load(&app_handle, &job_id)?;
+    if !job_manifest.exam_codes.contains(&code) {
+        return Err(CommandError::with_detail("E113_VARIANT_NOT_FOUND", &code));
+    }
+    unfreeze_variants(&mut job_manifest, Some(&code), force.unwrap_or(false))?;
+    let parsed_doc = load_parsed_doc(&app_handle, &job_id)?;
+
+    // Seed riêng lưu trong mapping.json, không suy ra từ master seed nữa
+    let variant = mixer::mix_variant(
+        &parsed_doc.questions,
+        &parsed_doc.groups,
+        &parsed_doc.parts,
+        &code,
+        mixer::random_master_seed(),
+        job_manifest.shuffle_statements,
+    );
+    let mapping_path = paths::variant_dir(&app_handle, &job_id, &code)?.join("mapping.json");
+    fs::write_json_protected(&mapping_path, &mixer::build_variant_mapping(&variant))?;
+    manifest::save(&app_handle, &job_manifest)?;
+
+    record_event(&app_handle, &job_id, JobEvent::VariantRegenerated { exam_code: code });
+    Ok(variant)
+}
+
+#[derive(Serialize)]
+pub struct ReimportResponse {
+    #[serde(rename = "examCode")]
+    pub exam_code: String,
+    pub discrepancies: Vec<crate::docx::reimport::KeyDiscrepancy>,
+    /// Số đáp án đã cập nhật theo file (khi gọi với `updateKey`)
+    #[serde(rename = "answersUpdated")]
+    pub answers_updated: usize,
+}
+
+/// Đọc lại một mã đề đã xuất rồi được sửa tay trong Word, nhận lại đáp án
+/// được đánh dấu (gạch chân/tô đỏ nhãn) và so với đáp án đã lưu: báo câu đổi
+/// đáp án, chưa đánh dấu, thiếu/thêm câu hoặc phương án. Với `update_key`, đáp
+/// án đã lưu được sửa theo các nhãn đánh dấu trong file; mã đề đã duyệt/xuất
+/// cần `force` và trở về bản nháp. Trước giờ công bố đáp án cần
+/// `override_passphrase` (kết quả chứa đáp án đã lưu).
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn reimport_variant(
+    app_handle: tauri::AppHandle,
+    path: String,
+    job_id: String,
+    code: String,
+    update_key: Option<bool>,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+    force: Option<bool>,
+) -> Result<ReimportResponse, CommandError> {
+    use crate::docx::{mixer::VariantMapping, read, reimport};
+    use crate::storage::{fs, manifest, paths};
+
+    check_answer_key_access(&app_handle, &job_id, passphrase.as_deref())?;
+    check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref())?;
+    let mut job_manifest = manifest::load(&app_handle, &job_id)?;
+    if !job_manifest.exam_codes.contains(&code) {
+        return Err(CommandError::with_detail("E113_VARIANT_NOT_FOUND", &code));
+    }
+    let mapping_path = paths::variant_dir(&app_handle, &job_id, &code)?.join("mapping.json");
+    let mut mapping: VariantMapping = fs::read_json(&mapping_path)?;
+
+    let docx_path = Path::new(&path);
+    let document_xml = read::read_document_xml(docx_path).map_err(|e| match read::inspect(docx_path) {
+        Some(read::InputProblem::NotADocx(kind)) => CommandError::with_detail("E170_NOT_A_DOCX", kind),
+        Some(problem) => CommandError::new(problem.code()),
+        None => CommandError::with_detail("E100_DOCX_READ_FAILED", format!("{:?}", e)),
+    })?;
+    let discrepancies = reimport::compare(&document_xml, &mapping, &job_manifest.parse_options);
+
+    let mut answers_updated = 0;
+    if update_key.unwrap_or(false) {
+        answers_updated = reimport::apply_marked_answers(&mut mapping, &discrepancies);
+        if answers_updated > 0 {
+            // Đáp án đã duyệt/đã xuất không đổi âm thầm
+            unfreeze_variants(&mut job_manifest, Some(&code), force.unwrap_or(false))?;
+            fs::write_json_protected(&mapping_path, &mapping)?;
+            manifest::save(&app_handle, &job_manifest)?;
+        }
+    }
+    println!(
+        "[Reimport] Variant {} of job {}: {} discrepancy(ies), {} answer(s) updated",
+        code,
+        job_id,
+        discrepancies.len(),
+        answers_updated
+    );
+
+    record_event(
+        &app_handle,
+        &job_id,
+        JobEvent::VariantReimported {
+            exam_code: code.clone(),
+            discrepancy_count: discrepancies.len(),
+            answers_updated,
+        },
+    );
+    Ok(ReimportResponse { exam_code: code, discrepancies, answers_updated })
+}
+
+/// Chép đáp án của mã đề `code` vào clipboard, dạng bảng (tab) hoặc gọn
+/// "1A 2C 3D…" để dán vào Zalo/tin nhắn. Trả về nội dung đã chép.
+#[tauri::command]
+fn copy_answer_key_to_clipboard(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    code: String,
+    format: crate::docx::answer_text::AnswerTextFormat,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+) -> Result<String, CommandError> {
+    use crate::storage::{fs, paths};
+
+    check_answer_key_access(&app_handle, &job_id, passphrase.as_deref())?;
+    check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref())?;
+
+    let mapping_path = paths::variant_dir(&app_handle, &job_id, &code)?.join("mapping.json");
+    let mapping: crate::docx::mixer::VariantMapping = fs::read_json(&mapping_path)?;
+    // Câu Đúng/Sai: ghi Đ/S thay cho chữ cái
+    let symbols = load_parsed_doc(&app_handle, &job_id)
+        .map(|doc| crate::docx::answer_symbols::from_parsed_doc(&doc))
+        .unwrap_or_default();
+    let text = crate::docx::answer_text::render(&mapping, format, &symbols);
+    {
+        use tauri_plugin_clipboard_manager::ClipboardExt;
+        app_handle
+            .clipboard()
+            .write_text(text.clone())
+            .map_err(|e| CommandError::with_detail("E181_CLIPBOARD_FAILED", e.to_string()))?;
+    }
+    Ok(text)
+}
+
+/// Câu hỏi của một mã đề theo dạng `ExamWriter` cần. `original_questions`
+/// (câu của đề gốc theo số câu, khi giữ nguyên định dạng) cho các đoạn XML gốc.
+fn variant_questions(
+    exam: &crate::docx::excel::MixedExam,
+    original_questions: &std::collections::HashMap<u32, crate::docx::model::Question>,
+) -> Vec<crate::docx::model::Question> {
+    exam
+        .questions
+        .iter()
+        .map(|mq| {
+            let original = original_questions.get(&(mq.original_number as u32));
+
+            // Convert MixedOptions to OptionItems
+            let options: Vec<crate::docx::model::OptionItem> = mq.options
+                .iter()
+                .map(|opt| crate::docx::model::OptionItem {
+                    label: opt.label.clone(),
+                    locked: false, // Options are not locked in mixed exams
+                    content: opt.content.clone(),
+                    content_alt: opt.content_alt.clone(),
+                    raw_paragraphs: original
+                        .and_then(|q| q.options.iter().find(|o| o.label == opt.original_label))
+                        .map(|o| o.raw_paragraphs.clone())
+                        .unwrap_or_default(),
+                })
+                .collect();
+
+            crate::docx::model::Question {
+                number: mq.display_number as u32,
+                stem: mq.stem.clone(),
+                options,
+                correct_label: mq.correct_answer.clone(),
+                stem_alt: mq.stem_alt.clone(),
+                raw_paragraphs: original.map(|q| q.raw_paragraphs.clone()).unwrap_or_default(),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Export mixed exams to DOCX and XLSX files
+///
+/// `roster`: danh sách học sinh (xem `load_roster`); mỗi học sinh có thêm một
+/// bản đề riêng ghi họ tên, số báo danh, và bảng phân công mã đề `PhanCong_MaDe.xlsx`
+/// `rooms`: các phòng thi; có thì học sinh được xếp chỗ sao cho hai bạn ngồi
+/// cạnh nhau khác mã đề, kèm sơ đồ phòng `SoDoPhong.xlsx` và `SoDoPhong.docx`
+///
+/// Thiếu hoặc sai mật khẩu đáp án (`passphrase`, nếu job đặt), hoặc trước giờ
+/// công bố đáp án (không có `override_passphrase` đúng) thì chỉ xuất đề: bảng
+/// đáp án và bản giáo viên được giữ lại, `xlsxFile` để trống.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn export_mixed_exams(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    mut exams: Vec<crate::docx::excel::MixedExam>,
+    output_dir: String,
+    options: Option<crate::docx::export_options::ExportOptions>,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+    roster: Option<Vec<crate::roster::Student>>,
+    rooms: Option<Vec<crate::roster::seating::Room>>,
+) -> Result<ExportResponse, CommandError> {
+    use crate::storage::{audit, export_manifest, paths};
+    use crate::storage::export_progress::{self, ExportProgress};
+    use crate::docx::writer::ExamWriter;
+    use crate::docx::{excel, file_names, verify};
+    use std::path::PathBuf;
+
+    // Đề của học sinh luôn được xuất; bảng đáp án và bản giáo viên chỉ khi có
+    // mật khẩu đáp án đúng (nếu job đặt) và đã đến giờ công bố
+    let key_unlocked = check_answer_key_access(&app_handle, &job_id, passphrase.as_deref()).is_ok();
+    let key_released = key_unlocked
+        && check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref()).is_ok();
+
+    let workspace_dir = paths::job_workspace_dir(&app_handle, &job_id)?;
+    let assets_dir = workspace_dir.join("assets");
+    let output_path = PathBuf::from(&output_dir);
+    let mut options = options.unwrap_or_default();
+    let settings = storage::settings::load(&app_handle).ok();
+    // Lời văn đầu đề: theo tuỳ chọn xuất, không có thì theo môn trong cài đặt
+    if options.lexicon.is_none() {
+        options.lexicon = settings
+            .as_ref()
+            .and_then(|settings| storage::settings::subject_lexicon(settings, &options.subject));
+    }
+    // Mẫu đề (Kiểm tra 15 phút, Giữa kỳ...): điền các tuỳ chọn còn để mặc định
+    if let Some(name) = options.preset.clone() {
+        let presets = settings.as_ref().map_or(&[][..], |settings| &settings.exam_presets[..]);
+        crate::docx::exam_preset::find(presets, &name)
+            .ok_or_else(|| CommandError::with_detail("E133_EXAM_PRESET_NOT_FOUND", name))?
+            .apply(&mut options);
+    }
+
+    // Đáp án lấy từ hoán vị đã lưu (mapping.json) và đáp án gốc trong
+    // parsed.json, không lấy từ giao diện; kiểm tra trước khi ghi file nào
+    let mut mappings = std::collections::HashMap::new();
+    for exam in &exams {
+        let mapping_path =
+            paths::variant_dir(&app_handle, &job_id, &exam.exam_code)?.join("mapping.json");
+        if mapping_path.exists() {
+            mappings.insert(exam.exam_code.clone(), crate::storage::fs::read_json(&mapping_path)?);
+        }
+    }
+    let original_answers: Vec<String> = load_parsed_doc(&app_handle, &job_id)?
+        .questions
+        .iter()
+        .map(|q| q.correct_label.clone())
+        .collect();
+    let mut problems = verify::apply_stored_answers(&mut exams, &mappings);
+    if problems.is_empty() {
+        problems.extend(
+            verify::verify_answer_keys(&exams, &original_answers, &mappings)
+                .iter()
+                .map(|m| m.to_string()),
+        );
+    }
+    if !problems.is_empty() {
+        return Err(CommandError::with_detail(
+            "E130_ANSWER_KEY_MISMATCH",
+            format!("\n{}", problems.join("\n")),
+        ));
+    }
+    if let Some(layout) = options.answer_sheet {
+        crate::docx::answer_sheet::check(layout, &exams)
+            .map_err(|detail| CommandError::with_detail("E137_ANSWER_SHEET_MISMATCH", detail))?;
+    }
+
+    // Chế độ giữ nguyên XML gốc: lấy các đoạn <w:p> gốc từ parsed.json
+    let original_questions: std::collections::HashMap<u32, crate::docx::model::Question> = if options.raw_passthrough {
+        let parsed_path = paths::job_workspace_dir(&app_handle, &job_id)?.join("parsed.json");
+        let parsed: ParsedDoc = crate::storage::fs::read_json(&parsed_path)?;
+        parsed.questions.into_iter().map(|q| (q.number, q)).collect()
+    } else {
+        std::collections::HashMap::new()
+    };
+
+    // Bản in: chuyển lại ảnh WMF/EMF ở độ phân giải cao hơn
+    let print_assets: Vec<_> = match options.image_dpi {
+        Some(dpi) => {
+            let cache_dir = paths::conversion_cache_dir(&app_handle).ok();
+            crate::docx::assets::convert_for_print(&workspace_dir, dpi, cache_dir.as_deref()).await
+        }
+        None => Vec::new(),
+    };
+
+    let mut docx_files = Vec::new();
+
+    // Lần xuất trước bị gián đoạn: bỏ qua các đề đã ghi xong và còn nguyên vẹn
+    let mut progress = ExportProgress::load(&workspace_dir);
+
+    // Tên file theo mẫu của người dùng ({subject}, {grade}, {code}, {date}, {variant_index})
+    let name_context = file_names::FileNameContext::new(&options.subject, &options.grade);
+
+    // Phần đầu đề: tên trường bỏ trống thì lấy theo giấy phép của trường
+    let school_name = match options.school_name.as_ref().filter(|name| !name.trim().is_empty()) {
+        Some(name) => name.trim().to_string(),
+        None => storage::license::status(&app_handle)
+            .ok()
+            .and_then(|status| status.license)
+            .map(|license| license.school_name)
+            .unwrap_or_default(),
+    };
+    let academic_year = options.academic_year_on(chrono::Local::now().date_naive());
+
+    // Bản cá nhân: chia mã đề theo chỗ ngồi nếu có phòng thi, không thì theo thứ tự danh sách
+    let exam_codes: Vec<String> = exams.iter().map(|e| e.exam_code.clone()).collect();
+    let students = roster.as_deref().unwrap_or_default();
+    let mut assignments = match rooms.as_deref() {
+        Some(rooms) if !students.is_empty() => crate::roster::seating::seat(students, rooms, &exam_codes)
+            .map_err(|detail| CommandError::with_detail("E136_SEATING_IMPOSSIBLE", detail))?,
+        _ => crate::roster::assignment::assign(students, &exam_codes),
+    };
+
+    // Generate DOCX for each exam variant
+    for (index, exam) in exams.iter().enumerate() {
+        let stem = file_names::variant_stem(
+            &options.file_name_template,
+            &name_context.for_variant(&exam.exam_code, index + 1),
+        );
+        for (position, assignment) in assignments.iter_mut().enumerate() {
+            if assignment.exam_code == exam.exam_code {
+                let copy_stem = crate::roster::assignment::copy_stem(&stem, &assignment.student, position);
+                assignment.file_name = format!("{}.docx", copy_stem);
+            }
+        }
+        let personal: Vec<_> = assignments.iter().filter(|a| a.exam_code == exam.exam_code).collect();
+        let fingerprint = export_progress::fingerprint(&(exam, &options, &output_dir, &stem, &personal, key_released));
+        if let Some(files) = progress.completed_files(&exam.exam_code, &fingerprint, &output_path) {
+            println!("[Export] Variant {} already written, skipping", exam.exam_code);
+            docx_files.extend(files);
+            continue;
+        }
+        let first_file = docx_files.len();
+
+        let mut questions = variant_questions(exam, &original_questions);
+        if !print_assets.is_empty() {
+            for question in &mut questions {
+                remap_image_paths(question, |path| {
+                    let name = Path::new(path).file_name()?;
+                    print_assets
+                        .iter()
+                        .find(|job| job.target.file_name() == Some(name))
+                        .map(|job| job.target.to_string_lossy().into_owned())
+                });
+            }
+        }
+
+        let mut writer = ExamWriter {
+            exam_code: exam.exam_code.clone(),
+            questions,
+            exam_title: "ĐỀ THI GIỮA KỲ I".to_string(),
+            subject: options.subject.clone(),
+            duration_minutes: options.duration_minutes.unwrap_or(90),
+            assets_dir: assets_dir.clone(),
+            school_name: school_name.clone(),
+            exam_name: options.exam_name.clone().unwrap_or_else(|| "KIỂM TRA GIỮA HKII".to_string()),
+            academic_year: academic_year.clone(),
+            grade: options.grade.clone(),
+            options: options.clone(),
+            large_print: false,
+            groups: exam.groups.clone(),
+            parts: exam.parts.clone(),
+            teacher_mark: None,
+            student: None,
+        };
+
+        let filename = format!("{}.docx", stem);
+        let file_path = output_path.join(&filename);
+
+        writer
+            .write_to_file(&file_path)
+            .map_err(|e| CommandError::with_detail("E131_DOCX_WRITE_FAILED", format!("{}: {:?}", filename, e)))?;
+
+        docx_files.push(filename);
+
+        // Bản chữ lớn cho học sinh khiếm thị: cùng câu hỏi, luôn in A4 thường
+        if options.large_print_copy {
+            writer.large_print = true;
+            writer.options.print_layout = crate::docx::export_options::PrintLayout::Standard;
+
+            let filename = format!("{}_ChuLon.docx", stem);
+            writer
+                .write_to_file(&output_path.join(&filename))
+                .map_err(|e| CommandError::with_detail("E131_DOCX_WRITE_FAILED", format!("{}: {:?}", filename, e)))?;
+
+            docx_files.push(filename);
+        }
+
+        // Bản giáo viên: đánh dấu đáp án đúng theo quy ước của trường
+        if let Some(style) = options.teacher_copy.filter(|_| key_released) {
+            writer.large_print = false;
+            writer.options = options.clone();
+            writer.teacher_mark = Some(style);
+
+            let filename = format!("{}_GiaoVien.docx", stem);
+            writer
+                .write_to_file(&output_path.join(&filename))
+                .map_err(|e| CommandError::with_detail("E131_DOCX_WRITE_FAILED", format!("{}: {:?}", filename, e)))?;
+
+            docx_files.push(filename);
+        }
+
+        // Bản cá nhân: họ tên, số báo danh, phòng thi dưới phần đầu đề
+        writer.large_print = false;
+        writer.options = options.clone();
+        writer.teacher_mark = None;
+        for assignment in &personal {
+            writer.student = Some(assignment.student.clone());
+            writer.write_to_file(&output_path.join(&assignment.file_name)).map_err(|e| {
+                CommandError::with_detail("E131_DOCX_WRITE_FAILED", format!("{}: {:?}", assignment.file_name, e))
+            })?;
+            docx_files.push(assignment.file_name.clone());
+        }
+
+        progress.record(&workspace_dir, &exam.exam_code, fingerprint, &output_path, &docx_files[first_file..])?;
+    }
+
+    let mut written: Vec<PathBuf> = docx_files.iter().map(|f| output_path.join(f)).collect();
+
+    // Generate XLSX answer key
+    let xlsx_filename = if key_released {
+        let xlsx_filename = format!(
+            "{}.xlsx",
+            file_names::render(&options.answer_key_file_name_template, &name_context)
+        );
+        let xlsx_path = output_path.join(&xlsx_filename);
+        excel::write_answer_key(&exams, &original_answers, &xlsx_path, &options)
+            .map_err(|e| CommandError::with_detail("E132_XLSX_WRITE_FAILED", format!("{:?}", e)))?;
+        written.push(xlsx_path);
+        xlsx_filename
+    } else {
+        String::new()
+    };
+
+    // Bảng phân công mã đề, giữ thêm trong workspace để đối chiếu khi chấm
+    if !assignments.is_empty() {
+        use crate::roster::assignment::{self, ASSIGNMENTS_FILE, ASSIGNMENT_FILE_NAME};
+        let assignment_path = output_path.join(ASSIGNMENT_FILE_NAME);
+        assignment::write_xlsx(&assignments, &assignment_path).map_err(|e| {
+            CommandError::with_detail("E132_XLSX_WRITE_FAILED", format!("{}: {:?}", ASSIGNMENT_FILE_NAME, e))
+        })?;
+        storage::fs::write_json(&workspace_dir.join(ASSIGNMENTS_FILE), &assignments)?;
+        written.push(assignment_path);
+    }
+
+    // Sơ đồ phòng thi cho giám thị
+    if let Some(rooms) = rooms.as_deref().filter(|_| assignments.iter().any(|a| a.seat.is_some())) {
+        use crate::roster::room_map::{self, ROOM_MAP_FILE_STEM};
+        let xlsx_path = output_path.join(format!("{}.xlsx", ROOM_MAP_FILE_STEM));
+        room_map::write_xlsx(rooms, &assignments, &xlsx_path)
+            .map_err(|e| CommandError::with_detail("E132_XLSX_WRITE_FAILED", format!("{}: {:?}", ROOM_MAP_FILE_STEM, e)))?;
+        let docx_path = output_path.join(format!("{}.docx", ROOM_MAP_FILE_STEM));
+        room_map::write_docx(rooms, &assignments, &docx_path).map_err(|e| {
+            CommandError::with_detail("E131_DOCX_WRITE_FAILED", format!("{}: {}", docx_path.display(), e))
+        })?;
+        written.extend([xlsx_path, docx_path]);
+    }
+
+    // Phiếu trả lời trắc nghiệm cho máy chấm, chung cho mọi mã đề
+    if let Some(layout) = options.answer_sheet {
+        use crate::docx::answer_sheet::{self, ANSWER_SHEET_FILE_NAME};
+        let sheet_path = output_path.join(ANSWER_SHEET_FILE_NAME);
+        answer_sheet::write(layout, &options, &sheet_path).map_err(|e| {
+            CommandError::with_detail("E131_DOCX_WRITE_FAILED", format!("{}: {}", ANSWER_SHEET_FILE_NAME, e))
+        })?;
+        written.push(sheet_path);
+    }
+
+    // Bài nghe nhúng trong đề: chép kèm, cùng thứ tự phát của từng mã đề
+    let audio_files = crate::docx::listening::copy_audio(&exams, &output_path)?;
+    if !audio_files.is_empty() {
+        let scripts = crate::docx::listening::write_scripts(&exams, &output_path, &options)?;
+        written.extend(audio_files.iter().chain(&scripts).map(|f| output_path.join(f)));
+    }
+    record_audit(&app_handle, &job_id, audit::AuditAction::ExportExams, &exams, &written)?;
+
+    // Mã đề đã xuất không bị ghi đè nếu không xác nhận (xem `set_variant_approval`)
+    let mut job_manifest = storage::manifest::load(&app_handle, &job_id)?;
+    for exam in &exams {
+        if job_manifest.exam_codes.contains(&exam.exam_code) {
+            job_manifest
+                .variant_states
+                .insert(exam.exam_code.clone(), storage::manifest::VariantState::Exported);
+        }
+    }
+    storage::manifest::save(&app_handle, &job_manifest)?;
+
+    // Tóm tắt lần xuất (export_manifest.json) trong thư mục xuất và workspace
+    let parsed = load_parsed_doc(&app_handle, &job_id).ok();
+    let export_manifest = export_manifest::build(
+        &job_id,
+        &crate::storage::settings::current_author(&app_handle),
+        &output_path,
+        &exams,
+        parsed.as_ref(),
+        &written,
+        &options,
+    )?;
+    export_manifest::save(&app_handle, &export_manifest)?;
+    ExportProgress::clear(&workspace_dir);
+
+    // Lịch sử sử dụng trong ngân hàng câu hỏi: lỗi ở đây không làm hỏng bản xuất
+    if let Err(e) = record_bank_usage(&app_handle, &job_id, &exams) {
+        eprintln!("[Bank] Failed to record usage for job {}: {}", job_id, e);
+    }
+    record_event(
+        &app_handle,
+        &job_id,
+        JobEvent::Exported {
+            exam_codes: exams.iter().map(|e| e.exam_code.clone()).collect(),
+            output_directory: output_dir.clone(),
+        },
+    );
+
+    Ok(ExportResponse {
+        success: true,
+        message: i18n::message(match (key_unlocked, key_released) {
+            (_, true) => "S003_EXPORT_OK",
+            (false, _) => "S006_EXPORT_KEY_LOCKED",
+            (true, false) => "S005_EXPORT_KEY_WITHHELD",
+        }),
+        docx_files,
+        xlsx_file: xlsx_filename,
+        output_directory: output_dir,
+    })
+}
+
+/// Chỉ xuất bảng đáp án (XLSX) của các mã đề vào `output_path`. Các mã đề
+/// và đáp án lấy từ workspace (mapping.json của lần trộn gần nhất), không
+/// lấy từ giao diện. Trước giờ công bố đáp án (nếu có hẹn giờ) cần
+/// `override_passphrase`.
+#[tauri::command]
+fn export_answer_key(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    output_path: String,
+    options: Option<crate::docx::export_options::ExportOptions>,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+) -> Result<(), CommandError> {
+    check_answer_key_access(&app_handle, &job_id, passphrase.as_deref())?;
+    check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref())?;
+
+    let job_manifest = storage::manifest::load(&app_handle, &job_id)?;
+    let parsed_doc = load_parsed_doc(&app_handle, &job_id)?;
+    let mut exams = Vec::with_capacity(job_manifest.exam_codes.len());
+    for code in &job_manifest.exam_codes {
+        let mapping_path = storage::paths::variant_dir(&app_handle, &job_id, code)?.join("mapping.json");
+        if !mapping_path.exists() {
+            return Err(CommandError::with_detail("E113_VARIANT_NOT_FOUND", code));
+        }
+        let mapping: crate::docx::mixer::VariantMapping = storage::fs::read_json(&mapping_path)?;
+        exams.push(crate::docx::excel::MixedExam::from_mapping(&mapping, &parsed_doc.questions));
+    }
+    let original_answers: Vec<String> = parsed_doc.questions.iter().map(|q| q.correct_label.clone()).collect();
+
+    let output_path = std::path::PathBuf::from(output_path);
+    crate::docx::excel::write_answer_key(
+        &exams,
+        &original_answers,
+        &output_path,
+        &options.unwrap_or_default(),
+    )
+    .map_err(|e| CommandError::with_detail("E132_XLSX_WRITE_FAILED", format!("{:?}", e)))?;
+
+    record_audit(
+        &app_handle,
+        &job_id,
+        storage::audit::AuditAction::ExportAnswerKey,
+        &exams,
+        std::slice::from_ref(&output_path),
+    )?;
+    record_event(
+        &app_handle,
+        &job_id,
+        JobEvent::AnswerKeyExported { path: output_path.to_string_lossy().into_owned() },
+    );
+    Ok(())
+}
+
+/// Xuất kịch bản đọc (TTS) cho từng mã đề: `<tên file>_Doc.txt` hoặc `.ssml`,
+/// tên file theo mẫu trong tuỳ chọn xuất (mặc định `De_<mã>`).
+/// Kịch bản không chứa đáp án. Trả về tên các file đã ghi.
+#[tauri::command]
+fn export_speech_scripts(
+    exams: Vec<crate::docx::excel::MixedExam>,
+    output_dir: String,
+    format: crate::docx::speech::ScriptFormat,
+    options: Option<crate::docx::export_options::ExportOptions>,
+) -> Result<Vec<String>, CommandError> {
+    let options = options.unwrap_or_default();
+    Ok(crate::docx::speech::write_scripts(&exams, Path::new(&output_dir), &options, format)?)
+}
+
+/// Xuất kịch bản phát bài nghe cho từng mã đề: `<tên file>_BaiNghe.txt`
+/// liệt kê các bài nghe theo thứ tự câu trong đề đó, kèm số câu của mã đề.
+/// Trả về tên các file đã ghi.
+#[tauri::command]
+fn export_listening_scripts(
+    exams: Vec<crate::docx::excel::MixedExam>,
+    output_dir: String,
+    options: Option<crate::docx::export_options::ExportOptions>,
+) -> Result<Vec<String>, CommandError> {
+    let options = options.unwrap_or_default();
+    let files = crate::docx::listening::write_scripts(&exams, Path::new(&output_dir), &options)?;
+    if files.is_empty() {
+        return Err(CommandError::new("E134_NO_TRACK_CUES"));
+    }
+    Ok(files)
+}
+
+/// Đọc danh sách học sinh từ tệp CSV hoặc phân tách bằng tab (Excel "Lưu
+/// dưới dạng CSV"), cột Họ và tên, Số báo danh, Phòng thi (xem `roster`).
+/// Danh sách truyền cho `export_mixed_exams` để xuất bản đề cá nhân.
+#[tauri::command]
+fn load_roster(path: String) -> Result<Vec<crate::roster::Student>, CommandError> {
+    let data = std::fs::read(&path)
+        .map_err(|e| CommandError::with_detail("E135_ROSTER_INVALID", format!("{}: {}", path, e)))?;
+    crate::roster::parse(&String::from_utf8_lossy(&data))
+        .map_err(|detail| CommandError::with_detail("E135_ROSTER_INVALID", detail))
+}
+
+#[derive(Deserialize)]
+pub struct RunExporterPayload {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    #[serde(rename = "exporterId")]
+    pub exporter_id: String,
+    pub exams: Vec<crate::docx::excel::MixedExam>,
+    #[serde(rename = "originalAnswers", default)]
+    pub original_answers: Vec<String>,
+    #[serde(rename = "outputDir")]
+    pub output_dir: String,
+    #[serde(default)]
+    pub options: Option<crate::docx::export_options::ExportOptions>,
+    #[serde(default)]
+    pub passphrase: Option<String>,
+    #[serde(rename = "overridePassphrase", default)]
+    pub override_passphrase: Option<String>,
+}
+
+/// Các định dạng xuất bổ sung: có sẵn trong ứng dụng và tiện ích cài trong
+/// `<app_data>/SiroMix/exporters`.
+#[tauri::command]
+fn list_exporters(app_handle: tauri::AppHandle) -> Result<Vec<exporters::ExporterInfo>, CommandError> {
+    Ok(exporters::Registry::load(&storage::paths::exporters_dir(&app_handle)?).infos())
+}
+
+/// Xuất các mã đề bằng một định dạng bổ sung; trả về đường dẫn các file đã ghi.
+#[tauri::command]
+async fn run_exporter(
+    app_handle: tauri::AppHandle,
+    mut payload: RunExporterPayload,
+) -> Result<Vec<String>, CommandError> {
+    let registry = exporters::Registry::load(&storage::paths::exporters_dir(&app_handle)?);
+    let info = registry
+        .get(&payload.exporter_id)
+        .ok_or_else(|| CommandError::with_detail("E210_EXPORTER_NOT_FOUND", &payload.exporter_id))?
+        .info()
+        .clone();
+
+    // Giấy phép theo mã định dạng, không theo khai báo của tiện ích
+    if let Some(feature) = exporters::required_feature(&info.id) {
+        if !storage::license::status(&app_handle)?.allows(feature) {
+            return Err(CommandError::with_detail("E203_LICENSE_REQUIRED", &info.name));
+        }
+    }
+    if info.includes_answers {
+        check_answer_key_access(&app_handle, &payload.job_id, payload.passphrase.as_deref())?;
+        check_answer_key_release(&app_handle, &payload.job_id, payload.override_passphrase.as_deref())?;
+    } else {
+        // Không qua kiểm tra đáp án: không gửi đáp án cho trình xuất
+        exporters::strip_answers(&mut payload.exams, &mut payload.original_answers);
+    }
+
+    let mut options = payload.options.clone().unwrap_or_default();
+    // Trình xuất luôn dựng trang chuẩn; tệp PDF được dàn trang sau (sổ gấp, 2 bản/tờ)
+    let print_layout = std::mem::take(&mut options.print_layout);
+    let output_dir = std::path::PathBuf::from(&payload.output_dir);
+    storage::fs::ensure_dir(&output_dir)?;
+    let files = tokio::task::spawn_blocking(move || {
+        let request = exporters::ExportRequest {
+            job_id: &payload.job_id,
+            exams: &payload.exams,
+            original_answers: &payload.original_answers,
+            output_dir: &output_dir,
+            options: &options,
+        };
+        let files = registry.get(&payload.exporter_id).map(|exporter| -> Result<_, String> {
+            let files = exporter.export(&request)?;
+            for file in files.iter().filter(|f| f.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf"))) {
+                exporters::imposition::impose(file, print_layout)?;
+            }
+            Ok(files)
+        });
+        (payload, files)
+    })
+    .await
+    .map_err(|e| CommandError::with_detail("E211_EXPORTER_FAILED", e))?;
+    let (payload, files) = files;
+    let files = files
+        .ok_or_else(|| CommandError::with_detail("E210_EXPORTER_NOT_FOUND", &payload.exporter_id))?
+        .map_err(|e| CommandError::with_detail("E211_EXPORTER_FAILED", e))?;
+
+    if info.includes_answers {
+        record_audit(&app_handle, &payload.job_id, storage::audit::AuditAction::CustomExport, &payload.exams, &files)?;
+    }
+    record_event(
+        &app_handle,
+        &payload.job_id,
+        JobEvent::CustomExported { exporter_id: payload.exporter_id.clone(), file_count: files.len() },
+    );
+    Ok(files.iter().map(|f| f.to_string_lossy().into_owned()).collect())
+}
+
+/// Ghi một dòng vào nhật ký xuất đề của job (ai, khi nào, mã đề, tệp + SHA-256).
+fn record_audit(
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    action: storage::audit::AuditAction,
+    exams: &[crate::docx::excel::MixedExam],
+    files: &[std::path::PathBuf],
+) -> Result<(), CommandError> {
+    let variant_codes = exams.iter().map(|e| e.exam_code.clone()).collect();
+    storage::audit::append(
+        &storage::audit::audit_path(app_handle, job_id)?,
+        action,
+        &storage::settings::current_author(app_handle),
+        variant_codes,
+        files,
+    )?;
+    Ok(())
+}
+
+/// Ghi một sự kiện vào lịch sử của job và gửi ngay cho giao diện
+/// (`job-timeline`). Lịch sử chỉ để tham khảo: lỗi ở đây không làm hỏng thao tác.
+fn record_event(app_handle: &tauri::AppHandle, job_id: &str, event: JobEvent) {
+    use tauri::Emitter;
+
+    match storage::timeline::record(app_handle, job_id, event) {
+        Ok(entry) => {
+            if let Err(e) = app_handle.emit(storage::timeline::TIMELINE_EVENT, entry) {
+                eprintln!("[Timeline] Failed to emit event for job {}: {}", job_id, e);
+            }
+        }
+        Err(e) => eprintln!("[Timeline] Failed to record event for job {}: {}", job_id, e),
+    }
+}
+
+fn record_analyzed(app_handle: &tauri::AppHandle, job_id: &str, response: &AnalyzeDocxResponse) {
+    record_event(
+        app_handle,
+        job_id,
+        JobEvent::Analyzed {
+            ok: response.ok,
+            error_count: response.errors.as_ref().map_or(0, Vec::len),
+            warning_count: response.warnings.as_ref().map_or(0, Vec::len),
+        },
+    );
+}
+
+/// Lịch sử thao tác trên một job (phân tích, sửa, trộn, xuất), cũ trước mới sau.
+#[tauri::command]
+fn get_job_timeline(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+) -> Result<Vec<storage::timeline::TimelineEntry>, CommandError> {
+    Ok(storage::timeline::load(&storage::timeline::timeline_path(&app_handle, &job_id)?)?)
+}
+
+/// Bản tóm tắt lần xuất gần nhất của một job (mã đề, phân bố đáp án, file
+/// đã ghi và checksum, tuỳ chọn xuất); `None` nếu job chưa xuất lần nào.
+#[tauri::command]
+fn get_export_manifest(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+) -> Result<Option<storage::export_manifest::ExportManifest>, CommandError> {
+    Ok(storage::export_manifest::load(&app_handle, &job_id)?)
+}
+
+/// Mở thư mục chứa các file của lần xuất gần nhất; workspace của job nếu
+/// job chưa xuất lần nào.
+#[tauri::command]
+fn open_output_folder(app_handle: tauri::AppHandle, job_id: String) -> Result<(), CommandError> {
+    let dir = match storage::export_manifest::load(&app_handle, &job_id)? {
+        Some(manifest) => std::path::PathBuf::from(manifest.output_directory),
+        None => storage::paths::job_workspace_dir(&app_handle, &job_id)?,
+    };
+    let dir = shell::within(&dir, &job_folders(&app_handle, &job_id)?)?;
+    shell::open_folder(&dir).map_err(|e| CommandError::with_detail("E180_OPEN_FAILED", e))
+}
+
+/// Các thư mục giao diện được mở của một job: workspace và thư mục của lần
+/// xuất gần nhất
+fn job_folders(app_handle: &tauri::AppHandle, job_id: &str) -> Result<Vec<std::path::PathBuf>, CommandError> {
+    let mut folders = vec![storage::paths::job_workspace_dir(app_handle, job_id)?];
+    if let Some(manifest) = storage::export_manifest::load(app_handle, job_id)? {
+        folders.push(std::path::PathBuf::from(manifest.output_directory));
+    }
+    Ok(folders)
+}
+
+/// Tạo gói gửi đồng nghiệp từ lần xuất gần nhất: file nén các đề + đáp án
+/// và thư ngỏ (môn, tên kỳ thi, mã đề). Với `open_mail_client`, mở trình
+/// email mặc định với tiêu đề và thư ngỏ, và mở thư mục chứa file nén để
+/// đính kèm (mailto không gửi kèm được tệp).
+#[tauri::command]
+fn create_share_bundle(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    exam_name: String,
+    open_mail_client: bool,
+    passphrase: Option<String>,
+    override_passphrase: Option<String>,
+) -> Result<storage::share_bundle::ShareBundle, CommandError> {
+    // Gói gửi có kèm đáp án
+    check_answer_key_access(&app_handle, &job_id, passphrase.as_deref())?;
+    check_answer_key_release(&app_handle, &job_id, override_passphrase.as_deref())?;
+
+    let manifest = storage::export_manifest::load(&app_handle, &job_id)?
+        .ok_or_else(|| CommandError::new("E182_NOTHING_EXPORTED"))?;
+    let bundle = storage::share_bundle::build(&manifest, &exam_name)?;
+
+    storage::audit::append(
+        &storage::audit::audit_path(&app_handle, &job_id)?,
+        storage::audit::AuditAction::ShareBundle,
+        &storage::settings::current_author(&app_handle),
+        manifest.variants.iter().map(|v| v.exam_code.clone()).collect(),
+        &[std::path::PathBuf::from(&bundle.zip_path)],
+    )?;
+    record_event(&app_handle, &job_id, JobEvent::ShareBundleCreated { path: bundle.zip_path.clone() });
+
+    if open_mail_client {
+        shell::open_url(&bundle.mailto).map_err(|e| CommandError::with_detail("E180_OPEN_FAILED", e))?;
+        shell::reveal_file(Path::new(&bundle.zip_path))
+            .map_err(|e| CommandError::with_detail("E180_OPEN_FAILED", e))?;
+    }
+    Ok(bundle)
+}
+
+/// Mở thư mục chứa `path` và chọn sẵn file đó. Chỉ nhận file trong
+/// workspace hoặc thư mục xuất gần nhất của job (E183_PATH_NOT_ALLOWED).
+#[tauri::command]
+fn reveal_file(app_handle: tauri::AppHandle, job_id: String, path: String) -> Result<(), CommandError> {
+    let path = shell::within(Path::new(&path), &job_folders(&app_handle, &job_id)?)?;
+    shell::reveal_file(&path).map_err(|e| CommandError::with_detail("E180_OPEN_FAILED", e))
+}
+
+/// Nhật ký xuất đề của một job, cũ nhất trước; `intact = false` nếu nhật ký
+/// đã bị sửa hoặc xoá bớt dòng.
+#[tauri::command]
+fn get_audit_log(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+) -> Result<storage::audit::AuditLog, CommandError> {
+    Ok(storage::audit::load(&storage::audit::audit_path(&app_handle, &job_id)?)?)
+}
+
+/// Đặt, đổi hoặc bỏ (`passphrase = None`) mật khẩu bảo vệ đáp án của một
+/// job. Nếu đã có mật khẩu thì phải nhập đúng `current`.
+#[tauri::command]
+fn set_answer_key_passphrase(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    passphrase: Option<String>,
+    current: Option<String>,
+) -> Result<(), CommandError> {
+    use crate::storage::{crypto::PassphraseHash, manifest};
+
+    check_answer_key_access(&app_handle, &job_id, current.as_deref())?;
+
+    let mut job_manifest = manifest::load(&app_handle, &job_id)?;
+    job_manifest.answer_key_passphrase = passphrase
+        .filter(|p| !p.is_empty())
+        .map(|p| PassphraseHash::new(&p));
+    manifest::save(&app_handle, &job_manifest)?;
+    Ok(())
+}
+
+/// Hẹn giờ công bố đáp án: trước `unlock_at` (Unix, giây) không xuất được
+/// đáp án, trừ khi nhập mật khẩu mở sớm `override_passphrase`.
+/// `unlock_at = None` bỏ hẹn giờ. Cần mật khẩu đáp án (`current`) nếu có.
+#[tauri::command]
+fn set_answer_key_release(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    unlock_at: Option<u64>,
+    override_passphrase: Option<String>,
+    current: Option<String>,
+) -> Result<(), CommandError> {
+    use crate::storage::{crypto::PassphraseHash, manifest};
+
+    check_answer_key_access(&app_handle, &job_id, current.as_deref())?;
+
+    let mut job_manifest = manifest::load(&app_handle, &job_id)?;
+    job_manifest.answer_key_unlock_at = unlock_at;
+    job_manifest.answer_key_override = override_passphrase
+        .filter(|p| !p.is_empty() && unlock_at.is_some())
+        .map(|p| PassphraseHash::new(&p));
+    manifest::save(&app_handle, &job_manifest)?;
+    Ok(())
+}
+
+/// Lỗi E154 nếu chưa đến giờ công bố đáp án của job và không có mật khẩu
+/// mở sớm đúng.
+fn check_answer_key_release(
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    override_passphrase: Option<&str>,
+) -> Result<(), CommandError> {
+    let job_manifest = crate::storage::manifest::load(app_handle, job_id)?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    job_manifest
+        .answer_key_released(now, override_passphrase)
+        .map_err(|unlock_at| {
+            CommandError::with_detail(
+                "E154_ANSWER_KEY_NOT_RELEASED",
+                crate::docx::file_names::format_timestamp(unlock_at),
+            )
+        })
+}
+
+/// Lỗi E153 nếu đáp án của job được bảo vệ mà `passphrase` thiếu hoặc sai.
+fn check_answer_key_access(
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    passphrase: Option<&str>,
+) -> Result<(), CommandError> {
+    let job_manifest = crate::storage::manifest::load(app_handle, job_id)?;
+    match job_manifest.answer_key_passphrase {
+        Some(hash) if !passphrase.is_some_and(|p| hash.verify(p)) => {
+            Err(CommandError::new("E153_ANSWER_KEY_LOCKED"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Ghi nhận các câu của job vừa xuất vào lịch sử sử dụng của ngân hàng.
+fn record_bank_usage(
+    app_handle: &tauri::AppHandle,
+    job_id: &str,
+    exams: &[crate::docx::excel::MixedExam],
+) -> Result<(), CommandError> {
+    let parsed = load_parsed_doc(app_handle, job_id)?;
+    let used: Vec<(crate::docx::model::Question, Vec<String>)> = parsed
+        .questions
+        .into_iter()
+        .filter_map(|question| {
+            let codes: Vec<String> = exams
+                .iter()
+                .filter(|exam| {
+                    exam.questions
+                        .iter()
+                        .any(|mq| mq.original_number as u32 == question.number)
+                })
+                .map(|exam| exam.exam_code.clone())
+                .collect();
+            (!codes.is_empty()).then_some((question, codes))
+        })
+        .collect();
+
+    let dir = bank::store::bank_dir(app_handle)?;
+    // Không khoá (và không tạo) ngân hàng khi không có gì để ghi
+    if used.is_empty() || bank::store::load_from(&dir)?.questions.is_empty() {
+        return Ok(());
+    }
+    bank::store::update(&dir, &storage::settings::current_author(app_handle), |question_bank| {
+        bank::usage::record_export(question_bank, job_id, &used);
+        Ok(())
+    })
+}
+
+/// Số câu và điểm của đề theo chủ đề và mức độ nhận thức, so với ma trận
+/// `matrix`. Chủ đề và mức độ lấy từ tag của câu trong ngân hàng và chương
+/// của câu; câu không ghi điểm được tính `points_per_question` (mặc định 0,25).
+#[tauri::command]
+fn get_coverage_report(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    matrix: Option<bank::coverage::TargetMatrix>,
+    points_per_question: Option<f64>,
+) -> Result<bank::coverage::CoverageReport, CommandError> {
+    let parsed = load_parsed_doc(&app_handle, &job_id)?;
+    let question_bank = bank::store::load(&app_handle)?;
+    let tags = bank::coverage::job_tags(&question_bank.questions, &job_id, &parsed.questions);
+    Ok(bank::coverage::report(
+        &parsed.questions,
+        &tags,
+        &matrix.unwrap_or_default(),
+        points_per_question.unwrap_or(0.25),
+    ))
+}
+
+/// Xuất báo cáo của `get_coverage_report` thành tệp .xlsx có trang
+/// "Bản đặc tả" (ô lệch ma trận tô đỏ) và trang "Chênh lệch".
+#[tauri::command]
+fn export_coverage_report(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    matrix: Option<bank::coverage::TargetMatrix>,
+    points_per_question: Option<f64>,
+    output_path: String,
+) -> Result<(), CommandError> {
+    let report = get_coverage_report(app_handle, job_id, matrix, points_per_question)?;
+    bank::coverage::write_xlsx(&report, Path::new(&output_path))
+        .map_err(|e| CommandError::with_detail("E132_XLSX_WRITE_FAILED", format!("{:?}", e)))
+}
+
+/// Đưa các câu hỏi của một job đã phân tích vào ngân hàng câu hỏi, gắn sẵn
+/// `tags`. Câu gần trùng với câu đã có được bỏ qua, gộp tag hoặc đánh dấu
+/// tùy `dedup`; trả về báo cáo trùng lặp.
+#[tauri::command]
+async fn import_job_to_bank(
+    app_handle: tauri::AppHandle,
+    job_id: String,
+    tags: Vec<String>,
+    dedup: Option<bank::dedup::DedupOptions>,
+) -> Result<bank::dedup::DedupReport, CommandError> {
+    let parsed = load_parsed_doc(&app_handle, &job_id)?;
+    let dir = bank::store::bank_dir(&app_handle)?;
+    let author = storage::settings::current_author(&app_handle);
+
+    let incoming = bank::store::questions_from_job(&job_id, parsed, &bank::normalize_tags(tags));
+    blocking_bank_write(move || {
+        bank::store::update(&dir, &author, |question_bank| {
+            let report =
+                bank::dedup::import_questions(question_bank, incoming, &dedup.unwrap_or_default());
+            for id in &report.imported {
+                if let Some(entry) = question_bank.get_mut(id) {
+                    bank::store::store_assets(&dir, entry)?;
+                }
+            }
+            Ok(report)
+        })
+    })
+    .await
+}
+
+/// Xử lý một câu bị đánh dấu trùng khi nhập: `keep = true` giữ lại và bỏ
+/// đánh dấu, `false` xoá câu khỏi ngân hàng.
+#[tauri::command]
+async fn resolve_bank_duplicate(
+    app_handle: tauri::AppHandle,
+    id: String,
+    keep: bool,
+) -> Result<(), CommandError> {
+    let dir = bank::store::bank_dir(&app_handle)?;
+    let author = storage::settings::current_author(&app_handle);
+    blocking_bank_write(move || {
+        bank::store::update(&dir, &author, |question_bank| {
+            let entry = question_bank
+                .get_mut(&id)
+                .ok_or_else(|| CommandError::with_detail("E140_BANK_QUESTION_NOT_FOUND", &id))?;
+
+            if keep {
+                entry.duplicate_of = None;
+            } else {
+                question_bank.questions.retain(|q| q.id != id);
+                bank::store::remove_assets(&dir, &id)?;
+            }
+            Ok(())
+        })
+    })
+    .await
+}
+
+/// Gửi nội dung một câu trong ngân hàng tới dịch vụ diễn đạt lại (địa chỉ
+/// trong cài đặt) để lấy `count` (mặc định 2) cách diễn đạt khác. Các câu
+/// mới được thêm vào ngân hàng ở trạng thái chờ duyệt; trả về id của chúng.
+/// Kết quả được lưu đệm nên hỏi lại cùng câu không gọi dịch vụ lần nữa.
+#[tauri::command]
+async fn paraphrase_bank_question(
+    app_handle: tauri::AppHandle,
+    id: String,
+    count: Option<u32>,
+) -> Result<Vec<String>, CommandError> {
+    use crate::bank::paraphrase;
+
+    let settings = storage::settings::load(&app_handle)?;
+    if settings.paraphrase.endpoint.trim().is_empty() {
+        return Err(CommandError::new("E192_PARAPHRASE_URL_NOT_SET"));
+    }
+    let dir = bank::store::bank_dir(&app_handle)?;
+    let source = bank::store::load_from(&dir)?
+        .questions
+        .into_iter()
+        .find(|q| q.id == id)
+        .ok_or_else(|| CommandError::with_detail("E140_BANK_QUESTION_NOT_FOUND", &id))?;
+    let request = paraphrase::request_for(&source.question, count.unwrap_or(2), settings.language)
+        .ok_or_else(|| CommandError::with_detail("E194_PARAPHRASE_NOT_TEXT", &id))?;
+
+    let cache_dir = storage::paths::paraphrase_cache_dir(&app_handle).ok();
+    let paraphrases = tokio::task::spawn_blocking(move || {
+        paraphrase::fetch(&settings.paraphrase, &request, cache_dir.as_deref())
+    })
+    .await
+    .map_err(|e| CommandError::with_detail("E193_PARAPHRASE_FAILED", e))?
+    .map_err(|e| CommandError::with_detail("E193_PARAPHRASE_FAILED", e))?;
+
+    let pending = paraphrase::pending_questions(&source, paraphrases);
+    let ids = pending.iter().map(|q| q.id.clone()).collect();
+    let author = storage::settings::current_author(&app_handle);
+    blocking_bank_write(move || {
+        bank::store::update(&dir, &author, |question_bank| {
+            question_bank.questions.extend(pending);
+            Ok(())
+        })
+    })
+    .await?;
+    Ok(ids)
+}
+
+/// Duyệt một câu diễn đạt lại đang chờ: `accept = true` đưa vào ngân hàng
+/// (tìm kiếm được như các câu khác), `false` xoá câu.
+#[tauri::command]
+async fn review_paraphrase(
+    app_handle: tauri::AppHandle,
+    id: String,
+    accept: bool,
+) -> Result<(), CommandError> {
+    let dir = bank::store::bank_dir(&app_handle)?;
+    let author = storage::settings::current_author(&app_handle);
+    blocking_bank_write(move || {
+        bank::store::update(&dir, &author, |question_bank| {
+            let entry = question_bank
+                .get_mut(&id)
+                .ok_or_else(|| CommandError::with_detail("E140_BANK_QUESTION_NOT_FOUND", &id))?;
+
+            if accept {
+                entry.pending_review = false;
+            } else {
+                question_bank.questions.retain(|q| q.id != id);
+            }
+            Ok(())
+        })
+    })
+    .await
+}
+
+/// Thay toàn bộ tag của một câu trong ngân hàng.
+#[tauri::command]
+async fn set_bank_question_tags(
+    app_handle: tauri::AppHandle,
+    id: String,
+    tags: Vec<String>,
+) -> Result<(), CommandError> {
+    blocking_bank_write(move || {
+        edit_bank_question(&app_handle, &id, None, |entry| {
+            entry.tags = bank::normalize_tags(tags);
+            Ok(())
+        })
+    })
+    .await
+}
+
+/// Sửa nội dung (và tag, nếu có) của một câu trong ngân hàng. Phiên bản cũ
+/// được giữ trong lịch sử. `expected_revision` là phiên bản client đã tải về,
+/// để không ghi đè thay đổi của người khác trên ngân hàng dùng chung.
+#[tauri::command]
+async fn update_bank_question(
+    app_handle: tauri::AppHandle,
+    id: String,
+    question: crate::docx::model::Question,
+    tags: Option<Vec<String>>,
+    expected_revision: Option<u32>,
+) -> Result<(), CommandError> {
+    blocking_bank_write(move || {
+        edit_bank_question(&app_handle, &id, expected_revision, |entry| {
+            entry.question = question;
+            if let Some(tags) = tags {
+                entry.tags = bank::normalize_tags(tags);
+            }
+            Ok(())
+        })
+    })
+    .await
+}
+
+/// Gợi ý `count` (mặc định 3) phương án sai cho câu có đáp án là số `answer`:
+/// đổi dấu, sai dấu phẩy thập phân, quên hệ số 2, lệch 1. Số được viết với
+/// dấu thập phân như trong `answer`.
+#[tauri::command]
+fn suggest_numeric_distractors(answer: String, count: Option<usize>) -> Result<Vec<String>, CommandError> {
+    use crate::docx::distractors;
+
+    let value = distractors::parse_number(&answer)
+        .ok_or_else(|| CommandError::with_detail("E149_ANSWER_NOT_NUMERIC", &answer))?;
+    let separator = if answer.contains(',') { ',' } else { '.' };
+    Ok(distractors::numeric_distractors(value, count.unwrap_or(3))
+        .into_iter()
+        .map(|distractor| distractors::format_number(distractor, separator))
+        .collect())
+}
+
+/// Chuyển một câu trả lời ngắn trong ngân hàng thành câu trắc nghiệm: đáp án
+/// `answer` và các phương án sai `distractors` (bỏ trống = gợi ý như
+/// `suggest_numeric_distractors`) được xếp tăng dần thành A, B, C… Phiên bản
+/// cũ được giữ trong lịch sử.
+#[tauri::command]
+async fn convert_bank_question_to_choice(
+    app_handle: tauri::AppHandle,
+    id: String,
+    answer: String,
+    distractors: Option<Vec<String>>,
+    expected_revision: Option<u32>,
+) -> Result<(), CommandError> {
+    let distractors = match distractors {
+        Some(distractors) => distractors.into_iter().filter(|d| !d.trim().is_empty()).collect(),
+        None => suggest_numeric_distractors(answer.clone(), None)?,
+    };
+    // Kiểm tra trên bản đang khoá: người khác có thể vừa thêm phương án
+    blocking_bank_write(move || {
+        edit_bank_question(&app_handle, &id, expected_revision, |entry| {
+            if !entry.question.options.is_empty() {
+                return Err(CommandError::with_detail("E148_BANK_QUESTION_HAS_OPTIONS", &entry.id));
+            }
+            crate::docx::distractors::to_multiple_choice(&mut entry.question, &answer, &distractors);
+            Ok(())
+        })
+    })
+    .await
+}
+
+/// Các phiên bản của một câu trong ngân hàng, mới nhất trước (phần tử đầu là
+/// phiên bản hiện tại).
+#[tauri::command]
+fn get_question_history(
+    app_handle: tauri::AppHandle,
+    id: String,
+) -> Result<Vec<bank::history::Revision>, CommandError> {
+    let dir = bank::store::bank_dir(&app_handle)?;
+    let question_bank = bank::store::load_from(&dir)?;
+    let entry = question_bank
+        .questions
+        .iter()
+        .find(|q| q.id == id)
+        .ok_or_else(|| CommandError::with_detail("E140_BANK_QUESTION_NOT_FOUND", &id))?;
+
+    let mut revisions = bank::history::load_history(&dir, &id)?;
+    revisions.push(bank::history::current_revision(entry));
+    revisions.reverse();
+    Ok(revisions)
+}
+
+/// Khôi phục một phiên bản cũ của câu hỏi. Bản đang có được lưu vào lịch sử
+/// nên có thể hoàn tác việc khôi phục.
+#[tauri::command]
+async fn restore_question_revision(
+    app_handle: tauri::AppHandle,
+    id: String,
+    revision: u32,
+) -> Result<(), CommandError> {
+    let dir = bank::store::bank_dir(&app_handle)?;
+    let restored = bank::history::load_history(&dir, &id)?
+        .into_iter()
+        .find(|r| r.revision == revision)
+        .ok_or_else(|| {
+            CommandError::with_detail("E141_BANK_REVISION_NOT_FOUND", format!("{} #{}", id, revision))
+        })?;
+
+    blocking_bank_write(move || {
+        edit_bank_question(&app_handle, &id, None, |entry| {
+            entry.question = restored.question;
+            entry.tags = restored.tags;
+            Ok(())
+        })
+    })
+    .await
+}
+
+/// Chạy một lần đọc-ghi ngân hàng ở luồng riêng: chờ khoá ngân hàng dùng
+/// chung (tối đa vài giây) không được chặn luồng giao diện.
+async fn blocking_bank_write<T: Send + 'static>(
+    write: impl FnOnce() -> Result<T, CommandError> + Send + 'static,
+) -> Result<T, CommandError> {
+    tokio::task::spawn_blocking(write)
+        .await
+        .map_err(|e| CommandError::from(e.to_string()))?
+}
+
+/// Lưu phiên bản hiện tại vào lịch sử, áp dụng `edit` (sửa câu hỏi và tag,
+/// kiểm tra trên bản đang khoá) rồi ghi lại ngân hàng. Nếu có
+/// `expected_revision` mà câu hỏi đã sang phiên bản khác thì báo xung đột.
+fn edit_bank_question(
+    app_handle: &tauri::AppHandle,
+    id: &str,
+    expected_revision: Option<u32>,
+    edit: impl FnOnce(&mut bank::BankQuestion) -> Result<(), CommandError>,
+) -> Result<(), CommandError> {
+    let dir = bank::store::bank_dir(app_handle)?;
+    let author = storage::settings::current_author(app_handle);
+    bank::store::update(&dir, &author, |question_bank| {
+        let entry = question_bank
+            .get_mut(id)
+            .ok_or_else(|| CommandError::with_detail("E140_BANK_QUESTION_NOT_FOUND", id))?;
+
+        // Người khác đã sửa câu này kể từ lúc client tải về
+        if let Some(expected) = expected_revision.filter(|r| *r != entry.revision) {
+            return Err(CommandError::with_detail(
+                "E143_BANK_CONFLICT",
+                format!("#{} → #{} ({})", expected, entry.revision, entry.updated_by),
+            ));
+        }
+
+        // `edit` (câu hỏi và tag) có thể từ chối: khi đó không ghi gì vào lịch sử
+        let mut edited = entry.clone();
+        edit(&mut edited)?;
+        bank::history::record_edit(&dir, entry, &author)?;
+        entry.question = edited.question;
+        entry.tags = edited.tags;
+        Ok(())
+    })
+}
+
+#[derive(Serialize)]
+pub struct BenchmarkReport {
+    #[serde(rename = "questionCount")]
+    pub question_count: usize,
+    #[serde(rename = "variantCount")]
+    pub variant_count: usize,
+    /// Thời gian từng bước: unzip, convert, parse, mix, write
+    pub phases: Vec<crate::docx::benchmark::PhaseTiming>,
+    #[serde(rename = "totalMs")]
+    pub total_ms: u128,
+    /// Máy chạy đo, để so sánh với máy khác
+    pub os: String,
+    #[serde(rename = "cpuThreads")]
+    pub cpu_threads: usize,
+    #[serde(rename = "appVersion")]
+    pub app_version: String,
+}
+
+/// Đo thời gian từng bước (giải nén, trích xuất/chuyển hình, phân tích, trộn,
+/// ghi đề) trên một đề mẫu 100 câu tạo sẵn, giống nhau ở mọi máy, để bộ phận
+/// hỗ trợ phân biệt máy chậm với lỗi làm chậm phần mềm. Không đụng tới job nào;
+/// các tệp tạm được xoá sau khi đo.
+#[tauri::command]
+async fn run_benchmark(app_handle: tauri::AppHandle) -> Result<BenchmarkReport, CommandError> {
+    let dir = std::env::temp_dir().join(format!("siromix-benchmark-{}", uuid::Uuid::new_v4()));
+    let report = benchmark_in(&app_handle, &dir).await;
+    let _ = fs::remove_dir_all(&dir);
+    let report = report?;
+    println!("[Benchmark] {} ms: {:?}", report.total_ms, report.phases);
+    Ok(report)
+}
+
+async fn benchmark_in(app_handle: &tauri::AppHandle, dir: &Path) -> Result<BenchmarkReport, CommandError> {
+    use crate::docx::{assets, benchmark, mixer, parser, read, thumbnails, validator};
+    use crate::docx::writer::ExamWriter;
+
+    storage::fs::ensure_dir(dir)?;
+    let docx_path = dir.join("source.docx");
+    let data = benchmark::synthetic_docx(benchmark::QUESTION_COUNT)
+        .map_err(|e| CommandError::with_detail("E102_WORKSPACE_WRITE_FAILED", e.to_string()))?;
+    storage::fs::write_bytes(&docx_path, &data)?;
+    let env = AnalyzeEnv::load(app_handle)?;
+
+    let mut stopwatch = benchmark::Stopwatch::start();
+    let document_xml = read::read_document_xml(&docx_path)
+        .map_err(|e| CommandError::with_detail("E100_DOCX_READ_FAILED", format!("{:?}", e)))?;
+    stopwatch.lap("unzip");
+
+    // Không dùng bộ nhớ đệm chuyển đổi: mỗi lần đo đều chuyển lại
+    let mut extracted_assets = assets::extract_media(&docx_path, &dir.join("assets"), &env.wmf_conversion, None)
+        .map_err(|e| CommandError::with_detail("E101_MEDIA_EXTRACT_FAILED", format!("{:?}", e)))?;
+    for job in assets::run_pending_conversions(dir, None).await {
+        if let Some(asset) = extracted_assets.iter_mut().find(|a| a.absolute_path == job.source) {
+            asset.converted_path = (job.status == assets::ConversionStatus::Done).then_some(job.target);
+        }
+    }
+    thumbnails::generate_all(&extracted_assets, &dir.join("thumbnails"));
+    stopwatch.lap("convert");
+
+    let parse_options = parser::ParseOptions::default();
+    let mut parsed_doc = parser::parse_document_xml_to_parsed_doc(&document_xml, &extracted_assets, &parse_options);
+    let labeled_option_runs = parser::collect_labeled_option_runs(&document_xml, &parse_options);
+    for q in &mut parsed_doc.questions {
+        if let Some(Ok(label)) = labeled_option_runs
+            .get(&q.number)
+            .map(|runs| validator::detect_correct_label_for_question(q.number, runs))
+        {
+            q.correct_label = label;
+        }
+    }
+    stopwatch.lap("parse");
+
+    let question_count = parsed_doc.questions.len();
+    let exams = mixer::mix_exams(
+        parsed_doc.questions,
+        &parsed_doc.groups,
+        &parsed_doc.parts,
+        benchmark::VARIANT_COUNT,
+        None,
+        benchmark::QUESTION_COUNT as u64,
+        false,
+    );
+    stopwatch.lap("mix");
+
+    // Các mã đề đi qua giao diện trước khi xuất: cùng dạng dữ liệu như `export_mixed_exams`
+    let exams: Vec<crate::docx::excel::MixedExam> = serde_json::to_value(&exams)
+        .and_then(serde_json::from_value)
+        .map_err(|e| CommandError::from(e.to_string()))?;
+    let no_originals = std::collections::HashMap::new();
+    for exam in &exams {
+        let writer = ExamWriter {
+            exam_code: exam.exam_code.clone(),
+            questions: variant_questions(exam, &no_originals),
+            exam_title: "ĐỀ KIỂM TRA".to_string(),
+            subject: "Toán".to_string(),
+            duration_minutes: 90,
+            assets_dir: dir.join("assets"),
+            school_name: String::new(),
+            exam_name: String::new(),
+            academic_year: String::new(),
+            grade: String::new(),
+            options: Default::default(),
+            large_print: false,
+            groups: exam.groups.clone(),
+            parts: exam.parts.clone(),
+            teacher_mark: None,
+            student: None,
+        };
+        let file_path = dir.join(format!("{}.docx", exam.exam_code));
+        writer
+            .write_to_file(&file_path)
+            .map_err(|e| CommandError::with_detail("E131_DOCX_WRITE_FAILED", format!("{:?}", e)))?;
+    }
+    stopwatch.lap("write");
+
+    let (phases, total_ms) = stopwatch.finish();
+    Ok(BenchmarkReport {
+        question_count,
+        variant_count: exams.len(),
+        phases,
+        total_ms,
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        cpu_threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+/// Tình trạng thư mục ngân hàng câu hỏi: dùng chung hay cục bộ, có ghi được
+/// không, ai đang giữ khoá.
+#[tauri::command]
+fn get_bank_status(app_handle: tauri::AppHandle) -> Result<BankStatus, CommandError> {
+    let dir = bank::store::bank_dir(&app_handle)?;
+    let shared = !storage::settings::load(&app_handle)?.bank_dir.trim().is_empty();
+    Ok(BankStatus {
+        dir: dir.to_string_lossy().to_string(),
+        shared,
+        read_only: !bank::lock::is_writable(&dir),
+        locked_by: bank::lock::owner(&dir),
+    })
+}
+
+/// Xuất toàn bộ ngân hàng (câu hỏi, hình, lịch sử sửa) ra một tệp mã hoá
+/// bằng mật khẩu để chia sẻ qua USB/email. Trả về số câu đã xuất.
+#[tauri::command]
+fn export_bank(
+    app_handle: tauri::AppHandle,
+    path: String,
+    password: String,
+) -> Result<usize, CommandError> {
+    if password.is_empty() {
+        return Err(CommandError::new("E147_BANK_PASSWORD_REQUIRED"));
+    }
+    let dir = bank::store::bank_dir(&app_handle)?;
+    let question_bank = bank::store::load_from(&dir)?;
+
+    let zip = bank::archive::pack(&dir, &question_bank)?;
+    storage::fs::write_bytes(std::path::Path::new(&path), &bank::archive::encrypt(&zip, &password))?;
+    Ok(question_bank.questions.len())
+}
+
+/// Nhập một tệp ngân hàng đã mã hoá. Câu đã có (cùng id) được bỏ qua, các câu
+/// khác được kiểm tra trùng như khi nhập từ job.
+#[tauri::command]
+async fn import_bank(
+    app_handle: tauri::AppHandle,
+    path: String,
+    password: String,
+    dedup: Option<bank::dedup::DedupOptions>,
+) -> Result<bank::dedup::DedupReport, CommandError> {
+    let data = std::fs::read(&path)
+        .map_err(|e| CommandError::with_detail("E145_BANK_ARCHIVE_INVALID", format!("{}: {}", path, e)))?;
+    let archive = bank::archive::unpack(&bank::archive::decrypt(&data, &password)?)?;
+
+    let dir = bank::store::bank_dir(&app_handle)?;
+    let author = storage::settings::current_author(&app_handle);
+    blocking_bank_write(move || {
+        bank::store::update(&dir, &author, |question_bank| {
+            bank::archive::install(&dir, question_bank, archive, &dedup.unwrap_or_default())
+                .map_err(CommandError::from)
+        })
+    })
+    .await
+}
+
+/// Tìm câu hỏi trong ngân hàng theo nội dung (không phân biệt dấu) và tag.
+#[tauri::command]
+fn search_bank(
+    app_handle: tauri::AppHandle,
+    query: String,
+    filters: Option<bank::search::SearchFilters>,
+    page: Option<bank::search::PageRequest>,
+) -> Result<bank::search::SearchResult, CommandError> {
+    let question_bank = bank::store::load(&app_handle)?;
+    let mut index = bank::index::SearchIndex::open(&storage::paths::bank_index_path(&app_handle)?)?;
+    Ok(bank::search::search(
+        &mut index,
+        &question_bank.questions,
+        &query,
+        &filters.unwrap_or_default(),
+        &page.unwrap_or_default(),
+    )?)
+}
+
+/// Bật mã hoá dữ liệu đề trong workspace (parsed.json, mapping các mã đề)
+/// bằng mật khẩu người dùng. Các tệp đã có được mã hoá khi được ghi lại.
+/// File đề gốc, hình ảnh và ảnh thu nhỏ không được mã hoá (xem
+/// `storage::crypto::UNPROTECTED_ENTRIES`).
+#[tauri::command]
+fn enable_workspace_encryption(
+    app_handle: tauri::AppHandle,
+    passphrase: String,
+) -> Result<(), CommandError> {
+    if passphrase.is_empty() {
+        return Err(CommandError::new("E151_WORKSPACE_PASSPHRASE"));
+    }
+    Ok(storage::crypto::enable(&app_handle, &passphrase)?)
+}
+
+/// Mở khoá dữ liệu đề đã mã hoá cho phiên làm việc hiện tại.
+#[tauri::command]
+fn unlock_workspaces(app_handle: tauri::AppHandle, passphrase: String) -> Result<(), CommandError> {
+    Ok(storage::crypto::unlock(&app_handle, &passphrase)?)
+}
+
+/// Khoá lại dữ liệu đề (quên khoá đang giữ trong bộ nhớ).
+#[tauri::command]
+fn lock_workspaces() {
+    storage::crypto::lock();
+}
+
+#[tauri::command]
+fn get_workspace_encryption_status() -> storage::crypto::EncryptionStatus {
+    storage::crypto::status()
+}
+
+/// Kích hoạt giấy phép cho trường (khoá ký số, kiểm tra offline), thay giấy
+/// phép cũ nếu có.
+#[tauri::command]
+fn activate_license(
+    app_handle: tauri::AppHandle,
+    key: String,
+) -> Result<storage::license::License, CommandError> {
+    Ok(storage::license::activate(&app_handle, &key)?)
+}
+
+/// Trạng thái giấy phép: chưa kích hoạt, còn hiệu lực, hết hạn hoặc không hợp lệ.
+#[tauri::command]
+fn get_license_status(app_handle: tauri::AppHandle) -> Result<storage::license::LicenseStatus, CommandError> {
+    Ok(storage::license::status(&app_handle)?)
+}
+
+/// Đưa một đề của năm trước (tệp .docx) vào kho đề cũ với tên `label`
+/// (mặc định là tên tệp). Chỉ giữ nội dung chữ của các câu để so sánh khi
+/// phân tích đề mới.
+#[tauri::command]
+fn add_past_exam(
+    app_handle: tauri::AppHandle,
+    path: String,
+    label: Option<String>,
+) -> Result<storage::past_exams::PastExamInfo, CommandError> {
+    use crate::docx::{parser, read};
+    use crate::storage::past_exams::{self, PastExam, PastExamInfo};
+
+    let docx_path = Path::new(&path);
+    let document_xml = read::read_document_xml(docx_path)
+        .map_err(|e| CommandError::with_detail("E100_DOCX_READ_FAILED", format!("{:?}", e)))?;
+    let parsed = parser::parse_document_xml_to_parsed_doc(&document_xml, &[], &parser::ParseOptions::default());
+    if parsed.questions.is_empty() {
+        return Err(CommandError::with_detail("E111_NO_QUESTIONS", &path));
+    }
+
+    let file_name = docx_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let label = label.filter(|l| !l.trim().is_empty()).unwrap_or_else(|| file_name.clone());
+    let exam = PastExam::from_parsed(&label, &file_name, &parsed);
+    let info = PastExamInfo::from(&exam);
+    let mut corpus = past_exams::load(&app_handle)?;
+    corpus.exams.push(exam);
+    past_exams::save(&app_handle, &corpus)?;
+    Ok(info)
+}
+
+/// Các đề trong kho đề cũ, theo thứ tự đã thêm.
+#[tauri::command]
+fn list_past_exams(app_handle: tauri::AppHandle) -> Result<Vec<storage::past_exams::PastExamInfo>, CommandError> {
+    let corpus = storage::past_exams::load(&app_handle)?;
+    Ok(corpus.exams.iter().map(Into::into).collect())
+}
+
+/// Bỏ một đề khỏi kho đề cũ.
+#[tauri::command]
+fn remove_past_exam(app_handle: tauri::AppHandle, id: String) -> Result<(), CommandError> {
+    let mut corpus = storage::past_exams::load(&app_handle)?;
+    corpus.exams.retain(|exam| exam.id != id);
+    Ok(storage::past_exams::save(&app_handle, &corpus)?)
+}
+
+/// Kiểm tra phiên bản mới theo manifest ở địa chỉ trong cài đặt; trả về
+/// phiên bản hiện tại, phiên bản mới nhất, ghi chú phát hành và link tải.
+#[tauri::command]
+async fn check_for_updates(app_handle: tauri::AppHandle) -> Result<updates::UpdateCheck, CommandError> {
+    let url = storage::settings::load(&app_handle)?.update_manifest_url.trim().to_string();
+    if url.is_empty() {
+        return Err(CommandError::new("E190_UPDATE_URL_NOT_SET"));
+    }
+    tokio::task::spawn_blocking(move || updates::check(&url))
+        .await
+        .map_err(|e| CommandError::with_detail("E191_UPDATE_CHECK_FAILED", e))?
+        .map_err(|e| CommandError::with_detail("E191_UPDATE_CHECK_FAILED", e))
+}
+
+/// Đọc cài đặt ứng dụng (ngôn ngữ, ...).
+#[tauri::command]
+fn get_settings(app_handle: tauri::AppHandle) -> Result<storage::settings::AppSettings, CommandError> {
+    Ok(storage::settings::load(&app_handle)?)
+}
+
+/// Lời văn mẫu của phần đầu đề, hướng dẫn và dòng đáp án theo ngôn ngữ,
+/// để làm điểm xuất phát khi đặt lời văn riêng cho một môn.
+#[tauri::command]
+fn get_lexicon_preset(language: i18n::Lang) -> crate::docx::lexicon::Lexicon {
+    crate::docx::lexicon::Lexicon::preset(language)
+}
+
+/// Các mẫu đề để chọn khi xuất: mẫu riêng của trường trong cài đặt, rồi
+/// các mẫu có sẵn chưa bị thay.
+#[tauri::command]
+fn get_exam_presets(app_handle: tauri::AppHandle) -> Result<Vec<crate::docx::exam_preset::ExamPreset>, CommandError> {
+    let settings = storage::settings::load(&app_handle)?;
+    Ok(crate::docx::exam_preset::all(&settings.exam_presets))
+}
+
+/// Lưu cài đặt ứng dụng và áp dụng ngay ngôn ngữ cho các thông báo.
+#[tauri::command]
+fn update_settings(
+    app_handle: tauri::AppHandle,
+    settings: storage::settings::AppSettings,
+) -> Result<(), CommandError> {
+    storage::settings::save(&app_handle, &settings)?;
+    i18n::set_lang(settings.language);
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct BankStatus {
+    pub dir: String,
+    pub shared: bool,
+    #[serde(rename = "readOnly")]
+    pub read_only: bool,
+    #[serde(rename = "lockedBy")]
+    pub locked_by: Option<bank::lock::LockOwner>,
+}
+
+#[derive(Serialize)]
+pub struct ExportResponse {
+    pub success: bool,
+    pub message: String,
+    #[serde(rename = "docxFiles")]
+    pub docx_files: Vec<String>,
+    #[serde(rename = "xlsxFile")]
+    pub xlsx_file: String,
+    #[serde(rename = "outputDirectory")]
+    pub output_directory: String,
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        // Lần mở thứ hai (vd. nhấp đúp một tệp đề): chuyển các tệp sang cửa sổ đang chạy
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            use tauri::Manager;
+
+            open_files(app, launch::from_args(argv.into_iter().skip(1), Path::new(&cwd)));
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            // Áp dụng ngôn ngữ đã lưu cho các thông báo từ backend
+            if let Ok(settings) = storage::settings::load(app.handle()) {
+                i18n::set_lang(settings.language);
+            }
+            storage::crypto::init(app.handle())?;
+            storage::recovery::init(app.handle())?;
+            let cwd = std::env::current_dir().unwrap_or_default();
+            launch::push(&launch::from_args(std::env::args().skip(1), &cwd));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             analyze_docx,
+            get_incomplete_jobs,
+            recover_incomplete_job,
+            take_opened_files,
+            enqueue_analysis,
+            get_analysis_queue,
+            clear_analysis_queue,
+            duplicate_job,
             get_parsed,
+            get_parsed_page,
+            get_question,
+            get_parsed_summary,
+            get_asset_thumbnail,
+            retry_failed_conversions,
+            reassign_paragraph,
+            set_correct_label,
+            autofix_job,
+            diff_jobs,
+            get_question_stats,
+            spell_check_job,
+            import_job_to_bank,
+            get_coverage_report,
+            export_coverage_report,
+            resolve_bank_duplicate,
+            paraphrase_bank_question,
+            review_paraphrase,
+            set_bank_question_tags,
+            update_bank_question,
+            suggest_numeric_distractors,
+            convert_bank_question_to_choice,
+            get_question_history,
+            restore_question_revision,
+            search_bank,
+            get_bank_status,
+            export_bank,
+            import_bank,
             mix_exams,
-            export_mixed_exams
+            preview_mix,
+            get_variant_mapping,
+            get_variant_states,
+            set_variant_approval,
+            swap_questions,
+            regenerate_variant,
+            reimport_variant,
+            copy_answer_key_to_clipboard,
+            export_mixed_exams,
+            export_answer_key,
+            export_speech_scripts,
+            export_listening_scripts,
+            load_roster,
+            list_exporters,
+            run_exporter,
+            set_answer_key_passphrase,
+            set_answer_key_release,
+            get_audit_log,
+            get_job_timeline,
+            get_export_manifest,
+            open_output_folder,
+            reveal_file,
+            create_share_bundle,
+            enable_workspace_encryption,
+            unlock_workspaces,
+            lock_workspaces,
+            get_workspace_encryption_status,
+            get_settings,
+            get_lexicon_preset,
+            get_exam_presets,
+            check_for_updates,
+            add_past_exam,
+            list_past_exams,
+            remove_past_exam,
+            run_benchmark,
+            activate_license,
+            get_license_status,
+            update_settings
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
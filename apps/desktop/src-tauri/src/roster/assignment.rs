@@ -0,0 +1,139 @@
+//! Which variant each student of the roster sits, recorded for grading.
+//!
+//! Variants are dealt in roster order (first student the first variant,
+//! second the second, ...), so students listed next to each other get
+//! different codes; with exam rooms, by seat instead (see
+//! [`super::seating`]). The assignment is written next to the exported copies
+//! (`PhanCong_MaDe.xlsx`) and kept in the job workspace
+//! (`assignments.json`), so that scanned answer sheets can be matched to
+//! the right answer key.
+
+use std::path::Path;
+
+use rust_xlsxwriter::{Color, Format, Workbook, XlsxError};
+use serde::{Deserialize, Serialize};
+
+use super::Student;
+use crate::docx::file_names::sanitize;
+
+/// Name of the assignment workbook in the output folder
+pub const ASSIGNMENT_FILE_NAME: &str = "PhanCong_MaDe.xlsx";
+
+/// File in the job workspace keeping the last assignment
+pub const ASSIGNMENTS_FILE: &str = "assignments.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Assignment {
+    pub student: Student,
+    pub exam_code: String,
+    /// Personalized copy written for the student (empty until exported)
+    pub file_name: String,
+    /// Seat in the exam room, when the students were seated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seat: Option<super::seating::Seat>,
+}
+
+/// Deal `exam_codes` to `students` in roster order
+pub fn assign(students: &[Student], exam_codes: &[String]) -> Vec<Assignment> {
+    if exam_codes.is_empty() {
+        return Vec::new();
+    }
+    students
+        .iter()
+        .zip(exam_codes.iter().cycle())
+        .map(|(student, exam_code)| Assignment {
+            student: student.clone(),
+            exam_code: exam_code.clone(),
+            file_name: String::new(),
+            seat: None,
+        })
+        .collect()
+}
+
+/// File name (without extension) of a student's copy of the variant file
+/// `variant_stem`: the student's ID, else their position in the roster
+pub fn copy_stem(variant_stem: &str, student: &Student, position: usize) -> String {
+    let id = sanitize(&student.id);
+    if id.is_empty() {
+        format!("{}_HS{:03}", variant_stem, position + 1)
+    } else {
+        format!("{}_{}", variant_stem, id)
+    }
+}
+
+/// Write the assignment sheet (one row per student) and a count of the
+/// students of each variant
+pub fn write_xlsx(assignments: &[Assignment], output_path: &Path) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold().set_background_color(Color::RGB(0x4F46E5));
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Phân công mã đề")?;
+    for (col, title) in ["STT", "Số báo danh", "Họ và tên", "Phòng thi", "Chỗ ngồi", "Mã đề", "Tệp đề"].iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, *title, &header_format)?;
+    }
+    for (index, assignment) in assignments.iter().enumerate() {
+        let row = (index + 1) as u32;
+        sheet.write_number(row, 0, row as f64)?;
+        sheet.write_string(row, 1, &assignment.student.id)?;
+        sheet.write_string(row, 2, &assignment.student.name)?;
+        sheet.write_string(row, 3, &assignment.student.room)?;
+        if let Some(seat) = &assignment.seat {
+            sheet.write_number(row, 4, seat.number as f64)?;
+        }
+        sheet.write_string(row, 5, &assignment.exam_code)?;
+        sheet.write_string(row, 6, &assignment.file_name)?;
+    }
+    for (col, width) in [6, 14, 28, 12, 10, 10, 28].into_iter().enumerate() {
+        sheet.set_column_width(col as u16, width)?;
+    }
+
+    let counts = workbook.add_worksheet();
+    counts.set_name("Số lượng")?;
+    counts.write_string_with_format(0, 0, "Mã đề", &header_format)?;
+    counts.write_string_with_format(0, 1, "Số học sinh", &header_format)?;
+    let mut codes: Vec<&str> = Vec::new();
+    for assignment in assignments {
+        if !codes.contains(&assignment.exam_code.as_str()) {
+            codes.push(&assignment.exam_code);
+        }
+    }
+    for (index, code) in codes.iter().enumerate() {
+        let row = (index + 1) as u32;
+        counts.write_string(row, 0, *code)?;
+        let count = assignments.iter().filter(|a| a.exam_code == *code).count();
+        counts.write_number(row, 1, count as f64)?;
+    }
+    counts.set_column_width(1, 14)?;
+
+    workbook.save(output_path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deals_variants_in_roster_order() {
+        let students: Vec<Student> = ["An", "Bình", "Cường"]
+            .iter()
+            .map(|name| Student {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let codes = vec!["101".to_string(), "102".to_string()];
+        let assignments = assign(&students, &codes);
+        let dealt: Vec<&str> = assignments.iter().map(|a| a.exam_code.as_str()).collect();
+        assert_eq!(dealt, ["101", "102", "101"]);
+        assert!(assign(&students, &[]).is_empty());
+        assert_eq!(copy_stem("De_101", &students[0], 0), "De_101_HS001");
+
+        let path = std::env::temp_dir().join(format!("siromix-assignment-{}.xlsx", uuid::Uuid::new_v4()));
+        write_xlsx(&assignments, &path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
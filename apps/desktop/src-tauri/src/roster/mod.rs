@@ -0,0 +1,145 @@
+//! Class lists for personalized exam copies.
+//!
+//! A roster is a CSV or tab-separated file (Excel "Save as CSV", or cells
+//! pasted from a spreadsheet) with one student per row. A header row naming
+//! the columns ("Họ và tên", "SBD", "Phòng thi", in any order, other columns
+//! such as "STT" ignored) is recommended; without one the columns are read
+//! as name, ID, room. Each student then gets a copy of one variant with
+//! their name and ID in the header (see [`assignment`]), and may be seated
+//! in the exam rooms (see [`seating`], [`room_map`]).
+
+pub mod assignment;
+pub mod room_map;
+pub mod seating;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bank::search::fold;
+
+/// A student of the roster
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Student {
+    /// Candidate number (số báo danh) or school ID; may be empty
+    pub id: String,
+    pub name: String,
+    /// Exam room; empty when the roster has none
+    pub room: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Name,
+    Id,
+    Room,
+}
+
+/// Column named by a header cell
+fn column_of(header: &str) -> Option<Column> {
+    match fold(header.trim()).as_str() {
+        "ho va ten" | "ho ten" | "ten" | "ten hoc sinh" | "ho ten hoc sinh" | "ho va ten thi sinh" | "name"
+        | "full name" | "student name" => Some(Column::Name),
+        "sbd" | "so bao danh" | "ma hs" | "ma hoc sinh" | "ma so" | "id" | "student id" | "candidate number" => {
+            Some(Column::Id)
+        }
+        "phong" | "phong thi" | "room" => Some(Column::Room),
+        _ => None,
+    }
+}
+
+/// Students of a roster file's text, in file order. Fails on a roster with
+/// no student or with the same ID twice.
+pub fn parse(text: &str) -> Result<Vec<Student>, String> {
+    let text = text.trim_start_matches('\u{feff}');
+    let delimiter = ['\t', ';', ',']
+        .into_iter()
+        .max_by_key(|d| text.lines().next().unwrap_or_default().matches(*d).count())
+        .unwrap_or(',');
+    let mut rows = text
+        .lines()
+        .map(|line| split_record(line, delimiter))
+        .filter(|cells| cells.iter().any(|cell| !cell.is_empty()));
+
+    let Some(first) = rows.next() else {
+        return Err("danh sách trống".to_string());
+    };
+    let header: Vec<Option<Column>> = first.iter().map(|cell| column_of(cell)).collect();
+    let (columns, first_row) = if header.contains(&Some(Column::Name)) {
+        (header, None)
+    } else {
+        (vec![Some(Column::Name), Some(Column::Id), Some(Column::Room)], Some(first))
+    };
+
+    let mut students: Vec<Student> = Vec::new();
+    for cells in first_row.into_iter().chain(rows) {
+        let mut student = Student::default();
+        for (cell, column) in cells.into_iter().zip(&columns) {
+            match column {
+                Some(Column::Name) => student.name = cell,
+                Some(Column::Id) => student.id = cell,
+                Some(Column::Room) => student.room = cell,
+                None => {}
+            }
+        }
+        if student.name.is_empty() {
+            continue;
+        }
+        if !student.id.is_empty() && students.iter().any(|s| s.id == student.id) {
+            return Err(format!("số báo danh {} trùng", student.id));
+        }
+        students.push(student);
+    }
+    if students.is_empty() {
+        return Err("không có học sinh nào".to_string());
+    }
+    Ok(students)
+}
+
+/// Cells of one line, trimmed; fields in double quotes may hold the
+/// delimiter and `""` for a quote
+fn split_record(line: &str, delimiter: char) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_columns_by_header() {
+        let csv = "\u{feff}STT;Số báo danh;Họ và tên;Phòng thi\r\n1;0101;\"Nguyễn Văn An\";P.1\r\n2;0102;Trần Thị Bình;P.1\r\n;;;\r\n";
+        let students = parse(csv).unwrap();
+        assert_eq!(students.len(), 2);
+        assert_eq!(
+            students[0],
+            Student {
+                id: "0101".to_string(),
+                name: "Nguyễn Văn An".to_string(),
+                room: "P.1".to_string(),
+            }
+        );
+
+        // No header: name, ID, room
+        let students = parse("Lê Văn Cường\t0201\t\"P.2\"\nPhạm Thu Dung\t0202\tP.2").unwrap();
+        assert_eq!(students[1].id, "0202");
+        assert_eq!(students[0].room, "P.2");
+
+        assert!(parse("Họ tên,SBD\nAn,1\nBình,1").unwrap_err().contains("trùng"));
+    }
+}
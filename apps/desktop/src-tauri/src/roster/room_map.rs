@@ -0,0 +1,175 @@
+//! Seating plans of the exam rooms, printed for the proctors and posted at
+//! the door: `SoDoPhong.xlsx` (a sheet per room) and `SoDoPhong.docx` (a
+//! page per room). Each seat shows its number, the student and the exam
+//! code; the front of the room is at the top.
+
+use std::path::Path;
+
+use rust_xlsxwriter::{Color, Format, FormatAlign, FormatBorder, Workbook, XlsxError};
+
+use super::assignment::Assignment;
+use super::seating::Room;
+use crate::docx::config::NghiDinh30;
+use crate::docx::writer::{escape_xml, write_document_only};
+
+/// Name of the room maps in the output folder, without extension
+pub const ROOM_MAP_FILE_STEM: &str = "SoDoPhong";
+
+/// Seats of `room` in row-major order (`None` = empty seat)
+fn seats<'a>(room: &Room, assignments: &'a [Assignment]) -> Vec<Option<&'a Assignment>> {
+    (1..=room.capacity)
+        .map(|number| {
+            assignments
+                .iter()
+                .find(|a| a.seat.as_ref().is_some_and(|seat| seat.room == room.name && seat.number == number))
+        })
+        .collect()
+}
+
+/// Lines of a seat: number, then the student and their exam code
+fn seat_lines(number: u32, assignment: Option<&Assignment>) -> Vec<String> {
+    let mut lines = vec![format!("Chỗ {}", number)];
+    if let Some(a) = assignment {
+        lines.push(a.student.name.clone());
+        if !a.student.id.is_empty() {
+            lines.push(format!("SBD {}", a.student.id));
+        }
+        lines.push(format!("Mã đề {}", a.exam_code));
+    }
+    lines
+}
+
+/// Sheet name of a room: Excel forbids `[]:*?/\` and allows 31 characters
+fn sheet_name(room: &Room, index: usize) -> String {
+    let name: String = format!("Phòng {}", room.name)
+        .chars()
+        .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+        .take(27)
+        .collect();
+    format!("{} ({})", name.trim(), index + 1)
+}
+
+pub fn write_xlsx(rooms: &[Room], assignments: &[Assignment], output_path: &Path) -> Result<(), XlsxError> {
+    let mut workbook = Workbook::new();
+    let title_format = Format::new().set_bold().set_font_size(14);
+    let front_format = Format::new()
+        .set_italic()
+        .set_align(FormatAlign::Center)
+        .set_background_color(Color::RGB(0xE5E7EB));
+    let seat_format = Format::new()
+        .set_text_wrap()
+        .set_align(FormatAlign::Center)
+        .set_align(FormatAlign::VerticalCenter)
+        .set_border(FormatBorder::Thin);
+
+    for (index, room) in rooms.iter().enumerate() {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(sheet_name(room, index))?;
+        let columns = room.columns.max(1) as u16;
+        sheet.write_string_with_format(0, 0, format!("SƠ ĐỒ PHÒNG THI {}", room.name), &title_format)?;
+        sheet.merge_range(1, 0, 1, columns - 1, "Bàn giám thị - Bảng", &front_format)?;
+        for (position, assignment) in seats(room, assignments).into_iter().enumerate() {
+            let (row, column) = room.position(position as u32 + 1);
+            let cell_row = row + 2;
+            sheet.write_string_with_format(cell_row, column as u16 - 1, seat_lines(position as u32 + 1, assignment).join("\n"), &seat_format)?;
+            sheet.set_row_height(cell_row, 62)?;
+        }
+        for column in 0..columns {
+            sheet.set_column_width(column, 24)?;
+        }
+    }
+
+    workbook.save(output_path)?;
+    Ok(())
+}
+
+pub fn write_docx(rooms: &[Room], assignments: &[Assignment], output_path: &Path) -> Result<(), std::io::Error> {
+    let font = NghiDinh30::FONT_NAME;
+    let run = |text: &str, bold: bool| {
+        format!(
+            r#"<w:r><w:rPr><w:rFonts w:ascii="{font}" w:hAnsi="{font}" w:cs="{font}" w:eastAsia="{font}"/>{}<w:sz w:val="26"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r>"#,
+            if bold { "<w:b/>" } else { "" },
+            escape_xml(text)
+        )
+    };
+    let centered = |content: String| format!(r#"<w:p><w:pPr><w:jc w:val="center"/></w:pPr>{}</w:p>"#, content);
+
+    let mut body = String::new();
+    for (index, room) in rooms.iter().enumerate() {
+        if index > 0 {
+            body.push_str(r#"<w:p><w:r><w:br w:type="page"/></w:r></w:p>"#);
+        }
+        body.push_str(&centered(run(&format!("SƠ ĐỒ PHÒNG THI {}", room.name), true)));
+        body.push_str(&centered(run("Bàn giám thị - Bảng", false)));
+
+        let columns = room.columns.max(1) as usize;
+        let width = 9_000 / columns;
+        let grid: String = (0..columns).map(|_| format!(r#"<w:gridCol w:w="{width}"/>"#)).collect();
+        let cells: Vec<String> = seats(room, assignments)
+            .into_iter()
+            .enumerate()
+            .map(|(position, assignment)| {
+                let paragraphs: String = seat_lines(position as u32 + 1, assignment)
+                    .iter()
+                    .enumerate()
+                    .map(|(line, text)| centered(run(text, line == 0)))
+                    .collect();
+                format!(r#"<w:tc><w:tcPr><w:tcW w:w="{width}" w:type="dxa"/></w:tcPr>{paragraphs}</w:tc>"#)
+            })
+            .collect();
+        let rows: String = cells
+            .chunks(columns)
+            .map(|row| {
+                let padding = r#"<w:tc><w:p/></w:tc>"#.repeat(columns - row.len());
+                format!(r#"<w:tr><w:trPr><w:cantSplit/></w:trPr>{}{}</w:tr>"#, row.concat(), padding)
+            })
+            .collect();
+        body.push_str(&format!(
+            r#"<w:tbl><w:tblPr><w:jc w:val="center"/><w:tblBorders><w:top w:val="single" w:sz="4"/><w:left w:val="single" w:sz="4"/><w:bottom w:val="single" w:sz="4"/><w:right w:val="single" w:sz="4"/><w:insideH w:val="single" w:sz="4"/><w:insideV w:val="single" w:sz="4"/></w:tblBorders></w:tblPr><w:tblGrid>{grid}</w:tblGrid>{rows}</w:tbl><w:p/>"#
+        ));
+    }
+    let document_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{body}<w:sectPr><w:pgSz w:w="{}" w:h="{}"/><w:pgMar w:top="1134" w:right="1134" w:bottom="1134" w:left="1134" w:header="0" w:footer="0" w:gutter="0"/></w:sectPr></w:body></w:document>"#,
+        NghiDinh30::PAGE_WIDTH_TWIPS,
+        NghiDinh30::PAGE_HEIGHT_TWIPS
+    );
+
+    write_document_only(output_path, &document_xml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::roster::seating::seat;
+    use crate::roster::Student;
+
+    #[test]
+    fn writes_a_map_per_room() {
+        let rooms = [Room {
+            name: "P.1".to_string(),
+            capacity: 5,
+            columns: 2,
+        }];
+        let students: Vec<Student> = ["An", "Bình", "Cường"]
+            .iter()
+            .map(|name| Student {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let codes = vec!["101".to_string(), "102".to_string()];
+        let assignments = seat(&students, &rooms, &codes).unwrap();
+        assert_eq!(seat_lines(2, Some(&assignments[1])), ["Chỗ 2", "Bình", "Mã đề 102"]);
+        assert_eq!(sheet_name(&rooms[0], 0), "Phòng P.1 (1)");
+
+        let dir = std::env::temp_dir().join(format!("siromix-room-map-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        write_xlsx(&rooms, &assignments, &dir.join("map.xlsx")).unwrap();
+        write_docx(&rooms, &assignments, &dir.join("map.docx")).unwrap();
+        let document_xml = crate::docx::read::read_document_xml(&dir.join("map.docx")).unwrap();
+        // 5 seats in rows of 2: the last row is padded
+        assert_eq!(document_xml.matches("<w:tr>").count(), 3);
+        assert!(document_xml.contains("Cường"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
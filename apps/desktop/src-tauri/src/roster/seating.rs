@@ -0,0 +1,168 @@
+//! Seats of the students in the exam rooms, with the variant of each seat.
+//!
+//! A room is a grid of `columns` seats per row, numbered from the front row,
+//! left to right. The seat in row `r`, column `c` sits variant `(r + c) mod
+//! n`: two seats side by side or one behind the other never share a code,
+//! as long as there are two variants or more.
+//!
+//! Students whose roster row names a room are seated in it, in roster order;
+//! the others fill the seats left, room after room.
+
+use serde::{Deserialize, Serialize};
+
+use super::assignment::Assignment;
+use super::Student;
+use crate::bank::search::fold;
+
+/// An exam room
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Room {
+    /// As written in the roster's room column, e.g. "P.12"
+    pub name: String,
+    /// Number of seats
+    pub capacity: u32,
+    /// Seats per row
+    pub columns: u32,
+}
+
+impl Default for Room {
+    /// The usual exam room: 24 candidates, 4 per row
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            capacity: 24,
+            columns: 4,
+        }
+    }
+}
+
+impl Room {
+    fn columns(&self) -> u32 {
+        self.columns.max(1)
+    }
+
+    /// Row and column (from 1) of seat `number`
+    pub fn position(&self, number: u32) -> (u32, u32) {
+        ((number - 1) / self.columns() + 1, (number - 1) % self.columns() + 1)
+    }
+}
+
+/// Where a student sits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Seat {
+    pub room: String,
+    /// From 1, front row first
+    pub number: u32,
+    pub row: u32,
+    pub column: u32,
+}
+
+/// Seat `students` in `rooms` and give each seat its variant. Fails with the
+/// reason when there are fewer than two variants, a room named in the
+/// roster is missing or full, or the rooms are too small.
+pub fn seat(students: &[Student], rooms: &[Room], exam_codes: &[String]) -> Result<Vec<Assignment>, String> {
+    if exam_codes.len() < 2 {
+        return Err("cần ít nhất 2 mã đề để hai chỗ ngồi cạnh nhau khác mã đề".to_string());
+    }
+    let capacity: u32 = rooms.iter().map(|room| room.capacity).sum();
+    if students.len() > capacity as usize {
+        return Err(format!("{} học sinh nhưng chỉ có {} chỗ ngồi", students.len(), capacity));
+    }
+
+    // Students of each room, roster rows naming a room first
+    let mut seated: Vec<Vec<&Student>> = vec![Vec::new(); rooms.len()];
+    let mut unplaced: Vec<&Student> = Vec::new();
+    for student in students {
+        if student.room.trim().is_empty() {
+            unplaced.push(student);
+            continue;
+        }
+        let wanted = fold(student.room.trim());
+        let Some(index) = rooms.iter().position(|room| fold(room.name.trim()) == wanted) else {
+            return Err(format!("phòng {} không có trong danh sách phòng", student.room));
+        };
+        if seated[index].len() >= rooms[index].capacity as usize {
+            return Err(format!("phòng {} không đủ {} chỗ", rooms[index].name, rooms[index].capacity));
+        }
+        seated[index].push(student);
+    }
+    let mut unplaced = unplaced.into_iter();
+    for (room, students) in rooms.iter().zip(&mut seated) {
+        let free = room.capacity as usize - students.len();
+        students.extend(unplaced.by_ref().take(free));
+    }
+
+    let mut assignments = Vec::new();
+    for (room, students) in rooms.iter().zip(seated) {
+        for (index, student) in students.into_iter().enumerate() {
+            let number = index as u32 + 1;
+            let (row, column) = room.position(number);
+            assignments.push(Assignment {
+                student: Student {
+                    room: room.name.clone(),
+                    ..student.clone()
+                },
+                exam_code: exam_codes[(row + column) as usize % exam_codes.len()].clone(),
+                file_name: String::new(),
+                seat: Some(Seat {
+                    room: room.name.clone(),
+                    number,
+                    row,
+                    column,
+                }),
+            });
+        }
+    }
+    Ok(assignments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student(name: &str, room: &str) -> Student {
+        Student {
+            id: name.to_string(),
+            name: name.to_string(),
+            room: room.to_string(),
+        }
+    }
+
+    #[test]
+    fn neighbours_never_share_a_code() {
+        let rooms = [
+            Room {
+                name: "P.1".to_string(),
+                capacity: 6,
+                columns: 3,
+            },
+            Room {
+                name: "P.2".to_string(),
+                ..Default::default()
+            },
+        ];
+        let mut students: Vec<Student> = (1..=8).map(|i| student(&format!("HS{}", i), "")).collect();
+        students.push(student("Trưởng phòng", "p.2"));
+        let codes: Vec<String> = ["101", "102", "103"].iter().map(|c| c.to_string()).collect();
+
+        let assignments = seat(&students, &rooms, &codes).unwrap();
+        assert_eq!(assignments.len(), 9);
+        // The student naming P.2 sits there first, the others fill P.1 then P.2
+        assert_eq!(assignments[6].student.name, "Trưởng phòng");
+        assert_eq!(assignments[6].student.room, "P.2");
+        assert_eq!(assignments[5].seat.as_ref().unwrap().room, "P.1");
+
+        for a in &assignments {
+            for b in &assignments {
+                let (sa, sb) = (a.seat.as_ref().unwrap(), b.seat.as_ref().unwrap());
+                let adjacent = sa.room == sb.room && sa.row.abs_diff(sb.row) + sa.column.abs_diff(sb.column) == 1;
+                assert!(!adjacent || a.exam_code != b.exam_code, "{:?} {:?}", sa, sb);
+            }
+        }
+
+        assert!(seat(&students, &rooms, &codes[..1]).is_err());
+        assert!(seat(&[student("An", "P.9")], &rooms, &codes).unwrap_err().contains("P.9"));
+    }
+}
@@ -0,0 +1,60 @@
+// src-tauri/src/shell.rs
+//! Show exported files in the system file manager and open links, through
+//! `tauri-plugin-opener`. Paths come from the frontend, so they are first
+//! checked to lie in the folders of the job ([`within`]).
+
+use std::path::{Path, PathBuf};
+
+/// Error string (catalog code) for a path outside the allowed folders
+pub const PATH_NOT_ALLOWED: &str = "E183_PATH_NOT_ALLOWED";
+
+/// `path`, resolved, if it exists and lies in one of `roots` (an export
+/// folder, a job workspace). Links (`..`, symlinks) are resolved first.
+pub fn within(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let resolved = path
+        .canonicalize()
+        .map_err(|_| format!("Không tìm thấy {}", path.display()))?;
+    roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| resolved.starts_with(root))
+        .then_some(resolved)
+        .ok_or_else(|| PATH_NOT_ALLOWED.to_string())
+}
+
+/// Open the folder `dir` in the file manager.
+pub fn open_folder(dir: &Path) -> Result<(), String> {
+    tauri_plugin_opener::open_path(dir, None::<&str>).map_err(|e| e.to_string())
+}
+
+/// Open the folder containing `path` with the file selected.
+pub fn reveal_file(path: &Path) -> Result<(), String> {
+    tauri_plugin_opener::reveal_item_in_dir(path).map_err(|e| e.to_string())
+}
+
+/// Open a link (`mailto:`, `https:`) with its default application.
+pub fn open_url(url: &str) -> Result<(), String> {
+    tauri_plugin_opener::open_url(url, None::<&str>).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_paths_inside_the_roots_are_allowed() {
+        let root = std::env::temp_dir().join(format!("siromix-shell-{}", uuid::Uuid::new_v4()));
+        let export = root.join("export");
+        std::fs::create_dir_all(&export).unwrap();
+        std::fs::write(export.join("De_101.docx"), b"docx").unwrap();
+        std::fs::write(root.join("other.txt"), b"").unwrap();
+        let roots = [export.clone()];
+
+        assert!(within(&export.join("De_101.docx"), &roots).is_ok());
+        assert!(within(&export, &roots).is_ok());
+        assert_eq!(within(&export.join("../other.txt"), &roots), Err(PATH_NOT_ALLOWED.to_string()));
+        assert!(within(&export.join("missing.docx"), &roots).is_err());
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
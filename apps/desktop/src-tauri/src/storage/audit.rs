@@ -0,0 +1,264 @@
+//! Append-only audit log of exports, `<workspace>/audit.jsonl` (one JSON
+//! entry per line).
+//!
+//! Each entry records the SHA-256 of the files written and the hash of the
+//! previous line, so editing or deleting an earlier entry breaks the chain
+//! and is reported by [`verify_chain`]. The chain alone cannot show that the
+//! last entries (or the whole file) were removed: `audit.head.json` keeps
+//! the number of entries and the hash of the last line, checked by [`load`]
+//! and before each append. Once a check fails the head stays marked as
+//! tampered, so appending again does not make the log look intact. A line
+//! that no longer parses counts as an edit too.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+
+use super::{fs, paths};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditAction {
+    /// Exam DOCX files plus the answer key
+    ExportExams,
+    /// Answer key only
+    ExportAnswerKey,
+    /// Zip of the exported files prepared for sending
+    ShareBundle,
+    /// Files written by an add-on exporter that includes the answers
+    CustomExport,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditFile {
+    pub path: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub action: AuditAction,
+    /// Unix timestamp (seconds)
+    pub at: u64,
+    pub author: String,
+    #[serde(rename = "variantCodes")]
+    pub variant_codes: Vec<String>,
+    pub files: Vec<AuditFile>,
+    /// SHA-256 of the previous line of the log, empty for the first entry
+    #[serde(rename = "prevHash")]
+    pub prev_hash: String,
+}
+
+/// What the log looked like after the last append
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct AuditHead {
+    entries: usize,
+    /// SHA-256 of the last line
+    #[serde(rename = "lastHash")]
+    last_hash: String,
+    /// A check failed at some point; never cleared
+    tampered: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditLog {
+    pub entries: Vec<AuditEntry>,
+    /// Whether every entry still chains to the one before it
+    pub intact: bool,
+}
+
+pub fn audit_path(app_handle: &AppHandle, job_id: &str) -> Result<PathBuf, String> {
+    Ok(paths::job_workspace_dir(app_handle, job_id)?.join("audit.jsonl"))
+}
+
+/// Append an entry for files just written; `prev_hash` is filled in here.
+pub fn append(
+    path: &Path,
+    action: AuditAction,
+    author: &str,
+    variant_codes: Vec<String>,
+    files: &[PathBuf],
+) -> Result<(), String> {
+    let files = files
+        .iter()
+        .map(|file| {
+            let data = std::fs::read(file)
+                .map_err(|e| format!("Không đọc được file {}: {e}", file.display()))?;
+            Ok(AuditFile {
+                path: file.to_string_lossy().to_string(),
+                sha256: hex_digest(&data),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let lines = read_lines(path)?;
+    let head = read_head(path)?;
+    // An empty log without a head: nothing was ever exported
+    let intact = head.as_ref().map_or(lines.is_empty(), |h| !h.tampered) && matches_head(&lines, head.as_ref());
+    let prev_hash = lines
+        .last()
+        .map(|line| hex_digest(line.as_bytes()))
+        .unwrap_or_default();
+    let entry = AuditEntry {
+        action,
+        at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        author: author.to_string(),
+        variant_codes,
+        files,
+        prev_hash,
+    };
+    let line = serde_json::to_string(&entry)
+        .map_err(|e| format!("Không serialize được nhật ký: {e}"))?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Không ghi được file {}: {e}", path.display()))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Không ghi được file {}: {e}", path.display()))?;
+
+    fs::write_json_atomic(
+        &head_path(path),
+        &AuditHead {
+            entries: lines.len() + 1,
+            last_hash: hex_digest(line.as_bytes()),
+            tampered: !intact,
+        },
+    )
+}
+
+/// All entries of the log, oldest first (empty if nothing was exported yet).
+/// Lines that do not parse are left out and the log is not intact.
+pub fn load(path: &Path) -> Result<AuditLog, String> {
+    let lines = read_lines(path)?;
+    let mut entries = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match serde_json::from_str::<AuditEntry>(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => eprintln!("[Audit] {} line {} unreadable: {}", path.display(), i + 1, e),
+        }
+    }
+    let head = read_head(path)?;
+    let intact = entries.len() == lines.len()
+        && verify_chain(&lines, &entries)
+        && head.as_ref().map_or(lines.is_empty(), |h| !h.tampered)
+        && matches_head(&lines, head.as_ref());
+    Ok(AuditLog { entries, intact })
+}
+
+/// Whether each entry's `prev_hash` matches the line before it.
+pub fn verify_chain(lines: &[String], entries: &[AuditEntry]) -> bool {
+    entries.iter().enumerate().all(|(i, entry)| match i {
+        0 => entry.prev_hash.is_empty(),
+        _ => entry.prev_hash == hex_digest(lines[i - 1].as_bytes()),
+    })
+}
+
+/// Whether the log still ends where the head says
+fn matches_head(lines: &[String], head: Option<&AuditHead>) -> bool {
+    match head {
+        None => lines.is_empty(),
+        Some(head) => {
+            lines.len() == head.entries
+                && lines.last().map(|line| hex_digest(line.as_bytes())).unwrap_or_default() == head.last_hash
+        }
+    }
+}
+
+fn head_path(path: &Path) -> PathBuf {
+    path.with_extension("head.json")
+}
+
+fn read_head(path: &Path) -> Result<Option<AuditHead>, String> {
+    let head_path = head_path(path);
+    if !head_path.exists() {
+        return Ok(None);
+    }
+    fs::read_json(&head_path).map(Some)
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Không đọc được file {}: {e}", path.display()))?;
+    Ok(text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+pub(crate) fn hex_digest(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_edited_or_removed_entries() {
+        let dir = std::env::temp_dir().join(format!("siromix-audit-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exported = dir.join("De_101.docx");
+        std::fs::write(&exported, b"docx").unwrap();
+        let log = dir.join("audit.jsonl");
+
+        let codes = vec!["101".to_string()];
+        let files = vec![exported];
+        append(&log, AuditAction::ExportExams, "cô Lan", codes.clone(), &files).unwrap();
+        append(&log, AuditAction::ExportAnswerKey, "cô Lan", codes, &files).unwrap();
+
+        let audit = load(&log).unwrap();
+        assert_eq!(audit.entries.len(), 2);
+        assert!(audit.intact);
+        assert_eq!(audit.entries[0].files[0].sha256.len(), 64);
+
+        let original = std::fs::read_to_string(&log).unwrap();
+        let text = original.replace("cô Lan", "thầy Minh");
+        std::fs::write(&log, text).unwrap();
+        assert!(!load(&log).unwrap().intact);
+
+        // A line that no longer parses: the other entries are still listed
+        let mut lines: Vec<&str> = original.lines().collect();
+        lines[0] = "{\"action\":\"exportExams\",\"at\":";
+        std::fs::write(&log, format!("{}\n", lines.join("\n"))).unwrap();
+        let audit = load(&log).unwrap();
+        assert!(!audit.intact);
+        assert_eq!(audit.entries.len(), 1);
+        assert_eq!(audit.entries[0].action, AuditAction::ExportAnswerKey);
+
+        // Dropping the last entry keeps the chain valid but not the head,
+        // and a later export does not hide it
+        let log2 = dir.join("audit2.jsonl");
+        let files = vec![dir.join("De_101.docx")];
+        append(&log2, AuditAction::ExportExams, "cô Lan", Vec::new(), &files).unwrap();
+        append(&log2, AuditAction::ExportAnswerKey, "cô Lan", Vec::new(), &files).unwrap();
+        let first_line = std::fs::read_to_string(&log2).unwrap().lines().next().unwrap().to_string();
+        std::fs::write(&log2, format!("{first_line}\n")).unwrap();
+        assert!(!load(&log2).unwrap().intact);
+        append(&log2, AuditAction::ShareBundle, "cô Lan", Vec::new(), &files).unwrap();
+        let audit = load(&log2).unwrap();
+        assert_eq!(audit.entries.len(), 2);
+        assert!(!audit.intact);
+
+        // So is deleting the whole log
+        std::fs::remove_file(&log2).unwrap();
+        assert!(!load(&log2).unwrap().intact);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
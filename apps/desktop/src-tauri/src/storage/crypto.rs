@@ -0,0 +1,321 @@
+//! Optional at-rest encryption of the job metadata (`parsed.json`,
+//! `variants/<code>/mapping.json`): the parsed questions, their answers and
+//! the variant mappings.
+//!
+//! It does not cover the documents and images of a job
+//! ([`UNPROTECTED_ENTRIES`]: `source.docx`, `source.orig.docx`, subdocuments,
+//! `assets/`, `thumbnails/`) nor the shared WMF/EMF conversion cache: the
+//! parser and exporters open them by path and the UI loads images straight
+//! from disk, so they stay plain. Keeping those private needs disk
+//! encryption from the OS.
+//!
+//! Enabling it stores a random salt and a check value in
+//! `<app_data>/SiroMix/workspace-key.json`; the passphrase itself is never
+//! written. Unlocking derives the key (PBKDF2-HMAC-SHA256) once and keeps it in
+//! memory for the session. Protected files are `"SIROENC1" | nonce (12) |
+//! AES-256-GCM ciphertext`; `fs::read_json` decrypts them transparently and
+//! still reads files written before encryption was turned on.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::{fs, paths};
+
+const MAGIC: &[u8; 8] = b"SIROENC1";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+const KDF_ROUNDS: u32 = 200_000;
+
+/// Encrypted with the key to tell a wrong passphrase from a right one
+const CHECK_PLAINTEXT: &[u8] = b"siromix-workspace";
+
+/// Error strings (catalog codes) returned through the storage helpers
+pub const LOCKED: &str = "E150_WORKSPACE_LOCKED";
+pub const WRONG_PASSPHRASE: &str = "E151_WORKSPACE_PASSPHRASE";
+pub const ALREADY_ENABLED: &str = "E152_WORKSPACE_ENCRYPTION_ENABLED";
+
+/// Workspace entries (files or folders) that stay plain when encryption is on
+pub const UNPROTECTED_ENTRIES: &[&str] = &[
+    "source.docx",
+    "source.orig.docx",
+    crate::docx::parts::SUBDOCS_DIR,
+    "assets",
+    "thumbnails",
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static SESSION_KEY: Mutex<Option<[u8; 32]>> = Mutex::new(None);
+
+/// `workspace-key.json`
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyFile {
+    /// Hex
+    salt: String,
+    rounds: u32,
+    /// Hex of `seal(key, CHECK_PLAINTEXT)`
+    check: String,
+}
+
+/// Salted PBKDF2 hash of a passphrase, for checking it without storing it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassphraseHash {
+    /// Hex
+    pub salt: String,
+    pub rounds: u32,
+    /// Hex
+    pub hash: String,
+}
+
+impl PassphraseHash {
+    pub fn new(passphrase: &str) -> Self {
+        Self::with_rounds(passphrase, KDF_ROUNDS)
+    }
+
+    pub(crate) fn with_rounds(passphrase: &str, rounds: u32) -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Self {
+            salt: to_hex(&salt),
+            rounds,
+            hash: to_hex(&derive_key(passphrase, &salt, rounds)),
+        }
+    }
+
+    pub fn verify(&self, passphrase: &str) -> bool {
+        let (Some(salt), Some(expected)) = (from_hex(&self.salt), from_hex(&self.hash)) else {
+            return false;
+        };
+        let actual = derive_key(passphrase, &salt, self.rounds);
+        // Compare every byte so timing doesn't reveal the matching prefix
+        expected.len() == actual.len()
+            && expected.iter().zip(actual.iter()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    /// [`UNPROTECTED_ENTRIES`], for the settings screen
+    pub unprotected: &'static [&'static str],
+}
+
+fn key_file_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(paths::app_root_dir(app_handle)?.join("workspace-key.json"))
+}
+
+/// Pick up whether encryption is enabled (at startup).
+pub fn init(app_handle: &AppHandle) -> Result<(), String> {
+    ENABLED.store(key_file_path(app_handle)?.exists(), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Turn encryption on with `passphrase` and unlock the session.
+pub fn enable(app_handle: &AppHandle, passphrase: &str) -> Result<(), String> {
+    let path = key_file_path(app_handle)?;
+    if path.exists() {
+        return Err(ALREADY_ENABLED.to_string());
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, KDF_ROUNDS);
+    let key_file = KeyFile {
+        salt: to_hex(&salt),
+        rounds: KDF_ROUNDS,
+        check: to_hex(&seal(&key, CHECK_PLAINTEXT)),
+    };
+    fs::write_json(&path, &key_file)?;
+
+    ENABLED.store(true, Ordering::Relaxed);
+    set_session_key(Some(key));
+    Ok(())
+}
+
+/// Derive the key from `passphrase` and keep it for the session.
+pub fn unlock(app_handle: &AppHandle, passphrase: &str) -> Result<(), String> {
+    let key_file: KeyFile = fs::read_json(&key_file_path(app_handle)?)?;
+    let salt = from_hex(&key_file.salt).ok_or("workspace-key.json: salt")?;
+    let check = from_hex(&key_file.check).ok_or("workspace-key.json: check")?;
+
+    let key = derive_key(passphrase, &salt, key_file.rounds);
+    if open(&key, &check).as_deref() != Some(CHECK_PLAINTEXT) {
+        return Err(WRONG_PASSPHRASE.to_string());
+    }
+    set_session_key(Some(key));
+    Ok(())
+}
+
+/// Forget the session key.
+pub fn lock() {
+    set_session_key(None);
+}
+
+pub fn status() -> EncryptionStatus {
+    EncryptionStatus {
+        enabled: ENABLED.load(Ordering::Relaxed),
+        unlocked: session_key().is_some(),
+        unprotected: UNPROTECTED_ENTRIES,
+    }
+}
+
+/// Content to write for a protected workspace file: encrypted when
+/// encryption is enabled (which requires the session to be unlocked).
+pub fn protect(plain: Vec<u8>) -> Result<Vec<u8>, String> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return Ok(plain);
+    }
+    let key = session_key().ok_or_else(|| LOCKED.to_string())?;
+    Ok(seal(&key, &plain))
+}
+
+/// Plain content of a file written with [`protect`] (or before encryption
+/// was enabled).
+pub fn unprotect(data: Vec<u8>) -> Result<Vec<u8>, String> {
+    if !data.starts_with(MAGIC) {
+        return Ok(data);
+    }
+    let key = session_key().ok_or_else(|| LOCKED.to_string())?;
+    open(&key, &data).ok_or_else(|| WRONG_PASSPHRASE.to_string())
+}
+
+/// PBKDF2-HMAC-SHA256 key for AES-256.
+pub fn derive_key(passphrase: &str, salt: &[u8], rounds: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), salt, rounds, &mut key);
+    key
+}
+
+fn seal(key: &[u8; 32], plain: &[u8]) -> Vec<u8> {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let cipher = Aes256Gcm::new(key.into());
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plain)
+        .expect("AES-GCM encryption of an in-memory buffer cannot fail");
+
+    let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    out
+}
+
+fn open(key: &[u8; 32], data: &[u8]) -> Option<Vec<u8>> {
+    let body = data.strip_prefix(MAGIC)?;
+    if body.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .ok()
+}
+
+fn session_key() -> Option<[u8; 32]> {
+    *SESSION_KEY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn set_session_key(key: Option<[u8; 32]>) {
+    *SESSION_KEY.lock().unwrap_or_else(|e| e.into_inner()) = key;
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn from_hex(text: &str) -> Option<Vec<u8>> {
+    text.as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            let pair = std::str::from_utf8(pair).ok().filter(|p| p.len() == 2)?;
+            u8::from_str_radix(pair, 16).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealed_content_needs_the_same_key() {
+        let key = derive_key("đề thi", b"0123456789abcdef", 1000);
+        let other = derive_key("de thi", b"0123456789abcdef", 1000);
+
+        let sealed = seal(&key, b"{\"questions\":[]}");
+        assert!(sealed.starts_with(MAGIC));
+        assert_eq!(open(&key, &sealed).unwrap(), b"{\"questions\":[]}");
+        assert!(open(&other, &sealed).is_none());
+
+        assert_eq!(from_hex(&to_hex(&[0, 15, 255])).unwrap(), vec![0, 15, 255]);
+
+        let hash = PassphraseHash::with_rounds("đáp án", 1000);
+        assert!(hash.verify("đáp án"));
+        assert!(!hash.verify("dap an"));
+    }
+
+    fn files_under(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .flat_map(|entry| {
+                let path = entry.path();
+                if path.is_dir() {
+                    files_under(&path)
+                } else {
+                    vec![path]
+                }
+            })
+            .collect()
+    }
+
+    /// With encryption on, nothing outside `UNPROTECTED_ENTRIES` keeps the
+    /// question text or answers in plain form
+    #[test]
+    fn workspace_metadata_holds_no_plaintext() {
+        // Another test may have turned it on already: keep its key so its
+        // files stay readable
+        let _ = SESSION_KEY
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(|| derive_key("đề thi", b"0123456789abcdef", 1000));
+        ENABLED.store(true, Ordering::Relaxed);
+
+        let secret = "Thủ đô của Việt Nam là Hà Nội";
+        let dir = std::env::temp_dir().join(format!("siromix-crypto-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        std::fs::write(dir.join("source.docx"), secret).unwrap();
+        std::fs::write(dir.join("assets/image1.png"), secret).unwrap();
+        let parsed = serde_json::json!({ "questions": [{ "stem": secret, "correctAnswer": "A" }] });
+        fs::write_json_protected(&dir.join("parsed.json"), &parsed).unwrap();
+        let mapping = serde_json::json!({ "examCode": "101", "answers": [secret] });
+        fs::write_json_protected(&dir.join("variants/101/mapping.json"), &mapping).unwrap();
+
+        let mut checked = 0;
+        for path in files_under(&dir) {
+            let relative = path.strip_prefix(&dir).unwrap();
+            let first = relative.components().next().unwrap().as_os_str();
+            if UNPROTECTED_ENTRIES.iter().any(|entry| first == *entry) {
+                continue;
+            }
+            let data = std::fs::read(&path).unwrap();
+            assert!(data.starts_with(MAGIC), "{}", relative.display());
+            assert!(!String::from_utf8_lossy(&data).contains(secret), "{}", relative.display());
+            checked += 1;
+        }
+        assert_eq!(checked, 2);
+        let read_back: serde_json::Value = fs::read_json(&dir.join("parsed.json")).unwrap();
+        assert_eq!(read_back, parsed);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
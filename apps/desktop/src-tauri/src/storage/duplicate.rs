@@ -0,0 +1,156 @@
+//! Copy of a job's workspace under a new job id, to try other mixing
+//! settings side by side without analyzing the source again.
+//!
+//! The analysis (source, `parsed.json`, manifest and settings) is copied;
+//! the results of the original job (variants, export and audit logs,
+//! timeline) are not. Extracted images are copied, not hard-linked: the
+//! analysis rewrites them in place (`File::create`, cache copies), so a
+//! shared file would let re-analyzing one job change the other's images.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use super::{export_manifest, export_progress, fs, recovery, timeline};
+
+/// Workspace entries that belong to the original job only
+const NOT_COPIED: &[&str] = &[
+    "variants",
+    "audit.jsonl",
+    export_manifest::EXPORT_MANIFEST_FILE,
+    export_progress::EXPORT_PROGRESS_FILE,
+    timeline::TIMELINE_FILE,
+    recovery::ANALYZING_MARKER,
+];
+
+/// Files holding absolute paths into the workspace, and whether they are
+/// written with `write_json_protected`
+const RELOCATED_FILES: &[(&str, bool)] = &[("parsed.json", true), (crate::docx::assets::CONVERSIONS_FILE, false)];
+
+/// Copy the workspace `from` into the new folder `to` and point the paths
+/// stored in it to `to`. Returns how many files were copied.
+pub fn clone_workspace(from: &Path, to: &Path) -> Result<usize, String> {
+    if to.exists() {
+        return Err(format!("Thư mục đã tồn tại: {}", to.display()));
+    }
+    fs::ensure_dir(to)?;
+
+    let mut copied = 0;
+    for entry in read_dir(from)? {
+        let name = entry.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        if NOT_COPIED.contains(&name.as_str()) || name.ends_with(".tmp") {
+            continue;
+        }
+        copied += copy_tree(&entry, &to.join(&name))?;
+    }
+
+    let prefixes = path_prefixes(from, to);
+    for (file, protected) in RELOCATED_FILES {
+        let path = to.join(file);
+        if path.exists() {
+            relocate_json(&path, &prefixes, *protected)?;
+        }
+    }
+    Ok(copied)
+}
+
+fn read_dir(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Không đọc được thư mục {}: {e}", dir.display()))?
+        .flatten()
+        .map(|e| e.path())
+        .collect();
+    entries.sort();
+    Ok(entries)
+}
+
+fn copy_tree(src: &Path, dest: &Path) -> Result<usize, String> {
+    if src.is_dir() {
+        fs::ensure_dir(dest)?;
+        let mut copied = 0;
+        for entry in read_dir(src)? {
+            copied += copy_tree(&entry, &dest.join(entry.file_name().unwrap_or_default()))?;
+        }
+        return Ok(copied);
+    }
+    fs::copy_file(src, dest)?;
+    Ok(1)
+}
+
+/// Pairs of (old, new) workspace path prefixes, as written by the analysis
+/// (canonical) and as built from the app folder
+fn path_prefixes(from: &Path, to: &Path) -> Vec<(String, String)> {
+    let mut prefixes = Vec::new();
+    if let (Ok(from), Ok(to)) = (from.canonicalize(), to.canonicalize()) {
+        prefixes.push((from.to_string_lossy().into_owned(), to.to_string_lossy().into_owned()));
+    }
+    prefixes.push((from.to_string_lossy().into_owned(), to.to_string_lossy().into_owned()));
+    prefixes
+}
+
+fn relocate_json(path: &Path, prefixes: &[(String, String)], protected: bool) -> Result<(), String> {
+    let mut value: Value = fs::read_json(path)?;
+    relocate_value(&mut value, prefixes);
+    if protected {
+        fs::write_json_protected(path, &value)
+    } else {
+        fs::write_json(path, &value)
+    }
+}
+
+fn relocate_value(value: &mut Value, prefixes: &[(String, String)]) {
+    match value {
+        Value::String(s) => {
+            if let Some((from, to)) = prefixes.iter().find(|(from, _)| s.starts_with(from.as_str())) {
+                *s = format!("{}{}", to, &s[from.len()..]);
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| relocate_value(item, prefixes)),
+        Value::Object(map) => map.values_mut().for_each(|item| relocate_value(item, prefixes)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_analysis_and_copies_assets() {
+        let root = std::env::temp_dir().join(format!("siromix-duplicate-{}", uuid::Uuid::new_v4()));
+        let from = root.join("job-a");
+        let to = root.join("job-b");
+        std::fs::create_dir_all(from.join("assets")).unwrap();
+        std::fs::create_dir_all(from.join("variants/101")).unwrap();
+        std::fs::write(from.join("source.docx"), b"docx").unwrap();
+        std::fs::write(from.join("assets/image1.png"), b"png").unwrap();
+        std::fs::write(from.join("variants/101/mapping.json"), b"{}").unwrap();
+        std::fs::write(from.join(timeline::TIMELINE_FILE), b"").unwrap();
+        let image = from.join("assets/image1.png").canonicalize().unwrap();
+        fs::write_json(
+            &from.join("parsed.json"),
+            &serde_json::json!({ "questions": [{ "stem": [{ "asset_path": image, "rawXml": "" }] }] }),
+        )
+        .unwrap();
+
+        // source.docx, parsed.json, the image
+        assert_eq!(clone_workspace(&from, &to).unwrap(), 3);
+        assert_eq!(std::fs::read(to.join("assets/image1.png")).unwrap(), b"png");
+        // Rewriting an image of one job leaves the other's alone
+        std::fs::write(from.join("assets/image1.png"), b"new").unwrap();
+        assert_eq!(std::fs::read(to.join("assets/image1.png")).unwrap(), b"png");
+        assert_eq!(std::fs::read(to.join("source.docx")).unwrap(), b"docx");
+        assert!(!to.join("variants").exists());
+        assert!(!to.join(timeline::TIMELINE_FILE).exists());
+
+        let parsed: Value = fs::read_json(&to.join("parsed.json")).unwrap();
+        let copied_image = to.join("assets/image1.png").canonicalize().unwrap();
+        assert_eq!(
+            parsed["questions"][0]["stem"][0]["asset_path"],
+            Value::String(copied_image.to_string_lossy().into_owned())
+        );
+        assert!(clone_workspace(&from, &to).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
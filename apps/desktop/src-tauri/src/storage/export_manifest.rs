@@ -0,0 +1,196 @@
+//! Summary of the latest export of a job, `export_manifest.json`: variant
+//! codes, per-variant answer distribution and question counts per chapter,
+//! the files written with their size and SHA-256, and the export options.
+//!
+//! It is written next to the exported files, for whoever archives the run,
+//! and in the job workspace for [`load`].
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::audit::hex_digest;
+use super::{fs, paths};
+use crate::docx::chapters::ChapterCount;
+use crate::docx::excel::MixedExam;
+use crate::docx::export_options::ExportOptions;
+use crate::docx::model::ParsedDoc;
+
+pub const EXPORT_MANIFEST_FILE: &str = "export_manifest.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantSummary {
+    #[serde(rename = "examCode")]
+    pub exam_code: String,
+    #[serde(rename = "questionCount")]
+    pub question_count: usize,
+    /// Questions per correct label ("A" → 10)
+    #[serde(rename = "answerDistribution")]
+    pub answer_distribution: BTreeMap<String, usize>,
+    /// Questions per chapter, when the job was parsed with chapters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sections: Vec<ChapterCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// File name in the output directory
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    /// Unix timestamp (seconds)
+    #[serde(rename = "exportedAt")]
+    pub exported_at: u64,
+    pub author: String,
+    #[serde(rename = "outputDirectory")]
+    pub output_directory: String,
+    pub variants: Vec<VariantSummary>,
+    pub files: Vec<ManifestFile>,
+    pub options: ExportOptions,
+}
+
+pub fn manifest_path(app_handle: &AppHandle, job_id: &str) -> Result<PathBuf, String> {
+    Ok(paths::job_workspace_dir(app_handle, job_id)?.join(EXPORT_MANIFEST_FILE))
+}
+
+/// Summarize an export. `parsed` (the job's original questions) gives the
+/// chapter of each question; `files` are the files just written.
+pub fn build(
+    job_id: &str,
+    author: &str,
+    output_dir: &Path,
+    exams: &[MixedExam],
+    parsed: Option<&ParsedDoc>,
+    files: &[PathBuf],
+    options: &ExportOptions,
+) -> Result<ExportManifest, String> {
+    let variants = exams
+        .iter()
+        .map(|exam| {
+            let mut answer_distribution = BTreeMap::new();
+            let mut sections: Vec<ChapterCount> = Vec::new();
+            for question in &exam.questions {
+                *answer_distribution.entry(question.correct_answer.clone()).or_default() += 1;
+
+                let chapter = parsed
+                    .and_then(|doc| doc.questions.iter().find(|q| q.number as usize == question.original_number))
+                    .and_then(|q| q.chapter.as_ref());
+                if let Some(chapter) = chapter {
+                    match sections.iter_mut().find(|s| &s.chapter == chapter) {
+                        Some(section) => section.count += 1,
+                        None => sections.push(ChapterCount {
+                            chapter: chapter.clone(),
+                            count: 1,
+                        }),
+                    }
+                }
+            }
+            VariantSummary {
+                exam_code: exam.exam_code.clone(),
+                question_count: exam.questions.len(),
+                answer_distribution,
+                sections,
+            }
+        })
+        .collect();
+
+    let files = files
+        .iter()
+        .map(|file| {
+            let data = std::fs::read(file)
+                .map_err(|e| format!("Không đọc được file {}: {e}", file.display()))?;
+            Ok(ManifestFile {
+                name: file
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                size: data.len() as u64,
+                sha256: hex_digest(&data),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(ExportManifest {
+        job_id: job_id.to_string(),
+        exported_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        author: author.to_string(),
+        output_directory: output_dir.to_string_lossy().into_owned(),
+        variants,
+        files,
+        options: options.clone(),
+    })
+}
+
+/// Write `manifest` to the output directory and the job workspace.
+pub fn save(app_handle: &AppHandle, manifest: &ExportManifest) -> Result<(), String> {
+    fs::write_json(&Path::new(&manifest.output_directory).join(EXPORT_MANIFEST_FILE), manifest)?;
+    fs::write_json(&manifest_path(app_handle, &manifest.job_id)?, manifest)
+}
+
+/// Manifest of the latest export of a job, `None` if it was never exported.
+pub fn load(app_handle: &AppHandle, job_id: &str) -> Result<Option<ExportManifest>, String> {
+    let path = manifest_path(app_handle, job_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read_json(&path).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::excel::MixedQuestion;
+    use crate::docx::model::Question;
+
+    #[test]
+    fn summarizes_variants_and_files() {
+        let dir = std::env::temp_dir().join(format!("siromix-export-manifest-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exported = dir.join("De_101.docx");
+        std::fs::write(&exported, b"docx").unwrap();
+
+        let question = |original_number: usize, correct: &str| MixedQuestion {
+            original_number,
+            correct_answer: correct.to_string(),
+            ..Default::default()
+        };
+        let exams = vec![MixedExam {
+            exam_code: "101".to_string(),
+            questions: vec![question(2, "C"), question(1, "C"), question(3, "A")],
+            groups: Vec::new(),
+            parts: Vec::new(),
+        }];
+        let parsed = ParsedDoc {
+            questions: (1..=3)
+                .map(|number| Question {
+                    number,
+                    chapter: Some(if number < 3 { "Chương 1" } else { "Chương 2" }.to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let manifest = build("job", "cô Lan", &dir, &exams, Some(&parsed), &[exported], &ExportOptions::default())
+            .unwrap();
+        let variant = &manifest.variants[0];
+        assert_eq!(variant.question_count, 3);
+        assert_eq!(variant.answer_distribution["C"], 2);
+        assert_eq!(variant.sections[0], ChapterCount { chapter: "Chương 1".to_string(), count: 2 });
+        assert_eq!(manifest.files[0].size, 4);
+        assert_eq!(manifest.files[0].sha256.len(), 64);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
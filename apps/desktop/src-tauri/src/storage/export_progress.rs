@@ -0,0 +1,138 @@
+//! Progress of an export in flight, `<workspace>/export_progress.json`.
+//!
+//! Each variant is recorded once its files are written, with their SHA-256
+//! and a fingerprint of what produced them (questions, options, output
+//! folder). When an export is interrupted (crash, power loss), running it
+//! again skips the variants whose files are still there and unchanged. The
+//! file is removed when an export completes.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::audit::hex_digest;
+use super::fs;
+
+pub const EXPORT_PROGRESS_FILE: &str = "export_progress.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressFile {
+    /// File name in the output directory
+    pub name: String,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantProgress {
+    #[serde(rename = "examCode")]
+    pub exam_code: String,
+    pub fingerprint: String,
+    pub files: Vec<ProgressFile>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportProgress {
+    pub variants: Vec<VariantProgress>,
+}
+
+/// SHA-256 of the JSON form of `value`, to tell whether a variant would be
+/// written the same way again.
+pub fn fingerprint<T: Serialize>(value: &T) -> String {
+    let json = serde_json::to_vec(value).unwrap_or_default();
+    Sha256::digest(&json).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl ExportProgress {
+    /// Progress of the interrupted export of a job, empty if there is none
+    /// (or it cannot be read: the export then starts over).
+    pub fn load(workspace_dir: &Path) -> Self {
+        let path = workspace_dir.join(EXPORT_PROGRESS_FILE);
+        if !path.exists() {
+            return Self::default();
+        }
+        fs::read_json(&path).unwrap_or_default()
+    }
+
+    /// File names of the variant `exam_code` if it was already written with
+    /// the same `fingerprint` and every file in `output_dir` still has its
+    /// recorded checksum.
+    pub fn completed_files(&self, exam_code: &str, fingerprint: &str, output_dir: &Path) -> Option<Vec<String>> {
+        let variant = self
+            .variants
+            .iter()
+            .find(|v| v.exam_code == exam_code && v.fingerprint == fingerprint)?;
+        let intact = variant.files.iter().all(|file| {
+            std::fs::read(output_dir.join(&file.name)).is_ok_and(|data| hex_digest(&data) == file.sha256)
+        });
+        intact.then(|| variant.files.iter().map(|f| f.name.clone()).collect())
+    }
+
+    /// Record the files of a variant just written and save the progress.
+    pub fn record(
+        &mut self,
+        workspace_dir: &Path,
+        exam_code: &str,
+        fingerprint: String,
+        output_dir: &Path,
+        file_names: &[String],
+    ) -> Result<(), String> {
+        let files = file_names
+            .iter()
+            .map(|name| {
+                let data = std::fs::read(output_dir.join(name))
+                    .map_err(|e| format!("Không đọc được file {}: {e}", name))?;
+                Ok(ProgressFile {
+                    name: name.clone(),
+                    sha256: hex_digest(&data),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        self.variants.retain(|v| v.exam_code != exam_code);
+        self.variants.push(VariantProgress {
+            exam_code: exam_code.to_string(),
+            fingerprint,
+            files,
+        });
+        fs::write_json_atomic(&workspace_dir.join(EXPORT_PROGRESS_FILE), self)
+    }
+
+    /// Forget the progress once the export is complete.
+    pub fn clear(workspace_dir: &Path) {
+        let _ = std::fs::remove_file(workspace_dir.join(EXPORT_PROGRESS_FILE));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_only_unchanged_variants() {
+        let dir = std::env::temp_dir().join(format!("siromix-export-progress-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("De_101.docx"), b"docx").unwrap();
+
+        let mut progress = ExportProgress::default();
+        progress
+            .record(&dir, "101", fingerprint(&"v1"), &dir, &["De_101.docx".to_string()])
+            .unwrap();
+
+        let progress = ExportProgress::load(&dir);
+        assert_eq!(
+            progress.completed_files("101", &fingerprint(&"v1"), &dir),
+            Some(vec!["De_101.docx".to_string()])
+        );
+        assert_eq!(progress.completed_files("101", &fingerprint(&"v2"), &dir), None);
+
+        std::fs::write(dir.join("De_101.docx"), b"truncated").unwrap();
+        assert_eq!(progress.completed_files("101", &fingerprint(&"v1"), &dir), None);
+
+        ExportProgress::clear(&dir);
+        assert!(ExportProgress::load(&dir).variants.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,11 @@
 use std::fs;
 use std::path::Path;
 
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::crypto;
+
 pub fn ensure_dir(path: &Path) -> Result<(), String> {
     fs::create_dir_all(path)
         .map_err(|e| format!("Không tạo được thư mục {}: {}", path.display(), e))
@@ -30,3 +35,69 @@ pub fn copy_file(src: &Path, dest: &Path) -> Result<(), String> {
         )
     })
 }
+
+/// Remove a directory and its contents; a missing directory is not an error.
+pub fn remove_dir(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Ok(());
+    }
+    fs::remove_dir_all(path)
+        .map_err(|e| format!("Không xoá được thư mục {}: {e}", path.display()))
+}
+
+/// Write raw bytes to `path`, creating parent dirs.
+pub fn write_bytes(path: &Path, data: &[u8]) -> Result<(), String> {
+    ensure_parent_dir(path)?;
+    fs::write(path, data).map_err(|e| format!("Không ghi được file {}: {e}", path.display()))
+}
+
+/// Serialize `value` as pretty JSON and write it to `path`, creating parent dirs.
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    ensure_parent_dir(path)?;
+
+    let json = serde_json::to_vec_pretty(value)
+        .map_err(|e| format!("Không serialize được {}: {e}", path.display()))?;
+
+    fs::write(path, json)
+        .map_err(|e| format!("Không ghi được file {}: {e}", path.display()))
+}
+
+/// Like `write_json`, but writes a temporary file next to `path` and renames
+/// it over `path`, so concurrent readers never see a partial file.
+pub fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+    write_json(&temp_path, value)?;
+
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Không ghi được file {}: {e}", path.display())
+    })
+}
+
+/// Like `write_json`, encrypted when workspace encryption is enabled (see
+/// `crypto`). Use for files revealing exam content or answers.
+pub fn write_json_protected<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    ensure_parent_dir(path)?;
+
+    let json = serde_json::to_vec_pretty(value)
+        .map_err(|e| format!("Không serialize được {}: {e}", path.display()))?;
+
+    fs::write(path, crypto::protect(json)?)
+        .map_err(|e| format!("Không ghi được file {}: {e}", path.display()))
+}
+
+/// Read and deserialize a JSON file written by `write_json` (or
+/// `write_json_protected`).
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    if !path.exists() {
+        return Err(format!("Không tìm thấy file {}", path.display()));
+    }
+
+    let data = fs::read(path)
+        .map_err(|e| format!("Không đọc được file {}: {e}", path.display()))?;
+    let data = crypto::unprotect(data)?;
+
+    serde_json::from_slice(&data)
+        .map_err(|e| format!("Không parse được {}: {e}", path.display()))
+}
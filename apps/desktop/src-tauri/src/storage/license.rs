@@ -0,0 +1,185 @@
+//! Optional institutional license, `<app_data>/SiroMix/license.json`.
+//!
+//! A license key is `<payload>.<signature>`, both hex: the payload is the
+//! JSON of a [`License`] (school, seats, expiry, features) and the signature
+//! its Ed25519 signature by the vendor. Keys are verified offline against
+//! the public key compiled into the build (`SIROMIX_LICENSE_PUBLIC_KEY`, hex,
+//! at build time); a build without one cannot activate licenses and runs
+//! unlicensed, which is the normal state for the free features.
+//!
+//! Seats are informative: there is no server to count installations.
+
+use std::path::PathBuf;
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::crypto::from_hex;
+use super::{fs, paths};
+
+/// Vendor public key, hex (32 bytes), set when building release binaries
+const PUBLIC_KEY_HEX: Option<&str> = option_env!("SIROMIX_LICENSE_PUBLIC_KEY");
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct License {
+    #[serde(rename = "schoolName")]
+    pub school_name: String,
+    pub seats: u32,
+    /// Unix timestamp (seconds); `None` for a perpetual license
+    #[serde(rename = "expiresAt", default)]
+    pub expires_at: Option<u64>,
+    /// Premium features covered, e.g. "pdfExport", "moodleExport"
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LicenseState {
+    /// No license activated
+    Unlicensed,
+    Active,
+    Expired,
+    /// The stored key no longer verifies (edited file, other build)
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LicenseStatus {
+    pub state: LicenseState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<License>,
+}
+
+impl LicenseStatus {
+    /// Whether an active license covers `feature`.
+    pub fn allows(&self, feature: &str) -> bool {
+        self.state == LicenseState::Active
+            && self.license.as_ref().is_some_and(|l| l.features.iter().any(|f| f == feature))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LicenseError {
+    /// This build has no vendor public key
+    NotConfigured,
+    /// Malformed key or bad signature
+    InvalidKey,
+    Expired,
+}
+
+impl LicenseError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LicenseError::NotConfigured => "E200_LICENSE_NOT_SUPPORTED",
+            LicenseError::InvalidKey => "E201_LICENSE_INVALID",
+            LicenseError::Expired => "E202_LICENSE_EXPIRED",
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LicenseFile {
+    key: String,
+}
+
+pub fn license_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(paths::app_root_dir(app_handle)?.join("license.json"))
+}
+
+fn public_key() -> Option<VerifyingKey> {
+    let bytes: [u8; 32] = from_hex(PUBLIC_KEY_HEX?.trim())?.try_into().ok()?;
+    VerifyingKey::from_bytes(&bytes).ok()
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The license in `key` if its signature verifies with `public_key` and it
+/// has not expired at `now`.
+pub fn verify(key: &str, public_key: &VerifyingKey, now: u64) -> Result<License, LicenseError> {
+    let (payload_hex, signature_hex) = key.trim().split_once('.').ok_or(LicenseError::InvalidKey)?;
+    let payload = from_hex(payload_hex).ok_or(LicenseError::InvalidKey)?;
+    let signature: [u8; 64] = from_hex(signature_hex)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(LicenseError::InvalidKey)?;
+    public_key
+        .verify_strict(&payload, &Signature::from_bytes(&signature))
+        .map_err(|_| LicenseError::InvalidKey)?;
+
+    let license: License = serde_json::from_slice(&payload).map_err(|_| LicenseError::InvalidKey)?;
+    if license.expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(LicenseError::Expired);
+    }
+    Ok(license)
+}
+
+/// Verify `key` and store it; the previous license is replaced. Errors are
+/// catalog codes, or details when the file cannot be written.
+pub fn activate(app_handle: &AppHandle, key: &str) -> Result<License, String> {
+    let public_key = public_key().ok_or_else(|| LicenseError::NotConfigured.code().to_string())?;
+    let license = verify(key, &public_key, now()).map_err(|e| e.code().to_string())?;
+    fs::write_json(&license_path(app_handle)?, &LicenseFile { key: key.trim().to_string() })?;
+    Ok(license)
+}
+
+/// State of the stored license, checked again on every call so an expiry
+/// takes effect without restarting.
+pub fn status(app_handle: &AppHandle) -> Result<LicenseStatus, String> {
+    let path = license_path(app_handle)?;
+    if !path.exists() {
+        return Ok(LicenseStatus { state: LicenseState::Unlicensed, license: None });
+    }
+    let file: LicenseFile = fs::read_json(&path)?;
+    let Some(public_key) = public_key() else {
+        return Ok(LicenseStatus { state: LicenseState::Invalid, license: None });
+    };
+    Ok(match verify(&file.key, &public_key, now()) {
+        Ok(license) => LicenseStatus { state: LicenseState::Active, license: Some(license) },
+        Err(LicenseError::Expired) => LicenseStatus {
+            state: LicenseState::Expired,
+            // Still shown so the school knows which license to renew
+            license: file
+                .key
+                .split_once('.')
+                .and_then(|(payload, _)| from_hex(payload))
+                .and_then(|payload| serde_json::from_slice(&payload).ok()),
+        },
+        Err(_) => LicenseStatus { state: LicenseState::Invalid, license: None },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::crypto::to_hex;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    #[test]
+    fn verifies_signed_keys_offline() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = signing_key.verifying_key();
+        let license = License {
+            school_name: "THCS Nguyễn An Ninh".to_string(),
+            seats: 30,
+            expires_at: Some(2_000),
+            features: vec!["pdfExport".to_string()],
+        };
+        let payload = serde_json::to_vec(&license).unwrap();
+        let key = format!("{}.{}", to_hex(&payload), to_hex(&signing_key.sign(&payload).to_bytes()));
+
+        assert_eq!(verify(&key, &public_key, 1_000), Ok(license));
+        assert_eq!(verify(&key, &public_key, 2_000), Err(LicenseError::Expired));
+
+        // Seats raised without re-signing
+        let forged = serde_json::to_vec(&License { seats: 300, ..verify(&key, &public_key, 0).unwrap() }).unwrap();
+        let forged_key = format!("{}.{}", to_hex(&forged), key.split_once('.').unwrap().1);
+        assert_eq!(verify(&forged_key, &public_key, 1_000), Err(LicenseError::InvalidKey));
+        assert_eq!(verify("not a key", &public_key, 1_000), Err(LicenseError::InvalidKey));
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::crypto::PassphraseHash;
+use super::{fs, paths};
+use crate::docx::parser::ParseOptions;
+
+/// Job-level metadata persisted as `<workspace>/manifest.json`.
+///
+/// Every field has a default so older workspaces (without a manifest, or
+/// with fewer fields) still load.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobManifest {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    /// Master seed of the latest mix; all variant seeds derive from it
+    #[serde(
+        rename = "masterSeed",
+        skip_serializing_if = "Option::is_none",
+        with = "crate::docx::mixer::optional_seed_string"
+    )]
+    pub master_seed: Option<u64>,
+    /// Exam codes produced by the latest mix
+    #[serde(rename = "examCodes")]
+    pub exam_codes: Vec<String>,
+    /// Whether the latest mix shuffled (I), (II), … statements, reused when
+    /// a single variant is rebuilt
+    #[serde(rename = "shuffleStatements")]
+    pub shuffle_statements: bool,
+    /// Review state of the variants of the latest mix, by exam code
+    /// (missing = draft)
+    #[serde(rename = "variantStates", skip_serializing_if = "BTreeMap::is_empty")]
+    pub variant_states: BTreeMap<String, VariantState>,
+    /// Settings of the latest analysis, including per-paragraph overrides
+    #[serde(rename = "parseOptions")]
+    pub parse_options: ParseOptions,
+    /// When set, the answer key (XLSX, variant mappings) is only released
+    /// with this passphrase
+    #[serde(rename = "answerKeyPassphrase", skip_serializing_if = "Option::is_none")]
+    pub answer_key_passphrase: Option<PassphraseHash>,
+    /// Unix timestamp (seconds) before which the answer key is not released,
+    /// so it cannot circulate before the exam is administered
+    #[serde(rename = "answerKeyUnlockAt", skip_serializing_if = "Option::is_none")]
+    pub answer_key_unlock_at: Option<u64>,
+    /// Passphrase that releases the answer key before `answer_key_unlock_at`
+    #[serde(rename = "answerKeyOverride", skip_serializing_if = "Option::is_none")]
+    pub answer_key_override: Option<PassphraseHash>,
+}
+
+/// draft → approved → exported. Approved and exported variants are frozen:
+/// changing the analysis or mixing again needs an explicit `force`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VariantState {
+    #[default]
+    Draft,
+    Approved,
+    Exported,
+}
+
+impl JobManifest {
+    pub fn variant_state(&self, code: &str) -> VariantState {
+        self.variant_states.get(code).copied().unwrap_or_default()
+    }
+
+    /// Exam codes of the latest mix that are approved or exported
+    pub fn frozen_variants(&self) -> Vec<String> {
+        self.exam_codes
+            .iter()
+            .filter(|code| self.variant_state(code) != VariantState::Draft)
+            .cloned()
+            .collect()
+    }
+
+
+    /// Whether the answer key may be released at `now`; before the unlock
+    /// time only with the override passphrase. `Err` holds the unlock time.
+    pub fn answer_key_released(&self, now: u64, override_passphrase: Option<&str>) -> Result<(), u64> {
+        match self.answer_key_unlock_at {
+            Some(unlock_at) if now < unlock_at => {
+                let overridden = self
+                    .answer_key_override
+                    .as_ref()
+                    .is_some_and(|hash| override_passphrase.is_some_and(|p| hash.verify(p)));
+                if overridden {
+                    Ok(())
+                } else {
+                    Err(unlock_at)
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn manifest_path(app_handle: &AppHandle, job_id: &str) -> Result<PathBuf, String> {
+    Ok(paths::job_workspace_dir(app_handle, job_id)?.join("manifest.json"))
+}
+
+/// Load the manifest at `path`, or a fresh one for `job_id` if it doesn't exist yet.
+pub fn load_from(path: &Path, job_id: &str) -> Result<JobManifest, String> {
+    if !path.exists() {
+        return Ok(JobManifest {
+            job_id: job_id.to_string(),
+            ..Default::default()
+        });
+    }
+    fs::read_json(path)
+}
+
+pub fn load(app_handle: &AppHandle, job_id: &str) -> Result<JobManifest, String> {
+    load_from(&manifest_path(app_handle, job_id)?, job_id)
+}
+
+pub fn save(app_handle: &AppHandle, manifest: &JobManifest) -> Result<(), String> {
+    fs::write_json(&manifest_path(app_handle, &manifest.job_id)?, manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_key_locked_until_release_time() {
+        let manifest = JobManifest {
+            answer_key_unlock_at: Some(1_000),
+            answer_key_override: Some(PassphraseHash::with_rounds("mở sớm", 1000)),
+            ..Default::default()
+        };
+
+        assert_eq!(manifest.answer_key_released(999, None), Err(1_000));
+        assert_eq!(manifest.answer_key_released(999, Some("sai")), Err(1_000));
+        assert_eq!(manifest.answer_key_released(999, Some("mở sớm")), Ok(()));
+        assert_eq!(manifest.answer_key_released(1_000, None), Ok(()));
+        assert_eq!(JobManifest::default().answer_key_released(0, None), Ok(()));
+    }
+
+    #[test]
+    fn frozen_variants_of_latest_mix() {
+        let mut manifest = JobManifest {
+            exam_codes: vec!["101".to_string(), "102".to_string(), "103".to_string()],
+            ..Default::default()
+        };
+        manifest.variant_states.insert("101".to_string(), VariantState::Approved);
+        manifest.variant_states.insert("103".to_string(), VariantState::Exported);
+        // State left over from an earlier mix
+        manifest.variant_states.insert("999".to_string(), VariantState::Approved);
+
+        assert_eq!(manifest.variant_state("102"), VariantState::Draft);
+        assert_eq!(manifest.frozen_variants(), vec!["101".to_string(), "103".to_string()]);
+    }
+}
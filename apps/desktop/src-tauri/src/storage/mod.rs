@@ -1,2 +1,15 @@
 pub mod paths;
 pub mod fs;
+pub mod manifest;
+pub mod settings;
+pub mod crypto;
+pub mod audit;
+pub mod export_manifest;
+pub mod export_progress;
+pub mod share_bundle;
+pub mod recovery;
+pub mod license;
+pub mod past_exams;
+pub mod timeline;
+pub mod duplicate;
+pub mod parsed_doc;
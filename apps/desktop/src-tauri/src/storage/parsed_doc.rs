@@ -0,0 +1,109 @@
+//! Writes of `<workspace>/parsed.json`.
+//!
+//! The file is written by each analysis and updated in the background once
+//! WMF/EMF conversions finish (image paths pointed at the PNGs). Both go
+//! through here and hold the workspace's lock, and an update reads the file
+//! under that lock, so a background update can never write back a copy read
+//! before a reanalysis or a manual answer edit saved a newer one.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use super::fs;
+use crate::docx::model::ParsedDoc;
+
+pub const PARSED_FILE: &str = "parsed.json";
+
+/// One lock per workspace whose `parsed.json` was written this session
+static WORKSPACE_LOCKS: Mutex<Vec<(PathBuf, Arc<Mutex<()>>)>> = Mutex::new(Vec::new());
+
+fn workspace_lock(workspace_dir: &Path) -> Arc<Mutex<()>> {
+    let mut locks = WORKSPACE_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((_, lock)) = locks.iter().find(|(dir, _)| dir == workspace_dir) {
+        return lock.clone();
+    }
+    let lock = Arc::new(Mutex::new(()));
+    locks.push((workspace_dir.to_path_buf(), lock.clone()));
+    lock
+}
+
+/// Replace `parsed.json` with `parsed_doc` (encrypted when workspace
+/// encryption is on).
+pub fn save(workspace_dir: &Path, parsed_doc: &ParsedDoc) -> Result<(), String> {
+    let lock = workspace_lock(workspace_dir);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    fs::write_json_protected(&workspace_dir.join(PARSED_FILE), parsed_doc)
+}
+
+/// Apply `change` to `parsed.json` as it is now and save it. Returns false,
+/// writing nothing, when the workspace has no `parsed.json`.
+pub fn update(workspace_dir: &Path, change: impl FnOnce(&mut ParsedDoc)) -> Result<bool, String> {
+    let lock = workspace_lock(workspace_dir);
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    let path = workspace_dir.join(PARSED_FILE);
+    if !path.exists() {
+        return Ok(false);
+    }
+    let mut parsed_doc: ParsedDoc = fs::read_json(&path)?;
+    change(&mut parsed_doc);
+    fs::write_json_protected(&path, &parsed_doc)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::Question;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    fn doc(numbers: &[u32]) -> ParsedDoc {
+        ParsedDoc {
+            questions: numbers
+                .iter()
+                .map(|&number| Question {
+                    number,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn background_update_never_overwrites_a_newer_save() {
+        let dir = std::env::temp_dir().join(format!("siromix-parsed-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(!update(&dir, |_| {}).unwrap());
+        save(&dir, &doc(&[1, 2])).unwrap();
+
+        // A conversion update reads the old analysis and is still working on
+        // it when a reanalysis saves its result
+        let (started, wait) = mpsc::channel();
+        let background = {
+            let dir = dir.clone();
+            std::thread::spawn(move || {
+                update(&dir, |parsed| {
+                    started.send(()).unwrap();
+                    std::thread::sleep(Duration::from_millis(200));
+                    parsed.questions[0].correct_label = "B".to_string();
+                })
+                .unwrap()
+            })
+        };
+        wait.recv().unwrap();
+        save(&dir, &doc(&[1, 2, 3])).unwrap();
+        assert!(background.join().unwrap());
+
+        let saved: ParsedDoc = fs::read_json(&dir.join(PARSED_FILE)).unwrap();
+        assert_eq!(saved.questions.len(), 3);
+
+        // The next update starts from the reanalysis
+        update(&dir, |parsed| parsed.questions[2].correct_label = "C".to_string()).unwrap();
+        let saved: ParsedDoc = fs::read_json(&dir.join(PARSED_FILE)).unwrap();
+        assert_eq!(saved.questions.len(), 3);
+        assert_eq!(saved.questions[2].correct_label, "C");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,184 @@
+//! Exams of previous years registered as a history corpus,
+//! `<app_data>/SiroMix/past_exams.json`.
+//!
+//! Only the folded text of each question is kept (see
+//! `bank::dedup::normalized_text`), not the documents. Every analysis
+//! compares the questions of the new exam with the corpus and warns about
+//! those too close to a question already given (`W012_SIMILAR_TO_PAST_EXAM`).
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::{fs, paths};
+use crate::bank::dedup::{normalized_text, similarity_at_least};
+use crate::docx::model::{ParsedDoc, Question};
+
+/// Similarity from which a question is reported as reused
+pub const SIMILARITY_THRESHOLD: f64 = 0.85;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PastExamCorpus {
+    pub exams: Vec<PastExam>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PastExam {
+    pub id: String,
+    /// Shown in the warnings, e.g. "Cuối kỳ I 2023-2024"
+    pub label: String,
+    pub file_name: String,
+    /// Unix timestamp (seconds) of the registration
+    pub added_at: u64,
+    pub questions: Vec<PastQuestion>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PastQuestion {
+    pub number: u32,
+    /// Folded text of the stem and options
+    pub text: String,
+}
+
+/// A registered exam without its questions, for the settings list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PastExamInfo {
+    pub id: String,
+    pub label: String,
+    pub file_name: String,
+    pub added_at: u64,
+    pub question_count: usize,
+}
+
+impl From<&PastExam> for PastExamInfo {
+    fn from(exam: &PastExam) -> Self {
+        Self {
+            id: exam.id.clone(),
+            label: exam.label.clone(),
+            file_name: exam.file_name.clone(),
+            added_at: exam.added_at,
+            question_count: exam.questions.len(),
+        }
+    }
+}
+
+/// A question of the new exam close to a question of a past one
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PastExamMatch {
+    pub question_number: u32,
+    pub exam_label: String,
+    pub past_number: u32,
+    pub similarity: f64,
+}
+
+impl PastExam {
+    pub fn from_parsed(label: &str, file_name: &str, parsed: &ParsedDoc) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            label: label.trim().to_string(),
+            file_name: file_name.to_string(),
+            added_at: crate::bank::now_secs(),
+            questions: parsed
+                .questions
+                .iter()
+                .map(|q| PastQuestion {
+                    number: q.number,
+                    text: normalized_text(q),
+                })
+                .filter(|q| !q.text.is_empty())
+                .collect(),
+        }
+    }
+}
+
+pub fn corpus_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(paths::app_root_dir(app_handle)?.join("past_exams.json"))
+}
+
+/// Load the corpus, empty when no exam was registered yet.
+pub fn load(app_handle: &AppHandle) -> Result<PastExamCorpus, String> {
+    let path = corpus_path(app_handle)?;
+    if !path.exists() {
+        return Ok(PastExamCorpus::default());
+    }
+    fs::read_json(&path)
+}
+
+pub fn save(app_handle: &AppHandle, corpus: &PastExamCorpus) -> Result<(), String> {
+    fs::write_json_atomic(&corpus_path(app_handle)?, corpus)
+}
+
+/// For each question reaching `threshold` against the corpus, its closest
+/// past question
+pub fn find_similar(questions: &[Question], corpus: &PastExamCorpus, threshold: f64) -> Vec<PastExamMatch> {
+    questions
+        .iter()
+        .filter_map(|question| {
+            let text = normalized_text(question);
+            if text.is_empty() {
+                return None;
+            }
+            corpus
+                .exams
+                .iter()
+                .flat_map(|exam| exam.questions.iter().map(move |past| (exam, past)))
+                .filter_map(|(exam, past)| {
+                    let similarity = similarity_at_least(&text, &past.text, threshold)?;
+                    Some(PastExamMatch {
+                        question_number: question.number,
+                        exam_label: exam.label.clone(),
+                        past_number: past.number,
+                        similarity,
+                    })
+                })
+                .max_by(|a, b| a.similarity.total_cmp(&b.similarity))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::model::Segment;
+
+    fn question(number: u32, stem: &str) -> Question {
+        Question {
+            number,
+            stem: vec![Segment::Text {
+                text: stem.to_string(),
+                raw_xml: String::new(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_the_closest_past_question() {
+        let past = ParsedDoc {
+            questions: vec![
+                question(3, "Một vật dao động điều hoà với biên độ 5 cm, chu kì 2 s."),
+                question(7, "Tính đạo hàm của hàm số y = x^2."),
+            ],
+            ..Default::default()
+        };
+        let corpus = PastExamCorpus {
+            exams: vec![PastExam::from_parsed(" Cuối kỳ I 2023 ", "de_2023.docx", &past)],
+        };
+        let new_questions = [
+            question(1, "Tính đạo hàm của hàm số y = x^3."),
+            question(2, "Nêu định nghĩa dao động tắt dần."),
+        ];
+        let matches = find_similar(&new_questions, &corpus, SIMILARITY_THRESHOLD);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].question_number, 1);
+        assert_eq!(matches[0].past_number, 7);
+        assert_eq!(matches[0].exam_label, "Cuối kỳ I 2023");
+        assert_eq!(PastExamInfo::from(&corpus.exams[0]).question_count, 2);
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 use tauri::{AppHandle, Manager};
 
@@ -9,10 +9,100 @@ pub fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
         .map_err(|e| format!("Không lấy được app_data_dir: {e}"))
 }
 
-pub fn job_workspace_dir(app_handle: &AppHandle, job_id: &str) -> Result<PathBuf, String> {
+/// `<app_data>/SiroMix` - root for everything the app stores locally
+pub fn app_root_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
     let mut base = app_data_dir(app_handle)?;
     base.push("SiroMix");
+    Ok(base)
+}
+
+/// `<app_data>/SiroMix/cache/conversions` - WMF/EMF conversions shared by
+/// all jobs, keyed by content hash
+pub fn conversion_cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let mut base = app_root_dir(app_handle)?;
+    base.push("cache");
+    base.push("conversions");
+    Ok(base)
+}
+
+/// `<app_data>/SiroMix/cache/paraphrases` - answers of the paraphrasing
+/// service, keyed by request hash
+pub fn paraphrase_cache_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let mut base = app_root_dir(app_handle)?;
+    base.push("cache");
+    base.push("paraphrases");
+    Ok(base)
+}
+
+/// `<app_data>/SiroMix/cache/bank_index.sqlite` - full-text index of the
+/// question bank; kept local even when the bank is on a shared drive
+pub fn bank_index_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let mut base = app_root_dir(app_handle)?;
+    base.push("cache");
+    base.push("bank_index.sqlite");
+    Ok(base)
+}
+
+/// `<app_data>/SiroMix/exporters` - one folder per installed sidecar exporter
+pub fn exporters_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let mut base = app_root_dir(app_handle)?;
+    base.push("exporters");
+    Ok(base)
+}
+
+/// `<app_data>/SiroMix/rules` - validation scripts (`*.rhai`) run on every analysis
+pub fn rules_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let mut base = app_root_dir(app_handle)?;
+    base.push("rules");
+    Ok(base)
+}
+
+/// `<app_data>/SiroMix/jobs` - one workspace per job
+pub fn jobs_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let mut base = app_root_dir(app_handle)?;
     base.push("jobs");
-    base.push(job_id);
     Ok(base)
 }
+
+/// `name` if it is one plain path component (a job ID, an exam code): not
+/// empty, no separator or drive, not `.` or `..`. IDs come from the
+/// frontend and are joined to workspace paths, so anything else could reach
+/// outside the workspace.
+pub fn safe_component(name: &str) -> Result<&str, String> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(component)), None)
+            if component == name && !name.contains(['/', '\\', ':', '\0']) =>
+        {
+            Ok(name)
+        }
+        _ => Err("E108_INVALID_ID".to_string()),
+    }
+}
+
+pub fn job_workspace_dir(app_handle: &AppHandle, job_id: &str) -> Result<PathBuf, String> {
+    let mut base = jobs_dir(app_handle)?;
+    base.push(safe_component(job_id)?);
+    Ok(base)
+}
+
+pub fn variant_dir(app_handle: &AppHandle, job_id: &str, exam_code: &str) -> Result<PathBuf, String> {
+    let mut base = job_workspace_dir(app_handle, job_id)?;
+    base.push("variants");
+    base.push(safe_component(exam_code)?);
+    Ok(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plain_components_are_safe() {
+        assert_eq!(safe_component("3f2a-job"), Ok("3f2a-job"));
+        assert_eq!(safe_component("101"), Ok("101"));
+        for name in ["", ".", "..", "../x", "a/b", "a\\b", "/etc", "C:", "x/"] {
+            assert!(safe_component(name).is_err(), "{name:?}");
+        }
+    }
+}
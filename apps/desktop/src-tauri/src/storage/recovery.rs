@@ -0,0 +1,160 @@
+//! Jobs left half-written by a crash.
+//!
+//! Each analysis writes `<workspace>/.analyzing` before it starts and removes
+//! it when it returns, successful or not; a marker still present at startup
+//! means the app died mid-analysis. A workspace with its `source.docx` but no
+//! `parsed.json` was never analyzed to the end either. Both are collected
+//! once at startup ([`init`]) so an analysis running later is not mistaken
+//! for an interrupted one; the user then re-analyzes or discards each job.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::paths;
+
+pub const ANALYZING_MARKER: &str = ".analyzing";
+
+static INCOMPLETE: Mutex<Vec<IncompleteJob>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IncompleteReason {
+    /// The app stopped while the job was being analyzed
+    Interrupted,
+    /// The source was copied but no analysis result was saved
+    NotAnalyzed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IncompleteJob {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    pub reason: IncompleteReason,
+    /// Unix timestamp (seconds) of the last change to the workspace source
+    #[serde(rename = "modifiedAt")]
+    pub modified_at: u64,
+}
+
+/// What to do with an incomplete job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecoveryAction {
+    /// Analyze the workspace source again with the job's saved options
+    Reanalyze,
+    /// Delete the workspace
+    Discard,
+}
+
+/// Marks a workspace as being analyzed for as long as it is alive.
+pub struct AnalysisMarker(PathBuf);
+
+impl AnalysisMarker {
+    pub fn create(workspace_dir: &Path) -> Self {
+        let path = workspace_dir.join(ANALYZING_MARKER);
+        if let Err(e) = std::fs::write(&path, b"") {
+            eprintln!("[Recovery] Could not write {}: {}", path.display(), e);
+        }
+        Self(path)
+    }
+}
+
+impl Drop for AnalysisMarker {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Incomplete jobs under `jobs_dir`, most recent first.
+pub fn scan(jobs_dir: &Path) -> Vec<IncompleteJob> {
+    let Ok(entries) = std::fs::read_dir(jobs_dir) else {
+        return Vec::new();
+    };
+    let mut jobs: Vec<IncompleteJob> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let source = dir.join("source.docx");
+            let reason = if dir.join(ANALYZING_MARKER).exists() {
+                IncompleteReason::Interrupted
+            } else if source.exists() && !dir.join("parsed.json").exists() {
+                IncompleteReason::NotAnalyzed
+            } else {
+                return None;
+            };
+            let modified_at = std::fs::metadata(&source)
+                .or_else(|_| entry.metadata())
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            Some(IncompleteJob {
+                job_id: entry.file_name().to_string_lossy().into_owned(),
+                reason,
+                modified_at,
+            })
+        })
+        .collect();
+    jobs.sort_by_key(|job| std::cmp::Reverse(job.modified_at));
+    jobs
+}
+
+/// Look for incomplete jobs; called once at startup.
+pub fn init(app_handle: &AppHandle) -> Result<(), String> {
+    let jobs = scan(&paths::jobs_dir(app_handle)?);
+    if !jobs.is_empty() {
+        println!("[Recovery] {} incomplete job(s) found", jobs.len());
+    }
+    *INCOMPLETE.lock().unwrap_or_else(|e| e.into_inner()) = jobs;
+    Ok(())
+}
+
+/// Incomplete jobs found at startup and not dealt with yet.
+pub fn incomplete_jobs() -> Vec<IncompleteJob> {
+    INCOMPLETE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Forget `job_id` once it was re-analyzed or discarded.
+pub fn resolve(job_id: &str) {
+    INCOMPLETE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|job| job.job_id != job_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_interrupted_and_unanalyzed_jobs() {
+        let dir = std::env::temp_dir().join(format!("siromix-recovery-{}", uuid::Uuid::new_v4()));
+        for job in ["done", "crashed", "half"] {
+            std::fs::create_dir_all(dir.join(job)).unwrap();
+            std::fs::write(dir.join(job).join("source.docx"), b"docx").unwrap();
+        }
+        std::fs::write(dir.join("done").join("parsed.json"), b"{}").unwrap();
+        std::fs::write(dir.join("crashed").join("parsed.json"), b"{").unwrap();
+        {
+            let _marker = AnalysisMarker::create(&dir.join("done"));
+            std::mem::forget(AnalysisMarker::create(&dir.join("crashed")));
+        }
+
+        let mut jobs: Vec<(String, IncompleteReason)> =
+            scan(&dir).into_iter().map(|job| (job.job_id, job.reason)).collect();
+        jobs.sort();
+        assert_eq!(
+            jobs,
+            vec![
+                ("crashed".to_string(), IncompleteReason::Interrupted),
+                ("half".to_string(), IncompleteReason::NotAnalyzed),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
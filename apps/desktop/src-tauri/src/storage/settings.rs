@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::{fs, paths};
+use crate::bank::paraphrase::ParaphraseSettings;
+use crate::docx::assets::ConversionParams;
+use crate::docx::exam_preset::ExamPreset;
+use crate::docx::lexicon::Lexicon;
+use crate::i18n::Lang;
+
+/// Application-wide settings persisted as `<app_data>/SiroMix/settings.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Language of backend messages
+    pub language: Lang,
+    /// Name recorded as the author of question bank edits and exports (OS user
+    /// name if empty)
+    #[serde(rename = "authorName")]
+    pub author_name: String,
+    /// Folder of the question bank, e.g. on a shared network drive
+    /// (empty = `<app_data>/SiroMix/bank`)
+    #[serde(rename = "bankDir")]
+    pub bank_dir: String,
+    /// Terms flagged when analyzing a document (politically sensitive
+    /// phrasing, outdated place names), matched as whole words ignoring case
+    #[serde(rename = "forbiddenTerms")]
+    pub forbidden_terms: Vec<String>,
+    /// How WMF/EMF equations are converted to PNG when analyzing
+    #[serde(rename = "wmfConversion")]
+    pub wmf_conversion: ConversionParams,
+    /// Version manifest checked by `check_for_updates` (empty = no check)
+    #[serde(rename = "updateManifestUrl")]
+    pub update_manifest_url: String,
+    /// Wording of the exam boilerplate per subject (subject name as typed in
+    /// the export options), for subjects taught in another language
+    #[serde(rename = "subjectLexicons")]
+    pub subject_lexicons: BTreeMap<String, Lexicon>,
+    /// The school's own exam presets, offered before the built-in ones (a
+    /// preset with the name of a built-in one replaces it)
+    #[serde(rename = "examPresets")]
+    pub exam_presets: Vec<ExamPreset>,
+    /// Service rewording bank questions (`paraphrase_bank_question`)
+    pub paraphrase: ParaphraseSettings,
+}
+
+pub fn settings_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    Ok(paths::app_root_dir(app_handle)?.join("settings.json"))
+}
+
+/// Load settings, falling back to defaults when the file doesn't exist yet.
+pub fn load(app_handle: &AppHandle) -> Result<AppSettings, String> {
+    let path = settings_path(app_handle)?;
+    if !path.exists() {
+        return Ok(AppSettings::default());
+    }
+    fs::read_json(&path)
+}
+
+pub fn save(app_handle: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    fs::write_json(&settings_path(app_handle)?, settings)
+}
+
+/// Wording set for `subject` (case and surrounding spaces ignored)
+pub fn subject_lexicon(settings: &AppSettings, subject: &str) -> Option<Lexicon> {
+    let subject = subject.trim().to_lowercase();
+    settings
+        .subject_lexicons
+        .iter()
+        .find(|(name, _)| name.trim().to_lowercase() == subject)
+        .map(|(_, lexicon)| lexicon.clone())
+}
+
+/// Name recorded as the author of bank edits and exports: the name set in
+/// the settings, or the OS user name.
+pub fn current_author(app_handle: &AppHandle) -> String {
+    load(app_handle)
+        .ok()
+        .map(|s| s.author_name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_default()
+}
@@ -0,0 +1,154 @@
+//! Ready-to-send package of the latest export of a job: one zip with the
+//! variants and the answer key, and a cover note (subject, exam name, exam
+//! codes) to paste into the message.
+//!
+//! `mailto:` links cannot carry attachments, so the mail client is opened
+//! with the subject and the note filled in, and the zip is attached by hand.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use zip::write::{FileOptions, ZipWriter};
+use zip::CompressionMethod;
+
+use super::export_manifest::ExportManifest;
+use crate::docx::file_names::{self, FileNameContext};
+
+/// Cover note stored in the zip next to the exam files
+pub const COVER_NOTE_FILE: &str = "Ghi_chu.txt";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareBundle {
+    /// The zip, in the export's output directory
+    #[serde(rename = "zipPath")]
+    pub zip_path: String,
+    /// Mail subject
+    pub subject: String,
+    #[serde(rename = "coverNote")]
+    pub cover_note: String,
+    /// `mailto:` link with the subject and the cover note
+    pub mailto: String,
+}
+
+/// Zip the files of `manifest` into its output directory and write the
+/// cover note. `exam_name` is the name teachers know the exam by
+/// ("Kiểm tra giữa học kỳ II").
+pub fn build(manifest: &ExportManifest, exam_name: &str) -> Result<ShareBundle, String> {
+    let options = &manifest.options;
+    let output_dir = Path::new(&manifest.output_directory);
+    let context = FileNameContext::new(&options.subject, &options.grade);
+    let zip_name = format!("{}.zip", file_names::render("Bo_De_{subject}_{date}", &context));
+    let zip_path = output_dir.join(&zip_name);
+
+    let subject = format!("{} - {} - {}", exam_name, options.subject, options.grade);
+    let cover_note = cover_note(manifest, exam_name, &zip_name);
+
+    let files: Vec<PathBuf> = manifest.files.iter().map(|f| output_dir.join(&f.name)).collect();
+    write_zip(&zip_path, &files, &cover_note)?;
+
+    Ok(ShareBundle {
+        zip_path: zip_path.to_string_lossy().into_owned(),
+        mailto: format!("mailto:?subject={}&body={}", encode(&subject), encode(&cover_note)),
+        subject,
+        cover_note,
+    })
+}
+
+fn cover_note(manifest: &ExportManifest, exam_name: &str, zip_name: &str) -> String {
+    let options = &manifest.options;
+    let codes: Vec<&str> = manifest.variants.iter().map(|v| v.exam_code.as_str()).collect();
+    let mut lines = vec![
+        "Kính gửi quý thầy cô,".to_string(),
+        String::new(),
+        format!("Gửi kèm bộ đề {} môn {} ({}).", exam_name, options.subject, options.grade),
+        format!("Các mã đề: {}.", codes.join(", ")),
+    ];
+    if let Some(count) = manifest.variants.first().map(|v| v.question_count) {
+        lines.push(format!("Số câu mỗi đề: {}.", count));
+    }
+    lines.push(format!(
+        "Tệp đính kèm {} gồm {} tệp (đề và bảng đáp án).",
+        zip_name,
+        manifest.files.len()
+    ));
+    if !manifest.author.is_empty() {
+        lines.push(String::new());
+        lines.push(manifest.author.clone());
+    }
+    lines.join("\n")
+}
+
+fn write_zip(zip_path: &Path, files: &[PathBuf], cover_note: &str) -> Result<(), String> {
+    let zip_err = |e: zip::result::ZipError| format!("Không tạo được tệp nén: {e}");
+    let io_err = |e: std::io::Error| format!("Không tạo được tệp nén: {e}");
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let file = std::fs::File::create(zip_path)
+        .map_err(|e| format!("Không ghi được file {}: {e}", zip_path.display()))?;
+    let mut zip = ZipWriter::new(file);
+    for path in files {
+        let data = std::fs::read(path).map_err(|e| format!("Không đọc được file {}: {e}", path.display()))?;
+        let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        zip.start_file(name, options).map_err(zip_err)?;
+        zip.write_all(&data).map_err(io_err)?;
+    }
+    zip.start_file(COVER_NOTE_FILE, options).map_err(zip_err)?;
+    zip.write_all(cover_note.as_bytes()).map_err(io_err)?;
+    zip.finish().map_err(zip_err)?;
+    Ok(())
+}
+
+/// Percent-encoding for `mailto:` fields (RFC 6068): everything but
+/// unreserved characters, so line breaks become `%0A` and spaces `%20`.
+fn encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::export_options::ExportOptions;
+    use crate::storage::export_manifest::{ManifestFile, VariantSummary};
+
+    #[test]
+    fn zips_exported_files_with_cover_note() {
+        let dir = std::env::temp_dir().join(format!("siromix-share-bundle-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("De_101.docx"), b"docx").unwrap();
+        std::fs::write(dir.join("Dap_An.xlsx"), b"xlsx").unwrap();
+
+        let file = |name: &str| ManifestFile { name: name.to_string(), size: 4, sha256: String::new() };
+        let manifest = ExportManifest {
+            job_id: "job".to_string(),
+            exported_at: 0,
+            author: "cô Lan".to_string(),
+            output_directory: dir.to_string_lossy().into_owned(),
+            variants: vec![VariantSummary {
+                exam_code: "101".to_string(),
+                question_count: 40,
+                answer_distribution: Default::default(),
+                sections: Vec::new(),
+            }],
+            files: vec![file("De_101.docx"), file("Dap_An.xlsx")],
+            options: ExportOptions::default(),
+        };
+
+        let bundle = build(&manifest, "Kiểm tra giữa kỳ").unwrap();
+        assert!(bundle.cover_note.contains("Các mã đề: 101."));
+        assert!(bundle.mailto.starts_with("mailto:?subject=Ki%E1%BB%83m%20tra"));
+        assert!(!bundle.mailto.contains('\n'));
+
+        let archive = zip::ZipArchive::new(std::fs::File::open(&bundle.zip_path).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(names, vec!["Dap_An.xlsx", "De_101.docx", COVER_NOTE_FILE]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,188 @@
+//! History of a job for the timeline panel, `<workspace>/timeline.jsonl`
+//! (one JSON event per line, oldest first).
+//!
+//! Unlike the audit log, the timeline is informative only: it is not
+//! chained, and failing to write it never fails the action it describes.
+//! Each new event is also sent to the frontend as [`TIMELINE_EVENT`].
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use super::{paths, settings};
+
+pub const TIMELINE_FILE: &str = "timeline.jsonl";
+
+/// Name of the live event emitted for each new entry
+pub const TIMELINE_EVENT: &str = "job-timeline";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum JobEvent {
+    /// Created as a copy of another job (`duplicate_job`)
+    #[serde(rename_all = "camelCase")]
+    DuplicatedFrom { source_job_id: String },
+    /// Source analyzed (again); `ok = false` when it has blocking errors
+    #[serde(rename_all = "camelCase")]
+    Analyzed { ok: bool, error_count: usize, warning_count: usize },
+    /// A paragraph was moved by hand (`reassign_paragraph`)
+    #[serde(rename_all = "camelCase")]
+    ParagraphReassigned { paragraph_index: usize },
+    /// Correct answer of a question chosen by hand (`None` = choice removed)
+    #[serde(rename_all = "camelCase")]
+    AnswerChosen { question_number: u32, label: Option<String> },
+    /// Formatting fixed automatically in the source
+    #[serde(rename_all = "camelCase")]
+    Autofixed { change_count: usize },
+    /// Variants mixed. The master seed is not recorded: the timeline is
+    /// readable without the answer-key passphrase, and the seed rebuilds
+    /// every key (it stays in the manifest)
+    #[serde(rename_all = "camelCase")]
+    Mixed { exam_codes: Vec<String> },
+    /// Two questions of one variant swapped by hand (display positions)
+    #[serde(rename_all = "camelCase")]
+    QuestionsSwapped { exam_code: String, a: u32, b: u32 },
+    /// One variant mixed again with a new seed
+    #[serde(rename_all = "camelCase")]
+    VariantRegenerated { exam_code: String },
+    /// A variant approved (frozen) or sent back to draft
+    #[serde(rename_all = "camelCase")]
+    VariantApproval { exam_code: String, approved: bool },
+    /// An exported variant edited in Word checked against the stored key
+    #[serde(rename_all = "camelCase")]
+    VariantReimported { exam_code: String, discrepancy_count: usize, answers_updated: usize },
+    /// Variants and answer key written
+    #[serde(rename_all = "camelCase")]
+    Exported { exam_codes: Vec<String>, output_directory: String },
+    #[serde(rename_all = "camelCase")]
+    AnswerKeyExported { path: String },
+    #[serde(rename_all = "camelCase")]
+    ShareBundleCreated { path: String },
+    /// Written with an exporter of the registry (see `exporters`)
+    #[serde(rename_all = "camelCase")]
+    CustomExported { exporter_id: String, file_count: usize },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    #[serde(rename = "jobId")]
+    pub job_id: String,
+    /// Unix timestamp (seconds)
+    pub at: u64,
+    pub author: String,
+    #[serde(flatten)]
+    pub event: JobEvent,
+}
+
+pub fn timeline_path(app_handle: &AppHandle, job_id: &str) -> Result<PathBuf, String> {
+    Ok(paths::job_workspace_dir(app_handle, job_id)?.join(TIMELINE_FILE))
+}
+
+/// Append `event` to the timeline of `job_id`, by the current author.
+pub fn record(app_handle: &AppHandle, job_id: &str, event: JobEvent) -> Result<TimelineEntry, String> {
+    let entry = TimelineEntry {
+        job_id: job_id.to_string(),
+        at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        author: settings::current_author(app_handle),
+        event,
+    };
+    append(&timeline_path(app_handle, job_id)?, &entry)?;
+    Ok(entry)
+}
+
+/// Append `entry` to the timeline at `path`.
+pub fn append(path: &Path, entry: &TimelineEntry) -> Result<(), String> {
+    let line = serde_json::to_string(entry).map_err(|e| format!("Không serialize được sự kiện: {e}"))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Không ghi được file {}: {e}", path.display()))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Không ghi được file {}: {e}", path.display()))
+}
+
+/// Every event of the timeline, oldest first. Lines that do not parse (an
+/// event kind from a newer version) are skipped.
+pub fn load(path: &Path) -> Result<Vec<TimelineEntry>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut text = std::fs::read_to_string(path).map_err(|e| format!("Không đọc được file {}: {e}", path.display()))?;
+    if text.contains(r#""masterSeed""#) {
+        text = redact_seeds(&text);
+        std::fs::write(path, &text).map_err(|e| format!("Không ghi được file {}: {e}", path.display()))?;
+    }
+    Ok(text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Timelines written before `Mixed` events left out the master seed still
+/// hold it: the same lines without it
+fn redact_seeds(text: &str) -> String {
+    text.lines()
+        .map(|line| match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(serde_json::Value::Object(mut entry)) if entry.contains_key("masterSeed") => {
+                entry.remove("masterSeed");
+                serde_json::Value::Object(entry).to_string()
+            }
+            _ => line.to_string(),
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_and_reads_events_in_order() {
+        let dir = std::env::temp_dir().join(format!("siromix-timeline-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(TIMELINE_FILE);
+        let entry = |event| TimelineEntry {
+            job_id: "job".to_string(),
+            at: 1,
+            author: "cô Lan".to_string(),
+            event,
+        };
+
+        append(&path, &entry(JobEvent::Analyzed { ok: true, error_count: 0, warning_count: 2 })).unwrap();
+        append(&path, &entry(JobEvent::AnswerChosen { question_number: 12, label: Some("B".to_string()) })).unwrap();
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut f| writeln!(f, r#"{{"kind":"fromTheFuture","jobId":"job","at":2,"author":""}}"#))
+            .unwrap();
+        // Written by an older version, with the master seed
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut f| writeln!(f, r#"{{"jobId":"job","at":3,"author":"","kind":"mixed","examCodes":["101"],"masterSeed":42}}"#))
+            .unwrap();
+
+        let events: Vec<JobEvent> = load(&path).unwrap().into_iter().map(|e| e.event).collect();
+        assert_eq!(
+            events,
+            vec![
+                JobEvent::Analyzed { ok: true, error_count: 0, warning_count: 2 },
+                JobEvent::AnswerChosen { question_number: 12, label: Some("B".to_string()) },
+                JobEvent::Mixed { exam_codes: vec!["101".to_string()] },
+            ]
+        );
+        let line = std::fs::read_to_string(&path).unwrap();
+        assert!(!line.contains("masterSeed"));
+        assert!(line.starts_with(r#"{"jobId":"job","at":1,"author":"cô Lan","kind":"analyzed","ok":true,"errorCount":0"#));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
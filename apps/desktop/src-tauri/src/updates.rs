@@ -0,0 +1,174 @@
+// src-tauri/src/updates.rs
+//! Update check against a version manifest published by the school IT or
+//! the maintainers, at the URL set in the settings (`https://`, or `file://`
+//! on a shared drive):
+//!
+//! ```json
+//! { "version": "0.3.1", "notes": "Sửa lỗi đọc đáp án gạch chân", "url": "https://…/SiroMix_0.3.1.msi" }
+//! ```
+//!
+//! The manifest is downloaded over HTTPS only (certificates checked, no
+//! redirect to plain HTTP) and must fit in [`MAX_MANIFEST_BYTES`].
+
+use std::cmp::Ordering;
+use std::io::Read;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Version of this build
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Seconds before giving up on the manifest server
+const FETCH_TIMEOUT_SECS: u64 = 15;
+
+/// A manifest is a few lines of JSON; anything bigger is not one
+const MAX_MANIFEST_BYTES: u64 = 64 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+struct LatestRelease {
+    version: String,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateCheck {
+    #[serde(rename = "currentVersion")]
+    pub current_version: String,
+    #[serde(rename = "latestVersion")]
+    pub latest_version: String,
+    #[serde(rename = "updateAvailable")]
+    pub update_available: bool,
+    #[serde(rename = "releaseNotes")]
+    pub release_notes: String,
+    #[serde(rename = "downloadUrl")]
+    pub download_url: String,
+}
+
+/// Download the manifest at `manifest_url` and compare it with this build.
+pub fn check(manifest_url: &str) -> Result<UpdateCheck, String> {
+    let body = fetch(manifest_url)?;
+    let latest: LatestRelease =
+        serde_json::from_slice(&body).map_err(|e| format!("Manifest phiên bản không hợp lệ: {e}"))?;
+    let update_available = compare_versions(&latest.version, CURRENT_VERSION)
+        .ok_or_else(|| format!("Số phiên bản không hợp lệ: {}", latest.version))?
+        == Ordering::Greater;
+
+    Ok(UpdateCheck {
+        current_version: CURRENT_VERSION.to_string(),
+        latest_version: latest.version,
+        update_available,
+        release_notes: latest.notes,
+        download_url: latest.url,
+    })
+}
+
+fn fetch(url: &str) -> Result<Vec<u8>, String> {
+    let reader: Box<dyn Read> = if let Some(path) = url.strip_prefix("file://") {
+        Box::new(std::fs::File::open(path).map_err(|e| format!("{path}: {e}"))?)
+    } else if url.starts_with("https://") {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+            .https_only(true)
+            .build();
+        agent.get(url).call().map_err(|e| e.to_string())?.into_reader()
+    } else {
+        return Err(format!("Địa chỉ manifest phải là https:// hoặc file://: {url}"));
+    };
+    read_limited(reader, MAX_MANIFEST_BYTES)
+}
+
+/// Everything `reader` gives, or an error past `limit` bytes
+fn read_limited(reader: impl Read, limit: u64) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    reader
+        .take(limit + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| e.to_string())?;
+    if body.len() as u64 > limit {
+        return Err(format!("Manifest phiên bản lớn hơn {} KB", limit / 1024));
+    }
+    Ok(body)
+}
+
+/// Semantic version order of `a` and `b` ("1.2.0" < "1.10.0", "1.0.0-beta.2"
+/// < "1.0.0"); build metadata is ignored. `None` if either does not parse.
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let (a_core, a_pre) = parse_version(a)?;
+    let (b_core, b_pre) = parse_version(b)?;
+    Some(a_core.cmp(&b_core).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_prerelease(a, b),
+    }))
+}
+
+fn parse_version(version: &str) -> Option<([u64; 3], Option<&str>)> {
+    let version = version.trim().trim_start_matches('v');
+    let version = version.split('+').next()?;
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let mut numbers = core.split('.').map(|n| n.parse::<u64>().ok());
+    let parsed = [numbers.next()??, numbers.next()??, numbers.next()??];
+    numbers.next().is_none().then_some((parsed, pre))
+}
+
+/// Dot-separated identifiers; numeric ones compare as numbers and sort
+/// before alphanumeric ones.
+fn compare_prerelease(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split('.');
+    let mut b_parts = b.split('.');
+    loop {
+        match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => {
+                let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+                    (Ok(x), Ok(y)) => x.cmp(&y),
+                    (Ok(_), Err(_)) => Ordering::Less,
+                    (Err(_), Ok(_)) => Ordering::Greater,
+                    (Err(_), Err(_)) => x.cmp(y),
+                };
+                if order != Ordering::Equal {
+                    return order;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_semantic_versions() {
+        assert_eq!(compare_versions("0.10.0", "0.9.3"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("v1.2.3", "1.2.3+build.5"), Some(Ordering::Equal));
+        assert_eq!(compare_versions("1.0.0-beta.2", "1.0.0"), Some(Ordering::Less));
+        assert_eq!(compare_versions("1.0.0-beta.11", "1.0.0-beta.2"), Some(Ordering::Greater));
+        assert_eq!(compare_versions("1.0.0-alpha", "1.0.0-alpha.1"), Some(Ordering::Less));
+        assert_eq!(compare_versions("1.0", "1.0.0"), None);
+    }
+
+    #[test]
+    fn fetches_only_small_manifests_over_https_or_files() {
+        assert!(fetch("http://example.com/latest.json").is_err());
+        assert!(fetch("ftp://example.com/latest.json").is_err());
+        assert_eq!(read_limited(&b"{}"[..], 2).unwrap(), b"{}");
+        assert!(read_limited(&b"{ }"[..], 2).is_err());
+
+        let path = std::env::temp_dir().join(format!("siromix-latest-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&path, r#"{"version":"0.0.1"}"#).unwrap();
+        let manifest = check(&format!("file://{}", path.display())).unwrap();
+        assert!(!manifest.update_available);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,5 +1,6 @@
 // components/shared/SegmentRenderer.tsx
 import type { FC } from "react";
+import { convertFileSrc } from "@tauri-apps/api/core";
 import { MathBlock } from "../../lib/mathjax";
 import { ommlToMathml } from "../../lib/omml";
 import { ImageSegment } from "../ImageSegment";
@@ -12,7 +13,7 @@ interface SegmentRendererProps {
 }
 
 /**
- * Shared component for rendering different segment types (Text, Math, Image)
+ * Shared component for rendering different segment types (Text, Math, Image, Symbol, Blank, Audio)
  * Extracted from PreviewPage to avoid duplication
  */
 export const SegmentRenderer: FC<SegmentRendererProps> = ({
@@ -59,6 +60,38 @@ export const SegmentRenderer: FC<SegmentRendererProps> = ({
       );
     }
 
+    case "Symbol": {
+      // Ký tự <w:sym> chưa có mã Unicode tương ứng: hiển thị bằng font gốc
+      const code = parseInt(segment.char, 16);
+      return (
+        <span
+          key={index}
+          className={className}
+          style={{ fontFamily: segment.font }}
+          title={`${segment.font} ${segment.char}`}
+        >
+          {Number.isNaN(code) ? "?" : String.fromCharCode(code)}
+        </span>
+      );
+    }
+
+    case "Blank":
+      // Chỗ trống: hiển thị độ dài cố định như khi xuất đề
+      return (
+        <span key={index} className={className}>
+          {segment.width < 15 ? ".".repeat(10) : ".".repeat(25)}{" "}
+        </span>
+      );
+
+    case "Audio":
+      // Bài nghe: nghe thử ngay trong bản xem trước
+      return (
+        <span key={index} className={`inline-flex items-center gap-2 ${className}`}>
+          <audio controls preload="none" src={convertFileSrc(segment.asset_path, "asset")} />
+          <span className="text-xs text-gray-500">{segment.fileName}</span>
+        </span>
+      );
+
     default:
       return null;
   }
@@ -79,6 +79,8 @@ export const MIX_PROGRESS_STAGES = [
 export const FILE_EXTENSIONS = {
   DOCX: ".docx",
   XLSX: ".xlsx",
+  /** Ngân hàng câu hỏi xuất ra (exportBank) */
+  SIROMIX: ".siromix",
 } as const;
 
 /**
@@ -3,6 +3,7 @@ import { useState, useCallback } from "react";
 import { analyzeDocx } from "../services/tauri/analyzeDocx";
 import { useMixStore } from "../store/mixStore";
 import { ERROR_CODES, ERROR_MESSAGES } from "../constants/exam";
+import { errorMessage } from "../lib/errors";
 
 /**
  * Custom hook for managing exam file analysis
@@ -14,10 +15,11 @@ export function useExamAnalysis() {
   const [error, setError] = useState<string | null>(null);
 
   /**
-   * Analyze a DOCX file or use cached result
+   * Analyze a DOCX file or use cached result. `newJobId` is the job id given
+   * to a file opened with the app (a new one is generated otherwise).
    */
   const analyze = useCallback(
-    async (sourcePath: string) => {
+    async (sourcePath: string, newJobId?: string) => {
       if (!sourcePath) {
         setError(ERROR_MESSAGES[ERROR_CODES.INVALID_FILE_PATH]);
         return { success: false, error: ERROR_MESSAGES[ERROR_CODES.INVALID_FILE_PATH] };
@@ -33,11 +35,15 @@ export function useExamAnalysis() {
       setError(null);
 
       try {
-        const jobId = crypto.randomUUID();
+        const jobId = newJobId ?? crypto.randomUUID();
         const result = await analyzeDocx({ jobId, sourcePath });
 
         console.log("analyze_docx result", result);
 
+        if (result.warnings?.length) {
+          console.warn("analyze_docx warnings", result.warnings);
+        }
+
         if (result.ok) {
           setJobId(result.jobId);
           setIsAnalyzing(false);
@@ -49,8 +55,7 @@ export function useExamAnalysis() {
         setError(errorMsg);
         return { success: false, error: errorMsg };
       } catch (err) {
-        const message =
-          err instanceof Error ? err.message : String(err ?? "Unknown error");
+        const message = errorMessage(err);
         setIsAnalyzing(false);
         setError(message);
         return { success: false, error: message };
@@ -0,0 +1,24 @@
+/**
+ * Lỗi trả về từ Tauri command: `{ code, message }` với message đã bản địa hoá
+ * theo cài đặt ngôn ngữ ở backend.
+ */
+export type CommandError = {
+  code: string;
+  message: string;
+};
+
+export function isCommandError(err: unknown): err is CommandError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as CommandError).code === "string" &&
+    typeof (err as CommandError).message === "string"
+  );
+}
+
+/** Lấy thông báo hiển thị được từ lỗi bất kỳ (Error, CommandError, string). */
+export function errorMessage(err: unknown): string {
+  if (err instanceof Error) return err.message;
+  if (isCommandError(err)) return err.message;
+  return String(err ?? "Unknown error");
+}
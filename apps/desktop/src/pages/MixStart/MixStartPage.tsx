@@ -6,6 +6,7 @@ import { LoadingOverlay } from "../../components/LoadingOverlay";
 import { FlowNavigation } from "../../components/FlowNavigation";
 import { useExamAnalysis } from "../../hooks/useExamAnalysis";
 import { useMixStore } from "../../store/mixStore";
+import { onOpenedFiles, takeOpenedFiles, type OpenedFile } from "../../services/tauri/openedFiles";
 import type { ExamMetadata } from "../../store/mixStore";
 import { ERROR_CODES, ERROR_MESSAGES, DEFAULT_EXAM_CODES, DEFAULT_DURATION, DEFAULT_NUM_VARIANTS } from "../../constants/exam";
 import {
@@ -36,6 +37,8 @@ export function MixStartPage() {
   const [isErrorModalOpen, setIsErrorModalOpen] = useState(false);
   const [errorMessage, setErrorMessage] = useState<string | null>(null);
   const [sourcePath, setSourcePath] = useState<string | null>(null);
+  // Đề mở bằng "Mở bằng SiroMix" và mã job được cấp cho nó
+  const [openedExam, setOpenedExam] = useState<{ path: string; jobId: string } | null>(null);
 
   // Form state
   const [examName, setExamName] = useState(cachedMetadata?.examName || "");
@@ -84,6 +87,29 @@ export function MixStartPage() {
     }
   }, [selectedFilePath]);
 
+  // Đề được mở cùng ứng dụng (nhấp đúp tệp .docx): chọn sẵn tệp đó
+  useEffect(() => {
+    const selectOpened = (files: OpenedFile[]) => {
+      const exam = files
+        .filter((file): file is Extract<OpenedFile, { kind: "exam" }> => file.kind === "exam")
+        .pop();
+      if (!exam) return;
+      clearAnalysis();
+      setSelectedFile(exam.path);
+      setSourcePath(exam.path);
+      setHasFile(true);
+      setOpenedExam({ path: exam.path, jobId: exam.jobId });
+    };
+
+    takeOpenedFiles().then(selectOpened).catch((error) => {
+      console.error("Failed to read opened files:", error);
+    });
+    const unlisten = onOpenedFiles(selectOpened);
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [clearAnalysis, setSelectedFile]);
+
   const handleFileChange = (event: ChangeEvent<HTMLInputElement>) => {
     const file = event.target.files?.[0] ?? null;
     setHasFile(!!file);
@@ -218,7 +244,7 @@ export function MixStartPage() {
     }
 
     // Need to analyze the file using custom hook
-    const result = await analyze(sourcePath);
+    const result = await analyze(sourcePath, openedExam?.path === sourcePath ? openedExam.jobId : undefined);
 
     if (result.success && result.jobId) {
       navigate(`/preview/${result.jobId}`);
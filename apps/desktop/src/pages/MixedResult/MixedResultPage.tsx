@@ -7,10 +7,11 @@ import { FlowNavigation } from "../../components/FlowNavigation";
 import { AnswerKeyTable } from "./components/AnswerKeyTable";
 import { useMixStore } from "../../store/mixStore";
 import { exportMixedExams } from "../../services/tauri/exportMixed";
+import { errorMessage } from "../../lib/errors";
 
 export const MixedResultPage: FC = () => {
   const navigate = useNavigate();
-  const { mixedExams, parsedData, jobId } = useMixStore();
+  const { mixedExams, parsedData, jobId, examMetadata } = useMixStore();
   const [exporting, setExporting] = useState(false);
   const [exportSuccess, setExportSuccess] = useState(false);
   const [exportError, setExportError] = useState<string | null>(null);
@@ -48,8 +49,17 @@ export const MixedResultPage: FC = () => {
       const result = await exportMixedExams({
         jobId: jobId || "",
         exams: mixedExams,
-        originalAnswers: originalAnswers,
         outputDir: selectedPath as string,
+        // Phần đầu đề theo thông tin đã nhập ở bước đầu
+        options: examMetadata
+          ? {
+              schoolName: examMetadata.schoolName,
+              examName: examMetadata.examName,
+              subject: examMetadata.subject,
+              grade: examMetadata.gradeLevel,
+              durationMinutes: examMetadata.durationMinutes,
+            }
+          : undefined,
       });
 
       console.log("Export result:", result);
@@ -61,7 +71,7 @@ export const MixedResultPage: FC = () => {
     } catch (error) {
       console.error("Export error:", error);
       setExporting(false);
-      setExportError(error instanceof Error ? error.message : String(error));
+      setExportError(errorMessage(error));
     }
   };
 
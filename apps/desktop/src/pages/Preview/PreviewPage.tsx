@@ -9,6 +9,7 @@ import { AcademicCapIcon, XMarkIcon, CheckIcon } from "@heroicons/react/24/outli
 import { useMixStore, type ParsedDoc } from "../../store/mixStore";
 import { mixExams } from "../../services/tauri/mixExams";
 import { MIX_PROGRESS_STAGES } from "../../constants/exam";
+import { errorMessage } from "../../lib/errors";
 
 export const PreviewPage: FC = () => {
   const { jobId } = useParams<{ jobId: string }>();
@@ -52,9 +53,7 @@ export const PreviewPage: FC = () => {
       })
       .catch((err) => {
         if (isCancelled) return;
-        const message =
-          err instanceof Error ? err.message : String(err ?? "Unknown error");
-        setError(message);
+        setError(errorMessage(err));
         setLoading(false);
       });
 
@@ -85,7 +84,8 @@ export const PreviewPage: FC = () => {
       const variants = await mixExams(
         parsed,
         numVariants,
-        examMetadata?.customExamCodes
+        examMetadata?.customExamCodes,
+        jobId
       );
 
       // Mark final stage as complete
@@ -0,0 +1,56 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { CommandError } from "../../lib/errors";
+
+export type IntakeState = "queued" | "analyzing" | "analyzed" | "hasErrors" | "failed";
+
+export type IntakeItem = {
+  jobId: string;
+  path: string;
+  /** Tên tệp để hiển thị */
+  name: string;
+  state: IntakeState;
+  /** Số câu đã phân tích và lưu */
+  questionCount: number;
+  errorCount: number;
+  warningCount: number;
+  /** Lý do không phân tích được (state = "failed") */
+  error?: CommandError;
+};
+
+export type IntakeStatus = {
+  /** Các tệp theo thứ tự thả vào, kèm kết quả của các tệp đã xong */
+  items: IntakeItem[];
+  running: boolean;
+  finished: number;
+  total: number;
+};
+
+export type EnqueueAnalysisResult = {
+  queued: IntakeItem[];
+  /** Tệp không phải .docx hoặc không tồn tại */
+  rejected: string[];
+};
+
+/**
+ * Đưa nhiều đề (.docx) kéo thả cùng lúc vào hàng đợi; chúng được phân tích
+ * lần lượt, mỗi tệp một job, với cấu hình mặc định.
+ */
+export async function enqueueAnalysis(paths: string[]): Promise<EnqueueAnalysisResult> {
+  return invoke<EnqueueAnalysisResult>("enqueue_analysis", { paths });
+}
+
+/** Trạng thái hàng đợi và kết quả tổng hợp của các tệp đã phân tích */
+export async function getAnalysisQueue(): Promise<IntakeStatus> {
+  return invoke<IntakeStatus>("get_analysis_queue");
+}
+
+/** Xoá các tệp đã xong khỏi danh sách; tệp đang chờ vẫn được giữ */
+export async function clearAnalysisQueue(): Promise<IntakeStatus> {
+  return invoke<IntakeStatus>("clear_analysis_queue");
+}
+
+/** Nhận mỗi thay đổi trạng thái của một tệp trong hàng đợi; gọi hàm trả về để huỷ */
+export async function onAnalysisQueue(handler: (item: IntakeItem) => void): Promise<UnlistenFn> {
+  return listen<IntakeItem>("analysis-queue", (event) => handler(event.payload));
+}
@@ -3,17 +3,88 @@ import { invoke } from "@tauri-apps/api/core";
 export type AnalyzeDocxPayload = {
   jobId: string;
   sourcePath: string;
+  /** Số phương án tối đa mỗi câu (mặc định 8: A–H) */
+  maxOptions?: number;
+  /** Quy tắc gán đoạn không có tiền tố sau phương án cuối */
+  continuation?: ContinuationRules;
+  /**
+   * Đề song ngữ Việt/Anh: "Question N." và nhãn phương án lặp lại là phần
+   * tiếng Anh của câu/phương án trước; đáp án đọc từ nhãn tiếng Việt
+   */
+  bilingual?: boolean;
+  /** Giữ XML gốc của từng câu cho chế độ xuất giữ nguyên định dạng */
+  keepRawXml?: boolean;
+  /** Nhận dạng chữ trong hình (OCR, cần tesseract) để tìm kiếm trong ngân hàng */
+  ocrImages?: boolean;
+  /** Dùng đáp án chọn tay (setCorrectLabel) cho các câu chưa đánh dấu đáp án (E020) */
+  continueWithManualAnswers?: boolean;
+  /**
+   * Bỏ qua các câu lỗi thay vì chặn cả đề. Câu nhắc tới câu bị bỏ được cảnh
+   * báo (W014); lỗi của cả đề (vd. E024) vẫn chặn phân tích.
+   */
+  allowPartial?: boolean;
+  /** Nhận tiêu đề chương ("CHƯƠNG 1: …") và gắn chương cho các câu sau đó */
+  detectChapters?: boolean;
+  /** Phân tích lại cả khi có mã đề đã duyệt/đã xuất (E116); chúng trở về bản nháp */
+  force?: boolean;
+  /**
+   * Phân tích lại đề đã sửa mà vẫn giữ đáp án chọn tay và đoạn gán lại: chúng
+   * được chuyển sang đúng câu/đoạn mới, phần không giữ được nằm trong `conflicts`
+   */
+  preserveEdits?: boolean;
 };
 
+export type ContinuationRules = {
+  /** Dòng trống sau các phương án kết thúc câu hỏi */
+  blankLineSeparates?: boolean;
+  /** Style (w:pStyle) luôn thuộc câu kế tiếp, vd. đoạn đọc hiểu */
+  stemStyles?: string[];
+  /** Đoạn thụt lề ít hơn ngưỡng này (twips) thuộc câu kế tiếp */
+  minOptionIndent?: number;
+};
+
+export type ParagraphAssignment = "stem" | "option" | "nextQuestion";
+
 export type AnalyzeDocxError = {
   code: string;
   questionNumber: number;
+  /** Thông báo đã bản địa hoá */
+  message: string;
+  /** Nội dung khớp, vd. từ ngữ bị cấm (W004_FORBIDDEN_TERM) */
+  detail?: string;
 };
 
 export type AnalyzeDocxResult = {
   ok: boolean;
   jobId: string;
+  message: string;
   errors?: AnalyzeDocxError[];
+  /** Cảnh báo không chặn việc trộn (vd. W001_IMAGE_MISSING) */
+  warnings?: AnalyzeDocxError[];
+  /** Số các câu bị bỏ qua khi allowPartial (lý do trong errors) */
+  skipped?: number[];
+  /** Số câu theo từng chương (khi detectChapters) */
+  chapters?: { chapter: string; count: number }[];
+  /** Phân bố đáp án đúng của đề gốc */
+  answerDistribution?: AnswerDistribution;
+  /** Chỉnh sửa tay không giữ lại được (khi preserveEdits) */
+  conflicts?: EditConflict[];
+};
+
+/** `questionNumber` / `paragraphIndex` theo lần phân tích trước */
+export type EditConflict =
+  | { kind: "questionMissing"; questionNumber: number; label: string }
+  | { kind: "optionChanged"; questionNumber: number; label: string }
+  | { kind: "paragraphMissing"; paragraphIndex: number; preview: string };
+
+export type AnswerDistribution = {
+  /** Số câu theo nhãn đáp án đúng, vd. { A: 12, B: 9 } */
+  counts: Record<string, number>;
+  /** Nhãn đúng nhiều nhất */
+  dominant?: string;
+  dominantRate: number;
+  /** Lệch nhiều (trên 50% cùng một nhãn): trộn ít tác dụng hoặc đánh dấu sai */
+  biased: boolean;
 };
 
 export async function analyzeDocx(
@@ -22,3 +93,67 @@ export async function analyzeDocx(
   // Tauri command nhận tham số tên là `payload`, nên cần wrap lại.
   return invoke<AnalyzeDocxResult>("analyze_docx", { payload });
 }
+
+/**
+ * Gán lại một đoạn văn (chỉ số `<w:p>` trong `continuationParagraphs`) rồi
+ * phân tích lại đề. Truyền `null` để bỏ gán thủ công.
+ */
+export async function reassignParagraph(
+  jobId: string,
+  paragraphIndex: number,
+  assignment: ParagraphAssignment | null,
+  force?: boolean,
+): Promise<AnalyzeDocxResult> {
+  return invoke<AnalyzeDocxResult>("reassign_paragraph", {
+    jobId,
+    paragraphIndex,
+    assignment,
+    force: force ?? null,
+  });
+}
+
+/**
+ * Chọn tay đáp án đúng cho một câu bị lỗi E020 rồi phân tích lại.
+ * Truyền `null` để bỏ đáp án đã chọn.
+ */
+export async function setCorrectLabel(
+  jobId: string,
+  questionNumber: number,
+  label: string | null,
+  force?: boolean,
+): Promise<AnalyzeDocxResult> {
+  return invoke<AnalyzeDocxResult>("set_correct_label", {
+    jobId,
+    questionNumber,
+    label,
+    force: force ?? null,
+  });
+}
+
+export type AutofixRule = "labelSpacing" | "mergeLabelRuns" | "markStyle" | "strayHash";
+
+export type AutofixChange = {
+  rule: AutofixRule;
+  paragraphIndex: number;
+  /** Nội dung đoạn văn trước / sau khi sửa */
+  before: string;
+  after: string;
+};
+
+export type AutofixResult = {
+  changes: AutofixChange[];
+  /** Kết quả phân tích lại sau khi sửa */
+  analysis: AnalyzeDocxResult;
+};
+
+/**
+ * Tự sửa các lỗi đánh dấu thường gặp trong đề gốc rồi phân tích lại.
+ * Bản gốc được giữ lại trong workspace (source.orig.docx).
+ */
+export async function autofixJob(
+  jobId: string,
+  rules: AutofixRule[],
+  force?: boolean,
+): Promise<AutofixResult> {
+  return invoke<AutofixResult>("autofix_job", { jobId, rules, force: force ?? null });
+}
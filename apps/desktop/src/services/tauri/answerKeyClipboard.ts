@@ -0,0 +1,21 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** "tsv": bảng hai cột để dán vào Excel; "compact": "Mã đề 101: 1A 2C 3D…" */
+export type AnswerTextFormat = "tsv" | "compact";
+
+/** Chép đáp án của một mã đề vào clipboard, trả về nội dung đã chép */
+export async function copyAnswerKeyToClipboard(
+  jobId: string,
+  code: string,
+  format: AnswerTextFormat,
+  passphrase?: string,
+  overridePassphrase?: string
+): Promise<string> {
+  return invoke<string>("copy_answer_key_to_clipboard", {
+    jobId,
+    code,
+    format,
+    passphrase,
+    overridePassphrase,
+  });
+}
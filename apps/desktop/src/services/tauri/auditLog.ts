@@ -0,0 +1,30 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type AuditAction = "exportExams" | "exportAnswerKey" | "shareBundle" | "customExport";
+
+export interface AuditFile {
+  path: string;
+  sha256: string;
+}
+
+export interface AuditEntry {
+  action: AuditAction;
+  /** Thời điểm xuất (Unix, giây) */
+  at: number;
+  author: string;
+  variantCodes: string[];
+  files: AuditFile[];
+  prevHash: string;
+}
+
+export interface AuditLog {
+  /** Cũ nhất trước */
+  entries: AuditEntry[];
+  /** false nếu nhật ký đã bị sửa hoặc xoá bớt dòng */
+  intact: boolean;
+}
+
+/** Nhật ký các lần xuất đề / đáp án của một job */
+export async function getAuditLog(jobId: string): Promise<AuditLog> {
+  return invoke<AuditLog>("get_audit_log", { jobId });
+}
@@ -0,0 +1,210 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Question } from "../../store/mixStore";
+
+export interface UsageRecord {
+  jobId: string;
+  /** Thời điểm xuất đề (Unix, giây) */
+  usedAt: number;
+  /** Mã đề của các phiên bản có câu này */
+  variantCodes: string[];
+}
+
+export interface BankQuestion {
+  id: string;
+  question: Question;
+  tags: string[];
+  sourceJobId: string;
+  /** Thời điểm nhập (Unix, giây) */
+  createdAt: number;
+  /** Id câu đã có trong ngân hàng mà câu này có vẻ trùng (chờ xem lại) */
+  duplicateOf?: string;
+  /** Id câu mà câu này diễn đạt lại */
+  paraphraseOf?: string;
+  /** Câu diễn đạt lại chưa được duyệt; không hiện khi tìm kiếm trừ khi lọc pendingReview */
+  pendingReview?: boolean;
+  /** Các đề đã xuất có dùng câu này, cũ nhất trước */
+  usage?: UsageRecord[];
+  /** Số phiên bản hiện tại (bắt đầu từ 1) */
+  revision: number;
+  /** Thời điểm sửa gần nhất (Unix, giây), 0 nếu chưa sửa */
+  updatedAt: number;
+  updatedBy: string;
+}
+
+export interface Revision {
+  revision: number;
+  question: Question;
+  tags: string[];
+  /** Thời điểm lưu phiên bản (Unix, giây) */
+  editedAt: number;
+  author: string;
+}
+
+/** Xử lý câu gần trùng khi nhập: bỏ qua, gộp tag vào câu cũ, hoặc vẫn nhập và đánh dấu */
+export type DedupMode = "skip" | "merge" | "flag";
+
+export interface DedupOptions {
+  mode?: DedupMode;
+  /** Độ giống (0–1) từ đó hai câu bị coi là trùng, mặc định 0.9 */
+  threshold?: number;
+}
+
+export interface DuplicateMatch {
+  questionNumber: number;
+  existingId: string;
+  similarity: number;
+}
+
+export interface DedupReport {
+  /** Id các câu đã thêm (kể cả câu bị đánh dấu) */
+  imported: string[];
+  skipped: DuplicateMatch[];
+  merged: DuplicateMatch[];
+  flagged: DuplicateMatch[];
+}
+
+export interface LockOwner {
+  author: string;
+  host: string;
+  pid: number;
+  lockedAt: number;
+}
+
+export interface BankStatus {
+  dir: string;
+  /** Ngân hàng nằm trong thư mục dùng chung (cài đặt bankDir) */
+  shared: boolean;
+  /** Không ghi được vào thư mục: chỉ xem và tìm kiếm */
+  readOnly: boolean;
+  /** Người đang ghi vào ngân hàng, nếu có */
+  lockedBy: LockOwner | null;
+}
+
+export interface SearchFilters {
+  /** Câu hỏi phải có đủ tất cả các tag này */
+  tags?: string[];
+  /** Bỏ các câu đã dùng trong N đề xuất gần nhất */
+  notUsedInLast?: number;
+  /** Chỉ các câu diễn đạt lại đang chờ duyệt (hàng chờ duyệt) */
+  pendingReview?: boolean;
+}
+
+export interface PageRequest {
+  /** Bắt đầu từ 1 */
+  page: number;
+  pageSize: number;
+}
+
+export interface SearchHit {
+  question: BankQuestion;
+  score: number;
+}
+
+export interface SearchResult {
+  total: number;
+  page: number;
+  pageSize: number;
+  items: SearchHit[];
+}
+
+/** Đưa các câu hỏi của một job vào ngân hàng, trả về báo cáo trùng lặp */
+export async function importJobToBank(
+  jobId: string,
+  tags: string[],
+  dedup?: DedupOptions
+): Promise<DedupReport> {
+  return invoke<DedupReport>("import_job_to_bank", { jobId, tags, dedup });
+}
+
+/** Giữ lại (bỏ đánh dấu) hoặc xoá một câu bị đánh dấu trùng */
+export async function resolveBankDuplicate(id: string, keep: boolean): Promise<void> {
+  return invoke<void>("resolve_bank_duplicate", { id, keep });
+}
+
+/**
+ * Lấy `count` (mặc định 2) cách diễn đạt khác của một câu từ dịch vụ trong cài đặt;
+ * các câu mới ở trạng thái chờ duyệt, trả về id của chúng
+ */
+export async function paraphraseBankQuestion(id: string, count?: number): Promise<string[]> {
+  return invoke<string[]>("paraphrase_bank_question", { id, count });
+}
+
+/** Nhận (accept = true) hoặc xoá một câu diễn đạt lại đang chờ duyệt */
+export async function reviewParaphrase(id: string, accept: boolean): Promise<void> {
+  return invoke<void>("review_paraphrase", { id, accept });
+}
+
+/** Thay toàn bộ tag của một câu trong ngân hàng */
+export async function setBankQuestionTags(id: string, tags: string[]): Promise<void> {
+  return invoke<void>("set_bank_question_tags", { id, tags });
+}
+
+/** Tìm câu hỏi theo nội dung (không phân biệt dấu) và tag */
+export async function searchBank(
+  query: string,
+  filters?: SearchFilters,
+  page?: PageRequest
+): Promise<SearchResult> {
+  return invoke<SearchResult>("search_bank", { query, filters, page });
+}
+
+/** Sửa nội dung (và tag nếu truyền vào) của một câu; bản cũ được giữ trong lịch sử */
+export async function updateBankQuestion(
+  id: string,
+  question: Question,
+  tags?: string[],
+  /** Phiên bản đã tải về; nếu người khác đã sửa câu này, lệnh báo lỗi E143_BANK_CONFLICT */
+  expectedRevision?: number
+): Promise<void> {
+  return invoke<void>("update_bank_question", { id, question, tags, expectedRevision });
+}
+
+/**
+ * Gợi ý phương án sai cho câu có đáp án là số (đổi dấu, sai dấu phẩy thập phân,
+ * quên hệ số 2, lệch 1); báo lỗi E149_ANSWER_NOT_NUMERIC nếu đáp án không phải số
+ */
+export async function suggestNumericDistractors(answer: string, count?: number): Promise<string[]> {
+  return invoke<string[]>("suggest_numeric_distractors", { answer, count });
+}
+
+/**
+ * Chuyển câu trả lời ngắn thành câu trắc nghiệm: đáp án và các phương án sai
+ * (bỏ trống = gợi ý tự động) được xếp tăng dần thành A, B, C…
+ */
+export async function convertBankQuestionToChoice(
+  id: string,
+  answer: string,
+  distractors?: string[],
+  expectedRevision?: number
+): Promise<void> {
+  return invoke<void>("convert_bank_question_to_choice", { id, answer, distractors, expectedRevision });
+}
+
+/** Các phiên bản của một câu, mới nhất trước (phần tử đầu là bản hiện tại) */
+export async function getQuestionHistory(id: string): Promise<Revision[]> {
+  return invoke<Revision[]>("get_question_history", { id });
+}
+
+/** Khôi phục một phiên bản cũ; bản hiện tại được lưu vào lịch sử */
+export async function restoreQuestionRevision(id: string, revision: number): Promise<void> {
+  return invoke<void>("restore_question_revision", { id, revision });
+}
+
+/** Tình trạng thư mục ngân hàng (dùng chung, chỉ đọc, đang bị khoá) */
+export async function getBankStatus(): Promise<BankStatus> {
+  return invoke<BankStatus>("get_bank_status");
+}
+
+/** Xuất toàn bộ ngân hàng (câu hỏi, hình, lịch sử) ra tệp mã hoá bằng mật khẩu; trả về số câu */
+export async function exportBank(path: string, password: string): Promise<number> {
+  return invoke<number>("export_bank", { path, password });
+}
+
+/** Nhập tệp ngân hàng đã mã hoá; câu đã có được bỏ qua, câu gần trùng xử lý theo `dedup` */
+export async function importBank(
+  path: string,
+  password: string,
+  dedup?: DedupOptions
+): Promise<DedupReport> {
+  return invoke<DedupReport>("import_bank", { path, password, dedup });
+}
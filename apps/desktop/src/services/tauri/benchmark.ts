@@ -0,0 +1,23 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type BenchmarkPhase = "unzip" | "convert" | "parse" | "mix" | "write";
+
+export type BenchmarkReport = {
+  questionCount: number;
+  variantCount: number;
+  /** Thời gian từng bước, theo thứ tự chạy */
+  phases: { phase: BenchmarkPhase; ms: number }[];
+  totalMs: number;
+  /** Hệ điều hành và kiến trúc, vd. "windows x86_64" */
+  os: string;
+  cpuThreads: number;
+  appVersion: string;
+};
+
+/**
+ * Đo hiệu năng trên đề mẫu 100 câu (giống nhau ở mọi máy) để gửi cho bộ phận
+ * hỗ trợ khi người dùng báo phần mềm chạy chậm.
+ */
+export async function runBenchmark(): Promise<BenchmarkReport> {
+  return invoke<BenchmarkReport>("run_benchmark");
+}
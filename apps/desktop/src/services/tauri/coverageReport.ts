@@ -0,0 +1,60 @@
+// services/tauri/coverageReport.ts
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Ma trận đề: số câu (và điểm, nếu đặt) mong muốn của từng chủ đề ở từng mức độ.
+ * Chủ đề và mức độ của câu lấy từ tag trong ngân hàng ("Nhận biết", "TH", "Dao động cơ")
+ * và chương của câu.
+ */
+export interface TargetMatrix {
+  /** Mặc định Nhận biết, Thông hiểu, Vận dụng, Vận dụng cao; tag khớp theo tên hoặc chữ cái đầu ("VDC") */
+  levels?: string[];
+  targets: { topic: string; level: string; questions: number; points?: number }[];
+}
+
+export interface CoverageCell {
+  topic: string;
+  level: string;
+  /** Số thứ tự các câu trong ô */
+  questions: number[];
+  points: number;
+  targetQuestions: number | null;
+  targetPoints: number | null;
+}
+
+export interface CoverageReport {
+  levels: string[];
+  topics: string[];
+  cells: CoverageCell[];
+  /** Câu chưa gắn chủ đề hoặc mức độ */
+  untagged: number[];
+  totalPoints: number;
+}
+
+/** Số câu và điểm theo chủ đề, mức độ so với ma trận; câu không ghi điểm tính pointsPerQuestion (mặc định 0,25) */
+export async function getCoverageReport(
+  jobId: string,
+  matrix?: TargetMatrix,
+  pointsPerQuestion?: number
+): Promise<CoverageReport> {
+  return invoke<CoverageReport>("get_coverage_report", {
+    jobId,
+    matrix: matrix ?? null,
+    pointsPerQuestion: pointsPerQuestion ?? null,
+  });
+}
+
+/** Xuất tệp .xlsx có trang "Bản đặc tả" (ô lệch ma trận tô đỏ) và trang "Chênh lệch" */
+export async function exportCoverageReport(
+  jobId: string,
+  outputPath: string,
+  matrix?: TargetMatrix,
+  pointsPerQuestion?: number
+): Promise<void> {
+  return invoke<void>("export_coverage_report", {
+    jobId,
+    matrix: matrix ?? null,
+    pointsPerQuestion: pointsPerQuestion ?? null,
+    outputPath,
+  });
+}
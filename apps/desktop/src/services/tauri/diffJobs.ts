@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface OptionDiff {
+  label: string;
+  before: string | null;
+  after: string | null;
+}
+
+export interface QuestionDiff {
+  number: number;
+  /** Độ giống phần dẫn, 0–1 */
+  stemSimilarity: number;
+  stemBefore?: string;
+  stemAfter?: string;
+  /** Phần dẫn thay đổi gần như hoàn toàn */
+  rewritten: boolean;
+  changedOptions: OptionDiff[];
+  answerBefore?: string;
+  answerAfter?: string;
+}
+
+export interface JobDiff {
+  changed: QuestionDiff[];
+  added: number[];
+  removed: number[];
+  unchanged: number;
+}
+
+/** So sánh hai bản nháp đề đã phân tích (jobA = bản cũ, jobB = bản mới) */
+export async function diffJobs(jobA: string, jobB: string): Promise<JobDiff> {
+  return invoke<JobDiff>("diff_jobs", { jobA, jobB });
+}
@@ -0,0 +1,9 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Nhân bản một job đã phân tích để thử cách trộn khác mà không phân tích lại.
+ * Bản sao chưa có mã đề, bản xuất hay nhật ký của job gốc. Trả về mã job mới.
+ */
+export async function duplicateJob(jobId: string): Promise<string> {
+  return invoke<string>("duplicate_job", { jobId });
+}
@@ -0,0 +1,30 @@
+// services/tauri/examPresets.ts
+import { invoke } from "@tauri-apps/api/core";
+import type { ExportOptions } from "./exportMixed";
+
+/**
+ * Mẫu đề: tên kỳ kiểm tra, thời gian, điểm mỗi câu và cách trình bày đi kèm
+ * một loại bài kiểm tra. Giao diện điền sẵn tuỳ chọn xuất theo mẫu; khi xuất,
+ * mẫu ghi trong ExportOptions.preset điền các tuỳ chọn còn để mặc định.
+ */
+export interface ExamPreset {
+  /** Ví dụ "Kiểm tra 15 phút", "Giữa kỳ 45 phút", "Cuối kỳ 90 phút THPT" */
+  name: string;
+  /** Tên kỳ kiểm tra in ở phần đầu đề */
+  examName: string;
+  durationMinutes: number;
+  /** Số câu thường dùng, để nhắc khi đề có số câu khác; null = không cố định */
+  questionCount: number | null;
+  pointsPerQuestion: number;
+  printLayout: NonNullable<ExportOptions["printLayout"]>;
+  twoColumns: boolean;
+  spacing: NonNullable<ExportOptions["spacing"]>;
+  answerLine: NonNullable<ExportOptions["answerLine"]>;
+  /** Phiếu trả lời xuất kèm; null = không */
+  answerSheet: ExportOptions["answerSheet"] | null;
+}
+
+/** Các mẫu đề để chọn: mẫu riêng trong cài đặt, rồi các mẫu có sẵn chưa bị thay */
+export async function getExamPresets(): Promise<ExamPreset[]> {
+  return invoke<ExamPreset[]>("get_exam_presets");
+}
@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ExportOptions } from "./exportMixed";
+
+export interface VariantSummary {
+  examCode: string;
+  questionCount: number;
+  /** Số câu theo nhãn đáp án đúng, vd. { A: 10, B: 11 } */
+  answerDistribution: Record<string, number>;
+  /** Số câu theo chương (khi phân tích với detectChapters) */
+  sections?: { chapter: string; count: number }[];
+}
+
+export interface ManifestFile {
+  name: string;
+  size: number;
+  sha256: string;
+}
+
+export interface ExportManifest {
+  jobId: string;
+  /** Thời điểm xuất (Unix, giây) */
+  exportedAt: number;
+  author: string;
+  outputDirectory: string;
+  variants: VariantSummary[];
+  files: ManifestFile[];
+  options: ExportOptions;
+}
+
+/** Tóm tắt lần xuất gần nhất của một job (null nếu chưa xuất lần nào) */
+export async function getExportManifest(jobId: string): Promise<ExportManifest | null> {
+  return invoke<ExportManifest | null>("get_export_manifest", { jobId });
+}
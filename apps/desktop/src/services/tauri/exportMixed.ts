@@ -1,17 +1,156 @@
 // services/tauri/exportMixed.ts
 import { invoke } from "@tauri-apps/api/core";
 import type { MixedExam } from "../../lib/mixAlgorithm";
+import type { Lexicon } from "./lexicon";
+import type { Room, Student } from "./roster";
+
+export interface ExportOptions {
+  /** "Câu" | "Question" | "Câu hỏi" ... */
+  questionPrefix?: string;
+  /** "dot" → "A." ; "paren" → "A)" */
+  optionSeparator?: "dot" | "paren";
+  decimalSeparator?: string;
+  pointsPerQuestion?: number;
+  /** Giữ hình nổi (wp:anchor) ở vị trí gốc thay vì chèn inline sau nội dung */
+  preserveFloatingImages?: boolean;
+  /** Chép nguyên các đoạn gốc của câu hỏi (cần phân tích với keepRawXml) */
+  rawPassthrough?: boolean;
+  /**
+   * "standard" → A4 dọc; "booklet" → sổ gấp A5 (Word in theo thứ tự gấp);
+   * "twoUp" → 2 bản đề A5 trên mỗi tờ A4 ngang (dành cho đề 1 trang).
+   * Tệp PDF của trình xuất (runExporter) cũng được dàn trang như vậy.
+   */
+  printLayout?: "standard" | "booklet" | "twoUp";
+  /** Xuất thêm bản chữ lớn De_<mã>_ChuLon.docx cho học sinh khiếm thị */
+  largePrintCopy?: boolean;
+  /** In đậm các từ phủ định KHÔNG, NGOẠI TRỪ, SAI trong thân câu */
+  boldNegativeWords?: boolean;
+  /** Chuyển lại ảnh WMF/EMF ở độ phân giải này cho bản in (ví dụ 300) */
+  imageDpi?: number;
+  /** Môn và khối in ở phần đầu đề, cũng là {subject}, {grade} trong mẫu tên file */
+  subject?: string;
+  grade?: string;
+  /**
+   * Mẫu tên file đề, không có phần mở rộng (mặc định "De_{code}").
+   * Các biến: {subject}, {grade}, {code}, {date} (YYYY-MM-DD), {variant_index}
+   */
+  fileNameTemplate?: string;
+  /** Mẫu tên file đáp án (mặc định "Dap_An") */
+  answerKeyFileNameTemplate?: string;
+  /** Trang bìa in trước phần đầu của mỗi mã đề; bỏ trống = không có trang bìa */
+  coverPage?: CoverPageTemplate;
+  /**
+   * Chỗ trống để làm bài sau mỗi câu không có phương án (tự luận, trả lời ngắn):
+   * "blank" → dòng trống, "ruled" → dòng kẻ chấm, "box" → khung; lines = số dòng (mặc định 5)
+   */
+  answerSpace?: { style?: "none" | "blank" | "ruled" | "box"; lines?: number };
+  /**
+   * Dòng ghi đáp án dưới mỗi câu (làm bài trực tiếp trên đề):
+   * "line" → "Đáp án: ....", "bubbles" → "○ A ○ B ○ C ○ D"
+   */
+  answerLine?: "none" | "line" | "bubbles";
+  /** Phần câu hỏi chia 2 cột dưới phần đầu đề (môn có câu ngắn như Tiếng Anh); bản chữ lớn vẫn 1 cột */
+  twoColumns?: boolean;
+  /**
+   * Giãn cách câu hỏi: "normal" (như trước), "compact" (dòng sát hơn) hoặc tuỳ chỉnh
+   * (pt sau mỗi đoạn, pt trước mỗi câu, cm thụt lề phương án, số dòng)
+   */
+  spacing?:
+    | "normal"
+    | "compact"
+    | { paragraphAfterPt?: number; questionGapPt?: number; optionIndentCm?: number; lineSpacing?: number };
+  /**
+   * Xuất thêm bản giáo viên De_<mã>_GiaoVien.docx, đáp án đúng được đánh dấu:
+   * gạch chân nhãn, tô đỏ, in đậm, dấu * sau phương án, hoặc dòng "→ Đáp án: X"
+   */
+  teacherCopy?: "underline" | "red" | "bold" | "asterisk" | "answerLine";
+  /** Lời văn đầu đề, hướng dẫn, dòng đáp án; bỏ trống = lời văn đặt cho môn trong cài đặt, không có thì tiếng Việt */
+  lexicon?: Partial<Lexicon>;
+  /** Dòng "HẾT" và khung chữ ký sau câu cuối; bỏ trống = không in */
+  closing?: ClosingTemplate;
+  /**
+   * Hướng dẫn in sau phần đầu đề, mỗi dòng một đoạn; {questions}, {minutes},
+   * {points} được thay bằng số câu, thời gian, điểm mỗi câu của từng mã đề
+   */
+  instructions?: string;
+  /** Tên mẫu đề (xem getExamPresets); điền các tuỳ chọn còn để mặc định */
+  preset?: string;
+  /** Tên kỳ kiểm tra và thời gian làm bài in ở phần đầu đề; bỏ trống = theo mẫu đề */
+  examName?: string;
+  durationMinutes?: number;
+  /** Tên trường in ở phần đầu đề; bỏ trống = tên trường trong giấy phép */
+  schoolName?: string;
+  /** Năm học in ở phần đầu đề, vd. "2025 - 2026"; bỏ trống = năm học hiện tại */
+  academicYear?: string;
+  /**
+   * Mã vạch Code128 "<mã đề>-<số trang>" (vd. "101-03") ở chân mỗi trang, để phần mềm
+   * chấm thi tự xếp và ghép các trang đã quét theo mã đề
+   */
+  pageBarcode?: boolean;
+  /**
+   * Xuất thêm phiếu trả lời trắc nghiệm PhieuTraLoi.docx theo mẫu máy chấm: 20, 40, 50
+   * hoặc 100 câu A-D, ô số báo danh 6 chữ số và mã đề 3 chữ số. Lỗi E137_ANSWER_SHEET_MISMATCH
+   * nếu đề nhiều câu hơn phiếu, có câu quá 4 phương án hoặc mã đề không phải số 3 chữ số
+   */
+  answerSheet?: "20" | "40" | "50" | "100";
+}
+
+/** Các trường bỏ trống dùng mẫu mặc định */
+export interface ClosingTemplate {
+  /** In dòng "----------- HẾT -----------" (mặc định có) */
+  endMarker?: boolean;
+  /** Các cột chữ ký, mặc định "GIÁO VIÊN RA ĐỀ" và "DUYỆT ĐỀ"; [] = không có khung chữ ký */
+  signatures?: { title: string; name?: string }[];
+}
+
+/** Các trường bỏ trống dùng mẫu mặc định */
+export interface CoverPageTemplate {
+  /** Mặc định "ĐỀ KIỂM TRA" */
+  title?: string;
+  /** Hướng dẫn làm bài, mỗi dòng một ý (đánh số tự động) */
+  instructions?: string[];
+  /** Tài liệu, dụng cụ được sử dụng; rỗng → "Thí sinh không được sử dụng tài liệu." */
+  materialsAllowed?: string[];
+  /** Các dòng thông tin thí sinh (mặc định Họ và tên, Số báo danh, Lớp, Phòng thi) */
+  studentFields?: string[];
+  /** Số ô ký của cán bộ coi thi (mặc định 2, 0 = không có) */
+  proctorSignatures?: number;
+}
 
 export interface ExportMixedParams {
   jobId: string;
+  /** Đáp án luôn lấy từ hoán vị đã lưu trong workspace, không lấy từ đây */
   exams: MixedExam[];
-  originalAnswers: string[];
   outputDir: string;
+  options?: ExportOptions;
+  /**
+   * Mật khẩu đáp án, nếu job đã đặt (xem setAnswerKeyPassphrase); thiếu/sai thì
+   * chỉ xuất đề, không có bảng đáp án và bản giáo viên
+   */
+  passphrase?: string;
+  /**
+   * Mật khẩu mở sớm; trước giờ công bố đáp án mà thiếu/sai thì chỉ xuất đề,
+   * không có bảng đáp án và bản giáo viên
+   */
+  overridePassphrase?: string;
+  /**
+   * Danh sách học sinh (xem loadRoster): thêm một bản đề cho mỗi học sinh,
+   * De_<mã>_<SBD>.docx, và bảng phân công PhanCong_MaDe.xlsx
+   */
+  roster?: Student[];
+  /**
+   * Phòng thi (cùng roster): xếp chỗ để hai bạn ngồi cạnh nhau khác mã đề,
+   * kèm sơ đồ phòng SoDoPhong.xlsx / SoDoPhong.docx. Lỗi E136_SEATING_IMPOSSIBLE
+   * nếu thiếu chỗ, thiếu phòng hoặc chỉ có một mã đề
+   */
+  rooms?: Room[];
 }
 
 export interface ExportResponse {
   success: boolean;
+  message: string;
   docxFiles: string[];
+  /** Trống nếu bảng đáp án chưa được xuất (thiếu mật khẩu đáp án, chưa đến giờ công bố) */
   xlsxFile: string;
   outputDirectory: string;
 }
@@ -22,7 +161,104 @@ export async function exportMixedExams(
   return invoke<ExportResponse>("export_mixed_exams", {
     jobId: params.jobId,
     exams: params.exams,
-    originalAnswers: params.originalAnswers,
     outputDir: params.outputDir,
+    options: params.options ?? null,
+    passphrase: params.passphrase ?? null,
+    overridePassphrase: params.overridePassphrase ?? null,
+    roster: params.roster ?? null,
+    rooms: params.rooms ?? null,
+  });
+}
+
+/** Các mã đề và đáp án được đọc từ workspace của job */
+export interface ExportAnswerKeyParams {
+  jobId: string;
+  /** Đường dẫn tệp .xlsx */
+  outputPath: string;
+  options?: ExportOptions;
+  passphrase?: string;
+  /** Mật khẩu mở sớm, khi xuất trước giờ công bố đáp án */
+  overridePassphrase?: string;
+}
+
+/** Chỉ xuất bảng đáp án (XLSX) */
+export async function exportAnswerKey(params: ExportAnswerKeyParams): Promise<void> {
+  return invoke<void>("export_answer_key", {
+    jobId: params.jobId,
+    outputPath: params.outputPath,
+    options: params.options ?? null,
+    passphrase: params.passphrase ?? null,
+    overridePassphrase: params.overridePassphrase ?? null,
+  });
+}
+
+/**
+ * Đặt, đổi hoặc bỏ (passphrase = null) mật khẩu bảo vệ đáp án của job.
+ * Nếu job đã có mật khẩu thì phải truyền đúng `current`.
+ */
+export async function setAnswerKeyPassphrase(
+  jobId: string,
+  passphrase: string | null,
+  current?: string
+): Promise<void> {
+  return invoke<void>("set_answer_key_passphrase", {
+    jobId,
+    passphrase,
+    current: current ?? null,
+  });
+}
+
+/**
+ * Hẹn giờ công bố đáp án (unlockAt: Unix, giây; null = bỏ hẹn giờ). Trước giờ
+ * đó chỉ xuất được đáp án với mật khẩu mở sớm `overridePassphrase`.
+ */
+export async function setAnswerKeyRelease(
+  jobId: string,
+  unlockAt: number | null,
+  overridePassphrase?: string,
+  current?: string
+): Promise<void> {
+  return invoke<void>("set_answer_key_release", {
+    jobId,
+    unlockAt,
+    overridePassphrase: overridePassphrase ?? null,
+    current: current ?? null,
+  });
+}
+
+export interface ExportSpeechScriptsParams {
+  exams: MixedExam[];
+  outputDir: string;
+  /** "text" → .txt ; "ssml" → .ssml (có khoảng nghỉ) */
+  format: "text" | "ssml";
+  options?: ExportOptions;
+}
+
+/** Xuất kịch bản đọc (TTS) cho từng mã đề, trả về tên các file đã ghi */
+export async function exportSpeechScripts(
+  params: ExportSpeechScriptsParams
+): Promise<string[]> {
+  return invoke<string[]>("export_speech_scripts", {
+    exams: params.exams,
+    outputDir: params.outputDir,
+    format: params.format,
+    options: params.options ?? null,
+  });
+}
+
+/**
+ * Xuất kịch bản phát bài nghe cho từng mã đề (<tên file>_BaiNghe.txt): các bài nghe
+ * (Track 1, Bài nghe 2...) theo thứ tự câu trong mã đề đó, kèm số câu của mã đề.
+ * Báo lỗi E134_NO_TRACK_CUES nếu đề không ghi bài nghe nào.
+ */
+export async function exportListeningScripts(
+  exams: MixedExam[],
+  outputDir: string,
+  options?: ExportOptions
+): Promise<string[]> {
+  return invoke<string[]>("export_listening_scripts", {
+    exams,
+    outputDir,
+    options: options ?? null,
   });
 }
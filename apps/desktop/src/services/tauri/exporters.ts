@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { MixedExam } from "../../lib/mixAlgorithm";
+import type { ExportOptions } from "./exportMixed";
+
+export interface ExporterInfo {
+  id: string;
+  name: string;
+  /** Phần mở rộng của file xuất, không có dấu chấm */
+  extension: string;
+  description: string;
+  /** File xuất có chứa đáp án (cần mật khẩu đáp án nếu có) */
+  includesAnswers: boolean;
+  /** Tính năng giấy phép cần có để dùng định dạng này */
+  licenseFeature?: string;
+}
+
+export interface RunExporterParams {
+  jobId: string;
+  exporterId: string;
+  exams: MixedExam[];
+  originalAnswers: string[];
+  outputDir: string;
+  options?: ExportOptions;
+  passphrase?: string;
+  overridePassphrase?: string;
+}
+
+/** Các định dạng xuất bổ sung (có sẵn và tiện ích đã cài) */
+export async function listExporters(): Promise<ExporterInfo[]> {
+  return invoke<ExporterInfo[]>("list_exporters");
+}
+
+/** Xuất bằng một định dạng bổ sung, trả về đường dẫn các file đã ghi */
+export async function runExporter(payload: RunExporterParams): Promise<string[]> {
+  return invoke<string[]>("run_exporter", { payload });
+}
@@ -0,0 +1,60 @@
+// services/tauri/lexicon.ts
+import { invoke } from "@tauri-apps/api/core";
+import type { AppLanguage } from "./settings";
+
+/**
+ * Lời văn in quanh câu hỏi: phần đầu đề, dòng hướng dẫn, dòng đáp án và tiêu đề trang bìa.
+ * Các trường bỏ trống dùng lời văn tiếng Việt. Chỗ {questions}, {pages}, {minutes}, {prefix}, {points}
+ * được thay khi xuất đề.
+ */
+export interface Lexicon {
+  /** "Mã đề thi" */
+  examCode: string;
+  /** "ĐỀ CHÍNH THỨC" */
+  officialExam: string;
+  /** "(Đề gồm có {questions} câu / {pages} trang)", số câu và số trang của từng mã đề in đậm */
+  pageCount: string;
+  /** "Năm học" */
+  academicYear: string;
+  /** "Tên môn" */
+  subject: string;
+  /** "Thời gian làm bài: {minutes}" */
+  duration: string;
+  /** "(Không kể thời gian phát đề)" */
+  distributionNote: string;
+  /** "ĐỀ" */
+  examHeading: string;
+  /** "I. PHẦN TRẮC NGHIỆM" */
+  multipleChoicePart: string;
+  /** Dòng hướng dẫn, {prefix} = tiền tố câu hỏi, {points} = điểm mỗi câu */
+  instructions: string;
+  /** "Đáp án" (dòng đáp án, bản giáo viên) */
+  answer: string;
+  /** "Hình bị thiếu" */
+  missingImage: string;
+  /** "Bài nghe" (chỗ có tệp âm thanh nhúng) */
+  audio: string;
+  /** Bản cá nhân: nhãn dòng thông tin học sinh dưới phần đầu đề ("Họ và tên", "Số báo danh", "Phòng thi") */
+  studentName: string;
+  studentId: string;
+  room: string;
+  /** Trang bìa: "Môn" */
+  coverSubject: string;
+  /** Trang bìa: "Thời gian làm bài: {minutes} phút (không kể thời gian phát đề)" */
+  coverDuration: string;
+  /** Trang bìa: "Hướng dẫn làm bài" */
+  coverInstructions: string;
+  /** Trang bìa: "Thí sinh được sử dụng" */
+  materialsAllowed: string;
+  /** Trang bìa: "Thí sinh không được sử dụng tài liệu." */
+  noMaterials: string;
+  /** Chữ của dòng "----------- HẾT -----------" */
+  endMarker: string;
+  /** Dưới tên mỗi cột chữ ký: "(Ký, ghi rõ họ tên)" */
+  signatureNote: string;
+}
+
+/** Lời văn mẫu theo ngôn ngữ, làm điểm xuất phát khi đặt lời văn riêng cho một môn */
+export async function getLexiconPreset(language: AppLanguage): Promise<Lexicon> {
+  return invoke<Lexicon>("get_lexicon_preset", { language });
+}
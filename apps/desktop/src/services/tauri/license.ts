@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface License {
+  schoolName: string;
+  seats: number;
+  /** Hết hạn (Unix, giây); null = vĩnh viễn */
+  expiresAt: number | null;
+  /** Tính năng trả phí được cấp, vd. "pdfExport", "moodleExport" */
+  features: string[];
+}
+
+export interface LicenseStatus {
+  state: "unlicensed" | "active" | "expired" | "invalid";
+  license?: License;
+}
+
+/** Kích hoạt giấy phép của trường bằng mã giấy phép (kiểm tra offline) */
+export async function activateLicense(key: string): Promise<License> {
+  return invoke<License>("activate_license", { key });
+}
+
+export async function getLicenseStatus(): Promise<LicenseStatus> {
+  return invoke<LicenseStatus>("get_license_status");
+}
@@ -8,6 +8,26 @@ import type { ParsedDoc } from "../../store/mixStore";
 export interface MixedExam {
   examCode: string;
   questions: MixedQuestion[];
+  /** Seed của mã đề, dạng chuỗi số (vượt quá độ chính xác của number) */
+  seed?: string;
+  /** Dữ kiện chung, đã đánh lại số câu theo mã đề */
+  groups?: MixedGroup[];
+  /** Tiêu đề các phần, đã đánh lại số câu theo mã đề */
+  parts?: MixedPart[];
+}
+
+export interface MixedPart {
+  heading: any[];
+  points?: number;
+  /** Số thứ tự hiển thị của các câu trong phần */
+  questions: number[];
+}
+
+export interface MixedGroup {
+  header: any[];
+  paragraphs?: any[][];
+  /** Số thứ tự hiển thị của các câu trong nhóm */
+  questions: number[];
 }
 
 export interface MixedQuestion {
@@ -31,11 +51,130 @@ export interface MixedOption {
 export async function mixExams(
   parsedDoc: ParsedDoc,
   numVariants: number,
-  customExamCodes?: string[]
+  customExamCodes?: string[],
+  jobId?: string,
+  shuffleStatements?: boolean,
+  masterSeed?: string,
+  force?: boolean,
+  passphrase?: string,
+  overridePassphrase?: string
 ): Promise<MixedExam[]> {
+  // Khi có jobId, backend lưu mapping.json cho từng mã đề trong workspace.
+  // shuffleStatements: đảo thứ tự các phát biểu (I), (II)… trong thân câu.
+  // masterSeed: trộn lại đúng như một lần trước (vd. bản xem trước).
+  // force: trộn lại cả khi có mã đề đã duyệt/đã xuất (E116).
+  // passphrase, overridePassphrase: như previewMix; thiếu/sai thì correctAnswer
+  // của các câu để trống (đáp án vẫn được lưu và xuất từ workspace).
   return invoke<MixedExam[]>("mix_exams", {
     parsedDoc,
     numVariants,
     customExamCodes: customExamCodes || null,
+    jobId: jobId || null,
+    masterSeed: masterSeed ?? null,
+    shuffleStatements: shuffleStatements ?? null,
+    force: force ?? null,
+    passphrase: passphrase ?? null,
+    overridePassphrase: overridePassphrase ?? null,
+  });
+}
+
+export interface MixPreviewOptions {
+  numVariants: number;
+  customExamCodes?: string[];
+  /** Bỏ trống để chọn ngẫu nhiên; seed đã dùng nằm trong kết quả */
+  masterSeed?: string;
+  shuffleStatements?: boolean;
+}
+
+/** Thứ tự câu và đáp án của một mã đề trong bản xem trước */
+export interface VariantPreview {
+  examCode: string;
+  /** Số câu gốc ở mỗi vị trí: originalNumbers[0] là câu gốc của "Câu 1" */
+  originalNumbers: number[];
+  /** Đáp án đúng ở mỗi vị trí; trống nếu answersHidden */
+  answers: string[];
+}
+
+export interface MixPreview {
+  /** Truyền lại cho mixExams để trộn đúng như bản xem trước (chuỗi số 64 bit) */
+  masterSeed: string;
+  variants: VariantPreview[];
+  /** Đáp án bị ẩn: job có mật khẩu đáp án mà passphrase thiếu/sai, hoặc chưa đến giờ công bố */
+  answersHidden: boolean;
+}
+
+/**
+ * Xem trước kết quả trộn đề của job mà không ghi file nào. Đáp án chỉ có khi
+ * xuất được đáp án: mật khẩu đáp án (passphrase) và, trước giờ công bố, mật khẩu mở sớm
+ */
+export async function previewMix(
+  jobId: string,
+  options: MixPreviewOptions,
+  passphrase?: string,
+  overridePassphrase?: string
+): Promise<MixPreview> {
+  return invoke<MixPreview>("preview_mix", {
+    jobId,
+    options,
+    passphrase: passphrase ?? null,
+    overridePassphrase: overridePassphrase ?? null,
   });
 }
+
+/**
+ * Đổi chỗ hai câu (số thứ tự trong mã đề) của một mã đề đã trộn; đáp án được
+ * tính lại. Không đổi riêng được câu thuộc nhóm dùng chung dữ kiện (E115).
+ * Trước giờ công bố đáp án cần overridePassphrase (E154).
+ */
+export async function swapQuestions(
+  jobId: string,
+  code: string,
+  a: number,
+  b: number,
+  passphrase?: string,
+  force?: boolean,
+  overridePassphrase?: string
+): Promise<MixedExam> {
+  return invoke<MixedExam>("swap_questions", {
+    jobId,
+    code,
+    a,
+    b,
+    passphrase: passphrase ?? null,
+    overridePassphrase: overridePassphrase ?? null,
+    force: force ?? null,
+  });
+}
+
+/**
+ * Trộn lại riêng một mã đề với seed mới; các mã đề khác giữ nguyên. Trước giờ
+ * công bố đáp án cần overridePassphrase (E154).
+ */
+export async function regenerateVariant(
+  jobId: string,
+  code: string,
+  passphrase?: string,
+  force?: boolean,
+  overridePassphrase?: string
+): Promise<MixedExam> {
+  return invoke<MixedExam>("regenerate_variant", {
+    jobId,
+    code,
+    passphrase: passphrase ?? null,
+    overridePassphrase: overridePassphrase ?? null,
+    force: force ?? null,
+  });
+}
+
+/** "draft" → "approved" → "exported"; mã đề đã duyệt/xuất chỉ bị ghi đè khi có force */
+export type VariantState = "draft" | "approved" | "exported";
+
+/** Trạng thái duyệt của các mã đề trong lần trộn gần nhất */
+export async function getVariantStates(jobId: string): Promise<Record<string, VariantState>> {
+  return invoke<Record<string, VariantState>>("get_variant_states", { jobId });
+}
+
+/** Duyệt (khoá) hoặc bỏ duyệt một mã đề; trả về trạng thái mới */
+export async function setVariantApproval(jobId: string, code: string, approved: boolean): Promise<VariantState> {
+  return invoke<VariantState>("set_variant_approval", { jobId, code, approved });
+}
@@ -0,0 +1,27 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+/**
+ * Tệp được mở cùng ứng dụng ("Mở bằng SiroMix", nhấp đúp, dòng lệnh).
+ * Đề thi: phân tích với `analyzeDocx({ jobId, sourcePath: path })`.
+ * Ngân hàng: hỏi mật khẩu rồi `importBank(path, password)`.
+ */
+export type OpenedFile =
+  | { kind: "exam"; path: string; jobId: string }
+  | { kind: "bankArchive"; path: string };
+
+/** Các tệp đang chờ xử lý, cũ trước mới sau; mỗi tệp chỉ được trả về một lần */
+export async function takeOpenedFiles(): Promise<OpenedFile[]> {
+  return invoke<OpenedFile[]>("take_opened_files");
+}
+
+/**
+ * Nhận các tệp được mở khi ứng dụng đang chạy (từ lần mở thứ hai); gọi hàm
+ * trả về để huỷ. Gọi thêm `takeOpenedFiles()` lúc khởi động cho các tệp có sẵn.
+ */
+export async function onOpenedFiles(handler: (files: OpenedFile[]) => void): Promise<UnlistenFn> {
+  return listen("file-opened", async () => {
+    const files = await takeOpenedFiles();
+    if (files.length) handler(files);
+  });
+}
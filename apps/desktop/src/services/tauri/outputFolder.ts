@@ -0,0 +1,14 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Mở thư mục của lần xuất gần nhất (hoặc workspace nếu job chưa xuất) */
+export async function openOutputFolder(jobId: string): Promise<void> {
+  return invoke<void>("open_output_folder", { jobId });
+}
+
+/**
+ * Mở thư mục chứa file và chọn sẵn file đó trong trình quản lý file.
+ * File phải nằm trong workspace hoặc thư mục xuất gần nhất của job.
+ */
+export async function revealFile(jobId: string, path: string): Promise<void> {
+  return invoke<void>("reveal_file", { jobId, path });
+}
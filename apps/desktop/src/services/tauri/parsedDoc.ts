@@ -0,0 +1,81 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { Question, QuestionGroup } from "../../store/mixStore";
+import type { ConversionParams } from "./settings";
+
+export interface ParsedPage {
+  /** Tổng số câu của đề */
+  total: number;
+  offset: number;
+  questions: Question[];
+  /** Phần dữ kiện chung có câu nằm trong trang này */
+  groups?: QuestionGroup[];
+}
+
+export interface QuestionSummary {
+  number: number;
+  /** Vài chữ đầu của phần dẫn */
+  preview: string;
+  optionCount: number;
+  correctLabel: string;
+  hasImages: boolean;
+  hasMath: boolean;
+  /** Có phương án bị khoá ("#A.") */
+  hasLockedOptions: boolean;
+  inGroup: boolean;
+  chapter?: string;
+}
+
+/** Lấy `limit` câu hỏi từ vị trí `offset` của một job đã phân tích */
+export async function getParsedPage(
+  jobId: string,
+  offset: number,
+  limit: number,
+): Promise<ParsedPage> {
+  return invoke<ParsedPage>("get_parsed_page", { jobId, offset, limit });
+}
+
+/** Lấy một câu hỏi theo số câu trong đề gốc */
+export async function getQuestion(jobId: string, number: number): Promise<Question> {
+  return invoke<Question>("get_question", { jobId, number });
+}
+
+/** Danh sách rút gọn các câu hỏi (không gồm nội dung đầy đủ) */
+export async function getParsedSummary(jobId: string): Promise<QuestionSummary[]> {
+  return invoke<QuestionSummary[]>("get_parsed_summary", { jobId });
+}
+
+/**
+ * Đường dẫn ảnh thu nhỏ (PNG, tối đa 160px) của một hình trong đề;
+ * trả về hình gốc nếu không tạo được ảnh thu nhỏ.
+ */
+export async function getAssetThumbnail(jobId: string, assetPath: string): Promise<string> {
+  return invoke<string>("get_asset_thumbnail", { jobId, assetPath });
+}
+
+export type ConversionStatus = "pending" | "done" | "failed" | "unavailable";
+
+export interface ConversionJob {
+  fileName: string;
+  source: string;
+  target: string;
+  /** "unavailable": máy chưa cài ImageMagick */
+  status: ConversionStatus;
+  attempts: number;
+  lastError?: string;
+  /** Thông số đã dùng để chuyển */
+  params: ConversionParams;
+}
+
+/** Chạy lại các lần chuyển WMF/EMF sang PNG bị lỗi của một job */
+export async function retryFailedConversions(jobId: string): Promise<ConversionJob[]> {
+  return invoke<ConversionJob[]>("retry_failed_conversions", { jobId });
+}
+
+/**
+ * Nhận mã job mỗi khi các hình WMF/EMF của nó được chuyển xong ở background
+ * sau khi phân tích (cần tải lại câu hỏi); gọi hàm trả về để huỷ
+ */
+export async function onConversionsDone(handler: (jobId: string) => void): Promise<UnlistenFn> {
+  return listen<string>("wmf-conversions", (event) => handler(event.payload));
+}
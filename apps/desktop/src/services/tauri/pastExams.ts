@@ -0,0 +1,29 @@
+// services/tauri/pastExams.ts
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Đề các năm trước trong kho đề cũ. Khi phân tích đề mới, câu quá giống một
+ * câu trong kho được báo W012_SIMILAR_TO_PAST_EXAM ("<tên đề>, câu <số> (<độ giống>%)").
+ */
+export interface PastExamInfo {
+  id: string;
+  /** Ví dụ "Cuối kỳ I 2023-2024" */
+  label: string;
+  fileName: string;
+  /** Thời điểm thêm vào kho (Unix, giây) */
+  addedAt: number;
+  questionCount: number;
+}
+
+/** Thêm một đề cũ (.docx) vào kho; label bỏ trống = tên tệp */
+export async function addPastExam(path: string, label?: string): Promise<PastExamInfo> {
+  return invoke<PastExamInfo>("add_past_exam", { path, label: label ?? null });
+}
+
+export async function listPastExams(): Promise<PastExamInfo[]> {
+  return invoke<PastExamInfo[]>("list_past_exams");
+}
+
+export async function removePastExam(id: string): Promise<void> {
+  return invoke<void>("remove_past_exam", { id });
+}
@@ -0,0 +1,27 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface QuestionStats {
+  number: number;
+  stemWords: number;
+  /** Số từ của từng phương án, theo thứ tự phương án */
+  optionWords: number[];
+  /** Đáp án đúng dài hơn hẳn mọi phương án khác */
+  correctLongest: boolean;
+  /** Phương án dài nhất dài gấp đôi phương án ngắn nhất trở lên */
+  optionsUnbalanced: boolean;
+  /** Từ tuyệt đối tìm thấy ("luôn", "không bao giờ"…), viết thường */
+  absoluteTerms: string[];
+}
+
+export interface DocStats {
+  questions: QuestionStats[];
+  /** Tỉ lệ câu có đáp án đúng là phương án dài nhất, 0–1 */
+  correctLongestRate: number;
+  /** Đáp án đúng có xu hướng luôn là phương án dài nhất */
+  longestAnswerBias: boolean;
+}
+
+/** Thống kê độ dài và cách dùng từ của các câu hỏi trong một job đã phân tích */
+export async function getQuestionStats(jobId: string): Promise<DocStats> {
+  return invoke<DocStats>("get_question_stats", { jobId });
+}
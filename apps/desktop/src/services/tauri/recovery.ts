@@ -0,0 +1,26 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AnalyzeDocxResult } from "./analyzeDocx";
+
+export interface IncompleteJob {
+  jobId: string;
+  /** "interrupted": ứng dụng bị tắt khi đang phân tích; "notAnalyzed": chưa phân tích xong */
+  reason: "interrupted" | "notAnalyzed";
+  /** Thời điểm sửa file nguồn (Unix, giây) */
+  modifiedAt: number;
+}
+
+/** Các job phân tích dở dang tìm thấy lúc khởi động */
+export async function getIncompleteJobs(): Promise<IncompleteJob[]> {
+  return invoke<IncompleteJob[]>("get_incomplete_jobs");
+}
+
+/**
+ * Phân tích lại (trả về kết quả) hoặc xoá workspace của một job dở dang.
+ * Lỗi E108_INVALID_ID nếu jobId không nằm trong getIncompleteJobs()
+ */
+export async function recoverIncompleteJob(
+  jobId: string,
+  action: "reanalyze" | "discard"
+): Promise<AnalyzeDocxResult | null> {
+  return invoke<AnalyzeDocxResult | null>("recover_incomplete_job", { jobId, action });
+}
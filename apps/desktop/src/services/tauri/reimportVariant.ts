@@ -0,0 +1,43 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Khác biệt giữa file đã sửa tay và đáp án đã lưu (theo số thứ tự câu trong mã đề) */
+export type KeyDiscrepancy =
+  | { kind: "answerChanged"; displayNumber: number; stored: string; marked: string }
+  | { kind: "unmarked"; displayNumber: number; stored: string }
+  | { kind: "multipleMarked"; displayNumber: number; stored: string }
+  | { kind: "optionCountChanged"; displayNumber: number; stored: number; found: number }
+  | { kind: "questionMissing"; displayNumber: number }
+  | { kind: "questionAdded"; displayNumber: number };
+
+export interface ReimportResult {
+  examCode: string;
+  discrepancies: KeyDiscrepancy[];
+  /** Số đáp án đã cập nhật theo file (khi updateKey) */
+  answersUpdated: number;
+}
+
+/**
+ * Đọc lại file DOCX của một mã đề đã sửa tay trong Word và so đáp án được
+ * đánh dấu (gạch chân/tô đỏ nhãn) với đáp án đã lưu. Với updateKey, đáp án
+ * đã lưu được sửa theo file; mã đề đã duyệt/xuất cần force và trở về bản
+ * nháp. Trước giờ công bố đáp án cần overridePassphrase.
+ */
+export async function reimportVariant(
+  path: string,
+  jobId: string,
+  code: string,
+  updateKey?: boolean,
+  passphrase?: string,
+  overridePassphrase?: string,
+  force?: boolean
+): Promise<ReimportResult> {
+  return invoke<ReimportResult>("reimport_variant", {
+    path,
+    jobId,
+    code,
+    updateKey: updateKey ?? null,
+    passphrase: passphrase ?? null,
+    overridePassphrase: overridePassphrase ?? null,
+    force: force ?? null,
+  });
+}
@@ -0,0 +1,48 @@
+// services/tauri/roster.ts
+import { invoke } from "@tauri-apps/api/core";
+
+/** Một học sinh trong danh sách lớp/phòng thi */
+export interface Student {
+  /** Số báo danh hoặc mã học sinh; có thể trống */
+  id: string;
+  name: string;
+  /** Phòng thi; trống nếu danh sách không có cột phòng */
+  room: string;
+}
+
+/** Một phòng thi; mặc định 24 chỗ, 4 chỗ mỗi hàng */
+export interface Room {
+  /** Như ghi ở cột Phòng thi của danh sách, ví dụ "P.12" */
+  name: string;
+  capacity?: number;
+  /** Số chỗ mỗi hàng */
+  columns?: number;
+}
+
+/** Chỗ ngồi, đánh số từ hàng đầu, trái sang phải */
+export interface Seat {
+  room: string;
+  number: number;
+  row: number;
+  column: number;
+}
+
+/** Một học sinh và mã đề được phân (bảng PhanCong_MaDe.xlsx) */
+export interface Assignment {
+  student: Student;
+  examCode: string;
+  /** Bản đề cá nhân đã xuất, ví dụ "De_101_0123.docx" */
+  fileName: string;
+  /** Có khi xuất kèm phòng thi */
+  seat?: Seat;
+}
+
+/**
+ * Đọc danh sách học sinh từ tệp CSV (hoặc phân tách bằng tab) có dòng tiêu đề
+ * "Họ và tên", "Số báo danh", "Phòng thi" (thứ tự tuỳ ý, cột khác bỏ qua).
+ * Không có dòng tiêu đề: các cột là họ tên, số báo danh, phòng thi.
+ * Lỗi E135_ROSTER_INVALID nếu không có học sinh nào hoặc trùng số báo danh.
+ */
+export async function loadRoster(path: string): Promise<Student[]> {
+  return invoke<Student[]>("load_roster", { path });
+}
@@ -0,0 +1,42 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ExamPreset } from "./examPresets";
+import type { Lexicon } from "./lexicon";
+
+export type AppLanguage = "vi" | "en";
+
+/** Cách chuyển ảnh WMF/EMF (công thức) sang PNG */
+export interface ConversionParams {
+  /** Độ phân giải: 96 cho màn hình, từ 300 cho bản in (giới hạn 72–600) */
+  dpi: number;
+  /** Cắt bỏ lề trắng quanh hình */
+  trim: boolean;
+  /** Màu nền, ví dụ "white" hoặc "none" (trong suốt); trống = mặc định của ImageMagick */
+  background: string;
+}
+
+export interface AppSettings {
+  language: AppLanguage;
+  /** Tên ghi vào lịch sử sửa ngân hàng câu hỏi (trống = tên người dùng hệ điều hành) */
+  authorName: string;
+  /** Thư mục ngân hàng câu hỏi, ví dụ trên ổ mạng dùng chung (trống = thư mục của ứng dụng) */
+  bankDir: string;
+  /** Từ ngữ bị cấm (cách nói nhạy cảm, địa danh cũ…), cảnh báo khi phân tích đề */
+  forbiddenTerms: string[];
+  wmfConversion: ConversionParams;
+  /** Địa chỉ manifest phiên bản (https:// hoặc file://) để kiểm tra cập nhật; rỗng = không kiểm tra */
+  updateManifestUrl: string;
+  /** Lời văn đầu đề riêng theo môn (tên môn như trong tuỳ chọn xuất), ví dụ môn dạy bằng tiếng Anh */
+  subjectLexicons: Record<string, Partial<Lexicon>>;
+  /** Mẫu đề riêng của trường, đứng trước các mẫu có sẵn (trùng tên thì thay mẫu có sẵn) */
+  examPresets: ExamPreset[];
+  /** Dịch vụ diễn đạt lại câu hỏi (máy chủ trong trường hoặc API); endpoint trống = tắt */
+  paraphrase: { endpoint: string; apiKey: string };
+}
+
+export async function getSettings(): Promise<AppSettings> {
+  return invoke<AppSettings>("get_settings");
+}
+
+export async function updateSettings(settings: AppSettings): Promise<void> {
+  return invoke<void>("update_settings", { settings });
+}
@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface ShareBundle {
+  /** File nén các đề + đáp án, trong thư mục xuất */
+  zipPath: string;
+  /** Tiêu đề email */
+  subject: string;
+  /** Thư ngỏ: môn, tên kỳ thi, các mã đề */
+  coverNote: string;
+  /** Liên kết mailto: có tiêu đề và thư ngỏ (không kèm được tệp) */
+  mailto: string;
+}
+
+/**
+ * Tạo gói gửi đồng nghiệp từ lần xuất gần nhất. Với openMailClient, mở trình
+ * email mặc định và thư mục chứa file nén để đính kèm.
+ */
+export async function createShareBundle(
+  jobId: string,
+  examName: string,
+  openMailClient: boolean,
+  passphrase?: string,
+  overridePassphrase?: string
+): Promise<ShareBundle> {
+  return invoke<ShareBundle>("create_share_bundle", {
+    jobId,
+    examName,
+    openMailClient,
+    passphrase,
+    overridePassphrase,
+  });
+}
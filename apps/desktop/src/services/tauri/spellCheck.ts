@@ -0,0 +1,22 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface Misspelling {
+  questionNumber: number;
+  /** Nhãn phương án, null nếu lỗi nằm ở phần dẫn */
+  optionLabel: string | null;
+  word: string;
+}
+
+export interface SpellCheckResult {
+  misspellings: Misspelling[];
+  /** W013 khi không có từ điển (chỉ báo lần đầu trong phiên) */
+  warning?: { code: string; message: string };
+}
+
+/**
+ * Kiểm tra chính tả tiếng Việt cho phần dẫn và phương án của một job đã phân
+ * tích. Không có từ điển thì danh sách rỗng và chỉ cảnh báo một lần.
+ */
+export async function spellCheckJob(jobId: string): Promise<SpellCheckResult> {
+  return invoke<SpellCheckResult>("spell_check_job", { jobId });
+}
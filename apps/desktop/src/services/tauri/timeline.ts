@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+export type JobEvent =
+  | { kind: "duplicatedFrom"; sourceJobId: string }
+  | { kind: "analyzed"; ok: boolean; errorCount: number; warningCount: number }
+  | { kind: "paragraphReassigned"; paragraphIndex: number }
+  /** label = null: bỏ đáp án đã chọn tay */
+  | { kind: "answerChosen"; questionNumber: number; label: string | null }
+  | { kind: "autofixed"; changeCount: number }
+  | { kind: "mixed"; examCodes: string[] }
+  | { kind: "questionsSwapped"; examCode: string; a: number; b: number }
+  | { kind: "variantRegenerated"; examCode: string }
+  | { kind: "variantApproval"; examCode: string; approved: boolean }
+  | { kind: "variantReimported"; examCode: string; discrepancyCount: number; answersUpdated: number }
+  | { kind: "exported"; examCodes: string[]; outputDirectory: string }
+  | { kind: "answerKeyExported"; path: string }
+  | { kind: "shareBundleCreated"; path: string }
+  | { kind: "customExported"; exporterId: string; fileCount: number };
+
+export type TimelineEntry = JobEvent & {
+  jobId: string;
+  /** Unix, giây */
+  at: number;
+  author: string;
+};
+
+/** Lịch sử thao tác trên một job, cũ trước mới sau */
+export async function getJobTimeline(jobId: string): Promise<TimelineEntry[]> {
+  return invoke<TimelineEntry[]>("get_job_timeline", { jobId });
+}
+
+/** Nhận các sự kiện mới của mọi job ngay khi được ghi; gọi hàm trả về để huỷ */
+export async function onJobTimeline(handler: (entry: TimelineEntry) => void): Promise<UnlistenFn> {
+  return listen<TimelineEntry>("job-timeline", (event) => handler(event.payload));
+}
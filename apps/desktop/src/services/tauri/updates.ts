@@ -0,0 +1,14 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface UpdateCheck {
+  currentVersion: string;
+  latestVersion: string;
+  updateAvailable: boolean;
+  releaseNotes: string;
+  downloadUrl: string;
+}
+
+/** Kiểm tra phiên bản mới theo địa chỉ manifest trong cài đặt */
+export async function checkForUpdates(): Promise<UpdateCheck> {
+  return invoke<UpdateCheck>("check_for_updates");
+}
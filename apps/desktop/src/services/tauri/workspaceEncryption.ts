@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface EncryptionStatus {
+  /** Đã bật mã hoá dữ liệu đề trong workspace */
+  enabled: boolean;
+  /** Đã nhập mật khẩu trong phiên làm việc này */
+  unlocked: boolean;
+  /** Các tệp/thư mục của workspace KHÔNG được mã hoá (file đề gốc, hình ảnh, ảnh thu nhỏ) */
+  unprotected: string[];
+}
+
+/**
+ * Bật mã hoá parsed.json và mapping các mã đề bằng mật khẩu người dùng.
+ * Chỉ mã hoá dữ liệu phân tích: file đề gốc và hình ảnh vẫn để nguyên
+ * (xem `EncryptionStatus.unprotected`).
+ */
+export async function enableWorkspaceEncryption(passphrase: string): Promise<void> {
+  return invoke<void>("enable_workspace_encryption", { passphrase });
+}
+
+/** Mở khoá dữ liệu đề cho phiên làm việc (sai mật khẩu: E151_WORKSPACE_PASSPHRASE) */
+export async function unlockWorkspaces(passphrase: string): Promise<void> {
+  return invoke<void>("unlock_workspaces", { passphrase });
+}
+
+/** Khoá lại dữ liệu đề */
+export async function lockWorkspaces(): Promise<void> {
+  return invoke<void>("lock_workspaces");
+}
+
+export async function getWorkspaceEncryptionStatus(): Promise<EncryptionStatus> {
+  return invoke<EncryptionStatus>("get_workspace_encryption_status");
+}
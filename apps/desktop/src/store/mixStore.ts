@@ -8,7 +8,12 @@ import type { MixedExam } from "../services/tauri/mixExams";
 type Segment =
   | { type: "Text"; text: string }
   | { type: "Image"; asset_path: string }
-  | { type: "Math"; omml: string };
+  | { type: "Math"; omml: string }
+  | { type: "Symbol"; font: string; char: string }
+  /** Chỗ trống điền từ (dãy dấu chấm/gạch dưới); width = số ký tự trong file gốc */
+  | { type: "Blank"; width: number }
+  /** Bài nghe nhúng trong file gốc, đã trích vào assets/audio/ */
+  | { type: "Audio"; asset_path: string; fileName: string };
 
 type OptionItem = {
   label: string;
@@ -21,10 +26,37 @@ type Question = {
   stem: Segment[];
   options: OptionItem[];
   correct_label: string;
+  /** Đoạn không có tiền tố đã được gán vào câu này (có thể gán lại) */
+  continuationParagraphs?: {
+    paragraphIndex: number;
+    assignment: "stem" | "option" | "nextQuestion";
+    preview: string;
+  }[];
+  /** Chương chứa câu này (khi phân tích với detectChapters) */
+  chapter?: string;
+  /** Điểm ghi ở đầu câu ("(0,5 điểm)") */
+  points?: number;
+};
+
+/** Dữ kiện chung cho một dãy câu ("Dùng dữ kiện sau cho câu 15 và 16") */
+type QuestionGroup = {
+  header: Segment[];
+  paragraphs?: Segment[][];
+  questions: number[];
+};
+
+/** Phần của đề có thang điểm riêng ("PHẦN I. TRẮC NGHIỆM (3 điểm)"); câu chỉ được trộn trong phần của nó */
+type ExamPart = {
+  heading: Segment[];
+  /** Tổng điểm của phần, khi tiêu đề có ghi */
+  points?: number;
+  questions: number[];
 };
 
 type ParsedDoc = {
   questions: Question[];
+  groups?: QuestionGroup[];
+  parts?: ExamPart[];
 };
 
 // Exam metadata
@@ -134,4 +166,4 @@ export const useMixStore = create<MixState>()(
 );
 
 // Export types for reuse
-export type { ParsedDoc, Question, OptionItem, Segment };
+export type { ExamPart, ParsedDoc, Question, QuestionGroup, OptionItem, Segment };